@@ -51,6 +51,24 @@ fn main() {
         }
     };
 
+    // The hash of the commit the build is made from, if it is known
+    let git_hash = repo
+        .as_ref()
+        .and_then(|repo| repo.head().ok())
+        .and_then(|head| head.peel_to_commit().ok())
+        .map(|commit| commit.id().to_string());
+    let git_hash_fn = match git_hash {
+        Some(git_hash) => format!(
+            "\npub fn namada_git_hash() -> Option<&'static str> {{ \
+             Some(\"{git_hash}\") }}"
+        ),
+        None => "\npub fn namada_git_hash() -> Option<&'static str> { None }"
+            .to_owned(),
+    };
+    version_rs
+        .write_all(git_hash_fn.as_bytes())
+        .expect("cannot write version");
+
     // Tell Cargo that if the given file changes, to rerun this build script.
     println!("cargo:rerun-if-changed={}", PROTO_SRC);
 }
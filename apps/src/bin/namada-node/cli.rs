@@ -38,6 +38,40 @@ pub fn main() -> Result<()> {
                 ledger::rollback(chain_ctx.config.ledger)
                     .wrap_err("Failed to rollback the Namada node")?;
             }
+            cmds::Ledger::Bench(cmds::LedgerBench(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                let wasm_dir = chain_ctx.wasm_dir();
+                let report =
+                    ledger::bench(chain_ctx.config.ledger, wasm_dir, args)
+                        .wrap_err("Failed to benchmark the Namada node")?;
+                println!("{report}");
+            }
+            cmds::Ledger::DebugBundle(cmds::LedgerDebugBundle(args)) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                let path = ledger::debug_bundle(chain_ctx.config.ledger, args)
+                    .wrap_err("Failed to export the debug bundle")?;
+                println!(
+                    "Wrote the debug bundle to {}",
+                    path.to_string_lossy()
+                );
+            }
+            cmds::Ledger::PauseEthOracle(_) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                ledger::pause_eth_oracle(chain_ctx.config.ledger)
+                    .wrap_err("Failed to pause the Ethereum oracle")?;
+                println!(
+                    "The Ethereum oracle will be paused from the next block on"
+                );
+            }
+            cmds::Ledger::ResumeEthOracle(_) => {
+                let chain_ctx = ctx.take_chain_or_exit();
+                ledger::resume_eth_oracle(chain_ctx.config.ledger)
+                    .wrap_err("Failed to resume the Ethereum oracle")?;
+                println!(
+                    "The Ethereum oracle will be resumed from the next block \
+                     on"
+                );
+            }
         },
         cmds::NamadaNode::Config(sub) => match sub {
             cmds::Config::Gen(cmds::ConfigGen) => {
@@ -52,7 +52,6 @@ use namada::ibc::core::host::types::path::{
 };
 use namada::ibc::primitives::proto::{Any, Protobuf};
 use namada::ibc::primitives::{Msg, Timestamp as IbcTimestamp};
-use namada::ledger::dry_run_tx;
 use namada::ledger::gas::TxGasMeter;
 use namada::ledger::ibc::storage::{channel_key, connection_key};
 use namada::ledger::native_vp::ibc::get_dummy_header;
@@ -60,6 +59,7 @@ use namada::ledger::queries::{
     Client, EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
 };
 use namada::ledger::storage_api::StorageRead;
+use namada::ledger::{dry_run_tx, view_tx};
 use namada::proto::{Code, Data, Section, Signature, Tx};
 use namada::tendermint::Hash;
 use namada::tendermint_rpc::{self};
@@ -250,6 +250,7 @@ impl Default for BenchShell {
                 voting_start_epoch,
                 voting_end_epoch: voting_start_epoch + 3_u64,
                 grace_epoch: voting_start_epoch + 9_u64,
+                expedited: false,
             },
             None,
             Some(vec![content_section]),
@@ -706,10 +707,18 @@ impl Client for BenchShell {
             vp_wasm_cache: self.vp_wasm_cache.read_only(),
             tx_wasm_cache: self.tx_wasm_cache.read_only(),
             storage_read_past_height_limit: None,
+            crash_reports: self.crash_reports(),
+            write_hotspots: self.write_hotspots(),
+            node_metrics: self.node_metrics(),
+            valset_proof_cache: self.valset_proof_cache(),
+            archive_endpoints: &[],
+            oracle_last_processed_block: None,
         };
 
         if request.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &request)
+        } else if request.path == "/shell/view_tx" {
+            view_tx(ctx, &request)
         } else {
             RPC.handle(ctx, &request)
         }
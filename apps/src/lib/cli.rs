@@ -222,6 +222,7 @@ pub mod cmds {
                 // Governance transactions
                 .subcommand(TxInitProposal::def().display_order(1))
                 .subcommand(TxVoteProposal::def().display_order(1))
+                .subcommand(TxWithdrawProposal::def().display_order(1))
                 // PoS transactions
                 .subcommand(TxBecomeValidator::def().display_order(2))
                 .subcommand(TxInitValidator::def().display_order(2))
@@ -233,11 +234,16 @@ pub mod cmds {
                 .subcommand(Withdraw::def().display_order(2))
                 .subcommand(Redelegate::def().display_order(2))
                 .subcommand(ClaimRewards::def().display_order(2))
+                .subcommand(AutoCompound::def().display_order(2))
+                .subcommand(SlashingInsurance::def().display_order(2))
                 .subcommand(TxCommissionRateChange::def().display_order(2))
                 .subcommand(TxChangeConsensusKey::def().display_order(2))
+                .subcommand(TxChangeProtocolKey::def().display_order(2))
+                .subcommand(TxChangeEthBridgeKeys::def().display_order(2))
                 .subcommand(TxMetadataChange::def().display_order(2))
                 // Ethereum bridge transactions
                 .subcommand(AddToEthBridgePool::def().display_order(3))
+                .subcommand(CancelEthBridgePoolTransfer::def().display_order(3))
                 // PGF transactions
                 .subcommand(TxUpdateStewardCommission::def().display_order(4))
                 .subcommand(TxResignSteward::def().display_order(4))
@@ -293,6 +299,8 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, TxInitProposal);
             let tx_vote_proposal =
                 Self::parse_with_ctx(matches, TxVoteProposal);
+            let tx_withdraw_proposal =
+                Self::parse_with_ctx(matches, TxWithdrawProposal);
             let tx_update_steward_commission =
                 Self::parse_with_ctx(matches, TxUpdateStewardCommission);
             let tx_resign_steward =
@@ -301,6 +309,10 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, TxCommissionRateChange);
             let tx_change_consensus_key =
                 Self::parse_with_ctx(matches, TxChangeConsensusKey);
+            let tx_change_protocol_key =
+                Self::parse_with_ctx(matches, TxChangeProtocolKey);
+            let tx_change_eth_bridge_keys =
+                Self::parse_with_ctx(matches, TxChangeEthBridgeKeys);
             let tx_change_metadata =
                 Self::parse_with_ctx(matches, TxMetadataChange);
             let bond = Self::parse_with_ctx(matches, Bond);
@@ -308,6 +320,9 @@ pub mod cmds {
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
             let redelegate = Self::parse_with_ctx(matches, Redelegate);
             let claim_rewards = Self::parse_with_ctx(matches, ClaimRewards);
+            let auto_compound = Self::parse_with_ctx(matches, AutoCompound);
+            let slashing_insurance =
+                Self::parse_with_ctx(matches, SlashingInsurance);
             let query_epoch = Self::parse_with_ctx(matches, QueryEpoch);
             let query_account = Self::parse_with_ctx(matches, QueryAccount);
             let query_transfers = Self::parse_with_ctx(matches, QueryTransfers);
@@ -339,6 +354,8 @@ pub mod cmds {
             let query_metadata = Self::parse_with_ctx(matches, QueryMetaData);
             let add_to_eth_bridge_pool =
                 Self::parse_with_ctx(matches, AddToEthBridgePool);
+            let cancel_eth_bridge_pool_transfer =
+                Self::parse_with_ctx(matches, CancelEthBridgePoolTransfer);
             let sign_tx = Self::parse_with_ctx(matches, SignTx);
             let gen_ibc_shielded =
                 Self::parse_with_ctx(matches, GenIbcShieldedTransafer);
@@ -351,10 +368,13 @@ pub mod cmds {
                 .or(tx_reveal_pk)
                 .or(tx_init_proposal)
                 .or(tx_vote_proposal)
+                .or(tx_withdraw_proposal)
                 .or(tx_become_validator)
                 .or(tx_init_validator)
                 .or(tx_commission_rate_change)
                 .or(tx_change_consensus_key)
+                .or(tx_change_protocol_key)
+                .or(tx_change_eth_bridge_keys)
                 .or(tx_change_metadata)
                 .or(tx_unjail_validator)
                 .or(tx_deactivate_validator)
@@ -364,7 +384,10 @@ pub mod cmds {
                 .or(withdraw)
                 .or(redelegate)
                 .or(claim_rewards)
+                .or(auto_compound)
+                .or(slashing_insurance)
                 .or(add_to_eth_bridge_pool)
+                .or(cancel_eth_bridge_pool_transfer)
                 .or(tx_update_steward_commission)
                 .or(tx_resign_steward)
                 .or(query_epoch)
@@ -437,19 +460,25 @@ pub mod cmds {
         TxInitValidator(TxInitValidator),
         TxCommissionRateChange(TxCommissionRateChange),
         TxChangeConsensusKey(TxChangeConsensusKey),
+        TxChangeProtocolKey(TxChangeProtocolKey),
+        TxChangeEthBridgeKeys(TxChangeEthBridgeKeys),
         TxMetadataChange(TxMetadataChange),
         TxUnjailValidator(TxUnjailValidator),
         TxDeactivateValidator(TxDeactivateValidator),
         TxReactivateValidator(TxReactivateValidator),
         TxInitProposal(TxInitProposal),
         TxVoteProposal(TxVoteProposal),
+        TxWithdrawProposal(TxWithdrawProposal),
         TxRevealPk(TxRevealPk),
         Bond(Bond),
         Unbond(Unbond),
         Withdraw(Withdraw),
         ClaimRewards(ClaimRewards),
+        AutoCompound(AutoCompound),
+        SlashingInsurance(SlashingInsurance),
         Redelegate(Redelegate),
         AddToEthBridgePool(AddToEthBridgePool),
+        CancelEthBridgePoolTransfer(CancelEthBridgePoolTransfer),
         TxUpdateStewardCommission(TxUpdateStewardCommission),
         TxResignSteward(TxResignSteward),
         QueryEpoch(QueryEpoch),
@@ -987,6 +1016,10 @@ pub mod cmds {
         Reset(LedgerReset),
         DumpDb(LedgerDumpDb),
         RollBack(LedgerRollBack),
+        Bench(LedgerBench),
+        DebugBundle(LedgerDebugBundle),
+        PauseEthOracle(LedgerPauseEthOracle),
+        ResumeEthOracle(LedgerResumeEthOracle),
     }
 
     impl SubCmd for Ledger {
@@ -999,10 +1032,21 @@ pub mod cmds {
                 let dump_db = SubCmd::parse(matches).map(Self::DumpDb);
                 let rollback = SubCmd::parse(matches).map(Self::RollBack);
                 let run_until = SubCmd::parse(matches).map(Self::RunUntil);
+                let bench = SubCmd::parse(matches).map(Self::Bench);
+                let debug_bundle =
+                    SubCmd::parse(matches).map(Self::DebugBundle);
+                let pause_eth_oracle =
+                    SubCmd::parse(matches).map(Self::PauseEthOracle);
+                let resume_eth_oracle =
+                    SubCmd::parse(matches).map(Self::ResumeEthOracle);
                 run.or(reset)
                     .or(dump_db)
                     .or(rollback)
                     .or(run_until)
+                    .or(bench)
+                    .or(debug_bundle)
+                    .or(pause_eth_oracle)
+                    .or(resume_eth_oracle)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun(args::LedgerRun {
                         start_time: None,
@@ -1021,6 +1065,10 @@ pub mod cmds {
                 .subcommand(LedgerReset::def())
                 .subcommand(LedgerDumpDb::def())
                 .subcommand(LedgerRollBack::def())
+                .subcommand(LedgerBench::def())
+                .subcommand(LedgerDebugBundle::def())
+                .subcommand(LedgerPauseEthOracle::def())
+                .subcommand(LedgerResumeEthOracle::def())
         }
     }
 
@@ -1102,6 +1150,91 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerBench(pub args::LedgerBench);
+
+    impl SubCmd for LedgerBench {
+        const CMD: &'static str = "bench";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerBench::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Replay a range of blocks on a checkpoint of the DB, and \
+                     report the time spent in each stage of block processing.",
+                )
+                .add_args::<args::LedgerBench>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerDebugBundle(pub args::LedgerDebugBundle);
+
+    impl SubCmd for LedgerDebugBundle {
+        const CMD: &'static str = "debug-bundle";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerDebugBundle::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Export the state of the node into a single archive, to \
+                     be attached to bug reports. The archive holds the last \
+                     block summaries, recent logs, the config with secrets \
+                     redacted, health and stats snapshots, the status of the \
+                     Ethereum oracle and the results of DB consistency checks.",
+                )
+                .add_args::<args::LedgerDebugBundle>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerPauseEthOracle;
+
+    impl SubCmd for LedgerPauseEthOracle {
+        const CMD: &'static str = "pause-eth-oracle";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Pause the Ethereum oracle of the running node from its next \
+                 block on, e.g. after an exploit of the bridge contracts, \
+                 until it is resumed. The node doesn't have to be restarted.",
+            )
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerResumeEthOracle;
+
+    impl SubCmd for LedgerResumeEthOracle {
+        const CMD: &'static str = "resume-eth-oracle";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Resume the Ethereum oracle of the running node from its next \
+                 block on, after it has been paused. The oracle remains \
+                 paused if governance has paused it.",
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct LedgerRollBack;
 
@@ -1589,6 +1722,53 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct AutoCompound(pub args::AutoCompound<args::CliTypes>);
+
+    impl SubCmd for AutoCompound {
+        const CMD: &'static str = "auto-compound";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| AutoCompound(args::AutoCompound::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Enable or disable the automatic compounding of the \
+                     rewards of a bond at the start of every epoch.",
+                )
+                .add_args::<args::AutoCompound<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SlashingInsurance(pub args::SlashingInsurance<args::CliTypes>);
+
+    impl SubCmd for SlashingInsurance {
+        const CMD: &'static str = "slashing-insurance";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                SlashingInsurance(args::SlashingInsurance::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Opt a bond into or out of the slashing insurance pool, \
+                     which compensates the bond for slashes of its validator \
+                     in exchange for a cut of its rewards. The bond is only \
+                     covered for the infractions committed after the epoch it \
+                     was opted in.",
+                )
+                .add_args::<args::SlashingInsurance<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Redelegate(pub args::Redelegate<args::CliTypes>);
 
@@ -2097,6 +2277,59 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxChangeProtocolKey(pub args::ProtocolKeyChange<args::CliTypes>);
+
+    impl SubCmd for TxChangeProtocolKey {
+        const CMD: &'static str = "change-protocol-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxChangeProtocolKey(args::ProtocolKeyChange::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Change protocol key. The current key remains valid until \
+                     the new one becomes active at the pipeline epoch.",
+                )
+                .add_args::<args::ProtocolKeyChange<args::CliTypes>>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxChangeEthBridgeKeys(
+        pub args::EthBridgeKeysChange<args::CliTypes>,
+    );
+
+    impl SubCmd for TxChangeEthBridgeKeys {
+        const CMD: &'static str = "change-eth-bridge-keys";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxChangeEthBridgeKeys(args::EthBridgeKeysChange::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Change Ethereum bridge hot and/or cold keys. The current \
+                     keys remain valid until the new ones become active at \
+                     the pipeline epoch.",
+                )
+                .add_args::<args::EthBridgeKeysChange<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxVoteProposal(pub args::VoteProposal<args::CliTypes>);
 
@@ -2119,6 +2352,31 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxWithdrawProposal(pub args::WithdrawProposal<args::CliTypes>);
+
+    impl SubCmd for TxWithdrawProposal {
+        const CMD: &'static str = "withdraw-proposal";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxWithdrawProposal(args::WithdrawProposal::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Withdraw a proposal before its voting period starts, \
+                     refunding a share of its locked funds to its author.",
+                )
+                .add_args::<args::WithdrawProposal<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxRevealPk(pub args::RevealPk<args::CliTypes>);
 
@@ -2204,6 +2462,7 @@ pub mod cmds {
         GenesisBond(GenesisBond),
         InitGenesisEstablishedAccount(InitGenesisEstablishedAccount),
         InitGenesisValidator(InitGenesisValidator),
+        InitLocalnetValidators(InitLocalnetValidators),
         PkToTmAddress(PkToTmAddress),
         DefaultBaseDir(DefaultBaseDir),
         EpochSleep(EpochSleep),
@@ -2231,6 +2490,8 @@ pub mod cmds {
                     .map(Self::InitGenesisEstablishedAccount);
                 let init_genesis =
                     SubCmd::parse(matches).map(Self::InitGenesisValidator);
+                let init_localnet_validators =
+                    SubCmd::parse(matches).map(Self::InitLocalnetValidators);
                 let pk_to_tm_address =
                     SubCmd::parse(matches).map(Self::PkToTmAddress);
                 let default_base_dir =
@@ -2248,6 +2509,7 @@ pub mod cmds {
                     .or(genesis_bond)
                     .or(init_established)
                     .or(init_genesis)
+                    .or(init_localnet_validators)
                     .or(pk_to_tm_address)
                     .or(default_base_dir)
                     .or(epoch_sleep)
@@ -2267,6 +2529,7 @@ pub mod cmds {
                 .subcommand(GenesisBond::def())
                 .subcommand(InitGenesisEstablishedAccount::def())
                 .subcommand(InitGenesisValidator::def())
+                .subcommand(InitLocalnetValidators::def())
                 .subcommand(PkToTmAddress::def())
                 .subcommand(DefaultBaseDir::def())
                 .subcommand(EpochSleep::def())
@@ -2441,6 +2704,30 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct InitLocalnetValidators(pub args::InitLocalnetValidators);
+
+    impl SubCmd for InitLocalnetValidators {
+        const CMD: &'static str = "init-localnet-validators";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                Self(args::InitLocalnetValidators::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Add genesis validators with freshly generated, \
+                     unencrypted keys to the genesis templates of a local \
+                     test network. Stores a pre-genesis wallet for each \
+                     validator, to join the network with its alias.",
+                )
+                .add_args::<args::InitLocalnetValidators>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct ValidateGenesisTemplates(pub args::ValidateGenesisTemplates);
 
@@ -2515,6 +2802,8 @@ pub mod cmds {
         /// Check the confirmation status of `TransferToEthereum`
         /// events.
         QueryRelays(QueryRelayProgress),
+        /// Query the last processed Ethereum block.
+        QueryEthereumHeight(QueryEthereumHeight),
     }
 
     impl Cmd for EthBridgePool {
@@ -2525,6 +2814,7 @@ pub mod cmds {
                 .subcommand(QueryEthBridgePool::def().display_order(1))
                 .subcommand(QuerySignedBridgePool::def().display_order(1))
                 .subcommand(QueryRelayProgress::def().display_order(1))
+                .subcommand(QueryEthereumHeight::def().display_order(1))
         }
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
@@ -2538,6 +2828,8 @@ pub mod cmds {
             let query_pool = Self::parse_without_ctx(matches, QueryPool);
             let query_signed = Self::parse_without_ctx(matches, QuerySigned);
             let query_relays = Self::parse_without_ctx(matches, QueryRelays);
+            let query_ethereum_height =
+                Self::parse_without_ctx(matches, QueryEthereumHeight);
 
             construct_proof
                 .or(recommend)
@@ -2545,6 +2837,7 @@ pub mod cmds {
                 .or(query_pool)
                 .or(query_signed)
                 .or(query_relays)
+                .or(query_ethereum_height)
         }
     }
 
@@ -2587,6 +2880,7 @@ pub mod cmds {
                 .subcommand(QueryEthBridgePool::def().display_order(1))
                 .subcommand(QuerySignedBridgePool::def().display_order(1))
                 .subcommand(QueryRelayProgress::def().display_order(1))
+                .subcommand(QueryEthereumHeight::def().display_order(1))
         }
     }
 
@@ -2610,6 +2904,32 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct CancelEthBridgePoolTransfer(
+        pub args::CancelBridgePoolTransfer<args::CliTypes>,
+    );
+
+    impl SubCmd for CancelEthBridgePoolTransfer {
+        const CMD: &'static str = "cancel-erc20-transfer";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                Self(args::CancelBridgePoolTransfer::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Cancel a transfer that has sat unrelayed in the Ethereum \
+                     Bridge pool for long enough, to be refunded its escrowed \
+                     tokens.",
+                )
+                .arg_required_else_help(true)
+                .add_args::<args::CancelBridgePoolTransfer<args::CliTypes>>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct ConstructProof(pub args::BridgePoolProof<args::CliTypes>);
 
@@ -2739,6 +3059,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryEthereumHeight(pub args::Query<args::CliTypes>);
+
+    impl SubCmd for QueryEthereumHeight {
+        const CMD: &'static str = "query-ethereum-height";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::Query::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Get the last Ethereum block processed by the chain, and \
+                     by the Ethereum oracle of the queried node.",
+                )
+                .add_args::<args::Query<args::CliTypes>>()
+        }
+    }
+
     /// Used as sub-commands (`SubCmd` instance) in `namadar` binary.
     #[derive(Clone, Debug)]
     pub enum ValidatorSet {
@@ -2940,6 +3282,7 @@ pub mod args {
     use std::path::PathBuf;
     use std::str::FromStr;
 
+    use namada::core::ledger::governance::utils::ProposalState;
     use namada::ibc::core::host::types::identifiers::{ChannelId, PortId};
     use namada::types::address::{Address, EstablishedAddress};
     use namada::types::chain::{ChainId, ChainIdPrefix};
@@ -2955,15 +3298,18 @@ pub mod args {
     use namada::types::transaction::GasLimit;
     pub use namada_sdk::args::*;
     pub use namada_sdk::tx::{
-        TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_BRIDGE_POOL_WASM,
+        TX_AUTO_COMPOUND_WASM, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
+        TX_BRIDGE_POOL_CANCEL_WASM, TX_BRIDGE_POOL_WASM,
         TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
-        TX_CHANGE_METADATA_WASM, TX_CLAIM_REWARDS_WASM,
-        TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
-        TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REDELEGATE_WASM,
-        TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM,
+        TX_CHANGE_ETH_BRIDGE_KEYS_WASM,
+        TX_CHANGE_METADATA_WASM, TX_CHANGE_PROTOCOL_KEY_WASM,
+        TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
+        TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
+        TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK,
+        TX_SLASHING_INSURANCE_WASM, TX_TRANSFER_WASM, TX_UNBOND_WASM,
         TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-        TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-        VP_USER_WASM,
+        TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_PROPOSAL,
+        TX_WITHDRAW_WASM, VP_USER_WASM,
     };
 
     use super::context::*;
@@ -2980,9 +3326,12 @@ pub mod args {
     pub const ALIAS: Arg<String> = arg("alias");
     pub const ALIAS_FORCE: ArgFlag = flag("alias-force");
     pub const ALIAS_MANY: ArgMulti<String, GlobPlus> = arg_multi("aliases");
+    pub const ALLOWED_TX_CODES: ArgMulti<PathBuf, GlobStar> =
+        arg_multi("allowed-tx-codes");
     pub const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     pub const AMOUNT: Arg<token::DenominatedAmount> = arg("amount");
     pub const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
+    pub const AUTO_WITHDRAW: ArgFlag = flag("auto-withdraw");
     pub const BALANCE_OWNER: ArgOpt<WalletBalanceOwner> = arg_opt("owner");
     pub const BASE_DIR: ArgDefault<PathBuf> = arg_default(
         "base-dir",
@@ -2993,6 +3342,8 @@ pub mod args {
     );
     pub const BLOCK_HEIGHT: Arg<BlockHeight> = arg("block-height");
     pub const BLOCK_HEIGHT_OPT: ArgOpt<BlockHeight> = arg_opt("height");
+    pub const BLOCK_HEIGHT_FROM: Arg<BlockHeight> = arg("from");
+    pub const BLOCK_HEIGHT_TO: Arg<BlockHeight> = arg("to");
     pub const BRIDGE_POOL_GAS_AMOUNT: ArgDefault<token::DenominatedAmount> =
         arg_default(
             "pool-gas-amount",
@@ -3011,6 +3362,7 @@ pub mod args {
     pub const BRIDGE_POOL_TARGET: Arg<EthAddress> = arg("target");
     pub const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
     pub const CHAIN_ID: Arg<ChainId> = arg("chain-id");
+    pub const CLEAR_ALLOWED_TX_CODES: ArgFlag = flag("clear-allowed-tx-codes");
     pub const CHAIN_ID_OPT: ArgOpt<ChainId> = CHAIN_ID.opt();
     pub const CHAIN_ID_PREFIX: Arg<ChainIdPrefix> = arg("chain-prefix");
     pub const CHANNEL_ID: Arg<ChannelId> = arg("channel-id");
@@ -3024,12 +3376,14 @@ pub mod args {
     );
     pub const CONVERSION_TABLE: Arg<PathBuf> = arg("conversion-table");
     pub const DAEMON_MODE: ArgFlag = flag("daemon");
+    pub const DB_SNAPSHOT: ArgOpt<PathBuf> = arg_opt("db-snapshot");
     pub const DAEMON_MODE_RETRY_DUR: ArgOpt<Duration> = arg_opt("retry-sleep");
     pub const DAEMON_MODE_SUCCESS_DUR: ArgOpt<Duration> =
         arg_opt("success-sleep");
     pub const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
     pub const DATA_PATH: Arg<PathBuf> = arg("data-path");
     pub const DECRYPT: ArgFlag = flag("decrypt");
+    pub const DISABLE: ArgFlag = flag("disable");
     pub const DESCRIPTION_OPT: ArgOpt<String> = arg_opt("description");
     pub const DISPOSABLE_SIGNING_KEY: ArgFlag = flag("disposable-gas-payer");
     pub const DESTINATION_VALIDATOR: Arg<WalletAddress> =
@@ -3055,6 +3409,7 @@ pub mod args {
     pub const EXPIRATION_OPT: ArgOpt<DateTimeUtc> = arg_opt("expiration");
     pub const EMAIL: Arg<String> = arg("email");
     pub const EMAIL_OPT: ArgOpt<String> = EMAIL.opt();
+    pub const FROM_EPOCH_OPT: ArgOpt<Epoch> = arg_opt("from-epoch");
     pub const FEE_UNSHIELD_SPENDING_KEY: ArgOpt<WalletTransferSource> =
         arg_opt("gas-spending-key");
     pub const FEE_AMOUNT_OPT: ArgOpt<token::DenominatedAmount> =
@@ -3098,14 +3453,19 @@ pub mod args {
 
     pub const LEDGER_ADDRESS: Arg<TendermintAddress> = arg("node");
     pub const LOCALHOST: ArgFlag = flag("localhost");
+    pub const LOG_DIR: ArgOpt<PathBuf> = arg_opt("log-dir");
     pub const MASP_VALUE: Arg<MaspValue> = arg("value");
     pub const MAX_COMMISSION_RATE_CHANGE: Arg<Dec> =
         arg("max-commission-rate-change");
     pub const MAX_ETH_GAS: ArgOpt<u64> = arg_opt("max_eth-gas");
     pub const MODE: ArgOpt<String> = arg_opt("mode");
+    pub const MONIKER_OPT: ArgOpt<String> = arg_opt("moniker");
     pub const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     pub const NAMADA_START_TIME: ArgOpt<DateTimeUtc> = arg_opt("time");
     pub const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
+    pub const NUM_BLOCKS: ArgDefault<u64> =
+        arg_default("blocks", DefaultFn(|| 100));
+    pub const NUM_VALIDATORS: Arg<u64> = arg("num-validators");
     pub const NUT: ArgFlag = flag("nut");
     pub const OUT_FILE_PATH_OPT: ArgOpt<PathBuf> = arg_opt("out-file-path");
     pub const OUTPUT: ArgOpt<PathBuf> = arg_opt("output");
@@ -3120,7 +3480,13 @@ pub mod args {
         DefaultFn(|| PortId::from_str("transfer").unwrap()),
     );
     pub const PRE_GENESIS: ArgFlag = flag("pre-genesis");
+    pub const PROPOSAL_AUTHOR_OPT: ArgOpt<WalletAddress> = arg_opt("author");
     pub const PROPOSAL_ETH: ArgFlag = flag("eth");
+    pub const PROPOSAL_PARAMETER_CHANGE: ArgFlag = flag("parameter-change");
+    pub const PROPOSAL_CODE_ALLOWLIST: ArgFlag = flag("code-allowlist");
+    pub const PROPOSAL_CONSENSUS_PARAMS: ArgFlag = flag("consensus-params");
+    pub const PROPOSAL_TEXT: ArgFlag = flag("text");
+    pub const PROPOSAL_TREASURY_SPEND: ArgFlag = flag("treasury-spend");
     pub const PROPOSAL_PGF_STEWARD: ArgFlag = flag("pgf-stewards");
     pub const PROPOSAL_PGF_FUNDING: ArgFlag = flag("pgf-funding");
     pub const PROPOSAL_OFFLINE: ArgFlag = flag("offline");
@@ -3133,6 +3499,7 @@ pub mod args {
     pub const PROPOSAL_ID_OPT: ArgOpt<u64> = arg_opt("proposal-id");
     pub const PROPOSAL_VOTE_PGF_OPT: ArgOpt<String> = arg_opt("pgf");
     pub const PROPOSAL_VOTE_ETH_OPT: ArgOpt<String> = arg_opt("eth");
+    pub const PROPOSAL_STATE_OPT: ArgOpt<ProposalState> = arg_opt("state");
     pub const PROPOSAL_VOTE: Arg<String> = arg("vote");
     pub const RAW_ADDRESS: Arg<Address> = arg("address");
     pub const RAW_ADDRESS_ESTABLISHED: Arg<EstablishedAddress> = arg("address");
@@ -3145,6 +3512,8 @@ pub mod args {
     pub const SAFE_MODE: ArgFlag = flag("safe-mode");
     pub const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
+    pub const SECURITY_CONTACT_OPT: ArgOpt<String> =
+        arg_opt("security-contact");
     pub const SELF_BOND_AMOUNT: Arg<token::DenominatedAmount> =
         arg("self-bond-amount");
     pub const SENDER: Arg<String> = arg("sender");
@@ -3162,8 +3531,10 @@ pub mod args {
     pub const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     pub const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
     pub const TM_ADDRESS: Arg<String> = arg("tm-address");
+    pub const TO_EPOCH_OPT: ArgOpt<Epoch> = arg_opt("to-epoch");
     pub const TOKEN_OPT: ArgOpt<WalletAddress> = TOKEN.opt();
     pub const TOKEN: Arg<WalletAddress> = arg("token");
+    pub const TRANSFER_HASH: Arg<String> = arg("transfer-hash");
     pub const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     pub const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
     pub const TX_HASH: Arg<String> = arg("tx-hash");
@@ -3353,6 +3724,105 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerBench {
+        pub from: BlockHeight,
+        pub to: BlockHeight,
+        pub ledger_address: TendermintAddress,
+        pub db_snapshot: Option<PathBuf>,
+        pub out_file_path: Option<PathBuf>,
+    }
+
+    impl Args for LedgerBench {
+        fn parse(matches: &ArgMatches) -> Self {
+            let from = BLOCK_HEIGHT_FROM.parse(matches);
+            let to = BLOCK_HEIGHT_TO.parse(matches);
+            let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
+            let db_snapshot = DB_SNAPSHOT.parse(matches);
+            let out_file_path = OUT_FILE_PATH_OPT.parse(matches);
+
+            Self {
+                from,
+                to,
+                ledger_address,
+                db_snapshot,
+                out_file_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                BLOCK_HEIGHT_FROM
+                    .def()
+                    .help("The height of the first block to replay."),
+            )
+            .arg(
+                BLOCK_HEIGHT_TO
+                    .def()
+                    .help("The height of the last block to replay."),
+            )
+            .arg(LEDGER_ADDRESS_DEFAULT.def().help(
+                "Address of the CometBFT node from which the blocks are \
+                 fetched.",
+            ))
+            .arg(DB_SNAPSHOT.def().help(
+                "Path to a snapshot of the DB at the height preceding the \
+                 first replayed block. Defaults to the node's own DB, in \
+                 which case the node must be stopped. The blocks are replayed \
+                 on a checkpoint of the DB, which is never modified.",
+            ))
+            .arg(OUT_FILE_PATH_OPT.def().help(
+                "Path of a file to which the report is written as JSON, to \
+                 compare it with the reports of other releases.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerDebugBundle {
+        pub num_blocks: u64,
+        pub ledger_address: TendermintAddress,
+        pub log_dir: Option<PathBuf>,
+        pub out_file_path: Option<PathBuf>,
+    }
+
+    impl Args for LedgerDebugBundle {
+        fn parse(matches: &ArgMatches) -> Self {
+            let num_blocks = NUM_BLOCKS.parse(matches);
+            let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
+            let log_dir = LOG_DIR.parse(matches);
+            let out_file_path = OUT_FILE_PATH_OPT.parse(matches);
+
+            Self {
+                num_blocks,
+                ledger_address,
+                log_dir,
+                out_file_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                NUM_BLOCKS
+                    .def()
+                    .help("The number of most recent blocks to summarize."),
+            )
+            .arg(LEDGER_ADDRESS_DEFAULT.def().help(
+                "Address of the CometBFT node from which the blocks, health \
+                 and stats of the node are fetched.",
+            ))
+            .arg(LOG_DIR.def().help(
+                "Directory of the log files of the node. Defaults to the \
+                 value of the `NAMADA_LOG_DIR` env var, if set.",
+            ))
+            .arg(OUT_FILE_PATH_OPT.def().help(
+                "Path of the archive to write. Defaults to \
+                 \"debug_bundle.{chain_id}.{time}.tar.gz\" in the current \
+                 working directory.",
+            ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct UpdateLocalConfig {
         pub config_path: PathBuf,
@@ -3506,23 +3976,64 @@ pub mod args {
         }
     }
 
-    impl CliToSdk<RecommendBatch<SdkTypes>> for RecommendBatch<CliTypes> {
-        fn to_sdk(self, ctx: &mut Context) -> RecommendBatch<SdkTypes> {
-            let chain_ctx = ctx.borrow_chain_or_exit();
-            RecommendBatch::<SdkTypes> {
-                query: self.query.to_sdk_ctxless(),
-                max_gas: self.max_gas,
-                gas: self.gas,
-                conversion_table: {
-                    let file = std::io::BufReader::new(
-                        std::fs::File::open(self.conversion_table).expect(
-                            "Failed to open the provided file to the \
-                             conversion table",
-                        ),
-                    );
-                    let table: HashMap<String, f64> =
-                        serde_json::from_reader(file)
-                            .expect("Failed to parse conversion table");
+    impl CliToSdk<CancelBridgePoolTransfer<SdkTypes>>
+        for CancelBridgePoolTransfer<CliTypes>
+    {
+        fn to_sdk(self, ctx: &mut Context) -> CancelBridgePoolTransfer<SdkTypes> {
+            CancelBridgePoolTransfer::<SdkTypes> {
+                tx: self.tx.to_sdk(ctx),
+                transfer_hash: self.transfer_hash,
+                code_path: self.code_path,
+            }
+        }
+    }
+
+    impl Args for CancelBridgePoolTransfer<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let transfer_hash = TRANSFER_HASH.parse(matches);
+            let transfer_hash = KeccakHash::try_from(transfer_hash.as_str())
+                .unwrap_or_else(|_| {
+                    tracing::info!(
+                        "Could not parse '{}' as a Keccak hash.",
+                        transfer_hash
+                    );
+                    safe_exit(1)
+                });
+            let code_path = PathBuf::from(TX_BRIDGE_POOL_CANCEL_WASM);
+            Self {
+                tx,
+                transfer_hash,
+                code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>().arg(
+                TRANSFER_HASH
+                    .def()
+                    .help("The Keccak hash of the transfer to cancel."),
+            )
+        }
+    }
+
+    impl CliToSdk<RecommendBatch<SdkTypes>> for RecommendBatch<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> RecommendBatch<SdkTypes> {
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            RecommendBatch::<SdkTypes> {
+                query: self.query.to_sdk_ctxless(),
+                max_gas: self.max_gas,
+                gas: self.gas,
+                conversion_table: {
+                    let file = std::io::BufReader::new(
+                        std::fs::File::open(self.conversion_table).expect(
+                            "Failed to open the provided file to the \
+                             conversion table",
+                        ),
+                    );
+                    let table: HashMap<String, f64> =
+                        serde_json::from_reader(file)
+                            .expect("Failed to parse conversion table");
                     table
                         .into_iter()
                         .map(|(token, conversion_rate)| {
@@ -4170,6 +4681,8 @@ pub mod args {
                 description: self.description,
                 website: self.website,
                 discord_handle: self.discord_handle,
+                moniker: self.moniker,
+                security_contact: self.security_contact,
                 unsafe_dont_encrypt: self.unsafe_dont_encrypt,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
@@ -4192,6 +4705,8 @@ pub mod args {
             let description = DESCRIPTION_OPT.parse(matches);
             let website = WEBSITE_OPT.parse(matches);
             let discord_handle = DISCORD_OPT.parse(matches);
+            let moniker = MONIKER_OPT.parse(matches);
+            let security_contact = SECURITY_CONTACT_OPT.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
             let tx_code_path = PathBuf::from(TX_BECOME_VALIDATOR_WASM);
             Self {
@@ -4208,6 +4723,8 @@ pub mod args {
                 description,
                 website,
                 discord_handle,
+                moniker,
+                security_contact,
                 unsafe_dont_encrypt,
                 tx_code_path,
             }
@@ -4256,6 +4773,12 @@ pub mod args {
                 .arg(DESCRIPTION_OPT.def().help("The validator's description."))
                 .arg(WEBSITE_OPT.def().help("The validator's website."))
                 .arg(DISCORD_OPT.def().help("The validator's discord handle."))
+                .arg(MONIKER_OPT.def().help("The validator's moniker."))
+                .arg(
+                    SECURITY_CONTACT_OPT
+                        .def()
+                        .help("The validator's security contact."),
+                )
                 .arg(VALIDATOR_CODE_PATH.def().help(
                     "The path to the validity predicate WASM code to be used \
                      for the validator account. Uses the default validator VP \
@@ -4291,6 +4814,8 @@ pub mod args {
                 description: self.description,
                 website: self.website,
                 discord_handle: self.discord_handle,
+                moniker: self.moniker,
+                security_contact: self.security_contact,
                 validator_vp_code_path: self
                     .validator_vp_code_path
                     .to_path_buf(),
@@ -4321,6 +4846,8 @@ pub mod args {
             let description = DESCRIPTION_OPT.parse(matches);
             let website = WEBSITE_OPT.parse(matches);
             let discord_handle = DISCORD_OPT.parse(matches);
+            let moniker = MONIKER_OPT.parse(matches);
+            let security_contact = SECURITY_CONTACT_OPT.parse(matches);
             let validator_vp_code_path = VALIDATOR_CODE_PATH
                 .parse(matches)
                 .unwrap_or_else(|| PathBuf::from(VP_USER_WASM));
@@ -4344,6 +4871,8 @@ pub mod args {
                 description,
                 website,
                 discord_handle,
+                moniker,
+                security_contact,
                 validator_vp_code_path,
                 unsafe_dont_encrypt,
                 tx_init_account_code_path,
@@ -4396,6 +4925,12 @@ pub mod args {
                 .arg(DESCRIPTION_OPT.def().help("The validator's description."))
                 .arg(WEBSITE_OPT.def().help("The validator's website."))
                 .arg(DISCORD_OPT.def().help("The validator's discord handle."))
+                .arg(MONIKER_OPT.def().help("The validator's moniker."))
+                .arg(
+                    SECURITY_CONTACT_OPT
+                        .def()
+                        .help("The validator's security contact."),
+                )
                 .arg(VALIDATOR_CODE_PATH.def().help(
                     "The path to the validity predicate WASM code to be used \
                      for the validator account. Uses the default validator VP \
@@ -4428,6 +4963,7 @@ pub mod args {
                     .map(|pk| chain_ctx.get(pk))
                     .collect(),
                 threshold: self.threshold,
+                allowed_tx_code_paths: self.allowed_tx_code_paths,
             }
         }
     }
@@ -4440,6 +4976,13 @@ pub mod args {
             let tx_code_path = PathBuf::from(TX_UPDATE_ACCOUNT_WASM);
             let public_keys = PUBLIC_KEYS.parse(matches);
             let threshold = THRESHOLD.parse(matches);
+            let allowed_tx_code_paths = if CLEAR_ALLOWED_TX_CODES.parse(matches)
+            {
+                Some(vec![])
+            } else {
+                Some(ALLOWED_TX_CODES.parse(matches))
+                    .filter(|code_paths| !code_paths.is_empty())
+            };
             Self {
                 tx,
                 vp_code_path,
@@ -4447,6 +4990,7 @@ pub mod args {
                 tx_code_path,
                 public_keys,
                 threshold,
+                allowed_tx_code_paths,
             }
         }
 
@@ -4470,6 +5014,20 @@ pub mod args {
                      authorization. Must be less then the maximum number of \
                      public keys provided.",
                 ))
+                .arg(ALLOWED_TX_CODES.def().help(
+                    "The paths to the WASM codes of the only txs allowed to \
+                     act on the account's storage. The code of this tx must \
+                     be among them.",
+                ))
+                .arg(
+                    CLEAR_ALLOWED_TX_CODES
+                        .def()
+                        .help(
+                            "Allow any tx to act on the account's storage \
+                             again.",
+                        )
+                        .conflicts_with(ALLOWED_TX_CODES.name),
+                )
         }
     }
 
@@ -4533,6 +5091,7 @@ pub mod args {
                 validator: chain_ctx.get(&self.validator),
                 amount: self.amount,
                 source: self.source.map(|x| chain_ctx.get(&x)),
+                auto_withdraw: self.auto_withdraw,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
         }
@@ -4552,12 +5111,14 @@ pub mod args {
                 })
                 .amount;
             let source = SOURCE_OPT.parse(matches);
+            let auto_withdraw = AUTO_WITHDRAW.parse(matches);
             let tx_code_path = PathBuf::from(TX_UNBOND_WASM);
             Self {
                 tx,
                 validator,
                 amount,
                 source,
+                auto_withdraw,
                 tx_code_path,
             }
         }
@@ -4575,6 +5136,11 @@ pub mod args {
                      unbonding from self-bonds, the validator is also the \
                      source.",
                 ))
+                .arg(AUTO_WITHDRAW.def().help(
+                    "Automatically withdraw the unbonded tokens back to the \
+                     source at the start of the epoch in which they become \
+                     withdrawable.",
+                ))
         }
     }
 
@@ -4714,6 +5280,11 @@ pub mod args {
                 is_offline: self.is_offline,
                 is_pgf_stewards: self.is_pgf_stewards,
                 is_pgf_funding: self.is_pgf_funding,
+                is_parameter_change: self.is_parameter_change,
+                is_code_allowlist: self.is_code_allowlist,
+                is_consensus_params: self.is_consensus_params,
+                is_treasury_spend: self.is_treasury_spend,
+                is_text: self.is_text,
                 native_token: ctx.borrow_chain_or_exit().native_token.clone(),
                 tx_code_path: self.tx_code_path,
             }
@@ -4727,6 +5298,11 @@ pub mod args {
             let is_offline = PROPOSAL_OFFLINE.parse(matches);
             let is_pgf_stewards = PROPOSAL_PGF_STEWARD.parse(matches);
             let is_pgf_funding = PROPOSAL_PGF_FUNDING.parse(matches);
+            let is_parameter_change = PROPOSAL_PARAMETER_CHANGE.parse(matches);
+            let is_code_allowlist = PROPOSAL_CODE_ALLOWLIST.parse(matches);
+            let is_consensus_params = PROPOSAL_CONSENSUS_PARAMS.parse(matches);
+            let is_treasury_spend = PROPOSAL_TREASURY_SPEND.parse(matches);
+            let is_text = PROPOSAL_TEXT.parse(matches);
             let tx_code_path = PathBuf::from(TX_INIT_PROPOSAL);
 
             Self {
@@ -4737,6 +5313,11 @@ pub mod args {
                 is_offline,
                 is_pgf_stewards,
                 is_pgf_funding,
+                is_parameter_change,
+                is_code_allowlist,
+                is_consensus_params,
+                is_treasury_spend,
+                is_text,
             }
         }
 
@@ -4756,6 +5337,11 @@ pub mod args {
                             PROPOSAL_PGF_FUNDING.name,
                             PROPOSAL_PGF_STEWARD.name,
                             PROPOSAL_ETH.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
                         ]),
                 )
                 .arg(
@@ -4765,6 +5351,11 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_PGF_FUNDING.name,
                             PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
                         ]),
                 )
                 .arg(
@@ -4777,6 +5368,11 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_ETH.name,
                             PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
                         ]),
                 )
                 .arg(
@@ -4789,6 +5385,101 @@ pub mod args {
                         .conflicts_with_all([
                             PROPOSAL_ETH.name,
                             PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_PARAMETER_CHANGE
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type \
+                             parameter-change. Used to update protocol \
+                             parameters without a WASM payload.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_CODE_ALLOWLIST
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type code-allowlist. \
+                             Used to add or remove tx and VP code hashes from \
+                             the allowlists.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_CONSENSUS_PARAMS
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type \
+                             consensus-params. Used to change the block and \
+                             evidence parameters of CometBFT.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_TREASURY_SPEND.name,
+                            PROPOSAL_TEXT.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_TREASURY_SPEND
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type treasury-spend. \
+                             Used to transfer native tokens from the PGF \
+                             treasury to a recipient.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TEXT.name,
+                        ]),
+                )
+                .arg(
+                    PROPOSAL_TEXT
+                        .def()
+                        .help(
+                            "Flag if the proposal is of type text. Used to \
+                             signal the support of its content without a \
+                             payload, with lower locked funds.",
+                        )
+                        .conflicts_with_all([
+                            PROPOSAL_ETH.name,
+                            PROPOSAL_PGF_STEWARD.name,
+                            PROPOSAL_PGF_FUNDING.name,
+                            PROPOSAL_PARAMETER_CHANGE.name,
+                            PROPOSAL_CODE_ALLOWLIST.name,
+                            PROPOSAL_CONSENSUS_PARAMS.name,
+                            PROPOSAL_TREASURY_SPEND.name,
                         ]),
                 )
         }
@@ -4843,11 +5534,9 @@ pub mod args {
                             DATA_PATH_OPT.name,
                         ]),
                 )
-                .arg(
-                    PROPOSAL_VOTE
-                        .def()
-                        .help("The vote for the proposal. Either yay or nay."),
-                )
+                .arg(PROPOSAL_VOTE.def().help(
+                    "The vote for the proposal. Either yay, nay or veto.",
+                ))
                 .arg(
                     PROPOSAL_OFFLINE
                         .def()
@@ -4868,6 +5557,38 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<WithdrawProposal<SdkTypes>> for WithdrawProposal<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> WithdrawProposal<SdkTypes> {
+            WithdrawProposal::<SdkTypes> {
+                tx: self.tx.to_sdk(ctx),
+                proposal_id: self.proposal_id,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for WithdrawProposal<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let proposal_id = PROPOSAL_ID.parse(matches);
+            let tx_code_path = PathBuf::from(TX_WITHDRAW_PROPOSAL);
+
+            Self {
+                tx,
+                proposal_id,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>().arg(
+                PROPOSAL_ID
+                    .def()
+                    .help("The identifier of the proposal to withdraw."),
+            )
+        }
+    }
+
     impl CliToSdk<RevealPk<SdkTypes>> for RevealPk<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> RevealPk<SdkTypes> {
             let tx = self.tx.to_sdk(ctx);
@@ -4898,6 +5619,10 @@ pub mod args {
             QueryProposal::<SdkTypes> {
                 query: self.query.to_sdk(ctx),
                 proposal_id: self.proposal_id,
+                state: self.state,
+                author: self.author.map(|x| ctx.get(&x)),
+                from_epoch: self.from_epoch,
+                to_epoch: self.to_epoch,
             }
         }
     }
@@ -4906,13 +5631,52 @@ pub mod args {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
             let proposal_id = PROPOSAL_ID_OPT.parse(matches);
+            let state = PROPOSAL_STATE_OPT.parse(matches);
+            let author = PROPOSAL_AUTHOR_OPT.parse(matches);
+            let from_epoch = FROM_EPOCH_OPT.parse(matches);
+            let to_epoch = TO_EPOCH_OPT.parse(matches);
 
-            Self { query, proposal_id }
+            Self {
+                query,
+                proposal_id,
+                state,
+                author,
+                from_epoch,
+                to_epoch,
+            }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Query<CliTypes>>()
-                .arg(PROPOSAL_ID_OPT.def().help("The proposal identifier."))
+                .arg(
+                    PROPOSAL_ID_OPT
+                        .def()
+                        .help("The proposal identifier.")
+                        .conflicts_with_all([
+                            PROPOSAL_STATE_OPT.name,
+                            PROPOSAL_AUTHOR_OPT.name,
+                            FROM_EPOCH_OPT.name,
+                            TO_EPOCH_OPT.name,
+                        ]),
+                )
+                .arg(PROPOSAL_STATE_OPT.def().help(
+                    "List only the proposals in this state. Either pending, \
+                     voting, tallying, passed, rejected, executed or \
+                     execution-failed.",
+                ))
+                .arg(
+                    PROPOSAL_AUTHOR_OPT
+                        .def()
+                        .help("List only the proposals of this author."),
+                )
+                .arg(FROM_EPOCH_OPT.def().help(
+                    "List only the proposals whose voting period ends at or \
+                     after this epoch.",
+                ))
+                .arg(TO_EPOCH_OPT.def().help(
+                    "List only the proposals whose voting period starts at or \
+                     before this epoch.",
+                ))
         }
     }
 
@@ -5108,6 +5872,94 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<AutoCompound<SdkTypes>> for AutoCompound<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> AutoCompound<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            AutoCompound::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                source: self.source.map(|x| chain_ctx.get(&x)),
+                enabled: self.enabled,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for AutoCompound<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let source = SOURCE_OPT.parse(matches);
+            let enabled = !DISABLE.parse(matches);
+            let tx_code_path = PathBuf::from(TX_AUTO_COMPOUND_WASM);
+            Self {
+                tx,
+                validator,
+                source,
+                enabled,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(SOURCE_OPT.def().help(
+                    "Source address of a delegation. For self-bonds, the \
+                     validator is also the source.",
+                ))
+                .arg(DISABLE.def().help(
+                    "Disable the automatic compounding of the rewards of the \
+                     bond, instead of enabling it.",
+                ))
+        }
+    }
+
+    impl CliToSdk<SlashingInsurance<SdkTypes>> for SlashingInsurance<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> SlashingInsurance<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_chain_or_exit();
+            SlashingInsurance::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                source: self.source.map(|x| chain_ctx.get(&x)),
+                enabled: self.enabled,
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for SlashingInsurance<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let source = SOURCE_OPT.parse(matches);
+            let enabled = !DISABLE.parse(matches);
+            let tx_code_path = PathBuf::from(TX_SLASHING_INSURANCE_WASM);
+            Self {
+                tx,
+                validator,
+                source,
+                enabled,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help("Validator address."))
+                .arg(SOURCE_OPT.def().help(
+                    "Source address of a delegation. For self-bonds, the \
+                     validator is also the source.",
+                ))
+                .arg(DISABLE.def().help(
+                    "Opt the bond out of the slashing insurance pool, instead \
+                     of opting it in.",
+                ))
+        }
+    }
+
     impl CliToSdk<QueryConversions<SdkTypes>> for QueryConversions<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> QueryConversions<SdkTypes> {
             QueryConversions::<SdkTypes> {
@@ -5150,6 +6002,7 @@ pub mod args {
             QueryAccount::<SdkTypes> {
                 query: self.query.to_sdk(ctx),
                 owner: ctx.borrow_chain_or_exit().get(&self.owner),
+                height: self.height,
             }
         }
     }
@@ -5158,16 +6011,27 @@ pub mod args {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
             let owner = OWNER.parse(matches);
-            Self { query, owner }
+            let height = BLOCK_HEIGHT_OPT.parse(matches);
+            Self {
+                query,
+                owner,
+                height,
+            }
         }
 
         fn def(app: App) -> App {
-            app.add_args::<Query<CliTypes>>().arg(
-                OWNER
-                    .def()
-                    .help("The substorage space address to query.")
-                    .required(true),
-            )
+            app.add_args::<Query<CliTypes>>()
+                .arg(
+                    OWNER
+                        .def()
+                        .help("The substorage space address to query.")
+                        .required(true),
+                )
+                .arg(BLOCK_HEIGHT_OPT.def().help(
+                    "The block height at which to query the account. \
+                     Defaults to the last committed block. The height must \
+                     be within the past height limit of the queried node.",
+                ))
         }
     }
 
@@ -5180,6 +6044,7 @@ pub mod args {
                 owner: self.owner.map(|x| chain_ctx.get_cached(&x)),
                 token: self.token.map(|x| chain_ctx.get(&x)),
                 no_conversions: self.no_conversions,
+                height: self.height,
             }
         }
     }
@@ -5190,11 +6055,13 @@ pub mod args {
             let owner = BALANCE_OWNER.parse(matches);
             let token = TOKEN_OPT.parse(matches);
             let no_conversions = NO_CONVERSIONS.parse(matches);
+            let height = BLOCK_HEIGHT_OPT.parse(matches);
             Self {
                 query,
                 owner,
                 token,
                 no_conversions,
+                height,
             }
         }
 
@@ -5215,6 +6082,12 @@ pub mod args {
                         "Whether not to automatically perform conversions.",
                     ),
                 )
+                .arg(BLOCK_HEIGHT_OPT.def().help(
+                    "The block height at which to query the transparent \
+                     balance of an owner and token. Defaults to the last \
+                     committed block. The height must be within the past \
+                     height limit of the queried node.",
+                ))
         }
     }
 
@@ -5443,6 +6316,101 @@ pub mod args {
         }
     }
 
+    impl CliToSdk<ProtocolKeyChange<SdkTypes>> for ProtocolKeyChange<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> ProtocolKeyChange<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            ProtocolKeyChange::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                protocol_key: self.protocol_key.map(|x| chain_ctx.get(&x)),
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for ProtocolKeyChange<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let protocol_key = PROTOCOL_KEY.parse(matches);
+            let tx_code_path = PathBuf::from(TX_CHANGE_PROTOCOL_KEY_WASM);
+            Self {
+                tx,
+                validator,
+                protocol_key,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help(
+                    "The validator's address whose protocol key to change.",
+                ))
+                .arg(PROTOCOL_KEY.def().help(
+                    "The desired new protocol key. A new one will be \
+                     generated if none given.",
+                ))
+        }
+    }
+
+    impl CliToSdk<EthBridgeKeysChange<SdkTypes>> for EthBridgeKeysChange<CliTypes> {
+        fn to_sdk(self, ctx: &mut Context) -> EthBridgeKeysChange<SdkTypes> {
+            let tx = self.tx.to_sdk(ctx);
+            let chain_ctx = ctx.borrow_mut_chain_or_exit();
+            EthBridgeKeysChange::<SdkTypes> {
+                tx,
+                validator: chain_ctx.get(&self.validator),
+                eth_hot_key: self.eth_hot_key.map(|x| chain_ctx.get(&x)),
+                eth_cold_key: self.eth_cold_key.map(|x| chain_ctx.get(&x)),
+                tx_code_path: self.tx_code_path.to_path_buf(),
+            }
+        }
+    }
+
+    impl Args for EthBridgeKeysChange<CliTypes> {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let eth_hot_key = VALIDATOR_ETH_HOT_KEY.parse(matches);
+            let eth_cold_key = VALIDATOR_ETH_COLD_KEY.parse(matches);
+            let tx_code_path = PathBuf::from(TX_CHANGE_ETH_BRIDGE_KEYS_WASM);
+            Self {
+                tx,
+                validator,
+                eth_hot_key,
+                eth_cold_key,
+                tx_code_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx<CliTypes>>()
+                .arg(VALIDATOR.def().help(
+                    "The validator's address whose Eth bridge keys to change.",
+                ))
+                .arg(VALIDATOR_ETH_HOT_KEY.def().help(
+                    "The desired new Eth hot key, used by the validator's \
+                     node to sign Ethereum bridge txs. Its secret key must be \
+                     in the wallet. Note this key must be secp256k1.",
+                ))
+                .arg(VALIDATOR_ETH_COLD_KEY.def().help(
+                    "The desired new Eth cold key. Note this key must be \
+                     secp256k1.",
+                ))
+                .group(
+                    ArgGroup::new("eth_keys")
+                        .args([
+                            VALIDATOR_ETH_HOT_KEY.name,
+                            VALIDATOR_ETH_COLD_KEY.name,
+                        ])
+                        .multiple(true)
+                        .required(true),
+                )
+        }
+    }
+
     impl CliToSdk<MetaDataChange<SdkTypes>> for MetaDataChange<CliTypes> {
         fn to_sdk(self, ctx: &mut Context) -> MetaDataChange<SdkTypes> {
             MetaDataChange::<SdkTypes> {
@@ -5452,6 +6420,8 @@ pub mod args {
                 description: self.description,
                 website: self.website,
                 discord_handle: self.discord_handle,
+                moniker: self.moniker,
+                security_contact: self.security_contact,
                 commission_rate: self.commission_rate,
                 tx_code_path: self.tx_code_path.to_path_buf(),
             }
@@ -5466,6 +6436,8 @@ pub mod args {
             let description = DESCRIPTION_OPT.parse(matches);
             let website = WEBSITE_OPT.parse(matches);
             let discord_handle = DISCORD_OPT.parse(matches);
+            let moniker = MONIKER_OPT.parse(matches);
+            let security_contact = SECURITY_CONTACT_OPT.parse(matches);
             let commission_rate = COMMISSION_RATE_OPT.parse(matches);
             let tx_code_path = PathBuf::from(TX_CHANGE_METADATA_WASM);
             Self {
@@ -5475,6 +6447,8 @@ pub mod args {
                 description,
                 website,
                 discord_handle,
+                moniker,
+                security_contact,
                 commission_rate,
                 tx_code_path,
             }
@@ -5503,6 +6477,15 @@ pub mod args {
                      existing discord handle, pass an empty string to this \
                      argument.",
                 ))
+                .arg(MONIKER_OPT.def().help(
+                    "The desired new validator moniker. To remove the \
+                     existing moniker, pass an empty string to this argument.",
+                ))
+                .arg(SECURITY_CONTACT_OPT.def().help(
+                    "The desired new validator security contact. To remove \
+                     the existing security contact, pass an empty string to \
+                     this argument.",
+                ))
                 .arg(
                     COMMISSION_RATE_OPT
                         .def()
@@ -6823,6 +7806,8 @@ pub mod args {
         pub description: Option<String>,
         pub website: Option<String>,
         pub discord_handle: Option<String>,
+        pub moniker: Option<String>,
+        pub security_contact: Option<String>,
         pub address: EstablishedAddress,
         pub tx_path: PathBuf,
     }
@@ -6842,6 +7827,8 @@ pub mod args {
             let description = DESCRIPTION_OPT.parse(matches);
             let website = WEBSITE_OPT.parse(matches);
             let discord_handle = DISCORD_OPT.parse(matches);
+            let moniker = MONIKER_OPT.parse(matches);
+            let security_contact = SECURITY_CONTACT_OPT.parse(matches);
             let address = RAW_ADDRESS_ESTABLISHED.parse(matches);
             let tx_path = PATH.parse(matches);
             Self {
@@ -6856,6 +7843,8 @@ pub mod args {
                 description,
                 website,
                 discord_handle,
+                moniker,
+                security_contact,
                 tx_path,
                 address,
             }
@@ -6913,6 +7902,51 @@ pub mod args {
                     "The validator's discord handle. This is an optional \
                      parameter.",
                 ))
+                .arg(MONIKER_OPT.def().help(
+                    "The validator's moniker. This is an optional parameter.",
+                ))
+                .arg(SECURITY_CONTACT_OPT.def().help(
+                    "The validator's security contact. This is an optional \
+                     parameter.",
+                ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct InitLocalnetValidators {
+        /// Templates dir
+        pub templates_path: PathBuf,
+        /// The number of validators to add
+        pub num_validators: u64,
+        /// The self-bond of each validator, in native tokens
+        pub self_bond_amount: token::DenominatedAmount,
+    }
+
+    impl Args for InitLocalnetValidators {
+        fn parse(matches: &ArgMatches) -> Self {
+            let templates_path = TEMPLATES_PATH.parse(matches);
+            let num_validators = NUM_VALIDATORS.parse(matches);
+            let self_bond_amount = SELF_BOND_AMOUNT.parse(matches);
+            Self {
+                templates_path,
+                num_validators,
+                self_bond_amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(TEMPLATES_PATH.def().help(
+                "Path to the directory with the genesis templates to update.",
+            ))
+            .arg(
+                NUM_VALIDATORS
+                    .def()
+                    .help("The number of genesis validators to add."),
+            )
+            .arg(SELF_BOND_AMOUNT.def().help(
+                "The amount of native token to self-bond in PoS, for each \
+                 validator.",
+            ))
         }
     }
 
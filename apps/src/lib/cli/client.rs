@@ -176,6 +176,17 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_vote_proposal(&namada, args).await?;
                     }
+                    Sub::TxWithdrawProposal(TxWithdrawProposal(mut args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_withdraw_proposal(&namada, args).await?;
+                    }
                     Sub::TxRevealPk(TxRevealPk(mut args)) => {
                         let client = client.unwrap_or_else(|| {
                             C::from_tendermint_address(
@@ -231,6 +242,28 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_claim_rewards(&namada, args).await?;
                     }
+                    Sub::AutoCompound(AutoCompound(mut args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_auto_compound(&namada, args).await?;
+                    }
+                    Sub::SlashingInsurance(SlashingInsurance(mut args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_slashing_insurance(&namada, args).await?;
+                    }
                     Sub::Redelegate(Redelegate(mut args)) => {
                         let client = client.unwrap_or_else(|| {
                             C::from_tendermint_address(
@@ -286,6 +319,64 @@ impl CliApi {
                         )
                         .await?;
                     }
+                    Sub::TxChangeProtocolKey(TxChangeProtocolKey(mut args)) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let cli::context::ChainContext {
+                            wallet,
+                            mut config,
+                            shielded,
+                            native_token,
+                        } = ctx.take_chain_or_exit();
+                        let namada = NamadaImpl::native_new(
+                            client,
+                            wallet,
+                            shielded,
+                            io,
+                            native_token,
+                        );
+                        tx::submit_change_protocol_key(
+                            &namada,
+                            &mut config,
+                            args,
+                        )
+                        .await?;
+                    }
+                    Sub::TxChangeEthBridgeKeys(TxChangeEthBridgeKeys(
+                        mut args,
+                    )) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let cli::context::ChainContext {
+                            wallet,
+                            mut config,
+                            shielded,
+                            native_token,
+                        } = ctx.take_chain_or_exit();
+                        let namada = NamadaImpl::native_new(
+                            client,
+                            wallet,
+                            shielded,
+                            io,
+                            native_token,
+                        );
+                        tx::submit_change_eth_bridge_keys(
+                            &namada,
+                            &mut config,
+                            args,
+                        )
+                        .await?;
+                    }
                     Sub::TxMetadataChange(TxMetadataChange(mut args)) => {
                         let client = client.unwrap_or_else(|| {
                             C::from_tendermint_address(
@@ -311,6 +402,19 @@ impl CliApi {
                         let namada = ctx.to_sdk(client, io);
                         tx::submit_bridge_pool_tx(&namada, args).await?;
                     }
+                    Sub::CancelEthBridgePoolTransfer(
+                        CancelEthBridgePoolTransfer(mut args),
+                    ) => {
+                        let client = client.unwrap_or_else(|| {
+                            C::from_tendermint_address(
+                                &mut args.tx.ledger_address,
+                            )
+                        });
+                        client.wait_until_node_is_synced(&io).await?;
+                        let args = args.to_sdk(&mut ctx);
+                        let namada = ctx.to_sdk(client, io);
+                        tx::submit_cancel_bridge_pool_tx(&namada, args).await?;
+                    }
                     Sub::TxUnjailValidator(TxUnjailValidator(mut args)) => {
                         let client = client.unwrap_or_else(|| {
                             C::from_tendermint_address(
@@ -657,6 +761,9 @@ impl CliApi {
                 Utils::InitGenesisValidator(InitGenesisValidator(args)) => {
                     utils::init_genesis_validator(global_args, args)
                 }
+                Utils::InitLocalnetValidators(InitLocalnetValidators(args)) => {
+                    utils::init_localnet_validators(global_args, args).await
+                }
                 Utils::PkToTmAddress(PkToTmAddress(args)) => {
                     utils::pk_to_tm_address(global_args, args)
                 }
@@ -91,6 +91,15 @@ impl CliApi {
                     client.wait_until_node_is_synced(&io).await?;
                     bridge_pool::query_relay_progress(&client, &io).await?;
                 }
+                EthBridgePoolWithoutCtx::QueryEthereumHeight(
+                    QueryEthereumHeight(mut query),
+                ) => {
+                    let client = client.unwrap_or_else(|| {
+                        C::from_tendermint_address(&mut query.ledger_address)
+                    });
+                    client.wait_until_node_is_synced(&io).await?;
+                    bridge_pool::query_ethereum_height(&client, &io).await?;
+                }
             },
             cli::NamadaRelayer::ValidatorSet(sub) => match sub {
                 ValidatorSet::BridgeValidatorSet(BridgeValidatorSet(
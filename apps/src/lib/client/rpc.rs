@@ -25,7 +25,8 @@ use namada::core::ledger::governance::storage::proposal::{
     StoragePgfFunding, StorageProposal,
 };
 use namada::core::ledger::governance::utils::{
-    compute_proposal_result, ProposalVotes, TallyType, TallyVote, VotePower,
+    compute_proposal_result, ProposalVotes, ProposalsFilter, TallyType,
+    TallyVote, VotePower,
 };
 use namada::core::ledger::pgf::parameters::PgfParameters;
 use namada::core::ledger::pgf::storage::steward::StewardDetail;
@@ -36,6 +37,7 @@ use namada::ledger::ibc::storage::{
 use namada::ledger::parameters::{storage as param_storage, EpochDuration};
 use namada::ledger::pos::types::{CommissionPair, Slash};
 use namada::ledger::pos::PosParams;
+use namada::ledger::queries::vp::governance::MAX_PROPOSALS_PER_PAGE;
 use namada::ledger::queries::RPC;
 use namada::proof_of_stake::types::{ValidatorState, WeightedValidator};
 use namada::types::address::{Address, InternalAddress, MASP};
@@ -282,6 +284,18 @@ pub async fn query_raw_bytes<N: Namada>(
 
 /// Query token balance(s)
 pub async fn query_balance(context: &impl Namada, args: args::QueryBalance) {
+    // Only transparent balances are read from the stored diffs at a past
+    // height
+    if args.height.is_some()
+        && !matches!(args.owner, Some(BalanceOwner::Address(_)))
+    {
+        edisplay_line!(
+            context.io(),
+            "A balance can only be queried at a past height for a transparent \
+             address owner."
+        );
+        cli::safe_exit(1)
+    }
     // Query the balances of shielded or transparent account types depending on
     // the CLI arguments
     match &args.owner {
@@ -320,13 +334,26 @@ pub async fn query_transparent_balance(
             let tokens =
                 query_tokens(context, Some(&base_token), Some(&owner)).await;
             for (token_alias, token) in tokens {
-                let balance_key = token::balance_key(&token, &owner);
-                match query_storage_value::<_, token::Amount>(
-                    context.client(),
-                    &balance_key,
-                )
-                .await
-                {
+                let balance = match args.height {
+                    Some(height) => {
+                        rpc::get_token_balance_at_height(
+                            context.client(),
+                            &token,
+                            &owner,
+                            height,
+                        )
+                        .await
+                    }
+                    None => {
+                        let balance_key = token::balance_key(&token, &owner);
+                        query_storage_value::<_, token::Amount>(
+                            context.client(),
+                            &balance_key,
+                        )
+                        .await
+                    }
+                };
+                match balance {
                     Ok(balance) => {
                         let balance =
                             context.format_amount(&token, balance).await;
@@ -353,8 +380,26 @@ pub async fn query_transparent_balance(
             let owner = owner.address().unwrap();
             let tokens = query_tokens(context, None, Some(&owner)).await;
             for (token_alias, token) in tokens {
-                let balance =
-                    get_token_balance(context.client(), &token, &owner).await;
+                let balance = match args.height {
+                    Some(height) => match rpc::get_token_balance_at_height(
+                        context.client(),
+                        &token,
+                        &owner,
+                        height,
+                    )
+                    .await
+                    {
+                        Ok(balance) => balance,
+                        Err(e) => {
+                            edisplay_line!(context.io(), "Querying error: {e}");
+                            continue;
+                        }
+                    },
+                    None => {
+                        get_token_balance(context.client(), &token, &owner)
+                            .await
+                    }
+                };
                 if !balance.is_zero() {
                     let balance = context.format_amount(&token, balance).await;
                     display_line!(context.io(), "{}: {}", token_alias, balance);
@@ -738,30 +783,59 @@ pub async fn query_proposal(context: &impl Namada, args: args::QueryProposal) {
                 "{}",
                 proposal.to_string_with_status(current_epoch)
             );
+            let execution = rpc::query_proposal_execution(context.client(), id)
+                .await
+                .unwrap();
+            if let Some(execution) = execution {
+                display_line!(context.io(), "{:2}Execution: {}", "", execution);
+            }
         } else {
             edisplay_line!(context.io(), "No proposal found with id: {}", id);
         }
     } else {
-        let last_proposal_id_key = governance_storage::get_counter_key();
-        let last_proposal_id: u64 =
-            query_storage_value(context.client(), &last_proposal_id_key)
-                .await
-                .unwrap();
-
-        let from_id = if last_proposal_id > 10 {
-            last_proposal_id - 10
+        let filter = ProposalsFilter {
+            state: args.state,
+            author: args.author,
+            from_epoch: args.from_epoch,
+            to_epoch: args.to_epoch,
+        };
+        // without any filters, only the last proposals are listed
+        let after = if filter == ProposalsFilter::default() {
+            let last_proposal_id_key = governance_storage::get_counter_key();
+            let last_proposal_id: u64 =
+                query_storage_value(context.client(), &last_proposal_id_key)
+                    .await
+                    .unwrap();
+            display_line!(context.io(), "id: {}", last_proposal_id);
+            last_proposal_id.checked_sub(11)
         } else {
-            0
+            None
         };
 
-        display_line!(context.io(), "id: {}", last_proposal_id);
-
-        for id in from_id..last_proposal_id {
-            let proposal = query_proposal_by_id(context.client(), id)
-                .await
-                .unwrap()
-                .expect("Proposal should be written to storage.");
-            display_line!(context.io(), "{}", proposal);
+        let mut page = rpc::query_proposals_page(
+            context.client(),
+            MAX_PROPOSALS_PER_PAGE,
+            after,
+            filter.clone(),
+        )
+        .await
+        .unwrap();
+        loop {
+            for (proposal, state) in &page.proposals {
+                display_line!(context.io(), "{}", proposal);
+                display_line!(context.io(), "{:2}State: {}", "", state);
+            }
+            if page.next.is_none() {
+                break;
+            }
+            page = rpc::query_proposals_page(
+                context.client(),
+                MAX_PROPOSALS_PER_PAGE,
+                page.next,
+                filter.clone(),
+            )
+            .await
+            .unwrap();
         }
     }
 }
@@ -1111,53 +1185,44 @@ pub async fn query_proposal_result(
     if args.proposal_id.is_some() {
         let proposal_id =
             args.proposal_id.expect("Proposal id should be defined.");
-        let proposal = if let Some(proposal) =
-            query_proposal_by_id(context.client(), proposal_id)
+        let proposal_result = if let Some(proposal_result) =
+            rpc::query_proposal_tally(context.client(), proposal_id)
                 .await
                 .unwrap()
         {
-            proposal
+            proposal_result
         } else {
             edisplay_line!(context.io(), "Proposal {} not found.", proposal_id);
             return;
         };
 
-        let proposal_result_key =
-            governance_storage::get_proposal_result_key(proposal_id);
-        let proposal_result =
-        // Try to directly query the result in storage first
-            match query_storage_value(context.client(), &proposal_result_key).await {
-                Ok(result) => result,
-                Err(_) => {
-                    // If failure, run the tally
-                    let is_author_steward = query_pgf_stewards(context.client())
-                        .await
-                        .iter()
-                        .any(|steward| steward.address.eq(&proposal.author));
-                    let tally_type = proposal.get_tally_type(is_author_steward);
-                    let total_voting_power = get_total_staked_tokens(
-                        context.client(),
-                        proposal.voting_end_epoch,
-                    )
-                    .await;
-
-                    let votes = compute_proposal_votes(
-                        context.client(),
-                        proposal_id,
-                        proposal.voting_end_epoch,
-                    )
-                    .await;
-
-                    compute_proposal_result(
-                        votes,
-                        total_voting_power,
-                        tally_type,
-                    )
-                }
-            };
-
         display_line!(context.io(), "Proposal Id: {} ", proposal_id);
         display_line!(context.io(), "{:4}{}", "", proposal_result);
+
+        if let Some(snapshot) =
+            rpc::query_proposal_bridge_valset(context.client(), proposal_id)
+                .await
+                .unwrap()
+        {
+            display_line!(
+                context.io(),
+                "{:4}Bridge validator set snapshot at epoch {}:",
+                "",
+                snapshot.bridge.epoch
+            );
+            display_line!(
+                context.io(),
+                "{:8}Bridge hash: {}",
+                "",
+                snapshot.bridge_hash
+            );
+            display_line!(
+                context.io(),
+                "{:8}Governance hash: {}",
+                "",
+                snapshot.governance_hash
+            );
+        }
     } else {
         let proposal_folder = args.proposal_folder.expect(
             "The argument --proposal-folder is required with --offline.",
@@ -1227,11 +1292,14 @@ pub async fn query_proposal_result(
             proposal.proposal.tally_epoch,
         )
         .await;
+        let governance_parameters =
+            query_governance_parameters(context.client()).await;
 
         let proposal_result = compute_proposal_result(
             proposal_votes,
             total_voting_power,
             TallyType::TwoThirds,
+            governance_parameters.veto_threshold,
         );
 
         display_line!(
@@ -1245,9 +1313,18 @@ pub async fn query_proposal_result(
 }
 
 pub async fn query_account(context: &impl Namada, args: args::QueryAccount) {
-    let account = rpc::get_account_info(context.client(), &args.owner)
-        .await
-        .unwrap();
+    let account = match args.height {
+        Some(height) => {
+            rpc::get_account_info_at_height(
+                context.client(),
+                &args.owner,
+                height,
+            )
+            .await
+        }
+        None => rpc::get_account_info(context.client(), &args.owner).await,
+    }
+    .unwrap();
     if let Some(account) = account {
         display_line!(context.io(), "Address: {}", account.address);
         display_line!(context.io(), "Threshold: {}", account.threshold);
@@ -1354,6 +1431,69 @@ pub async fn query_protocol_parameters(
         "",
         governance_parameters.min_proposal_grace_epochs
     );
+    display_line!(
+        context.io(),
+        "{:4}Min. proposal execution delay: {}",
+        "",
+        governance_parameters.min_proposal_execution_delay
+    );
+    display_line!(
+        context.io(),
+        "{:4}Min. expedited proposal fund: {}",
+        "",
+        governance_parameters
+            .min_expedited_proposal_fund
+            .to_string_native()
+    );
+    display_line!(
+        context.io(),
+        "{:4}Expedited proposal voting period (blocks): {}",
+        "",
+        governance_parameters.expedited_proposal_voting_period
+    );
+    display_line!(
+        context.io(),
+        "{:4}Max. proposal execution gas: {}",
+        "",
+        governance_parameters.max_proposal_execution_gas
+    );
+    display_line!(
+        context.io(),
+        "{:4}Min. text proposal fund: {}",
+        "",
+        governance_parameters
+            .min_text_proposal_fund
+            .to_string_native()
+    );
+    display_line!(
+        context.io(),
+        "{:4}Proposal withdrawal refund: {}",
+        "",
+        governance_parameters.proposal_withdrawal_refund
+    );
+    display_line!(
+        context.io(),
+        "{:4}Veto threshold: {}",
+        "",
+        governance_parameters.veto_threshold
+    );
+    match namada_sdk::rpc::query_proposal_epochs(context.client()).await {
+        Some(epochs) => display_line!(
+            context.io(),
+            "{:4}Earliest proposal epochs: voting start {}, voting end {}, \
+             grace {}",
+            "",
+            epochs.voting_start_epoch,
+            epochs.voting_end_epoch,
+            epochs.grace_epoch
+        ),
+        None => display_line!(
+            context.io(),
+            "{:4}Earliest proposal epochs: the parameters don't allow any \
+             proposal",
+            ""
+        ),
+    }
 
     let pgf_parameters = query_pgf_parameters(context.client()).await;
     display_line!(context.io(), "Public Goods Funding Parameters\n");
@@ -1991,6 +2131,8 @@ pub async fn query_and_print_metadata(
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
         }) => {
             display_line!(
                 context.io(),
@@ -2017,6 +2159,20 @@ pub async fn query_and_print_metadata(
             } else {
                 display_line!(context.io(), "No discord handle");
             }
+            if let Some(moniker) = moniker {
+                display_line!(context.io(), "Moniker: {}", moniker);
+            } else {
+                display_line!(context.io(), "No moniker");
+            }
+            if let Some(security_contact) = security_contact {
+                display_line!(
+                    context.io(),
+                    "Security contact: {}",
+                    security_contact
+                );
+            } else {
+                display_line!(context.io(), "No security contact");
+            }
         }
         None => display_line!(
             context.io(),
@@ -2660,7 +2816,8 @@ pub async fn compute_offline_proposal_votes(
     let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
     let mut validator_voting_power: HashMap<Address, VotePower> =
         HashMap::default();
-    let mut delegators_vote: HashMap<Address, TallyVote> = HashMap::default();
+    let mut delegators_vote: HashMap<Address, HashMap<Address, TallyVote>> =
+        HashMap::default();
     let mut delegator_voting_power: HashMap<
         Address,
         HashMap<Address, VotePower>,
@@ -2692,7 +2849,9 @@ pub async fn compute_offline_proposal_votes(
                     validators.get(&validator).cloned().unwrap_or_default();
 
                 delegators_vote
-                    .insert(vote.address.clone(), vote.clone().into());
+                    .entry(vote.address.clone())
+                    .or_default()
+                    .insert(validator.clone(), vote.clone().into());
                 delegator_voting_power
                     .entry(vote.address.clone())
                     .or_default()
@@ -2714,58 +2873,3 @@ pub async fn compute_offline_proposal_votes(
         delegator_voting_power,
     }
 }
-
-pub async fn compute_proposal_votes<
-    C: namada::ledger::queries::Client + Sync,
->(
-    client: &C,
-    proposal_id: u64,
-    epoch: Epoch,
-) -> ProposalVotes {
-    let votes = namada_sdk::rpc::query_proposal_votes(client, proposal_id)
-        .await
-        .unwrap();
-
-    let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
-    let mut validator_voting_power: HashMap<Address, VotePower> =
-        HashMap::default();
-    let mut delegators_vote: HashMap<Address, TallyVote> = HashMap::default();
-    let mut delegator_voting_power: HashMap<
-        Address,
-        HashMap<Address, VotePower>,
-    > = HashMap::default();
-
-    for vote in votes {
-        if vote.is_validator() {
-            let validator_stake =
-                get_validator_stake(client, epoch, &vote.validator.clone())
-                    .await
-                    .unwrap_or_default();
-
-            validators_vote.insert(vote.validator.clone(), vote.data.into());
-            validator_voting_power.insert(vote.validator, validator_stake);
-        } else {
-            let delegator_stake = get_bond_amount_at(
-                client,
-                &vote.delegator,
-                &vote.validator,
-                epoch,
-            )
-            .await
-            .unwrap_or_default();
-
-            delegators_vote.insert(vote.delegator.clone(), vote.data.into());
-            delegator_voting_power
-                .entry(vote.delegator.clone())
-                .or_default()
-                .insert(vote.validator, delegator_stake);
-        }
-    }
-
-    ProposalVotes {
-        validators_vote,
-        validator_voting_power,
-        delegators_vote,
-        delegator_voting_power,
-    }
-}
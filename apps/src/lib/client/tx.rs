@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use std::fs::File;
 use std::io::Write;
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use ledger_namada_rs::{BIP44Path, NamadaApp};
 use ledger_transport_hid::hidapi::HidApi;
@@ -11,15 +11,20 @@ use namada::core::ledger::governance::cli::offline::{
     OfflineProposal, OfflineSignedProposal, OfflineVote,
 };
 use namada::core::ledger::governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal, ProposalVote,
+    CodeAllowlistProposal, ConsensusParamsProposal, DefaultProposal,
+    ParameterChangeProposal, PgfFundingProposal, PgfStewardProposal,
+    ProposalVote, TextProposal, TreasurySpendProposal,
 };
 use namada::ibc::apps::transfer::types::Memo;
+use namada::proof_of_stake::MAX_VALIDATOR_METADATA_LEN;
 use namada::proto::{CompressedSignature, Section, Signer, Tx};
 use namada::types::address::{Address, ImplicitAddress};
 use namada::types::dec::Dec;
 use namada::types::io::Io;
 use namada::types::key::{self, *};
-use namada::types::transaction::pos::{BecomeValidator, ConsensusKeyChange};
+use namada::types::transaction::pos::{
+    BecomeValidator, ConsensusKeyChange, EthBridgeKeysChange, ProtocolKeyChange,
+};
 use namada_sdk::rpc::{TxBroadcastData, TxResponse};
 use namada_sdk::wallet::alias::validator_consensus_key;
 use namada_sdk::wallet::{Wallet, WalletIo};
@@ -258,6 +263,27 @@ pub async fn submit_bridge_pool_tx<N: Namada>(
     Ok(())
 }
 
+pub async fn submit_cancel_bridge_pool_tx<N: Namada>(
+    namada: &N,
+    args: args::CancelBridgePoolTransfer,
+) -> Result<(), error::Error> {
+    let (mut tx, signing_data, _epoch) = args.build(namada).await?;
+
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_custom<N: Namada>(
     namada: &N,
     args: args::TxCustom,
@@ -481,6 +507,269 @@ pub async fn submit_change_consensus_key(
     Ok(())
 }
 
+pub async fn submit_change_protocol_key(
+    namada: &impl Namada,
+    config: &mut crate::config::Config,
+    args::ProtocolKeyChange {
+        tx: tx_args,
+        validator,
+        protocol_key,
+        tx_code_path: _,
+    }: args::ProtocolKeyChange,
+) -> Result<(), error::Error> {
+    let tx_args = args::Tx {
+        chain_id: tx_args
+            .clone()
+            .chain_id
+            .or_else(|| Some(config.ledger.chain_id.clone())),
+        ..tx_args.clone()
+    };
+
+    let mut wallet = namada.wallet_mut().await;
+    let protocol_sk = match protocol_key {
+        Some(protocol_key) => wallet
+            .find_key_by_pk(&protocol_key, None)
+            .map_err(|err| error::Error::Other(err.to_string()))?,
+        None => {
+            display_line!(namada.io(), "Generating new protocol key...");
+            let alias =
+                new_validator_key_alias(&wallet, &validator, "protocol");
+            let password = read_and_confirm_encryption_password(false);
+            wallet
+                .gen_store_secret_key(
+                    SchemeType::Ed25519,
+                    Some(alias),
+                    tx_args.wallet_alias_force,
+                    password,
+                    &mut OsRng,
+                )
+                .expect("Key generation should not fail.")
+                .1
+        }
+    };
+    // To avoid wallet deadlocks in following operations
+    drop(wallet);
+
+    let data = ProtocolKeyChange {
+        validator: validator.clone(),
+        protocol_key: protocol_sk.ref_to(),
+    };
+    let new_pks = vec![data.protocol_key.clone()];
+    let submitted = submit_validator_keys_change(
+        namada,
+        &tx_args,
+        &validator,
+        args::TX_CHANGE_PROTOCOL_KEY_WASM,
+        data,
+        new_pks,
+    )
+    .await?;
+    if submitted {
+        record_next_validator_keys(namada, &validator, Some(protocol_sk), None)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn submit_change_eth_bridge_keys(
+    namada: &impl Namada,
+    config: &mut crate::config::Config,
+    args::EthBridgeKeysChange {
+        tx: tx_args,
+        validator,
+        eth_hot_key,
+        eth_cold_key,
+        tx_code_path: _,
+    }: args::EthBridgeKeysChange,
+) -> Result<(), error::Error> {
+    let tx_args = args::Tx {
+        chain_id: tx_args
+            .clone()
+            .chain_id
+            .or_else(|| Some(config.ledger.chain_id.clone())),
+        ..tx_args.clone()
+    };
+
+    if eth_hot_key.is_none() && eth_cold_key.is_none() {
+        edisplay_line!(
+            namada.io(),
+            "At least one of the Eth hot and cold keys must be given"
+        );
+        safe_exit(1)
+    }
+    let to_secp256k1 = |key: common::PublicKey, kind: &str| {
+        key::secp256k1::PublicKey::try_from_pk(&key).unwrap_or_else(|_| {
+            edisplay_line!(namada.io(), "Eth {kind} key can only be secp256k1");
+            safe_exit(1)
+        })
+    };
+    let eth_hot_key = eth_hot_key.map(|key| to_secp256k1(key, "hot"));
+    let eth_cold_key = eth_cold_key.map(|key| to_secp256k1(key, "cold"));
+
+    // The node of the validator signs with the secret hot key
+    let eth_hot_sk = match &eth_hot_key {
+        Some(eth_hot_key) => Some(
+            namada
+                .wallet_mut()
+                .await
+                .find_key_by_pk(
+                    &common::PublicKey::Secp256k1(eth_hot_key.clone()),
+                    None,
+                )
+                .map_err(|err| error::Error::Other(err.to_string()))?,
+        ),
+        None => None,
+    };
+
+    let data = EthBridgeKeysChange {
+        validator: validator.clone(),
+        eth_hot_key,
+        eth_cold_key,
+    };
+    let new_pks = data
+        .eth_hot_key
+        .iter()
+        .chain(data.eth_cold_key.iter())
+        .cloned()
+        .map(common::PublicKey::Secp256k1)
+        .collect();
+    let submitted = submit_validator_keys_change(
+        namada,
+        &tx_args,
+        &validator,
+        args::TX_CHANGE_ETH_BRIDGE_KEYS_WASM,
+        data,
+        new_pks,
+    )
+    .await?;
+    if submitted {
+        record_next_validator_keys(namada, &validator, None, eth_hot_sk)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Find an alias for a new key of a validator, which is not taken in the
+/// wallet yet.
+fn new_validator_key_alias<U: WalletIo>(
+    wallet: &Wallet<U>,
+    validator: &Address,
+    key_name: &str,
+) -> String {
+    let validator_alias = wallet
+        .find_alias(validator)
+        .map(ToString::to_string)
+        .unwrap_or_else(|| validator.to_string());
+    let base_alias = format!("{validator_alias}-{key_name}-key");
+    let all_keys = wallet.get_secret_keys();
+    let mut alias = base_alias.clone();
+    let mut key_counter = 0;
+    while all_keys.contains_key(&alias) {
+        key_counter += 1;
+        alias = format!("{base_alias}-{key_counter}");
+    }
+    alias
+}
+
+/// Submit a tx changing some keys of a validator. The tx is signed with the
+/// validator's account keys and, to prove their ownership, with the new
+/// keys. Returns whether the tx was actually submitted.
+async fn submit_validator_keys_change(
+    namada: &impl Namada,
+    tx_args: &args::Tx,
+    validator: &Address,
+    tx_code_path: &str,
+    data: impl BorshSerialize,
+    new_pks: Vec<common::PublicKey>,
+) -> Result<bool, error::Error> {
+    let tx_code_hash = query_wasm_code_hash(namada, tx_code_path).await?;
+
+    let chain_id = tx_args.chain_id.clone().unwrap();
+    let mut tx = Tx::new(chain_id, tx_args.expiration);
+    tx.add_code_from_hash(tx_code_hash, Some(tx_code_path.to_string()))
+        .add_data(data);
+
+    // Put together all the PKs that we have to sign with
+    let account = namada_sdk::rpc::get_account_info(namada.client(), validator)
+        .await?
+        .unwrap_or_else(|| {
+            edisplay_line!(
+                namada.io(),
+                "Unable to query account keys for address {validator}."
+            );
+            safe_exit(1)
+        });
+    let mut all_pks: Vec<_> =
+        account.public_keys_map.pk_to_idx.into_keys().collect();
+    all_pks.extend(new_pks);
+    let signing_data =
+        init_validator_signing_data(namada, tx_args, all_pks).await?;
+
+    tx::prepare_tx(
+        namada,
+        tx_args,
+        &mut tx,
+        signing_data.fee_payer.clone(),
+        None,
+    )
+    .await?;
+
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if tx_args.dump_tx {
+        tx::dump_tx(namada.io(), tx_args, tx);
+        return Ok(false);
+    }
+    sign(namada, &mut tx, tx_args, signing_data).await?;
+
+    signing::generate_test_vector(namada, &tx).await?;
+
+    namada.submit(tx, tx_args).await?;
+    Ok(!tx_args.dry_run)
+}
+
+/// Record the keypairs a validator is rotating to in the validator data of
+/// the wallet, such that its node switches to them once they are active.
+async fn record_next_validator_keys(
+    namada: &impl Namada,
+    validator: &Address,
+    next_protocol_keypair: Option<common::SecretKey>,
+    next_eth_bridge_keypair: Option<common::SecretKey>,
+) -> Result<(), error::Error> {
+    let pos_params = rpc::query_pos_parameters(namada.client()).await;
+    let current_epoch = namada_sdk::rpc::query_epoch(namada.client()).await?;
+    let activation_epoch = current_epoch + pos_params.pipeline_len;
+
+    let mut wallet = namada.wallet_mut().await;
+    match wallet.get_validator_data_mut() {
+        Some(data) if &data.address == validator => {
+            if next_protocol_keypair.is_some() {
+                data.keys.next_protocol_keypair = next_protocol_keypair;
+            }
+            if next_eth_bridge_keypair.is_some() {
+                data.keys.next_eth_bridge_keypair = next_eth_bridge_keypair;
+            }
+            wallet
+                .save()
+                .unwrap_or_else(|err| edisplay_line!(namada.io(), "{}", err));
+            display_line!(
+                namada.io(),
+                "The new keys become active at epoch {activation_epoch}. Be \
+                 sure to restart the validator's node before then, for it to \
+                 switch to the new keys."
+            );
+        }
+        _ => display_line!(
+            namada.io(),
+            "The new keys become active at epoch {activation_epoch}. The \
+             validator data of this wallet does not belong to the validator, \
+             so the new keys must be provided to the validator's node before \
+             then."
+        ),
+    }
+    Ok(())
+}
+
 pub async fn submit_become_validator(
     namada: &impl Namada,
     config: &mut crate::config::Config,
@@ -498,6 +787,8 @@ pub async fn submit_become_validator(
         website,
         description,
         discord_handle,
+        moniker,
+        security_contact,
         unsafe_dont_encrypt,
         tx_code_path,
     }: args::TxBecomeValidator,
@@ -581,6 +872,27 @@ pub async fn submit_become_validator(
             safe_exit(1)
         }
     }
+    // Validate the length of the metadata
+    for (field, value) in [
+        ("email", Some(&email)),
+        ("description", description.as_ref()),
+        ("website", website.as_ref()),
+        ("discord handle", discord_handle.as_ref()),
+        ("moniker", moniker.as_ref()),
+        ("security contact", security_contact.as_ref()),
+    ] {
+        let len = value.map(String::len).unwrap_or_default();
+        if len > MAX_VALIDATOR_METADATA_LEN {
+            edisplay_line!(
+                namada.io(),
+                "The validator {field} is {len} bytes long, exceeding the \
+                 maximum length of {MAX_VALIDATOR_METADATA_LEN} bytes."
+            );
+            if !tx_args.force {
+                safe_exit(1)
+            }
+        }
+    }
 
     let alias = tx_args
         .initialized_account_alias
@@ -736,6 +1048,8 @@ pub async fn submit_become_validator(
         description,
         website,
         discord_handle,
+        moniker,
+        security_contact,
     };
 
     // Put together all the PKs that we have to sign with to verify ownership
@@ -870,6 +1184,8 @@ pub async fn submit_init_validator(
         website,
         description,
         discord_handle,
+        moniker,
+        security_contact,
         validator_vp_code_path,
         unsafe_dont_encrypt,
         tx_init_account_code_path,
@@ -921,6 +1237,8 @@ pub async fn submit_init_validator(
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
             tx_code_path: tx_become_validator_code_path,
             unsafe_dont_encrypt,
         },
@@ -1101,6 +1419,140 @@ where
             .await?;
 
         tx::build_pgf_stewards_proposal(namada, &args, proposal).await?
+    } else if args.is_parameter_change {
+        let proposal =
+            ParameterChangeProposal::try_from(args.proposal_data.as_ref())
+                .map_err(|e| {
+                    error::TxError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+        let author_balance = rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+        )
+        .await;
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| error::TxError::InvalidProposal(e.to_string()))?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_parameter_change_proposal(namada, &args, proposal).await?
+    } else if args.is_code_allowlist {
+        let proposal =
+            CodeAllowlistProposal::try_from(args.proposal_data.as_ref())
+                .map_err(|e| {
+                    error::TxError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+        let author_balance = rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+        )
+        .await;
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| error::TxError::InvalidProposal(e.to_string()))?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_code_allowlist_proposal(namada, &args, proposal).await?
+    } else if args.is_consensus_params {
+        let proposal =
+            ConsensusParamsProposal::try_from(args.proposal_data.as_ref())
+                .map_err(|e| {
+                    error::TxError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+        let author_balance = rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+        )
+        .await;
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| error::TxError::InvalidProposal(e.to_string()))?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_consensus_params_proposal(namada, &args, proposal).await?
+    } else if args.is_treasury_spend {
+        let proposal =
+            TreasurySpendProposal::try_from(args.proposal_data.as_ref())
+                .map_err(|e| {
+                    error::TxError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+        let author_balance = rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+        )
+        .await;
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| error::TxError::InvalidProposal(e.to_string()))?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_treasury_spend_proposal(namada, &args, proposal).await?
+    } else if args.is_text {
+        let proposal = TextProposal::try_from(args.proposal_data.as_ref())
+            .map_err(|e| {
+                error::TxError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+        let author_balance = rpc::get_token_balance(
+            namada.client(),
+            &namada.native_token(),
+            &proposal.proposal.author,
+        )
+        .await;
+        let proposal = proposal
+            .validate(
+                &governance_parameters,
+                current_epoch,
+                author_balance,
+                args.tx.force,
+            )
+            .map_err(|e| error::TxError::InvalidProposal(e.to_string()))?;
+
+        submit_reveal_aux(namada, args.tx.clone(), &proposal.proposal.author)
+            .await?;
+
+        tx::build_text_proposal(namada, &args, proposal).await?
     } else {
         let proposal = DefaultProposal::try_from(args.proposal_data.as_ref())
             .map_err(|e| {
@@ -1227,6 +1679,30 @@ where
     Ok(())
 }
 
+pub async fn submit_withdraw_proposal<N: Namada>(
+    namada: &N,
+    args: args::WithdrawProposal,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn sign_tx<N: Namada>(
     namada: &N,
     args::SignTx {
@@ -1421,6 +1897,54 @@ where
     Ok(())
 }
 
+pub async fn submit_auto_compound<N: Namada>(
+    namada: &N,
+    args: args::AutoCompound,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn submit_slashing_insurance<N: Namada>(
+    namada: &N,
+    args: args::SlashingInsurance,
+) -> Result<(), error::Error>
+where
+    <N::Client as namada::ledger::queries::Client>::Error: std::fmt::Display,
+{
+    let (mut tx, signing_data, _fee_unshield_epoch) =
+        args.build(namada).await?;
+    signing::generate_test_vector(namada, &tx).await?;
+
+    if args.tx.dump_tx {
+        tx::dump_tx(namada.io(), &args.tx, tx);
+    } else {
+        sign(namada, &mut tx, &args.tx, signing_data).await?;
+
+        signing::generate_test_vector(namada, &tx).await?;
+
+        namada.submit(tx, &args.tx).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn submit_redelegate<N: Namada>(
     namada: &N,
     args: args::Redelegate,
@@ -1,6 +1,7 @@
 use std::env;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -11,6 +12,7 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use itertools::Either;
 use namada::core::types::string_encoding::StringEncoded;
+use namada::types::address::Address;
 use namada::types::chain::ChainId;
 use namada::types::dec::Dec;
 use namada::types::key::*;
@@ -19,6 +21,7 @@ use namada::types::uint::Uint;
 use namada::vm::validate_untrusted_wasm;
 use namada_sdk::wallet::{alias, Wallet};
 use prost::bytes::Bytes;
+use rand_core::OsRng;
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use tokio::sync::RwLock;
@@ -29,6 +32,7 @@ use crate::config::genesis::chain::DeriveEstablishedAddress;
 use crate::config::genesis::transactions::{
     sign_delegation_bond_tx, sign_validator_account_tx, UnsignedTransactions,
 };
+use crate::config::genesis::GenesisAddress;
 use crate::config::global::GlobalConfig;
 use crate::config::{
     self, genesis, get_default_namada_folder, Config, TendermintMode,
@@ -51,6 +55,13 @@ const DEFAULT_NETWORK_CONFIGS_SERVER: &str =
 /// We do pre-genesis validator set up in this directory
 pub const PRE_GENESIS_DIR: &str = "pre-genesis";
 
+/// The P2P port of the first localnet validator. The ports of the other
+/// validators are offset from it by [`LOCALNET_PORT_OFFSET`].
+const LOCALNET_FIRST_P2P_PORT: u16 = 27656;
+/// The offset between the P2P ports of consecutive localnet validators, which
+/// leaves room for the other ports derived from the P2P port of a node.
+const LOCALNET_PORT_OFFSET: u16 = 6;
+
 /// Configure Namada to join an existing network. The chain must be released in
 /// the <https://github.com/heliaxdev/anoma-network-config> repository.
 pub async fn join_network(
@@ -771,6 +782,8 @@ pub fn init_genesis_validator(
         description,
         website,
         discord_handle,
+        moniker,
+        security_contact,
         tx_path,
         address,
     }: args::InitGenesisValidator,
@@ -849,6 +862,8 @@ pub fn init_genesis_validator(
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
         },
         &validator_wallet,
     );
@@ -884,6 +899,207 @@ pub fn init_genesis_validator(
     println!("{}: {toml_path_str}", "Wrote genesis tx to".bold());
 }
 
+/// The P2P port of the localnet validator with the given index, if it fits in
+/// a port number.
+fn localnet_p2p_port(index: u64) -> Option<u16> {
+    u16::try_from(index)
+        .ok()
+        .and_then(|index| LOCALNET_PORT_OFFSET.checked_mul(index))
+        .and_then(|offset| LOCALNET_FIRST_P2P_PORT.checked_add(offset))
+}
+
+/// Add genesis validators with freshly generated keys to the genesis templates
+/// of a local test network, in place.
+///
+/// For each validator, an unencrypted pre-genesis validator wallet holding its
+/// consensus, protocol, Ethereum bridge and CometBFT node keys is stored
+/// under the validator's alias, and the key of its established account is
+/// added to the pre-genesis wallet. The established account, its validator
+/// account and self-bond txs, signed with these keys, and the native tokens
+/// needed for the self-bond are added to the templates. The account key is
+/// credited with as many native tokens, to pay for the validator's txs.
+///
+/// The P2P ports of the validators are spread apart, such that their nodes
+/// can all run on the same host once they have joined the network with
+/// their `--genesis-validator` alias.
+pub async fn init_localnet_validators(
+    global_args: args::Global,
+    args::InitLocalnetValidators {
+        templates_path,
+        num_validators,
+        self_bond_amount,
+    }: args::InitLocalnetValidators,
+) {
+    let mut templates =
+        genesis::templates::All::read_toml_files(&templates_path)
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "Unable to read the genesis templates from {}. Failed \
+                     with {err}.",
+                    templates_path.to_string_lossy()
+                );
+                safe_exit(1)
+            });
+    let native_token = templates.parameters.parameters.native_token.clone();
+    if !templates.balances.token.contains_key(&native_token) {
+        eprintln!(
+            "The genesis templates contain no balances of the native token \
+             {native_token}."
+        );
+        safe_exit(1)
+    }
+    let wallet_lock = RwLock::new(crate::wallet::load_or_new(
+        &global_args.base_dir.join(PRE_GENESIS_DIR),
+    ));
+
+    // Continue the numbering of the validators already in the templates
+    let first_index = templates
+        .transactions
+        .validator_account
+        .as_ref()
+        .map(Vec::len)
+        .unwrap_or_default() as u64;
+    // Check up front that the P2P ports of all the new validators fit
+    if num_validators > 0
+        && first_index
+            .checked_add(num_validators - 1)
+            .and_then(localnet_p2p_port)
+            .is_none()
+    {
+        eprintln!(
+            "Cannot assign P2P ports to {num_validators} more validators, the \
+             ports would overflow."
+        );
+        safe_exit(1)
+    }
+    for index in first_index..first_index + num_validators {
+        let alias = format!("validator-{index}");
+        let pre_genesis_dir =
+            validator_pre_genesis_dir(&global_args.base_dir, &alias);
+        if pre_genesis_dir.exists() {
+            eprintln!(
+                "The pre-genesis directory of {alias} already exists at {}.",
+                pre_genesis_dir.to_string_lossy()
+            );
+            safe_exit(1)
+        }
+
+        let account_key = {
+            let mut wallet = wallet_lock.write().await;
+            let (_alias, account_key) = wallet
+                .gen_store_secret_key(
+                    SchemeType::Ed25519,
+                    Some(format!("{alias}-account-key")),
+                    true,
+                    None,
+                    &mut OsRng,
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!(
+                        "Unable to generate the account key of {alias}: {err}"
+                    );
+                    safe_exit(1)
+                });
+            account_key
+        };
+        let established_account_tx =
+            genesis::transactions::EstablishedAccountTx {
+                vp: genesis::utils::VP_USER.to_string(),
+                threshold: 1,
+                public_keys: vec![StringEncoded::new(account_key.ref_to())],
+            };
+        let address = established_account_tx.derive_established_address();
+        wallet_lock.write().await.insert_address(
+            alias.clone(),
+            Address::Established(address.clone()),
+            true,
+        );
+
+        let validator_wallet = pre_genesis::gen_and_store(
+            SchemeType::Ed25519,
+            true,
+            &pre_genesis_dir,
+        )
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Unable to generate the validator pre-genesis wallet of \
+                 {alias}: {err}"
+            );
+            safe_exit(1)
+        });
+        let port = localnet_p2p_port(index)
+            .expect("The ports of all the validators have been checked");
+        let (_address, mut txs) = genesis::transactions::init_validator(
+            genesis::transactions::GenesisValidatorData {
+                address: address.clone(),
+                commission_rate: Dec::new(5, 2).expect("This can't fail"),
+                max_commission_rate_change: Dec::new(1, 2)
+                    .expect("This can't fail"),
+                net_address: SocketAddr::new(
+                    IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    port,
+                ),
+                self_bond_amount,
+                email: "null@null.net".to_string(),
+                description: None,
+                website: None,
+                discord_handle: None,
+                moniker: Some(alias.clone()),
+                security_contact: None,
+            },
+            &validator_wallet,
+        );
+        txs.established_account = Some(vec![established_account_tx]);
+        let signed_txs = genesis::transactions::sign_txs(
+            txs,
+            &wallet_lock,
+            Some(&validator_wallet),
+            false,
+        )
+        .await;
+        templates.transactions.merge(signed_txs);
+
+        let balances = templates
+            .balances
+            .token
+            .get_mut(&native_token)
+            .expect("The native token balances must exist");
+        balances.0.insert(
+            GenesisAddress::EstablishedAddress(address.clone()),
+            self_bond_amount,
+        );
+        balances.0.insert(
+            GenesisAddress::PublicKey(StringEncoded::new(account_key.ref_to())),
+            self_bond_amount,
+        );
+
+        println!(
+            "{}: {} with P2P port {port}",
+            format!("Generated {alias}").bold(),
+            address.green()
+        );
+    }
+
+    crate::wallet::save(&wallet_lock.into_inner()).unwrap_or_else(|err| {
+        eprintln!("Unable to save the pre-genesis wallet: {err}");
+        safe_exit(1)
+    });
+    templates
+        .write_toml_files(&templates_path)
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Failed to write the genesis templates to {} with {err}.",
+                templates_path.to_string_lossy()
+            );
+            safe_exit(1)
+        });
+    println!(
+        "{}: {}",
+        "Updated the genesis templates at".bold(),
+        templates_path.to_string_lossy()
+    );
+}
+
 /// Try to load a pre-genesis wallet or return nothing,
 /// if it cannot be found.
 pub fn try_load_pre_genesis_wallet(
@@ -1,6 +1,10 @@
 //! Runtime configuration for a validator node.
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+
 #[allow(unused_imports)]
 use namada::types::ethereum_events::EthereumEvent;
+use namada::types::key::common;
 use serde::{Deserialize, Serialize};
 
 /// Default [Ethereum JSON-RPC](https://ethereum.org/en/developers/docs/apis/json-rpc/) endpoint used by the oracle
@@ -10,6 +14,18 @@ pub const DEFAULT_ORACLE_RPC_ENDPOINT: &str = "http://127.0.0.1:8545";
 /// the oracle and the shell can hold.
 pub const ORACLE_CHANNEL_BUFFER_SIZE: usize = 1000;
 
+/// The default maximum number of times the oracle retries a request
+/// rejected by a rate limited Ethereum RPC endpoint.
+pub const DEFAULT_ORACLE_RPC_MAX_RETRIES: u32 = 5;
+
+/// The default number of processed Ethereum blocks whose events the oracle
+/// holds back, re-checking them for reorgs.
+pub const DEFAULT_ORACLE_REORG_SAFETY_DEPTH: u64 = 0;
+
+/// The default number of blocks an Ethereum event may stay queued up for
+/// inclusion in vote extensions, before it is reported as stalled.
+pub const DEFAULT_STALLED_EVENT_BLOCKS: u64 = 100;
+
 /// The mode in which to run the Ethereum bridge.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Mode {
@@ -21,10 +37,37 @@ pub enum Mode {
     /// itself for submission of Borsh-serialized [`EthereumEvent`]
     /// instances. Mostly useful for testing purposes.
     SelfHostedEndpoint,
+    /// Instead of the oracle listening for events using an Ethereum
+    /// JSON-RPC endpoint, the [`EthereumEvent`] instances in the file
+    /// specified in the `oracle_events_file` setting are imported, once
+    /// their signatures have been checked against the key specified in
+    /// the `oracle_events_file_signer` setting. Useful for test networks
+    /// without access to an Ethereum node.
+    EventsFile,
+    /// Instead of the oracle listening for events using an Ethereum
+    /// JSON-RPC endpoint, the [`EthereumEvent`] instances in the JSON
+    /// fixture file specified in the `oracle_simulation_fixture` setting
+    /// are replayed, followed by any event submitted to the control socket
+    /// specified in the `oracle_simulation_socket` setting. Useful for
+    /// local test networks and e2e tests.
+    Simulation,
     /// Do not run any components of the Ethereum bridge.
     Off,
 }
 
+/// Configuration of the light client verifying the Ethereum events found
+/// by the oracle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LightClientConfig {
+    /// The Ethereum JSON-RPC endpoints from which block headers and
+    /// receipts are fetched. These should be run by independent
+    /// providers.
+    pub endpoints: Vec<String>,
+    /// The minimum number of `endpoints` that must agree on the header of
+    /// a block, for the events found in that block to be trusted.
+    pub min_agreeing_endpoints: usize,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     /// The mode in which to run the Ethereum node and oracle setup of this
@@ -33,10 +76,64 @@ pub struct Config {
     /// The Ethereum JSON-RPC endpoint that the Ethereum event oracle will use
     /// to listen for events from the Ethereum bridge smart contracts
     pub oracle_rpc_endpoint: String,
+    /// Additional Ethereum JSON-RPC endpoints, in order of preference, that
+    /// the oracle fails over to whenever `oracle_rpc_endpoint` is unhealthy
+    #[serde(default)]
+    pub oracle_rpc_fallback_endpoints: Vec<String>,
+    /// When set, the oracle runs in quorum mode, querying
+    /// `oracle_rpc_endpoint` and all of `oracle_rpc_fallback_endpoints`,
+    /// and only emitting the Ethereum events that at least this many of
+    /// them agree on. These endpoints should then be run by independent
+    /// providers.
+    #[serde(default)]
+    pub oracle_rpc_quorum: Option<usize>,
+    /// The maximum number of requests per second the oracle sends to each
+    /// Ethereum JSON-RPC endpoint. If unset, requests are not throttled.
+    #[serde(default)]
+    pub oracle_rpc_max_requests_per_sec: Option<NonZeroU32>,
+    /// The maximum number of times the oracle retries a request rejected
+    /// by an Ethereum JSON-RPC endpoint for exceeding its request cap,
+    /// backing off exponentially between retries.
+    #[serde(default = "default_oracle_rpc_max_retries")]
+    pub oracle_rpc_max_retries: u32,
     /// The size of bounded channel between the Ethereum oracle and main
     /// ledger subprocesses. This is the number of Ethereum events that
     /// can be held in the channel. The default is 1000.
     pub channel_buffer_size: usize,
+    /// The number of already processed Ethereum blocks whose events the
+    /// oracle holds back, re-checking them for reorgs, such that only the
+    /// events of blocks at least this deep are sent to the ledger. This
+    /// may be overridden by governance. The default is 0, i.e. disabled.
+    #[serde(default)]
+    pub oracle_reorg_safety_depth: u64,
+    /// When set, the Ethereum events found by the oracle are only trusted
+    /// once their inclusion in a block has been verified against the
+    /// block header agreed upon by multiple Ethereum JSON-RPC endpoints,
+    /// rather than fully trusting `oracle_rpc_endpoint`.
+    #[serde(default)]
+    pub oracle_light_client: Option<LightClientConfig>,
+    /// The JSONL file of signed Ethereum events imported in the
+    /// `EventsFile` mode.
+    #[serde(default)]
+    pub oracle_events_file: Option<PathBuf>,
+    /// The public key which must have signed the events imported in the
+    /// `EventsFile` mode.
+    #[serde(default)]
+    pub oracle_events_file_signer: Option<common::PublicKey>,
+    /// The fixture file of Ethereum events replayed in the `Simulation`
+    /// mode, holding a JSON encoded event per line.
+    #[serde(default)]
+    pub oracle_simulation_fixture: Option<PathBuf>,
+    /// The address of the control socket on which Ethereum events are
+    /// submitted in the `Simulation` mode, e.g. `127.0.0.1:8546`.
+    #[serde(default)]
+    pub oracle_simulation_socket: Option<String>,
+    /// The number of blocks after which an Ethereum event that stayed
+    /// queued up for inclusion in vote extensions, without reaching a
+    /// quorum of stake on chain, is reported as stalled. Stalled events
+    /// are not reported if set to 0. The default is 100.
+    #[serde(default = "default_stalled_event_blocks")]
+    pub stalled_event_blocks: u64,
 }
 
 impl Default for Config {
@@ -44,7 +141,26 @@ impl Default for Config {
         Self {
             mode: Mode::RemoteEndpoint,
             oracle_rpc_endpoint: DEFAULT_ORACLE_RPC_ENDPOINT.to_owned(),
+            oracle_rpc_fallback_endpoints: vec![],
+            oracle_rpc_quorum: None,
+            oracle_rpc_max_requests_per_sec: None,
+            oracle_rpc_max_retries: DEFAULT_ORACLE_RPC_MAX_RETRIES,
             channel_buffer_size: ORACLE_CHANNEL_BUFFER_SIZE,
+            oracle_reorg_safety_depth: DEFAULT_ORACLE_REORG_SAFETY_DEPTH,
+            oracle_light_client: None,
+            oracle_events_file: None,
+            oracle_events_file_signer: None,
+            oracle_simulation_fixture: None,
+            oracle_simulation_socket: None,
+            stalled_event_blocks: DEFAULT_STALLED_EVENT_BLOCKS,
         }
     }
 }
+
+fn default_oracle_rpc_max_retries() -> u32 {
+    DEFAULT_ORACLE_RPC_MAX_RETRIES
+}
+
+fn default_stalled_event_blocks() -> u64 {
+    DEFAULT_STALLED_EVENT_BLOCKS
+}
@@ -472,6 +472,8 @@ pub fn make_dev_genesis(
                     description: None,
                     website: None,
                     discord_handle: None,
+                    moniker: None,
+                    security_contact: None,
                 },
                 net_address: SocketAddr::new(
                     IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
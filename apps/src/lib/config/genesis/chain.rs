@@ -350,6 +350,8 @@ impl Finalized {
             validator_stake_threshold,
             liveness_window_check,
             liveness_threshold,
+            max_evidence_age_epochs,
+            max_evidence_age_blocks,
         } = self.parameters.pos_params.clone();
 
         namada::proof_of_stake::parameters::PosParams {
@@ -368,6 +370,8 @@ impl Finalized {
                 validator_stake_threshold,
                 liveness_window_check,
                 liveness_threshold,
+                max_evidence_age_epochs,
+                max_evidence_age_blocks,
             },
             max_proposal_period: self.parameters.gov_params.max_proposal_period,
         }
@@ -384,6 +388,13 @@ impl Finalized {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            min_proposal_execution_delay,
+            min_expedited_proposal_fund,
+            expedited_proposal_voting_period,
+            max_proposal_execution_gas,
+            min_text_proposal_fund,
+            proposal_withdrawal_refund,
+            veto_threshold,
         } = self.parameters.gov_params.clone();
         namada::core::ledger::governance::parameters::GovernanceParameters {
             min_proposal_fund: Amount::native_whole(min_proposal_fund),
@@ -392,6 +403,17 @@ impl Finalized {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             min_proposal_voting_period,
+            min_proposal_execution_delay,
+            min_expedited_proposal_fund: Amount::native_whole(
+                min_expedited_proposal_fund,
+            ),
+            expedited_proposal_voting_period,
+            max_proposal_execution_gas,
+            min_text_proposal_fund: Amount::native_whole(
+                min_text_proposal_fund,
+            ),
+            proposal_withdrawal_refund,
+            veto_threshold,
         }
     }
 
@@ -15,6 +15,7 @@ use namada::types::dec::Dec;
 use namada::types::token::{
     Amount, DenominatedAmount, Denomination, NATIVE_MAX_DECIMAL_PLACES,
 };
+use namada::types::voting_power::FractionalVotingPower;
 use serde::{Deserialize, Serialize};
 
 use super::transactions::{self, Transactions};
@@ -410,6 +411,13 @@ pub struct PosParams {
     /// The minimum required activity of consensus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The maximum age, in epochs, of the evidence of an infraction that
+    /// may still lead to a slash. Must be >= `unbonding_len`.
+    pub max_evidence_age_epochs: u64,
+    /// The maximum age, in blocks, of the evidence of an infraction that
+    /// may still lead to a slash. Evidence older than both maximum ages is
+    /// discarded.
+    pub max_evidence_age_blocks: u64,
 }
 
 #[derive(
@@ -435,6 +443,23 @@ pub struct GovernanceParams {
     pub max_proposal_content_size: u64,
     /// Minimum number of epoch between end and grace epoch
     pub min_proposal_grace_epochs: u64,
+    /// Minimum number of epochs between a proposal passing and its
+    /// execution
+    pub min_proposal_execution_delay: u64,
+    /// Minimum amount of locked funds for an expedited proposal
+    pub min_expedited_proposal_fund: u64,
+    /// Expedited proposal voting period in blocks
+    pub expedited_proposal_voting_period: u64,
+    /// Maximum gas that the code of a passed proposal may consume
+    pub max_proposal_execution_gas: u64,
+    /// Minimum amount of locked funds for a text proposal
+    pub min_text_proposal_fund: u64,
+    /// Share of the locked funds refunded to the author of a withdrawn
+    /// proposal
+    pub proposal_withdrawal_refund: Dec,
+    /// Share of the voting power that voted on a proposal above which veto
+    /// votes reject it and burn its funds
+    pub veto_threshold: FractionalVotingPower,
 }
 
 #[derive(
@@ -843,6 +868,49 @@ pub fn validate_parameters(
             );
         }
     }
+    // check that evidence doesn't expire while the stake of the offending
+    // validator can still be slashed
+    if parameters.pos_params.max_evidence_age_epochs
+        < parameters.pos_params.unbonding_len
+    {
+        eprintln!(
+            "The maximum evidence age of {} epochs must be at least the \
+             unbonding length of {} epochs",
+            parameters.pos_params.max_evidence_age_epochs,
+            parameters.pos_params.unbonding_len
+        );
+        is_valid = false;
+    }
+    if parameters.pos_params.max_evidence_age_blocks == 0 {
+        eprintln!("The maximum evidence age in blocks must be at least 1");
+        is_valid = false;
+    }
+    // check that the expedited proposals track can be used, and only with a
+    // higher deposit
+    if parameters.gov_params.expedited_proposal_voting_period == 0 {
+        eprintln!(
+            "The expedited proposal voting period must be at least 1 block"
+        );
+        is_valid = false;
+    }
+    if parameters.gov_params.min_expedited_proposal_fund
+        <= parameters.gov_params.min_proposal_fund
+    {
+        eprintln!(
+            "The minimum expedited proposal fund of {} must be greater than \
+             the minimum proposal fund of {}",
+            parameters.gov_params.min_expedited_proposal_fund,
+            parameters.gov_params.min_proposal_fund
+        );
+        is_valid = false;
+    }
+    let refund = parameters.gov_params.proposal_withdrawal_refund;
+    if refund.is_negative() || refund > Dec::one() {
+        eprintln!(
+            "The proposal withdrawal refund of {refund} must be between 0 and 1"
+        );
+        is_valid = false;
+    }
     let Parameters {
         parameters,
         pos_params,
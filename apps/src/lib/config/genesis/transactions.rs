@@ -144,6 +144,8 @@ pub struct GenesisValidatorData {
     pub description: Option<String>,
     pub website: Option<String>,
     pub discord_handle: Option<String>,
+    pub moniker: Option<String>,
+    pub security_contact: Option<String>,
 }
 
 /// Panics if given `txs.validator_accounts` is not empty, because validator
@@ -269,6 +271,8 @@ pub fn init_validator(
         description,
         website,
         discord_handle,
+        moniker,
+        security_contact,
     }: GenesisValidatorData,
     validator_wallet: &ValidatorWallet,
 ) -> (Address, UnsignedTransactions) {
@@ -302,6 +306,8 @@ pub fn init_validator(
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
         },
     };
     let unsigned_validator_addr =
@@ -613,6 +619,8 @@ impl TxToSign for ValidatorAccountTx<SignedPk> {
                 description: self.metadata.description.clone(),
                 website: self.metadata.website.clone(),
                 discord_handle: self.metadata.discord_handle.clone(),
+                moniker: self.metadata.moniker.clone(),
+                security_contact: self.metadata.security_contact.clone(),
             },
         )
     }
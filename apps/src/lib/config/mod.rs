@@ -8,6 +8,8 @@ pub mod utils;
 use std::collections::HashMap;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 
 use directories::ProjectDirs;
@@ -35,6 +37,9 @@ pub const FILENAME: &str = "config.toml";
 pub const COMETBFT_DIR: &str = "cometbft";
 /// Chain-specific Namada DB. Nested in chain dirs.
 pub const DB_DIR: &str = "db";
+/// Chain-specific marker file, whose presence makes the running node pause
+/// its Ethereum oracle. Nested in chain dirs.
+pub const ETH_ORACLE_PAUSE_FILE: &str = "eth_oracle_paused";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -86,6 +91,46 @@ pub struct ActionAtHeight {
     pub action: Action,
 }
 
+/// The message brokers events can be published to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSinkBackend {
+    /// Publish events to NATS subjects.
+    Nats,
+    /// Publish events to Kafka topics.
+    Kafka,
+}
+
+/// The delivery guarantees of the events published to a message broker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryGuarantee {
+    /// Events which cannot be published, or which do not fit in the
+    /// publishing queue, are dropped. The ledger is never blocked.
+    AtMostOnce,
+    /// Events are published until acknowledged by the broker. The
+    /// ledger is blocked while the publishing queue is full.
+    AtLeastOnce,
+}
+
+/// Configuration of an external message broker, to which the events
+/// emitted by the ledger are published.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventSink {
+    /// The message broker to publish events to.
+    pub backend: EventSinkBackend,
+    /// Addresses of the brokers to connect to.
+    pub brokers: Vec<String>,
+    /// Events are published to the `<topic_prefix>.events` topic, and
+    /// summaries of finalized blocks to `<topic_prefix>.blocks`.
+    pub topic_prefix: String,
+    /// The delivery guarantees of the published events.
+    pub delivery: DeliveryGuarantee,
+    /// The maximum number of finalized blocks whose events may be
+    /// waiting to be published. Must be non-zero.
+    pub queue_capacity: NonZeroUsize,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ledger {
     pub genesis_time: Rfc3339String,
@@ -111,6 +156,29 @@ pub struct Shell {
     /// When set, will limit the how many block heights in the past can the
     /// storage be queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// Endpoints of archive nodes, suggested to clients querying heights
+    /// that are beyond the `storage_read_past_height_limit`.
+    #[serde(default)]
+    pub archive_endpoints: Vec<String>,
+    /// Whether evidence of validator misbehavior, submitted by watchtowers
+    /// at the `/shell/submit_evidence` query path, is broadcast to the
+    /// network. Only validator nodes can broadcast evidence.
+    #[serde(default)]
+    pub accept_misbehavior_evidence: bool,
+    /// When set, every storage write made during block execution is recorded
+    /// in an append-only audit file at this path, relative to the chain
+    /// directory.
+    pub storage_audit_log: Option<PathBuf>,
+    /// When set, the events emitted by the ledger are published to an
+    /// external message broker, in addition to the in-memory event log.
+    pub event_sink: Option<EventSink>,
+    /// When set, the health and readiness probes of the ledger, including
+    /// the progress of its startup, are served at `/health` and `/ready` on
+    /// this address, before the ABCI server is up.
+    pub startup_status_addr: Option<SocketAddr>,
+    /// When set, the queries of the ledger are served as JSON over HTTP on
+    /// this address, at the paths of the queries router.
+    pub query_gateway_addr: Option<SocketAddr>,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::cometbft_dir()`] method to read the value.
@@ -143,6 +211,12 @@ impl Ledger {
                 tx_wasm_compilation_cache_bytes: None,
                 // Default corresponds to 1 hour of past blocks at 1 block/sec
                 storage_read_past_height_limit: Some(3600),
+                archive_endpoints: vec![],
+                accept_misbehavior_evidence: false,
+                storage_audit_log: None,
+                event_sink: None,
+                startup_status_addr: None,
+                query_gateway_addr: None,
                 db_dir: DB_DIR.into(),
                 cometbft_dir: COMETBFT_DIR.into(),
                 action_at_height: None,
@@ -158,6 +232,11 @@ impl Ledger {
         self.shell.base_dir.join(self.chain_id.as_str())
     }
 
+    /// Get the path to the marker file that pauses the Ethereum oracle
+    pub fn eth_oracle_pause_file(&self) -> PathBuf {
+        self.chain_dir().join(ETH_ORACLE_PAUSE_FILE)
+    }
+
     /// Get the directory path to the DB
     pub fn db_dir(&self) -> PathBuf {
         self.shell.db_dir(&self.chain_id)
@@ -175,6 +254,12 @@ impl Shell {
         self.base_dir.join(chain_id.as_str()).join(&self.db_dir)
     }
 
+    /// Set the path to the DB, relative to the chain directory, unless it's
+    /// an absolute path
+    pub fn set_db_dir(&mut self, db_dir: impl Into<PathBuf>) {
+        self.db_dir = db_dir.into();
+    }
+
     /// Get the directory path to Tendermint
     pub fn cometbft_dir(&self, chain_id: &ChainId) -> PathBuf {
         self.base_dir
@@ -845,11 +930,29 @@ namespace = "cometbft"
 
 #[cfg(test)]
 mod tests {
-    use super::DEFAULT_COMETBFT_CONFIG;
+    use super::{EventSink, DEFAULT_COMETBFT_CONFIG};
     use crate::facade::tendermint_config::TendermintConfig;
 
     #[test]
     fn test_default_cometbft_config() {
         assert!(TendermintConfig::parse_toml(DEFAULT_COMETBFT_CONFIG).is_ok());
     }
+
+    /// Test that an event sink with an empty publishing queue is rejected.
+    #[test]
+    fn test_event_sink_queue_capacity() {
+        let event_sink = |queue_capacity: usize| {
+            toml::from_str::<EventSink>(&format!(
+                r#"
+                backend = "nats"
+                brokers = ["127.0.0.1:4222"]
+                topic_prefix = "namada"
+                delivery = "at_least_once"
+                queue_capacity = {queue_capacity}
+                "#
+            ))
+        };
+        assert!(event_sink(0).is_err());
+        assert_eq!(event_sink(1).unwrap().queue_capacity.get(), 1);
+    }
 }
@@ -15,11 +15,11 @@ const COLOR_ENV_KEY: &str = "NAMADA_LOG_COLOR";
 // Env var to log formatting (one of "full" (default), "json", "pretty")
 const FMT_ENV_KEY: &str = "NAMADA_LOG_FMT";
 // Env var to append logs to file(s) in the given dir
-const DIR_ENV_KEY: &str = "NAMADA_LOG_DIR";
+pub const DIR_ENV_KEY: &str = "NAMADA_LOG_DIR";
 // Env var to set rolling log frequency
 const ROLLING_ENV_KEY: &str = "NAMADA_LOG_ROLLING";
 
-const LOG_FILE_NAME_PREFIX: &str = "namada.log";
+pub const LOG_FILE_NAME_PREFIX: &str = "namada.log";
 
 #[derive(Clone, Debug)]
 enum Fmt {
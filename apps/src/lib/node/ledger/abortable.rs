@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::pin::Pin;
 
 use namada::types::control_flow::{install_shutdown_signal, ShutdownSignal};
+use namada_sdk::queries::Shared;
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
@@ -16,6 +18,25 @@ pub struct AbortableSpawner {
     abort_send: UnboundedSender<AbortingTask>,
     abort_recv: UnboundedReceiver<AbortingTask>,
     cleanup_jobs: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+    running_tasks: RunningTasks,
+}
+
+/// Shared record of which of the tasks spawned with an [`AbortableSpawner`]
+/// are still running.
+#[derive(Debug, Clone, Default)]
+pub struct RunningTasks {
+    inner: Shared<BTreeMap<AbortingTask, bool>>,
+}
+
+impl RunningTasks {
+    /// Return whether each of the spawned tasks is still running.
+    pub fn snapshot(&self) -> BTreeMap<AbortingTask, bool> {
+        self.inner.lock().clone()
+    }
+
+    fn set_running(&self, who: AbortingTask, running: bool) {
+        self.inner.lock().insert(who, running);
+    }
 }
 
 /// Contains the state of an on-going [`AbortableSpawner`] task spawn.
@@ -42,9 +63,16 @@ impl AbortableSpawner {
             abort_recv,
             shutdown_recv,
             cleanup_jobs: Vec::new(),
+            running_tasks: RunningTasks::default(),
         }
     }
 
+    /// Get a handle to the record of the spawned tasks that are still
+    /// running.
+    pub fn running_tasks(&self) -> RunningTasks {
+        self.running_tasks.clone()
+    }
+
     /// Spawns a new task into the asynchronous runtime, with an [`Aborter`]
     /// that shall be dropped when it is no longer running.
     ///
@@ -113,9 +141,11 @@ impl AbortableSpawner {
         F: Future<Output = R> + Send + 'static,
         R: Send + 'static,
     {
+        self.running_tasks.set_running(who, true);
         let abort = Aborter {
             who,
             sender: self.abort_send.clone(),
+            running_tasks: self.running_tasks.clone(),
         };
         tokio::spawn(abortable(abort))
     }
@@ -152,10 +182,12 @@ impl<'a, A> WithCleanup<'a, A> {
 pub struct Aborter {
     sender: mpsc::UnboundedSender<AbortingTask>,
     who: AbortingTask,
+    running_tasks: RunningTasks,
 }
 
 impl Drop for Aborter {
     fn drop(&mut self) {
+        self.running_tasks.set_running(self.who, false);
         // Send abort message, ignore result
         let _ = self.sender.send(self.who);
     }
@@ -0,0 +1,475 @@
+//! Benchmark of the ledger over recorded blocks.
+//!
+//! Blocks are fetched from a CometBFT node, and replayed through a
+//! [`Shell`] running on a checkpoint of the DB. The time spent in each stage
+//! of block processing is recorded, such that the reports produced by
+//! different releases of the ledger can be compared against each other. The
+//! app hash of each replayed block must match the one recorded by CometBFT.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+use data_encoding::HEXUPPER;
+use namada::proof_of_stake::find_validator_by_raw_hash;
+use namada::proto::Tx;
+use namada::types::key::tm_raw_hash_to_string;
+use namada::types::storage::{BlockHash, BlockHeight};
+use namada::types::transaction::hash_tx;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use super::shell::{self, Shell};
+use super::shims::abcipp_shim_types::shim::request::{
+    FinalizeBlock, ProcessedTx,
+};
+use super::shims::abcipp_shim_types::shim::TxBytes;
+use super::storage;
+use crate::cli::args;
+use crate::config::{self, TendermintMode};
+use crate::facade::tendermint::abci::types::{
+    BlockSignatureInfo, CommitInfo, Misbehavior, MisbehaviorKind, Validator,
+    VoteInfo,
+};
+use crate::facade::tendermint::block::{Block, BlockIdFlag, CommitSig};
+use crate::facade::tendermint::evidence::Evidence;
+use crate::facade::tendermint::v0_37::abci::request;
+use crate::facade::tendermint_rpc::{self, Client, HttpClient, Paging};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid block range: {from} to {to}")]
+    InvalidRange { from: BlockHeight, to: BlockHeight },
+    #[error(
+        "The DB is at height {db_height}, but replaying from height {from} \
+         requires the state at height {}. Use a DB snapshot taken at that \
+         height.",
+        .from.prev_height()
+    )]
+    HeightMismatch {
+        db_height: BlockHeight,
+        from: BlockHeight,
+    },
+    #[error(
+        "Error creating a checkpoint of the DB. The node must be stopped, \
+         unless a DB snapshot is given: {0}"
+    )]
+    CheckpointDb(namada::ledger::storage::Error),
+    #[error("Error creating a directory for the DB checkpoint: {0}")]
+    CheckpointDir(io::Error),
+    #[error("Error fetching block {0} from CometBFT: {1}")]
+    FetchBlock(BlockHeight, tendermint_rpc::Error),
+    #[error(
+        "The app hash of the replayed block {height} is {}, but CometBFT \
+         recorded {}",
+        HEXUPPER.encode(.replayed),
+        HEXUPPER.encode(.recorded)
+    )]
+    AppHashMismatch {
+        height: BlockHeight,
+        replayed: Vec<u8>,
+        recorded: Vec<u8>,
+    },
+    #[error("Error finalizing block {0}: {1}")]
+    FinalizeBlock(BlockHeight, shell::Error),
+    #[error("Error writing the benchmark report: {0}")]
+    WriteReport(io::Error),
+}
+
+/// The stages of block processing that are measured.
+#[derive(Debug, Default)]
+struct StageTimings {
+    /// Decoding of the txs of the block.
+    decode: Vec<Duration>,
+    /// Validation of the txs of the block, as performed by
+    /// `ProcessProposal`. This includes the verification of the signatures.
+    signature_verification: Vec<Duration>,
+    /// Application of the block in `FinalizeBlock`, which includes the
+    /// execution of the wasm code of txs and VPs.
+    wasm_execution: Vec<Duration>,
+    /// Commitment of the block to the DB.
+    commit: Vec<Duration>,
+}
+
+/// Statistics of the time spent in a stage of block processing, in
+/// milliseconds.
+#[derive(Debug, Serialize)]
+pub struct StageReport {
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl StageReport {
+    fn new(mut timings: Vec<Duration>) -> Self {
+        timings.sort_unstable();
+        let as_ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let last = timings.len().saturating_sub(1);
+        let percentile = |p: usize| {
+            timings
+                .get((timings.len() * p / 100).min(last))
+                .copied()
+                .map(as_ms)
+                .unwrap_or_default()
+        };
+        let total: Duration = timings.iter().sum();
+        Self {
+            total_ms: as_ms(total),
+            mean_ms: as_ms(total) / timings.len().max(1) as f64,
+            p50_ms: percentile(50),
+            p99_ms: percentile(99),
+            max_ms: timings.last().copied().map(as_ms).unwrap_or_default(),
+        }
+    }
+}
+
+/// The result of a benchmark run.
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    /// The version of the ledger that produced this report.
+    pub version: String,
+    pub from: BlockHeight,
+    pub to: BlockHeight,
+    pub blocks: u64,
+    pub txs: u64,
+    /// Whether the app hash of the last replayed block could be checked,
+    /// which requires its successor to be committed by CometBFT.
+    pub last_app_hash_checked: bool,
+    pub decode: StageReport,
+    pub signature_verification: StageReport,
+    pub wasm_execution: StageReport,
+    pub commit: StageReport,
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Replayed {} blocks ({} txs) from height {} to {} with version {}",
+            self.blocks, self.txs, self.from, self.to, self.version
+        )?;
+        writeln!(
+            f,
+            "{:<24}{:>12}{:>12}{:>12}{:>12}{:>12}",
+            "stage", "total ms", "mean ms", "p50 ms", "p99 ms", "max ms"
+        )?;
+        for (stage, report) in [
+            ("decode", &self.decode),
+            ("signature verification", &self.signature_verification),
+            ("wasm execution", &self.wasm_execution),
+            ("commit", &self.commit),
+        ] {
+            writeln!(
+                f,
+                "{:<24}{:>12.2}{:>12.2}{:>12.2}{:>12.2}{:>12.2}",
+                stage,
+                report.total_ms,
+                report.mean_ms,
+                report.p50_ms,
+                report.p99_ms,
+                report.max_ms
+            )?;
+        }
+        if !self.last_app_hash_checked {
+            writeln!(
+                f,
+                "The app hash of the last replayed block could not be checked"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replay the blocks in the range given by `args` and report the time spent
+/// in each stage of block processing.
+pub fn bench(
+    mut config: config::Ledger,
+    wasm_dir: PathBuf,
+    args::LedgerBench {
+        from,
+        to,
+        ledger_address,
+        db_snapshot,
+        out_file_path,
+    }: args::LedgerBench,
+) -> Result<BenchReport, Error> {
+    if from.0 == 0 || from > to {
+        return Err(Error::InvalidRange { from, to });
+    }
+
+    // Never touch the node's own DB, but replay the blocks on a consistent
+    // checkpoint of it
+    let checkpoint_dir = tempfile::tempdir().map_err(Error::CheckpointDir)?;
+    let db_checkpoint = checkpoint_dir.path().join("db");
+    let db_source = db_snapshot.unwrap_or_else(|| config.db_dir());
+    tracing::info!(
+        "Creating a checkpoint of the DB at {} in {}",
+        db_source.to_string_lossy(),
+        db_checkpoint.to_string_lossy()
+    );
+    storage::try_open_db(&db_source)
+        .and_then(|db| db.checkpoint(&db_checkpoint))
+        .map_err(Error::CheckpointDb)?;
+    config.shell.set_db_dir(&db_checkpoint);
+    // Replay the blocks as a full node, such that no protocol txs are
+    // broadcasted and no wallet is required
+    config.shell.tendermint_mode = TendermintMode::Full;
+    config.shell.storage_audit_log = None;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Must be able to start a new tokio runtime");
+    let setup_data = runtime.block_on(super::run_aux_setup(&config, &wasm_dir));
+    let db_cache = rocksdb::Cache::new_lru_cache(
+        setup_data.db_block_cache_size_bytes as usize,
+    );
+    let (broadcast_sender, _broadcast_receiver) = mpsc::unbounded_channel();
+    let mut shell: Shell = Shell::new(
+        config,
+        wasm_dir,
+        broadcast_sender,
+        None,
+        Some(&db_cache),
+        setup_data.vp_wasm_compilation_cache,
+        setup_data.tx_wasm_compilation_cache,
+    );
+
+    let db_height = shell.wl_storage.storage.get_last_block_height();
+    if db_height.next_height() != from {
+        return Err(Error::HeightMismatch { db_height, from });
+    }
+
+    let client = HttpClient::new(ledger_address)
+        .expect("Must be able to connect to CometBFT");
+    let mut timings = StageTimings::default();
+    let mut txs = 0;
+    let mut last_app_hash: Option<(BlockHeight, Vec<u8>)> = None;
+
+    for height in from.0..=to.0 {
+        let height = BlockHeight(height);
+        let (block, votes) = runtime
+            .block_on(fetch_block(&client, height))
+            .map_err(|err| Error::FetchBlock(height, err))?;
+        let block_txs: Vec<TxBytes> =
+            block.data.iter().cloned().map(TxBytes::from).collect();
+
+        // The app hash of each block is the result of committing its
+        // predecessor
+        if let Some((replayed_height, app_hash)) = last_app_hash.take() {
+            check_app_hash(replayed_height, app_hash, &block)?;
+        }
+
+        let start = Instant::now();
+        for tx_bytes in &block_txs {
+            let _ = Tx::try_from(tx_bytes.as_ref());
+        }
+        timings.decode.push(start.elapsed());
+        txs += block_txs.len() as u64;
+
+        let block_time = block
+            .header
+            .time
+            .try_into()
+            .expect("valid RFC3339 block time");
+        let block_proposer = find_validator_by_raw_hash(
+            &shell.wl_storage,
+            tm_raw_hash_to_string(block.header.proposer_address),
+        )
+        .map_err(|err| Error::FinalizeBlock(height, err.into()))?
+        .expect(
+            "Unable to find native validator address of block proposer from \
+             tendermint raw hash",
+        );
+        let start = Instant::now();
+        let (processing_results, _) =
+            shell.process_txs(&block_txs, block_time, &block_proposer);
+        timings.signature_verification.push(start.elapsed());
+
+        let bytes: Vec<u8> = block.data.iter().flatten().copied().collect();
+        let hash = hash_tx(&bytes);
+        let begin_block = request::BeginBlock {
+            hash: Default::default(),
+            header: block.header.clone(),
+            last_commit_info: CommitInfo {
+                round: block
+                    .last_commit
+                    .as_ref()
+                    .map(|commit| commit.round)
+                    .unwrap_or_default(),
+                votes,
+            },
+            byzantine_validators: misbehavior_from_evidence(&block),
+        };
+        let mut finalize_req: FinalizeBlock = begin_block.into();
+        finalize_req.hash = BlockHash::from(hash);
+        finalize_req.txs = processing_results
+            .into_iter()
+            .zip(block_txs.into_iter())
+            .map(|(result, tx)| ProcessedTx { tx, result })
+            .collect();
+
+        let start = Instant::now();
+        shell
+            .finalize_block(finalize_req)
+            .map_err(|err| Error::FinalizeBlock(height, err))?;
+        timings.wasm_execution.push(start.elapsed());
+
+        let start = Instant::now();
+        let commit = shell.commit();
+        timings.commit.push(start.elapsed());
+        last_app_hash = Some((height, commit.data.to_vec()));
+    }
+
+    // The app hash of the last block is recorded in its successor, if it
+    // has been committed already
+    let mut last_app_hash_checked = false;
+    if let Some((replayed_height, app_hash)) = last_app_hash {
+        match runtime.block_on(client.block(to.next_height().0 as u32)) {
+            Ok(response) => {
+                check_app_hash(replayed_height, app_hash, &response.block)?;
+                last_app_hash_checked = true;
+            }
+            Err(err) => tracing::warn!(
+                "The app hash of the last replayed block {} could not be \
+                 checked, as its successor could not be fetched: {}",
+                replayed_height,
+                err
+            ),
+        }
+    }
+
+    let report = BenchReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        from,
+        to,
+        blocks: to.0 - from.0 + 1,
+        txs,
+        last_app_hash_checked,
+        decode: StageReport::new(timings.decode),
+        signature_verification: StageReport::new(
+            timings.signature_verification,
+        ),
+        wasm_execution: StageReport::new(timings.wasm_execution),
+        commit: StageReport::new(timings.commit),
+    };
+    if let Some(path) = out_file_path {
+        let json = serde_json::to_vec_pretty(&report)
+            .expect("Serializing the report should not fail");
+        fs::write(path, json).map_err(Error::WriteReport)?;
+    }
+    Ok(report)
+}
+
+/// Check that the app hash resulting from the replay of the block at
+/// `height` matches the one recorded by CometBFT in its successor `next`.
+fn check_app_hash(
+    height: BlockHeight,
+    replayed: Vec<u8>,
+    next: &Block,
+) -> Result<(), Error> {
+    let recorded = next.header.app_hash.as_bytes();
+    if recorded == replayed.as_slice() {
+        return Ok(());
+    }
+    Err(Error::AppHashMismatch {
+        height,
+        replayed,
+        recorded: recorded.to_vec(),
+    })
+}
+
+/// Fetch a block from CometBFT, along with the votes of the validators on
+/// its predecessor.
+async fn fetch_block(
+    client: &HttpClient,
+    height: BlockHeight,
+) -> Result<(Block, Vec<VoteInfo>), tendermint_rpc::Error> {
+    let block = client.block(height.0 as u32).await?.block;
+    let Some(last_commit) = block.last_commit.as_ref() else {
+        return Ok((block, vec![]));
+    };
+    // the commit signatures are sorted like the validator set that
+    // produced them
+    let validators = client
+        .validators(last_commit.height, Paging::All)
+        .await?
+        .validators;
+    let votes = validators
+        .into_iter()
+        .zip(last_commit.signatures.iter())
+        .map(|(validator, sig)| VoteInfo {
+            validator: Validator {
+                address: validator
+                    .address
+                    .as_bytes()
+                    .try_into()
+                    .expect("Validator addresses must have 20 bytes"),
+                power: validator.power,
+            },
+            sig_info: if matches!(sig, CommitSig::BlockIdFlagAbsent) {
+                BlockSignatureInfo::Flag(BlockIdFlag::Absent)
+            } else {
+                BlockSignatureInfo::LegacySigned
+            },
+        })
+        .collect();
+    Ok((block, votes))
+}
+
+/// Convert the evidence of misbehavior included in a block to the format
+/// expected by `FinalizeBlock`.
+fn misbehavior_from_evidence(block: &Block) -> Vec<Misbehavior> {
+    block
+        .evidence
+        .iter()
+        .filter_map(|evidence| match evidence {
+            Evidence::DuplicateVote(evidence) => Some(Misbehavior {
+                kind: MisbehaviorKind::DuplicateVote,
+                validator: Validator {
+                    address: evidence
+                        .vote_a
+                        .validator_address
+                        .as_bytes()
+                        .try_into()
+                        .expect("Validator addresses must have 20 bytes"),
+                    power: evidence.validator_power,
+                },
+                height: evidence.vote_a.height,
+                time: evidence.timestamp,
+                total_voting_power: evidence.total_voting_power,
+            }),
+            Evidence::LightClientAttack(_) => {
+                tracing::warn!(
+                    "Skipping light client attack evidence, which is not \
+                     supported by the benchmark"
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_bench {
+    use super::*;
+
+    /// Test the statistics computed over the timings of a stage.
+    #[test]
+    fn test_stage_report() {
+        let report = StageReport::new(vec![]);
+        assert_eq!(report.total_ms, 0.0);
+        assert_eq!(report.max_ms, 0.0);
+
+        let timings = (1..=100).rev().map(Duration::from_millis).collect();
+        let report = StageReport::new(timings);
+        assert_eq!(report.total_ms, 5050.0);
+        assert_eq!(report.mean_ms, 50.5);
+        assert_eq!(report.p50_ms, 51.0);
+        assert_eq!(report.p99_ms, 100.0);
+        assert_eq!(report.max_ms, 100.0);
+    }
+}
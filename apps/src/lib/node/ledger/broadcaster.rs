@@ -2,9 +2,11 @@ use std::net::SocketAddr;
 use std::ops::ControlFlow;
 
 use namada::types::control_flow::time;
+use namada_sdk::queries::CrashReports;
 use tokio::sync::mpsc::UnboundedReceiver;
 
 use crate::facade::tendermint_rpc::{Client, HttpClient};
+use crate::node::ledger::supervisor;
 
 /// A service for broadcasting txs via an HTTP client.
 /// The receiver is for receiving message payloads for other services
@@ -12,16 +14,23 @@ use crate::facade::tendermint_rpc::{Client, HttpClient};
 pub struct Broadcaster {
     client: HttpClient,
     receiver: UnboundedReceiver<Vec<u8>>,
+    crash_reports: CrashReports,
 }
 
 impl Broadcaster {
     /// Create a new broadcaster that will send Http messages
-    /// over the given url.
-    pub fn new(url: SocketAddr, receiver: UnboundedReceiver<Vec<u8>>) -> Self {
+    /// over the given url. Crashes of the broadcaster are recorded
+    /// in `crash_reports`.
+    pub fn new(
+        url: SocketAddr,
+        receiver: UnboundedReceiver<Vec<u8>>,
+        crash_reports: CrashReports,
+    ) -> Self {
         Self {
             client: HttpClient::new(format!("http://{}", url).as_str())
                 .unwrap(),
             receiver,
+            crash_reports,
         }
     }
 
@@ -71,14 +80,22 @@ impl Broadcaster {
     }
 
     /// Loop until an abort signal is received, forwarding messages over
-    /// the HTTP client as they are received from the receiver.
+    /// the HTTP client as they are received from the receiver. The
+    /// broadcaster is restarted if it panics.
     pub async fn run(
         &mut self,
         abort_recv: tokio::sync::oneshot::Receiver<()>,
     ) {
         tracing::info!("Starting broadcaster.");
+        let crash_reports = self.crash_reports.clone();
+        let supervised_loop = supervisor::supervise(
+            "Broadcaster",
+            &crash_reports,
+            self,
+            |broadcaster| Box::pin(broadcaster.run_loop()),
+        );
         tokio::select! {
-            _ = self.run_loop() => {
+            _ = supervised_loop => {
                 tracing::error!("Broadcaster unexpectedly shut down.");
                 tracing::info!("Shutting down broadcaster...");
             },
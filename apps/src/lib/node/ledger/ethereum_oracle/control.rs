@@ -15,19 +15,20 @@ pub struct Sender {
 }
 
 impl Sender {
-    /// Send a [`Command`] if the last one is not repeated.
+    /// Send a [`Command`] if the last one is not repeated. A command that
+    /// couldn't be sent is not remembered, such that it can be sent again.
     pub fn try_send(
         &mut self,
         cmd: Command,
     ) -> Result<(), TrySendError<Command>> {
         // NOTE: this code may be buggy if we happen to need to
         // send repeated commands
-        if self.last_command.as_ref() != Some(&cmd) {
-            self.last_command = Some(cmd.clone());
-            self.inner_sender.try_send(cmd)
-        } else {
-            Ok(())
+        if self.last_command.as_ref() == Some(&cmd) {
+            return Ok(());
         }
+        self.inner_sender.try_send(cmd.clone())?;
+        self.last_command = Some(cmd);
+        Ok(())
     }
 }
 
@@ -48,5 +49,13 @@ pub enum Command {
     /// Update the config if it changes in storage.
     /// Also used to send an initial configuration to the oracle for it to use.
     /// The oracle will not do anything until this command has been sent.
+    /// A handover of the bridge contract scheduled by governance is sent
+    /// along with the config, and takes effect from its handover block.
     UpdateConfig(Config),
+    /// Halt the processing of Ethereum blocks, e.g. after an exploit of
+    /// the bridge contracts, until a [`Command::Resume`] is sent.
+    Pause,
+    /// Resume the processing of Ethereum blocks, after the oracle has been
+    /// paused.
+    Resume,
 }
@@ -10,7 +10,8 @@ pub mod eth_events {
     use namada::core::types::ethereum_structs;
     use namada::types::address::Address;
     use namada::types::ethereum_events::{
-        EthAddress, EthereumEvent, TransferToEthereum, TransferToNamada, Uint,
+        EthAddress, EthereumEvent, NftTransferToNamada, TransferToEthereum,
+        TransferToNamada, Uint,
     };
     use namada::types::hash::Hash;
     use namada::types::keccak::KeccakHash;
@@ -28,6 +29,39 @@ pub mod eth_events {
 
     pub type Result<T> = std::result::Result<T, Error>;
 
+    /// The ABI signature of the event emitted by the NFT bridge contract,
+    /// upon transferring a batch of ERC-721 tokens to Namada.
+    pub const NFT_TRANSFER_TO_CHAIN_SIGNATURE: &str =
+        "NftTransferToChain(uint256,(address,uint256,string)[],uint256)";
+
+    /// The ABI of the event emitted by the NFT bridge contract, upon
+    /// transferring a batch of ERC-721 tokens to Namada.
+    pub fn nft_transfer_to_chain_event() -> ethabi::Event {
+        use ethabi::{EventParam, ParamType};
+
+        let param = |name: &str, kind| EventParam {
+            name: name.to_owned(),
+            kind,
+            indexed: false,
+        };
+        ethabi::Event {
+            name: "NftTransferToChain".to_owned(),
+            inputs: vec![
+                param("nonce", ParamType::Uint(256)),
+                param(
+                    "transfers",
+                    ParamType::Array(Box::new(ParamType::Tuple(vec![
+                        ParamType::Address,
+                        ParamType::Uint(256),
+                        ParamType::String,
+                    ]))),
+                ),
+                param("confirmations", ParamType::Uint(256)),
+            ],
+            anonymous: false,
+        }
+    }
+
     #[derive(Clone, Debug, PartialEq)]
     /// An event waiting for a certain number of confirmations
     /// before being sent to the ledger
@@ -112,6 +146,72 @@ pub mod eth_events {
             })
         }
 
+        /// Decodes an event emitted by the NFT bridge contract into an
+        /// [`EthereumEvent::NftTransfersToNamada`], which is turned into
+        /// a [`PendingEvent`] along with the block height passed in here.
+        ///
+        /// As with transfers of ERC20 tokens, the number of confirmations
+        /// requested in the event is only used if it is greater than the
+        /// given `confirmations`.
+        pub fn decode_nft_transfers(
+            block_height: Uint256,
+            log: &ethabi::RawLog,
+            mut confirmations: Uint256,
+        ) -> Result<Self> {
+            use ethabi::Token;
+
+            let log = nft_transfer_to_chain_event()
+                .parse_log(log.clone())
+                .map_err(|e| Error::Decode(e.to_string()))?;
+            let mut params = log.params.into_iter().map(|param| param.value);
+            let (
+                Some(Token::Uint(nonce)),
+                Some(Token::Array(transfers)),
+                Some(Token::Uint(requested_confirmations)),
+                None,
+            ) = (params.next(), params.next(), params.next(), params.next())
+            else {
+                return Err(Error::Decode(
+                    "Unexpected parameters of an NFT transfer event".into(),
+                ));
+            };
+            confirmations = confirmations.max({
+                let mut num_buf = [0; 32];
+                requested_confirmations.to_little_endian(&mut num_buf);
+                Uint256::from_bytes_le(&num_buf)
+            });
+            let transfers = transfers
+                .into_iter()
+                .map(|transfer| match transfer {
+                    Token::Tuple(fields) => match &fields[..] {
+                        [
+                            Token::Address(contract),
+                            Token::Uint(token_id),
+                            Token::String(receiver),
+                        ] => Ok(NftTransferToNamada {
+                            contract: contract.parse_eth_address()?,
+                            token_id: token_id.parse_uint256()?,
+                            receiver: receiver.clone().parse_address()?,
+                        }),
+                        _ => Err(Error::Decode(format!(
+                            "Unexpected fields of an NFT transfer: {fields:?}"
+                        ))),
+                    },
+                    transfer => Err(Error::Decode(format!(
+                        "Unexpected NFT transfer: {transfer:?}"
+                    ))),
+                })
+                .collect::<Result<_>>()?;
+            Ok(PendingEvent {
+                confirmations,
+                block_height,
+                event: EthereumEvent::NftTransfersToNamada {
+                    nonce: nonce.parse_uint256()?,
+                    transfers,
+                },
+            })
+        }
+
         /// Check if the minimum number of confirmations has been
         /// reached at the input block height.
         pub fn is_confirmed(&self, height: &Uint256) -> bool {
@@ -119,6 +219,79 @@ pub mod eth_events {
         }
     }
 
+    /// Merge the runs of transfers to Namada events with consecutive
+    /// nonces, emitted in the same Ethereum block and requesting the same
+    /// number of confirmations, into
+    /// [`EthereumEvent::TransfersToNamadaBatch`] events, such that each
+    /// run takes up less space in vote extensions.
+    ///
+    /// The batches of different validators need not match, since the
+    /// ledger splits them back into their events and tallies the votes on
+    /// each nonce separately.
+    pub(in super::super) fn batch_transfers_to_namada(
+        confirmed: Vec<PendingEvent>,
+    ) -> Vec<EthereumEvent> {
+        let mut batched: Vec<PendingEvent> =
+            Vec::with_capacity(confirmed.len());
+        for pending in confirmed {
+            let previous = batched.last_mut().filter(|previous| {
+                previous.block_height == pending.block_height
+                    && previous.confirmations == pending.confirmations
+            });
+            let event = match (previous, pending.event) {
+                (
+                    Some(previous),
+                    EthereumEvent::TransfersToNamada { nonce, transfers },
+                ) => {
+                    match append_to_batch(&mut previous.event, nonce, transfers)
+                    {
+                        Ok(()) => continue,
+                        Err(transfers) => EthereumEvent::TransfersToNamada {
+                            nonce,
+                            transfers,
+                        },
+                    }
+                }
+                (_, event) => event,
+            };
+            batched.push(PendingEvent { event, ..pending });
+        }
+        batched.into_iter().map(|pending| pending.event).collect()
+    }
+
+    /// Append the `transfers` of the transfers to Namada event with the
+    /// given `nonce` to the transfers to Namada event (or batch thereof)
+    /// preceding it. The `transfers` are handed back if the event does not
+    /// directly follow the preceding one.
+    fn append_to_batch(
+        preceding: &mut EthereumEvent,
+        nonce: Uint,
+        transfers: Vec<TransferToNamada>,
+    ) -> std::result::Result<(), Vec<TransferToNamada>> {
+        match preceding {
+            EthereumEvent::TransfersToNamada {
+                nonce: first_nonce,
+                transfers: first_transfers,
+            } if first_nonce.checked_increment() == Some(nonce) => {
+                *preceding = EthereumEvent::TransfersToNamadaBatch {
+                    nonce: *first_nonce,
+                    batches: vec![std::mem::take(first_transfers), transfers],
+                };
+                Ok(())
+            }
+            EthereumEvent::TransfersToNamadaBatch {
+                nonce: first_nonce,
+                batches,
+            } if first_nonce.checked_add(batches.len() as u64)
+                == Some(nonce) =>
+            {
+                batches.push(transfers);
+                Ok(())
+            }
+            _ => Err(transfers),
+        }
+    }
+
     macro_rules! parse_method {
         ($name:ident -> $type:ty) => {
             fn $name(self) -> Result<$type> {
@@ -537,6 +710,123 @@ pub mod eth_events {
                 update
             );
         }
+
+        /// Test decoding an event emitted by the NFT bridge contract, upon
+        /// transferring ERC-721 tokens to Namada.
+        #[test]
+        fn test_nft_transfers_decode() -> Result<()> {
+            use ethabi::Token;
+
+            let event = nft_transfer_to_chain_event();
+            assert_eq!(
+                event.signature().0,
+                namada::types::keccak::keccak_hash(
+                    NFT_TRANSFER_TO_CHAIN_SIGNATURE
+                )
+                .0
+            );
+
+            let receiver = "tnam1q87teqzjytwa9xd9qk8u558xxnrwuzdjzs7zvhzr";
+            let log = ethabi::RawLog {
+                topics: vec![event.signature()],
+                data: ethabi::encode(&[
+                    Token::Uint(3u64.into()),
+                    Token::Array(vec![Token::Tuple(vec![
+                        Token::Address(H160([1; 20])),
+                        Token::Uint(42u64.into()),
+                        Token::String(receiver.into()),
+                    ])]),
+                    Token::Uint(5u64.into()),
+                ]),
+            };
+            let min_confirmations: Uint256 = 100u64.into();
+            let pending_event = PendingEvent::decode_nft_transfers(
+                123u64.into(),
+                &log,
+                min_confirmations.clone(),
+            )?;
+
+            assert_eq!(pending_event.confirmations, min_confirmations);
+            assert_eq!(
+                pending_event.event,
+                EthereumEvent::NftTransfersToNamada {
+                    nonce: 3u64.into(),
+                    transfers: vec![NftTransferToNamada {
+                        contract: EthAddress([1; 20]),
+                        token_id: 42u64.into(),
+                        receiver: Address::decode(receiver)
+                            .expect("Test failed"),
+                    }],
+                }
+            );
+            Ok(())
+        }
+
+        /// Test that only transfers to Namada events with consecutive
+        /// nonces, emitted in the same block and requesting the same
+        /// number of confirmations, are batched.
+        #[test]
+        fn test_batch_transfers_to_namada() {
+            let transfers = |nonce: u64| {
+                vec![TransferToNamada {
+                    amount: Amount::from(nonce),
+                    asset: EthAddress([1; 20]),
+                    receiver: Address::decode(
+                        "tnam1q87teqzjytwa9xd9qk8u558xxnrwuzdjzs7zvhzr",
+                    )
+                    .expect("Test failed"),
+                }]
+            };
+            let pending =
+                |nonce: u64, block_height: u64, confirmations: u64| {
+                    PendingEvent {
+                        confirmations: confirmations.into(),
+                        block_height: block_height.into(),
+                        event: EthereumEvent::TransfersToNamada {
+                            nonce: nonce.into(),
+                            transfers: transfers(nonce),
+                        },
+                    }
+                };
+            let validator_set_update = PendingEvent {
+                confirmations: 200u64.into(),
+                block_height: 2u64.into(),
+                event: EthereumEvent::ValidatorSetUpdate {
+                    nonce: 0u64.into(),
+                    bridge_validator_hash: KeccakHash([0; 32]),
+                    governance_validator_hash: KeccakHash([0; 32]),
+                },
+            };
+
+            let batched = batch_transfers_to_namada(vec![
+                pending(0, 1, 100),
+                pending(1, 1, 100),
+                pending(2, 1, 100),
+                // different number of confirmations
+                pending(3, 1, 200),
+                // non-consecutive nonce
+                pending(5, 1, 200),
+                // different block
+                pending(6, 2, 200),
+                validator_set_update.clone(),
+                pending(7, 2, 200),
+            ]);
+
+            assert_eq!(
+                batched,
+                vec![
+                    EthereumEvent::TransfersToNamadaBatch {
+                        nonce: 0u64.into(),
+                        batches: vec![transfers(0), transfers(1), transfers(2)],
+                    },
+                    pending(3, 1, 200).event,
+                    pending(5, 1, 200).event,
+                    pending(6, 2, 200).event,
+                    validator_set_update.event,
+                    pending(7, 2, 200).event,
+                ]
+            );
+        }
     }
 }
 
@@ -0,0 +1,311 @@
+//! Failover between multiple Ethereum RPC endpoints.
+//!
+//! The oracle may be configured with a list of endpoints, in order of
+//! preference. Requests are sent to the active endpoint, and should it fail,
+//! the endpoint is temporarily marked as unhealthy and the request is retried
+//! against the next endpoint, in a round robin fashion. The period for which
+//! an endpoint is considered unhealthy doubles with each consecutive failure,
+//! such that dead endpoints are backed off from, rather than retried on every
+//! request.
+
+use std::cell::Cell;
+use std::future::Future;
+
+use async_trait::async_trait;
+use ethabi::Address;
+use namada::core::types::ethereum_structs;
+use namada::types::control_flow::time::{Duration, Instant};
+
+use super::{Error, RpcClient, SyncStatus};
+
+/// How long an endpoint is deprioritized for, after failing a request.
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// The maximum period an endpoint is deprioritized for, after consecutively
+/// failing multiple requests.
+const MAX_UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30 * 60);
+
+/// An Ethereum RPC endpoint, along with its health status.
+struct Endpoint<C> {
+    /// The url of the endpoint
+    url: String,
+    /// The client connected to the endpoint
+    client: C,
+    /// The instant until which the endpoint is considered unhealthy, if it
+    /// failed recently
+    unhealthy_until: Cell<Option<Instant>>,
+    /// The number of requests the endpoint failed in a row
+    failures: Cell<u32>,
+}
+
+impl<C> Endpoint<C> {
+    /// Check if the endpoint is healthy at the given instant.
+    #[inline]
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until
+            .get()
+            .map(|until| now >= until)
+            .unwrap_or(true)
+    }
+
+    /// Mark the endpoint as healthy, after it served a request.
+    #[inline]
+    fn mark_healthy(&self) {
+        self.unhealthy_until.set(None);
+        self.failures.set(0);
+    }
+
+    /// Mark the endpoint as unhealthy, after it failed a request, backing
+    /// off from it exponentially in the number of consecutive failures.
+    fn mark_unhealthy(&self) {
+        let failures = self.failures.get().saturating_add(1);
+        self.failures.set(failures);
+        self.unhealthy_until
+            .set(Some(Instant::now() + unhealthy_cooldown(failures)));
+    }
+}
+
+/// How long an endpoint is deprioritized for, after failing the given
+/// number of requests in a row.
+fn unhealthy_cooldown(failures: u32) -> Duration {
+    let factor = 1u32
+        .checked_shl(failures.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    UNHEALTHY_COOLDOWN
+        .checked_mul(factor)
+        .map(|cooldown| cooldown.min(MAX_UNHEALTHY_COOLDOWN))
+        .unwrap_or(MAX_UNHEALTHY_COOLDOWN)
+}
+
+/// [`RpcClient`] spread over multiple Ethereum RPC endpoints, which fails
+/// over to the next healthy endpoint whenever the active one fails.
+pub struct FailoverClient<C> {
+    /// The endpoints, in order of preference
+    endpoints: Vec<Endpoint<C>>,
+    /// The index of the endpoint requests are currently sent to
+    active: Cell<usize>,
+}
+
+impl<C: RpcClient> FailoverClient<C> {
+    /// Build a new [`FailoverClient`] from a non-empty list of endpoint
+    /// urls, in order of preference.
+    pub fn new<S: AsRef<str>>(urls: impl IntoIterator<Item = S>) -> Self {
+        Self::from_clients(urls.into_iter().map(|url| {
+            let url = url.as_ref();
+            (url.to_owned(), C::new_client(url))
+        }))
+    }
+
+    /// Build a new [`FailoverClient`] from a non-empty list of clients,
+    /// paired with the urls of their endpoints, in order of preference.
+    pub fn from_clients(
+        clients: impl IntoIterator<Item = (String, C)>,
+    ) -> Self {
+        let endpoints: Vec<_> = clients
+            .into_iter()
+            .map(|(url, client)| Endpoint {
+                url,
+                client,
+                unhealthy_until: Cell::new(None),
+                failures: Cell::new(0),
+            })
+            .collect();
+        assert!(
+            !endpoints.is_empty(),
+            "At least one Ethereum RPC endpoint must be configured"
+        );
+        Self {
+            endpoints,
+            active: Cell::new(0),
+        }
+    }
+
+    /// The url of the endpoint requests are currently sent to.
+    pub fn active_url(&self) -> &str {
+        &self.endpoints[self.active.get()].url
+    }
+
+    /// The indices of the endpoints, in the order in which they should be
+    /// tried. Healthy endpoints come first, starting from the active one,
+    /// followed by the unhealthy endpoint whose cooldown ends first, as a
+    /// last resort. The other unhealthy endpoints are backed off from.
+    fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let num_endpoints = self.endpoints.len();
+        let active = self.active.get();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..num_endpoints)
+            .map(|offset| (active + offset) % num_endpoints)
+            .partition(|&index| self.endpoints[index].is_healthy(now));
+        let last_resort = unhealthy
+            .into_iter()
+            .min_by_key(|&index| self.endpoints[index].unhealthy_until.get());
+        healthy.extend(last_resort);
+        healthy
+    }
+
+    /// Send a request to the endpoints, failing over to the next candidate
+    /// endpoint whenever one of them fails.
+    async fn with_failover<'this, T, F, Fut>(
+        &'this self,
+        request: F,
+    ) -> Result<T, Error>
+    where
+        F: Fn(&'this C) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut last_error = None;
+        for index in self.candidates() {
+            let endpoint = &self.endpoints[index];
+            match request(&endpoint.client).await {
+                Ok(output) => {
+                    if index != self.active.get() {
+                        tracing::info!(
+                            url = endpoint.url,
+                            "Ethereum oracle failed over to a new RPC endpoint"
+                        );
+                        self.active.set(index);
+                    }
+                    endpoint.mark_healthy();
+                    return Ok(output);
+                }
+                Err(error) if is_endpoint_failure(&error) => {
+                    tracing::warn!(
+                        url = endpoint.url,
+                        %error,
+                        "Ethereum RPC endpoint failed"
+                    );
+                    endpoint.mark_unhealthy();
+                    last_error = Some(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.expect("There is at least one Ethereum RPC endpoint"))
+    }
+}
+
+/// Check if the given error was caused by a faulty endpoint, as opposed to
+/// the state of the Ethereum chain or of the oracle.
+#[inline]
+fn is_endpoint_failure(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Timeout
+            | Error::CheckEvents(_, _, _)
+            | Error::RateLimited(_, _, _)
+    )
+}
+
+#[async_trait(?Send)]
+impl<C: RpcClient> RpcClient for FailoverClient<C> {
+    type Log = C::Log;
+
+    #[inline]
+    fn new_client(rpc_url: &str) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new([rpc_url])
+    }
+
+    async fn check_events_in_block(
+        &self,
+        block: ethereum_structs::BlockHeight,
+        address: Address,
+        abi_signature: &str,
+    ) -> Result<Vec<Self::Log>, Error> {
+        self.with_failover(|client| {
+            client.check_events_in_block(block.clone(), address, abi_signature)
+        })
+        .await
+    }
+
+    async fn syncing(
+        &self,
+        last_processed_block: Option<&ethereum_structs::BlockHeight>,
+        backoff: Duration,
+        deadline: Instant,
+    ) -> Result<SyncStatus, Error> {
+        // every endpoint is given the same amount of time to respond
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        self.with_failover(|client| {
+            let deadline = Instant::now() + timeout;
+            client.syncing(last_processed_block, backoff, deadline)
+        })
+        .await
+    }
+
+    fn may_recover(&self, error: &Error) -> bool {
+        // endpoint failures are transient, as long as there are
+        // other endpoints to fail over to
+        (self.endpoints.len() > 1 && is_endpoint_failure(error))
+            || self.endpoints[self.active.get()].client.may_recover(error)
+    }
+}
+
+#[cfg(test)]
+mod test_failover {
+    use super::*;
+    use crate::node::ledger::ethereum_oracle::test_tools::mock_web3_client::{
+        TestCmd, Web3Client,
+    };
+
+    /// Test that requests fail over to the next healthy endpoint, and that
+    /// unhealthy endpoints are only used as a last resort.
+    #[tokio::test]
+    async fn test_failover_between_endpoints() {
+        let (_blocks_0, client_0) = Web3Client::setup();
+        let (_blocks_1, client_1) = Web3Client::setup();
+        let controller_0 = client_0.controller();
+        let controller_1 = client_1.controller();
+        let client = FailoverClient::from_clients([
+            ("http://eth-0".to_owned(), client_0),
+            ("http://eth-1".to_owned(), client_1),
+        ]);
+        let check_events = || {
+            client.check_events_in_block(
+                1u64.into(),
+                Address::default(),
+                "Event()",
+            )
+        };
+
+        assert!(check_events().await.is_ok());
+        assert_eq!(client.active_url(), "http://eth-0");
+
+        // the active endpoint fails, so we fail over to the next one
+        controller_0.apply_cmd(TestCmd::Unresponsive);
+        assert!(check_events().await.is_ok());
+        assert_eq!(client.active_url(), "http://eth-1");
+
+        // the first endpoint recovers, but it is still cooling down
+        controller_0.apply_cmd(TestCmd::Normal);
+        assert!(check_events().await.is_ok());
+        assert_eq!(client.active_url(), "http://eth-1");
+
+        // when every healthy endpoint fails, unhealthy ones are tried
+        controller_1.apply_cmd(TestCmd::Unresponsive);
+        assert!(check_events().await.is_ok());
+        assert_eq!(client.active_url(), "http://eth-0");
+
+        // once all endpoints fail, the error is recoverable
+        controller_0.apply_cmd(TestCmd::Unresponsive);
+        let error = check_events().await.unwrap_err();
+        assert!(client.may_recover(&error));
+
+        // consecutive failures are backed off from for longer
+        assert_eq!(client.endpoints[0].failures.get(), 1);
+        assert_eq!(client.endpoints[1].failures.get(), 2);
+    }
+
+    /// Test that the cooldown of unhealthy endpoints doubles with each
+    /// consecutive failure, up to a maximum.
+    #[test]
+    fn test_unhealthy_cooldown_backoff() {
+        assert_eq!(unhealthy_cooldown(1), UNHEALTHY_COOLDOWN);
+        assert_eq!(unhealthy_cooldown(2), UNHEALTHY_COOLDOWN * 2);
+        assert_eq!(unhealthy_cooldown(3), UNHEALTHY_COOLDOWN * 4);
+        assert_eq!(unhealthy_cooldown(10), MAX_UNHEALTHY_COOLDOWN);
+        assert_eq!(unhealthy_cooldown(u32::MAX), MAX_UNHEALTHY_COOLDOWN);
+    }
+}
@@ -0,0 +1,600 @@
+//! Light client verification of the Ethereum events found by the oracle.
+//!
+//! By default, the oracle fully trusts the Ethereum JSON-RPC endpoint it
+//! is connected to, such that a compromised endpoint is able to forge
+//! bridge events. When the light client is enabled, the events found in
+//! an Ethereum block are only trusted once they are proven to be part of
+//! the receipts of that block. The receipts are authenticated against
+//! the receipts root in the header of the block, which must be agreed
+//! upon by a minimum number of independent endpoints.
+//!
+//! Note that the light client does not protect against an endpoint
+//! withholding events, which merely delays the bridge, as the events
+//! of each block are voted on by all validators.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use ethabi::Address;
+use namada::core::types::ethereum_structs;
+use namada::eth_bridge::ethers::providers::{Http, Middleware, Provider};
+use namada::eth_bridge::ethers::types::{TransactionReceipt, H256};
+use namada::eth_bridge::ethers::utils::keccak256;
+use namada::eth_bridge::ethers::utils::rlp::{self, RlpStream};
+use num256::Uint256;
+
+use super::Error;
+
+/// The fields of an Ethereum block header relevant to the light client.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BlockHeader {
+    /// The hash of the block.
+    pub hash: H256,
+    /// The root of the trie of the receipts of the block.
+    pub receipts_root: H256,
+}
+
+/// A source of Ethereum block data, e.g. a JSON-RPC endpoint.
+#[async_trait(?Send)]
+pub trait BlockSource {
+    /// Fetch the header of the block at the given height, if it
+    /// exists.
+    async fn block_header(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockHeader>, String>;
+
+    /// Fetch the receipts of all the transactions in the block at
+    /// the given height, in the order of the transactions.
+    async fn block_receipts(
+        &self,
+        height: u64,
+    ) -> Result<Vec<TransactionReceipt>, String>;
+}
+
+#[async_trait(?Send)]
+impl BlockSource for Provider<Http> {
+    async fn block_header(
+        &self,
+        height: u64,
+    ) -> Result<Option<BlockHeader>, String> {
+        let block = self.get_block(height).await.map_err(|e| e.to_string())?;
+        Ok(block.and_then(|block| {
+            Some(BlockHeader {
+                hash: block.hash?,
+                receipts_root: block.receipts_root,
+            })
+        }))
+    }
+
+    async fn block_receipts(
+        &self,
+        height: u64,
+    ) -> Result<Vec<TransactionReceipt>, String> {
+        self.get_block_receipts(height)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Verifies the inclusion of Ethereum event logs in the blocks they
+/// were found in.
+pub struct LightClient<S = Provider<Http>> {
+    /// The sources of block headers, along with their urls.
+    sources: Vec<(String, S)>,
+    /// The minimum number of sources that must agree on a block header.
+    min_agreeing_sources: usize,
+    /// The receipts of the last verified block.
+    verified_receipts: Mutex<Option<(u64, Vec<TransactionReceipt>)>>,
+}
+
+impl<S: BlockSource> LightClient<S> {
+    /// Create a new [`LightClient`], trusting the block headers agreed
+    /// upon by at least `min_agreeing_sources` of the given `sources`.
+    /// Fails if `min_agreeing_sources` is not between 1 and the number of
+    /// sources.
+    pub fn new(
+        sources: impl IntoIterator<Item = (String, S)>,
+        min_agreeing_sources: usize,
+    ) -> Result<Self, Error> {
+        let sources: Vec<_> = sources.into_iter().collect();
+        if !(1..=sources.len()).contains(&min_agreeing_sources) {
+            return Err(Error::InvalidQuorum(
+                min_agreeing_sources,
+                sources.len(),
+            ));
+        }
+        Ok(Self {
+            sources,
+            min_agreeing_sources,
+            verified_receipts: Mutex::new(None),
+        })
+    }
+
+    /// Verify that the given `logs`, emitted by the contract at `address`,
+    /// are included in the receipts of the given Ethereum block.
+    pub async fn verify_logs(
+        &self,
+        block: &ethereum_structs::BlockHeight,
+        address: Address,
+        logs: &[ethabi::RawLog],
+    ) -> Result<(), Error> {
+        let height = {
+            let n: Uint256 = block.clone().into();
+            let n: u64 =
+                n.0.try_into().expect("Ethereum block number overflow");
+            n
+        };
+        let cached = {
+            let verified = self.verified_receipts.lock().unwrap();
+            verified
+                .as_ref()
+                .filter(|(verified_height, _)| *verified_height == height)
+                .map(|(_, receipts)| receipts.clone())
+        };
+        let receipts = match cached {
+            Some(receipts) => receipts,
+            None => {
+                let receipts = self.fetch_verified_receipts(height).await?;
+                *self.verified_receipts.lock().unwrap() =
+                    Some((height, receipts.clone()));
+                receipts
+            }
+        };
+        let all_logs: Vec<_> = receipts
+            .iter()
+            .flat_map(|receipt| receipt.logs.iter())
+            .collect();
+        for log in logs {
+            let included = all_logs.iter().any(|included| {
+                included.address == address
+                    && included.topics == log.topics
+                    && included.data.as_ref() == log.data.as_slice()
+            });
+            if !included {
+                tracing::error!(
+                    height,
+                    ?address,
+                    ?log,
+                    "An Ethereum event log is not included in the receipts of \
+                     its block, the Ethereum RPC endpoint may have been \
+                     compromised"
+                );
+                return Err(Error::UnverifiedEvents(
+                    height,
+                    "An event log is not included in the block receipts".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch the receipts of the given Ethereum block, authenticated
+    /// against the receipts root of its header.
+    async fn fetch_verified_receipts(
+        &self,
+        height: u64,
+    ) -> Result<Vec<TransactionReceipt>, Error> {
+        let header = self.agreed_block_header(height).await?;
+        for (url, source) in &self.sources {
+            match source.block_receipts(height).await {
+                Ok(receipts)
+                    if receipts_root(&receipts) == header.receipts_root =>
+                {
+                    return Ok(receipts);
+                }
+                Ok(_) => tracing::warn!(
+                    height,
+                    %url,
+                    "The receipts of an Ethereum block returned by an RPC \
+                     endpoint do not match the receipts root of its header"
+                ),
+                Err(error) => tracing::debug!(
+                    height,
+                    %url,
+                    %error,
+                    "Failed to fetch the receipts of an Ethereum block"
+                ),
+            }
+        }
+        Err(Error::UnverifiedEvents(
+            height,
+            "No endpoint returned receipts matching the block header".into(),
+        ))
+    }
+
+    /// Fetch the header of the given Ethereum block agreed upon by at
+    /// least the minimum number of sources.
+    async fn agreed_block_header(
+        &self,
+        height: u64,
+    ) -> Result<BlockHeader, Error> {
+        let mut votes: HashMap<BlockHeader, usize> = HashMap::new();
+        for (url, source) in &self.sources {
+            match source.block_header(height).await {
+                Ok(Some(header)) => *votes.entry(header).or_default() += 1,
+                Ok(None) => {}
+                Err(error) => tracing::debug!(
+                    height,
+                    %url,
+                    %error,
+                    "Failed to fetch the header of an Ethereum block"
+                ),
+            }
+        }
+        let mut agreed = votes
+            .into_iter()
+            .filter(|(_, count)| *count >= self.min_agreeing_sources)
+            .map(|(header, _)| header);
+        match (agreed.next(), agreed.next()) {
+            (Some(header), None) => Ok(header),
+            (None, _) => Err(Error::UnverifiedEvents(
+                height,
+                format!(
+                    "Fewer than {} endpoints agree on the block header",
+                    self.min_agreeing_sources
+                ),
+            )),
+            (Some(_), Some(_)) => Err(Error::UnverifiedEvents(
+                height,
+                "Endpoints agree on conflicting block headers".into(),
+            )),
+        }
+    }
+}
+
+/// Compute the root of the Merkle Patricia trie of the given receipts,
+/// keyed by their index in the block, as committed to by the
+/// `receiptsRoot` field of Ethereum block headers.
+pub fn receipts_root(receipts: &[TransactionReceipt]) -> H256 {
+    trie_root(receipts.iter().enumerate().map(|(index, receipt)| {
+        (
+            rlp::encode(&(index as u64)).to_vec(),
+            encode_receipt(receipt),
+        )
+    }))
+}
+
+/// Compute the root of the Merkle Patricia trie holding the given
+/// key-value pairs.
+fn trie_root(items: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> H256 {
+    let mut items: Vec<_> = items
+        .into_iter()
+        .map(|(key, value)| (to_nibbles(&key), value))
+        .collect();
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    H256(keccak256(encode_trie_node(&items, 0)))
+}
+
+/// Encode a receipt as found in the leaves of the receipts trie, i.e.
+/// prefixed with its transaction type, as per EIP-2718.
+fn encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
+    let mut encoded = vec![];
+    if let Some(tx_type) = receipt.transaction_type {
+        if !tx_type.is_zero() {
+            encoded.push(tx_type.as_u64() as u8);
+        }
+    }
+    encoded.extend_from_slice(&rlp::encode(receipt));
+    encoded
+}
+
+/// Split the given bytes into nibbles.
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Compact encoding of a path of nibbles in a trie node, flagging
+/// whether the node is a leaf.
+fn hex_prefix(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 2 } else { 0 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag << 4);
+        nibbles
+    };
+    encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// RLP encode the trie node holding the given `items`, sorted by their
+/// keys, whose first `depth` nibbles are consumed by the parent nodes.
+fn encode_trie_node(items: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    match items {
+        [] => rlp::NULL_RLP.to_vec(),
+        [(key, value)] => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(&key[depth..], true));
+            stream.append(value);
+            stream.out().to_vec()
+        }
+        _ => {
+            let first = &items[0].0[depth..];
+            let last = &items[items.len() - 1].0[depth..];
+            let shared =
+                first.iter().zip(last).take_while(|(a, b)| a == b).count();
+            if shared > 0 {
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&hex_prefix(&first[..shared], false));
+                append_node_ref(
+                    &mut stream,
+                    encode_trie_node(items, depth + shared),
+                );
+                return stream.out().to_vec();
+            }
+            let mut stream = RlpStream::new_list(17);
+            // a key ending at this branch node sorts first
+            let (value, mut rest) = match items.split_first() {
+                Some(((key, value), rest)) if key.len() == depth => {
+                    (Some(value), rest)
+                }
+                _ => (None, items),
+            };
+            for nibble in 0..16 {
+                let len = rest
+                    .iter()
+                    .take_while(|(key, _)| key[depth] == nibble)
+                    .count();
+                let (children, tail) = rest.split_at(len);
+                if children.is_empty() {
+                    stream.append_empty_data();
+                } else {
+                    append_node_ref(
+                        &mut stream,
+                        encode_trie_node(children, depth + 1),
+                    );
+                }
+                rest = tail;
+            }
+            match value {
+                Some(value) => stream.append(value),
+                None => stream.append_empty_data(),
+            };
+            stream.out().to_vec()
+        }
+    }
+}
+
+/// Append a reference to a child node to a trie node. Child nodes shorter
+/// than a hash are embedded in their parent.
+fn append_node_ref(stream: &mut RlpStream, node: Vec<u8>) {
+    if node.len() < 32 {
+        stream.append_raw(&node, 1);
+    } else {
+        stream.append(&keccak256(&node).to_vec());
+    }
+}
+
+#[cfg(test)]
+mod test_light_client {
+    use namada::eth_bridge::ethers::types::{Log, U64};
+
+    use super::*;
+
+    /// A source of block data with a fixed header and receipts.
+    #[derive(Clone)]
+    struct MockSource {
+        header: Option<BlockHeader>,
+        receipts: Vec<TransactionReceipt>,
+    }
+
+    #[async_trait(?Send)]
+    impl BlockSource for MockSource {
+        async fn block_header(
+            &self,
+            _: u64,
+        ) -> Result<Option<BlockHeader>, String> {
+            Ok(self.header)
+        }
+
+        async fn block_receipts(
+            &self,
+            _: u64,
+        ) -> Result<Vec<TransactionReceipt>, String> {
+            Ok(self.receipts.clone())
+        }
+    }
+
+    fn log(address: Address, data: u8) -> Log {
+        Log {
+            address,
+            topics: vec![H256::repeat_byte(1)],
+            data: vec![data].into(),
+            ..Default::default()
+        }
+    }
+
+    fn raw_log(data: u8) -> ethabi::RawLog {
+        ethabi::RawLog {
+            topics: vec![H256::repeat_byte(1)],
+            data: vec![data],
+        }
+    }
+
+    /// Return a source of a block with the given receipts, along with
+    /// the header of the block.
+    fn source(receipts: Vec<TransactionReceipt>) -> (BlockHeader, MockSource) {
+        let header = BlockHeader {
+            hash: H256::repeat_byte(2),
+            receipts_root: receipts_root(&receipts),
+        };
+        let source = MockSource {
+            header: Some(header),
+            receipts,
+        };
+        (header, source)
+    }
+
+    fn receipts(address: Address) -> Vec<TransactionReceipt> {
+        (0..20)
+            .map(|i| TransactionReceipt {
+                transaction_index: U64::from(i),
+                transaction_type: Some(U64::from(i % 3)),
+                status: Some(U64::one()),
+                logs: vec![log(address, i as u8)],
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Test that the receipts root of a block without transactions is
+    /// the root of the empty trie.
+    #[test]
+    fn test_empty_receipts_root() {
+        assert_eq!(
+            format!("{:x}", receipts_root(&[])),
+            "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+        );
+    }
+
+    /// Test the trie root against the vectors of the Ethereum test suite,
+    /// from `TrieTests/trieanyorder.json` of `ethereum/tests`.
+    #[test]
+    fn test_trie_root_vectors() {
+        let vectors: [(&[(&str, &str)], &str); 4] = [
+            (
+                &[("A", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")],
+                "d23786fb4a010da3ce639d66d5e904a11dbc02746d1ce25029e53290cabf28ab",
+            ),
+            (
+                &[("doe", "reindeer"), ("dog", "puppy"), ("dogglesworth", "cat")],
+                "8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3",
+            ),
+            (
+                &[
+                    ("do", "verb"),
+                    ("horse", "stallion"),
+                    ("doge", "coin"),
+                    ("dog", "puppy"),
+                ],
+                "5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84",
+            ),
+            (
+                &[("be", "e"), ("dog", "puppy"), ("bed", "d")],
+                "3f67c7a47520f79faa29255d2d3c084a7a6df0453116ed7232ff10277a8be68b",
+            ),
+        ];
+        for (items, root) in vectors {
+            let items = items.iter().map(|(key, value)| {
+                (key.as_bytes().to_vec(), value.as_bytes().to_vec())
+            });
+            assert_eq!(format!("{:x}", trie_root(items)), root);
+        }
+    }
+
+    /// Test that a light client can't be built with an invalid number of
+    /// agreeing sources.
+    #[test]
+    fn test_invalid_light_client_quorum() {
+        let (_, source) = source(vec![]);
+        let sources = || [("http://eth-0".to_owned(), source.clone())];
+        assert!(matches!(
+            LightClient::new(sources(), 0),
+            Err(Error::InvalidQuorum(0, 1))
+        ));
+        assert!(matches!(
+            LightClient::new(sources(), 2),
+            Err(Error::InvalidQuorum(2, 1))
+        ));
+        assert!(LightClient::new(sources(), 1).is_ok());
+    }
+
+    /// Test that the receipts root commits to the contents and order
+    /// of the receipts.
+    #[test]
+    fn test_receipts_root_commits_to_receipts() {
+        let address = Address::repeat_byte(3);
+        let receipts = receipts(address);
+        let root = receipts_root(&receipts);
+
+        let mut reordered = receipts.clone();
+        reordered.swap(0, 1);
+        assert_ne!(receipts_root(&reordered), root);
+
+        let mut forged = receipts;
+        forged[10].logs[0].data = vec![255].into();
+        assert_ne!(receipts_root(&forged), root);
+    }
+
+    /// Test that logs are only verified if they are included in the
+    /// receipts of a block header agreed upon by enough sources.
+    #[tokio::test]
+    async fn test_verify_logs() {
+        let address = Address::repeat_byte(3);
+        let block = ethereum_structs::BlockHeight::from(1u64);
+        let (_, honest) = source(receipts(address));
+        let client = LightClient::new(
+            [
+                ("http://eth-0".to_owned(), honest.clone()),
+                ("http://eth-1".to_owned(), honest.clone()),
+            ],
+            2,
+        )
+        .unwrap();
+        assert!(
+            client
+                .verify_logs(&block, address, &[raw_log(1), raw_log(19)])
+                .await
+                .is_ok()
+        );
+        // forged log
+        assert!(
+            client
+                .verify_logs(&block, address, &[raw_log(100)])
+                .await
+                .is_err()
+        );
+        // log emitted by another contract
+        assert!(
+            client
+                .verify_logs(&block, Address::repeat_byte(4), &[raw_log(1)])
+                .await
+                .is_err()
+        );
+
+        // a compromised source serves a forged block, which the
+        // other source disagrees with
+        let mut forged_receipts = receipts(address);
+        forged_receipts[0].logs.push(log(address, 100));
+        let (_, forged) = source(forged_receipts);
+        let client = LightClient::new(
+            [
+                ("http://eth-0".to_owned(), forged),
+                ("http://eth-1".to_owned(), honest.clone()),
+            ],
+            2,
+        )
+        .unwrap();
+        assert!(
+            client
+                .verify_logs(&block, address, &[raw_log(100)])
+                .await
+                .is_err()
+        );
+
+        // a compromised source serves forged receipts for an
+        // honest header
+        let forged = MockSource {
+            receipts: vec![],
+            ..honest.clone()
+        };
+        let client = LightClient::new(
+            [
+                ("http://eth-0".to_owned(), forged.clone()),
+                ("http://eth-1".to_owned(), forged),
+            ],
+            1,
+        )
+        .unwrap();
+        assert!(
+            client
+                .verify_logs(&block, address, &[raw_log(1)])
+                .await
+                .is_err()
+        );
+    }
+}
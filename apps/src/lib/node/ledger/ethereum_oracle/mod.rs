@@ -1,28 +1,44 @@
 pub mod control;
 pub mod events;
+pub mod failover;
+pub mod light_client;
+pub mod progress;
+pub mod quorum;
+pub mod rate_limit;
 pub mod test_tools;
 
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::ops::ControlFlow;
+use std::sync::Mutex;
 
 use async_trait::async_trait;
 use ethabi::Address;
-use ethbridge_events::{event_codecs, EventKind};
+use ethbridge_events::{event_codecs, DynEventCodec, EventKind};
 use itertools::Either;
 use namada::core::hints;
 use namada::core::types::ethereum_structs;
 use namada::eth_bridge::ethers;
-use namada::eth_bridge::ethers::providers::{Http, Middleware, Provider};
+use namada::eth_bridge::ethers::providers::{
+    Http, Middleware, Provider, ProviderError, RpcError,
+};
 use namada::eth_bridge::oracle::config::Config;
 use namada::types::control_flow::time::{Constant, Duration, Instant, Sleep};
-use namada::types::ethereum_events::EthereumEvent;
+use namada::types::ethereum_events::{EthAddress, EthereumEvent};
 use namada_sdk::eth_bridge::{eth_syncing_status_timeout, SyncStatus};
+use namada_sdk::queries::NodeMetrics;
 use num256::Uint256;
 use thiserror::Error;
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::Sender as BoundedSender;
 use tokio::task::LocalSet;
 
-use self::events::PendingEvent;
+use self::events::{PendingEvent, NFT_TRANSFER_TO_CHAIN_SIGNATURE};
+use self::failover::FailoverClient;
+use self::light_client::LightClient;
+use self::progress::ProgressStore;
+use self::quorum::QuorumClient;
+use self::rate_limit::{RateLimitedClient, RateLimits};
 use super::abortable::AbortableSpawner;
 use crate::node::ledger::oracle::control::Command;
 
@@ -30,6 +46,12 @@ use crate::node::ledger::oracle::control::Command;
 const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
 const DEFAULT_CEILING: Duration = Duration::from_secs(30);
 
+/// Ethereum blocks that have already been processed by the oracle, paired
+/// with the confirmed events found in them, which are held back until the
+/// blocks are deeper than the reorg safety depth.
+type RecentBlocks =
+    VecDeque<(ethereum_structs::BlockHeight, Vec<EthereumEvent>)>;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Ethereum node has fallen out of sync")]
@@ -38,6 +60,11 @@ pub enum Error {
         "Couldn't check for events ({0} from {1}) with the RPC endpoint: {2}"
     )]
     CheckEvents(String, Address, String),
+    #[error(
+        "The RPC endpoint rejected the check for events ({0} from {1}) for \
+         exceeding its request cap: {2}"
+    )]
+    RateLimited(String, Address, String),
     #[error("Could not send all bridge events ({0} from {1}) to the shell")]
     Channel(String, Address),
     #[error(
@@ -46,6 +73,30 @@ pub enum Error {
     MoreConfirmations,
     #[error("The Ethereum oracle timed out")]
     Timeout,
+    #[error("Could not verify the Ethereum events in block {0}: {1}")]
+    UnverifiedEvents(u64, String),
+    #[error(
+        "The minimum number of agreeing Ethereum RPC endpoints ({0}) must be \
+         between 1 and the number of endpoints ({1})"
+    )]
+    InvalidQuorum(usize, usize),
+}
+
+/// JSON-RPC error codes with which Ethereum RPC endpoints reject requests
+/// exceeding their request cap: the `Limit exceeded` code of EIP-1474, and
+/// the HTTP status code reused by some providers.
+const RATE_LIMITED_ERROR_CODES: [i64; 2] = [-32005, 429];
+
+/// Check if the given error of an Ethereum RPC endpoint was returned for
+/// exceeding its request cap.
+fn is_rate_limited_response(error: &ProviderError) -> bool {
+    if let ProviderError::HTTPError(error) = error {
+        return error.status().map(|status| status.as_u16()) == Some(429);
+    }
+    error
+        .as_error_response()
+        .map(|response| RATE_LIMITED_ERROR_CODES.contains(&response.code))
+        .unwrap_or_default()
 }
 
 /// Convert values to [`ethabi`] Ethereum event logs.
@@ -142,11 +193,19 @@ impl RpcClient for Provider<Http> {
         )
         .await
         .map_err(|error| {
-            Error::CheckEvents(
-                abi_signature.into(),
-                contract_address,
-                error.to_string(),
-            )
+            if is_rate_limited_response(&error) {
+                Error::RateLimited(
+                    abi_signature.into(),
+                    contract_address,
+                    error.to_string(),
+                )
+            } else {
+                Error::CheckEvents(
+                    abi_signature.into(),
+                    contract_address,
+                    error.to_string(),
+                )
+            }
         })
     }
 
@@ -175,7 +234,10 @@ impl RpcClient for Provider<Http> {
     fn may_recover(&self, error: &Error) -> bool {
         !matches!(
             error,
-            Error::Timeout | Error::Channel(_, _) | Error::CheckEvents(_, _, _)
+            Error::Timeout
+                | Error::Channel(_, _)
+                | Error::CheckEvents(_, _, _)
+                | Error::RateLimited(_, _, _)
         )
     }
 }
@@ -197,6 +259,20 @@ pub struct Oracle<C = Provider<Http>> {
     ceiling: Duration,
     /// A channel for controlling and configuring the oracle.
     control: control::Receiver,
+    /// The number of processed Ethereum blocks whose events are held back
+    /// and re-checked for reorgs, unless overridden by governance.
+    reorg_safety_depth: u64,
+    /// The most recently processed Ethereum blocks, which are re-checked
+    /// for reorgs before their events are sent.
+    recent_blocks: Mutex<RecentBlocks>,
+    /// Whether the oracle has been paused by a [`Command::Pause`].
+    paused: bool,
+    /// Optional light client, verifying the inclusion of the events
+    /// found by the oracle in their Ethereum blocks.
+    light_client: Option<LightClient>,
+    /// Optional local store of the most recently processed block, from
+    /// which the oracle resumes after a restart.
+    progress: Option<ProgressStore>,
 }
 
 impl<C: RpcClient> Oracle<C> {
@@ -220,9 +296,36 @@ impl<C: RpcClient> Oracle<C> {
             ceiling,
             last_processed_block,
             control,
+            reorg_safety_depth: 0,
+            recent_blocks: Mutex::new(VecDeque::new()),
+            paused: false,
+            light_client: None,
+            progress: None,
         }
     }
 
+    /// Set the number of processed Ethereum blocks whose events are held
+    /// back and re-checked for reorgs, unless overridden by governance.
+    pub fn with_reorg_safety_depth(mut self, depth: u64) -> Self {
+        self.reorg_safety_depth = depth;
+        self
+    }
+
+    /// Verify the inclusion of the events found by the oracle in their
+    /// Ethereum blocks with the given [`LightClient`].
+    pub fn with_light_client(mut self, light_client: LightClient) -> Self {
+        self.light_client = Some(light_client);
+        self
+    }
+
+    /// Resume from the last block recorded in the given [`ProgressStore`]
+    /// after a restart. Blocks are recorded there by the ledger, once it
+    /// has received their events.
+    pub fn with_progress_store(mut self, progress: ProgressStore) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     /// Send a series of [`EthereumEvent`]s to the Namada
     /// ledger. Returns a boolean indicating that all sent
     /// successfully. If false is returned, the receiver
@@ -242,27 +345,109 @@ impl<C: RpcClient> Oracle<C> {
         true
     }
 
-    /// Check if a new config has been sent from teh Shell.
+    /// Check if a new config has been sent from teh Shell. Pending
+    /// [`Command::Pause`] and [`Command::Resume`] commands are applied
+    /// along the way.
     fn update_config(&mut self) -> Option<Config> {
-        match self.control.try_recv() {
-            Ok(Command::UpdateConfig(config)) => Some(config),
-            Err(TryRecvError::Disconnected) => panic!(
-                "The Ethereum oracle command channel has unexpectedly hung up."
-            ),
-            _ => None,
+        let mut new_config = None;
+        loop {
+            match self.control.try_recv() {
+                Ok(cmd) => {
+                    if let Some(config) = self.handle_command(cmd) {
+                        new_config = Some(config);
+                    }
+                }
+                Err(TryRecvError::Disconnected) => panic!(
+                    "The Ethereum oracle command channel has unexpectedly \
+                     hung up."
+                ),
+                Err(TryRecvError::Empty) => return new_config,
+            }
         }
     }
 
-    /// If the bridge has been deactivated, block here until a new
-    /// config is passed that reactivates the bridge
-    async fn wait_on_reactivation(&mut self) -> Config {
-        loop {
-            if let Some(Command::UpdateConfig(c)) = self.control.recv().await {
-                if c.active {
-                    return c;
+    /// Apply the given [`Command`], returning the new config it carries,
+    /// if any.
+    fn handle_command(&mut self, cmd: Command) -> Option<Config> {
+        match cmd {
+            Command::UpdateConfig(config) => Some(config),
+            Command::Pause => {
+                if !self.paused {
+                    tracing::warn!("Ethereum oracle has been paused");
+                }
+                self.paused = true;
+                None
+            }
+            Command::Resume => {
+                if self.paused {
+                    tracing::info!("Ethereum oracle has been resumed");
                 }
+                self.paused = false;
+                None
+            }
+        }
+    }
+
+    /// Switch over to the bridge contract handover in the given `config`.
+    /// If the handover block has already been processed, the oracle
+    /// rewinds to it, such that no event of the new bridge contract is
+    /// missed. Once the handover block is processed, the oracle attests it
+    /// with an [`EthereumEvent::BridgeContractHandover`], whose
+    /// confirmation applies the rotation on chain. Returns the next block
+    /// to process.
+    fn apply_contract_handover(
+        &self,
+        config: &Config,
+        next_block_to_process: ethereum_structs::BlockHeight,
+    ) -> ethereum_structs::BlockHeight {
+        let Some(handover) = &config.bridge_contract_handover else {
+            return next_block_to_process;
+        };
+        tracing::info!(
+            ?handover,
+            "Handing over to a new Ethereum bridge contract"
+        );
+        if handover.validator_set_hashes.is_none() {
+            tracing::warn!(
+                ?handover,
+                "The validator sets the new Ethereum bridge contract is \
+                 initialized with were not snapshotted by governance"
+            );
+        }
+        if handover.handover_block >= next_block_to_process {
+            return next_block_to_process;
+        }
+        tracing::warn!(
+            ?handover,
+            ?next_block_to_process,
+            "The handover block of the new Ethereum bridge contract has \
+             already been processed, re-processing from it"
+        );
+        // the held events of the rewound blocks are found again once these
+        // blocks are re-processed
+        self.recent_blocks
+            .lock()
+            .unwrap()
+            .retain(|(block, _)| *block < handover.handover_block);
+        handover.handover_block.clone()
+    }
+
+    /// If the bridge has been deactivated, or the oracle has been paused,
+    /// block here until a new config is passed that reactivates the bridge
+    /// and the oracle is resumed.
+    async fn wait_on_reactivation(&mut self, mut config: Config) -> Config {
+        while !config.active || self.paused {
+            let Some(cmd) = self.control.recv().await else {
+                panic!(
+                    "The Ethereum oracle command channel has unexpectedly \
+                     hung up."
+                );
+            };
+            if let Some(new_config) = self.handle_command(cmd) {
+                config = new_config;
             }
         }
+        config
     }
 }
 
@@ -279,34 +464,87 @@ async fn await_initial_configuration(
 }
 
 /// Set up an Oracle and run the process where the Oracle
-/// processes and forwards Ethereum events to the ledger
+/// processes and forwards Ethereum events to the ledger.
+///
+/// The Oracle fails over between the given RPC `urls`, in
+/// order of preference, observing the given `rate_limits` of
+/// each endpoint. If a `quorum` is given, the Oracle instead
+/// queries all of the `urls`, and only trusts the events that
+/// at least `quorum` of them agree on. It holds back the events
+/// of the last `reorg_safety_depth` processed blocks, re-checking
+/// them for reorgs, unless overridden by governance.
+/// If a `light_client` is given, the events found are only sent to
+/// the ledger once their inclusion in Ethereum has been verified. If a
+/// `progress` store is given, the Oracle resumes from the last block the
+/// ledger acknowledged there, unless the ledger's storage is further ahead.
+/// The calls affected by rate limiting are counted in the given
+/// `node_metrics`.
+#[allow(clippy::too_many_arguments)]
 pub fn run_oracle<C: RpcClient>(
-    url: impl AsRef<str>,
+    urls: Vec<String>,
+    quorum: Option<usize>,
+    rate_limits: RateLimits,
+    node_metrics: NodeMetrics,
+    reorg_safety_depth: u64,
+    light_client: Option<LightClient>,
+    progress: Option<ProgressStore>,
     sender: BoundedSender<EthereumEvent>,
     control: control::Receiver,
     last_processed_block: last_processed_block::Sender,
     spawner: &mut AbortableSpawner,
 ) -> tokio::task::JoinHandle<()> {
-    let url = url.as_ref().to_owned();
     let blocking_handle = tokio::task::spawn_blocking(move || {
         let rt = tokio::runtime::Handle::current();
         rt.block_on(async move {
             LocalSet::new()
                 .run_until(async move {
-                    tracing::info!(?url, "Ethereum event oracle is starting");
-
-                    let oracle = Oracle::<C>::new(
-                        Either::Right(&url),
+                    tracing::info!(?urls, "Ethereum event oracle is starting");
+
+                    let clients = urls.iter().map(|url| {
+                        let client = RateLimitedClient::new(
+                            C::new_client(url),
+                            rate_limits,
+                            node_metrics.clone(),
+                        );
+                        (url.clone(), client)
+                    });
+                    let setup = OracleSetup {
                         sender,
-                        last_processed_block,
-                        DEFAULT_BACKOFF,
-                        DEFAULT_CEILING,
                         control,
-                    );
-                    run_oracle_aux(oracle).await;
+                        last_processed_block,
+                        reorg_safety_depth,
+                        light_client,
+                        progress,
+                    };
+                    match quorum {
+                        Some(min_agreeing_endpoints) => {
+                            tracing::info!(
+                                min_agreeing_endpoints,
+                                "Ethereum event oracle is running in quorum \
+                                 mode"
+                            );
+                            match QuorumClient::from_clients(
+                                clients,
+                                min_agreeing_endpoints,
+                            ) {
+                                Ok(client) => {
+                                    run_oracle_aux(setup.build(client)).await
+                                }
+                                Err(error) => tracing::error!(
+                                    %error,
+                                    "Could not start the Ethereum event \
+                                     oracle in quorum mode"
+                                ),
+                            }
+                        }
+                        None => {
+                            let client = FailoverClient::from_clients(clients);
+                            run_oracle_aux(setup.build(client)).await;
+                        }
+                    }
 
                     tracing::info!(
-                        ?url,
+                        ?urls,
                         "Ethereum event oracle is no longer running"
                     );
                 })
@@ -321,6 +559,39 @@ pub fn run_oracle<C: RpcClient>(
         .with_no_cleanup()
 }
 
+/// The settings of an [`Oracle`] run by [`run_oracle`], independent of
+/// the client it talks to Ethereum with.
+struct OracleSetup {
+    sender: BoundedSender<EthereumEvent>,
+    control: control::Receiver,
+    last_processed_block: last_processed_block::Sender,
+    reorg_safety_depth: u64,
+    light_client: Option<LightClient>,
+    progress: Option<ProgressStore>,
+}
+
+impl OracleSetup {
+    /// Build an [`Oracle`] talking to Ethereum with the given `client`.
+    fn build<C: RpcClient>(self, client: C) -> Oracle<C> {
+        let mut oracle = Oracle::new(
+            Either::Left(client),
+            self.sender,
+            self.last_processed_block,
+            DEFAULT_BACKOFF,
+            DEFAULT_CEILING,
+            self.control,
+        )
+        .with_reorg_safety_depth(self.reorg_safety_depth);
+        if let Some(light_client) = self.light_client {
+            oracle = oracle.with_light_client(light_client);
+        }
+        if let Some(progress) = self.progress {
+            oracle = oracle.with_progress_store(progress);
+        }
+        oracle
+    }
+}
+
 /// Determine what action to take after attempting to
 /// process events contained in an Ethereum block.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -415,8 +686,29 @@ async fn run_oracle_aux<C: RpcClient>(mut oracle: Oracle<C>) {
                 return;
             }
         };
+    // the oracle may have been paused as soon as it was configured
+    if let Some(new_config) = oracle.update_config() {
+        config = new_config;
+    }
+    if oracle.paused {
+        config = oracle.wait_on_reactivation(config).await;
+    }
 
     let mut next_block_to_process = config.start_block.clone();
+    // the oracle may have processed blocks past the height recorded in
+    // the ledger's storage before the node was stopped
+    if let Some(last_processed_block) =
+        oracle.progress.as_ref().and_then(ProgressStore::load)
+    {
+        let resume_block = last_processed_block + 1.into();
+        if resume_block > next_block_to_process {
+            tracing::info!(
+                ?resume_block,
+                "Resuming the Ethereum oracle from its recorded progress"
+            );
+            next_block_to_process = resume_block;
+        }
+    }
 
     loop {
         tracing::info!(
@@ -443,22 +735,90 @@ async fn run_oracle_aux<C: RpcClient>(mut oracle: Oracle<C>) {
             break;
         }
 
-        oracle
-            .last_processed_block
-            .send_replace(Some(next_block_to_process.clone()));
+        // NB: the last processed block is published once its events are
+        // sent, and the progress is only recorded by the ledger, once it
+        // has pulled all the events sent until this point
         // check if a new config has been sent.
+        let handover = config.bridge_contract_handover.clone();
         if let Some(new_config) = oracle.update_config() {
             config = new_config;
         }
-        if !config.active {
-            config = oracle.wait_on_reactivation().await;
+        if !config.active || oracle.paused {
+            config = oracle.wait_on_reactivation(config).await;
         }
         next_block_to_process += 1.into();
+        if config.bridge_contract_handover != handover {
+            next_block_to_process =
+                oracle.apply_contract_handover(&config, next_block_to_process);
+        }
+    }
+}
+
+/// A source of Ethereum events relevant to the ledger.
+#[derive(Copy, Clone)]
+enum EventSource {
+    /// Events emitted by the bridge contract.
+    Bridge(DynEventCodec),
+    /// Transfers of ERC-721 tokens emitted by the NFT bridge contract.
+    NftBridge(EthAddress),
+}
+
+impl EventSource {
+    /// All the sources of events to check, given the oracle's `config`.
+    fn all(config: &Config) -> impl Iterator<Item = Self> {
+        event_codecs()
+            .map(Self::Bridge)
+            .chain(config.nft_bridge_contract.map(Self::NftBridge))
+    }
+
+    /// The ABI signature of the events of this source.
+    fn signature(&self) -> Cow<'static, str> {
+        match self {
+            Self::Bridge(codec) => Cow::from(codec.event_signature()),
+            Self::NftBridge(_) => {
+                Cow::Borrowed(NFT_TRANSFER_TO_CHAIN_SIGNATURE)
+            }
+        }
+    }
+
+    /// The address of the contract emitting the events of this source in
+    /// the given Ethereum block.
+    fn address(
+        &self,
+        config: &Config,
+        block: &ethereum_structs::BlockHeight,
+    ) -> Address {
+        match self {
+            Self::Bridge(codec) => match codec.kind() {
+                EventKind::Bridge => config.bridge_contract_at(block).into(),
+            },
+            Self::NftBridge(contract) => (*contract).into(),
+        }
+    }
+
+    /// Decode a log of this source into a [`PendingEvent`].
+    fn decode(
+        &self,
+        block_height: Uint256,
+        log: &ethabi::RawLog,
+        confirmations: Uint256,
+    ) -> Result<PendingEvent, events::Error> {
+        match self {
+            Self::Bridge(codec) => {
+                PendingEvent::decode(*codec, block_height, log, confirmations)
+            }
+            Self::NftBridge(_) => PendingEvent::decode_nft_transfers(
+                block_height,
+                log,
+                confirmations,
+            ),
+        }
     }
 }
 
 /// Checks if the given block has any events relating to the bridge, and if so,
-/// sends them to the oracle's `sender` channel
+/// sends them to the oracle's `sender` channel, once the block is deeper than
+/// the reorg safety depth
 async fn process_events_in_block<C: RpcClient>(
     block_to_process: &ethereum_structs::BlockHeight,
     oracle: &Oracle<C>,
@@ -468,13 +828,12 @@ async fn process_events_in_block<C: RpcClient>(
     let pending = &mut queue;
     // update the latest block height
 
-    let last_processed_block_ref = oracle.last_processed_block.borrow();
-    let last_processed_block = last_processed_block_ref.as_ref();
+    let last_processed_block = oracle.last_processed_block.borrow().clone();
     let backoff = oracle.backoff;
     let deadline = Instant::now() + oracle.ceiling;
     let latest_block = match oracle
         .client
-        .syncing(last_processed_block, backoff, deadline)
+        .syncing(last_processed_block.as_ref(), backoff, deadline)
         .await?
     {
         SyncStatus::AtHeight(height) => height,
@@ -497,13 +856,17 @@ async fn process_events_in_block<C: RpcClient>(
         ?latest_block,
         "Got latest Ethereum block height"
     );
+    // governance may override the reorg safety depth of the node
+    let reorg_safety_depth = config
+        .reorg_safety_depth
+        .unwrap_or(oracle.reorg_safety_depth);
+    check_for_reorgs(oracle, config, &latest_block).await?;
+    let mut found_events = vec![];
     // check for events in Ethereum blocks that have reached the minimum number
     // of confirmations
-    for codec in event_codecs() {
-        let sig = codec.event_signature();
-        let addr: Address = match codec.kind() {
-            EventKind::Bridge => config.bridge_contract.into(),
-        };
+    for source in EventSource::all(config) {
+        let sig = source.signature();
+        let addr = source.address(config, block_to_process);
         tracing::debug!(
             ?block_to_process,
             ?addr,
@@ -511,45 +874,9 @@ async fn process_events_in_block<C: RpcClient>(
             "Checking for bridge events"
         );
         // fetch the events for matching the given signature
-        let mut events = {
-            let logs = oracle
-                .client
-                .check_events_in_block(block_to_process.clone(), addr, &sig)
+        let mut events =
+            fetch_events_in_block(oracle, config, block_to_process, source)
                 .await?;
-            if !logs.is_empty() {
-                tracing::info!(
-                    ?block_to_process,
-                    ?addr,
-                    ?sig,
-                    n_events = logs.len(),
-                    "Found bridge events in Ethereum block"
-                )
-            }
-            logs.into_iter()
-                .map(IntoEthAbiLog::into_ethabi_log)
-                .filter_map(|log| {
-                    match PendingEvent::decode(
-                        codec,
-                        block_to_process.clone().into(),
-                        &log,
-                        u64::from(config.min_confirmations).into(),
-                    ) {
-                        Ok(event) => Some(event),
-                        Err(error) => {
-                            tracing::error!(
-                                ?error,
-                                ?block_to_process,
-                                ?addr,
-                                ?sig,
-                                "Couldn't decode event: {:#?}",
-                                log
-                            );
-                            None
-                        }
-                    }
-                })
-                .collect()
-        };
         pending.append(&mut events);
         if !pending.is_empty() {
             tracing::info!(
@@ -560,7 +887,7 @@ async fn process_events_in_block<C: RpcClient>(
                 "There are Ethereum events pending"
             );
         }
-        let confirmed = process_queue(&latest_block, pending);
+        let mut confirmed = process_queue(&latest_block, pending);
         if !confirmed.is_empty() {
             tracing::info!(
                 ?block_to_process,
@@ -573,8 +900,176 @@ async fn process_events_in_block<C: RpcClient>(
                  confirmations and will be sent onwards"
             );
         }
-        if !oracle.send(confirmed).await {
-            return Err(Error::Channel(sig.into(), addr));
+        found_events.append(&mut confirmed);
+    }
+    // attest that the handover block of a new bridge contract has been
+    // processed, such that the rotation is applied on chain
+    if let Some(handover) = config
+        .bridge_contract_handover
+        .as_ref()
+        .filter(|handover| &handover.handover_block == block_to_process)
+    {
+        let event = EthereumEvent::BridgeContractHandover {
+            new_contract: handover.new_contract,
+            handover_height: (&handover.handover_block).into(),
+        };
+        tracing::info!(
+            ?block_to_process,
+            ?event,
+            "Reached the handover block of the new Ethereum bridge contract"
+        );
+        found_events.push(event);
+    }
+    // the events are held back until the block is deeper than the reorg
+    // safety depth, such that events undone by a reorg are never sent
+    oracle
+        .recent_blocks
+        .lock()
+        .unwrap()
+        .push_back((block_to_process.clone(), found_events));
+    send_final_events(oracle, config, reorg_safety_depth).await
+}
+
+/// Send the events of the processed Ethereum blocks that are deeper than
+/// the `reorg_safety_depth`, and publish the last of these blocks as the
+/// last block processed by the oracle.
+async fn send_final_events<C: RpcClient>(
+    oracle: &Oracle<C>,
+    config: &Config,
+    reorg_safety_depth: u64,
+) -> Result<(), Error> {
+    let depth = usize::try_from(reorg_safety_depth).unwrap_or(usize::MAX);
+    loop {
+        let final_block = {
+            let mut recent_blocks = oracle.recent_blocks.lock().unwrap();
+            if recent_blocks.len() <= depth {
+                return Ok(());
+            }
+            recent_blocks.pop_front()
+        };
+        let Some((block, events)) = final_block else {
+            return Ok(());
+        };
+        if !oracle.send(events).await {
+            return Err(Error::Channel(
+                format!("events of Ethereum block {block}"),
+                config.bridge_contract_at(&block).into(),
+            ));
+        }
+        oracle.last_processed_block.send_replace(Some(block));
+    }
+}
+
+/// Fetch and decode the events of the given `source` in the given
+/// Ethereum block.
+async fn fetch_events_in_block<C: RpcClient>(
+    oracle: &Oracle<C>,
+    config: &Config,
+    block_to_process: &ethereum_structs::BlockHeight,
+    source: EventSource,
+) -> Result<Vec<PendingEvent>, Error> {
+    let sig = source.signature();
+    let addr = source.address(config, block_to_process);
+    let logs = oracle
+        .client
+        .check_events_in_block(block_to_process.clone(), addr, &sig)
+        .await?;
+    if !logs.is_empty() {
+        tracing::info!(
+            ?block_to_process,
+            ?addr,
+            ?sig,
+            n_events = logs.len(),
+            "Found bridge events in Ethereum block"
+        )
+    }
+    let logs: Vec<_> = logs
+        .into_iter()
+        .map(IntoEthAbiLog::into_ethabi_log)
+        .collect();
+    if let Some(light_client) = &oracle.light_client {
+        if !logs.is_empty() {
+            light_client
+                .verify_logs(block_to_process, addr, &logs)
+                .await?;
+        }
+    }
+    Ok(logs
+        .into_iter()
+        .filter_map(|log| {
+            match source.decode(
+                block_to_process.clone().into(),
+                &log,
+                u64::from(config.min_confirmations).into(),
+            ) {
+                Ok(event) => Some(event),
+                Err(error) => {
+                    tracing::error!(
+                        ?error,
+                        ?block_to_process,
+                        ?addr,
+                        ?sig,
+                        "Couldn't decode event: {:#?}",
+                        log
+                    );
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/// Re-check the processed Ethereum blocks whose events have not been sent
+/// yet, replacing their events with the confirmed events found in them now.
+/// Different events in a processed block mean that Ethereum has been
+/// reorged below that block.
+async fn check_for_reorgs<C: RpcClient>(
+    oracle: &Oracle<C>,
+    config: &Config,
+    latest_block: &Uint256,
+) -> Result<(), Error> {
+    let blocks_to_check: Vec<_> = oracle
+        .recent_blocks
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(block, _)| block.clone())
+        .collect();
+    for block in blocks_to_check {
+        let mut found_events = vec![];
+        for source in EventSource::all(config) {
+            let mut events =
+                fetch_events_in_block(oracle, config, &block, source).await?;
+            found_events.append(&mut process_queue(latest_block, &mut events));
+        }
+        let mut recent_blocks = oracle.recent_blocks.lock().unwrap();
+        let Some((_, held_events)) = recent_blocks
+            .iter_mut()
+            .find(|(recent_block, _)| *recent_block == block)
+        else {
+            continue;
+        };
+        // the attestation of a contract handover is not an Ethereum log
+        found_events.extend(
+            held_events
+                .iter()
+                .filter(|event| {
+                    matches!(
+                        event,
+                        EthereumEvent::BridgeContractHandover { .. }
+                    )
+                })
+                .cloned(),
+        );
+        if *held_events != found_events {
+            tracing::warn!(
+                ?block,
+                held = held_events.len(),
+                found = found_events.len(),
+                "Ethereum reorg detected, replacing the events of an already \
+                 processed block before they are sent"
+            );
+            *held_events = found_events;
         }
     }
     Ok(())
@@ -582,7 +1077,8 @@ async fn process_events_in_block<C: RpcClient>(
 
 /// Check which events in the queue have reached their
 /// required number of confirmations and remove them
-/// from the queue of pending events
+/// from the queue of pending events. Confirmed transfers
+/// to Namada are batched together, where possible.
 fn process_queue(
     latest_block: &Uint256,
     pending: &mut Vec<PendingEvent>,
@@ -592,12 +1088,12 @@ fn process_queue(
     let mut confirmed = vec![];
     for item in pending_tmp.into_iter() {
         if item.is_confirmed(latest_block) {
-            confirmed.push(item.event);
+            confirmed.push(item);
         } else {
             pending.push(item);
         }
     }
-    confirmed
+    events::batch_transfers_to_namada(confirmed)
 }
 
 pub mod last_processed_block {
@@ -622,6 +1118,7 @@ mod test_oracle {
 
     use ethbridge_bridge_events::{TransferToChainFilter, TransferToErcFilter};
     use namada::eth_bridge::ethers::types::H160;
+    use namada::eth_bridge::oracle::config::ContractHandover;
     use namada::eth_bridge::structs::Erc20Transfer;
     use namada::types::address::testing::gen_established_address;
     use namada::types::ethereum_events::{EthAddress, TransferToEthereum};
@@ -684,6 +1181,11 @@ mod test_oracle {
                 backoff: Duration::from_millis(5),
                 ceiling: DEFAULT_CEILING,
                 control: control_receiver,
+                reorg_safety_depth: 0,
+                recent_blocks: Mutex::new(VecDeque::new()),
+                paused: false,
+                light_client: None,
+                progress: None,
             },
             controller,
             eth_recv: eth_receiver,
@@ -1123,6 +1625,88 @@ mod test_oracle {
         oracle.await.expect("Test failed");
     }
 
+    /// Test that the Ethereum oracle can be paused and resumed via
+    /// commands, without being reconfigured.
+    /// NOTE: This test can flake due to async channel race
+    /// conditions.
+    #[tokio::test]
+    async fn test_oracle_pause_and_resume() {
+        let TestPackage {
+            oracle,
+            eth_recv,
+            controller,
+            mut blocks_processed_recv,
+            mut control_sender,
+        } = setup();
+        let config = Config::default();
+        let oracle = start_with_default_config(
+            oracle,
+            &mut control_sender,
+            config.clone(),
+        )
+        .await;
+        let min_confirmations = u64::from(config.min_confirmations);
+
+        // let the oracle process a few blocks
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(
+            min_confirmations + 1,
+        )));
+        for height in 0u64..=1 {
+            let block_processed = timeout(
+                std::time::Duration::from_secs(3),
+                blocks_processed_recv.recv(),
+            )
+            .await
+            .expect("Timed out waiting for block to be checked")
+            .unwrap();
+            assert_eq!(block_processed, Uint256::from(height));
+        }
+
+        // pause the oracle, which still finishes processing the block it
+        // is currently checking
+        control_sender
+            .try_send(Command::Pause)
+            .expect("Test failed");
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(
+            min_confirmations + 5,
+        )));
+        let block_processed = timeout(
+            std::time::Duration::from_secs(3),
+            blocks_processed_recv.recv(),
+        )
+        .await
+        .expect("Timed out waiting for block to be checked")
+        .unwrap();
+        assert_eq!(block_processed, Uint256::from(2u64));
+
+        // check that the oracle hasn't checked any further blocks
+        let res = timeout(
+            std::time::Duration::from_secs(3),
+            blocks_processed_recv.recv(),
+        )
+        .await;
+        assert!(res.is_err());
+
+        // resume the oracle and check that it processes the rest of the
+        // confirmed blocks
+        control_sender
+            .try_send(Command::Resume)
+            .expect("Test failed");
+        for height in 3u64..=5 {
+            let block_processed = timeout(
+                std::time::Duration::from_secs(3),
+                blocks_processed_recv.recv(),
+            )
+            .await
+            .expect("Timed out waiting for block to be checked")
+            .unwrap();
+            assert_eq!(block_processed, Uint256::from(height));
+        }
+        drop(eth_recv);
+        oracle.await.expect("Test failed");
+    }
+
     /// Test that if the Ethereum RPC endpoint returns a latest block that is
     /// more than one block later than the previous latest block we received, we
     /// still check all the blocks in between
@@ -1186,4 +1770,165 @@ mod test_oracle {
         drop(eth_recv);
         oracle.await.expect("Test failed");
     }
+
+    /// Test that the events of processed Ethereum blocks are only sent once
+    /// the blocks are deeper than the reorg safety depth, that events undone
+    /// by a reorg are never sent, and that governance may override the depth
+    /// configured in the node.
+    #[tokio::test]
+    async fn test_events_are_held_until_final() {
+        let TestPackage {
+            oracle,
+            mut eth_recv,
+            controller,
+            blocks_processed_recv: _processed,
+            ..
+        } = setup();
+        let oracle = oracle.with_reorg_safety_depth(3);
+        let mut config = Config::default();
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(200u32)));
+        let new_event = |height| {
+            let log = TransferToChainFilter {
+                nonce: 0.into(),
+                transfers: vec![],
+                confirmations: 100.into(),
+            }
+            .get_log();
+            let (sender, _seen) = channel();
+            controller.apply_cmd(TestCmd::NewEvent {
+                event_type: event_signature::<TransferToChainFilter>(),
+                log,
+                height,
+                seen: sender,
+            });
+        };
+        let held_blocks = |oracle: &TestOracle| -> Vec<_> {
+            oracle
+                .recent_blocks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(block, events)| (block.clone(), events.len()))
+                .collect()
+        };
+        let held = |block: u64, n_events: usize| {
+            (ethereum_structs::BlockHeight::from(block), n_events)
+        };
+
+        for block in 1u64..=5 {
+            process_events_in_block(&block.into(), &oracle, &config)
+                .await
+                .expect("Test failed");
+        }
+        assert!(eth_recv.try_recv().is_err());
+        assert_eq!(
+            *oracle.last_processed_block.borrow(),
+            Some(2u64.into())
+        );
+
+        // an event shows up in a block that was already processed, and is
+        // held back with it
+        new_event(4);
+        process_events_in_block(&6u64.into(), &oracle, &config)
+            .await
+            .expect("Test failed");
+        assert!(eth_recv.try_recv().is_err());
+        assert_eq!(held_blocks(&oracle), vec![held(4, 1), held(5, 0), held(6, 0)]);
+
+        // the event is undone by a reorg before its block is final
+        process_events_in_block(&7u64.into(), &oracle, &config)
+            .await
+            .expect("Test failed");
+        assert!(eth_recv.try_recv().is_err());
+        assert_eq!(held_blocks(&oracle), vec![held(5, 0), held(6, 0), held(7, 0)]);
+
+        // governance overrides the depth of the node, such that the events
+        // of a processed block are sent right away
+        new_event(8);
+        config.reorg_safety_depth = Some(0);
+        process_events_in_block(&8u64.into(), &oracle, &config)
+            .await
+            .expect("Test failed");
+        let event = eth_recv.try_recv().expect("Test failed");
+        assert!(matches!(
+            &event,
+            EthereumEvent::TransfersToNamada { transfers, .. }
+                if transfers.is_empty()
+        ));
+        assert!(eth_recv.try_recv().is_err());
+        assert!(held_blocks(&oracle).is_empty());
+        assert_eq!(
+            *oracle.last_processed_block.borrow(),
+            Some(8u64.into())
+        );
+    }
+
+    /// Test that the oracle rewinds to the handover block of a new bridge
+    /// contract, if it has already been processed.
+    #[tokio::test]
+    async fn test_contract_handover_rewinds() {
+        let TestPackage {
+            oracle, controller, ..
+        } = setup();
+        let oracle = oracle.with_reorg_safety_depth(5);
+        let config = Config::default();
+        controller.apply_cmd(TestCmd::NewHeight(Uint256::from(200u32)));
+        for block in 1u64..=5 {
+            process_events_in_block(&block.into(), &oracle, &config)
+                .await
+                .expect("Test failed");
+        }
+
+        let new_contract = EthAddress([1; 20]);
+        let handover_config = |handover_block: u64| Config {
+            bridge_contract_handover: Some(ContractHandover {
+                new_contract,
+                handover_block: handover_block.into(),
+                validator_set_hashes: None,
+            }),
+            ..Config::default()
+        };
+
+        // a handover in the future does not change the next block
+        let next_block =
+            oracle.apply_contract_handover(&handover_config(10), 6u64.into());
+        assert_eq!(next_block, 6u64.into());
+        assert_eq!(oracle.recent_blocks.lock().unwrap().len(), 5);
+
+        // a handover in the past rewinds to the handover block
+        let next_block =
+            oracle.apply_contract_handover(&handover_config(3), 6u64.into());
+        assert_eq!(next_block, 3u64.into());
+        let recent_blocks: Vec<_> = oracle
+            .recent_blocks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(block, _)| block.clone())
+            .collect();
+        assert_eq!(recent_blocks, vec![1u64.into(), 2u64.into()]);
+    }
+
+    /// Test that the errors returned by Ethereum RPC endpoints for
+    /// exceeding their request cap are told apart by their error code.
+    #[test]
+    fn test_is_rate_limited_response() {
+        let error = |code: i64| {
+            ProviderError::JsonRpcClientError(Box::new(
+                ethers::providers::HttpClientError::JsonRpcError(
+                    ethers::providers::JsonRpcError {
+                        code,
+                        message: "request failed".into(),
+                        data: None,
+                    },
+                ),
+            ))
+        };
+        assert!(is_rate_limited_response(&error(-32005)));
+        assert!(is_rate_limited_response(&error(429)));
+        assert!(!is_rate_limited_response(&error(-32000)));
+        assert!(!is_rate_limited_response(&ProviderError::CustomError(
+            "rate limit exceeded".into()
+        )));
+    }
 }
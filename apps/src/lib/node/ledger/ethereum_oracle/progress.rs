@@ -0,0 +1,146 @@
+//! Persistence of the progress made by the oracle, independently of the
+//! state of the chain.
+//!
+//! The last Ethereum block processed by the oracle is only written to the
+//! ledger's storage when a block is committed, such that after a restart
+//! the oracle would re-scan any Ethereum block it had processed since the
+//! last commit. The [`ProgressStore`] records the last processed block in a
+//! small local file, keyed by chain id, as soon as the ledger has received
+//! all its events, such that the oracle resumes where it stopped, without
+//! skipping any events still in flight.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use namada::core::types::ethereum_structs;
+use namada::types::chain::ChainId;
+use serde::{Deserialize, Serialize};
+
+/// The name of the file the progress of the oracle is stored in, within
+/// the directory of the chain.
+pub const PROGRESS_FILE_NAME: &str = "ethereum_oracle_progress.json";
+
+/// The progress made by the oracle on some chain.
+#[derive(Debug, Serialize, Deserialize)]
+struct Progress {
+    chain_id: ChainId,
+    last_processed_block: ethereum_structs::BlockHeight,
+}
+
+/// Local store of the progress made by the oracle on some chain.
+#[derive(Debug, Clone)]
+pub struct ProgressStore {
+    /// The file the progress is stored in.
+    path: PathBuf,
+    /// The chain the oracle is running for.
+    chain_id: ChainId,
+}
+
+impl ProgressStore {
+    /// Open the store of the progress of the oracle in the given chain
+    /// directory.
+    pub fn new(chain_dir: impl AsRef<Path>, chain_id: ChainId) -> Self {
+        Self {
+            path: chain_dir.as_ref().join(PROGRESS_FILE_NAME),
+            chain_id,
+        }
+    }
+
+    /// Read the last Ethereum block processed by the oracle on this chain,
+    /// if any. A missing, unreadable or foreign progress file is ignored.
+    pub fn load(&self) -> Option<ethereum_structs::BlockHeight> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::warn!(
+                    path = %self.path.display(),
+                    %err,
+                    "Failed to read the progress of the Ethereum oracle"
+                );
+                return None;
+            }
+        };
+        match serde_json::from_slice::<Progress>(&bytes) {
+            Ok(progress) if progress.chain_id == self.chain_id => {
+                Some(progress.last_processed_block)
+            }
+            Ok(progress) => {
+                tracing::warn!(
+                    path = %self.path.display(),
+                    chain_id = %progress.chain_id,
+                    "Ignoring the progress of the Ethereum oracle recorded \
+                     for another chain"
+                );
+                None
+            }
+            Err(err) => {
+                tracing::warn!(
+                    path = %self.path.display(),
+                    %err,
+                    "Failed to decode the progress of the Ethereum oracle"
+                );
+                None
+            }
+        }
+    }
+
+    /// Record the last Ethereum block processed by the oracle. The progress
+    /// is written to a temporary file, which is synced to disk before it
+    /// atomically replaces the progress file, such that the latter is never
+    /// left half written, even after a crash.
+    pub fn save(
+        &self,
+        last_processed_block: &ethereum_structs::BlockHeight,
+    ) -> io::Result<()> {
+        let progress = Progress {
+            chain_id: self.chain_id.clone(),
+            last_processed_block: last_processed_block.clone(),
+        };
+        let tmp_path = self.path.with_extension("json.tmp");
+        let mut tmp_file = fs::File::create(&tmp_path)?;
+        tmp_file.write_all(&serde_json::to_vec(&progress)?)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        // sync the directory too, for the rename to be durable
+        #[cfg(unix)]
+        if let Some(dir) = self.path.parent() {
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_progress {
+    use super::*;
+
+    /// Test that the recorded progress is only loaded back for the chain
+    /// it was recorded for.
+    #[test]
+    fn test_progress_is_keyed_by_chain_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain_id = ChainId("chain-a".to_owned());
+        let store = ProgressStore::new(dir.path(), chain_id);
+        assert_eq!(store.load(), None);
+
+        store.save(&5u64.into()).unwrap();
+        assert_eq!(store.load(), Some(5u64.into()));
+        store.save(&6u64.into()).unwrap();
+        assert_eq!(store.load(), Some(6u64.into()));
+
+        let other_chain_id = ChainId("chain-b".to_owned());
+        let other_store = ProgressStore::new(dir.path(), other_chain_id);
+        assert_eq!(other_store.load(), None);
+    }
+
+    /// Test that a corrupted progress file is ignored.
+    #[test]
+    fn test_corrupted_progress_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProgressStore::new(dir.path(), ChainId("chain".into()));
+        fs::write(dir.path().join(PROGRESS_FILE_NAME), b"{").unwrap();
+        assert_eq!(store.load(), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! Quorum of multiple Ethereum RPC endpoints.
+//!
+//! By default, the oracle trusts the events returned by whichever endpoint
+//! it is connected to. In quorum mode, every request is sent to all of the
+//! configured endpoints, and an event log is only emitted once a minimum
+//! number of them agree on it. Logs which enough endpoints agree are not
+//! part of a block are dropped, such that a single malicious or buggy
+//! endpoint can neither forge nor censor events. Logs on which no quorum
+//! is reached either way, e.g. because some endpoints lag behind, are
+//! re-checked later. Endpoints known to lag behind a block abstain from
+//! agreeing on its logs, rather than claiming that it has none.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use ethabi::{Address, Hash};
+use namada::core::types::ethereum_structs;
+use namada::types::control_flow::time::{Duration, Instant};
+use num256::Uint256;
+
+use super::{Error, IntoEthAbiLog, RpcClient, SyncStatus};
+
+/// The contents of an Ethereum event log, which endpoints must agree on.
+type LogContents = (Vec<Hash>, Vec<u8>);
+
+/// [`RpcClient`] spread over multiple Ethereum RPC endpoints, which only
+/// trusts the responses agreed upon by a minimum number of them.
+pub struct QuorumClient<C> {
+    /// The clients connected to the endpoints, along with their urls
+    clients: Vec<(String, C)>,
+    /// The minimum number of endpoints that must agree on a response
+    min_agreeing_endpoints: usize,
+    /// The last block height reached by each endpoint, if known
+    heights: RefCell<Vec<Option<Uint256>>>,
+}
+
+impl<C: RpcClient> QuorumClient<C> {
+    /// Build a new [`QuorumClient`] from a list of clients, paired with
+    /// the urls of their endpoints, trusting the responses agreed upon by
+    /// at least `min_agreeing_endpoints` of them. Fails if
+    /// `min_agreeing_endpoints` is not between 1 and the number of clients.
+    pub fn from_clients(
+        clients: impl IntoIterator<Item = (String, C)>,
+        min_agreeing_endpoints: usize,
+    ) -> Result<Self, Error> {
+        let clients: Vec<_> = clients.into_iter().collect();
+        if !(1..=clients.len()).contains(&min_agreeing_endpoints) {
+            return Err(Error::InvalidQuorum(
+                min_agreeing_endpoints,
+                clients.len(),
+            ));
+        }
+        let heights = RefCell::new(vec![None; clients.len()]);
+        Ok(Self {
+            clients,
+            min_agreeing_endpoints,
+            heights,
+        })
+    }
+
+    /// Check if the endpoint at the given index is known to lag behind
+    /// the given block.
+    fn lags_behind(
+        &self,
+        index: usize,
+        block: &ethereum_structs::BlockHeight,
+    ) -> bool {
+        self.heights.borrow()[index]
+            .as_ref()
+            .map(|height| height < &**block)
+            .unwrap_or_default()
+    }
+}
+
+/// Check if the given error was caused by a faulty endpoint, or a lack of
+/// agreement between endpoints, as opposed to the state of the oracle.
+#[inline]
+fn is_endpoint_failure(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Timeout
+            | Error::CheckEvents(_, _, _)
+            | Error::RateLimited(_, _, _)
+    )
+}
+
+#[async_trait(?Send)]
+impl<C: RpcClient> RpcClient for QuorumClient<C> {
+    type Log = ethabi::RawLog;
+
+    #[inline]
+    fn new_client(rpc_url: &str) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_clients([(rpc_url.to_owned(), C::new_client(rpc_url))], 1)
+            .expect("A single endpoint should make up a valid quorum")
+    }
+
+    async fn check_events_in_block(
+        &self,
+        block: ethereum_structs::BlockHeight,
+        address: Address,
+        abi_signature: &str,
+    ) -> Result<Vec<Self::Log>, Error> {
+        // the order in which logs were first returned, along with the
+        // number of times each endpoint returned them
+        let mut order: Vec<LogContents> = vec![];
+        let mut counts: HashMap<LogContents, Vec<usize>> = HashMap::new();
+        let mut responses = 0;
+        for (index, (url, client)) in self.clients.iter().enumerate() {
+            if self.lags_behind(index, &block) {
+                tracing::debug!(
+                    %url,
+                    ?block,
+                    "Ethereum RPC endpoint of the oracle quorum lags behind \
+                     the checked block, abstaining"
+                );
+                continue;
+            }
+            let logs = match client
+                .check_events_in_block(block.clone(), address, abi_signature)
+                .await
+            {
+                Ok(logs) => logs,
+                Err(error) => {
+                    tracing::debug!(
+                        %url,
+                        %error,
+                        "Ethereum RPC endpoint of the oracle quorum failed"
+                    );
+                    continue;
+                }
+            };
+            responses += 1;
+            for log in logs {
+                let log = log.into_ethabi_log();
+                let counts = counts
+                    .entry((log.topics, log.data))
+                    .or_insert_with_key(|contents| {
+                        order.push(contents.clone());
+                        vec![0; self.clients.len()]
+                    });
+                counts[index] += 1;
+            }
+        }
+        if responses < self.min_agreeing_endpoints {
+            return Err(Error::CheckEvents(
+                abi_signature.into(),
+                address,
+                format!(
+                    "Only {responses} Ethereum RPC endpoints responded, fewer \
+                     than the {} required by the oracle quorum",
+                    self.min_agreeing_endpoints
+                ),
+            ));
+        }
+
+        let mut agreed_logs = vec![];
+        for contents in order {
+            let mut counts = counts
+                .remove(&contents)
+                .expect("Every returned log should have been counted");
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            // the number of copies of the log at least the minimum number
+            // of endpoints agree on
+            let agreed = counts[self.min_agreeing_endpoints - 1];
+            // the number of endpoints which returned more copies
+            let disputed = counts.iter().filter(|&&n| n > agreed).count();
+            let undisputed = responses - disputed;
+            if disputed > 0 && undisputed < self.min_agreeing_endpoints {
+                tracing::warn!(
+                    ?block,
+                    ?contents,
+                    "Ethereum RPC endpoints of the oracle quorum disagree on \
+                     an event log"
+                );
+                return Err(Error::CheckEvents(
+                    abi_signature.into(),
+                    address,
+                    "No quorum of Ethereum RPC endpoints agrees on an event \
+                     log"
+                    .into(),
+                ));
+            }
+            if disputed > 0 {
+                tracing::warn!(
+                    ?block,
+                    ?contents,
+                    "Dropping an event log returned by a minority of the \
+                     Ethereum RPC endpoints of the oracle quorum"
+                );
+            }
+            let (topics, data) = contents;
+            agreed_logs.extend(
+                std::iter::repeat(ethabi::RawLog { topics, data }).take(agreed),
+            );
+        }
+        Ok(agreed_logs)
+    }
+
+    async fn syncing(
+        &self,
+        last_processed_block: Option<&ethereum_structs::BlockHeight>,
+        backoff: Duration,
+        deadline: Instant,
+    ) -> Result<SyncStatus, Error> {
+        // every endpoint is given the same amount of time to respond
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut heights: Vec<Uint256> = vec![];
+        let mut syncing = false;
+        let mut last_error = None;
+        for (index, (url, client)) in self.clients.iter().enumerate() {
+            let deadline = Instant::now() + timeout;
+            match client
+                .syncing(last_processed_block, backoff, deadline)
+                .await
+            {
+                Ok(SyncStatus::AtHeight(height)) => {
+                    self.heights.borrow_mut()[index] = Some(height.clone());
+                    heights.push(height);
+                }
+                Ok(SyncStatus::Syncing) => syncing = true,
+                Err(error) => {
+                    tracing::debug!(
+                        %url,
+                        %error,
+                        "Failed to get the sync status of an Ethereum RPC \
+                         endpoint of the oracle quorum"
+                    );
+                    last_error = Some(error);
+                }
+            }
+        }
+        // the highest block at least the minimum number of endpoints
+        // have reached
+        heights.sort_unstable_by(|a, b| b.cmp(a));
+        match heights.into_iter().nth(self.min_agreeing_endpoints - 1) {
+            Some(height) => Ok(SyncStatus::AtHeight(height)),
+            None if syncing => Ok(SyncStatus::Syncing),
+            None => Err(last_error.unwrap_or(Error::Timeout)),
+        }
+    }
+
+    fn may_recover(&self, error: &Error) -> bool {
+        // endpoint failures are transient, as long as there are other
+        // endpoints to make up the quorum
+        (self.clients.len() > self.min_agreeing_endpoints
+            && is_endpoint_failure(error))
+            || self
+                .clients
+                .iter()
+                .all(|(_, client)| client.may_recover(error))
+    }
+}
+
+#[cfg(test)]
+mod test_quorum {
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::node::ledger::ethereum_oracle::test_tools::mock_web3_client::{
+        TestCmd, Web3Client, Web3Controller,
+    };
+
+    const EVENT: &str = "Event()";
+
+    fn log(data: u8) -> ethabi::RawLog {
+        ethabi::RawLog {
+            topics: vec![Hash::repeat_byte(1)],
+            data: vec![data],
+        }
+    }
+
+    /// Make the endpoint controlled by `controller` return the given log,
+    /// returning a receiver notified once the log has been returned.
+    fn emit(
+        controller: &Web3Controller,
+        log: ethabi::RawLog,
+    ) -> oneshot::Receiver<()> {
+        let (seen, recv) = oneshot::channel();
+        controller.apply_cmd(TestCmd::NewEvent {
+            event_type: EVENT.into(),
+            log,
+            height: 1,
+            seen,
+        });
+        recv
+    }
+
+    /// Test that event logs are only returned once enough endpoints agree
+    /// on them, and dropped once enough endpoints agree they do not exist.
+    #[tokio::test]
+    async fn test_quorum_of_endpoints() {
+        let clients: Vec<_> = (0..3).map(|_| Web3Client::setup().1).collect();
+        let controllers: Vec<_> =
+            clients.iter().map(Web3Client::controller).collect();
+        let client = QuorumClient::from_clients(
+            clients
+                .into_iter()
+                .enumerate()
+                .map(|(i, client)| (format!("http://eth-{i}"), client)),
+            2,
+        )
+        .unwrap();
+        let check_events = || {
+            client.check_events_in_block(1u64.into(), Address::default(), EVENT)
+        };
+
+        // two of the endpoints agree on an event, while a single
+        // endpoint forges another one
+        let _seen = [
+            emit(&controllers[0], log(1)),
+            emit(&controllers[1], log(1)),
+            emit(&controllers[2], log(2)),
+        ];
+        assert_eq!(check_events().await.unwrap(), vec![log(1)]);
+
+        // one endpoint is unresponsive, and the other two disagree
+        controllers[2].apply_cmd(TestCmd::Unresponsive);
+        let _seen = emit(&controllers[0], log(3));
+        let error = check_events().await.unwrap_err();
+        assert!(client.may_recover(&error));
+
+        // too few endpoints respond
+        controllers[1].apply_cmd(TestCmd::Unresponsive);
+        let error = check_events().await.unwrap_err();
+        assert!(client.may_recover(&error));
+    }
+
+    /// Test that the endpoints are considered synced up to the highest
+    /// block reached by at least the minimum number of them.
+    #[tokio::test]
+    async fn test_quorum_sync_status() {
+        let clients: Vec<_> = (0..3).map(|_| Web3Client::setup().1).collect();
+        for (client, height) in clients.iter().zip([5u32, 7, 9]) {
+            client
+                .controller()
+                .apply_cmd(TestCmd::NewHeight(Uint256::from(height)));
+        }
+        let client = QuorumClient::from_clients(
+            clients
+                .into_iter()
+                .enumerate()
+                .map(|(i, client)| (format!("http://eth-{i}"), client)),
+            2,
+        )
+        .unwrap();
+        let status = client
+            .syncing(None, Duration::from_secs(1), Instant::now())
+            .await
+            .unwrap();
+        assert!(matches!(
+            status,
+            SyncStatus::AtHeight(height) if height == Uint256::from(7u32)
+        ));
+    }
+
+    /// Test that the endpoints lagging behind a block abstain from
+    /// agreeing on its logs, instead of outvoting the endpoints which
+    /// returned them.
+    #[tokio::test]
+    async fn test_lagging_endpoints_abstain() {
+        let clients: Vec<_> = (0..3).map(|_| Web3Client::setup().1).collect();
+        let controllers: Vec<_> =
+            clients.iter().map(Web3Client::controller).collect();
+        for (controller, height) in controllers.iter().zip([1u32, 0, 0]) {
+            controller.apply_cmd(TestCmd::NewHeight(Uint256::from(height)));
+        }
+        let client = QuorumClient::from_clients(
+            clients
+                .into_iter()
+                .enumerate()
+                .map(|(i, client)| (format!("http://eth-{i}"), client)),
+            2,
+        )
+        .unwrap();
+        let syncing =
+            || client.syncing(None, Duration::from_secs(1), Instant::now());
+        let check_events = || {
+            client.check_events_in_block(1u64.into(), Address::default(), EVENT)
+        };
+
+        // only a single endpoint reached the block, so the event is
+        // re-checked later rather than dropped
+        syncing().await.unwrap();
+        let _seen = emit(&controllers[0], log(1));
+        let error = check_events().await.unwrap_err();
+        assert!(client.may_recover(&error));
+
+        // another endpoint catches up
+        controllers[1].apply_cmd(TestCmd::NewHeight(Uint256::from(1u32)));
+        syncing().await.unwrap();
+        let _seen = [
+            emit(&controllers[0], log(1)),
+            emit(&controllers[1], log(1)),
+        ];
+        assert_eq!(check_events().await.unwrap(), vec![log(1)]);
+    }
+
+    /// Test that an invalid minimum number of agreeing endpoints is
+    /// rejected.
+    #[test]
+    fn test_invalid_quorum() {
+        let clients = || {
+            (0..2).map(|i| (format!("http://eth-{i}"), Web3Client::setup().1))
+        };
+        assert!(matches!(
+            QuorumClient::from_clients(clients(), 0),
+            Err(Error::InvalidQuorum(0, 2))
+        ));
+        assert!(matches!(
+            QuorumClient::from_clients(clients(), 3),
+            Err(Error::InvalidQuorum(3, 2))
+        ));
+        assert!(QuorumClient::from_clients(clients(), 2).is_ok());
+    }
+}
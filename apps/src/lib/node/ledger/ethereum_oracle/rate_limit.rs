@@ -0,0 +1,255 @@
+//! Client-side rate limiting of Ethereum RPC calls.
+//!
+//! Requests sent to an endpoint are spaced out, such that no more than a
+//! configured number of them are sent per second. Requests which are
+//! nonetheless rejected by the endpoint, for exceeding its request cap,
+//! are retried with an exponential backoff.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::num::NonZeroU32;
+
+use async_trait::async_trait;
+use ethabi::Address;
+use namada::core::types::ethereum_structs;
+use namada::types::control_flow::time::{self, Duration, Instant};
+use namada_sdk::queries::{EthRpcThrottleMetrics, NodeMetrics};
+
+use super::{Error, RpcClient, SyncStatus};
+
+/// The initial amount of time to wait before retrying a request rejected by
+/// a rate limited endpoint.
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The maximum amount of time to wait before retrying a request rejected by
+/// a rate limited endpoint.
+const MAX_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Rate limits to observe when talking to an Ethereum RPC endpoint.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimits {
+    /// The maximum number of requests sent per second. If [`None`],
+    /// requests are never throttled on the client side.
+    pub max_requests_per_sec: Option<NonZeroU32>,
+    /// The maximum number of times a request rejected by a rate limited
+    /// endpoint is retried.
+    pub max_retries: u32,
+}
+
+/// [`RpcClient`] which observes the [`RateLimits`] of the endpoint it is
+/// connected to.
+pub struct RateLimitedClient<C> {
+    /// The inner client
+    client: C,
+    /// The rate limits of the endpoint
+    limits: RateLimits,
+    /// The earliest instant at which the next request may be sent
+    next_request_at: Cell<Option<Instant>>,
+    /// The node-local metrics in which the calls affected by rate
+    /// limiting are counted
+    metrics: NodeMetrics,
+}
+
+impl<C: RpcClient> RateLimitedClient<C> {
+    /// Wrap `client` in a new [`RateLimitedClient`], counting the calls
+    /// affected by rate limiting in the given `metrics`.
+    pub fn new(client: C, limits: RateLimits, metrics: NodeMetrics) -> Self {
+        Self {
+            client,
+            limits,
+            next_request_at: Cell::new(None),
+            metrics,
+        }
+    }
+
+    /// Return the counters of the calls affected by rate limiting.
+    pub fn metrics(&self) -> EthRpcThrottleMetrics {
+        self.metrics.report().eth_rpc_throttle
+    }
+
+    /// Update the counters of the calls affected by rate limiting, and
+    /// return their new values.
+    fn update_metrics<F>(&self, update: F) -> EthRpcThrottleMetrics
+    where
+        F: FnOnce(&mut EthRpcThrottleMetrics),
+    {
+        let mut throttle = EthRpcThrottleMetrics::default();
+        self.metrics.update(|metrics| {
+            update(&mut metrics.eth_rpc_throttle);
+            throttle = metrics.eth_rpc_throttle;
+        });
+        throttle
+    }
+
+    /// Wait until the next request may be sent to the endpoint, according
+    /// to its maximum number of requests per second.
+    async fn throttle(&self) {
+        let Some(max_requests_per_sec) = self.limits.max_requests_per_sec
+        else {
+            return;
+        };
+        let interval = Duration::from_secs(1) / max_requests_per_sec.get();
+        let now = Instant::now();
+        let request_at = match self.next_request_at.get() {
+            Some(next_request_at) if next_request_at > now => next_request_at,
+            _ => now,
+        };
+        self.next_request_at.set(Some(request_at + interval));
+        if request_at > now {
+            let metrics =
+                self.update_metrics(|metrics| metrics.throttled_calls += 1);
+            tracing::debug!(
+                throttled_calls = metrics.throttled_calls,
+                "Throttling Ethereum RPC call"
+            );
+            time::sleep(request_at - now).await;
+        }
+    }
+
+    /// Send a request to the endpoint, retrying it with an exponential
+    /// backoff if it gets rejected for exceeding the request cap of the
+    /// endpoint.
+    async fn with_backoff<'this, T, F, Fut>(
+        &'this self,
+        request: F,
+    ) -> Result<T, Error>
+    where
+        F: Fn(&'this C) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut backoff = RATE_LIMIT_BACKOFF;
+        let mut retries = 0;
+        loop {
+            self.throttle().await;
+            match request(&self.client).await {
+                Err(error) if is_rate_limited(&error) => {
+                    let metrics = self
+                        .update_metrics(|metrics| metrics.rejected_calls += 1);
+                    if retries == self.limits.max_retries {
+                        return Err(error);
+                    }
+                    tracing::warn!(
+                        %error,
+                        ?backoff,
+                        rejected_calls = metrics.rejected_calls,
+                        "Ethereum RPC endpoint is rate limiting the oracle, \
+                         backing off"
+                    );
+                    time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RATE_LIMIT_BACKOFF);
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Check if the given error was caused by exceeding the request cap of an
+/// Ethereum RPC endpoint.
+#[inline]
+fn is_rate_limited(error: &Error) -> bool {
+    matches!(error, Error::RateLimited(_, _, _))
+}
+
+#[async_trait(?Send)]
+impl<C: RpcClient> RpcClient for RateLimitedClient<C> {
+    type Log = C::Log;
+
+    #[inline]
+    fn new_client(rpc_url: &str) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(
+            C::new_client(rpc_url),
+            RateLimits::default(),
+            NodeMetrics::default(),
+        )
+    }
+
+    async fn check_events_in_block(
+        &self,
+        block: ethereum_structs::BlockHeight,
+        address: Address,
+        abi_signature: &str,
+    ) -> Result<Vec<Self::Log>, Error> {
+        self.with_backoff(|client| {
+            client.check_events_in_block(block.clone(), address, abi_signature)
+        })
+        .await
+    }
+
+    async fn syncing(
+        &self,
+        last_processed_block: Option<&ethereum_structs::BlockHeight>,
+        backoff: Duration,
+        deadline: Instant,
+    ) -> Result<SyncStatus, Error> {
+        self.throttle().await;
+        self.client
+            .syncing(last_processed_block, backoff, deadline)
+            .await
+    }
+
+    #[inline]
+    fn may_recover(&self, error: &Error) -> bool {
+        is_rate_limited(error) || self.client.may_recover(error)
+    }
+}
+
+#[cfg(test)]
+mod test_rate_limit {
+    use super::*;
+    use crate::node::ledger::ethereum_oracle::test_tools::mock_web3_client::Web3Client;
+
+    /// Test that calls exceeding the maximum number of requests per second
+    /// get throttled.
+    #[tokio::test]
+    async fn test_calls_are_throttled() {
+        let (_blocks, client) = Web3Client::setup();
+        let client = RateLimitedClient::new(
+            client,
+            RateLimits {
+                max_requests_per_sec: NonZeroU32::new(20),
+                max_retries: 0,
+            },
+            NodeMetrics::default(),
+        );
+        let start = Instant::now();
+        for _ in 0..3 {
+            client
+                .check_events_in_block(
+                    1u64.into(),
+                    Address::default(),
+                    "Event()",
+                )
+                .await
+                .expect("Test failed");
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(
+            client.metrics(),
+            EthRpcThrottleMetrics {
+                throttled_calls: 2,
+                rejected_calls: 0,
+            }
+        );
+    }
+
+    /// Test that rate limiting errors are told apart from other errors.
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(is_rate_limited(&Error::RateLimited(
+            "Event()".into(),
+            Address::default(),
+            "limit exceeded".into(),
+        )));
+        assert!(!is_rate_limited(&Error::CheckEvents(
+            "Event()".into(),
+            Address::default(),
+            "rate limit exceeded".into(),
+        )));
+        assert!(!is_rate_limited(&Error::Timeout));
+    }
+}
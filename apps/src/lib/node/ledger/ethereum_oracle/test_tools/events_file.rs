@@ -0,0 +1,231 @@
+//! Import of Ethereum events from a file, for test networks which can not
+//! reach an Ethereum node.
+//!
+//! Each line of the file is a JSON encoded [`SignedEvent`], holding a
+//! hex encoded, Borsh-serialized [`EthereumEvent`], along with a signature
+//! over its bytes, made by the key configured to authorize the file.
+
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
+use data_encoding::{HEXLOWER, HEXLOWER_PERMISSIVE};
+use namada::types::ethereum_events::EthereumEvent;
+use namada::types::key::{common, SigScheme};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::mpsc::Sender as BoundedSender;
+use tokio::sync::oneshot::{Receiver, Sender};
+
+use super::replay;
+use crate::node::ledger::ethereum_oracle as oracle;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Couldn't read the Ethereum events file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed Ethereum event at line {0}: {1}")]
+    Malformed(usize, String),
+    #[error("Invalid signature of the Ethereum event at line {0}")]
+    InvalidSignature(usize),
+}
+
+/// An Ethereum event, signed by the key authorizing an events file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedEvent {
+    /// The hex encoded, Borsh-serialized event.
+    pub event: String,
+    /// The signature over the Borsh-serialized event.
+    pub signature: common::Signature,
+}
+
+impl SignedEvent {
+    /// Sign the given `event` with `signing_key`.
+    pub fn new(event: &EthereumEvent, signing_key: &common::SecretKey) -> Self {
+        let bytes = event.serialize_to_vec();
+        let signature = common::SigScheme::sign(signing_key, &bytes);
+        Self {
+            event: HEXLOWER.encode(&bytes),
+            signature,
+        }
+    }
+
+    /// Check the signature of the event against `signer`, and decode it.
+    fn verify(
+        &self,
+        signer: &common::PublicKey,
+        line: usize,
+    ) -> Result<EthereumEvent, Error> {
+        let bytes = HEXLOWER_PERMISSIVE
+            .decode(self.event.as_bytes())
+            .map_err(|err| Error::Malformed(line, err.to_string()))?;
+        common::SigScheme::verify_signature(signer, &bytes, &self.signature)
+            .map_err(|_| Error::InvalidSignature(line))?;
+        EthereumEvent::try_from_slice(&bytes)
+            .map_err(|err| Error::Malformed(line, err.to_string()))
+    }
+}
+
+/// Parse a JSON encoded value from the given line, at `line_number`.
+/// Returns [`None`] for blank lines.
+pub fn parse_json_line<T: DeserializeOwned>(
+    line: &str,
+    line_number: usize,
+) -> Result<Option<T>, Error> {
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(line)
+        .map(Some)
+        .map_err(|err| Error::Malformed(line_number, err.to_string()))
+}
+
+/// Read the JSON encoded values on each line of the file at `path`, in
+/// order, along with their line numbers. Blank lines are skipped.
+pub fn read_json_lines<T: DeserializeOwned>(
+    path: &Path,
+) -> Result<Vec<(usize, T)>, Error> {
+    let file = std::fs::File::open(path)?;
+    let mut values = vec![];
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_number = index + 1;
+        if let Some(value) = parse_json_line(&line?, line_number)? {
+            values.push((line_number, value));
+        }
+    }
+    Ok(values)
+}
+
+/// Read the Ethereum events in the file at `path`, in order, checking that
+/// all of them have been signed by `signer`. Blank lines are skipped.
+pub fn read_events(
+    path: &Path,
+    signer: &common::PublicKey,
+) -> Result<Vec<EthereumEvent>, Error> {
+    read_json_lines::<SignedEvent>(path)?
+        .into_iter()
+        .map(|(line_number, signed)| signed.verify(signer, line_number))
+        .collect()
+}
+
+/// Feeds the Ethereum events in the file at `path` to `sender`, once the
+/// oracle has been configured, as the live oracle would. It shuts down if
+/// a signal is sent on the `abort_recv` channel. Accepts the receive-half
+/// of an oracle control channel (`control_recv`) that will be kept alive
+/// until shutdown. Fails if the events can't be imported from the file.
+pub async fn serve(
+    path: PathBuf,
+    signer: common::PublicKey,
+    sender: BoundedSender<EthereumEvent>,
+    control_recv: oracle::control::Receiver,
+    abort_recv: Receiver<Sender<()>>,
+) -> Result<(), Error> {
+    let events = read_events(&path, &signer)?;
+    tracing::info!(
+        ?path,
+        num_events = events.len(),
+        "Ethereum events file importer is starting"
+    );
+    let feed = async move {
+        for event in events {
+            if sender.send(event).await.is_err() {
+                tracing::info!(
+                    "Ethereum events file importer can not send events to the \
+                     ledger; the receiver has hung up"
+                );
+                return false;
+            }
+        }
+        tracing::info!("Imported all the Ethereum events from file");
+        true
+    };
+    replay::serve(
+        "Ethereum events file importer",
+        control_recv,
+        feed,
+        abort_recv,
+    )
+    .await;
+    tracing::info!(?path, "Stopping importing Ethereum events from file");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_events_file {
+    use std::io::Write;
+
+    use namada::types::address;
+    use namada::types::ethereum_events::testing::arbitrary_single_transfer;
+    use namada::types::key::testing::{keypair_1, keypair_2};
+    use namada::types::key::RefTo;
+
+    use super::*;
+
+    /// Write the given lines to a new temporary file.
+    fn write_lines(lines: &[String]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for line in lines {
+            writeln!(file, "{line}").unwrap();
+        }
+        file
+    }
+
+    /// Test that signed events are imported in order, and that events
+    /// signed by some other key are rejected.
+    #[test]
+    fn test_read_signed_events() {
+        let events: Vec<_> = (0u64..3)
+            .map(|nonce| {
+                arbitrary_single_transfer(nonce.into(), address::nam())
+            })
+            .collect();
+        let lines: Vec<_> = events
+            .iter()
+            .map(|event| {
+                let signed = SignedEvent::new(event, &keypair_1());
+                serde_json::to_string(&signed).unwrap()
+            })
+            .collect();
+        let file = write_lines(&lines);
+        let signer = keypair_1().ref_to();
+        let read = read_events(file.path(), &signer).expect("Test failed");
+        assert_eq!(read, events);
+
+        let other_signer = keypair_2().ref_to();
+        assert!(matches!(
+            read_events(file.path(), &other_signer),
+            Err(Error::InvalidSignature(1))
+        ));
+    }
+
+    /// Test that malformed lines are rejected.
+    #[test]
+    fn test_read_malformed_events() {
+        let file = write_lines(&["not json".to_string()]);
+        let signer = keypair_1().ref_to();
+        assert!(matches!(
+            read_events(file.path(), &signer),
+            Err(Error::Malformed(1, _))
+        ));
+    }
+
+    /// Test that the import fails if the events file can't be read.
+    #[tokio::test]
+    async fn test_serve_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let (_control_sender, control_recv) = oracle::control::channel();
+        let (_abort_send, abort_recv) = tokio::sync::oneshot::channel();
+        let result = serve(
+            dir.path().join("missing.jsonl"),
+            keypair_1().ref_to(),
+            sender,
+            control_recv,
+            abort_recv,
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod events_endpoint;
+pub mod events_file;
+pub mod replay;
+pub mod simulation;
 
 #[cfg(test)]
 pub mod event_log {
@@ -0,0 +1,73 @@
+//! Replay of Ethereum events to the ledger in place of the Ethereum oracle,
+//! shared by the oracle modes which do not run against an Ethereum node.
+
+use std::future::Future;
+
+use tokio::sync::oneshot::{Receiver, Sender};
+
+use crate::node::ledger::ethereum_oracle as oracle;
+use crate::node::ledger::oracle::control::Command;
+
+/// Runs `feed` once the oracle has been configured, as the live oracle
+/// would. The `feed` resolves to whether the replay should keep running
+/// once it is done, i.e. `false` once the ledger has hung up. Until then,
+/// the commands received on `control_recv` are ignored, as no real oracle
+/// is running, and the control channel is kept alive. It shuts down if a
+/// signal is sent on the `abort_recv` channel. The `name` of the replay is
+/// used in its logs.
+pub async fn serve<F>(
+    name: &'static str,
+    mut control_recv: oracle::control::Receiver,
+    feed: F,
+    abort_recv: Receiver<Sender<()>>,
+) where
+    F: Future<Output = bool>,
+{
+    let replay = async move {
+        loop {
+            match control_recv.recv().await {
+                Some(Command::UpdateConfig(_)) => break,
+                Some(command) => tracing::debug!(
+                    ?command,
+                    "{name} received an oracle command before being \
+                     configured, which will be ignored"
+                ),
+                None => return,
+            }
+        }
+        let ignore_commands = async move {
+            while let Some(command) = control_recv.recv().await {
+                tracing::debug!(
+                    ?command,
+                    "{name} received an oracle command which will be ignored \
+                     since we are not running a real oracle"
+                )
+            }
+        };
+        tokio::pin!(ignore_commands);
+        let keep_running = tokio::select! {
+            keep_running = feed => keep_running,
+            _ = &mut ignore_commands => false,
+        };
+        if keep_running {
+            ignore_commands.await;
+        }
+    };
+    tokio::select! {
+        _ = replay => {},
+        abort = abort_recv => match abort {
+            Ok(abort_resp_send) => {
+                if abort_resp_send.send(()).is_err() {
+                    tracing::warn!(
+                        "Received signal to abort but failed to respond, \
+                         will abort now"
+                    )
+                }
+            }
+            Err(_) => tracing::warn!(
+                "Channel for receiving signal to abort was closed \
+                 abruptly, will abort now"
+            ),
+        },
+    }
+}
@@ -0,0 +1,327 @@
+//! Simulation of the Ethereum oracle, for local test networks and e2e tests
+//! which do not run an Ethereum node.
+//!
+//! The simulated oracle replays the Ethereum events of a fixture file, in
+//! the order of the Ethereum blocks they were emitted in, and then feeds any
+//! event submitted to its control socket. Each line of the fixture is a JSON
+//! encoded [`FixtureEvent`], holding an [`EthereumEvent`] along with the
+//! Ethereum block it was emitted in, such that fixtures can be written by
+//! hand. The control socket is a TCP socket, accepting one [`FixtureEvent`]
+//! per line, as well.
+//!
+//! As opposed to the live oracle, the Ethereum blocks of the simulated
+//! events are reported as processed once their events have been fed to the
+//! ledger.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use namada::types::ethereum_events::EthereumEvent;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::Sender as BoundedSender;
+use tokio::sync::oneshot::{Receiver, Sender};
+
+use super::{events_file, replay};
+use crate::node::ledger::ethereum_oracle as oracle;
+use crate::node::ledger::oracle::last_processed_block;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Couldn't read the Ethereum events fixture: {0}")]
+    Fixture(#[from] events_file::Error),
+    #[error("Couldn't listen on the control socket: {0}")]
+    Socket(std::io::Error),
+}
+
+/// An Ethereum event, along with the Ethereum block it was emitted in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureEvent {
+    /// The height of the Ethereum block the event was emitted in.
+    pub block: u64,
+    /// The event.
+    pub event: EthereumEvent,
+}
+
+/// Read the Ethereum events of the fixture file at `path`, holding a
+/// JSON encoded [`FixtureEvent`] per line. The events are returned in the
+/// order of their Ethereum blocks, and in the order of the fixture within
+/// a block.
+pub fn read_fixture(path: &Path) -> Result<Vec<(u64, EthereumEvent)>, Error> {
+    let mut events: Vec<_> =
+        events_file::read_json_lines::<FixtureEvent>(path)?
+            .into_iter()
+            .map(|(_, FixtureEvent { block, event })| (block, event))
+            .collect();
+    events.sort_by_key(|(block, _)| *block);
+    Ok(events)
+}
+
+/// Feeds simulated Ethereum events to `sender`, once the oracle has been
+/// configured, as the live oracle would. The events of the `fixture` file
+/// are fed first, followed by the events submitted to the control
+/// `socket`, if any. The blocks of the events fed are published on
+/// `last_processed_block`. It shuts down if a signal is sent on the
+/// `abort_recv` channel. Accepts the receive-half of an oracle control
+/// channel (`control_recv`) that will be kept alive until shutdown. Fails
+/// if the fixture can't be read, or the control socket can't be bound.
+pub async fn serve(
+    fixture: Option<PathBuf>,
+    socket: Option<String>,
+    sender: BoundedSender<EthereumEvent>,
+    control_recv: oracle::control::Receiver,
+    last_processed_block: last_processed_block::Sender,
+    abort_recv: Receiver<Sender<()>>,
+) -> Result<(), Error> {
+    let events = fixture
+        .as_deref()
+        .map(read_fixture)
+        .transpose()?
+        .unwrap_or_default();
+    let listener = match socket.as_deref().map(bind_socket) {
+        Some(listener) => Some(listener.await.map_err(Error::Socket)?),
+        None => None,
+    };
+    tracing::info!(
+        ?fixture,
+        ?socket,
+        num_events = events.len(),
+        "Simulated Ethereum oracle is starting"
+    );
+    let feed = async move {
+        for (block, event) in events {
+            if !feed_event(&sender, &last_processed_block, block, event).await {
+                return false;
+            }
+        }
+        tracing::info!("Replayed all the Ethereum events of the fixture");
+        match listener {
+            Some(listener) => {
+                accept_events(listener, &sender, &last_processed_block).await;
+                false
+            }
+            None => true,
+        }
+    };
+    replay::serve("Simulated Ethereum oracle", control_recv, feed, abort_recv)
+        .await;
+    tracing::info!("Stopping the simulated Ethereum oracle");
+    Ok(())
+}
+
+/// Listen on the control socket at the given address.
+async fn bind_socket(listen_addr: &str) -> std::io::Result<TcpListener> {
+    let listen_addr: SocketAddr = listen_addr.parse().map_err(|err| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, err)
+    })?;
+    TcpListener::bind(listen_addr).await
+}
+
+/// Feed the given `event`, emitted in the Ethereum `block`, to the ledger.
+/// Returns a boolean indicating whether the event was sent. If false is
+/// returned, the receiver has hung up.
+async fn feed_event(
+    sender: &BoundedSender<EthereumEvent>,
+    last_processed_block: &last_processed_block::Sender,
+    block: u64,
+    event: EthereumEvent,
+) -> bool {
+    tracing::debug!(block, ?event, "Feeding a simulated Ethereum event");
+    if sender.send(event).await.is_err() {
+        tracing::info!(
+            "Simulated Ethereum oracle can not send events to the ledger; the \
+             receiver has hung up"
+        );
+        return false;
+    }
+    last_processed_block.send_if_modified(|last| {
+        let block = block.into();
+        let modified = last.as_ref() < Some(&block);
+        if modified {
+            *last = Some(block);
+        }
+        modified
+    });
+    true
+}
+
+/// Feed the events submitted to the control socket, one connection at a
+/// time, until the ledger hangs up.
+async fn accept_events(
+    listener: TcpListener,
+    sender: &BoundedSender<EthereumEvent>,
+    last_processed_block: &last_processed_block::Sender,
+) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    "Failed to accept a connection on the control socket of \
+                     the simulated Ethereum oracle"
+                );
+                continue;
+            }
+        };
+        if !handle_connection(stream, sender, last_processed_block).await {
+            return;
+        }
+    }
+}
+
+/// Feed the events submitted on a connection to the control socket.
+/// Malformed events are skipped. Returns false if the ledger has hung up.
+async fn handle_connection(
+    stream: TcpStream,
+    sender: &BoundedSender<EthereumEvent>,
+    last_processed_block: &last_processed_block::Sender,
+) -> bool {
+    let mut lines = BufReader::new(stream).lines();
+    let mut line_number = 0;
+    loop {
+        line_number += 1;
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => return true,
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    "Failed to read from the control socket of the simulated \
+                     Ethereum oracle"
+                );
+                return true;
+            }
+        };
+        match events_file::parse_json_line::<FixtureEvent>(&line, line_number)
+        {
+            Ok(None) => {}
+            Ok(Some(FixtureEvent { block, event })) => {
+                if !feed_event(sender, last_processed_block, block, event).await
+                {
+                    return false;
+                }
+            }
+            Err(error) => tracing::warn!(
+                %error,
+                "Skipping a malformed event submitted to the simulated \
+                 Ethereum oracle"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_simulation {
+    use namada::types::address;
+    use namada::types::ethereum_events::testing::arbitrary_single_transfer;
+    use tokio::io::AsyncWriteExt;
+    use tokio::sync::{mpsc, oneshot};
+
+    use super::*;
+    use crate::node::ledger::oracle::control::Command;
+
+    /// Encode the given events as the lines of a fixture.
+    fn to_fixture(events: &[(u64, &EthereumEvent)]) -> String {
+        events
+            .iter()
+            .map(|&(block, event)| {
+                let event = FixtureEvent {
+                    block,
+                    event: event.clone(),
+                };
+                serde_json::to_string(&event).unwrap() + "\n"
+            })
+            .collect()
+    }
+
+    /// Test that the events of a fixture are read in the order of their
+    /// blocks, and that malformed fixtures are rejected.
+    #[test]
+    fn test_read_fixture() {
+        let events: Vec<_> = (0u64..3)
+            .map(|nonce| {
+                arbitrary_single_transfer(nonce.into(), address::nam())
+            })
+            .collect();
+        let fixture =
+            to_fixture(&[(7, &events[1]), (5, &events[0]), (7, &events[2])]);
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), fixture).unwrap();
+        let read = read_fixture(file.path()).expect("Test failed");
+        assert_eq!(
+            read,
+            vec![
+                (5, events[0].clone()),
+                (7, events[1].clone()),
+                (7, events[2].clone()),
+            ]
+        );
+
+        std::fs::write(file.path(), b"[{\"block\": 1, \"event\": \"zz\"}]")
+            .unwrap();
+        assert!(matches!(
+            read_fixture(file.path()),
+            Err(Error::Fixture(events_file::Error::Malformed(1, _)))
+        ));
+    }
+
+    /// Test that the simulated oracle replays the fixture once configured,
+    /// followed by the events submitted to its control socket.
+    #[tokio::test]
+    async fn test_serve_fixture_and_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture_path = dir.path().join("fixture.json");
+        let socket_addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .unwrap();
+        let first = arbitrary_single_transfer(0u64.into(), address::nam());
+        let second = arbitrary_single_transfer(1u64.into(), address::nam());
+        std::fs::write(
+            &fixture_path,
+            to_fixture(&[(3, &first)]),
+        )
+        .unwrap();
+
+        let (eth_sender, mut eth_receiver) = mpsc::channel(10);
+        let (mut control_sender, control_receiver) = oracle::control::channel();
+        let (blocks_sender, mut blocks_receiver) =
+            last_processed_block::channel();
+        let (abort_send, abort_recv) = oneshot::channel();
+        let handle = tokio::spawn(serve(
+            Some(fixture_path),
+            Some(socket_addr.to_string()),
+            eth_sender,
+            control_receiver,
+            blocks_sender,
+            abort_recv,
+        ));
+        control_sender
+            .try_send(Command::UpdateConfig(Default::default()))
+            .unwrap();
+
+        assert_eq!(eth_receiver.recv().await, Some(first));
+        let mut stream = loop {
+            match TcpStream::connect(socket_addr).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::task::yield_now().await,
+            }
+        };
+        let line = to_fixture(&[(4, &second)]);
+        stream
+            .write_all(format!("not json\n{line}").as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(eth_receiver.recv().await, Some(second));
+        while *blocks_receiver.borrow() != Some(4u64.into()) {
+            blocks_receiver.changed().await.unwrap();
+        }
+
+        let (abort_resp_send, abort_resp_recv) = oneshot::channel();
+        abort_send.send(abort_resp_send).unwrap();
+        abort_resp_recv.await.unwrap();
+        handle.await.unwrap();
+    }
+}
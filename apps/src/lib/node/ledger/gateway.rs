@@ -0,0 +1,325 @@
+//! An HTTP gateway to the queries of the ledger.
+//!
+//! The queries router of the shell is normally only reachable with an ABCI
+//! query, which requires an RPC client of CometBFT. The gateway serves the
+//! same queries as JSON over plain HTTP, at the paths of the router, e.g.
+//! `GET /shell/epoch`. The request data of a query is given hex-encoded in
+//! the `data` parameter of a `GET` request, or as the raw body of a `POST`
+//! request, and the block height to query in the `height` parameter. As with
+//! the `abci_query` method of CometBFT, the borsh-encoded value of the
+//! response is base64-encoded. Queries that require proofs are only served
+//! over ABCI.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use data_encoding::HEXLOWER_PERMISSIVE;
+use futures::future::poll_fn;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use tower::{Service, ServiceBuilder};
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::reply::{self, Reply};
+use warp::Filter;
+
+use crate::facade::tendermint::v0_37::abci::{request, response};
+use crate::facade::tower_abci::BoxError;
+use crate::node::ledger::shims::abcipp_shim::QueryHandle;
+
+/// The maximum number of queries waiting to be forwarded to the shell.
+const MAX_PENDING_QUERIES: usize = 100;
+
+/// The maximum number of queries forwarded to the shell per second. The
+/// shell handles the queries in between the requests of consensus, so these
+/// are rate-limited like the ABCI queries.
+const MAX_QUERIES_PER_SEC: u64 = 50;
+
+/// The maximum size in bytes of the body of a `POST` request.
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The parameters of a query
+#[derive(Debug, Default, Deserialize)]
+struct QueryParams {
+    /// The hex-encoded request data, ignored in `POST` requests
+    data: Option<String>,
+    /// The block height to query, where `0` or none is the last committed
+    /// height
+    height: Option<u64>,
+}
+
+/// The JSON response of a query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryResponse {
+    /// The response code of the query, where `0` means success
+    pub code: u32,
+    /// The info of the response, describing the error of a failed query
+    pub info: String,
+    /// The block height the query was served at
+    pub height: u64,
+    /// The base64-encoded value of the response
+    pub value: String,
+}
+
+impl QueryResponse {
+    /// A response to a query that wasn't forwarded to the shell.
+    fn error(info: String) -> Self {
+        Self {
+            code: 1,
+            info,
+            height: 0,
+            value: String::new(),
+        }
+    }
+}
+
+/// Serve the queries of the shell as JSON on the given address, until a
+/// message is received on `abort_recv`. The handle to the shell's queries is
+/// dropped on return, which the shell waits for to shut down.
+pub async fn serve(
+    addr: SocketAddr,
+    queries: QueryHandle,
+    abort_recv: tokio::sync::oneshot::Receiver<()>,
+) {
+    let queries = ServiceBuilder::new()
+        .load_shed()
+        .buffer(MAX_PENDING_QUERIES)
+        .rate_limit(MAX_QUERIES_PER_SEC, Duration::from_secs(1))
+        .service(queries);
+    let abort = abort_recv.map(|_| ()).shared();
+    match warp::serve(routes(queries))
+        .try_bind_with_graceful_shutdown(addr, abort.clone())
+    {
+        Ok((addr, server)) => {
+            tracing::info!("Serving the queries gateway at {addr}");
+            server.await;
+            tracing::info!("The queries gateway is no longer running.");
+        }
+        Err(err) => {
+            tracing::error!(
+                "Failed to serve the queries gateway at {addr}: {err}"
+            );
+            // the ledger keeps running without the gateway
+            abort.await
+        }
+    }
+}
+
+/// The routes of the gateway, forwarding queries to the given service.
+fn routes<S>(
+    queries: S,
+) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone
+where
+    S: Service<request::Query, Response = response::Query, Error = BoxError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+{
+    // NB: the filters must be `Sync`, unlike the buffered service
+    let queries = Arc::new(Mutex::new(queries));
+    let with_queries = warp::any().map(move || queries.lock().unwrap().clone());
+    let get = warp::get()
+        .and(warp::path::tail())
+        .and(warp::query::<QueryParams>())
+        .and(with_queries.clone())
+        .and_then(|path: warp::path::Tail, params: QueryParams, queries| {
+            let data = match params.data {
+                Some(data) => HEXLOWER_PERMISSIVE
+                    .decode(data.as_bytes())
+                    .map_err(|err| format!("Invalid hex-encoded data: {err}")),
+                None => Ok(vec![]),
+            };
+            query(queries, path.as_str(), data, params.height)
+        });
+    let post = warp::post()
+        .and(warp::path::tail())
+        .and(warp::query::<QueryParams>())
+        .and(warp::body::content_length_limit(MAX_BODY_BYTES))
+        .and(warp::body::bytes())
+        .and(with_queries)
+        .and_then(
+            |path: warp::path::Tail,
+             params: QueryParams,
+             data: Bytes,
+             queries| {
+                query(queries, path.as_str(), Ok(data.to_vec()), params.height)
+            },
+        );
+    get.or(post)
+}
+
+/// Forward a query to the shell and reply with its JSON response.
+fn query<S>(
+    mut queries: S,
+    path: &str,
+    data: Result<Vec<u8>, String>,
+    height: Option<u64>,
+) -> impl Future<Output = Result<reply::Response, Infallible>>
+where
+    S: Service<request::Query, Response = response::Query, Error = BoxError>,
+{
+    let path = format!("/{path}");
+    async move {
+        let data = match data {
+            Ok(data) => data,
+            Err(info) => return Ok(bad_request(info)),
+        };
+        let height = height.unwrap_or_default();
+        let Ok(height) = height.try_into() else {
+            return Ok(bad_request(format!("Invalid height {height}.")));
+        };
+        let req = request::Query {
+            data: data.into(),
+            path,
+            height,
+            prove: false,
+        };
+        let result = match poll_fn(|cx| queries.poll_ready(cx)).await {
+            Ok(()) => queries.call(req).await,
+            Err(err) => Err(err),
+        };
+        let resp = match result {
+            Ok(resp) => resp,
+            Err(err) => {
+                return Ok(reply::with_status(
+                    reply::json(&QueryResponse::error(format!(
+                        "The query couldn't be served: {err}"
+                    ))),
+                    StatusCode::SERVICE_UNAVAILABLE,
+                )
+                .into_response());
+            }
+        };
+        let resp = QueryResponse {
+            code: resp.code.value(),
+            info: resp.info,
+            height: resp.height.value(),
+            value: base64::encode(&resp.value),
+        };
+        let status = if resp.code == 0 {
+            StatusCode::OK
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        Ok(reply::with_status(reply::json(&resp), status).into_response())
+    }
+}
+
+/// Reply to an invalid query.
+fn bad_request(info: String) -> reply::Response {
+    reply::with_status(
+        reply::json(&QueryResponse::error(info)),
+        StatusCode::BAD_REQUEST,
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod test_gateway {
+    use std::future::Ready;
+    use std::task::{Context, Poll};
+
+    use super::*;
+
+    /// A service echoing the path and data of queries, failing queries
+    /// of unknown paths.
+    #[derive(Clone)]
+    struct EchoQueries;
+
+    impl Service<request::Query> for EchoQueries {
+        type Error = BoxError;
+        type Future = Ready<Result<response::Query, BoxError>>;
+        type Response = response::Query;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: request::Query) -> Self::Future {
+            let resp = if req.path == "/shell/echo" {
+                response::Query {
+                    value: req.data,
+                    height: req.height,
+                    ..Default::default()
+                }
+            } else {
+                response::Query {
+                    code: 1.into(),
+                    info: format!("Unknown path {}", req.path),
+                    ..Default::default()
+                }
+            };
+            std::future::ready(Ok(resp))
+        }
+    }
+
+    async fn reply(
+        req: warp::test::RequestBuilder,
+    ) -> (StatusCode, QueryResponse) {
+        let resp = req.reply(&routes(EchoQueries)).await;
+        let status = resp.status();
+        (status, serde_json::from_slice(resp.body()).unwrap())
+    }
+
+    /// Test that queries are forwarded with their path, data and height,
+    /// and that their responses are encoded as JSON.
+    #[tokio::test]
+    async fn test_gateway_queries() {
+        let (status, resp) = reply(
+            warp::test::request()
+                .method("GET")
+                .path("/shell/echo?data=0a0b&height=3"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(resp.code, 0);
+        assert_eq!(resp.height, 3);
+        assert_eq!(resp.value, base64::encode([0x0a, 0x0b]));
+
+        let (status, resp) = reply(
+            warp::test::request()
+                .method("POST")
+                .path("/shell/echo")
+                .body([1, 2, 3]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(resp.height, 0);
+        assert_eq!(resp.value, base64::encode([1, 2, 3]));
+
+        let (status, resp) =
+            reply(warp::test::request().method("GET").path("/shell/unknown"))
+                .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(resp.code, 1);
+        assert_eq!(resp.info, "Unknown path /shell/unknown");
+
+        let (status, resp) = reply(
+            warp::test::request()
+                .method("GET")
+                .path("/shell/echo?data=xyz"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(resp.info.starts_with("Invalid hex-encoded data"));
+    }
+
+    /// Test that the body of `POST` requests is limited in size.
+    #[tokio::test]
+    async fn test_gateway_body_limit() {
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/shell/echo")
+            .body(vec![0; MAX_BODY_BYTES as usize + 1])
+            .reply(&routes(EchoQueries))
+            .await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
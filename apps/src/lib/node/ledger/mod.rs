@@ -1,22 +1,27 @@
 mod abortable;
+mod bench;
 mod broadcaster;
+mod debug_bundle;
 pub mod ethereum_oracle;
+mod gateway;
+mod probes;
 pub mod shell;
 pub mod shims;
+mod startup;
 pub mod storage;
+mod supervisor;
 pub mod tendermint_node;
 
-use std::convert::TryInto;
+use std::convert::{Infallible, TryInto};
+use std::future::Future;
 use std::net::SocketAddr;
 use std::path::PathBuf;
-use std::str::FromStr;
 use std::thread;
 
 use byte_unit::Byte;
 use futures::future::TryFutureExt;
-use namada::core::ledger::governance::storage::keys as governance_storage;
 use namada::eth_bridge::ethers::providers::{Http, Provider};
-use namada::types::storage::Key;
+use namada_sdk::queries::{CrashReports, NodeMetrics};
 use namada_sdk::tendermint::abci::request::CheckTxKind;
 use once_cell::unsync::Lazy;
 use sysinfo::{RefreshKind, System, SystemExt};
@@ -28,6 +33,7 @@ use self::abortable::AbortableSpawner;
 use self::ethereum_oracle::last_processed_block;
 use self::shell::EthereumOracleChannels;
 use self::shims::abcipp_shim::AbciService;
+use self::startup::StartupProgress;
 use crate::cli::args;
 use crate::config::utils::{convert_tm_addr_to_socket_addr, num_of_threads};
 use crate::config::{ethereum_bridge, TendermintMode};
@@ -63,35 +69,6 @@ const ENV_VAR_RAYON_THREADS: &str = "NAMADA_RAYON_THREADS";
 //     }
 //```
 impl Shell {
-    fn load_proposals(&mut self) {
-        let proposals_key = governance_storage::get_commiting_proposals_prefix(
-            self.wl_storage.storage.last_epoch.0,
-        );
-
-        let (proposal_iter, _) =
-            self.wl_storage.storage.iter_prefix(&proposals_key);
-        for (key, _, _) in proposal_iter {
-            let key =
-                Key::from_str(key.as_str()).expect("Key should be parsable");
-            if governance_storage::get_commit_proposal_epoch(&key).unwrap()
-                != self.wl_storage.storage.last_epoch.0
-            {
-                // NOTE: `iter_prefix` iterate over the matching prefix. In this
-                // case  a proposal with grace_epoch 110 will be
-                // matched by prefixes  1, 11 and 110. Thus we
-                // have to skip to the next iteration of
-                //  the cycle for all the prefixes that don't actually match
-                //  the desired epoch.
-                continue;
-            }
-
-            let proposal_id = governance_storage::get_commit_proposal_id(&key);
-            if let Some(id) = proposal_id {
-                self.proposal_data.insert(id);
-            }
-        }
-    }
-
     fn call(&mut self, req: Request) -> Result<Response, Error> {
         match req {
             Request::InitChain(init) => {
@@ -127,7 +104,6 @@ impl Shell {
             }
             Request::FinalizeBlock(finalize) => {
                 tracing::debug!("Request FinalizeBlock");
-                self.load_proposals();
                 self.finalize_block(finalize).map(Response::FinalizeBlock)
             }
             Request::Commit => {
@@ -228,6 +204,40 @@ pub fn rollback(config: config::Ledger) -> Result<(), shell::Error> {
     shell::rollback(config)
 }
 
+/// Pause the Ethereum oracle of the running node from its next block on,
+/// until it is resumed with [`resume_eth_oracle`]
+pub fn pause_eth_oracle(config: config::Ledger) -> std::io::Result<()> {
+    std::fs::write(config.eth_oracle_pause_file(), [])
+}
+
+/// Resume the Ethereum oracle of the running node from its next block on,
+/// after it has been paused with [`pause_eth_oracle`]
+pub fn resume_eth_oracle(config: config::Ledger) -> std::io::Result<()> {
+    match std::fs::remove_file(config.eth_oracle_pause_file()) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+/// Replay a range of recorded blocks on a checkpoint of the DB, measuring the
+/// time spent in each stage of block processing
+pub fn bench(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args: args::LedgerBench,
+) -> Result<bench::BenchReport, bench::Error> {
+    bench::bench(config, wasm_dir, args)
+}
+
+/// Export the state of the node into an archive to be attached to bug
+/// reports, returning the path of the archive
+pub fn debug_bundle(
+    config: config::Ledger,
+    args: args::LedgerDebugBundle,
+) -> Result<PathBuf, debug_bundle::Error> {
+    debug_bundle::debug_bundle(config, args)
+}
+
 /// Runs and monitors a few concurrent tasks.
 ///
 /// This includes:
@@ -254,9 +264,12 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
     let (eth_oracle_channels, eth_oracle) =
         match maybe_start_ethereum_oracle(&mut spawner, &config).await {
             EthereumOracleTask::NotEnabled { handle } => (None, handle),
-            EthereumOracleTask::Enabled { handle, channels } => {
-                (Some(channels), handle)
-            }
+            EthereumOracleTask::Enabled { handle, channels } => (
+                Some(channels.with_stalled_event_blocks(
+                    config.ethereum_bridge.stalled_event_blocks,
+                )),
+                handle,
+            ),
         };
 
     // Start ABCI server and broadcaster (the latter only if we are a validator
@@ -431,6 +444,9 @@ fn start_abci_broadcaster_shell(
     // broadcaster service
     let (broadcaster_sender, broadcaster_receiver) = mpsc::unbounded_channel();
 
+    // Log of crashes of the supervised subsystems of the ledger
+    let crash_reports = CrashReports::default();
+
     // Start broadcaster
     let broadcaster = if matches!(
         config.shell.tendermint_mode,
@@ -438,13 +454,17 @@ fn start_abci_broadcaster_shell(
     ) {
         let (bc_abort_send, bc_abort_recv) =
             tokio::sync::oneshot::channel::<()>();
+        let crash_reports = crash_reports.clone();
 
         spawner
             .spawn_abortable("Broadcaster", move |aborter| async move {
                 // Construct a service for broadcasting protocol txs from
                 // the ledger
-                let mut broadcaster =
-                    Broadcaster::new(rpc_address, broadcaster_receiver);
+                let mut broadcaster = Broadcaster::new(
+                    rpc_address,
+                    broadcaster_receiver,
+                    crash_reports,
+                );
                 broadcaster.run(bc_abort_recv).await;
                 tracing::info!("Broadcaster is no longer running.");
 
@@ -466,15 +486,53 @@ fn start_abci_broadcaster_shell(
     let proxy_app_address =
         convert_tm_addr_to_socket_addr(&config.cometbft.proxy_app);
 
-    let (shell, abci_service, service_handle) = AbcippShim::new(
-        config,
-        wasm_dir,
-        broadcaster_sender,
-        eth_oracle,
-        &db_cache,
-        vp_wasm_compilation_cache,
-        tx_wasm_compilation_cache,
-    );
+    // Report the progress of loading the last state, optionally serving
+    // it with the health and readiness probes before the ABCI server is up
+    let startup_progress = StartupProgress::default();
+    if let Some(addr) = config.shell.startup_status_addr {
+        tokio::spawn(probes::serve(
+            addr,
+            probes::Probes {
+                progress: startup_progress.clone(),
+                running_tasks: spawner.running_tasks(),
+                cometbft_rpc: rpc_address,
+            },
+        ));
+    }
+
+    let query_gateway_addr = config.shell.query_gateway_addr;
+
+    // NB: loading the last state blocks the current worker thread,
+    // so we hand its other tasks over to the rest of the runtime
+    let (shell, abci_service, service_handle) = task::block_in_place(|| {
+        AbcippShim::new(
+            config,
+            wasm_dir,
+            broadcaster_sender,
+            eth_oracle,
+            &db_cache,
+            vp_wasm_compilation_cache,
+            tx_wasm_compilation_cache,
+            crash_reports,
+            &startup_progress,
+        )
+    });
+
+    // Optionally serve the queries of the shell over HTTP, alongside the
+    // ABCI server
+    if let Some(addr) = query_gateway_addr {
+        let queries = abci_service.query_handle();
+        let (gateway_abort_send, gateway_abort_recv) =
+            tokio::sync::oneshot::channel::<()>();
+        spawner
+            .spawn_abortable("Query gateway", move |aborter| async move {
+                gateway::serve(addr, queries, gateway_abort_recv).await;
+                drop(aborter);
+            })
+            .with_cleanup(async move {
+                let _ = gateway_abort_send.send(());
+            });
+    }
 
     // Channel for signalling shut down to ABCI server
     let (abci_abort_send, abci_abort_recv) = tokio::sync::oneshot::channel();
@@ -666,8 +724,62 @@ async fn maybe_start_ethereum_oracle(
 
     match config.ethereum_bridge.mode {
         ethereum_bridge::ledger::Mode::RemoteEndpoint => {
+            let urls = std::iter::once(ethereum_url)
+                .chain(
+                    config
+                        .ethereum_bridge
+                        .oracle_rpc_fallback_endpoints
+                        .iter()
+                        .cloned(),
+                )
+                .collect();
+            let light_client = config
+                .ethereum_bridge
+                .oracle_light_client
+                .as_ref()
+                .map(|light_client| {
+                    let sources = light_client.endpoints.iter().map(|url| {
+                        let client = Provider::<Http>::try_from(url.as_str())
+                            .expect("Invalid Ethereum RPC url");
+                        (url.clone(), client)
+                    });
+                    oracle::light_client::LightClient::new(
+                        sources,
+                        light_client.min_agreeing_endpoints,
+                    )
+                })
+                .transpose();
+            let light_client = match light_client {
+                Ok(light_client) => light_client,
+                Err(error) => {
+                    tracing::error!(
+                        %error,
+                        "Could not start the Ethereum event oracle with the \
+                         light client"
+                    );
+                    return EthereumOracleTask::NotEnabled {
+                        handle: spawn_dummy_task(()),
+                    };
+                }
+            };
+            let progress = oracle::progress::ProgressStore::new(
+                config.chain_dir(),
+                config.chain_id.clone(),
+            );
+            let node_metrics = NodeMetrics::default();
             let handle = oracle::run_oracle::<Provider<Http>>(
-                ethereum_url,
+                urls,
+                config.ethereum_bridge.oracle_rpc_quorum,
+                oracle::rate_limit::RateLimits {
+                    max_requests_per_sec: config
+                        .ethereum_bridge
+                        .oracle_rpc_max_requests_per_sec,
+                    max_retries: config.ethereum_bridge.oracle_rpc_max_retries,
+                },
+                node_metrics.clone(),
+                config.ethereum_bridge.oracle_reorg_safety_depth,
+                light_client,
+                Some(progress.clone()),
                 eth_sender,
                 control_receiver,
                 last_processed_block_sender,
@@ -680,50 +792,87 @@ async fn maybe_start_ethereum_oracle(
                     eth_receiver,
                     control_sender,
                     last_processed_block_receiver,
-                ),
+                )
+                .with_progress_store(progress)
+                .with_node_metrics(node_metrics),
             }
         }
         ethereum_bridge::ledger::Mode::SelfHostedEndpoint => {
-            let (oracle_abort_send, oracle_abort_recv) =
-                tokio::sync::oneshot::channel::<tokio::sync::oneshot::Sender<()>>(
-                );
-            let handle = spawner
-                .spawn_abortable(
-                    "Ethereum Events Endpoint",
-                    move |aborter| async move {
-                        oracle::test_tools::events_endpoint::serve(
-                            ethereum_url,
-                            eth_sender,
-                            control_receiver,
-                            oracle_abort_recv,
-                        )
-                        .await;
-                        tracing::info!(
-                            "Ethereum events endpoint is no longer running."
-                        );
-
-                        drop(aborter);
-                    },
-                )
-                .with_cleanup(async move {
-                    let (oracle_abort_resp_send, oracle_abort_resp_recv) =
-                        tokio::sync::oneshot::channel::<()>();
-
-                    if let Ok(()) =
-                        oracle_abort_send.send(oracle_abort_resp_send)
-                    {
-                        match oracle_abort_resp_recv.await {
-                            Ok(()) => {}
-                            Err(err) => {
-                                tracing::error!(
-                                    "Failed to receive an abort response from \
-                                     the Ethereum events endpoint task: {}",
-                                    err
-                                );
-                            }
-                        }
-                    }
-                });
+            let handle = spawn_oracle_stand_in(
+                spawner,
+                "Ethereum Events Endpoint",
+                |abort_recv| async move {
+                    oracle::test_tools::events_endpoint::serve(
+                        ethereum_url,
+                        eth_sender,
+                        control_receiver,
+                        abort_recv,
+                    )
+                    .await;
+                    Ok::<_, Infallible>(())
+                },
+            );
+            EthereumOracleTask::Enabled {
+                handle,
+                channels: EthereumOracleChannels::new(
+                    eth_receiver,
+                    control_sender,
+                    last_processed_block_receiver,
+                ),
+            }
+        }
+        ethereum_bridge::ledger::Mode::EventsFile => {
+            let events_file = config
+                .ethereum_bridge
+                .oracle_events_file
+                .clone()
+                .expect("An Ethereum events file must be configured");
+            let signer = config
+                .ethereum_bridge
+                .oracle_events_file_signer
+                .clone()
+                .expect("The signer of the Ethereum events file must be set");
+            let handle = spawn_oracle_stand_in(
+                spawner,
+                "Ethereum Events File",
+                |abort_recv| {
+                    oracle::test_tools::events_file::serve(
+                        events_file,
+                        signer,
+                        eth_sender,
+                        control_receiver,
+                        abort_recv,
+                    )
+                },
+            );
+            EthereumOracleTask::Enabled {
+                handle,
+                channels: EthereumOracleChannels::new(
+                    eth_receiver,
+                    control_sender,
+                    last_processed_block_receiver,
+                ),
+            }
+        }
+        ethereum_bridge::ledger::Mode::Simulation => {
+            let fixture =
+                config.ethereum_bridge.oracle_simulation_fixture.clone();
+            let socket =
+                config.ethereum_bridge.oracle_simulation_socket.clone();
+            let handle = spawn_oracle_stand_in(
+                spawner,
+                "Simulated Ethereum Oracle",
+                |abort_recv| {
+                    oracle::test_tools::simulation::serve(
+                        fixture,
+                        socket,
+                        eth_sender,
+                        control_receiver,
+                        last_processed_block_sender,
+                        abort_recv,
+                    )
+                },
+            );
             EthereumOracleTask::Enabled {
                 handle,
                 channels: EthereumOracleChannels::new(
@@ -739,8 +888,77 @@ async fn maybe_start_ethereum_oracle(
     }
 }
 
+/// Spawn a task feeding Ethereum events to the ledger in place of the
+/// Ethereum oracle. The `serve` closure is handed the channel on which it is
+/// signalled to abort when the ledger shuts down, which it must respond to.
+/// If the task fails, its error is reported, and the ledger shuts down.
+fn spawn_oracle_stand_in<F, R, E>(
+    spawner: &mut AbortableSpawner,
+    who: &'static str,
+    serve: F,
+) -> task::JoinHandle<()>
+where
+    F: FnOnce(
+        tokio::sync::oneshot::Receiver<tokio::sync::oneshot::Sender<()>>,
+    ) -> R,
+    R: Future<Output = Result<(), E>> + Send + 'static,
+    E: std::fmt::Display,
+{
+    let (oracle_abort_send, oracle_abort_recv) =
+        tokio::sync::oneshot::channel::<tokio::sync::oneshot::Sender<()>>();
+    let serve = serve(oracle_abort_recv);
+    spawner
+        .spawn_abortable(who, move |aborter| async move {
+            match serve.await {
+                Ok(()) => tracing::info!("{who} is no longer running."),
+                Err(error) => tracing::error!("{who} failed: {error}"),
+            }
+
+            drop(aborter);
+        })
+        .with_cleanup(async move {
+            let (oracle_abort_resp_send, oracle_abort_resp_recv) =
+                tokio::sync::oneshot::channel::<()>();
+
+            if let Ok(()) = oracle_abort_send.send(oracle_abort_resp_send) {
+                match oracle_abort_resp_recv.await {
+                    Ok(()) => {}
+                    Err(err) => {
+                        tracing::error!(
+                            "Failed to receive an abort response from the \
+                             {who} task: {}",
+                            err
+                        );
+                    }
+                }
+            }
+        })
+}
+
 /// Spawn a dummy asynchronous task into the runtime,
 /// which will resolve instantly.
 fn spawn_dummy_task<T: Send + 'static>(ready: T) -> task::JoinHandle<T> {
     tokio::spawn(async { std::future::ready(ready).await })
 }
+
+#[cfg(test)]
+mod test_ledger {
+    use super::*;
+
+    /// Test that an oracle stand-in which fails shuts the ledger down.
+    #[tokio::test]
+    async fn test_failed_oracle_stand_in() {
+        let mut spawner = AbortableSpawner::new();
+        let handle = spawn_oracle_stand_in(
+            &mut spawner,
+            "Failing Oracle",
+            |_abort_recv| async { Err("no events") },
+        );
+        handle.await.expect("Test failed");
+        assert_eq!(
+            spawner.running_tasks().snapshot().get("Failing Oracle"),
+            Some(&false)
+        );
+        assert!(spawner.wait_for_abort().await.child_terminated());
+    }
+}
@@ -0,0 +1,205 @@
+//! Health and readiness probes of the ledger.
+//!
+//! The probes are served over HTTP from the start of the ledger, such that
+//! they can be used by orchestrators such as Kubernetes:
+//!
+//! - `/health` responds with `200 OK` once the shell has loaded the last
+//!   committed state, for as long as all the subsystems of the ledger (e.g.
+//!   CometBFT, the ABCI server, the broadcaster or the Ethereum oracle) are
+//!   running.
+//! - `/ready` additionally requires CometBFT to have caught up with the chain,
+//!   such that the node serves up-to-date queries.
+//!
+//! Both respond with `503 Service Unavailable` otherwise.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use serde::Serialize;
+use warp::http::StatusCode;
+use warp::Filter;
+
+use crate::facade::tendermint_rpc::{Client, HttpClient};
+use crate::node::ledger::abortable::RunningTasks;
+use crate::node::ledger::startup::{
+    StartupProgress, StartupStage, StartupStatus,
+};
+
+/// The maximum time to wait for the status of CometBFT.
+const COMETBFT_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The health of the ledger, as served at `/health`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthStatus {
+    /// The progress of the startup of the ledger
+    #[serde(flatten)]
+    pub startup: StartupStatus,
+    /// Whether each of the subsystems of the ledger is still running
+    pub subsystems: BTreeMap<&'static str, bool>,
+}
+
+impl HealthStatus {
+    /// Check if the shell has loaded its state, and all the subsystems are
+    /// running.
+    pub fn is_healthy(&self) -> bool {
+        self.startup.stage == StartupStage::Ready
+            && self.subsystems.values().all(|running| *running)
+    }
+}
+
+/// The readiness of the ledger, as served at `/ready`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReadinessStatus {
+    /// The health of the ledger
+    #[serde(flatten)]
+    pub health: HealthStatus,
+    /// Whether CometBFT is catching up with the chain, if its status could
+    /// be read
+    pub catching_up: Option<bool>,
+}
+
+impl ReadinessStatus {
+    /// Check if the ledger is healthy, and CometBFT has caught up with the
+    /// chain.
+    pub fn is_ready(&self) -> bool {
+        self.health.is_healthy() && self.catching_up == Some(false)
+    }
+}
+
+/// The state read by the probes of the ledger.
+#[derive(Debug, Clone)]
+pub struct Probes {
+    /// The progress of the startup of the ledger
+    pub progress: StartupProgress,
+    /// The record of the running subsystems of the ledger
+    pub running_tasks: RunningTasks,
+    /// The address of the RPC server of CometBFT
+    pub cometbft_rpc: SocketAddr,
+}
+
+impl Probes {
+    /// Return a snapshot of the health of the ledger.
+    pub fn health(&self) -> HealthStatus {
+        HealthStatus {
+            startup: self.progress.status(),
+            subsystems: self.running_tasks.snapshot(),
+        }
+    }
+
+    /// Return a snapshot of the readiness of the ledger.
+    pub async fn readiness(&self) -> ReadinessStatus {
+        let health = self.health();
+        // NB: the RPC server of CometBFT is only up once it has completed
+        // its handshake with the shell
+        let catching_up = if health.is_healthy() {
+            self.cometbft_catching_up().await
+        } else {
+            None
+        };
+        ReadinessStatus {
+            health,
+            catching_up,
+        }
+    }
+
+    /// Read whether CometBFT is catching up with the chain.
+    async fn cometbft_catching_up(&self) -> Option<bool> {
+        let client =
+            HttpClient::new(format!("http://{}", self.cometbft_rpc).as_str())
+                .ok()?;
+        match tokio::time::timeout(COMETBFT_STATUS_TIMEOUT, client.status())
+            .await
+        {
+            Ok(Ok(status)) => Some(status.sync_info.catching_up),
+            Ok(Err(err)) => {
+                tracing::debug!("Failed to read the status of CometBFT: {err}");
+                None
+            }
+            Err(_) => {
+                tracing::debug!("Timed out reading the status of CometBFT");
+                None
+            }
+        }
+    }
+}
+
+/// Serve the health and readiness probes of the ledger at `/health` and
+/// `/ready` on the given address, until the ledger shuts down.
+pub async fn serve(addr: SocketAddr, probes: Probes) {
+    let health = {
+        let probes = probes.clone();
+        warp::get().and(warp::path("health")).map(move || {
+            let status = probes.health();
+            let code = if status.is_healthy() {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            warp::reply::with_status(warp::reply::json(&status), code)
+        })
+    };
+    let ready = warp::get().and(warp::path("ready")).then(move || {
+        let probes = probes.clone();
+        async move {
+            let status = probes.readiness().await;
+            let code = if status.is_ready() {
+                StatusCode::OK
+            } else {
+                StatusCode::SERVICE_UNAVAILABLE
+            };
+            warp::reply::with_status(warp::reply::json(&status), code)
+        }
+    });
+    match warp::serve(health.or(ready)).try_bind_ephemeral(addr) {
+        Ok((addr, server)) => {
+            tracing::info!(
+                "Serving the health and readiness probes at {addr}/health and \
+                 {addr}/ready"
+            );
+            server.await
+        }
+        Err(err) => {
+            tracing::error!("Failed to serve the probes at {addr}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_probes {
+    use super::*;
+
+    /// Test that the ledger is only healthy once started up with all its
+    /// subsystems running, and only ready once CometBFT has caught up.
+    #[test]
+    fn test_health_and_readiness() {
+        let status = |stage| StartupStatus {
+            stage,
+            percentage: None,
+            eta_secs: None,
+            elapsed_secs: 0,
+        };
+        let mut health = HealthStatus {
+            startup: status(StartupStage::WarmingUpCaches),
+            subsystems: BTreeMap::from([("ABCI", true), ("Broadcaster", true)]),
+        };
+        assert!(!health.is_healthy());
+
+        health.startup = status(StartupStage::Ready);
+        assert!(health.is_healthy());
+
+        let mut readiness = ReadinessStatus {
+            health: health.clone(),
+            catching_up: None,
+        };
+        assert!(!readiness.is_ready());
+        readiness.catching_up = Some(true);
+        assert!(!readiness.is_ready());
+        readiness.catching_up = Some(false);
+        assert!(readiness.is_ready());
+
+        readiness.health.subsystems.insert("Broadcaster", false);
+        assert!(!readiness.health.is_healthy());
+        assert!(!readiness.is_ready());
+    }
+}
@@ -0,0 +1,284 @@
+//! Opt-in, append-only audit log of the storage writes made during block
+//! execution.
+//!
+//! Each write is recorded as a line of JSON, containing the height of the
+//! block, the hash of the tx that performed the write (or none, for writes
+//! made by the protocol), the modified key and the hashes of its values
+//! before and after the write.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use namada::ledger::storage::write_log::StorageModification;
+use namada::ledger::storage::{DBIter, StorageHasher, WlStorage, DB};
+use namada::types::hash::Hash;
+use namada::types::storage::{BlockHeight, Key};
+use serde::Serialize;
+
+/// A single entry of the [`StorageAuditLog`].
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    height: u64,
+    tx_hash: Option<&'a str>,
+    key: String,
+    old_value_hash: Option<String>,
+    new_value_hash: Option<String>,
+}
+
+/// Append-only audit log of storage writes.
+#[derive(Debug)]
+pub struct StorageAuditLog {
+    /// The audit file.
+    file: BufWriter<File>,
+    /// Hashes of the last values written to each key by the txs of
+    /// the current block.
+    tx_writes: HashMap<Key, Option<Hash>>,
+}
+
+impl StorageAuditLog {
+    /// Open the audit log at the given path, creating it if it does not
+    /// exist yet.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+        Ok(Self {
+            file: BufWriter::new(file),
+            tx_writes: HashMap::new(),
+        })
+    }
+
+    /// Record the writes of the tx with the given hash. This must be called
+    /// before the tx is committed to the block write log.
+    pub fn record_tx_writes<D, H>(
+        &mut self,
+        height: BlockHeight,
+        tx_hash: &str,
+        wl_storage: &WlStorage<D, H>,
+    ) where
+        D: DB + for<'iter> DBIter<'iter>,
+        H: StorageHasher,
+    {
+        let write_log = &wl_storage.write_log;
+        for key in write_log.get_keys_with_precommit() {
+            let new_value_hash = match write_log.read(&key).0 {
+                // temporary values are never persisted
+                Some(StorageModification::Temp { .. }) | None => continue,
+                Some(modification) => modification_hash(modification),
+            };
+            let old_value_hash = match write_log.read_pre(&key).0 {
+                Some(modification) => modification_hash(modification),
+                None => committed_value_hash(wl_storage, &key),
+            };
+            self.append(AuditEntry {
+                height: height.0,
+                tx_hash: Some(tx_hash),
+                key: key.to_string(),
+                old_value_hash: old_value_hash.map(|h| h.to_string()),
+                new_value_hash: new_value_hash.map(|h| h.to_string()),
+            });
+            self.tx_writes.insert(key, new_value_hash);
+        }
+    }
+
+    /// Record the fee writes of the wrapper tx with the given hash, which
+    /// modified the given keys. The fees are committed to the block write log
+    /// while the wrapper is applied, so this must be called right after it.
+    pub fn record_wrapper_writes<D, H>(
+        &mut self,
+        height: BlockHeight,
+        wrapper_hash: &str,
+        changed_keys: &BTreeSet<Key>,
+        wl_storage: &WlStorage<D, H>,
+    ) where
+        D: DB + for<'iter> DBIter<'iter>,
+        H: StorageHasher,
+    {
+        for key in changed_keys {
+            let Some(modification) = wl_storage.write_log.read_pre(key).0
+            else {
+                continue;
+            };
+            let new_value_hash = modification_hash(modification);
+            let old_value_hash = match self.tx_writes.get(key) {
+                Some(last_tx_write) => *last_tx_write,
+                None => committed_value_hash(wl_storage, key),
+            };
+            self.append(AuditEntry {
+                height: height.0,
+                tx_hash: Some(wrapper_hash),
+                key: key.to_string(),
+                old_value_hash: old_value_hash.map(|h| h.to_string()),
+                new_value_hash: new_value_hash.map(|h| h.to_string()),
+            });
+            self.tx_writes.insert(key.clone(), new_value_hash);
+        }
+    }
+
+    /// Record the writes performed by the protocol itself, rather than by
+    /// any tx, and flush the log. This must be called before the block is
+    /// committed to storage.
+    pub fn record_block_writes<D, H>(
+        &mut self,
+        height: BlockHeight,
+        wl_storage: &WlStorage<D, H>,
+    ) where
+        D: DB + for<'iter> DBIter<'iter>,
+        H: StorageHasher,
+    {
+        let write_log = &wl_storage.write_log;
+        for key in write_log.get_block_keys() {
+            let Some(modification) = write_log.read_pre(&key).0 else {
+                continue;
+            };
+            let new_value_hash = modification_hash(modification);
+            let written_by_tx = self
+                .tx_writes
+                .get(&key)
+                .map(|last_tx_write| *last_tx_write == new_value_hash)
+                .unwrap_or(false);
+            if written_by_tx {
+                continue;
+            }
+            let old_value_hash = committed_value_hash(wl_storage, &key);
+            self.append(AuditEntry {
+                height: height.0,
+                tx_hash: None,
+                key: key.to_string(),
+                old_value_hash: old_value_hash.map(|h| h.to_string()),
+                new_value_hash: new_value_hash.map(|h| h.to_string()),
+            });
+        }
+        self.tx_writes.clear();
+        if let Err(err) = self.file.flush() {
+            tracing::error!("Failed to flush the storage audit log: {err}");
+        }
+    }
+
+    /// Append a new entry to the audit log.
+    fn append(&mut self, entry: AuditEntry<'_>) {
+        let result = serde_json::to_writer(&mut self.file, &entry)
+            .map_err(io::Error::from)
+            .and_then(|()| self.file.write_all(b"\n"));
+        if let Err(err) = result {
+            tracing::error!("Failed to write to the storage audit log: {err}");
+        }
+    }
+}
+
+/// Hash the value of a [`StorageModification`], returning [`None`] for
+/// deletions.
+fn modification_hash(modification: &StorageModification) -> Option<Hash> {
+    match modification {
+        StorageModification::Write { value }
+        | StorageModification::Temp { value } => Some(Hash::sha256(value)),
+        StorageModification::InitAccount { vp_code_hash } => {
+            Some(Hash::sha256(vp_code_hash))
+        }
+        StorageModification::Delete => None,
+    }
+}
+
+/// Hash the value of `key` committed to storage, if any.
+fn committed_value_hash<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    key: &Key,
+) -> Option<Hash>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    wl_storage
+        .storage
+        .db
+        .read_subspace_val(key)
+        .ok()
+        .flatten()
+        .map(Hash::sha256)
+}
+
+#[cfg(test)]
+mod test_audit_log {
+    use namada::ledger::storage::testing::TestWlStorage;
+    use namada::ledger::storage_api::StorageWrite;
+
+    use super::*;
+
+    /// Test that tx and protocol writes are recorded in the audit log.
+    #[test]
+    fn test_audit_log_records_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let mut audit_log = StorageAuditLog::open(&path).unwrap();
+        let mut wl_storage = TestWlStorage::default();
+
+        let tx_key = Key::parse("tx_key").unwrap();
+        let protocol_key = Key::parse("protocol_key").unwrap();
+
+        wl_storage
+            .write_log
+            .write(&tx_key, vec![1, 2, 3])
+            .expect("Test failed");
+        audit_log.record_tx_writes(BlockHeight(1), "deadbeef", &wl_storage);
+        wl_storage.write_log.commit_tx();
+        wl_storage.write_bytes(&protocol_key, [4, 5, 6]).unwrap();
+        audit_log.record_block_writes(BlockHeight(1), &wl_storage);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["key"], "tx_key");
+        assert_eq!(entries[0]["tx_hash"], "deadbeef");
+        assert_eq!(entries[0]["old_value_hash"], serde_json::Value::Null);
+        assert_eq!(
+            entries[0]["new_value_hash"],
+            Hash::sha256([1, 2, 3]).to_string()
+        );
+        assert_eq!(entries[1]["key"], "protocol_key");
+        assert_eq!(entries[1]["tx_hash"], serde_json::Value::Null);
+    }
+
+    /// Test that the fee writes of a wrapper, committed while the wrapper is
+    /// applied, are recorded under the hash of the wrapper.
+    #[test]
+    fn test_audit_log_records_wrapper_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let mut audit_log = StorageAuditLog::open(&path).unwrap();
+        let mut wl_storage = TestWlStorage::default();
+
+        let fee_key = Key::parse("fee_key").unwrap();
+        wl_storage
+            .write_log
+            .write(&fee_key, vec![1, 2, 3])
+            .expect("Test failed");
+        wl_storage.write_log.commit_tx();
+        audit_log.record_wrapper_writes(
+            BlockHeight(1),
+            "deadbeef",
+            &BTreeSet::from([fee_key]),
+            &wl_storage,
+        );
+        audit_log.record_tx_writes(BlockHeight(1), "deadbeef", &wl_storage);
+        audit_log.record_block_writes(BlockHeight(1), &wl_storage);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["key"], "fee_key");
+        assert_eq!(entries[0]["tx_hash"], "deadbeef");
+        assert_eq!(
+            entries[0]["new_value_hash"],
+            Hash::sha256([1, 2, 3]).to_string()
+        );
+    }
+}
@@ -34,6 +34,15 @@
 //! Gas is only relevant to DKG encrypted txs. Every encrypted tx defines its
 //! gas limit. We take this entire gas limit as the amount of gas requested by
 //! the tx.
+//!
+//! # Verifying the allocation of a proposal
+//!
+//! Both `PrepareProposal` and `ProcessProposal` allocate the txs of a
+//! proposal through a [`ProposalAllocator`], in the order they appear in the
+//! proposal. Since the allocator is a pure state machine, the proposer and
+//! the validators verifying its proposal go through the exact same states,
+//! such that any proposal built by an honest proposer is accepted, and any
+//! accepted proposal could have been built by an honest proposer.
 
 pub mod states;
 
@@ -55,9 +64,12 @@ pub mod states;
 
 use std::marker::PhantomData;
 
+use namada::core::hints;
 use namada::core::ledger::storage::{self, WlStorage};
 use namada::proof_of_stake::pos_queries::PosQueries;
 
+use self::states::{NextState, TryAlloc};
+
 #[allow(unused_imports)]
 use crate::facade::tendermint_proto::abci::RequestPrepareProposal;
 
@@ -126,7 +138,7 @@ impl Resource for BlockGas {
 ///   - Protocol transactions.
 ///
 /// Gas usage of DKG encrypted txs is also tracked.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct BlockAllocator<State> {
     /// The current state of the [`BlockAllocator`] state machine.
     _state: PhantomData<*const State>,
@@ -141,6 +153,18 @@ pub struct BlockAllocator<State> {
     decrypted_txs: TxBin<BlockSpace>,
 }
 
+impl<State> Default for BlockAllocator<State> {
+    fn default() -> Self {
+        Self {
+            _state: PhantomData,
+            block: TxBin::default(),
+            protocol_txs: TxBin::default(),
+            encrypted_txs: EncryptedTxsBins::default(),
+            decrypted_txs: TxBin::default(),
+        }
+    }
+}
+
 impl<D, H, M> From<&WlStorage<D, H>>
     for BlockAllocator<states::BuildingEncryptedTxBatch<M>>
 where
@@ -156,6 +180,25 @@ where
     }
 }
 
+impl<D, H> From<&WlStorage<D, H>> for states::EncryptedTxBatchAllocator
+where
+    D: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+{
+    /// Encrypted txs may not be included in the proposals of the second
+    /// and third block height offsets within an epoch.
+    fn from(storage: &WlStorage<D, H>) -> Self {
+        let pos_queries = storage.pos_queries();
+        let is_2nd_height_off = pos_queries.is_deciding_offset_within_epoch(1);
+        let is_3rd_height_off = pos_queries.is_deciding_offset_within_epoch(2);
+        if hints::unlikely(is_2nd_height_off || is_3rd_height_off) {
+            Self::WithoutEncryptedTxs(storage.into())
+        } else {
+            Self::WithEncryptedTxs(storage.into())
+        }
+    }
+}
+
 impl<M> BlockAllocator<states::BuildingEncryptedTxBatch<M>> {
     /// Construct a new [`BlockAllocator`], with an upper bound
     /// on the max size of all txs in a block defined by Tendermint and an upper
@@ -192,6 +235,95 @@ impl<State> BlockAllocator<State> {
     }
 }
 
+/// The kind of a tx allocated by a [`ProposalAllocator`], which determines
+/// the [`BlockAllocator`] state it is allocated in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TxKind {
+    /// A DKG encrypted (wrapper) tx, requesting the given amount of gas.
+    Wrapper { gas: u64 },
+    /// A DKG decrypted tx.
+    Decrypted,
+    /// A protocol tx.
+    Protocol,
+}
+
+impl TxKind {
+    /// The index of the [`ProposalAllocator`] state txs of this kind are
+    /// allocated in.
+    const fn stage(&self) -> u8 {
+        match self {
+            TxKind::Wrapper { .. } => 0,
+            TxKind::Decrypted => 1,
+            TxKind::Protocol => 2,
+        }
+    }
+}
+
+/// A [`BlockAllocator`] at any of its states, allocating the txs of a
+/// proposal in the order they appear in it.
+///
+/// Allocating a tx moves the allocator forward to the state of its
+/// [`TxKind`]. Since the state machine can't move backwards, txs of a kind
+/// whose state has been left behind are rejected, as the bin of that state
+/// was shrunk to fit upon leaving it.
+pub enum ProposalAllocator {
+    EncryptedTxs(states::EncryptedTxBatchAllocator),
+    DecryptedTxs(BlockAllocator<states::BuildingDecryptedTxBatch>),
+    ProtocolTxs(BlockAllocator<states::BuildingProtocolTxBatch>),
+}
+
+impl From<states::EncryptedTxBatchAllocator> for ProposalAllocator {
+    #[inline]
+    fn from(alloc: states::EncryptedTxBatchAllocator) -> Self {
+        Self::EncryptedTxs(alloc)
+    }
+}
+
+impl ProposalAllocator {
+    /// Try to allocate resources for a new tx of the given kind.
+    pub fn try_alloc(
+        &mut self,
+        tx: &[u8],
+        kind: TxKind,
+    ) -> Result<(), AllocFailure> {
+        while self.stage() < kind.stage() {
+            self.advance();
+        }
+        match (self, kind) {
+            (Self::EncryptedTxs(alloc), TxKind::Wrapper { gas }) => {
+                alloc.try_alloc(BlockResources::new(tx, gas))
+            }
+            (Self::DecryptedTxs(alloc), TxKind::Decrypted) => {
+                alloc.try_alloc(tx)
+            }
+            (Self::ProtocolTxs(alloc), TxKind::Protocol) => alloc.try_alloc(tx),
+            _ => Err(AllocFailure::Rejected {
+                bin_resource_left: 0,
+            }),
+        }
+    }
+
+    /// The index of the current state of the allocator.
+    const fn stage(&self) -> u8 {
+        match self {
+            Self::EncryptedTxs(_) => 0,
+            Self::DecryptedTxs(_) => 1,
+            Self::ProtocolTxs(_) => 2,
+        }
+    }
+
+    /// Transition to the next state of the allocator, if any.
+    fn advance(&mut self) {
+        // NB: the placeholder is immediately replaced by the next state
+        let placeholder = Self::ProtocolTxs(BlockAllocator::default());
+        *self = match std::mem::replace(self, placeholder) {
+            Self::EncryptedTxs(alloc) => Self::DecryptedTxs(alloc.next_state()),
+            Self::DecryptedTxs(alloc) => Self::ProtocolTxs(alloc.next_state()),
+            protocol_txs => protocol_txs,
+        };
+    }
+}
+
 /// Allotted resource for a batch of transactions of the same kind in some
 /// proposed block. At the moment this is used to track two resources of the
 /// block: space and gas. Space is measured in bytes while gas in gas units.
@@ -270,25 +402,18 @@ impl EncryptedTxsBins {
         }
     }
 
-    pub fn try_dump(&mut self, tx: &[u8], gas: u64) -> Result<(), String> {
-        self.space.try_dump(tx).map_err(|e| match e {
-            AllocFailure::Rejected { .. } => {
-                "No more space left in the block for wrapper txs".to_string()
-            }
-            AllocFailure::OverflowsBin { .. } => "The given wrapper tx is \
-                                                  larger than 1/3 of the \
-                                                  available block space"
-                .to_string(),
-        })?;
-        self.gas.try_dump(gas).map_err(|e| match e {
-            AllocFailure::Rejected { .. } => {
-                "No more gas left in the block for wrapper txs".to_string()
-            }
-            AllocFailure::OverflowsBin { .. } => {
-                "The given wrapper tx requires more gas than available to the \
-                 entire block"
-                    .to_string()
-            }
+    /// Try to dump a new wrapper tx into these bins. Either both its space
+    /// and gas are allocated, or none of them is.
+    pub fn try_dump(
+        &mut self,
+        tx: &[u8],
+        gas: u64,
+    ) -> Result<(), AllocFailure> {
+        let space = self.space;
+        self.space.try_dump(tx)?;
+        self.gas.try_dump(gas).map_err(|err| {
+            self.space = space;
+            err
         })
     }
 }
@@ -429,6 +554,152 @@ mod tests {
         fn test_tx_dump_doesnt_fill_up_bin(args in arb_transactions()) {
             proptest_tx_dump_doesnt_fill_up_bin(args)
         }
+
+        /// Test that any proposal built out of some candidate txs is
+        /// accepted by the verifier.
+        #[test]
+        fn test_built_proposals_are_verified(
+            args in arb_proposal_candidates(),
+        ) {
+            proptest_built_proposals_are_verified(args)
+        }
+
+        /// Test that any proposal accepted by the verifier is the one that
+        /// would be built out of the same txs.
+        #[test]
+        fn test_verified_proposals_are_built(
+            args in arb_proposal_candidates(),
+        ) {
+            proptest_verified_proposals_are_built(args)
+        }
+    }
+
+    /// Candidate txs of a proposal, listed along with their kind.
+    #[derive(Debug)]
+    struct ProposalCandidates {
+        tendermint_max_block_space_in_bytes: u64,
+        max_block_gas: u64,
+        with_encrypted_txs: bool,
+        txs: Vec<(TxKind, Vec<u8>)>,
+    }
+
+    impl ProposalCandidates {
+        /// Create a fresh [`ProposalAllocator`] for these candidates.
+        fn allocator(&self) -> ProposalAllocator {
+            let max = self.tendermint_max_block_space_in_bytes;
+            let alloc = if self.with_encrypted_txs {
+                states::EncryptedTxBatchAllocator::WithEncryptedTxs(
+                    BsaWrapperTxs::init(max, self.max_block_gas),
+                )
+            } else {
+                states::EncryptedTxBatchAllocator::WithoutEncryptedTxs(
+                    BsaNoWrapperTxs::init(max, self.max_block_gas),
+                )
+            };
+            alloc.into()
+        }
+    }
+
+    /// Build a proposal out of batches of candidate txs, the way
+    /// `PrepareProposal` does: txs overflowing their bin are skipped,
+    /// and a batch ends at the first tx rejected for lack of resources.
+    fn build_proposal<'tx>(
+        mut alloc: ProposalAllocator,
+        batches: impl IntoIterator<Item = Vec<&'tx (TxKind, Vec<u8>)>>,
+    ) -> Vec<&'tx (TxKind, Vec<u8>)> {
+        let mut proposal = vec![];
+        for batch in batches {
+            for candidate in batch {
+                let (kind, tx) = candidate;
+                match alloc.try_alloc(tx, *kind) {
+                    Ok(()) => proposal.push(candidate),
+                    Err(AllocFailure::OverflowsBin { .. }) => continue,
+                    Err(AllocFailure::Rejected { .. }) => break,
+                }
+            }
+        }
+        proposal
+    }
+
+    /// Verify a proposal, the way `ProcessProposal` does.
+    fn verify_proposal(
+        mut alloc: ProposalAllocator,
+        proposal: &[&(TxKind, Vec<u8>)],
+    ) -> bool {
+        proposal
+            .iter()
+            .all(|(kind, tx)| alloc.try_alloc(tx, *kind).is_ok())
+    }
+
+    /// Split the given txs into batches of the same kind, in the order
+    /// they are proposed in.
+    fn batches(
+        txs: &[(TxKind, Vec<u8>)],
+    ) -> impl Iterator<Item = Vec<&(TxKind, Vec<u8>)>> {
+        (0..=2).map(|stage| {
+            txs.iter()
+                .filter(|(kind, _)| kind.stage() == stage)
+                .collect()
+        })
+    }
+
+    /// Implementation of [`test_built_proposals_are_verified`].
+    fn proptest_built_proposals_are_verified(args: ProposalCandidates) {
+        let proposal = build_proposal(args.allocator(), batches(&args.txs));
+        assert!(verify_proposal(args.allocator(), &proposal));
+    }
+
+    /// Implementation of [`test_verified_proposals_are_built`].
+    fn proptest_verified_proposals_are_built(args: ProposalCandidates) {
+        // proposals with txs out of order must be rejected
+        let proposal: Vec<_> = args.txs.iter().collect();
+        let in_order = proposal
+            .windows(2)
+            .all(|pair| pair[0].0.stage() <= pair[1].0.stage());
+        if !verify_proposal(args.allocator(), &proposal) {
+            return;
+        }
+        assert!(in_order);
+        assert_eq!(
+            build_proposal(args.allocator(), batches(&args.txs)),
+            proposal
+        );
+    }
+
+    prop_compose! {
+        /// Generate arbitrary candidate txs of a proposal. Txs of the same
+        /// kind are mostly grouped together, such that a fair share of the
+        /// generated proposals is accepted by the verifier.
+        fn arb_proposal_candidates()
+            (
+                tendermint_max_block_space_in_bytes in 1..=1000u64,
+                max_block_gas in 1..=1000u64,
+                with_encrypted_txs in prop::bool::weighted(0.8),
+                shuffle in prop::bool::weighted(0.1),
+                txs in prop::collection::vec(arb_proposal_tx(), 0..=64),
+            )
+            -> ProposalCandidates {
+                let mut txs = txs;
+                if !shuffle {
+                    txs.sort_by_key(|(kind, _)| kind.stage());
+                }
+                ProposalCandidates {
+                    tendermint_max_block_space_in_bytes,
+                    max_block_gas,
+                    with_encrypted_txs,
+                    txs,
+                }
+            }
+    }
+
+    /// Return an arbitrary tx of any kind.
+    fn arb_proposal_tx() -> impl Strategy<Value = (TxKind, Vec<u8>)> {
+        let kind = prop_oneof![
+            (0..=200u64).prop_map(|gas| TxKind::Wrapper { gas }),
+            Just(TxKind::Decrypted),
+            Just(TxKind::Protocol),
+        ];
+        (kind, (0..=200usize).prop_map(|len| vec![0; len]))
     }
 
     /// Implementation of [`test_reject_tx_on_bin_cap_reached`].
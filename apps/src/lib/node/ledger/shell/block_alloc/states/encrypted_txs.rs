@@ -16,8 +16,8 @@ impl TryAlloc for BlockAllocator<BuildingEncryptedTxBatch<WithEncryptedTxs>> {
         &mut self,
         resource_required: Self::Resources<'_>,
     ) -> Result<(), AllocFailure> {
-        self.encrypted_txs.space.try_dump(resource_required.tx)?;
-        self.encrypted_txs.gas.try_dump(resource_required.gas)
+        self.encrypted_txs
+            .try_dump(resource_required.tx, resource_required.gas)
     }
 }
 
@@ -0,0 +1,84 @@
+//! Cached index of epoch start heights.
+//!
+//! Epoch lookups by block height are performed constantly while processing
+//! evidence and validating vote extensions. [`Epochs::get_epoch`] walks the
+//! list of predecessor epochs linearly, so the [`Shell`](super::Shell)
+//! keeps an [`EpochCache`] around, which is refreshed on epoch transitions
+//! and answers lookups with a binary search.
+
+use namada::types::storage::{BlockHeight, Epoch, Epochs};
+
+/// Index of the first block heights of each known epoch.
+#[derive(Debug, Default, Clone)]
+pub struct EpochCache {
+    /// The block heights of the first block of each known epoch, in
+    /// ascending order. The index of each height is its epoch.
+    first_block_heights: Vec<BlockHeight>,
+}
+
+impl EpochCache {
+    /// Build a new [`EpochCache`] from the given predecessor epochs.
+    pub fn new(pred_epochs: &Epochs) -> Self {
+        let mut cache = Self::default();
+        cache.refresh(pred_epochs);
+        cache
+    }
+
+    /// Synchronize the cache with the given predecessor epochs. This
+    /// should be called whenever a new epoch begins.
+    pub fn refresh(&mut self, pred_epochs: &Epochs) {
+        if self.is_synced_with(pred_epochs) {
+            return;
+        }
+        self.first_block_heights.clear();
+        self.first_block_heights
+            .extend_from_slice(pred_epochs.first_block_heights());
+    }
+
+    /// Check if the cache contains the same epochs as `pred_epochs`.
+    #[inline]
+    pub fn is_synced_with(&self, pred_epochs: &Epochs) -> bool {
+        let heights = pred_epochs.first_block_heights();
+        self.first_block_heights.len() == heights.len()
+            && self.first_block_heights.last() == heights.last()
+    }
+
+    /// Look up the epoch of a given block height. Follows the same
+    /// semantics as [`Epochs::get_epoch`], i.e. heights past the start of
+    /// the last known epoch resolve to the last known epoch.
+    pub fn get_epoch(&self, height: BlockHeight) -> Option<Epoch> {
+        if self.first_block_heights.is_empty() {
+            return None;
+        }
+        // the first epoch is returned for any height
+        // prior to the start of the second epoch
+        let next_epochs = &self.first_block_heights[1..];
+        let index = next_epochs.partition_point(|start| *start <= height);
+        Some(Epoch(index as u64))
+    }
+}
+
+#[cfg(test)]
+mod test_epoch_cache {
+    use super::*;
+
+    /// Test that cached lookups agree with [`Epochs::get_epoch`].
+    #[test]
+    fn test_cached_lookups_match_epochs() {
+        let mut pred_epochs = Epochs::default();
+        let mut cache = EpochCache::new(&pred_epochs);
+        assert_eq!(cache.get_epoch(BlockHeight(0)), None);
+
+        for start in [0, 10, 11, 25, 40] {
+            pred_epochs.new_epoch(BlockHeight(start));
+        }
+        assert!(!cache.is_synced_with(&pred_epochs));
+        cache.refresh(&pred_epochs);
+        assert!(cache.is_synced_with(&pred_epochs));
+
+        for height in 0..60 {
+            let height = BlockHeight(height);
+            assert_eq!(cache.get_epoch(height), pred_epochs.get_epoch(height));
+        }
+    }
+}
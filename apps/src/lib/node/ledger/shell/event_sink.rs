@@ -0,0 +1,421 @@
+//! Opt-in publisher of the events emitted by the ledger to an external
+//! message broker (NATS or Kafka), in addition to the in-memory
+//! [`EventLog`].
+//!
+//! The events of each finalized block, followed by a summary of the block,
+//! are handed over to a background thread, which publishes them to the
+//! `<prefix>.events` and `<prefix>.blocks` topics, respectively. The queue
+//! between the ledger and the publisher is bounded. With at-least-once
+//! delivery, publishing is retried until the broker acknowledges the
+//! events, and the ledger blocks once the queue is full, applying
+//! backpressure. With at-most-once delivery, the events of a block are
+//! dropped instead.
+//!
+//! Support for each broker is gated behind the `event-sink-nats` and
+//! `event-sink-kafka` features.
+//!
+//! [`EventLog`]: namada::ledger::events::log::EventLog
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use namada::ledger::events::{Event, EventLevel};
+use namada::types::storage::{BlockHeight, Epoch};
+use serde::Serialize;
+
+use crate::config::{DeliveryGuarantee, EventSink, EventSinkBackend};
+
+/// The upper bound on the backoff between consecutive attempts to
+/// publish the events of a block.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// An error raised by the client of a message broker.
+type PublishError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Summary of a finalized block, published after its events.
+#[derive(Debug, Serialize)]
+struct BlockSummary {
+    height: u64,
+    epoch: u64,
+    num_events: usize,
+    num_tx_events: usize,
+}
+
+/// An event emitted by the ledger, in the format it is published in.
+#[derive(Debug, Serialize)]
+struct PublishedEvent<'a> {
+    height: u64,
+    event_type: String,
+    level: &'static str,
+    attributes: &'a HashMap<String, String>,
+}
+
+/// The events of a finalized block, queued for publishing.
+#[derive(Debug)]
+struct BlockEvents {
+    summary: BlockSummary,
+    events: Vec<Event>,
+}
+
+/// The client of some message broker.
+trait Publisher: Send {
+    /// Publish `payload` to the given `topic`.
+    fn publish(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+    ) -> Result<(), PublishError>;
+
+    /// Wait until all the published messages have been received
+    /// by the broker.
+    fn flush(&mut self) -> Result<(), PublishError>;
+}
+
+/// Publisher of the events emitted by the ledger to an external
+/// message broker.
+#[derive(Debug)]
+pub struct EventPublisher {
+    /// Queue of blocks whose events are yet to be published.
+    sender: SyncSender<BlockEvents>,
+    /// The delivery guarantees of the published events.
+    delivery: DeliveryGuarantee,
+}
+
+impl EventPublisher {
+    /// Connect to the brokers in the given config, and start publishing
+    /// events in a background thread.
+    pub fn start(config: &EventSink) -> Result<Self, String> {
+        let publisher = connect(config)?;
+        let (sender, receiver) =
+            mpsc::sync_channel(config.queue_capacity.get());
+        let topics = Topics::new(&config.topic_prefix);
+        let delivery = config.delivery;
+        thread::Builder::new()
+            .name("event-sink".into())
+            .spawn(move || run(publisher, receiver, topics, delivery))
+            .map_err(|err| {
+                format!("Failed to spawn the event publisher thread: {err}")
+            })?;
+        Ok(Self { sender, delivery })
+    }
+
+    /// Queue the events of a finalized block for publishing.
+    pub fn publish_block(
+        &self,
+        height: BlockHeight,
+        epoch: Epoch,
+        events: &[Event],
+    ) {
+        let block = BlockEvents {
+            summary: BlockSummary {
+                height: height.0,
+                epoch: epoch.0,
+                num_events: events.len(),
+                num_tx_events: events
+                    .iter()
+                    .filter(|event| event.level == EventLevel::Tx)
+                    .count(),
+            },
+            events: events.to_vec(),
+        };
+        let result = match self.delivery {
+            DeliveryGuarantee::AtLeastOnce => {
+                self.sender.send(block).map_err(|mpsc::SendError(block)| {
+                    TrySendError::Disconnected(block)
+                })
+            }
+            DeliveryGuarantee::AtMostOnce => self.sender.try_send(block),
+        };
+        match result {
+            Ok(()) => {}
+            Err(TrySendError::Full(block)) => {
+                tracing::warn!(
+                    height = block.summary.height,
+                    "The event publisher is lagging behind, dropping the \
+                     events of a block"
+                );
+            }
+            Err(TrySendError::Disconnected(block)) => {
+                tracing::error!(
+                    height = block.summary.height,
+                    "The event publisher is no longer running, dropping the \
+                     events of a block"
+                );
+            }
+        }
+    }
+}
+
+/// The topics events are published to.
+#[derive(Debug)]
+struct Topics {
+    events: String,
+    blocks: String,
+}
+
+impl Topics {
+    fn new(prefix: &str) -> Self {
+        Self {
+            events: format!("{prefix}.events"),
+            blocks: format!("{prefix}.blocks"),
+        }
+    }
+}
+
+/// Publish the events of each block received from `receiver`, until the
+/// ledger shuts down.
+fn run(
+    mut publisher: Box<dyn Publisher>,
+    receiver: Receiver<BlockEvents>,
+    topics: Topics,
+    delivery: DeliveryGuarantee,
+) {
+    for block in receiver {
+        let mut backoff = Duration::from_secs(1);
+        while let Err(err) =
+            publish_block(publisher.as_mut(), &topics, &block, delivery)
+        {
+            if delivery == DeliveryGuarantee::AtMostOnce {
+                tracing::warn!(
+                    height = block.summary.height,
+                    %err,
+                    "Failed to publish the events of a block, dropping them"
+                );
+                break;
+            }
+            tracing::warn!(
+                height = block.summary.height,
+                %err,
+                "Failed to publish the events of a block, retrying in {}s",
+                backoff.as_secs()
+            );
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+    tracing::info!("The event publisher is no longer running");
+}
+
+/// Publish the events of a block, followed by its summary.
+fn publish_block(
+    publisher: &mut dyn Publisher,
+    topics: &Topics,
+    block: &BlockEvents,
+    delivery: DeliveryGuarantee,
+) -> Result<(), PublishError> {
+    for event in &block.events {
+        let event = PublishedEvent {
+            height: block.summary.height,
+            event_type: event.event_type.to_string(),
+            level: match event.level {
+                EventLevel::Block => "block",
+                EventLevel::Tx => "tx",
+            },
+            attributes: &event.attributes,
+        };
+        publisher.publish(&topics.events, &serde_json::to_vec(&event)?)?;
+    }
+    publisher.publish(&topics.blocks, &serde_json::to_vec(&block.summary)?)?;
+    if delivery == DeliveryGuarantee::AtLeastOnce {
+        publisher.flush()?;
+    }
+    Ok(())
+}
+
+/// Connect to the brokers in the given config.
+fn connect(config: &EventSink) -> Result<Box<dyn Publisher>, String> {
+    match config.backend {
+        #[cfg(feature = "event-sink-nats")]
+        EventSinkBackend::Nats => nats_publisher::connect(config),
+        #[cfg(feature = "event-sink-kafka")]
+        EventSinkBackend::Kafka => kafka_publisher::connect(config),
+        #[allow(unreachable_patterns)]
+        backend => Err(format!(
+            "This node was built without support for publishing events to \
+             {backend:?}"
+        )),
+    }
+}
+
+#[cfg(feature = "event-sink-nats")]
+mod nats_publisher {
+    use super::*;
+
+    impl Publisher for nats::Connection {
+        fn publish(
+            &mut self,
+            topic: &str,
+            payload: &[u8],
+        ) -> Result<(), PublishError> {
+            nats::Connection::publish(self, topic, payload)?;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), PublishError> {
+            nats::Connection::flush(self)?;
+            Ok(())
+        }
+    }
+
+    /// Connect to the given NATS servers.
+    pub fn connect(config: &EventSink) -> Result<Box<dyn Publisher>, String> {
+        let connection = nats::connect(config.brokers.join(",").as_str())
+            .map_err(|err| {
+                format!("Failed to connect to the NATS servers: {err}")
+            })?;
+        Ok(Box::new(connection))
+    }
+}
+
+#[cfg(feature = "event-sink-kafka")]
+mod kafka_publisher {
+    use kafka::producer::{Producer, Record, RequiredAcks};
+
+    use super::*;
+
+    /// Publisher to a Kafka cluster.
+    struct KafkaPublisher(Producer);
+
+    impl Publisher for KafkaPublisher {
+        fn publish(
+            &mut self,
+            topic: &str,
+            payload: &[u8],
+        ) -> Result<(), PublishError> {
+            self.0.send(&Record::from_value(topic, payload))?;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), PublishError> {
+            // NB: records are sent synchronously, waiting
+            // for the acknowledgements of the brokers
+            Ok(())
+        }
+    }
+
+    /// Connect to the given Kafka brokers.
+    pub fn connect(config: &EventSink) -> Result<Box<dyn Publisher>, String> {
+        let required_acks = match config.delivery {
+            DeliveryGuarantee::AtMostOnce => RequiredAcks::None,
+            DeliveryGuarantee::AtLeastOnce => RequiredAcks::All,
+        };
+        let producer = Producer::from_hosts(config.brokers.clone())
+            .with_required_acks(required_acks)
+            .create()
+            .map_err(|err| {
+                format!("Failed to connect to the Kafka brokers: {err}")
+            })?;
+        Ok(Box::new(KafkaPublisher(producer)))
+    }
+}
+
+#[cfg(test)]
+mod test_event_sink {
+    use std::sync::{Arc, Mutex};
+
+    use namada::ledger::events::EventType;
+
+    use super::*;
+
+    /// Publisher which records the published messages, failing
+    /// the first `failures` flushes.
+    #[derive(Clone, Default)]
+    struct MockPublisher {
+        messages: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+        failures: Arc<Mutex<usize>>,
+    }
+
+    impl Publisher for MockPublisher {
+        fn publish(
+            &mut self,
+            topic: &str,
+            payload: &[u8],
+        ) -> Result<(), PublishError> {
+            self.messages
+                .lock()
+                .unwrap()
+                .push((topic.to_string(), serde_json::from_slice(payload)?));
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), PublishError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("broker unavailable".into());
+            }
+            Ok(())
+        }
+    }
+
+    fn block_events() -> BlockEvents {
+        let mut event = Event {
+            event_type: EventType::Applied,
+            level: EventLevel::Tx,
+            attributes: HashMap::new(),
+        };
+        event["hash"] = "abcd".to_string();
+        BlockEvents {
+            summary: BlockSummary {
+                height: 5,
+                epoch: 1,
+                num_events: 1,
+                num_tx_events: 1,
+            },
+            events: vec![event],
+        }
+    }
+
+    /// Test that the events of a block are published before
+    /// its summary.
+    #[test]
+    fn test_publish_block() {
+        let publisher = MockPublisher::default();
+        publish_block(
+            &mut publisher.clone(),
+            &Topics::new("namada"),
+            &block_events(),
+            DeliveryGuarantee::AtLeastOnce,
+        )
+        .unwrap();
+
+        let messages = publisher.messages.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0, "namada.events");
+        assert_eq!(messages[0].1["event_type"], "applied");
+        assert_eq!(messages[0].1["level"], "tx");
+        assert_eq!(messages[0].1["attributes"]["hash"], "abcd");
+        assert_eq!(messages[1].0, "namada.blocks");
+        assert_eq!(messages[1].1["height"], 5);
+    }
+
+    /// Test that a flush failure is only reported with at-least-once
+    /// delivery, such that the events are published again.
+    #[test]
+    fn test_flush_only_with_at_least_once_delivery() {
+        let publisher = MockPublisher::default();
+        *publisher.failures.lock().unwrap() = 1;
+        let topics = Topics::new("namada");
+
+        assert!(
+            publish_block(
+                &mut publisher.clone(),
+                &topics,
+                &block_events(),
+                DeliveryGuarantee::AtMostOnce,
+            )
+            .is_ok()
+        );
+        assert!(
+            publish_block(
+                &mut publisher.clone(),
+                &topics,
+                &block_events(),
+                DeliveryGuarantee::AtLeastOnce,
+            )
+            .is_err()
+        );
+    }
+}
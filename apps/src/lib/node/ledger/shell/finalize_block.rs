@@ -4,7 +4,9 @@ use data_encoding::HEXUPPER;
 use namada::core::ledger::inflation;
 use namada::core::ledger::masp_conversions::update_allowed_conversions;
 use namada::core::ledger::pgf::ADDRESS as pgf_address;
-use namada::ledger::events::EventType;
+use namada::eth_bridge::protocol::transactions;
+use namada::ledger::events::index::{index_block_txs, IndexedTx};
+use namada::ledger::events::{EventLevel, EventType};
 use namada::ledger::gas::{GasMetering, TxGasMeter};
 use namada::ledger::parameters::storage as params_storage;
 use namada::ledger::pos::{namada_proof_of_stake, staking_token_address};
@@ -13,11 +15,15 @@ use namada::ledger::storage::wl_storage::WriteLogAndStorage;
 use namada::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
 use namada::ledger::storage_api::token::credit_tokens;
 use namada::ledger::storage_api::{pgf, StorageRead, StorageWrite};
+use namada::proof_of_stake::parameters::PosParams;
+use namada::proof_of_stake::types::BondId;
 use namada::proof_of_stake::{
     find_validator_by_raw_hash, read_last_block_proposer_address,
-    read_pos_params, read_total_stake, write_last_block_proposer_address,
+    read_pos_params, read_total_stake, write_block_proposer_address,
+    write_last_block_proposer_address,
 };
 use namada::types::dec::Dec;
+use namada::types::hash::Hash;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockResults, Epoch, Header};
 use namada::types::transaction::protocol::{
@@ -25,7 +31,11 @@ use namada::types::transaction::protocol::{
 };
 use namada::types::vote_extensions::ethereum_events::MultiSignedEthEvent;
 
-use super::governance::execute_governance_proposals;
+use super::governance::{
+    emit_voting_started_events, execute_expedited_governance_proposals,
+    execute_governance_proposals, execute_pgf_fundings, governance_tx_events,
+    snapshot_proposals_voting_power,
+};
 use super::*;
 use crate::facade::tendermint::abci::types::{Misbehavior, VoteInfo};
 use crate::node::ledger::shell::stats::InternalStats;
@@ -64,6 +74,9 @@ where
         // Begin the new block and check if a new epoch has begun
         let (height, new_epoch) =
             self.update_state(req.header, req.hash, req.byzantine_validators);
+        if new_epoch {
+            self.refresh_epoch_cache();
+        }
 
         let (current_epoch, _gas) = self.wl_storage.storage.get_current_epoch();
         let update_for_tendermint = matches!(
@@ -96,6 +109,37 @@ where
             update_allowed_conversions(&mut self.wl_storage)?;
 
             execute_governance_proposals(self, &mut response)?;
+            snapshot_proposals_voting_power(self)?;
+            emit_voting_started_events(self, &mut response)?;
+
+            // Apply a change of the slashing parameters passed by governance
+            if let Some(change) =
+                namada_proof_of_stake::apply_pending_slashing_params_change(
+                    &mut self.wl_storage,
+                    current_epoch,
+                )?
+            {
+                tracing::info!(
+                    ?change,
+                    "Applied a change of the PoS slashing parameters"
+                );
+            }
+
+            // Settle the time locks expiring at the new epoch
+            let settled = storage_api::htlc::settle_expired_time_locks(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
+            if !settled.is_empty() {
+                tracing::info!(?settled, "Settled expired time locks");
+            }
+
+            // Refund the Bridge pool transfers cancelled in past epochs
+            transactions::ethereum_events::refund_cancelled_transfers(
+                &mut self.wl_storage,
+                current_epoch,
+            )
+            .map_err(storage_api::Error::new)?;
 
             // Copy the new_epoch + pipeline_len - 1 validator set into
             // new_epoch + pipeline_len
@@ -106,6 +150,23 @@ where
                 current_epoch + pos_params.pipeline_len,
             )?;
 
+            // Apply a change of the maximum number of consensus validators
+            // passed by governance. Invariant: This has to be applied after
+            // `copy_validator_sets_and_positions`, as it resizes the
+            // validator sets at the pipeline epoch.
+            if let Some(max_validator_slots) =
+                namada_proof_of_stake::apply_pending_max_validator_slots_change(
+                    &mut self.wl_storage,
+                    current_epoch,
+                )?
+            {
+                tracing::info!(
+                    max_validator_slots,
+                    "Applied a change of the maximum number of consensus \
+                     validators"
+                );
+            }
+
             // Compute the total stake of the consensus validator set and record
             // it in storage
             namada_proof_of_stake::compute_and_store_total_consensus_stake(
@@ -114,6 +175,10 @@ where
             )?;
         }
 
+        // Tally the expedited governance proposals whose voting window closes
+        // at this block
+        execute_expedited_governance_proposals(self, height, &mut response)?;
+
         // Get the actual votes from cometBFT in the preferred format
         let votes = pos_votes_from_abci(&self.wl_storage, &req.votes);
 
@@ -135,25 +200,47 @@ where
         // Invariant: This has to be applied after
         // `copy_validator_sets_and_positions` if we're starting a new epoch
         if new_epoch {
+            // Invariant: Read the insured bonds before processing the slashes,
+            // such that the insurance pool can compensate their losses.
+            let insured_bonds = namada_proof_of_stake::insured_bond_amounts(
+                &self.wl_storage,
+                current_epoch,
+            )?;
             // Invariant: Process slashes before inflation as they may affect
             // the rewards in the current epoch.
             self.process_slashes();
+            self.process_insurance_payouts(
+                current_epoch,
+                insured_bonds,
+                &mut response,
+            )?;
             self.apply_inflation(current_epoch)?;
+            // Invariant: Charge the premiums of the insured bonds after
+            // applying the inflation, against their rewards of the last
+            // epoch, and before any of these rewards are claimed.
+            namada_proof_of_stake::charge_insurance_premiums(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
+            // Invariant: Pay the continuous PGF fundings after applying the
+            // inflation, such that the PGF inflation of this epoch is
+            // available to them.
+            execute_pgf_fundings(self, &mut response)?;
+            // Invariant: Withdraw the matured unbonds after processing the
+            // slashes, as they may reduce the withdrawn amounts.
+            self.process_auto_withdrawals(current_epoch, &mut response)?;
+            // Invariant: Compound the rewards after applying the inflation,
+            // such that the rewards of the last epoch are included.
+            self.process_auto_compounding(current_epoch, &mut response)?;
         }
 
         // Consensus set liveness check
         if !votes.is_empty() {
             let vote_height = height.prev_height();
-            let epoch_of_votes = self
-                .wl_storage
-                .storage
-                .block
-                .pred_epochs
-                .get_epoch(vote_height)
-                .expect(
-                    "Should always find an epoch when looking up the vote \
-                     height before recording liveness data.",
-                );
+            let epoch_of_votes = self.get_epoch(vote_height).expect(
+                "Should always find an epoch when looking up the vote height \
+                 before recording liveness data.",
+            );
             namada_proof_of_stake::record_liveness_data(
                 &mut self.wl_storage,
                 &votes,
@@ -167,20 +254,33 @@ where
             self.get_validator_set_update_epoch(current_epoch);
 
         // Jail validators for inactivity
-        namada_proof_of_stake::jail_for_liveness(
-            &mut self.wl_storage,
+        self.jail_for_liveness(
             &pos_params,
             current_epoch,
             validator_set_update_epoch,
+            &mut response,
         )?;
 
         if new_epoch {
+            // Jail validators who rarely signed the bridge vote extensions in
+            // the last epoch
+            self.jail_for_bridge_liveness(
+                &pos_params,
+                current_epoch,
+                validator_set_update_epoch,
+                &mut response,
+            )?;
             // Prune liveness data from validators that are no longer in the
             // consensus set
             namada_proof_of_stake::prune_liveness_data(
                 &mut self.wl_storage,
                 current_epoch,
             )?;
+            // Prune the heartbeats of validators older than the last epoch
+            namada_proof_of_stake::prune_validator_heartbeats(
+                &mut self.wl_storage,
+                current_epoch,
+            )?;
         }
 
         let mut stats = InternalStats::default();
@@ -199,6 +299,14 @@ where
         // Tracks the accepted transactions
         self.wl_storage.storage.block.results = BlockResults::default();
         let mut changed_keys = BTreeSet::new();
+        let mut attestations = vec![];
+        let mut misbehavior_evidence = vec![];
+        let mut heartbeats = vec![];
+        let mut bridge_signers = BTreeSet::new();
+        // Links between the wrapper and inner txs of this block, to index them
+        let mut inner_tx_hashes = BTreeMap::new();
+        let mut wrapper_hashes = BTreeMap::new();
+        let first_tx_event = response.events.len();
         for (tx_index, processed_tx) in req.txs.iter().enumerate() {
             let tx = if let Ok(tx) = Tx::try_from(processed_tx.tx.as_ref()) {
                 tx
@@ -269,10 +377,26 @@ where
                 continue;
             }
 
+            // once the chain has been halted, Ethereum events and Bridge
+            // pool roots are no longer applied, nor are the events
+            // dequeued, but their signers still participated in the bridge
+            let halted = match &tx_header.tx_type {
+                TxType::Protocol(protocol_tx) => {
+                    invariants::check_protocol_tx_allowed(
+                        &self.wl_storage,
+                        &protocol_tx.tx,
+                    )?
+                    .err()
+                }
+                _ => None,
+            };
+
             let (mut tx_event, embedding_wrapper, mut tx_gas_meter, wrapper) =
                 match &tx_header.tx_type {
                     TxType::Wrapper(wrapper) => {
                         stats.increment_wrapper_txs();
+                        inner_tx_hashes
+                            .insert(tx.header_hash(), tx.raw_header_hash());
                         let tx_event = Event::new_tx_event(&tx, height.0);
                         let gas_meter = TxGasMeter::new(wrapper.gas_limit);
                         (tx_event, None, gas_meter, Some(tx.clone()))
@@ -286,6 +410,10 @@ where
                             .pop()
                             .expect("Missing wrapper tx in queue");
                         let mut event = Event::new_tx_event(&tx, height.0);
+                        wrapper_hashes.insert(
+                            tx.raw_header_hash(),
+                            tx_in_queue.tx.header_hash(),
+                        );
 
                         match inner {
                             DecryptedTx::Decrypted => {
@@ -329,30 +457,119 @@ where
                         );
                         continue;
                     }
+                    TxType::View => {
+                        tracing::error!(
+                            "Internal logic error: FinalizeBlock received a \
+                             TxType::View transaction"
+                        );
+                        continue;
+                    }
                     TxType::Protocol(protocol_tx) => match protocol_tx.tx {
-                        ProtocolTxType::BridgePoolVext
-                        | ProtocolTxType::BridgePool
-                        | ProtocolTxType::ValSetUpdateVext
+                        ProtocolTxType::ValSetUpdateVext
                         | ProtocolTxType::ValidatorSetUpdate => (
                             Event::new_tx_event(&tx, height.0),
                             None,
                             TxGasMeter::new_from_sub_limit(0.into()),
                             None,
                         ),
+                        ProtocolTxType::BridgePoolVext => {
+                            let ext =
+                            ethereum_tx_data_variants::BridgePoolVext::try_from(
+                                &tx,
+                            )
+                            .unwrap();
+                            bridge_signers.insert((
+                                ext.data.validator_addr,
+                                ext.data.block_height,
+                            ));
+                            (
+                                Event::new_tx_event(&tx, height.0),
+                                None,
+                                TxGasMeter::new_from_sub_limit(0.into()),
+                                None,
+                            )
+                        }
+                        ProtocolTxType::BridgePool => {
+                            let ext =
+                            ethereum_tx_data_variants::BridgePool::try_from(
+                                &tx,
+                            )
+                            .unwrap();
+                            bridge_signers.extend(ext.0.into_iter().map(
+                                |vext| {
+                                    (
+                                        vext.data.validator_addr,
+                                        vext.data.block_height,
+                                    )
+                                },
+                            ));
+                            (
+                                Event::new_tx_event(&tx, height.0),
+                                None,
+                                TxGasMeter::new_from_sub_limit(0.into()),
+                                None,
+                            )
+                        }
+                        ProtocolTxType::AttestationVext => {
+                            let ext =
+                            ethereum_tx_data_variants::AttestationVext::try_from(
+                                &tx,
+                            )
+                            .unwrap();
+                            attestations.push(ext);
+                            (
+                                Event::new_tx_event(&tx, height.0),
+                                None,
+                                TxGasMeter::new_from_sub_limit(0.into()),
+                                None,
+                            )
+                        }
+                        ProtocolTxType::MisbehaviorEvidence => {
+                            let evidence =
+                            ethereum_tx_data_variants::MisbehaviorEvidence::try_from(
+                                &tx,
+                            )
+                            .unwrap();
+                            misbehavior_evidence.push(evidence);
+                            (
+                                Event::new_tx_event(&tx, height.0),
+                                None,
+                                TxGasMeter::new_from_sub_limit(0.into()),
+                                None,
+                            )
+                        }
+                        ProtocolTxType::ValidatorHeartbeat => {
+                            let heartbeat =
+                            ethereum_tx_data_variants::ValidatorHeartbeat::try_from(
+                                &tx,
+                            )
+                            .unwrap();
+                            heartbeats.push(heartbeat);
+                            (
+                                Event::new_tx_event(&tx, height.0),
+                                None,
+                                TxGasMeter::new_from_sub_limit(0.into()),
+                                None,
+                            )
+                        }
                         ProtocolTxType::EthEventsVext => {
                             let ext =
                             ethereum_tx_data_variants::EthEventsVext::try_from(
                                 &tx,
                             )
                             .unwrap();
-                            if self
+                            bridge_signers.insert((
+                                ext.data.validator_addr.clone(),
+                                ext.data.block_height,
+                            ));
+                            let is_signer = self
                                 .mode
                                 .get_validator_address()
                                 .map(|validator| {
                                     validator == &ext.data.validator_addr
                                 })
-                                .unwrap_or(false)
-                            {
+                                .unwrap_or(false);
+                            if halted.is_none() && is_signer {
                                 for event in ext.data.ethereum_events.iter() {
                                     self.mode.dequeue_eth_event(event);
                                 }
@@ -369,9 +586,12 @@ where
                             ethereum_tx_data_variants::EthereumEvents::try_from(
                                 &tx,
                             ).unwrap();
-                            if let Some(address) =
-                                self.mode.get_validator_address().cloned()
-                            {
+                            bridge_signers
+                                .extend(digest.signatures.keys().cloned());
+                            if let (None, Some(address)) = (
+                                &halted,
+                                self.mode.get_validator_address().cloned(),
+                            ) {
                                 let this_signer = &(
                                     address,
                                     self.wl_storage
@@ -396,6 +616,14 @@ where
                     },
                 };
 
+            if let Some(info) = halted {
+                tx_event["code"] = ErrorCodes::ChainHalted.into();
+                tx_event["info"] = info;
+                tx_event["gas_used"] = "0".into();
+                response.events.push(tx_event);
+                continue;
+            }
+
             match protocol::dispatch_tx(
                 tx,
                 processed_tx.tx.as_ref(),
@@ -424,6 +652,16 @@ where
                                 tx: wrapper.expect("Missing expected wrapper"),
                                 gas: tx_gas_meter.get_available_gas(),
                             });
+                            if let Some(audit_log) =
+                                self.storage_audit_log.as_mut()
+                            {
+                                audit_log.record_wrapper_writes(
+                                    height,
+                                    &tx_event["hash"],
+                                    &result.changed_keys,
+                                    &self.wl_storage,
+                                );
+                            }
                         } else {
                             tracing::trace!(
                                 "all VPs accepted transaction {} storage \
@@ -431,12 +669,30 @@ where
                                 tx_event["hash"],
                                 result
                             );
+                            for mut event in governance_tx_events(
+                                &self.wl_storage,
+                                &result.changed_keys,
+                            )? {
+                                event["hash"] = tx_event["hash"].clone();
+                                response.events.push(event);
+                            }
                             changed_keys.append(&mut result.changed_keys);
                             stats.increment_successful_txs();
                             if let Some(wrapper) = embedding_wrapper {
                                 self.commit_inner_tx_hash(wrapper);
                             }
                         }
+                        if let Some(audit_log) = self.storage_audit_log.as_mut()
+                        {
+                            audit_log.record_tx_writes(
+                                height,
+                                &tx_event["hash"],
+                                &self.wl_storage,
+                            );
+                        }
+                        let written_keys =
+                            self.wl_storage.write_log.get_keys_with_precommit();
+                        stats.record_tx_writes(&written_keys);
                         self.wl_storage.commit_tx();
                         if !tx_event.contains_key("code") {
                             tx_event["code"] = ErrorCodes::Ok.into();
@@ -539,6 +795,12 @@ where
                     if let EventType::Accepted = tx_event.event_type {
                         // If wrapper, invalid tx error code
                         tx_event["code"] = ErrorCodes::InvalidTx.into();
+                    } else if let Error::TxApply(
+                        protocol::Error::TxCodeNotAllowed(_, _)
+                        | protocol::Error::TxCodeLockout(_, _),
+                    ) = msg
+                    {
+                        tx_event["code"] = ErrorCodes::TxCodeNotAllowed.into();
                     } else {
                         tx_event["code"] = ErrorCodes::WasmRuntimeError.into();
                     }
@@ -546,6 +808,34 @@ where
             }
             response.events.push(tx_event);
         }
+        self.index_txs(
+            height,
+            &response.events[first_tx_event..],
+            &inner_tx_hashes,
+            &wrapper_hashes,
+        );
+
+        // aggregate the data attested in each lane
+        self.aggregate_attestations(attestations)?;
+
+        // slash the validators whose misbehavior was reported
+        // by external watchtowers
+        self.slash_misbehaving_validators(misbehavior_evidence)?;
+
+        // record the builds attested to by the heartbeats of validators
+        self.record_validator_heartbeats(heartbeats)?;
+
+        // record the validators who signed the bridge vote extensions
+        // included in this block, and the heights they signed them at
+        namada_proof_of_stake::record_bridge_participation(
+            &mut self.wl_storage,
+            current_epoch,
+            &bridge_signers,
+        )?;
+
+        // halt the chain if any of the invariants configured by governance
+        // has been violated
+        self.check_invariants(new_epoch)?;
 
         stats.set_tx_cache_size(
             self.tx_wasm_cache.get_size(),
@@ -556,27 +846,87 @@ where
             self.vp_wasm_cache.get_cache_size(),
         );
 
-        tracing::info!("{}", stats);
-        tracing::info!("{}", stats.format_tx_executed());
+        self.write_hotspots.record_block(
+            height,
+            current_epoch,
+            stats.take_key_prefix_writes(),
+        );
+
+        let crash_reports = self.crash_reports().clone();
+        supervisor::isolate("Stats", &crash_reports, || {
+            tracing::info!("{}", stats);
+            tracing::info!("{}", stats.format_tx_executed());
+        });
 
         if update_for_tendermint {
             self.update_epoch(&mut response);
             // send the latest oracle configs. These may have changed due to
             // governance.
             self.update_eth_oracle(&changed_keys);
+        } else {
+            // send the oracle configs as soon as they change, rather than
+            // waiting for the next epoch
+            self.refresh_eth_oracle(&changed_keys);
         }
+        self.check_eth_oracle_pause_file();
 
+        write_block_proposer_address(
+            &mut self.wl_storage,
+            height,
+            native_block_proposer_address.clone(),
+        )?;
         write_last_block_proposer_address(
             &mut self.wl_storage,
             native_block_proposer_address,
         )?;
 
-        self.event_log_mut().log_events(response.events.clone());
+        // NB: stalled Ethereum events are local to this node, so they are
+        // only logged and published, rather than returned to CometBFT
+        let mut events = response.events.clone();
+        events.extend(self.report_stalled_eth_events());
+        supervisor::isolate("Event log", &crash_reports, || {
+            self.event_log_mut().log_events(height, events.iter().cloned())
+        });
+        if let Some(publisher) = self.event_publisher.as_ref() {
+            supervisor::isolate("Event sink", &crash_reports, || {
+                publisher.publish_block(height, current_epoch, &events)
+            });
+        }
         tracing::debug!("End finalize_block {height} of epoch {current_epoch}");
 
+        #[cfg(feature = "pos-invariants")]
+        self.check_pos_invariants();
+
         Ok(response)
     }
 
+    /// Check the invariants of the PoS system at the end of a block, halting
+    /// the node with a report of the violations, if any.
+    #[cfg(feature = "pos-invariants")]
+    fn check_pos_invariants(&self) {
+        let height = self.wl_storage.storage.block.height;
+        let current_epoch = self.wl_storage.storage.block.epoch;
+        let violations = namada_proof_of_stake::invariants::check_invariants(
+            &self.wl_storage,
+            current_epoch,
+        )
+        .expect("Must be able to check the PoS invariants");
+        if violations.is_empty() {
+            return;
+        }
+        let report = violations
+            .iter()
+            .map(|violation| format!(" - {violation}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        tracing::error!(
+            %height,
+            %current_epoch,
+            "PoS invariants violated at the end of the block:\n{report}"
+        );
+        panic!("PoS invariants violated at height {height}:\n{report}");
+    }
+
     /// Sets the metadata necessary for a new block, including
     /// the hash, height, validator changes, and evidence of
     /// byzantine behavior. Applies slashes if necessary.
@@ -627,6 +977,227 @@ where
                 }
             })
             .expect("Must be able to update validator set");
+        self.emit_validator_set_changes(response)
+            .expect("Must be able to diff the validator sets");
+    }
+
+    /// Emit an event for each change of the consensus validator set from the
+    /// current epoch to the next one, matching the validator set update
+    /// communicated to Tendermint.
+    fn emit_validator_set_changes(
+        &self,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let current_epoch = self.wl_storage.storage.block.epoch;
+        let next_epoch = current_epoch.next();
+        let diff = namada_proof_of_stake::consensus_validator_set_diff(
+            &self.wl_storage,
+            current_epoch,
+            next_epoch,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        let changes = diff
+            .joined
+            .into_iter()
+            .map(|(validator, stake)| {
+                (validator, "joined", token::Amount::zero(), stake)
+            })
+            .chain(diff.left.into_iter().map(|(validator, stake)| {
+                (validator, "left", stake, token::Amount::zero())
+            }))
+            .chain(diff.changed.into_iter().map(
+                |(validator, (old_stake, new_stake))| {
+                    (validator, "changed", old_stake, new_stake)
+                },
+            ));
+        for (validator, change, old_stake, new_stake) in changes {
+            let mut event = Event {
+                event_type: EventType::ValidatorSetChange,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["validator"] = validator.to_string();
+            event["change"] = change.to_string();
+            event["old_stake"] = old_stake.to_string_native();
+            event["new_stake"] = new_stake.to_string_native();
+            event["epoch"] = next_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// Withdraw the unbonds that were scheduled for automatic withdrawal at
+    /// the start of the current epoch, emitting an event for each
+    /// withdrawal.
+    fn process_auto_withdrawals(
+        &mut self,
+        current_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let withdrawals = namada_proof_of_stake::process_auto_withdrawals(
+            &mut self.wl_storage,
+            current_epoch,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        for (bond_id, amount) in withdrawals {
+            tracing::info!(
+                "Automatically withdrew {} unbonded from {} to {}",
+                amount.to_string_native(),
+                bond_id.validator,
+                bond_id.source,
+            );
+            let mut event = Event {
+                event_type: EventType::AutoWithdrawal,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["source"] = bond_id.source.to_string();
+            event["validator"] = bond_id.validator.to_string();
+            event["amount"] = amount.to_string_native();
+            event["epoch"] = current_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// Compensate the insured bonds for the tokens slashed from them from the
+    /// slashing insurance pool, emitting an event for each payout.
+    fn process_insurance_payouts(
+        &mut self,
+        current_epoch: Epoch,
+        insured_bonds: BTreeMap<BondId, token::Amount>,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let payouts = namada_proof_of_stake::pay_insurance_claims(
+            &mut self.wl_storage,
+            current_epoch,
+            insured_bonds,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        for (bond_id, amount) in payouts {
+            tracing::info!(
+                "Paid out {} from the slashing insurance pool to {} for the \
+                 slashes of {}",
+                amount.to_string_native(),
+                bond_id.source,
+                bond_id.validator,
+            );
+            let mut event = Event {
+                event_type: EventType::InsurancePayout,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["source"] = bond_id.source.to_string();
+            event["validator"] = bond_id.validator.to_string();
+            event["amount"] = amount.to_string_native();
+            event["epoch"] = current_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// Add the rewards of the bonds that opted into automatic compounding to
+    /// the bonds, emitting an event for each compounded bond.
+    fn process_auto_compounding(
+        &mut self,
+        current_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let compounded = namada_proof_of_stake::process_auto_compounding(
+            &mut self.wl_storage,
+            current_epoch,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        for (bond_id, amount) in compounded {
+            tracing::info!(
+                "Automatically compounded {} of rewards from {} to {}",
+                amount.to_string_native(),
+                bond_id.source,
+                bond_id.validator,
+            );
+            let mut event = Event {
+                event_type: EventType::AutoCompound,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["source"] = bond_id.source.to_string();
+            event["validator"] = bond_id.validator.to_string();
+            event["amount"] = amount.to_string_native();
+            event["epoch"] = current_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// Jail the validators who missed too many votes in the liveness window,
+    /// from the given `jail_epoch`, emitting an event for each newly jailed
+    /// validator.
+    fn jail_for_liveness(
+        &mut self,
+        pos_params: &PosParams,
+        current_epoch: Epoch,
+        jail_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let jailed = namada_proof_of_stake::jail_for_liveness(
+            &mut self.wl_storage,
+            pos_params,
+            current_epoch,
+            jail_epoch,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        for (validator, missed_votes) in jailed {
+            let mut event = Event {
+                event_type: EventType::ValidatorJailed,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["validator"] = validator.to_string();
+            event["missed_votes"] = missed_votes.to_string();
+            event["liveness_window"] =
+                pos_params.liveness_window_check.to_string();
+            event["jail_epoch"] = jail_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
+    }
+
+    /// Jail the consensus validators of the last epoch who signed the bridge
+    /// vote extensions in too few of its blocks, from the given
+    /// `jail_epoch`, emitting an event for each newly jailed validator.
+    fn jail_for_bridge_liveness(
+        &mut self,
+        pos_params: &PosParams,
+        current_epoch: Epoch,
+        jail_epoch: Epoch,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Result<()> {
+        let jailed = namada_proof_of_stake::jail_for_bridge_liveness(
+            &mut self.wl_storage,
+            pos_params,
+            current_epoch,
+            jail_epoch,
+        )?;
+        let height = self.wl_storage.storage.block.height;
+        for (validator, participation) in jailed {
+            let mut event = Event {
+                event_type: EventType::ValidatorJailed,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["validator"] = validator.to_string();
+            event["reason"] = "bridge_liveness".to_string();
+            event["bridge_participation"] = participation.to_string();
+            event["jail_epoch"] = jail_epoch.to_string();
+            event["height"] = height.to_string();
+            response.events.push(event);
+        }
+        Ok(())
     }
 
     /// Calculate the new inflation rate, mint the new tokens to the PoS
@@ -745,34 +1316,6 @@ where
             pgf_inflation_amount.to_string_native()
         );
 
-        let mut pgf_fundings = pgf::get_payments(&self.wl_storage)?;
-        // we want to pay first the oldest fundings
-        pgf_fundings.sort_by(|a, b| a.id.cmp(&b.id));
-
-        for funding in pgf_fundings {
-            if storage_api::token::transfer(
-                &mut self.wl_storage,
-                &staking_token,
-                &pgf_address,
-                &funding.detail.target,
-                funding.detail.amount,
-            )
-            .is_ok()
-            {
-                tracing::info!(
-                    "Paying {} tokens for {} project.",
-                    funding.detail.amount.to_string_native(),
-                    &funding.detail.target,
-                );
-            } else {
-                tracing::warn!(
-                    "Failed to pay {} tokens for {} project.",
-                    funding.detail.amount.to_string_native(),
-                    &funding.detail.target,
-                );
-            }
-        }
-
         // Pgf steward inflation
         let stewards = pgf::get_stewards(&self.wl_storage)?;
         let pgf_stewards_pd_rate =
@@ -854,6 +1397,61 @@ where
         Ok(())
     }
 
+    /// Queue the results of the txs of this block, indexed from the events
+    /// they emitted and linked to their wrapper or inner tx, to be written
+    /// to the index of applied txs when the block is committed.
+    ///
+    /// The first successful result of a tx is never overwritten, such that
+    /// e.g. a rejected replay of a tx doesn't hide its original result.
+    fn index_txs(
+        &mut self,
+        height: BlockHeight,
+        tx_events: &[Event],
+        inner_tx_hashes: &BTreeMap<Hash, Hash>,
+        wrapper_hashes: &BTreeMap<Hash, Hash>,
+    ) {
+        for mut indexed_tx in index_block_txs(height, tx_events) {
+            if self.is_indexed_as_successful(&indexed_tx.hash) {
+                tracing::debug!(
+                    hash = %indexed_tx.hash,
+                    "Keeping the indexed result of a successful tx"
+                );
+                continue;
+            }
+            if indexed_tx.is_wrapper() {
+                indexed_tx.inner_tx_hash =
+                    inner_tx_hashes.get(&indexed_tx.hash).copied();
+            } else {
+                indexed_tx.wrapper_hash =
+                    wrapper_hashes.get(&indexed_tx.hash).copied();
+            }
+            self.wl_storage
+                .storage
+                .indexed_txs
+                .push((indexed_tx.hash, indexed_tx.serialize_to_vec()));
+        }
+    }
+
+    /// Check if a successful result of the tx with the given hash was
+    /// already indexed, or is queued to be.
+    fn is_indexed_as_successful(&self, hash: &Hash) -> bool {
+        let is_successful = |bytes: &[u8]| {
+            IndexedTx::try_from_slice(bytes)
+                .map(|indexed_tx| indexed_tx.is_successful())
+                .unwrap_or_default()
+        };
+        let storage = &self.wl_storage.storage;
+        storage
+            .indexed_txs
+            .iter()
+            .any(|(queued, bytes)| queued == hash && is_successful(bytes))
+            || storage
+                .db
+                .read_indexed_tx(hash)
+                .expect("Reading the index of applied txs shouldn't fail")
+                .is_some_and(|bytes| is_successful(&bytes))
+    }
+
     // Write the inner tx hash to storage and remove the corresponding wrapper
     // hash since it's redundant (we check the inner tx hash too when validating
     // the wrapper). Requires the wrapper transaction as argument to recover
@@ -942,21 +1540,45 @@ mod test_finalize_block {
 
     use data_encoding::HEXUPPER;
     use namada::core::ledger::eth_bridge::storage::wrapped_erc20s;
-    use namada::core::ledger::governance::storage::keys::get_proposal_execution_key;
-    use namada::core::ledger::governance::storage::proposal::ProposalType;
+    use namada::core::ledger::governance::cli::onchain::ProposalVote;
+    use namada::core::ledger::governance::storage::keys::{
+        get_author_key, get_committing_proposals_key, get_consensus_params_key,
+        get_expedited_proposal_voting_period_key, get_funds_key,
+        get_max_proposal_execution_gas_key, get_max_proposal_period_key,
+        get_min_expedited_proposal_fund_key,
+        get_min_proposal_execution_delay_key, get_min_proposal_grace_epoch_key,
+        get_min_proposal_voting_period_key, get_min_text_proposal_fund_key,
+        get_proposal_execution_failed_key, get_proposal_execution_key,
+        get_proposal_result_key, get_proposal_stake_snapshot_key,
+        get_proposal_withdrawal_refund_key, get_starting_proposals_key,
+        get_veto_threshold_key, get_vote_proposal_key,
+    };
+    use namada::core::ledger::governance::storage::proposal::{
+        AddRemove, BlockParamsUpdate, CodeAllowlistUpdate, ConsensusParams,
+        ConsensusParamsUpdate, EvidenceParamsUpdate, PGFTarget,
+        ParameterChange, ProposalType, StoragePgfFunding, TreasurySpend,
+    };
     use namada::core::ledger::governance::storage::vote::{
         StorageProposalVote, VoteType,
     };
+    use namada::core::ledger::governance::utils::{
+        ProposalResult, TallyResult,
+    };
+    use namada::core::ledger::governance::ADDRESS as gov_address;
+    use namada::core::ledger::pgf::storage::keys as pgf_storage;
     use namada::core::ledger::replay_protection;
     use namada::core::types::storage::KeySeg;
     use namada::eth_bridge::storage::bridge_pool::{
         self, get_key_from_hash, get_nonce_key, get_signed_root_key,
     };
-    use namada::eth_bridge::storage::min_confirmations_key;
+    use namada::eth_bridge::storage::{
+        min_confirmations_key, oracle_paused_key,
+    };
+    use namada::ledger::events::index::IndexedTx;
     use namada::ledger::gas::VpGasMeter;
     use namada::ledger::native_vp::parameters::ParametersVp;
     use namada::ledger::native_vp::NativeVp;
-    use namada::ledger::parameters::EpochDuration;
+    use namada::ledger::parameters::{self, EpochDuration};
     use namada::ledger::pos::PosQueries;
     use namada::ledger::storage_api;
     use namada::ledger::storage_api::StorageWrite;
@@ -968,6 +1590,7 @@ mod test_finalize_block {
     };
     use namada::proof_of_stake::{
         enqueued_slashes_handle, get_num_consensus_validators,
+        read_block_proposer_address,
         read_consensus_validator_set_addresses_with_stake,
         read_validator_stake, rewards_accumulator_handle, unjail_validator,
         validator_consensus_key_handle, validator_rewards_products_handle,
@@ -981,21 +1604,24 @@ mod test_finalize_block {
     use namada::types::keccak::KeccakHash;
     use namada::types::key::testing::common_sk_from_simple_seed;
     use namada::types::key::tm_consensus_key_raw_hash;
-    use namada::types::storage::Epoch;
+    use namada::types::storage::{Epoch, Key};
     use namada::types::time::{DateTimeUtc, DurationSecs};
     use namada::types::token::{Amount, NATIVE_MAX_DECIMAL_PLACES};
     use namada::types::transaction::governance::{
-        InitProposalData, VoteProposalData,
+        InitProposalData, VoteProposalData, WithdrawProposalData,
     };
     use namada::types::transaction::protocol::EthereumTxData;
     use namada::types::transaction::{Fee, WrapperTx};
     use namada::types::uint::Uint;
     use namada::types::vote_extensions::ethereum_events;
+    use namada::types::vote_extensions::validator_set_update::VotingPowersMapExt;
+    use namada::types::voting_power::FractionalVotingPower;
     use namada_sdk::eth_bridge::MinimumConfirmations;
     use namada_sdk::proof_of_stake::{
         liveness_missed_votes_handle, liveness_sum_missed_votes_handle,
         read_consensus_validator_set_addresses,
     };
+    use namada_sdk::tx::TX_INIT_PROPOSAL;
     use namada_test_utils::tx_data::TxWriteData;
     use namada_test_utils::TestWasms;
     use test_log::test;
@@ -1005,6 +1631,8 @@ mod test_finalize_block {
         Misbehavior, Validator, VoteInfo,
     };
     use crate::node::ledger::oracle::control::Command;
+    use crate::node::ledger::shell::governance::check_init_proposal_tx;
+    use crate::node::ledger::shell::invariants;
     use crate::node::ledger::shell::test_utils::*;
     use crate::node::ledger::shims::abcipp_shim_types::shim::request::{
         FinalizeBlock, ProcessedTx,
@@ -1351,7 +1979,95 @@ mod test_finalize_block {
         assert_eq!(counter, 2);
     }
 
-    /// Test if a rejected protocol tx is applied and emits
+    /// Test that the results of the txs of a block are indexed by their hash
+    /// on commit, with a wrapper tx resolving to the result of its inner tx
+    /// once it's applied.
+    #[test]
+    fn test_indexed_txs() {
+        let (mut shell, _, _, _) = setup();
+        let keypair = gen_keypair();
+        let balance_key = token::balance_key(
+            &shell.wl_storage.storage.native_token,
+            &Address::from(&keypair.ref_to()),
+        );
+        shell
+            .wl_storage
+            .storage
+            .write(&balance_key, Amount::native_whole(1000).serialize_to_vec())
+            .unwrap();
+
+        let (mut wrapper, processed_wrapper) = mk_wrapper_tx(&shell, &keypair);
+        let wrapper_hash = wrapper.header_hash();
+        let inner_tx_hash = wrapper.raw_header_hash();
+        let query = |shell: &TestShell, hash: &Hash| {
+            let response = shell.query(request::Query {
+                data: Default::default(),
+                path: format!("/shell/tx/{hash}"),
+                height: Default::default(),
+                prove: false,
+            });
+            assert!(response.code.is_ok());
+            Option::<IndexedTx>::try_from_slice(&response.value).unwrap()
+        };
+
+        // txs are only indexed once their block is committed
+        shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![processed_wrapper],
+                ..Default::default()
+            })
+            .expect("Test failed");
+        assert!(query(&shell, &wrapper_hash).is_none());
+        shell.commit();
+        let height = shell.wl_storage.storage.get_last_block_height();
+        let indexed_wrapper =
+            query(&shell, &wrapper_hash).expect("Test failed");
+        assert!(indexed_wrapper.is_wrapper());
+        assert_eq!(indexed_wrapper.height, height);
+        assert_eq!(indexed_wrapper.code, u32::from(ErrorCodes::Ok));
+        assert_eq!(indexed_wrapper.inner_tx_hash, Some(inner_tx_hash));
+        assert_eq!(indexed_wrapper.events.len(), 1);
+        assert!(query(&shell, &inner_tx_hash).is_none());
+
+        // once the inner tx is applied, both hashes resolve to its result
+        wrapper.update_header(TxType::Decrypted(DecryptedTx::Decrypted));
+        shell.finalize_and_commit(Some(FinalizeBlock {
+            txs: vec![ProcessedTx {
+                tx: wrapper.to_bytes().into(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            }],
+            ..Default::default()
+        }));
+        let indexed_tx = query(&shell, &inner_tx_hash).expect("Test failed");
+        assert!(!indexed_tx.is_wrapper());
+        assert_eq!(indexed_tx.hash, inner_tx_hash);
+        assert_eq!(indexed_tx.height, height.next_height());
+        assert_eq!(indexed_tx.wrapper_hash, Some(wrapper_hash));
+        assert_eq!(
+            indexed_tx.events.last().map(|event| &event["hash"]),
+            Some(&inner_tx_hash.to_string())
+        );
+        assert_eq!(query(&shell, &wrapper_hash), Some(indexed_tx.clone()));
+        assert!(query(&shell, &Hash::default()).is_none());
+
+        // a replay of the inner tx doesn't overwrite its successful result
+        shell.finalize_and_commit(Some(FinalizeBlock {
+            txs: vec![ProcessedTx {
+                tx: wrapper.to_bytes().into(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            }],
+            ..Default::default()
+        }));
+        assert_eq!(query(&shell, &inner_tx_hash), Some(indexed_tx));
+    }
+
+    /// Test if a rejected protocol tx is applied and emits
     /// the correct event
     #[test]
     fn test_rejected_protocol_tx() {
@@ -1734,7 +2450,6 @@ mod test_finalize_block {
         // Add a proposal to be executed on next epoch change.
         let mut add_proposal = |proposal_id, vote| {
             let validator = shell.mode.get_validator_address().unwrap().clone();
-            shell.proposal_data.insert(proposal_id);
 
             let proposal = InitProposalData {
                 id: Some(proposal_id),
@@ -1743,6 +2458,7 @@ mod test_finalize_block {
                 voting_start_epoch: Epoch::default(),
                 voting_end_epoch: Epoch::default().next(),
                 grace_epoch: Epoch::default().next(),
+                expedited: false,
                 r#type: ProposalType::Default(None),
             };
 
@@ -1766,102 +2482,1559 @@ mod test_finalize_block {
                 .unwrap();
         };
 
-        // Add a proposal to be accepted and one to be rejected.
-        add_proposal(0, StorageProposalVote::Yay(VoteType::Default));
-        add_proposal(1, StorageProposalVote::Nay);
+        // Add a proposal to be accepted and one to be rejected.
+        add_proposal(0, StorageProposalVote::Yay(VoteType::Default));
+        add_proposal(1, StorageProposalVote::Nay);
+
+        // Commit the genesis state
+        shell.wl_storage.commit_block().unwrap();
+        shell.commit();
+
+        // Collect all storage key-vals into a sorted map
+        let store_block_state = |shell: &TestShell| -> BTreeMap<_, _> {
+            shell
+                .wl_storage
+                .storage
+                .db
+                .iter_prefix(None)
+                .map(|(key, val, _gas)| (key, val))
+                .collect()
+        };
+
+        // Store the full state in sorted map
+        let mut last_storage_state: std::collections::BTreeMap<
+            String,
+            Vec<u8>,
+        > = store_block_state(&shell);
+
+        // Keep applying finalize block
+        let validator = shell.mode.get_validator_address().unwrap();
+        let pos_params =
+            namada_proof_of_stake::read_pos_params(&shell.wl_storage).unwrap();
+        let consensus_key =
+            namada_proof_of_stake::validator_consensus_key_handle(validator)
+                .get(&shell.wl_storage, Epoch::default(), &pos_params)
+                .unwrap()
+                .unwrap();
+        let proposer_address = HEXUPPER
+            .decode(consensus_key.tm_raw_hash().as_bytes())
+            .unwrap();
+        let val_stake = read_validator_stake(
+            &shell.wl_storage,
+            &pos_params,
+            validator,
+            Epoch::default(),
+        )
+        .unwrap();
+
+        let votes = vec![VoteInfo {
+            validator: Validator {
+                address: proposer_address.clone().try_into().unwrap(),
+                power: (u128::try_from(val_stake).expect("Test failed") as u64)
+                    .try_into()
+                    .unwrap(),
+            },
+            sig_info: tendermint::abci::types::BlockSignatureInfo::LegacySigned,
+        }];
+
+        // Need to supply a proposer address and votes to flow through the
+        // inflation code
+        for _ in 0..20 {
+            // Add some txs
+            let mut txs = vec![];
+            // create two decrypted txs
+            for _ in 0..2 {
+                txs.push(mk_decrypted_tx(&mut shell, &txs_key));
+            }
+            // create two wrapper txs
+            for _ in 0..2 {
+                let (_tx, processed_tx) = mk_wrapper_tx(&shell, &txs_key);
+                txs.push(processed_tx);
+            }
+
+            let req = FinalizeBlock {
+                txs,
+                proposer_address: proposer_address.clone(),
+                votes: votes.clone(),
+                ..Default::default()
+            };
+            // merkle tree root before finalize_block
+            let root_pre = shell.shell.wl_storage.storage.block.tree.root();
+
+            let _events = shell.finalize_block(req).unwrap();
+
+            // the merkle tree root should not change after finalize_block
+            let root_post = shell.shell.wl_storage.storage.block.tree.root();
+            assert_eq!(root_pre.0, root_post.0);
+            let new_state = store_block_state(&shell);
+            // The new state must be unchanged
+            itertools::assert_equal(
+                last_storage_state.iter(),
+                new_state.iter(),
+            );
+            // Commit the block to move on to the next one
+            shell.wl_storage.commit_block().unwrap();
+
+            // Store the state after commit for the next iteration
+            last_storage_state = store_block_state(&shell);
+        }
+    }
+
+    /// Test that the proposer of each finalized block is recorded in
+    /// storage, under the height of the block.
+    #[test]
+    fn test_block_proposer_is_recorded() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        let height = shell.wl_storage.storage.get_block_height().0;
+
+        let proposer = read_block_proposer_address(&shell.wl_storage, height)
+            .expect("Test failed");
+        assert_eq!(proposer, Some(validator));
+        let proposer = read_block_proposer_address(
+            &shell.wl_storage,
+            height.next_height(),
+        )
+        .expect("Test failed");
+        assert_eq!(proposer, None);
+    }
+
+    /// Get the proposal events emitted in a block, leaving out the typed
+    /// governance lifecycle events.
+    fn proposal_events(
+        response: &shim::response::FinalizeBlock,
+    ) -> Vec<&Event> {
+        response
+            .events
+            .iter()
+            .filter(|event| event.event_type == EventType::Proposal)
+            .collect()
+    }
+
+    /// Submit a proposal of the given type, voting from the first to the
+    /// second epoch, and cast the vote of the validator on it.
+    fn submit_proposal(
+        shell: &mut TestShell,
+        id: u64,
+        r#type: ProposalType,
+        vote: StorageProposalVote,
+    ) {
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let proposal = InitProposalData {
+            id: Some(id),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: Epoch::default(),
+            voting_end_epoch: Epoch::default().next(),
+            grace_epoch: Epoch::default().next(),
+            expedited: false,
+            r#type,
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            None,
+        )
+        .expect("Test failed");
+        let vote = VoteProposalData {
+            id,
+            vote,
+            voter: validator.clone(),
+            delegations: vec![validator],
+        };
+        storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+            .expect("Test failed");
+    }
+
+    /// Submit a proposal of the given type with no execution delay, vote yay
+    /// on it and execute it after its grace epoch, checking that it passed.
+    fn submit_and_pass_proposal(
+        shell: &mut TestShell,
+        r#type: ProposalType,
+    ) -> shim::response::FinalizeBlock {
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 0_u64)
+            .expect("Test failed");
+        let vote = StorageProposalVote::build(&ProposalVote::Yay, &r#type)
+            .expect("Test failed");
+        submit_proposal(shell, 0, r#type, vote);
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].attributes["tally_result"],
+            TallyResult::Passed.to_string()
+        );
+        response
+    }
+
+    /// Test that the lifecycle of a governance proposal is reported in typed
+    /// governance events.
+    #[test]
+    fn test_governance_lifecycle_events() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 0_u64)
+            .expect("Test failed");
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let proposal = InitProposalData {
+            id: Some(0),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: Epoch(1),
+            voting_end_epoch: Epoch(2),
+            grace_epoch: Epoch(2),
+            expedited: false,
+            r#type: ProposalType::Default(None),
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            None,
+        )
+        .expect("Test failed");
+        let vote = VoteProposalData {
+            id: 0,
+            vote: StorageProposalVote::Yay(VoteType::Default),
+            voter: validator.clone(),
+            delegations: vec![validator.clone()],
+        };
+        storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+            .expect("Test failed");
+
+        // The submission and the vote are reported from the keys changed by
+        // their txs
+        let changed_keys = BTreeSet::from([
+            get_author_key(0),
+            get_vote_proposal_key(0, validator.clone(), validator.clone()),
+        ]);
+        let events = governance_tx_events(&shell.wl_storage, &changed_keys)
+            .expect("Test failed");
+        assert_eq!(events.len(), 2);
+        let submitted = events
+            .iter()
+            .find(|event| event.event_type == EventType::ProposalSubmitted)
+            .expect("Test failed");
+        assert_eq!(submitted["proposal_id"], "0");
+        assert_eq!(submitted["author"], validator.to_string());
+        assert_eq!(submitted["proposal_type"], "Default");
+        let vote = events
+            .iter()
+            .find(|event| event.event_type == EventType::ProposalVote)
+            .expect("Test failed");
+        assert_eq!(vote["proposal_id"], "0");
+        assert_eq!(vote["voter"], validator.to_string());
+        assert_eq!(vote["validator"], validator.to_string());
+        assert_eq!(vote["vote"], "yay");
+
+        // The voting period starts at the voting start epoch only
+        let mut response = shim::response::FinalizeBlock::default();
+        emit_voting_started_events(&shell, &mut response).expect("Test failed");
+        assert!(response.events.is_empty());
+        shell.wl_storage.storage.block.epoch = Epoch(1);
+        emit_voting_started_events(&shell, &mut response).expect("Test failed");
+        assert_eq!(response.events.len(), 1);
+        let voting_started = &response.events[0];
+        assert_eq!(voting_started.event_type, EventType::ProposalVotingStarted);
+        assert_eq!(voting_started["proposal_id"], "0");
+        assert_eq!(voting_started["voting_end_epoch"], "2");
+
+        // The proposal is tallied and executed after its grace epoch
+        shell.wl_storage.storage.block.epoch = Epoch(3);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let tallied = response
+            .events
+            .iter()
+            .find(|event| event.event_type == EventType::ProposalTallied)
+            .expect("Test failed");
+        assert_eq!(tallied["proposal_id"], "0");
+        assert_eq!(tallied["tally_result"], TallyResult::Passed.to_string());
+        assert_eq!(tallied["vetoed"], "false");
+        assert_eq!(tallied["total_yay_power"], tallied["total_voting_power"]);
+        assert_eq!(
+            tallied["total_nay_power"],
+            Amount::zero().to_string_native()
+        );
+        let executed = response
+            .events
+            .iter()
+            .find(|event| event.event_type == EventType::ProposalExecuted)
+            .expect("Test failed");
+        assert_eq!(executed["proposal_id"], "0");
+        assert_eq!(executed["proposal_type"], "Default");
+        assert_eq!(executed["succeeded"], "true");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+    }
+
+    /// Test that the execution of passed governance proposals is delayed by
+    /// the minimum proposal execution delay parameter.
+    #[test]
+    fn test_delayed_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 2_u64)
+            .expect("Test failed");
+        submit_proposal(
+            &mut shell,
+            0,
+            ProposalType::Default(None),
+            StorageProposalVote::Yay(VoteType::Default),
+        );
+
+        // Tally the proposal at the start of the epoch following its grace
+        // epoch. It passes, but must not be executed yet.
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        assert!(proposal_events(&response).is_empty());
+        let execution_epoch =
+            storage_api::governance::get_proposal_execution_epoch(
+                &shell.wl_storage,
+                0,
+            )
+            .expect("Test failed");
+        assert_eq!(execution_epoch, Some(Epoch(4)));
+        assert!(
+            shell
+                .wl_storage
+                .has_key(&get_committing_proposals_key(0, 3))
+                .expect("Test failed")
+        );
+
+        // The proposal is executed at the start of its execution epoch
+        shell.wl_storage.storage.block.epoch = Epoch(4);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].attributes["proposal_id"], "0");
+        assert_eq!(
+            events[0].attributes["tally_result"],
+            TallyResult::Passed.to_string()
+        );
+    }
+
+    /// Test that the commitment of a proposal to its grace epoch is removed
+    /// once the proposal is tallied, such that it is not tallied again.
+    #[test]
+    fn test_committed_proposal_tallied_once() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 0_u64)
+            .expect("Test failed");
+        submit_proposal(
+            &mut shell,
+            0,
+            ProposalType::Default(None),
+            StorageProposalVote::Yay(VoteType::Default),
+        );
+        assert!(
+            shell
+                .wl_storage
+                .has_key(&get_committing_proposals_key(0, 1))
+                .expect("Test failed")
+        );
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        assert_eq!(proposal_events(&response).len(), 1);
+        assert!(
+            !shell
+                .wl_storage
+                .has_key(&get_committing_proposals_key(0, 1))
+                .expect("Test failed")
+        );
+
+        // Processing the same epoch again, as a restarted node would, doesn't
+        // execute the proposal twice
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        assert!(response.events.is_empty());
+    }
+
+    /// Test that a proposal withdrawn by its author before its voting period
+    /// starts is removed, refunds a share of its locked funds and is never
+    /// tallied.
+    #[test]
+    fn test_proposal_withdrawal() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let refund = Dec::new(5, 1).expect("Test failed");
+        shell
+            .wl_storage
+            .write(&get_proposal_withdrawal_refund_key(), refund)
+            .expect("Test failed");
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        for id in 0..2 {
+            let proposal = InitProposalData {
+                id: Some(id),
+                content: Hash::default(),
+                author: validator.clone(),
+                voting_start_epoch: Epoch(3),
+                voting_end_epoch: Epoch(6),
+                grace_epoch: Epoch(12),
+                expedited: false,
+                r#type: ProposalType::Default(None),
+            };
+            storage_api::governance::init_proposal(
+                &mut shell.wl_storage,
+                proposal,
+                vec![],
+                None,
+            )
+            .expect("Test failed");
+        }
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        let author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+        let pgf_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &pgf_address,
+        )
+        .expect("Test failed");
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let withdrawal = WithdrawProposalData {
+            id: 0,
+            author: validator.clone(),
+        };
+        storage_api::governance::withdraw_proposal(
+            &mut shell.wl_storage,
+            withdrawal,
+        )
+        .expect("Test failed");
+        assert!(
+            storage_api::governance::get_proposal_by_id(&shell.wl_storage, 0)
+                .expect("Test failed")
+                .is_none()
+        );
+        for key in [
+            get_committing_proposals_key(0, 12),
+            get_starting_proposals_key(0, 3),
+        ] {
+            assert!(!shell.wl_storage.has_key(&key).expect("Test failed"));
+        }
+        let new_author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+        let new_pgf_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &pgf_address,
+        )
+        .expect("Test failed");
+        assert_eq!(new_author_balance, author_balance + refund * funds);
+        assert_eq!(new_pgf_balance, pgf_balance + funds - refund * funds);
+
+        // The other proposal can't be withdrawn once its voting period
+        // started
+        shell.wl_storage.storage.block.epoch = Epoch(3);
+        let withdrawal = WithdrawProposalData {
+            id: 1,
+            author: validator,
+        };
+        assert!(
+            storage_api::governance::withdraw_proposal(
+                &mut shell.wl_storage,
+                withdrawal,
+            )
+            .is_err()
+        );
+
+        // Only the voting power of the proposal that wasn't withdrawn is
+        // snapshotted when their voting period starts
+        snapshot_proposals_voting_power(&mut shell).expect("Test failed");
+        assert!(
+            !shell
+                .wl_storage
+                .has_key(&get_proposal_stake_snapshot_key(0))
+                .expect("Test failed")
+        );
+        assert!(
+            shell
+                .wl_storage
+                .has_key(&get_proposal_stake_snapshot_key(1))
+                .expect("Test failed")
+        );
+
+        // Only the proposal that wasn't withdrawn is tallied
+        shell.wl_storage.storage.block.epoch = Epoch(13);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].attributes["proposal_id"], "1");
+    }
+
+    /// Test that txs submitting proposals are only accepted in blocks if the
+    /// epochs of the proposal are valid with the governance parameters.
+    #[test]
+    fn test_check_init_proposal_tx() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        shell
+            .wl_storage
+            .write(&get_min_proposal_voting_period_key(), 2_u64)
+            .expect("Test failed");
+        shell
+            .wl_storage
+            .write(&get_max_proposal_period_key(), 8_u64)
+            .expect("Test failed");
+        shell
+            .wl_storage
+            .write(&get_min_proposal_grace_epoch_key(), 2_u64)
+            .expect("Test failed");
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let proposal_tx = |start: u64, end: u64, grace: u64| {
+            let mut tx = Tx::from_type(TxType::Raw);
+            tx.set_code(Code::new(b"tx_init_proposal".to_vec(), None));
+            tx.set_data(Data::new(
+                InitProposalData {
+                    id: None,
+                    content: Hash::default(),
+                    author: validator.clone(),
+                    r#type: ProposalType::Text,
+                    voting_start_epoch: Epoch(start),
+                    voting_end_epoch: Epoch(end),
+                    grace_epoch: Epoch(grace),
+                    expedited: false,
+                }
+                .serialize_to_vec(),
+            ));
+            tx
+        };
+        let check = |shell: &TestShell, tx: &Tx| {
+            check_init_proposal_tx(&shell.wl_storage, tx).expect("Test failed")
+        };
+
+        let params = storage_api::governance::get_parameters(&shell.wl_storage)
+            .expect("Test failed");
+        let epochs = params
+            .earliest_proposal_epochs(shell.wl_storage.storage.block.epoch)
+            .expect("Test failed");
+        assert_eq!(
+            (
+                epochs.voting_start_epoch,
+                epochs.voting_end_epoch,
+                epochs.grace_epoch
+            ),
+            (Epoch(2), Epoch(4), Epoch(6))
+        );
+        let valid_tx = proposal_tx(2, 4, 6);
+        let invalid_txs = [
+            // the start epoch is not a multiple of the min voting period
+            proposal_tx(3, 5, 7),
+            // the voting period is longer than the max proposal period
+            proposal_tx(2, 12, 14),
+            // the grace period is shorter than the min grace epochs
+            proposal_tx(2, 4, 5),
+            // the grace epoch is too late
+            proposal_tx(2, 4, 12),
+            // the end epoch is before the start epoch
+            proposal_tx(4, 2, 6),
+        ];
+
+        // any tx is accepted until the code of proposal txs is known
+        assert!(invalid_txs.iter().all(|tx| check(&shell, tx).is_ok()));
+        let code_hash = invariants::inner_tx_code_hash(&valid_tx).unwrap();
+        shell
+            .wl_storage
+            .write(&Key::wasm_code_name(TX_INIT_PROPOSAL.to_owned()), code_hash)
+            .expect("Test failed");
+
+        assert!(check(&shell, &valid_tx).is_ok());
+        assert!(invalid_txs.iter().all(|tx| check(&shell, tx).is_err()));
+        let mut malformed_tx = valid_tx.clone();
+        malformed_tx.set_data(Data::new(b"proposal".to_vec()));
+        assert!(check(&shell, &malformed_tx).is_err());
+
+        // no proposal is valid without a min voting period
+        shell
+            .wl_storage
+            .write(&get_min_proposal_voting_period_key(), 0_u64)
+            .expect("Test failed");
+        let params = storage_api::governance::get_parameters(&shell.wl_storage)
+            .expect("Test failed");
+        assert!(
+            params
+                .earliest_proposal_epochs(shell.wl_storage.storage.block.epoch)
+                .is_none()
+        );
+        assert!(check(&shell, &valid_tx).is_err());
+    }
+
+    /// Test that the protocol parameters changed by a passed parameter change
+    /// proposal are written when it is executed, without a WASM payload.
+    #[test]
+    fn test_parameter_change_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let max_tx_bytes_key = params_storage::get_max_tx_bytes_key();
+        let new_max_tx_bytes = 1234_u32;
+        submit_and_pass_proposal(
+            &mut shell,
+            ProposalType::ParameterChange(vec![ParameterChange {
+                key: max_tx_bytes_key.clone(),
+                value: new_max_tx_bytes.serialize_to_vec(),
+            }]),
+        );
+
+        let max_tx_bytes: u32 = shell
+            .wl_storage
+            .read(&max_tx_bytes_key)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(max_tx_bytes, new_max_tx_bytes);
+    }
+
+    /// Test that a passed code allowlist proposal updates the tx and VP
+    /// allowlists when it is executed, so that they are enforced from its
+    /// execution epoch.
+    #[test]
+    fn test_code_allowlist_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let allowed_tx = Hash::sha256(b"allowed tx");
+        let new_tx = Hash::sha256(b"new tx");
+        let removed_vp = Hash::sha256(b"removed vp");
+        let kept_vp = Hash::sha256(b"kept vp");
+        parameters::update_tx_whitelist_parameter(
+            &mut shell.wl_storage,
+            vec![allowed_tx.to_string()],
+        )
+        .expect("Test failed");
+        parameters::update_vp_whitelist_parameter(
+            &mut shell.wl_storage,
+            vec![removed_vp.to_string(), kept_vp.to_string()],
+        )
+        .expect("Test failed");
+        // the new tx code is not allowed before the proposal is executed
+        assert!(
+            !parameters::is_tx_whitelisted(&shell.wl_storage, &new_tx)
+                .expect("Test failed")
+        );
+
+        submit_and_pass_proposal(
+            &mut shell,
+            ProposalType::CodeAllowlist(CodeAllowlistUpdate {
+                tx: vec![AddRemove::Add(new_tx)],
+                vp: vec![AddRemove::Remove(removed_vp)],
+            }),
+        );
+
+        for tx in [allowed_tx, new_tx] {
+            assert!(
+                parameters::is_tx_whitelisted(&shell.wl_storage, &tx)
+                    .expect("Test failed")
+            );
+        }
+        let vp_whitelist: Vec<String> = shell
+            .wl_storage
+            .read(&params_storage::get_vp_whitelist_storage_key())
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(vp_whitelist, vec![kept_vp.to_string().to_lowercase()]);
+    }
+
+    /// Test that a passed consensus params proposal returns the new params
+    /// to CometBFT in the response of the block in which it is executed.
+    #[test]
+    fn test_consensus_params_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let response = submit_and_pass_proposal(
+            &mut shell,
+            ProposalType::ConsensusParams(ConsensusParamsUpdate {
+                block: Some(BlockParamsUpdate {
+                    max_bytes: 20 * 1024 * 1024,
+                    max_gas: -1,
+                }),
+                evidence: None,
+            }),
+        );
+
+        let params = response
+            .consensus_param_updates
+            .expect("Consensus params should be updated");
+        let block = params.block.expect("Block params should be updated");
+        assert_eq!(block.max_bytes, 20 * 1024 * 1024);
+        assert_eq!(block.max_gas, -1);
+        assert!(params.evidence.is_none());
+
+        let params: ConsensusParams = shell
+            .wl_storage
+            .read(&get_consensus_params_key())
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(params.block.max_bytes, 20 * 1024 * 1024);
+        assert_eq!(params.block.max_gas, -1);
+    }
+
+    /// Test that a passed consensus params proposal which is no longer valid
+    /// against the current params fails, without updating the params.
+    #[test]
+    fn test_invalid_consensus_params_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let current_params = ConsensusParams {
+            block: BlockParamsUpdate {
+                max_bytes: 1024 * 1024,
+                max_gas: -1,
+            },
+            evidence: EvidenceParamsUpdate {
+                max_age_num_blocks: 100,
+                max_age_duration: DurationSecs(3600),
+                max_bytes: 1024,
+            },
+        };
+        shell
+            .wl_storage
+            .write(&get_consensus_params_key(), current_params.clone())
+            .expect("Test failed");
+
+        // the evidence may not exceed the current max block bytes
+        let response = submit_and_pass_proposal(
+            &mut shell,
+            ProposalType::ConsensusParams(ConsensusParamsUpdate {
+                block: None,
+                evidence: Some(EvidenceParamsUpdate {
+                    max_age_num_blocks: 100,
+                    max_age_duration: DurationSecs(3600),
+                    max_bytes: 2 * 1024 * 1024,
+                }),
+            }),
+        );
+        assert!(response.consensus_param_updates.is_none());
+        assert!(
+            shell
+                .wl_storage
+                .has_key(&get_proposal_execution_failed_key(0))
+                .expect("Test failed")
+        );
+        let params: Option<ConsensusParams> = shell
+            .wl_storage
+            .read(&get_consensus_params_key())
+            .expect("Test failed");
+        assert_eq!(params, Some(current_params));
+    }
+
+    /// Test that passed treasury spend proposals transfer funds from the PGF
+    /// treasury, and that a spend the treasury can't cover fails without
+    /// aborting the block.
+    #[test]
+    fn test_treasury_spend_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 0_u64)
+            .expect("Test failed");
+
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        credit_tokens(
+            &mut shell.wl_storage,
+            &native_token,
+            &pgf_address,
+            Amount::native_whole(50),
+        )
+        .expect("Test failed");
+        let treasury_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &pgf_address,
+        )
+        .expect("Test failed");
+
+        // The first spend is covered by the treasury, the second one isn't
+        // once the first one is paid
+        let spends = [
+            TreasurySpend {
+                recipient: address::testing::gen_implicit_address(),
+                amount: Amount::native_whole(30),
+            },
+            TreasurySpend {
+                recipient: address::testing::gen_implicit_address(),
+                amount: treasury_balance,
+            },
+        ];
+        for (id, spend) in spends.iter().enumerate() {
+            submit_proposal(
+                &mut shell,
+                id as u64,
+                ProposalType::TreasurySpend(spend.clone()),
+                StorageProposalVote::Yay(VoteType::TreasurySpend),
+            );
+        }
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.attributes["tally_result"]
+            == TallyResult::Passed.to_string()));
+
+        let spend_events: Vec<_> = response
+            .events
+            .iter()
+            .filter(|event| event.event_type == EventType::TreasurySpend)
+            .collect();
+        assert_eq!(spend_events.len(), 2);
+        for (id, spend) in spends.iter().enumerate() {
+            let event = spend_events
+                .iter()
+                .find(|event| event.attributes["proposal_id"] == id.to_string())
+                .expect("Test failed");
+            assert_eq!(
+                event.attributes["recipient"],
+                spend.recipient.to_string()
+            );
+            assert_eq!(
+                event.attributes["amount"],
+                spend.amount.to_string_native()
+            );
+            assert_eq!(event.attributes["succeeded"], (id == 0).to_string());
+        }
+
+        let paid_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &spends[0].recipient,
+        )
+        .expect("Test failed");
+        assert_eq!(paid_balance, Amount::native_whole(30));
+        let unpaid_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &spends[1].recipient,
+        )
+        .expect("Test failed");
+        assert!(unpaid_balance.is_zero());
+        let new_treasury_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &pgf_address,
+        )
+        .expect("Test failed");
+        assert_eq!(
+            new_treasury_balance,
+            treasury_balance - Amount::native_whole(30)
+        );
+
+        assert!(
+            !shell
+                .wl_storage
+                .has_key(&get_proposal_execution_failed_key(0))
+                .expect("Test failed")
+        );
+        assert!(
+            shell
+                .wl_storage
+                .has_key(&get_proposal_execution_failed_key(1))
+                .expect("Test failed")
+        );
+    }
+
+    /// Test that text proposals lock the text proposal fund and pass without
+    /// executing anything.
+    #[test]
+    fn test_text_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let min_text_fund_key = get_min_text_proposal_fund_key();
+        shell
+            .wl_storage
+            .write(&min_text_fund_key, Amount::native_whole(10))
+            .expect("Test failed");
+
+        let response = submit_and_pass_proposal(&mut shell, ProposalType::Text);
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(funds, Amount::native_whole(10));
+        assert!(response.consensus_param_updates.is_none());
+    }
+
+    /// Test that expedited governance proposals are tallied and executed in
+    /// the block at which their voting window closes.
+    #[test]
+    fn test_expedited_proposal_execution() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let min_expedited_fund_key = get_min_expedited_proposal_fund_key();
+        shell
+            .wl_storage
+            .write(&min_expedited_fund_key, Amount::native_whole(1_000))
+            .expect("Test failed");
+        let voting_period_key = get_expedited_proposal_voting_period_key();
+        shell
+            .wl_storage
+            .write(&voting_period_key, 10_u64)
+            .expect("Test failed");
+        shell.wl_storage.storage.block.height = BlockHeight(5);
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let current_epoch = shell.wl_storage.storage.block.epoch;
+        let proposal = InitProposalData {
+            id: Some(0),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: current_epoch,
+            voting_end_epoch: current_epoch,
+            grace_epoch: current_epoch,
+            expedited: true,
+            r#type: ProposalType::Default(None),
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            None,
+        )
+        .expect("Test failed");
+        let voting_end_height =
+            storage_api::governance::get_proposal_voting_end_height(
+                &shell.wl_storage,
+                0,
+            )
+            .expect("Test failed");
+        assert_eq!(voting_end_height, Some(BlockHeight(15)));
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(funds, Amount::native_whole(1_000));
+        // Expedited proposals are not committed to their grace epoch
+        assert!(
+            !shell
+                .wl_storage
+                .has_key(&get_committing_proposals_key(0, current_epoch.0))
+                .expect("Test failed")
+        );
+
+        let vote = VoteProposalData {
+            id: 0,
+            vote: StorageProposalVote::Yay(VoteType::Default),
+            voter: validator.clone(),
+            delegations: vec![validator],
+        };
+        storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+            .expect("Test failed");
+
+        // The proposal is not tallied before its voting window closes, nor
+        // at a height that only shares the prefix of its end height
+        for height in [BlockHeight(14), BlockHeight(1)] {
+            let mut response = shim::response::FinalizeBlock::default();
+            execute_expedited_governance_proposals(
+                &mut shell,
+                height,
+                &mut response,
+            )
+            .expect("Test failed");
+            assert!(response.events.is_empty());
+        }
+
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_expedited_governance_proposals(
+            &mut shell,
+            BlockHeight(15),
+            &mut response,
+        )
+        .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].attributes["proposal_id"], "0");
+        assert_eq!(
+            events[0].attributes["tally_result"],
+            TallyResult::Passed.to_string()
+        );
+    }
+
+    /// Test that the funds of a rejected proposal are returned to its author,
+    /// unless the proposal was vetoed, in which case they are burned.
+    #[test]
+    fn test_vetoed_proposal_funds_burned() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        for (id, vote) in [
+            (0, StorageProposalVote::Nay),
+            (1, StorageProposalVote::Veto),
+        ] {
+            submit_proposal(&mut shell, id, ProposalType::Default(None), vote);
+        }
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        let author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+        let gov_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &gov_address,
+        )
+        .expect("Test failed");
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 2);
+        for event in events {
+            assert_eq!(
+                event.attributes["tally_result"],
+                TallyResult::Rejected.to_string()
+            );
+            let is_vetoed = event.attributes["proposal_id"] == "1";
+            assert_eq!(event.attributes.contains_key("vetoed"), is_vetoed);
+        }
+
+        // Only the funds of the proposal that wasn't vetoed are returned
+        let new_author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+        assert_eq!(new_author_balance, author_balance + funds);
+        let new_gov_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &gov_address,
+        )
+        .expect("Test failed");
+        assert_eq!(new_gov_balance + funds + funds, gov_balance);
+    }
+
+    /// Test that the share of veto votes above which a proposal is vetoed is
+    /// set by the `veto_threshold` governance parameter.
+    #[test]
+    fn test_veto_threshold_parameter() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        // with a threshold of 1, no proposal can ever be vetoed
+        shell
+            .wl_storage
+            .write(&get_veto_threshold_key(), FractionalVotingPower::WHOLE)
+            .expect("Test failed");
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        submit_proposal(
+            &mut shell,
+            0,
+            ProposalType::Default(None),
+            StorageProposalVote::Veto,
+        );
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        let author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let tallied = response
+            .events
+            .iter()
+            .find(|event| event.event_type == EventType::ProposalTallied)
+            .expect("Test failed");
+        assert_eq!(tallied["tally_result"], TallyResult::Rejected.to_string());
+        assert_eq!(tallied["vetoed"], "false");
+
+        // the veto votes still count as nay votes, but the funds of the
+        // rejected proposal are returned
+        let new_author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
+        assert_eq!(new_author_balance, author_balance + funds);
+    }
+
+    /// Test that the vote of a delegator overrides the vote of its validator
+    /// only for the voting power of its delegation.
+    #[test]
+    fn test_delegator_vote_override() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let params = read_pos_params(&shell.wl_storage).unwrap();
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+
+        // Make an account with balance and delegate some tokens
+        let delegator = address::testing::gen_implicit_address();
+        let del_amount = Amount::native_whole(1_000);
+        credit_tokens(
+            &mut shell.wl_storage,
+            &native_token,
+            &delegator,
+            del_amount,
+        )
+        .unwrap();
+        namada_proof_of_stake::bond_tokens(
+            &mut shell.wl_storage,
+            Some(&delegator),
+            &validator,
+            del_amount,
+            Epoch::default(),
+            None,
+        )
+        .unwrap();
+
+        // The voting power of the proposal is snapshotted at the pipeline
+        // epoch, when the bond is active
+        let start_epoch = Epoch(params.pipeline_len);
+        let proposal = InitProposalData {
+            id: Some(0),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: start_epoch,
+            voting_end_epoch: start_epoch.next(),
+            grace_epoch: start_epoch.next(),
+            expedited: false,
+            r#type: ProposalType::Default(None),
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            None,
+        )
+        .expect("Test failed");
+        for (voter, vote, delegations) in [
+            (
+                validator.clone(),
+                StorageProposalVote::Yay(VoteType::Default),
+                vec![validator.clone()],
+            ),
+            (
+                delegator.clone(),
+                StorageProposalVote::Nay,
+                vec![validator.clone()],
+            ),
+        ] {
+            let vote = VoteProposalData {
+                id: 0,
+                vote,
+                voter,
+                delegations,
+            };
+            storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+                .expect("Test failed");
+        }
+
+        shell.wl_storage.storage.block.epoch = start_epoch.next().next();
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+
+        let validator_stake = read_validator_stake(
+            &shell.wl_storage,
+            &params,
+            &validator,
+            start_epoch,
+        )
+        .unwrap();
+        let result: ProposalResult = shell
+            .wl_storage
+            .read(&get_proposal_result_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(result.total_yay_power, validator_stake - del_amount);
+        assert_eq!(result.total_nay_power, del_amount);
+    }
+
+    /// Test that the votes of a proposal are tallied with the voting power
+    /// snapshotted when it entered its voting period.
+    #[test]
+    fn test_proposal_stake_snapshot() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let execution_delay_key = get_min_proposal_execution_delay_key();
+        shell
+            .wl_storage
+            .write(&execution_delay_key, 0_u64)
+            .expect("Test failed");
+
+        let params = read_pos_params(&shell.wl_storage).unwrap();
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let end_epoch = Epoch(params.pipeline_len);
+        let proposal = InitProposalData {
+            id: Some(0),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: Epoch::default(),
+            voting_end_epoch: end_epoch,
+            grace_epoch: end_epoch,
+            expedited: false,
+            r#type: ProposalType::Default(None),
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            None,
+        )
+        .expect("Test failed");
+        let vote = VoteProposalData {
+            id: 0,
+            vote: StorageProposalVote::Yay(VoteType::Default),
+            voter: validator.clone(),
+            delegations: vec![validator.clone()],
+        };
+        storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+            .expect("Test failed");
+
+        // The voting power is snapshotted when the voting period starts
+        snapshot_proposals_voting_power(&mut shell).expect("Test failed");
+        let snapshot = storage_api::governance::get_proposal_stake_snapshot(
+            &shell.wl_storage,
+            0,
+        )
+        .expect("Test failed")
+        .expect("Test failed");
+        let snapshot_stake = read_validator_stake(
+            &shell.wl_storage,
+            &params,
+            &validator,
+            Epoch::default(),
+        )
+        .unwrap();
+        assert_eq!(snapshot.epoch, Epoch::default());
+        assert_eq!(
+            snapshot.validator_voting_power.get(&validator),
+            Some(&snapshot_stake)
+        );
+
+        // A bond taking effect during the voting period doesn't change the
+        // voting power of the proposal
+        let delegator = address::testing::gen_implicit_address();
+        let del_amount = Amount::native_whole(1_000);
+        credit_tokens(
+            &mut shell.wl_storage,
+            &native_token,
+            &delegator,
+            del_amount,
+        )
+        .unwrap();
+        namada_proof_of_stake::bond_tokens(
+            &mut shell.wl_storage,
+            Some(&delegator),
+            &validator,
+            del_amount,
+            Epoch::default(),
+            None,
+        )
+        .unwrap();
+        let end_stake = read_validator_stake(
+            &shell.wl_storage,
+            &params,
+            &validator,
+            end_epoch,
+        )
+        .unwrap();
+        assert_eq!(end_stake, snapshot_stake + del_amount);
+
+        shell.wl_storage.storage.block.epoch = end_epoch.next();
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let result: ProposalResult = shell
+            .wl_storage
+            .read(&get_proposal_result_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(result.total_voting_power, snapshot.total_voting_power);
+        assert_eq!(result.total_yay_power, snapshot_stake);
+    }
+
+    /// Test that the Ethereum bridge validator sets are snapshotted when a
+    /// proposal changing the bridge enters its voting period, and only for
+    /// such proposals.
+    #[test]
+    fn test_bridge_proposal_valset_snapshot() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
+
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let proposal_types = [
+            ProposalType::ParameterChange(vec![ParameterChange {
+                key: oracle_paused_key(),
+                value: true.serialize_to_vec(),
+            }]),
+            ProposalType::ParameterChange(vec![ParameterChange {
+                key: params_storage::get_max_tx_bytes_key(),
+                value: 1234_u32.serialize_to_vec(),
+            }]),
+        ];
+        for (id, r#type) in proposal_types.into_iter().enumerate() {
+            let proposal = InitProposalData {
+                id: Some(id as u64),
+                content: Hash::default(),
+                author: validator.clone(),
+                voting_start_epoch: Epoch::default(),
+                voting_end_epoch: Epoch::default().next(),
+                grace_epoch: Epoch::default().next(),
+                expedited: false,
+                r#type,
+            };
+            storage_api::governance::init_proposal(
+                &mut shell.wl_storage,
+                proposal,
+                vec![],
+                None,
+            )
+            .expect("Test failed");
+        }
+
+        snapshot_proposals_voting_power(&mut shell).expect("Test failed");
+        let snapshot =
+            storage_api::governance::get_proposal_bridge_valset_snapshot(
+                &shell.wl_storage,
+                0,
+            )
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(snapshot.bridge.epoch, Epoch::default());
+        assert_eq!(snapshot.governance.epoch, Epoch::default());
+        assert!(!snapshot.bridge.validators.is_empty());
+
+        // The snapshot holds the validator set signed off for the bridge at
+        // the start epoch of the proposal
+        let (_, voting_powers_map) = shell
+            .wl_storage
+            .ethbridge_queries()
+            .get_bridge_validator_set(Some(Epoch::default()));
+        let (bridge_hash, governance_hash) =
+            voting_powers_map.get_bridge_and_gov_hashes(Epoch::default());
+        assert_eq!(snapshot.bridge_hash, bridge_hash);
+        assert_eq!(snapshot.governance_hash, governance_hash);
+
+        // Proposals that don't change the bridge have no snapshot
+        assert!(
+            storage_api::governance::get_proposal_bridge_valset_snapshot(
+                &shell.wl_storage,
+                1,
+            )
+            .expect("Test failed")
+            .is_none()
+        );
+    }
+
+    /// Test that a passed proposal whose code runs out of gas doesn't abort
+    /// the block, but is marked as passed with a failed execution.
+    #[test]
+    fn test_proposal_code_execution_out_of_gas() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
 
-        // Commit the genesis state
-        shell.wl_storage.commit_block().unwrap();
-        shell.commit();
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let proposal_code = TestWasms::TxNoOp.read_bytes();
+        let proposal = InitProposalData {
+            id: Some(0),
+            content: Hash::default(),
+            author: validator.clone(),
+            voting_start_epoch: Epoch::default(),
+            voting_end_epoch: Epoch::default().next(),
+            grace_epoch: Epoch::default().next(),
+            expedited: false,
+            r#type: ProposalType::Default(Some(Hash::sha256(&proposal_code))),
+        };
+        storage_api::governance::init_proposal(
+            &mut shell.wl_storage,
+            proposal,
+            vec![],
+            Some(proposal_code),
+        )
+        .expect("Test failed");
+        let vote = VoteProposalData {
+            id: 0,
+            vote: StorageProposalVote::Yay(VoteType::Default),
+            voter: validator.clone(),
+            delegations: vec![validator.clone()],
+        };
+        storage_api::governance::vote_proposal(&mut shell.wl_storage, vote)
+            .expect("Test failed");
+        // not enough gas to run any code
+        shell
+            .wl_storage
+            .write(&get_max_proposal_execution_gas_key(), 1_u64)
+            .expect("Test failed");
+        let author_balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &validator,
+        )
+        .expect("Test failed");
 
-        // Collect all storage key-vals into a sorted map
-        let store_block_state = |shell: &TestShell| -> BTreeMap<_, _> {
+        shell.wl_storage.storage.block.epoch = Epoch(2);
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_governance_proposals(&mut shell, &mut response)
+            .expect("Test failed");
+        let events = proposal_events(&response);
+        assert_eq!(events.len(), 1);
+        let event = events[0];
+        assert_eq!(
+            event.attributes["tally_result"],
+            TallyResult::Passed.to_string()
+        );
+        assert_eq!(event.attributes["execution_failed"], "true");
+        assert!(
             shell
                 .wl_storage
-                .storage
-                .db
-                .iter_prefix(None)
-                .map(|(key, val, _gas)| (key, val))
-                .collect()
-        };
-
-        // Store the full state in sorted map
-        let mut last_storage_state: std::collections::BTreeMap<
-            String,
-            Vec<u8>,
-        > = store_block_state(&shell);
+                .has_key(&get_proposal_execution_failed_key(0))
+                .expect("Test failed")
+        );
 
-        // Keep applying finalize block
-        let validator = shell.mode.get_validator_address().unwrap();
-        let pos_params =
-            namada_proof_of_stake::read_pos_params(&shell.wl_storage).unwrap();
-        let consensus_key =
-            namada_proof_of_stake::validator_consensus_key_handle(validator)
-                .get(&shell.wl_storage, Epoch::default(), &pos_params)
-                .unwrap()
-                .unwrap();
-        let proposer_address = HEXUPPER
-            .decode(consensus_key.tm_raw_hash().as_bytes())
-            .unwrap();
-        let val_stake = read_validator_stake(
+        // the funds of the proposal are still returned to its author
+        let funds: Amount = shell
+            .wl_storage
+            .read(&get_funds_key(0))
+            .expect("Test failed")
+            .expect("Test failed");
+        let new_author_balance = storage_api::token::read_balance(
             &shell.wl_storage,
-            &pos_params,
-            validator,
-            Epoch::default(),
+            &native_token,
+            &validator,
         )
-        .unwrap();
-
-        let votes = vec![VoteInfo {
-            validator: Validator {
-                address: proposer_address.clone().try_into().unwrap(),
-                power: (u128::try_from(val_stake).expect("Test failed") as u64)
-                    .try_into()
-                    .unwrap(),
-            },
-            sig_info: tendermint::abci::types::BlockSignatureInfo::LegacySigned,
-        }];
-
-        // Need to supply a proposer address and votes to flow through the
-        // inflation code
-        for _ in 0..20 {
-            // Add some txs
-            let mut txs = vec![];
-            // create two decrypted txs
-            for _ in 0..2 {
-                txs.push(mk_decrypted_tx(&mut shell, &txs_key));
-            }
-            // create two wrapper txs
-            for _ in 0..2 {
-                let (_tx, processed_tx) = mk_wrapper_tx(&shell, &txs_key);
-                txs.push(processed_tx);
-            }
+        .expect("Test failed");
+        assert_eq!(new_author_balance, author_balance + funds);
+    }
 
-            let req = FinalizeBlock {
-                txs,
-                proposer_address: proposer_address.clone(),
-                votes: votes.clone(),
-                ..Default::default()
-            };
-            // merkle tree root before finalize_block
-            let root_pre = shell.shell.wl_storage.storage.block.tree.root();
+    /// Test that continuous PGF fundings are paid from the PGF account every
+    /// epoch, until they are removed.
+    #[test]
+    fn test_continuous_pgf_fundings() {
+        let (mut shell, _broadcaster, _, _eth_control) = setup();
 
-            let _events = shell.finalize_block(req).unwrap();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let target = address::testing::gen_implicit_address();
+        let amount = Amount::native_whole(30);
+        credit_tokens(
+            &mut shell.wl_storage,
+            &native_token,
+            &pgf_address,
+            Amount::native_whole(100),
+        )
+        .expect("Test failed");
+        pgf_storage::fundings_handle()
+            .insert(
+                &mut shell.wl_storage,
+                target.clone(),
+                StoragePgfFunding::new(
+                    PGFTarget {
+                        target: target.clone(),
+                        amount,
+                    },
+                    0,
+                ),
+            )
+            .expect("Test failed");
 
-            // the merkle tree root should not change after finalize_block
-            let root_post = shell.shell.wl_storage.storage.block.tree.root();
-            assert_eq!(root_pre.0, root_post.0);
-            let new_state = store_block_state(&shell);
-            // The new state must be unchanged
-            itertools::assert_equal(
-                last_storage_state.iter(),
-                new_state.iter(),
+        // The funding is paid once per call
+        for epoch in 1..=2_u64 {
+            let mut response = shim::response::FinalizeBlock::default();
+            execute_pgf_fundings(&mut shell, &mut response)
+                .expect("Test failed");
+            assert_eq!(response.events.len(), 1);
+            let event = &response.events[0];
+            assert_eq!(event.attributes["proposal_id"], "0");
+            assert_eq!(
+                event.attributes["pgf_funding_target"],
+                target.to_string()
             );
-            // Commit the block to move on to the next one
-            shell.wl_storage.commit_block().unwrap();
-
-            // Store the state after commit for the next iteration
-            last_storage_state = store_block_state(&shell);
+            assert_eq!(event.attributes["pgf_funding_payment_status"], "0");
+            let balance = storage_api::token::read_balance(
+                &shell.wl_storage,
+                &native_token,
+                &target,
+            )
+            .expect("Test failed");
+            assert_eq!(balance, Amount::native_whole(30 * epoch));
         }
+
+        // Once removed, the funding is not paid anymore
+        pgf_storage::fundings_handle()
+            .remove(&mut shell.wl_storage, &target)
+            .expect("Test failed");
+        let mut response = shim::response::FinalizeBlock::default();
+        execute_pgf_fundings(&mut shell, &mut response).expect("Test failed");
+        assert!(response.events.is_empty());
+        let balance = storage_api::token::read_balance(
+            &shell.wl_storage,
+            &native_token,
+            &target,
+        )
+        .expect("Test failed");
+        assert_eq!(balance, Amount::native_whole(60));
     }
 
     /// A unit test for PoS inflationary rewards
@@ -2259,6 +4432,16 @@ mod test_finalize_block {
             current_epoch,
         )
         .unwrap();
+        let claimable = namada_proof_of_stake::query_claimable_rewards(
+            &shell.wl_storage,
+            &validator.address,
+            current_epoch,
+        )
+        .unwrap();
+        assert_eq!(
+            claimable,
+            BTreeMap::from([(validator.address.clone(), query_rewards)])
+        );
 
         // Claim the rewards from the initial epoch
         let reward_1 = namada_proof_of_stake::claim_reward_tokens(
@@ -2282,6 +4465,15 @@ mod test_finalize_block {
         )
         .unwrap();
         assert_eq!(query_rewards, token::Amount::zero());
+        assert!(
+            namada_proof_of_stake::query_claimable_rewards(
+                &shell.wl_storage,
+                &validator.address,
+                current_epoch,
+            )
+            .unwrap()
+            .is_empty()
+        );
 
         // Try a claim the next block and ensure we get 0 tokens back
         next_block_for_inflation(
@@ -5103,7 +7295,7 @@ mod test_finalize_block {
 
         // Now advance one more block to the next epoch, where validator 2 will
         // miss its 10th vote and should thus be jailed for liveness
-        next_block_for_inflation(
+        let response = next_block_for_inflation(
             &mut shell,
             pkh1.to_vec(),
             votes_no2.clone(),
@@ -5116,6 +7308,19 @@ mod test_finalize_block {
             liveness_sum_missed_votes_handle().get(&shell.wl_storage, &val2)?;
         assert_eq!(val2_sum_missed_votes, Some(minimum_unsigned_blocks));
 
+        // Check that the jailing was announced in an event
+        let jailed_events: Vec<_> = response
+            .events
+            .iter()
+            .filter(|event| event.event_type == EventType::ValidatorJailed)
+            .collect();
+        assert_eq!(jailed_events.len(), 1);
+        assert_eq!(jailed_events[0]["validator"], val2.to_string());
+        assert_eq!(
+            jailed_events[0]["missed_votes"],
+            minimum_unsigned_blocks.to_string()
+        );
+
         // Check the validator sets for all epochs up through the pipeline
         let consensus_vals = read_consensus_validator_set_addresses(
             &shell.wl_storage,
@@ -5149,6 +7354,25 @@ mod test_finalize_block {
             assert_eq!(val5_state, ValidatorState::BelowThreshold);
         }
 
+        // Check that validator 2 leaving the consensus set in the next epoch
+        // is announced in an event
+        let mut response = shim::response::FinalizeBlock::default();
+        shell.emit_validator_set_changes(&mut response).unwrap();
+        let left_events: Vec<_> = response
+            .events
+            .iter()
+            .filter(|event| {
+                event.event_type == EventType::ValidatorSetChange
+                    && event["change"] == "left"
+            })
+            .collect();
+        assert_eq!(left_events.len(), 1);
+        assert_eq!(left_events[0]["validator"], val2.to_string());
+        assert_eq!(
+            left_events[0]["new_stake"],
+            Amount::zero().to_string_native()
+        );
+
         // Check the liveness data for validators 2 and 5 (2 should still be
         // there, 5 should be removed)
         for val in &initial_consensus_set {
@@ -5392,7 +7616,194 @@ mod test_finalize_block {
         }];
         next_block_for_inflation(&mut shell, pkh1.to_vec(), votes, None);
         let Command::UpdateConfig(cmd) =
-            control_receiver.recv().await.expect("Test failed");
+            control_receiver.recv().await.expect("Test failed")
+        else {
+            panic!("Test failed");
+        };
         assert_eq!(u64::from(cmd.min_confirmations), 42);
     }
+
+    /// Test that changes to the Ethereum bridge config are sent to the
+    /// oracle in the block they are made in, without waiting for the
+    /// next epoch.
+    #[test]
+    fn test_eth_oracle_config_updated_on_change() {
+        let (mut shell, _broadcaster, _, mut control_receiver) =
+            setup_at_height(3u64);
+        // drain the commands sent upon initializing the chain
+        while control_receiver.try_recv().is_ok() {}
+
+        // a block without changes to the config sends nothing
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(control_receiver.try_recv().is_err());
+
+        let new_min_confirmations =
+            MinimumConfirmations::from(NonZeroU64::new(42).unwrap());
+        shell
+            .wl_storage
+            .write(&min_confirmations_key(), new_min_confirmations)
+            .expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        let Ok(Command::UpdateConfig(config)) = control_receiver.try_recv()
+        else {
+            panic!("Test failed");
+        };
+        assert_eq!(u64::from(config.min_confirmations), 42);
+    }
+
+    /// Test that the latest Ethereum bridge config is sent to the oracle
+    /// again in the next block, if the control channel of the oracle was
+    /// full when it changed.
+    #[test]
+    fn test_eth_oracle_config_resent_if_channel_full() {
+        let (mut shell, _broadcaster, _, mut control_receiver) =
+            setup_at_height(3u64);
+        while control_receiver.try_recv().is_ok() {}
+
+        // fill up the channel, and change the config once more
+        for min_confirmations in 100..=105 {
+            let new_min_confirmations = MinimumConfirmations::from(
+                NonZeroU64::new(min_confirmations).unwrap(),
+            );
+            shell
+                .wl_storage
+                .write(&min_confirmations_key(), new_min_confirmations)
+                .expect("Test failed");
+            shell
+                .finalize_block(FinalizeBlock::default())
+                .expect("Test failed");
+            shell.commit();
+        }
+        let mut received = vec![];
+        while let Ok(Command::UpdateConfig(config)) =
+            control_receiver.try_recv()
+        {
+            received.push(u64::from(config.min_confirmations));
+        }
+        assert_eq!(received, vec![100, 101, 102, 103, 104]);
+
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        let Ok(Command::UpdateConfig(config)) = control_receiver.try_recv()
+        else {
+            panic!("Test failed");
+        };
+        assert_eq!(u64::from(config.min_confirmations), 105);
+        assert!(control_receiver.try_recv().is_err());
+    }
+
+    /// Test that the oracle is only told to pause or resume when the
+    /// paused status of the oracle changes in storage.
+    #[test]
+    fn test_eth_oracle_paused_status_sent_on_change() {
+        let (mut shell, _broadcaster, _, mut control_receiver) =
+            setup_at_height(3u64);
+        // the oracle starts out resumed
+        while let Ok(cmd) = control_receiver.try_recv() {
+            assert!(matches!(cmd, Command::UpdateConfig(_)));
+        }
+
+        shell
+            .wl_storage
+            .write(&oracle_paused_key(), true)
+            .expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(matches!(
+            control_receiver.try_recv(),
+            Ok(Command::UpdateConfig(_))
+        ));
+        assert_eq!(control_receiver.try_recv(), Ok(Command::Resume));
+    }
+
+    /// Test that the node operator can pause and resume the oracle of a
+    /// running node with its pause marker file, independently of the
+    /// paused status set by governance.
+    #[test]
+    fn test_eth_oracle_paused_by_operator() {
+        let (mut shell, _broadcaster, _, mut control_receiver) =
+            setup_at_height(3u64);
+        while let Ok(cmd) = control_receiver.try_recv() {
+            assert!(matches!(cmd, Command::UpdateConfig(_)));
+        }
+        let pause_file = shell.eth_oracle_pause_file();
+        std::fs::create_dir_all(pause_file.parent().unwrap())
+            .expect("Test failed");
+
+        std::fs::write(&pause_file, []).expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(matches!(
+            control_receiver.try_recv(),
+            Ok(Command::UpdateConfig(_))
+        ));
+        assert_eq!(control_receiver.try_recv(), Ok(Command::Pause));
+
+        // governance resuming the oracle doesn't override the operator
+        shell
+            .wl_storage
+            .write(&oracle_paused_key(), false)
+            .expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        while let Ok(cmd) = control_receiver.try_recv() {
+            assert!(matches!(cmd, Command::UpdateConfig(_)));
+        }
+
+        std::fs::remove_file(&pause_file).expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(matches!(
+            control_receiver.try_recv(),
+            Ok(Command::UpdateConfig(_))
+        ));
+        assert_eq!(control_receiver.try_recv(), Ok(Command::Pause));
+
+        // other changes to the config leave the oracle paused
+        let new_min_confirmations =
+            MinimumConfirmations::from(NonZeroU64::new(42).unwrap());
+        shell
+            .wl_storage
+            .write(&min_confirmations_key(), new_min_confirmations)
+            .expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(matches!(
+            control_receiver.try_recv(),
+            Ok(Command::UpdateConfig(_))
+        ));
+        assert!(control_receiver.try_recv().is_err());
+
+        shell
+            .wl_storage
+            .write(&oracle_paused_key(), false)
+            .expect("Test failed");
+        shell
+            .finalize_block(FinalizeBlock::default())
+            .expect("Test failed");
+        shell.commit();
+        assert!(matches!(
+            control_receiver.try_recv(),
+            Ok(Command::UpdateConfig(_))
+        ));
+        assert_eq!(control_receiver.try_recv(), Ok(Command::Resume));
+    }
 }
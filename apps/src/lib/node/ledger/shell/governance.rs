@@ -1,32 +1,42 @@
-use std::collections::HashMap;
-
+use namada::core::ledger::governance::cli::is_valid_proposal_epochs;
 use namada::core::ledger::governance::storage::keys as gov_storage;
 use namada::core::ledger::governance::storage::proposal::{
-    AddRemove, PGFAction, ProposalType, StoragePgfFunding,
+    AddRemove, CodeAllowlistUpdate, ConsensusParams, ConsensusParamsUpdate,
+    PGFAction, ParameterChange, ProposalType, StoragePgfFunding, TreasurySpend,
 };
 use namada::core::ledger::governance::utils::{
-    compute_proposal_result, ProposalVotes, TallyResult, TallyType, TallyVote,
-    VotePower,
+    compute_proposal_result, TallyResult, TallyType,
 };
 use namada::core::ledger::governance::ADDRESS as gov_address;
 use namada::core::ledger::pgf::storage::keys as pgf_storage;
 use namada::core::ledger::pgf::storage::steward::StewardDetail;
 use namada::core::ledger::pgf::ADDRESS;
 use namada::core::ledger::storage_api::governance as gov_api;
-use namada::ledger::governance::utils::ProposalEvent;
-use namada::ledger::pos::BondId;
+use namada::eth_bridge::storage as bridge_storage;
+use namada::ledger::governance::utils::{GovernanceEvent, ProposalEvent};
+use namada::ledger::parameters::storage::get_max_proposal_bytes_key;
 use namada::ledger::protocol;
+use namada::ledger::queries::vp::governance::{
+    compute_proposal_votes, read_proposal_stake_snapshot, take_stake_snapshot,
+};
 use namada::ledger::storage::types::encode;
 use namada::ledger::storage::{DBIter, StorageHasher, DB};
 use namada::ledger::storage_api::{pgf, token, StorageWrite};
-use namada::proof_of_stake::parameters::PosParams;
-use namada::proof_of_stake::{bond_amount, read_total_stake};
 use namada::proto::{Code, Data};
 use namada::types::address::Address;
-use namada::types::storage::Epoch;
+use namada::types::chain::ProposalBytes;
+use namada::types::hash::Hash;
+use namada::types::storage::{Epoch, Key};
+use namada::types::transaction::governance::InitProposalData;
+use namada::types::vote_extensions::validator_set_update::ValidatorSetSnapshot;
+use namada_sdk::tx::TX_INIT_PROPOSAL;
 
 use super::utils::force_read;
 use super::*;
+use crate::facade::tendermint_proto::google::protobuf::Duration;
+use crate::facade::tendermint_proto::v0_37::types::{
+    BlockParams, EvidenceParams,
+};
 
 #[derive(Default)]
 pub struct ProposalsResult {
@@ -44,224 +54,778 @@ where
 {
     let mut proposals_result = ProposalsResult::default();
 
-    for id in std::mem::take(&mut shell.proposal_data) {
-        let proposal_funds_key = gov_storage::get_funds_key(id);
-        let proposal_end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
-        let proposal_type_key = gov_storage::get_proposal_type_key(id);
-        let proposal_author_key = gov_storage::get_author_key(id);
-
-        let funds: token::Amount =
-            force_read(&shell.wl_storage, &proposal_funds_key)?;
-        let proposal_end_epoch: Epoch =
-            force_read(&shell.wl_storage, &proposal_end_epoch_key)?;
-        let proposal_type: ProposalType =
-            force_read(&shell.wl_storage, &proposal_type_key)?;
-        let proposal_author: Address =
-            force_read(&shell.wl_storage, &proposal_author_key)?;
-
-        let is_steward = pgf::is_steward(&shell.wl_storage, &proposal_author)?;
-
-        let params = read_pos_params(&shell.wl_storage)?;
-        let total_voting_power =
-            read_total_stake(&shell.wl_storage, &params, proposal_end_epoch)?;
-
-        let tally_type = TallyType::from(proposal_type.clone(), is_steward);
-        let votes = compute_proposal_votes(
-            &shell.wl_storage,
-            &params,
+    // proposals are committed to the epoch preceding the one in which they
+    // are tallied or executed
+    let Some(committing_epoch) =
+        shell.wl_storage.storage.block.epoch.checked_sub(1)
+    else {
+        return Ok(proposals_result);
+    };
+    let ids = gov_api::get_committed_proposal_ids(
+        &shell.wl_storage,
+        committing_epoch,
+    )?;
+    for id in ids {
+        // the commitment is removed with the rest of the changes of the block,
+        // so that a proposal is neither executed twice nor missed if the node
+        // restarts
+        let committing_proposals_key =
+            gov_storage::get_committing_proposals_key(id, committing_epoch.0);
+        shell.wl_storage.delete(&committing_proposals_key)?;
+
+        // proposals which already passed, and whose execution was delayed,
+        // are scheduled again at their execution epoch
+        if let Some(execution_epoch) =
+            gov_api::get_proposal_execution_epoch(&shell.wl_storage, id)?
+        {
+            tracing::debug!(
+                "Executing governance proposal {} delayed until epoch {}.",
+                id,
+                execution_epoch
+            );
+            let proposal_type_key = gov_storage::get_proposal_type_key(id);
+            let proposal_type: ProposalType =
+                force_read(&shell.wl_storage, &proposal_type_key)?;
+            let proposal_event =
+                execute_passed_proposal(shell, response, id, proposal_type)?;
+            response.events.push(proposal_event);
+            continue;
+        }
+
+        tally_and_execute_proposal(
+            shell,
+            response,
+            &mut proposals_result,
             id,
-            proposal_end_epoch,
+            false,
         )?;
-        let proposal_result =
-            compute_proposal_result(votes, total_voting_power, tally_type);
-        let proposal_result_key = gov_storage::get_proposal_result_key(id);
-        shell
-            .wl_storage
-            .write(&proposal_result_key, proposal_result)?;
-
-        let transfer_address = match proposal_result.result {
-            TallyResult::Passed => {
-                let proposal_event = match proposal_type {
-                    ProposalType::Default(_) => {
-                        let proposal_code_key =
-                            gov_storage::get_proposal_code_key(id);
-                        let proposal_code =
-                            shell.wl_storage.read_bytes(&proposal_code_key)?;
-                        let result = execute_default_proposal(
-                            shell,
-                            id,
-                            proposal_code.clone(),
-                        )?;
-                        tracing::info!(
-                            "Governance proposal (default) {} has been \
-                             executed ({}) and passed.",
-                            id,
-                            result
-                        );
-
-                        ProposalEvent::default_proposal_event(
-                            id,
-                            proposal_code.is_some(),
-                            result,
-                        )
-                        .into()
-                    }
-                    ProposalType::PGFSteward(stewards) => {
-                        let result = execute_pgf_steward_proposal(
-                            &mut shell.wl_storage,
-                            stewards,
-                        )?;
-                        tracing::info!(
-                            "Governance proposal (pgf stewards){} has been \
-                             executed and passed.",
-                            id
-                        );
-
-                        ProposalEvent::pgf_steward_proposal_event(id, result)
-                            .into()
-                    }
-                    ProposalType::PGFPayment(payments) => {
-                        let native_token =
-                            &shell.wl_storage.get_native_token()?;
-                        let result = execute_pgf_payment_proposal(
-                            &mut shell.wl_storage,
-                            native_token,
-                            payments,
-                            id,
-                        )?;
-                        tracing::info!(
-                            "Governance proposal (pgf funding) {} has been \
-                             executed and passed.",
-                            id
-                        );
-
-                        ProposalEvent::pgf_payments_proposal_event(id, result)
-                            .into()
-                    }
-                };
-                response.events.push(proposal_event);
-                proposals_result.passed.push(id);
+    }
+
+    Ok(proposals_result)
+}
+
+/// Check if the inner tx of a wrapper may be included in a block, if it is
+/// a tx submitting a proposal whose code and data are available in the
+/// clear. The epochs of the proposal must be valid in the current epoch, as
+/// they are later checked by the governance VP.
+pub fn check_init_proposal_tx<S>(
+    storage: &S,
+    tx: &Tx,
+) -> storage_api::Result<std::result::Result<(), String>>
+where
+    S: StorageRead,
+{
+    let Some(code_hash) = invariants::inner_tx_code_hash(tx) else {
+        return Ok(Ok(()));
+    };
+    let key = Key::wasm_code_name(TX_INIT_PROPOSAL.to_owned());
+    if storage.read::<Hash>(&key)? != Some(code_hash) {
+        return Ok(Ok(()));
+    }
+    let Some(data) = tx.data() else {
+        return Ok(Err("Missing the data of the proposal tx".to_owned()));
+    };
+    let proposal = match InitProposalData::try_from_slice(&data) {
+        Ok(proposal) => proposal,
+        Err(err) => {
+            return Ok(Err(format!(
+                "Failed to decode the proposal of the proposal tx: {err}"
+            )));
+        }
+    };
+    let current_epoch = storage.get_block_epoch()?;
+    let governance_parameters = gov_api::get_parameters(storage)?;
+    Ok(is_valid_proposal_epochs(
+        proposal.voting_start_epoch,
+        proposal.voting_end_epoch,
+        proposal.grace_epoch,
+        proposal.expedited,
+        current_epoch,
+        &governance_parameters,
+    )
+    .map_err(|err| err.to_string()))
+}
+
+/// Tally and execute the expedited proposals whose voting window closes at
+/// the given block height.
+pub fn execute_expedited_governance_proposals<D, H>(
+    shell: &mut Shell<D, H>,
+    height: BlockHeight,
+    response: &mut shim::response::FinalizeBlock,
+) -> Result<ProposalsResult>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let mut proposals_result = ProposalsResult::default();
+
+    let prefix = gov_storage::get_expedited_proposals_prefix(height.0);
+    let mut ids = BTreeSet::new();
+    for entry in storage_api::iter_prefix_bytes(&shell.wl_storage, &prefix)? {
+        let (key, _) = entry?;
+        // NOTE: the prefix of a height also matches greater heights starting
+        // with the same digits
+        if gov_storage::get_expedited_proposal_height(&key) != Some(height.0) {
+            continue;
+        }
+        if let Some(id) = gov_storage::get_expedited_proposal_id(&key) {
+            ids.insert(id);
+        }
+    }
+
+    for id in ids {
+        tracing::info!(
+            "Tallying expedited governance proposal {} at height {}.",
+            id,
+            height
+        );
+        tally_and_execute_proposal(
+            shell,
+            response,
+            &mut proposals_result,
+            id,
+            true,
+        )?;
+    }
+
+    Ok(proposals_result)
+}
+
+/// Pay the continuous PGF fundings set up by passed PGF payment proposals
+/// from the PGF account. This is done once per epoch, until the funding is
+/// removed by another proposal.
+pub fn execute_pgf_fundings<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let native_token = shell.wl_storage.storage.native_token.clone();
+
+    let mut pgf_fundings = pgf::get_payments(&shell.wl_storage)?;
+    // we want to pay first the oldest fundings
+    pgf_fundings.sort_by(|a, b| a.id.cmp(&b.id));
+
+    for funding in pgf_fundings {
+        let result = token::transfer(
+            &mut shell.wl_storage,
+            &native_token,
+            &ADDRESS,
+            &funding.detail.target,
+            funding.detail.amount,
+        );
+        match &result {
+            Ok(()) => tracing::info!(
+                "Paying {} tokens for {} project from proposal id {}.",
+                funding.detail.amount.to_string_native(),
+                &funding.detail.target,
+                funding.id
+            ),
+            Err(e) => tracing::warn!(
+                "Failed to pay {} tokens for {} project from proposal id {}: \
+                 {}",
+                funding.detail.amount.to_string_native(),
+                &funding.detail.target,
+                funding.id,
+                e
+            ),
+        }
+        let event = ProposalEvent::pgf_funding_payment_event(
+            funding.id,
+            &funding.detail.target,
+            funding.detail.amount,
+            result.is_ok(),
+        );
+        response.events.push(event.into());
+    }
+
+    Ok(())
+}
+
+/// Emit an event for each proposal whose voting period starts at the current
+/// epoch.
+pub fn emit_voting_started_events<D, H>(
+    shell: &Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let current_epoch = shell.wl_storage.storage.block.epoch;
+    for id in
+        gov_api::get_proposal_ids_starting_at(&shell.wl_storage, current_epoch)?
+    {
+        if let Some(proposal) =
+            gov_api::get_proposal_by_id(&shell.wl_storage, id)?
+        {
+            response
+                .events
+                .push(GovernanceEvent::VotingStarted { proposal }.into());
+        }
+    }
+    Ok(())
+}
 
-                let proposal_author_key = gov_storage::get_author_key(id);
-                shell.wl_storage.read::<Address>(&proposal_author_key)?
+/// Snapshot the voting power at the current epoch for each proposal whose
+/// voting period starts at this epoch. The votes of these proposals are
+/// tallied with this snapshot. The Ethereum bridge validator sets are also
+/// snapshotted for the proposals that change the bridge, such that the
+/// validator set signed off for the bridge matches the one of the tally.
+pub fn snapshot_proposals_voting_power<D, H>(
+    shell: &mut Shell<D, H>,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let current_epoch = shell.wl_storage.storage.block.epoch;
+    let ids = gov_api::get_proposal_ids_starting_at(
+        &shell.wl_storage,
+        current_epoch,
+    )?;
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let params = read_pos_params(&shell.wl_storage)?;
+    let snapshot =
+        take_stake_snapshot(&shell.wl_storage, &params, current_epoch)?;
+    let mut valset_snapshot = None;
+    for id in ids {
+        // a proposal may have been removed since it was indexed
+        let proposal_type_key = gov_storage::get_proposal_type_key(id);
+        let Some(proposal_type) =
+            shell.wl_storage.read::<ProposalType>(&proposal_type_key)?
+        else {
+            tracing::warn!(
+                "Governance proposal {} starting its voting period doesn't \
+                 exist, skipping its voting power snapshot.",
+                id
+            );
+            continue;
+        };
+
+        tracing::debug!(
+            "Snapshotting a total voting power of {} for governance proposal \
+             {}.",
+            snapshot.total_voting_power.to_string_native(),
+            id
+        );
+        let snapshot_key = gov_storage::get_proposal_stake_snapshot_key(id);
+        shell.wl_storage.write(&snapshot_key, snapshot.clone())?;
+
+        if proposal_type.affects_eth_bridge() {
+            let valset_snapshot = valset_snapshot
+                .get_or_insert_with(|| {
+                    shell
+                        .wl_storage
+                        .ethbridge_queries()
+                        .get_validator_set_snapshot(Some(current_epoch))
+                })
+                .clone();
+            tracing::debug!(
+                "Snapshotting the Ethereum bridge validator sets of epoch {} \
+                 for governance proposal {}.",
+                current_epoch,
+                id
+            );
+            let valset_snapshot_key =
+                gov_storage::get_proposal_bridge_valset_snapshot_key(id);
+            shell
+                .wl_storage
+                .write(&valset_snapshot_key, valset_snapshot)?;
+        }
+    }
+    Ok(())
+}
+
+/// Build the events of the proposals submitted or withdrawn and of the votes
+/// cast by an accepted transaction, from the storage keys that it changed.
+pub fn governance_tx_events<S>(
+    storage: &S,
+    changed_keys: &BTreeSet<Key>,
+) -> storage_api::Result<Vec<Event>>
+where
+    S: StorageRead,
+{
+    let current_epoch = storage.get_block_epoch()?;
+    let mut events = Vec::new();
+    for key in changed_keys {
+        if gov_storage::is_author_key(key) {
+            let Some(id) = gov_storage::get_proposal_id(key) else {
+                continue;
+            };
+            // the author key of a withdrawn proposal is deleted
+            let Some(proposal) = gov_api::get_proposal_by_id(storage, id)?
+            else {
+                events.push(GovernanceEvent::ProposalWithdrawn { id }.into());
+                continue;
+            };
+            // expedited proposals are open for voting as soon as they are
+            // submitted
+            let voting_started = proposal.voting_start_epoch == current_epoch;
+            events.push(
+                GovernanceEvent::ProposalSubmitted {
+                    proposal: proposal.clone(),
+                }
+                .into(),
+            );
+            if voting_started {
+                events.push(GovernanceEvent::VotingStarted { proposal }.into());
             }
-            TallyResult::Rejected => {
-                if let ProposalType::PGFPayment(_) = proposal_type {
-                    if proposal_result.two_thirds_nay_over_two_thirds_total() {
-                        pgf::remove_steward(
-                            &mut shell.wl_storage,
-                            &proposal_author,
-                        )?;
-
-                        tracing::info!(
-                            "Governance proposal {} was rejected with 2/3 of \
-                             nay votes over 2/3 of the total voting power. If \
-                             {} is a steward, it's being removed from the \
-                             stewards set.",
-                            id,
-                            proposal_author
-                        );
-                    }
+        } else if gov_storage::is_vote_key(key) {
+            let (Some(id), Some(validator), Some(voter)) = (
+                gov_storage::get_proposal_id(key),
+                gov_storage::get_vote_delegation_address(key),
+                gov_storage::get_voter_address(key),
+            ) else {
+                continue;
+            };
+            let Some(vote) = storage.read(key)? else {
+                continue;
+            };
+            events.push(
+                GovernanceEvent::VoteCast {
+                    id,
+                    voter: voter.clone(),
+                    validator: validator.clone(),
+                    vote,
                 }
+                .into(),
+            );
+        }
+    }
+    Ok(events)
+}
+
+/// Tally the votes of a proposal with the voting power snapshotted when it
+/// entered its voting period, execute it if it has passed and return or burn
+/// its locked funds.
+fn tally_and_execute_proposal<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+    proposals_result: &mut ProposalsResult,
+    id: u64,
+    expedited: bool,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let proposal_funds_key = gov_storage::get_funds_key(id);
+    let proposal_type_key = gov_storage::get_proposal_type_key(id);
+    let proposal_author_key = gov_storage::get_author_key(id);
+    let start_epoch_key = gov_storage::get_voting_start_epoch_key(id);
+
+    let funds: token::Amount =
+        force_read(&shell.wl_storage, &proposal_funds_key)?;
+    let proposal_type: ProposalType =
+        force_read(&shell.wl_storage, &proposal_type_key)?;
+    let proposal_author: Address =
+        force_read(&shell.wl_storage, &proposal_author_key)?;
+    let voting_start_epoch: Epoch =
+        force_read(&shell.wl_storage, &start_epoch_key)?;
+
+    let is_steward = pgf::is_steward(&shell.wl_storage, &proposal_author)?;
+
+    let params = read_pos_params(&shell.wl_storage)?;
+    let snapshot = read_proposal_stake_snapshot(
+        &shell.wl_storage,
+        &params,
+        id,
+        voting_start_epoch,
+    )?;
+
+    // expedited proposals require a supermajority to pass
+    let tally_type = if expedited {
+        TallyType::ThreeQuarters
+    } else {
+        TallyType::from(proposal_type.clone(), is_steward)
+    };
+    let votes = compute_proposal_votes(&shell.wl_storage, id, &snapshot)?;
+    let veto_threshold = gov_api::get_veto_threshold(&shell.wl_storage)?;
+    let proposal_result = compute_proposal_result(
+        votes,
+        snapshot.total_voting_power,
+        tally_type,
+        veto_threshold,
+    );
+    let is_vetoed = proposal_result.is_vetoed(veto_threshold);
+    let proposal_result_key = gov_storage::get_proposal_result_key(id);
+    shell
+        .wl_storage
+        .write(&proposal_result_key, proposal_result)?;
+    response.events.push(
+        GovernanceEvent::ProposalTallied {
+            id,
+            result: proposal_result,
+            expedited,
+            vetoed: is_vetoed,
+        }
+        .into(),
+    );
+
+    let transfer_address = match proposal_result.result {
+        TallyResult::Passed => {
+            let execution_delay =
+                gov_api::get_min_proposal_execution_delay(&shell.wl_storage)?;
+            // expedited proposals are not delayed
+            if execution_delay == 0 || expedited {
+                let proposal_event = execute_passed_proposal(
+                    shell,
+                    response,
+                    id,
+                    proposal_type,
+                )?;
+                response.events.push(proposal_event);
+            } else {
+                let current_epoch = shell.wl_storage.storage.block.epoch;
+                let execution_epoch = current_epoch + execution_delay;
+                schedule_proposal_execution(
+                    &mut shell.wl_storage,
+                    id,
+                    execution_epoch,
+                )?;
+                tracing::info!(
+                    "Governance proposal {} has passed and will be executed \
+                     at epoch {}.",
+                    id,
+                    execution_epoch
+                );
+            }
+            proposals_result.passed.push(id);
+
+            let proposal_author_key = gov_storage::get_author_key(id);
+            shell.wl_storage.read::<Address>(&proposal_author_key)?
+        }
+        TallyResult::Rejected => {
+            if let ProposalType::PGFPayment(_) = proposal_type {
+                if proposal_result.two_thirds_nay_over_two_thirds_total() {
+                    pgf::remove_steward(
+                        &mut shell.wl_storage,
+                        &proposal_author,
+                    )?;
+
+                    tracing::info!(
+                        "Governance proposal {} was rejected with 2/3 of nay \
+                         votes over 2/3 of the total voting power. If {} is a \
+                         steward, it's being removed from the stewards set.",
+                        id,
+                        proposal_author
+                    );
+                }
+            }
+            proposals_result.rejected.push(id);
+            shell.wl_storage.delete(
+                &gov_storage::get_proposal_bridge_valset_snapshot_key(id),
+            )?;
+
+            // the funds of a vetoed proposal are burned, otherwise they are
+            // returned to its author
+            if is_vetoed {
+                let proposal_event =
+                    ProposalEvent::vetoed_proposal_event(id).into();
+                response.events.push(proposal_event);
+
+                tracing::info!(
+                    "Governance proposal {} has been vetoed and its funds are \
+                     burned.",
+                    id
+                );
+
+                None
+            } else {
                 let proposal_event =
                     ProposalEvent::rejected_proposal_event(id).into();
                 response.events.push(proposal_event);
-                proposals_result.rejected.push(id);
 
                 tracing::info!(
                     "Governance proposal {} has been executed and rejected.",
                     id
                 );
 
-                None
+                Some(proposal_author)
             }
-        };
+        }
+    };
+
+    let native_token = shell.wl_storage.storage.native_token.clone();
+    if let Some(address) = transfer_address {
+        token::transfer(
+            &mut shell.wl_storage,
+            &native_token,
+            &gov_address,
+            &address,
+            funds,
+        )?;
+    } else {
+        token::burn(&mut shell.wl_storage, &native_token, &gov_address, funds)?;
+    }
+    Ok(())
+}
+
+/// Execute the payload of a proposal that has passed.
+fn execute_passed_proposal<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+    id: u64,
+    proposal_type: ProposalType,
+) -> Result<Event>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let proposal_type_name = proposal_type.to_string();
+    // the keys written by the proposal, to find out if it changed the
+    // Ethereum bridge
+    let mut written_keys = BTreeSet::new();
+    let (proposal_event, succeeded) = match proposal_type {
+        ProposalType::Default(_) => {
+            let proposal_code_key = gov_storage::get_proposal_code_key(id);
+            let proposal_code =
+                shell.wl_storage.read_bytes(&proposal_code_key)?;
+            let (result, changed_keys) =
+                execute_default_proposal(shell, id, proposal_code.clone())?;
+            written_keys = changed_keys;
+            tracing::info!(
+                "Governance proposal (default) {} has been executed ({}) and \
+                 passed.",
+                id,
+                result
+            );
+
+            let proposal_event = if proposal_code.is_some() && !result {
+                ProposalEvent::failed_execution_proposal_event(id)
+            } else {
+                ProposalEvent::default_proposal_event(
+                    id,
+                    proposal_code.is_some(),
+                    result,
+                )
+            };
+            (proposal_event, result)
+        }
+        ProposalType::PGFSteward(stewards) => {
+            let result =
+                execute_pgf_steward_proposal(&mut shell.wl_storage, stewards)?;
+            tracing::info!(
+                "Governance proposal (pgf stewards){} has been executed and \
+                 passed.",
+                id
+            );
+
+            (
+                ProposalEvent::pgf_steward_proposal_event(id, result),
+                result,
+            )
+        }
+        ProposalType::PGFPayment(payments) => {
+            let native_token = &shell.wl_storage.get_native_token()?;
+            let result = execute_pgf_payment_proposal(
+                &mut shell.wl_storage,
+                native_token,
+                payments,
+                id,
+            )?;
+            tracing::info!(
+                "Governance proposal (pgf funding) {} has been executed and \
+                 passed.",
+                id
+            );
 
-        let native_token = shell.wl_storage.storage.native_token.clone();
-        if let Some(address) = transfer_address {
-            token::transfer(
+            (
+                ProposalEvent::pgf_payments_proposal_event(id, result),
+                result,
+            )
+        }
+        ProposalType::ParameterChange(changes) => {
+            let changed_keys: BTreeSet<Key> =
+                changes.iter().map(|change| change.key.clone()).collect();
+            let result = execute_parameter_change_proposal(
                 &mut shell.wl_storage,
-                &native_token,
-                &gov_address,
-                &address,
-                funds,
+                changes,
+                id,
             )?;
-        } else {
-            token::burn(
+            if result {
+                written_keys = changed_keys;
+            } else {
+                let execution_failed_key =
+                    gov_storage::get_proposal_execution_failed_key(id);
+                shell.wl_storage.write(&execution_failed_key, ())?;
+            }
+            tracing::info!(
+                "Governance proposal (parameter change) {} has been executed \
+                 ({}) and passed.",
+                id,
+                result
+            );
+
+            (
+                ProposalEvent::parameter_change_proposal_event(id, result),
+                result,
+            )
+        }
+        ProposalType::CodeAllowlist(update) => {
+            let result = execute_code_allowlist_proposal(
                 &mut shell.wl_storage,
-                &native_token,
-                &gov_address,
-                funds,
+                update,
+                id,
             )?;
+            tracing::info!(
+                "Governance proposal (code allowlist) {} has been executed \
+                 and passed.",
+                id
+            );
+
+            (
+                ProposalEvent::code_allowlist_proposal_event(id, result),
+                result,
+            )
         }
-    }
+        ProposalType::ConsensusParams(update) => {
+            let result = execute_consensus_params_proposal(
+                &mut shell.wl_storage,
+                response,
+                update,
+                id,
+            )?;
+            if !result {
+                let execution_failed_key =
+                    gov_storage::get_proposal_execution_failed_key(id);
+                shell.wl_storage.write(&execution_failed_key, ())?;
+            }
+            tracing::info!(
+                "Governance proposal (consensus params) {} has been executed \
+                 ({}) and passed.",
+                id,
+                result
+            );
 
-    Ok(proposals_result)
+            (
+                ProposalEvent::consensus_params_proposal_event(id, result),
+                result,
+            )
+        }
+        ProposalType::TreasurySpend(spend) => {
+            let native_token = &shell.wl_storage.get_native_token()?;
+            let result = execute_treasury_spend_proposal(
+                &mut shell.wl_storage,
+                native_token,
+                &spend,
+                id,
+            )?;
+            if !result {
+                let execution_failed_key =
+                    gov_storage::get_proposal_execution_failed_key(id);
+                shell.wl_storage.write(&execution_failed_key, ())?;
+            }
+            tracing::info!(
+                "Governance proposal (treasury spend) {} has been executed \
+                 ({}) and passed.",
+                id,
+                result
+            );
+            response.events.push(
+                GovernanceEvent::TreasurySpend {
+                    id,
+                    recipient: spend.recipient,
+                    amount: spend.amount,
+                    succeeded: result,
+                }
+                .into(),
+            );
+
+            (
+                ProposalEvent::treasury_spend_proposal_event(id, result),
+                result,
+            )
+        }
+        ProposalType::Text => {
+            // text proposals have no payload, they only signal the support
+            // of their content
+            tracing::info!(
+                "Governance proposal (text) {} has been tallied and passed.",
+                id
+            );
+
+            (ProposalEvent::default_proposal_event(id, false, true), true)
+        }
+    };
+    consume_bridge_valset_snapshot(&mut shell.wl_storage, id, &written_keys)?;
+    response.events.push(
+        GovernanceEvent::ProposalExecuted {
+            id,
+            proposal_type: proposal_type_name,
+            succeeded,
+        }
+        .into(),
+    );
+    Ok(proposal_event.into())
 }
 
-fn compute_proposal_votes<S>(
-    storage: &S,
-    params: &PosParams,
-    proposal_id: u64,
-    epoch: Epoch,
-) -> storage_api::Result<ProposalVotes>
+/// Prune the snapshot of the Ethereum bridge validator sets of an executed
+/// proposal, if any. If the proposal scheduled a rotation of the bridge
+/// contract, the snapshot is kept as the validator sets that the new
+/// contract is initialized with, which are handed over to the oracle along
+/// with the rotation.
+fn consume_bridge_valset_snapshot<S>(
+    storage: &mut S,
+    id: u64,
+    written_keys: &BTreeSet<Key>,
+) -> storage_api::Result<()>
 where
-    S: StorageRead,
+    S: StorageRead + StorageWrite,
 {
-    let votes = gov_api::get_proposal_votes(storage, proposal_id)?;
-
-    let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
-    let mut validator_voting_power: HashMap<Address, VotePower> =
-        HashMap::default();
-    let mut delegators_vote: HashMap<Address, TallyVote> = HashMap::default();
-    let mut delegator_voting_power: HashMap<
-        Address,
-        HashMap<Address, VotePower>,
-    > = HashMap::default();
-
-    for vote in votes {
-        if vote.is_validator() {
-            let validator = vote.validator.clone();
-            let vote_data = vote.data.clone();
-
-            let validator_stake =
-                read_total_stake(storage, params, epoch).unwrap_or_default();
-
-            validators_vote.insert(validator.clone(), vote_data.into());
-            validator_voting_power.insert(validator, validator_stake);
-        } else {
-            let validator = vote.validator.clone();
-            let delegator = vote.delegator.clone();
-            let vote_data = vote.data.clone();
-
-            let bond_id = BondId {
-                source: delegator.clone(),
-                validator: validator.clone(),
-            };
-            let delegator_stake =
-                bond_amount(storage, &bond_id, epoch).unwrap_or_default();
-
-            delegators_vote.insert(delegator.clone(), vote_data.into());
-            delegator_voting_power
-                .entry(delegator)
-                .or_default()
-                .insert(validator, delegator_stake);
-        }
+    let snapshot_key = gov_storage::get_proposal_bridge_valset_snapshot_key(id);
+    let Some(snapshot) = storage.read::<ValidatorSetSnapshot>(&snapshot_key)?
+    else {
+        return Ok(());
+    };
+    storage.delete(&snapshot_key)?;
+    if written_keys.contains(&bridge_storage::bridge_contract_rotation_key()) {
+        tracing::info!(
+            "Governance proposal {} scheduled a rotation of the Ethereum \
+             bridge contract, initialized with the validator sets of epoch {}.",
+            id,
+            snapshot.bridge.epoch
+        );
+        storage.write(
+            &bridge_storage::bridge_contract_rotation_valset_key(),
+            snapshot,
+        )?;
     }
+    Ok(())
+}
 
-    Ok(ProposalVotes {
-        validators_vote,
-        validator_voting_power,
-        delegators_vote,
-        delegator_voting_power,
-    })
+/// Delay the execution of a passed proposal until `execution_epoch`.
+fn schedule_proposal_execution<S>(
+    storage: &mut S,
+    id: u64,
+    execution_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let execution_epoch_key = gov_storage::get_proposal_execution_epoch_key(id);
+    storage.write(&execution_epoch_key, execution_epoch)?;
+
+    // proposals are loaded in the first block of the epoch following
+    // their committing epoch
+    let committing_proposals_key =
+        gov_storage::get_committing_proposals_key(id, execution_epoch.prev().0);
+    storage.write(&committing_proposals_key, ())
 }
 
+/// Execute the code of a default proposal, if any. On success, the keys
+/// written by the code are returned along with the result.
 fn execute_default_proposal<D, H>(
     shell: &mut Shell<D, H>,
     id: u64,
     proposal_code: Option<Vec<u8>>,
-) -> storage_api::Result<bool>
+) -> storage_api::Result<(bool, BTreeSet<Key>)>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
     H: StorageHasher + Sync + 'static,
@@ -275,43 +839,55 @@ where
         tx.set_data(Data::new(encode(&id)));
         tx.set_code(Code::new(code, None));
 
-        let tx_result = protocol::dispatch_tx(
+        // the proposal code runs under its own gas and memory limits, the
+        // former being set by governance
+        let max_gas =
+            gov_api::get_max_proposal_execution_gas(&shell.wl_storage)?;
+        let tx_result = protocol::apply_proposal_code(
             tx,
-            &[], /*  this is used to compute the fee
-                  * based on the code size. We dont
-                  * need it here. */
-            TxIndex::default(),
-            &mut TxGasMeter::new_from_sub_limit(u64::MAX.into()), /* No gas limit for governance proposal */
-            &mut shell.wl_storage,
-            &mut shell.vp_wasm_cache,
-            &mut shell.tx_wasm_cache,
-            None,
+            protocol::ShellParams::new(
+                &mut TxGasMeter::new_from_sub_limit(max_gas.into()),
+                &mut shell.wl_storage,
+                &mut shell.vp_wasm_cache,
+                &mut shell.tx_wasm_cache,
+            ),
         );
         shell
             .wl_storage
             .storage
             .delete(&pending_execution_key)
             .expect("Should be able to delete the storage.");
-        match tx_result {
-            Ok(tx_result) => {
-                if tx_result.is_accepted() {
-                    shell.wl_storage.commit_tx();
-                    Ok(true)
-                } else {
-                    Ok(false)
-                }
+        let error = match tx_result {
+            Ok(tx_result) if tx_result.is_accepted() => {
+                shell.wl_storage.commit_tx();
+                return Ok((true, tx_result.changed_keys));
             }
-            Err(_) => {
-                shell.wl_storage.drop_tx();
-                Ok(false)
-            }
-        }
+            Ok(tx_result) => format!(
+                "rejected by the validity predicates of {:?}",
+                tx_result.vps_result.rejected_vps
+            ),
+            Err(err) => err.to_string(),
+        };
+        shell.wl_storage.drop_tx();
+
+        // a failed execution doesn't abort the block, the proposal is
+        // marked as passed with a failed execution instead
+        tracing::warn!(
+            "Governance proposal {} has passed but its code failed to \
+             execute: {}",
+            id,
+            error
+        );
+        let execution_failed_key =
+            gov_storage::get_proposal_execution_failed_key(id);
+        shell.wl_storage.write(&execution_failed_key, ())?;
+        Ok((false, BTreeSet::new()))
     } else {
         tracing::info!(
             "Governance proposal {} doesn't have any associated proposal code.",
             id
         );
-        Ok(true)
+        Ok((true, BTreeSet::new()))
     }
 }
 
@@ -340,6 +916,234 @@ where
     Ok(true)
 }
 
+/// Write the new values of the protocol parameters changed by a proposal.
+/// The changes were validated by the governance VP when the proposal was
+/// submitted. The update fails, without aborting the block, if a new max
+/// proposal bytes no longer fits the max block bytes of the consensus params.
+fn execute_parameter_change_proposal<S>(
+    storage: &mut S,
+    changes: Vec<ParameterChange>,
+    proposal_id: u64,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+{
+    let max_proposal_bytes_key = get_max_proposal_bytes_key();
+    if let Some(change) = changes
+        .iter()
+        .find(|change| change.key == max_proposal_bytes_key)
+    {
+        let params: ConsensusParams =
+            force_read(storage, &gov_storage::get_consensus_params_key())?;
+        let is_valid = ProposalBytes::try_from_slice(&change.value)
+            .map_or(false, |bytes| bytes.get() <= params.block.max_bytes);
+        if !is_valid {
+            tracing::warn!(
+                "Parameter change from proposal id {} failed: the max \
+                 proposal bytes no longer fit the max block bytes.",
+                proposal_id
+            );
+            return Ok(false);
+        }
+    }
+    for ParameterChange { key, value } in changes {
+        storage.write_bytes(&key, value)?;
+        tracing::info!(
+            "Execute parameter change from proposal id {}: updated {}.",
+            proposal_id,
+            key
+        );
+    }
+
+    Ok(true)
+}
+
+/// Update the tx and VP code allowlists with the code hashes added and
+/// removed by a proposal. The updated allowlists are enforced from the epoch
+/// in which the proposal is executed.
+///
+/// As an empty allowlist allows any code, the update fails, without changing
+/// either allowlist, if it would empty an allowlist that isn't empty.
+fn execute_code_allowlist_proposal<S>(
+    storage: &mut S,
+    update: CodeAllowlistUpdate,
+    proposal_id: u64,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+{
+    let CodeAllowlistUpdate { tx, vp } = update;
+    let tx_key = parameters::storage::get_tx_whitelist_storage_key();
+    let Some(tx_allowlist) = updated_allowlist(storage, &tx_key, tx)? else {
+        tracing::warn!(
+            "Code allowlist update from proposal id {} failed: it would empty \
+             the tx allowlist, allowing any tx code.",
+            proposal_id
+        );
+        return Ok(false);
+    };
+    let vp_key = parameters::storage::get_vp_whitelist_storage_key();
+    let Some(vp_allowlist) = updated_allowlist(storage, &vp_key, vp)? else {
+        tracing::warn!(
+            "Code allowlist update from proposal id {} failed: it would empty \
+             the VP allowlist, allowing any VP code.",
+            proposal_id
+        );
+        return Ok(false);
+    };
+    parameters::update_tx_whitelist_parameter(storage, tx_allowlist)?;
+    parameters::update_vp_whitelist_parameter(storage, vp_allowlist)?;
+    tracing::info!(
+        "Execute code allowlist update from proposal id {}.",
+        proposal_id
+    );
+
+    Ok(true)
+}
+
+/// Update the consensus params of the chain, if the update is still valid
+/// against the current params and fits the max proposal bytes. The update
+/// fails, without aborting the block, otherwise.
+fn execute_consensus_params_proposal<S>(
+    storage: &mut S,
+    response: &mut shim::response::FinalizeBlock,
+    update: ConsensusParamsUpdate,
+    proposal_id: u64,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+{
+    let params_key = gov_storage::get_consensus_params_key();
+    let mut params: ConsensusParams = force_read(storage, &params_key)?;
+    let max_proposal_bytes: ProposalBytes =
+        force_read(storage, &get_max_proposal_bytes_key())?;
+    let is_valid = update.is_valid(&params)
+        && update
+            .block
+            .as_ref()
+            .map_or(true, |block| block.max_bytes >= max_proposal_bytes.get());
+    if !is_valid {
+        tracing::warn!(
+            "Consensus params update from proposal id {} failed: the params \
+             have changed since it was proposed, such that it is no longer \
+             valid.",
+            proposal_id
+        );
+        return Ok(false);
+    }
+    update.clone().apply(&mut params);
+    storage.write(&params_key, params)?;
+    update_consensus_params(response, update);
+    tracing::info!(
+        "Execute consensus params update from proposal id {}.",
+        proposal_id
+    );
+
+    Ok(true)
+}
+
+/// Return the consensus params changed by a proposal to CometBFT, which
+/// applies them from the next block. The params of proposals executed later
+/// in the same block take precedence.
+fn update_consensus_params(
+    response: &mut shim::response::FinalizeBlock,
+    update: ConsensusParamsUpdate,
+) {
+    let params = response
+        .consensus_param_updates
+        .get_or_insert_with(Default::default);
+    if let Some(block) = update.block {
+        params.block = Some(BlockParams {
+            max_bytes: block.max_bytes as i64,
+            max_gas: block.max_gas,
+        });
+    }
+    if let Some(evidence) = update.evidence {
+        params.evidence = Some(EvidenceParams {
+            max_age_num_blocks: evidence.max_age_num_blocks as i64,
+            max_age_duration: Some(Duration {
+                seconds: evidence.max_age_duration.0 as i64,
+                nanos: 0,
+            }),
+            max_bytes: evidence.max_bytes as i64,
+        });
+    }
+}
+
+/// Read the allowlist stored at the given key, and apply the given changes to
+/// it. Returns `None` if the changes would empty an allowlist that isn't
+/// empty.
+fn updated_allowlist<S>(
+    storage: &S,
+    key: &Key,
+    changes: Vec<AddRemove<Hash>>,
+) -> Result<Option<Vec<String>>>
+where
+    S: StorageRead,
+{
+    let current: Vec<String> = storage.read(key)?.unwrap_or_default();
+    let was_empty = current.is_empty();
+    let allowlist = apply_allowlist_changes(current, changes);
+    Ok((was_empty || !allowlist.is_empty()).then_some(allowlist))
+}
+
+/// Add and remove code hashes from an allowlist of lowercase hex hashes
+fn apply_allowlist_changes(
+    mut allowlist: Vec<String>,
+    changes: Vec<AddRemove<Hash>>,
+) -> Vec<String> {
+    for change in changes {
+        match change {
+            AddRemove::Add(hash) => {
+                let hash = hash.to_string().to_lowercase();
+                if !allowlist.contains(&hash) {
+                    allowlist.push(hash);
+                }
+            }
+            AddRemove::Remove(hash) => {
+                let hash = hash.to_string().to_lowercase();
+                allowlist.retain(|allowed| allowed != &hash);
+            }
+        }
+    }
+    allowlist
+}
+
+/// Transfer the native tokens of a treasury spend from the PGF treasury to
+/// its recipient. The spend fails, without aborting the block, if the
+/// treasury can't cover it.
+fn execute_treasury_spend_proposal<S>(
+    storage: &mut S,
+    token: &Address,
+    spend: &TreasurySpend,
+    proposal_id: u64,
+) -> Result<bool>
+where
+    S: StorageRead + StorageWrite,
+{
+    let treasury_balance = token::read_balance(storage, token, &ADDRESS)?;
+    if treasury_balance < spend.amount {
+        tracing::warn!(
+            "Treasury spend from proposal id {} of {} to {} failed: the \
+             treasury balance is only {}.",
+            proposal_id,
+            spend.amount.to_string_native(),
+            spend.recipient,
+            treasury_balance.to_string_native()
+        );
+        return Ok(false);
+    }
+    token::transfer(storage, token, &ADDRESS, &spend.recipient, spend.amount)?;
+    tracing::info!(
+        "Execute treasury spend from proposal id {}: sent {} to {}.",
+        proposal_id,
+        spend.amount.to_string_native(),
+        spend.recipient
+    );
+
+    Ok(true)
+}
+
 fn execute_pgf_payment_proposal<S>(
     storage: &mut S,
     token: &Address,
@@ -370,10 +1174,9 @@ where
                     pgf_storage::fundings_handle()
                         .remove(storage, &target.target)?;
                     tracing::info!(
-                        "Execute ContinousPgf from proposal id {}: set {} to \
-                         {}.",
+                        "Execute ContinousPgf from proposal id {}: removed \
+                         funding of {}.",
                         proposal_id,
-                        target.amount.to_string_native(),
                         target.target
                     );
                 }
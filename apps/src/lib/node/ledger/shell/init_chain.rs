@@ -2,6 +2,10 @@
 use std::collections::HashMap;
 use std::hash::Hash;
 
+use namada::core::ledger::governance::storage::keys as gov_storage;
+use namada::core::ledger::governance::storage::proposal::{
+    BlockParamsUpdate, ConsensusParams, EvidenceParamsUpdate,
+};
 use namada::ledger::parameters::Parameters;
 use namada::ledger::storage::traits::StorageHasher;
 use namada::ledger::storage::{DBIter, DB};
@@ -12,7 +16,7 @@ use namada::proof_of_stake::BecomeValidator;
 use namada::types::address::Address;
 use namada::types::hash::Hash as CodeHash;
 use namada::types::key::*;
-use namada::types::time::{DateTimeUtc, TimeZone, Utc};
+use namada::types::time::{DateTimeUtc, DurationSecs, TimeZone, Utc};
 use namada::vm::validate_untrusted_wasm;
 use namada_sdk::eth_bridge::EthBridgeStatus;
 use namada_sdk::proof_of_stake::PosParams;
@@ -106,6 +110,27 @@ where
         let gov_params = genesis.get_gov_params();
         gov_params.init_storage(&mut self.wl_storage)?;
 
+        // Record the consensus params of CometBFT, against which their
+        // updates by governance are validated
+        let params = &init.consensus_params;
+        let consensus_params = ConsensusParams {
+            block: BlockParamsUpdate {
+                max_bytes: params.block.max_bytes,
+                max_gas: params.block.max_gas,
+            },
+            evidence: EvidenceParamsUpdate {
+                max_age_num_blocks: params.evidence.max_age_num_blocks,
+                max_age_duration: DurationSecs(
+                    params.evidence.max_age_duration.0.as_secs(),
+                ),
+                max_bytes: params.evidence.max_bytes as u64,
+            },
+        };
+        self.wl_storage.write(
+            &gov_storage::get_consensus_params_key(),
+            consensus_params,
+        )?;
+
         // configure the Ethereum bridge if the configuration is set.
         if let Some(config) = genesis.get_eth_bridge_params() {
             tracing::debug!("Initializing Ethereum bridge storage.");
@@ -125,6 +150,7 @@ where
             .storage
             .init_genesis_epoch(initial_height, genesis_time, &parameters)
             .expect("Initializing genesis epoch must not fail");
+        self.refresh_epoch_cache();
 
         // PoS system depends on epoch being initialized
         let pos_params = genesis.get_pos_params();
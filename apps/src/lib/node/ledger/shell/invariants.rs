@@ -0,0 +1,583 @@
+//! Critical invariants of the ledger's state, checked at the end of the
+//! first block of each epoch, or every given number of blocks.
+//!
+//! The set of invariants to check is configured by governance, via the
+//! invariant checks parameter, and so is the interval between checks.
+//! Should any of them be violated, the chain is halted: from then on, only
+//! governance txs are accepted, Ethereum events are no longer applied and
+//! the Bridge pool root is no longer signed, such that accounting bugs can't
+//! keep compounding while a fix is prepared. The chain resumes once a governance proposal deletes the
+//! halted status from storage.
+//!
+//! N.B. the halted status is part of the state of the chain, therefore all
+//! the nodes of a network agree on it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use namada::core::ledger::eth_bridge::storage::bridge_pool::{
+    is_pending_transfer_key, BRIDGE_POOL_ADDRESS,
+};
+use namada::core::ledger::eth_bridge::storage::native_erc20_key;
+use namada::core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
+use namada::ledger::parameters;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::ledger::storage_api::{self, ResultExt, StorageRead};
+use namada::proto::Tx;
+use namada::types::address::{Address, InternalAddress};
+use namada::types::eth_bridge_pool::PendingTransfer;
+use namada::types::ethereum_events::EthAddress;
+use namada::types::hash::Hash;
+use namada::types::storage::{DbKeySeg, Key};
+use namada::types::token;
+use namada::types::transaction::protocol::ProtocolTxType;
+use namada_sdk::tx::{TX_INIT_PROPOSAL, TX_VOTE_PROPOSAL};
+
+use crate::node::ledger::shell::Shell;
+
+/// Invariants of the ledger's state, which may be checked at the end of a
+/// block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Invariant {
+    /// The balances of the native token add up to its total supply.
+    NativeSupply,
+    /// The balances of each token minted by the Ethereum bridge add up to
+    /// its total supply.
+    ///
+    /// N.B. checking this invariant iterates over the balances of all
+    /// the tokens.
+    BridgedSupply,
+    /// The tokens escrowed by the Ethereum bridge cover the transfers
+    /// pending in the Bridge pool, and their gas fees.
+    BridgeEscrow,
+}
+
+impl Invariant {
+    /// All the invariants that may be checked.
+    pub const ALL: [Invariant; 3] = [
+        Invariant::NativeSupply,
+        Invariant::BridgedSupply,
+        Invariant::BridgeEscrow,
+    ];
+
+    /// The name of this invariant, as stored in the invariant checks
+    /// parameter.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Invariant::NativeSupply => "native_supply",
+            Invariant::BridgedSupply => "bridged_supply",
+            Invariant::BridgeEscrow => "bridge_escrow",
+        }
+    }
+
+    /// Check this invariant, returning a description of its violation,
+    /// if it does not hold.
+    pub fn check<S>(&self, storage: &S) -> storage_api::Result<Option<String>>
+    where
+        S: StorageRead,
+    {
+        match self {
+            Invariant::NativeSupply => {
+                let native_token = storage.get_native_token()?;
+                check_native_supply(storage, &native_token)
+            }
+            Invariant::BridgedSupply => check_bridged_supply(storage),
+            Invariant::BridgeEscrow => check_bridge_escrow(storage),
+        }
+    }
+}
+
+impl fmt::Display for Invariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Invariant {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|invariant| invariant.name() == name)
+            .ok_or_else(|| format!("Unknown invariant {name:?}"))
+    }
+}
+
+/// Check that the balances of the native token add up to its total supply.
+fn check_native_supply<S>(
+    storage: &S,
+    native_token: &Address,
+) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    let total_supply =
+        storage_api::token::read_total_supply(storage, native_token)?;
+    let mut balances = token::Amount::zero();
+    let prefix = token::balance_prefix(native_token);
+    for item in storage_api::iter_prefix::<token::Amount>(storage, &prefix)? {
+        let (key, balance) = item?;
+        if token::is_balance_key(native_token, &key).is_some() {
+            balances = checked_add(balances, balance)?;
+        }
+    }
+    Ok((balances != total_supply).then(|| {
+        format!(
+            "The balances of the native token add up to {}, but its total \
+             supply is {}",
+            balances.to_string_native(),
+            total_supply.to_string_native()
+        )
+    }))
+}
+
+/// Check that the balances of each token minted by the Ethereum bridge add
+/// up to its total supply.
+fn check_bridged_supply<S>(storage: &S) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    let mut balances: BTreeMap<Address, token::Amount> = BTreeMap::new();
+    let mut supplies: BTreeMap<Address, token::Amount> = BTreeMap::new();
+    let prefix = Key::from(DbKeySeg::AddressSeg(Address::Internal(
+        InternalAddress::Multitoken,
+    )));
+    for item in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, bytes) = item?;
+        if let Some([token, _]) = token::is_any_token_balance_key(&key) {
+            if is_bridged(token) {
+                let balance = decode_amount(&bytes)?;
+                let sum = balances.entry(token.clone()).or_default();
+                *sum = checked_add(*sum, balance)?;
+            }
+        } else if let Some(token) = token::is_any_minted_balance_key(&key) {
+            if is_bridged(token) {
+                supplies.insert(token.clone(), decode_amount(&bytes)?);
+            }
+        }
+    }
+    let tokens: Vec<_> = balances.keys().chain(supplies.keys()).collect();
+    for token in tokens {
+        let balance = balances.get(token).copied().unwrap_or_default();
+        let supply = supplies.get(token).copied().unwrap_or_default();
+        if balance != supply {
+            return Ok(Some(format!(
+                "The balances of the bridged token {token} add up to {}, but \
+                 its total supply is {}",
+                balance.raw_amount(),
+                supply.raw_amount()
+            )));
+        }
+    }
+    Ok(None)
+}
+
+/// Check that the tokens escrowed by the Ethereum bridge cover the
+/// transfers pending in the Bridge pool, and their gas fees. Transfers of
+/// wrapped NAM escrow NAM under the bridge's address, whereas all other
+/// tokens and the gas fees are escrowed under the Bridge pool's address.
+fn check_bridge_escrow<S>(storage: &S) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    let native_token = storage.get_native_token()?;
+    let wnam = storage.read::<EthAddress>(&native_erc20_key())?;
+    // the amounts owed by each escrow account, per token
+    let mut owed: BTreeMap<(Address, Address), token::Amount> = BTreeMap::new();
+    let mut owe = |escrow: &Address, token: Address, amount| {
+        let sum = owed.entry((escrow.clone(), token)).or_default();
+        *sum = checked_add(*sum, amount)?;
+        storage_api::Result::Ok(())
+    };
+    let prefix = Key::from(DbKeySeg::AddressSeg(BRIDGE_POOL_ADDRESS));
+    for item in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, bytes) = item?;
+        if !is_pending_transfer_key(&key) {
+            continue;
+        }
+        let pending =
+            PendingTransfer::try_from_slice(&bytes).into_storage_result()?;
+        if Some(&pending.transfer.asset) == wnam.as_ref() {
+            owe(
+                &BRIDGE_ADDRESS,
+                native_token.clone(),
+                pending.transfer.amount,
+            )?;
+        } else {
+            owe(
+                &BRIDGE_POOL_ADDRESS,
+                pending.token_address(),
+                pending.transfer.amount,
+            )?;
+        }
+        owe(
+            &BRIDGE_POOL_ADDRESS,
+            pending.gas_fee.token.clone(),
+            pending.gas_fee.amount,
+        )?;
+    }
+    for ((escrow, token), amount) in owed {
+        let escrowed =
+            storage_api::token::read_balance(storage, &token, &escrow)?;
+        if escrowed < amount {
+            return Ok(Some(format!(
+                "{escrow} escrows {} of the token {token}, but the transfers \
+                 pending in the Bridge pool add up to {}",
+                escrowed.raw_amount(),
+                amount.raw_amount()
+            )));
+        }
+    }
+    Ok(None)
+}
+
+/// Check if the given token is minted by the Ethereum bridge.
+#[inline]
+fn is_bridged(token: &Address) -> bool {
+    matches!(
+        token,
+        Address::Internal(InternalAddress::Erc20(_) | InternalAddress::Nut(_))
+    )
+}
+
+#[inline]
+fn decode_amount(bytes: &[u8]) -> storage_api::Result<token::Amount> {
+    token::Amount::try_from_slice(bytes).into_storage_result()
+}
+
+#[inline]
+fn checked_add(
+    lhs: token::Amount,
+    rhs: token::Amount,
+) -> storage_api::Result<token::Amount> {
+    lhs.checked_add(rhs)
+        .ok_or_else(|| storage_api::Error::new_const("Token balances overflow"))
+}
+
+/// Check if the inner tx of a wrapper with the given code hash may be
+/// included in a block. Once the chain has been halted, only governance
+/// txs are accepted, which requires the code of their inner tx to be
+/// available in the clear.
+pub fn check_tx_allowed<S>(
+    storage: &S,
+    code_hash: Option<&Hash>,
+) -> storage_api::Result<Result<(), String>>
+where
+    S: StorageRead,
+{
+    let Some(reason) = parameters::read_chain_halted(storage)? else {
+        return Ok(Ok(()));
+    };
+    if let Some(code_hash) = code_hash {
+        for code_name in [TX_INIT_PROPOSAL, TX_VOTE_PROPOSAL] {
+            let key = Key::wasm_code_name(code_name.to_owned());
+            if storage.read::<Hash>(&key)?.as_ref() == Some(code_hash) {
+                return Ok(Ok(()));
+            }
+        }
+    }
+    Ok(Err(format!(
+        "The chain has been halted, and only accepts governance txs: {reason}"
+    )))
+}
+
+/// Check if a protocol tx of the given type may be applied. Once the chain
+/// has been halted, the txs applying Ethereum events are not, as these
+/// mint and transfer tokens. The events remain in the queues of the
+/// validators, to be applied once the chain resumes. Neither are the
+/// signatures over the Bridge pool root, which release the tokens escrowed
+/// on Ethereum.
+///
+/// The other protocol txs remain exempt from the halt, as they do not move
+/// any tokens: validator set updates keep the bridge in sync with the
+/// consensus validator set, while attestations, misbehavior evidence and
+/// heartbeats keep the validators accountable whilst the chain is halted.
+pub fn check_protocol_tx_allowed<S>(
+    storage: &S,
+    tx_type: &ProtocolTxType,
+) -> storage_api::Result<Result<(), String>>
+where
+    S: StorageRead,
+{
+    let halted_action = match tx_type {
+        ProtocolTxType::EthEventsVext | ProtocolTxType::EthereumEvents => {
+            "apply Ethereum events"
+        }
+        ProtocolTxType::BridgePoolVext | ProtocolTxType::BridgePool => {
+            "sign the Bridge pool root"
+        }
+        ProtocolTxType::ValSetUpdateVext
+        | ProtocolTxType::ValidatorSetUpdate
+        | ProtocolTxType::AttestationVext
+        | ProtocolTxType::MisbehaviorEvidence
+        | ProtocolTxType::ValidatorHeartbeat => return Ok(Ok(())),
+    };
+    Ok(match parameters::read_chain_halted(storage)? {
+        Some(reason) => Err(format!(
+            "The chain has been halted, and does not {halted_action}: \
+             {reason}"
+        )),
+        None => Ok(()),
+    })
+}
+
+/// Return the hash of the code of the inner tx of a wrapper, if it is
+/// available in the clear.
+pub fn inner_tx_code_hash(tx: &Tx) -> Option<Hash> {
+    tx.get_section(tx.code_sechash())
+        .and_then(|section| section.code_sec())
+        .map(|code| code.code.hash())
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Check the invariants configured by governance, halting the chain
+    /// upon the first violation found. Unless governance configured the
+    /// number of blocks between checks, these run at the first block of
+    /// each epoch. Nothing is checked once the chain has been halted.
+    pub fn check_invariants(
+        &mut self,
+        new_epoch: bool,
+    ) -> storage_api::Result<()> {
+        let height = self.wl_storage.storage.block.height;
+        let due =
+            match parameters::read_invariant_checks_interval(&self.wl_storage)?
+            {
+                Some(interval) => height.0.checked_rem(interval) == Some(0),
+                None => new_epoch,
+            };
+        if !due || parameters::read_chain_halted(&self.wl_storage)?.is_some() {
+            return Ok(());
+        }
+        for name in parameters::read_invariant_checks(&self.wl_storage)? {
+            let invariant = match name.parse::<Invariant>() {
+                Ok(invariant) => invariant,
+                Err(err) => {
+                    tracing::warn!(%err, "Skipping unknown invariant check");
+                    continue;
+                }
+            };
+            if let Some(violation) = invariant.check(&self.wl_storage)? {
+                let reason =
+                    format!("Invariant {invariant} was violated: {violation}");
+                tracing::error!(%reason, "Halting the chain");
+                parameters::write_chain_halted(&mut self.wl_storage, reason)?;
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_invariants {
+    use namada::core::ledger::eth_bridge::storage::{
+        bridge_pool, wrapped_erc20s,
+    };
+    use namada::ledger::storage::WlStorage;
+    use namada::ledger::storage_api::StorageWrite;
+    use namada::types::address;
+    use namada::types::eth_bridge_pool::{
+        GasFee, TransferToEthereum, TransferToEthereumKind,
+    };
+    use namada::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Test that a violated invariant halts the chain, and that only
+    /// governance txs are accepted afterwards.
+    #[test]
+    fn test_violated_invariant_halts_chain() {
+        let (mut shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+        shell
+            .wl_storage
+            .write(
+                &parameters::storage::get_invariant_checks_key(),
+                vec![Invariant::BridgedSupply.name().to_string()],
+            )
+            .expect("Test failed");
+
+        // balanced supply of a bridged token
+        let dai = wrapped_erc20s::token(&DAI_ERC20_ETH_ADDRESS);
+        let amount = token::Amount::from_u64(100);
+        storage_api::token::credit_tokens(
+            &mut shell.wl_storage,
+            &dai,
+            &address::testing::established_address_1(),
+            amount,
+        )
+        .expect("Test failed");
+        shell.check_invariants(true).expect("Test failed");
+        assert!(
+            parameters::read_chain_halted(&shell.wl_storage)
+                .expect("Test failed")
+                .is_none()
+        );
+
+        // the supply of the token is no longer backed by balances
+        shell
+            .wl_storage
+            .write(
+                &token::balance_key(
+                    &dai,
+                    &address::testing::established_address_1(),
+                ),
+                token::Amount::from_u64(50),
+            )
+            .expect("Test failed");
+        shell.check_invariants(true).expect("Test failed");
+        assert!(
+            parameters::read_chain_halted(&shell.wl_storage)
+                .expect("Test failed")
+                .is_some()
+        );
+
+        // only governance txs are accepted
+        let vote_hash = Hash::sha256(b"tx_vote_proposal");
+        shell
+            .wl_storage
+            .write(&Key::wasm_code_name(TX_VOTE_PROPOSAL.to_owned()), vote_hash)
+            .expect("Test failed");
+        let check = |code_hash| {
+            check_tx_allowed(&shell.wl_storage, code_hash).expect("Test failed")
+        };
+        assert!(check(Some(&vote_hash)).is_ok());
+        assert!(check(Some(&Hash::sha256(b"tx_transfer"))).is_err());
+        assert!(check(None).is_err());
+
+        // Ethereum events are no longer applied
+        let check = |tx_type| {
+            check_protocol_tx_allowed(&shell.wl_storage, &tx_type)
+                .expect("Test failed")
+        };
+        assert!(check(ProtocolTxType::EthEventsVext).is_err());
+        assert!(check(ProtocolTxType::EthereumEvents).is_err());
+        // nor is the Bridge pool root signed
+        assert!(check(ProtocolTxType::BridgePoolVext).is_err());
+        assert!(check(ProtocolTxType::BridgePool).is_err());
+        // but the validators remain accountable
+        assert!(check(ProtocolTxType::ValSetUpdateVext).is_ok());
+        assert!(check(ProtocolTxType::ValidatorHeartbeat).is_ok());
+    }
+
+    /// Test that invariants are only checked at the first block of each
+    /// epoch, unless governance configured the interval between checks.
+    #[test]
+    fn test_invariant_checks_interval() {
+        let (mut shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+        shell
+            .wl_storage
+            .write(
+                &parameters::storage::get_invariant_checks_key(),
+                vec![Invariant::NativeSupply.name().to_string()],
+            )
+            .expect("Test failed");
+        // the supply of the native token is no longer backed by balances
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        shell
+            .wl_storage
+            .write(
+                &token::balance_key(
+                    &native_token,
+                    &address::testing::established_address_1(),
+                ),
+                token::Amount::from_u64(1),
+            )
+            .expect("Test failed");
+        let is_halted = |shell: &test_utils::TestShell| {
+            parameters::read_chain_halted(&shell.wl_storage)
+                .expect("Test failed")
+                .is_some()
+        };
+
+        shell.wl_storage.storage.block.height = 3.into();
+        shell
+            .wl_storage
+            .write(
+                &parameters::storage::get_invariant_checks_interval_key(),
+                2u64,
+            )
+            .expect("Test failed");
+        shell.check_invariants(true).expect("Test failed");
+        assert!(!is_halted(&shell));
+
+        shell
+            .wl_storage
+            .delete(&parameters::storage::get_invariant_checks_interval_key())
+            .expect("Test failed");
+        shell.check_invariants(false).expect("Test failed");
+        assert!(!is_halted(&shell));
+        shell.check_invariants(true).expect("Test failed");
+        assert!(is_halted(&shell));
+    }
+
+    /// Test that the tokens escrowed by the Ethereum bridge must cover the
+    /// transfers pending in the Bridge pool.
+    #[test]
+    fn test_bridge_escrow() {
+        let (mut shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+        let wl_storage = &mut shell.wl_storage;
+        let sender = address::testing::established_address_1();
+        let pending = PendingTransfer {
+            transfer: TransferToEthereum {
+                kind: TransferToEthereumKind::Erc20,
+                asset: DAI_ERC20_ETH_ADDRESS,
+                recipient: EthAddress([1; 20]),
+                sender: sender.clone(),
+                amount: token::Amount::from_u64(10),
+            },
+            gas_fee: GasFee {
+                token: wl_storage.storage.native_token.clone(),
+                amount: token::Amount::from_u64(5),
+                payer: sender,
+            },
+        };
+        wl_storage
+            .write(&bridge_pool::get_pending_key(&pending), pending)
+            .expect("Test failed");
+        let check = |wl_storage: &WlStorage<_, _>| {
+            Invariant::BridgeEscrow
+                .check(wl_storage)
+                .expect("Test failed")
+        };
+        assert!(check(wl_storage).is_some());
+
+        // escrow the transferred tokens, but not the gas fees
+        let dai = wrapped_erc20s::token(&DAI_ERC20_ETH_ADDRESS);
+        storage_api::token::credit_tokens(
+            wl_storage,
+            &dai,
+            &BRIDGE_POOL_ADDRESS,
+            token::Amount::from_u64(10),
+        )
+        .expect("Test failed");
+        assert!(check(wl_storage).is_some());
+
+        let native_token = wl_storage.storage.native_token.clone();
+        storage_api::token::credit_tokens(
+            wl_storage,
+            &native_token,
+            &BRIDGE_POOL_ADDRESS,
+            token::Amount::from_u64(5),
+        )
+        .expect("Test failed");
+        assert!(check(wl_storage).is_none());
+    }
+
+    /// Test that invariants are parsed from their names.
+    #[test]
+    fn test_invariant_names() {
+        for invariant in Invariant::ALL {
+            assert_eq!(invariant.name().parse(), Ok(invariant));
+        }
+        assert!("unknown".parse::<Invariant>().is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Rotation of the protocol and Ethereum bridge hot keys of this validator.
+//!
+//! A validator changes its keys with a tx, which sets the new keys on chain
+//! at the pipeline epoch, and records the new keypairs in the wallet of its
+//! node. The node keeps signing with its current keypairs until the new keys
+//! become active, as the vote extensions and protocol txs of a validator are
+//! verified against the keys active at the epoch they were signed in. Once
+//! the new keys are active, they are written back to the wallet, such that
+//! a restarted node doesn't sign with its former keys.
+
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::types::key::*;
+
+use super::{Shell, ShellMode};
+use crate::wallet::ValidatorData;
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Switch to the protocol and Ethereum bridge keypairs this validator is
+    /// rotating to, once their keys have become active on chain, and save
+    /// them to the validator's wallet.
+    pub fn activate_rotated_validator_keys(&mut self) {
+        let ShellMode::Validator {
+            data: ValidatorData { address, keys },
+            ..
+        } = &mut self.mode
+        else {
+            return;
+        };
+        if keys.next_protocol_keypair.is_none()
+            && keys.next_eth_bridge_keypair.is_none()
+        {
+            return;
+        }
+        let pos_queries = self.wl_storage.pos_queries();
+
+        let protocol_key =
+            pos_queries.read_validator_protocol_key(address, None);
+        let eth_hot_key = pos_queries.read_validator_eth_hot_key(address, None);
+        let mut rotated = false;
+        if rotate_keypair(
+            &mut keys.protocol_keypair,
+            &mut keys.next_protocol_keypair,
+            protocol_key,
+        ) {
+            tracing::info!(
+                validator = %address,
+                "Switched to the rotated protocol key of the validator"
+            );
+            rotated = true;
+        }
+        if rotate_keypair(
+            &mut keys.eth_bridge_keypair,
+            &mut keys.next_eth_bridge_keypair,
+            eth_hot_key,
+        ) {
+            tracing::info!(
+                validator = %address,
+                "Switched to the rotated Ethereum bridge hot key of the \
+                 validator"
+            );
+            rotated = true;
+        }
+        if rotated {
+            self.save_validator_keys();
+        }
+    }
+
+    /// Write the current and next keypairs of this validator to the wallet
+    /// its node loads them from.
+    fn save_validator_keys(&self) {
+        let ShellMode::Validator { data, .. } = &self.mode else {
+            return;
+        };
+        let wallet_path = self.base_dir.join(self.chain_id.as_str());
+        let Some(mut wallet) = crate::wallet::load(&wallet_path) else {
+            tracing::error!(
+                "Failed to load the wallet from {} to save the rotated keys \
+                 of the validator",
+                wallet_path.to_string_lossy()
+            );
+            return;
+        };
+        let Some(stored) = wallet.get_validator_data_mut() else {
+            tracing::error!(
+                "The wallet at {} has no validator data to save the rotated \
+                 keys of the validator to",
+                wallet_path.to_string_lossy()
+            );
+            return;
+        };
+        stored.keys = data.keys.clone();
+        if let Err(err) = crate::wallet::save(&wallet) {
+            tracing::error!(
+                "Failed to save the rotated keys of the validator to the \
+                 wallet: {err}"
+            );
+        }
+    }
+}
+
+/// Replace `keypair` with `next_keypair`, if the public key of the latter is
+/// the `active_pk`. Returns whether the keypair was replaced.
+fn rotate_keypair(
+    keypair: &mut common::SecretKey,
+    next_keypair: &mut Option<common::SecretKey>,
+    active_pk: Option<common::PublicKey>,
+) -> bool {
+    match next_keypair.take() {
+        Some(next) if Some(next.ref_to()) == active_pk => {
+            *keypair = next;
+            true
+        }
+        next => {
+            *next_keypair = next;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_key_rotation {
+    use namada::proof_of_stake::{change_protocol_key, read_pos_params};
+    use namada::types::vote_extensions::heartbeat::Heartbeat;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+    use crate::node::ledger::shell::vote_extensions::heartbeat::build_info;
+
+    /// Test that a validator keeps using its current protocol key until the
+    /// key it rotates to becomes active at the pipeline epoch, and that the
+    /// heartbeats it signs in the meantime remain valid.
+    #[test]
+    fn test_protocol_key_rotation() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let validator = shell
+            .mode
+            .get_validator_address()
+            .expect("Test failed")
+            .clone();
+        let old_keypair =
+            shell.mode.get_protocol_key().expect("Test failed").clone();
+        let protocol_pk = |shell: &test_utils::TestShell| {
+            shell.mode.get_protocol_key().map(RefTo::ref_to)
+        };
+        let new_keypair = testing::keypair_3();
+        let ShellMode::Validator { data, .. } = &mut shell.mode else {
+            panic!("Test failed")
+        };
+        data.keys.next_protocol_keypair = Some(new_keypair.clone());
+
+        let current_epoch = shell.wl_storage.storage.get_current_epoch().0;
+        change_protocol_key(
+            &mut shell.wl_storage,
+            &validator,
+            &new_keypair.ref_to(),
+            current_epoch,
+        )
+        .expect("Test failed");
+
+        // The old key is still used and honored until the pipeline epoch
+        shell.activate_rotated_validator_keys();
+        assert_eq!(protocol_pk(&shell), Some(old_keypair.ref_to()));
+        let heartbeat = Heartbeat {
+            validator_addr: validator.clone(),
+            epoch: current_epoch,
+            build: build_info(),
+        }
+        .sign(&old_keypair);
+        assert!(shell.validate_validator_heartbeat(&heartbeat).is_ok());
+
+        let params = read_pos_params(&shell.wl_storage).expect("Test failed");
+        shell.wl_storage.storage.block.epoch =
+            current_epoch + params.pipeline_len;
+        shell.activate_rotated_validator_keys();
+        assert_eq!(protocol_pk(&shell), Some(new_keypair.ref_to()));
+        let ShellMode::Validator { data, .. } = &shell.mode else {
+            panic!("Test failed")
+        };
+        assert!(data.keys.next_protocol_keypair.is_none());
+    }
+}
@@ -5,10 +5,15 @@
 //! and [`Shell::process_proposal`] must be also reverted
 //! (unless we can simply overwrite them in the next block).
 //! More info in <https://github.com/anoma/namada/issues/362>.
+mod audit_log;
 pub mod block_alloc;
+mod epoch_cache;
+mod event_sink;
 mod finalize_block;
 mod governance;
 mod init_chain;
+pub mod invariants;
+mod key_rotation;
 pub mod prepare_proposal;
 pub mod process_proposal;
 pub(super) mod queries;
@@ -16,10 +21,12 @@ mod stats;
 #[cfg(any(test, feature = "testing"))]
 #[allow(dead_code)]
 pub mod testing;
+pub mod unjail;
 pub mod utils;
 mod vote_extensions;
 
-use std::collections::{BTreeSet, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::mem;
 use std::path::{Path, PathBuf};
@@ -31,6 +38,7 @@ use borsh_ext::BorshSerializeExt;
 use masp_primitives::transaction::Transaction;
 use namada::core::hints;
 use namada::core::ledger::eth_bridge;
+use namada::core::types::ethereum_structs;
 use namada::ledger::events::log::EventLog;
 use namada::ledger::events::Event;
 use namada::ledger::gas::{Gas, TxGasMeter};
@@ -55,10 +63,10 @@ use namada::proof_of_stake::{self, process_slashes, read_pos_params, slash};
 use namada::proto::{self, Section, Tx};
 use namada::types::address::Address;
 use namada::types::chain::ChainId;
-use namada::types::ethereum_events::EthereumEvent;
+use namada::types::ethereum_events::{EthereumEvent, Uint};
 use namada::types::internal::{ExpiredTx, TxInQueue};
 use namada::types::key::*;
-use namada::types::storage::{BlockHeight, Key, TxIndex};
+use namada::types::storage::{BlockHeight, Epoch, Key, TxIndex};
 use namada::types::time::DateTimeUtc;
 use namada::types::transaction::protocol::EthereumTxData;
 use namada::types::transaction::{DecryptedTx, TxType, WrapperTx};
@@ -66,12 +74,24 @@ use namada::types::{address, token};
 use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::{WasmCacheAccess, WasmCacheRwAccess};
 use namada_sdk::eth_bridge::{EthBridgeQueries, EthereumOracleConfig};
+use namada_sdk::queries::{
+    CrashReports, EthEventsQueueMetrics, NodeMetrics, ValsetProofCache,
+    WriteHotspots,
+};
 use namada_sdk::tendermint::AppHash;
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
 use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
+use self::audit_log::StorageAuditLog;
+use self::epoch_cache::EpochCache;
+use self::event_sink::EventPublisher;
+use self::vote_extensions::attestation_vext::AttestationLanes;
+pub use self::vote_extensions::attestation_vext::{
+    Attestation, AttestationLane,
+};
+use super::ethereum_oracle::progress::ProgressStore;
 use super::ethereum_oracle::{self as oracle, last_processed_block};
 use crate::config::{self, genesis, TendermintMode, ValidatorLocalConfig};
 use crate::facade::tendermint::abci::types::{Misbehavior, MisbehaviorKind};
@@ -81,7 +101,8 @@ use crate::facade::tendermint_proto::google::protobuf::Timestamp;
 use crate::facade::tendermint_proto::v0_37::crypto::public_key;
 use crate::node::ledger::shims::abcipp_shim_types::shim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::response::TxResult;
-use crate::node::ledger::{storage, tendermint_node};
+use crate::node::ledger::startup::{StartupProgress, StartupStage};
+use crate::node::ledger::{storage, supervisor, tendermint_node};
 use crate::wallet::{ValidatorData, ValidatorKeys};
 
 fn key_to_tendermint(
@@ -156,6 +177,9 @@ pub enum ErrorCodes {
     FeeError = 12,
     InvalidVoteExtension = 13,
     TooLarge = 14,
+    TxNotWhitelisted = 15,
+    ChainHalted = 16,
+    TxCodeNotAllowed = 17,
 }
 
 impl ErrorCodes {
@@ -166,11 +190,11 @@ impl ErrorCodes {
         // NOTE: pattern match on all `ErrorCodes` variants, in order
         // to catch potential bugs when adding new codes
         match self {
-            Ok | WasmRuntimeError => true,
+            Ok | WasmRuntimeError | TxCodeNotAllowed => true,
             InvalidTx | InvalidSig | InvalidOrder | ExtraTxs
             | Undecryptable | AllocationError | ReplayTx | InvalidChainId
             | ExpiredTx | TxGasLimit | FeeError | InvalidVoteExtension
-            | TooLarge => false,
+            | TooLarge | TxNotWhitelisted | ChainHalted => false,
         }
     }
 }
@@ -236,12 +260,103 @@ pub(super) enum ShellMode {
     Seed,
 }
 
+/// The maximum number of Ethereum events queued up for inclusion in
+/// vote extensions. While the queue is full, new events are left in the
+/// channel of the oracle rather than being dropped, such that the oracle
+/// waits for the queue to drain before processing more Ethereum blocks.
+const MAX_QUEUED_ETH_EVENTS: usize = 10_000;
+
+/// An Ethereum event queued up for inclusion in vote extensions.
+#[derive(Debug, Clone, Copy)]
+struct QueuedEthEvent {
+    /// The height of the last committed block when the event was received
+    received_at_height: BlockHeight,
+    /// Whether the event was already reported as stalled
+    reported_stalled: bool,
+}
+
+/// The next nonces expected by Namada for each kind of Ethereum event
+/// with its own nonce space, from which the priority of queued events is
+/// computed.
+#[derive(Debug, Clone, Default)]
+pub(super) struct NextEthEventNonces {
+    /// The nonce of the next transfers to Namada
+    pub transfers_to_namada: Uint,
+    /// The nonce of the next transfers to Ethereum, i.e. the nonce of the
+    /// Bridge pool
+    pub transfers_to_ethereum: Uint,
+    /// The nonce of the next NFT transfers to Namada
+    pub nft_transfers_to_namada: Uint,
+}
+
+/// The priority of a queued Ethereum event to be included in a vote
+/// extension, in ascending order. Each kind of event has its own nonce
+/// space, hence events are compared by the distance of their nonce from
+/// the next nonce expected for their kind. The events closest to it come
+/// first, since events with newer nonces can't be processed before them,
+/// followed by the events transferring the most value.
+///
+/// Validator set updates are compared by the distance of their nonce from
+/// the oldest one queued, given as `next_valset_nonce`.
+fn eth_event_priority(
+    event: &EthereumEvent,
+    next_nonces: &NextEthEventNonces,
+    next_valset_nonce: Uint,
+) -> (Uint, Reverse<token::Amount>) {
+    fn total(amounts: impl Iterator<Item = token::Amount>) -> token::Amount {
+        amounts.fold(token::Amount::zero(), |total, amount| {
+            total.checked_add(amount).unwrap_or_else(token::Amount::max)
+        })
+    }
+    let (nonce, next_nonce, value) = match event {
+        EthereumEvent::TransfersToNamada { nonce, transfers } => (
+            nonce,
+            next_nonces.transfers_to_namada,
+            total(transfers.iter().map(|t| t.amount)),
+        ),
+        EthereumEvent::TransfersToEthereum {
+            nonce, transfers, ..
+        } => (
+            nonce,
+            next_nonces.transfers_to_ethereum,
+            total(transfers.iter().map(|t| t.amount)),
+        ),
+        EthereumEvent::TransfersToNamadaBatch { nonce, batches } => (
+            nonce,
+            next_nonces.transfers_to_namada,
+            total(batches.iter().flatten().map(|t| t.amount)),
+        ),
+        EthereumEvent::NftTransfersToNamada { nonce, .. } => (
+            nonce,
+            next_nonces.nft_transfers_to_namada,
+            token::Amount::zero(),
+        ),
+        EthereumEvent::ValidatorSetUpdate { nonce, .. } => {
+            (nonce, next_valset_nonce, token::Amount::zero())
+        }
+        // bridge contract handovers go first, since the events of the
+        // new contract are ignored until the handover is confirmed
+        EthereumEvent::BridgeContractHandover { .. } => {
+            return (Uint::from(0), Reverse(token::Amount::max()));
+        }
+    };
+    (nonce.saturating_sub(next_nonce), Reverse(value))
+}
+
 /// A channel for pulling events from the Ethereum oracle
 /// and queueing them up for inclusion in vote extensions
 #[derive(Debug)]
 pub(super) struct EthereumReceiver {
     channel: Receiver<EthereumEvent>,
-    queue: BTreeSet<EthereumEvent>,
+    /// The queued events, along with when they were received
+    queue: BTreeMap<EthereumEvent, QueuedEthEvent>,
+    /// The maximum number of queued events
+    max_queued_events: usize,
+    /// The number of blocks after which a queued event is reported as
+    /// stalled. Stalled events are not reported if set to zero.
+    stalled_after_blocks: u64,
+    /// Counters of the events evicted from the queue
+    metrics: EthEventsQueueMetrics,
 }
 
 impl EthereumReceiver {
@@ -250,7 +365,11 @@ impl EthereumReceiver {
     pub fn new(channel: Receiver<EthereumEvent>) -> Self {
         Self {
             channel,
-            queue: BTreeSet::new(),
+            queue: BTreeMap::new(),
+            max_queued_events: MAX_QUEUED_ETH_EVENTS,
+            stalled_after_blocks:
+                config::ethereum_bridge::ledger::DEFAULT_STALLED_EVENT_BLOCKS,
+            metrics: EthEventsQueueMetrics::default(),
         }
     }
 
@@ -260,18 +379,41 @@ impl EthereumReceiver {
     /// Since vote extensions require ordering of Ethereum
     /// events, we do that here. We also de-duplicate events.
     /// Events may be filtered out of the queue with a provided
-    /// predicate.
-    pub fn fill_queue<F>(&mut self, mut keep_event: F)
+    /// predicate, which is also applied to the events already
+    /// queued. No new events are pulled while the queue is full,
+    /// such that they are not lost. New events are recorded as
+    /// received at the given block height.
+    ///
+    /// Returns whether all the events sent by the oracle were pulled.
+    pub fn fill_queue<F>(
+        &mut self,
+        height: BlockHeight,
+        mut keep_event: F,
+    ) -> bool
     where
         F: FnMut(&EthereumEvent) -> bool,
     {
+        self.evict(&mut keep_event);
+
         let mut new_events = 0;
         let mut filtered_events = 0;
-        while let Ok(eth_event) = self.channel.try_recv() {
-            if keep_event(&eth_event) && self.queue.insert(eth_event) {
-                new_events += 1;
-            } else {
+        let mut pulled_all = false;
+        while self.queue.len() < self.max_queued_events {
+            let Ok(eth_event) = self.channel.try_recv() else {
+                pulled_all = true;
+                break;
+            };
+            if !keep_event(&eth_event) || self.queue.contains_key(&eth_event) {
                 filtered_events += 1;
+            } else {
+                self.queue.insert(
+                    eth_event,
+                    QueuedEthEvent {
+                        received_at_height: height,
+                        reported_stalled: false,
+                    },
+                );
+                new_events += 1;
             }
         }
         if new_events + filtered_events > 0 {
@@ -281,11 +423,91 @@ impl EthereumReceiver {
                 "received Ethereum events"
             );
         }
+        if self.queue.len() >= self.max_queued_events {
+            self.metrics.full += 1;
+            tracing::warn!(
+                times_full = self.metrics.full,
+                max_queued_events = self.max_queued_events,
+                "The queue of Ethereum events is full, leaving new events \
+                 with the oracle until queued events are included on chain"
+            );
+        }
+        pulled_all
+    }
+
+    /// Evict the queued events which are no longer to be kept according
+    /// to the given predicate, e.g. because they were confirmed.
+    fn evict<F>(&mut self, keep_event: &mut F)
+    where
+        F: FnMut(&EthereumEvent) -> bool,
+    {
+        let mut evicted_events = 0;
+        self.queue.retain(|event, _| {
+            let keep = keep_event(event);
+            if !keep {
+                evicted_events += 1;
+            }
+            keep
+        });
+        if evicted_events > 0 {
+            self.metrics.evicted += evicted_events;
+            tracing::debug!(
+                evicted_events,
+                total_evicted_events = self.metrics.evicted,
+                "Evicted Ethereum events which are no longer valid"
+            );
+        }
     }
 
-    /// Get a copy of the queue
-    pub fn get_events(&self) -> Vec<EthereumEvent> {
-        self.queue.iter().cloned().collect()
+    /// Return the counters of the events of the queue.
+    pub fn metrics(&self) -> EthEventsQueueMetrics {
+        self.metrics
+    }
+
+    /// Get a copy of the queue, holding at most `max_events` events.
+    ///
+    /// If more events are queued, those to be voted on first are picked
+    /// according to their [`eth_event_priority`], given the next nonces
+    /// expected by Namada. A slot is always reserved for the oldest queued
+    /// validator set update, as the bridge halts without it. The returned
+    /// events remain sorted and de-duplicated.
+    pub fn get_events(
+        &self,
+        max_events: Option<usize>,
+        next_nonces: &NextEthEventNonces,
+    ) -> Vec<EthereumEvent> {
+        let Some(max_events) = max_events.filter(|&max| max < self.queue.len())
+        else {
+            return self.queue.keys().cloned().collect();
+        };
+        let (next_valset_nonce, pending_valset_update) = self
+            .queue
+            .keys()
+            .filter_map(|event| match event {
+                EthereumEvent::ValidatorSetUpdate { nonce, .. } => {
+                    Some((*nonce, event))
+                }
+                _ => None,
+            })
+            .min()
+            .unzip();
+        let next_valset_nonce = next_valset_nonce.unwrap_or_default();
+        let pending_valset_update =
+            pending_valset_update.filter(|_| max_events > 0);
+        let mut events: Vec<_> = self
+            .queue
+            .keys()
+            .filter(|&event| Some(event) != pending_valset_update)
+            .collect();
+        events.sort_by_cached_key(|event| {
+            eth_event_priority(event, next_nonces, next_valset_nonce)
+        });
+        events.truncate(
+            max_events.saturating_sub(pending_valset_update.is_some() as usize),
+        );
+        events.extend(pending_valset_update);
+        events.sort();
+        events.into_iter().cloned().collect()
     }
 
     /// Remove the given [`EthereumEvent`] from the queue, if present.
@@ -295,6 +517,35 @@ impl EthereumReceiver {
     pub fn remove_event(&mut self, event: &EthereumEvent) {
         self.queue.remove(event);
     }
+
+    /// Take the queued events which were received more than
+    /// `stalled_after_blocks` blocks before the given height, and which
+    /// were not reported as stalled yet, along with the height they were
+    /// received at. Each stalled event is only returned once.
+    pub fn take_stalled_events(
+        &mut self,
+        height: BlockHeight,
+    ) -> Vec<(EthereumEvent, BlockHeight)> {
+        if self.stalled_after_blocks == 0 {
+            return vec![];
+        }
+        let stalled_after_blocks = self.stalled_after_blocks;
+        let stalled: Vec<_> = self
+            .queue
+            .iter_mut()
+            .filter(|(_, queued)| {
+                !queued.reported_stalled
+                    && height.0.saturating_sub(queued.received_at_height.0)
+                        > stalled_after_blocks
+            })
+            .map(|(event, queued)| {
+                queued.reported_stalled = true;
+                (event.clone(), queued.received_at_height)
+            })
+            .collect();
+        self.metrics.stalled += stalled.len() as u64;
+        stalled
+    }
 }
 
 impl ShellMode {
@@ -320,6 +571,53 @@ impl ShellMode {
         }
     }
 
+    /// Get the last Ethereum block processed by the Ethereum oracle, if
+    /// this node runs one.
+    pub fn get_oracle_last_processed_block(
+        &self,
+    ) -> Option<ethereum_structs::BlockHeight> {
+        match self {
+            ShellMode::Validator {
+                eth_oracle: Some(eth_oracle),
+                ..
+            } => eth_oracle.last_processed_block_receiver.borrow().clone(),
+            _ => None,
+        }
+    }
+
+    /// Return the counters of the queue of Ethereum events, if this node
+    /// runs an Ethereum oracle.
+    pub fn eth_events_queue_metrics(&self) -> Option<EthEventsQueueMetrics> {
+        match self {
+            ShellMode::Validator {
+                eth_oracle:
+                    Some(EthereumOracleChannels {
+                        ethereum_receiver, ..
+                    }),
+                ..
+            } => Some(ethereum_receiver.metrics()),
+            _ => None,
+        }
+    }
+
+    /// Take the queued Ethereum events which stalled as of the given
+    /// block height, if any.
+    pub fn take_stalled_eth_events(
+        &mut self,
+        height: BlockHeight,
+    ) -> Vec<(EthereumEvent, BlockHeight)> {
+        match self {
+            ShellMode::Validator {
+                eth_oracle:
+                    Some(EthereumOracleChannels {
+                        ethereum_receiver, ..
+                    }),
+                ..
+            } => ethereum_receiver.take_stalled_events(height),
+            _ => vec![],
+        }
+    }
+
     /// Get the protocol keypair for this validator.
     pub fn get_protocol_key(&self) -> Option<&common::SecretKey> {
         match self {
@@ -410,10 +708,43 @@ where
     /// limit the how many block heights in the past can the storage be
     /// queried for reading values.
     storage_read_past_height_limit: Option<u64>,
-    /// Proposal execution tracking
-    pub proposal_data: HashSet<u64>,
+    /// Taken from config `archive_endpoints`. Endpoints of archive nodes
+    /// suggested to clients querying heights that are no longer available.
+    archive_endpoints: Vec<String>,
+    /// Taken from config `accept_misbehavior_evidence`. Whether evidence
+    /// of validator misbehavior submitted by watchtowers is broadcast.
+    accept_misbehavior_evidence: bool,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// Cached index of the first block heights of each epoch.
+    epoch_cache: EpochCache,
+    /// Log of crashes of the supervised subsystems of the ledger.
+    crash_reports: CrashReports,
+    /// The most frequently written storage key prefixes.
+    write_hotspots: WriteHotspots,
+    /// Cache of the complete validator set update proofs.
+    valset_proof_cache: ValsetProofCache,
+    /// Whether the Ethereum oracle was last told to pause, rather than to
+    /// resume.
+    eth_oracle_paused: bool,
+    /// Whether the Ethereum oracle has been paused by the node operator.
+    eth_oracle_paused_by_operator: bool,
+    /// The Ethereum bridge config last sent to the oracle.
+    eth_oracle_config: Option<EthereumOracleConfig>,
+    /// Optional audit log of the storage writes made during block
+    /// execution.
+    storage_audit_log: Option<StorageAuditLog>,
+    /// Optional publisher of the emitted events to an external
+    /// message broker.
+    event_publisher: Option<EventPublisher>,
+    /// Lanes of validator-attested data, which may be enabled by
+    /// governance.
+    attestation_lanes: AttestationLanes<D, H>,
+    /// The last epoch in which this validator broadcast a heartbeat.
+    last_heartbeat_epoch: Option<Epoch>,
+    /// Node-local metrics, e.g. the counters of the discarded evidence of
+    /// validator misbehavior.
+    node_metrics: NodeMetrics,
 }
 
 /// Channels for communicating with an Ethereum oracle.
@@ -422,6 +753,15 @@ pub struct EthereumOracleChannels {
     ethereum_receiver: EthereumReceiver,
     control_sender: oracle::control::Sender,
     last_processed_block_receiver: last_processed_block::Receiver,
+    /// The store in which the last Ethereum block acknowledged by the
+    /// ledger is recorded, for the oracle to resume from after a restart.
+    progress: Option<ProgressStore>,
+    /// The last Ethereum block processed by the oracle whose events were
+    /// all pulled into the queue of the [`EthereumReceiver`].
+    acknowledged_block: Option<ethereum_structs::BlockHeight>,
+    /// The node-local metrics the oracle records its counters in, to be
+    /// shared with the shell.
+    node_metrics: Option<NodeMetrics>,
 }
 
 impl EthereumOracleChannels {
@@ -434,8 +774,66 @@ impl EthereumOracleChannels {
             ethereum_receiver: EthereumReceiver::new(events_receiver),
             control_sender,
             last_processed_block_receiver,
+            progress: None,
+            acknowledged_block: None,
+            node_metrics: None,
         }
     }
+
+    /// Share the [`NodeMetrics`] the oracle records its counters in with
+    /// the shell.
+    pub fn with_node_metrics(mut self, node_metrics: NodeMetrics) -> Self {
+        self.node_metrics = Some(node_metrics);
+        self
+    }
+
+    /// Record the Ethereum blocks acknowledged by the ledger in the given
+    /// [`ProgressStore`].
+    pub fn with_progress_store(mut self, progress: ProgressStore) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Set the number of blocks an Ethereum event may stay queued up for
+    /// inclusion in vote extensions before it is reported as stalled.
+    /// Stalled events are not reported if set to zero.
+    pub fn with_stalled_event_blocks(mut self, blocks: u64) -> Self {
+        self.ethereum_receiver.stalled_after_blocks = blocks;
+        self
+    }
+
+    /// Pull the Ethereum events sent by the oracle into the queue of the
+    /// [`EthereumReceiver`], as per [`EthereumReceiver::fill_queue`].
+    ///
+    /// The last block processed by the oracle is acknowledged once all the
+    /// events sent until then were pulled, and only then recorded in the
+    /// [`ProgressStore`], such that the oracle never resumes past a block
+    /// whose events the ledger didn't receive.
+    fn pull_events<F>(&mut self, height: BlockHeight, keep_event: F)
+    where
+        F: FnMut(&EthereumEvent) -> bool,
+    {
+        // the oracle only publishes the last block it processed after
+        // sending all the events it found in it
+        let last_processed_block =
+            self.last_processed_block_receiver.borrow().clone();
+        let pulled_all = self.ethereum_receiver.fill_queue(height, keep_event);
+        let Some(block) = last_processed_block.filter(|_| pulled_all) else {
+            return;
+        };
+        if self.acknowledged_block.as_ref() == Some(&block) {
+            return;
+        }
+        if let Some(progress) = &self.progress {
+            if let Err(err) = progress.save(&block) {
+                tracing::warn!(
+                    %err,
+                    "Failed to record the progress of the Ethereum oracle"
+                );
+            }
+        }
+        self.acknowledged_block = Some(block);
+    }
 }
 
 impl<D, H> Shell<D, H>
@@ -454,6 +852,31 @@ where
         db_cache: Option<&D::Cache>,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
+    ) -> Self {
+        Self::new_with_startup_progress(
+            config,
+            wasm_dir,
+            broadcast_sender,
+            eth_oracle,
+            db_cache,
+            vp_wasm_compilation_cache,
+            tx_wasm_compilation_cache,
+            &StartupProgress::default(),
+        )
+    }
+
+    /// Create a new shell, as in [`Shell::new`], reporting the progress
+    /// made while loading the last state to `startup_progress`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_startup_progress(
+        config: config::Ledger,
+        wasm_dir: PathBuf,
+        broadcast_sender: UnboundedSender<Vec<u8>>,
+        eth_oracle: Option<EthereumOracleChannels>,
+        db_cache: Option<&D::Cache>,
+        vp_wasm_compilation_cache: u64,
+        tx_wasm_compilation_cache: u64,
+        startup_progress: &StartupProgress,
     ) -> Self {
         let chain_id = config.chain_id;
         let db_path = config.shell.db_dir(&chain_id);
@@ -461,6 +884,19 @@ where
         let mode = config.shell.tendermint_mode;
         let storage_read_past_height_limit =
             config.shell.storage_read_past_height_limit;
+        let archive_endpoints = config.shell.archive_endpoints.clone();
+        let accept_misbehavior_evidence =
+            config.shell.accept_misbehavior_evidence;
+        let storage_audit_log_path = config
+            .shell
+            .storage_audit_log
+            .as_ref()
+            .map(|path| base_dir.join(chain_id.as_str()).join(path));
+        let event_sink = config.shell.event_sink.clone();
+        let node_metrics = eth_oracle
+            .as_ref()
+            .and_then(|eth_oracle| eth_oracle.node_metrics.clone())
+            .unwrap_or_default();
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Namada should not fail");
@@ -478,6 +914,7 @@ where
         };
 
         // load last state from storage
+        startup_progress.enter_stage(StartupStage::OpeningDb);
         let mut storage = Storage::open(
             db_path,
             chain_id.clone(),
@@ -486,7 +923,9 @@ where
             config.shell.storage_read_past_height_limit,
         );
         storage
-            .load_last_state()
+            .load_last_state_with_progress(|stage| {
+                startup_progress.report_load_stage(stage)
+            })
             .map_err(|e| {
                 tracing::error!("Cannot load the last state from the DB {}", e);
             })
@@ -547,6 +986,8 @@ where
                             keys: ValidatorKeys {
                                 protocol_keypair,
                                 eth_bridge_keypair,
+                                next_protocol_keypair: None,
+                                next_eth_bridge_keypair: None,
                             },
                         },
                         broadcast_sender,
@@ -559,6 +1000,31 @@ where
             TendermintMode::Seed => ShellMode::Seed,
         };
 
+        startup_progress.enter_stage(StartupStage::WarmingUpCaches);
+        let epoch_cache = EpochCache::new(&storage.block.pred_epochs);
+        let storage_audit_log = storage_audit_log_path.map(|path| {
+            tracing::info!(
+                "Recording storage writes in the audit log at {}",
+                path.to_string_lossy()
+            );
+            StorageAuditLog::open(path)
+                .expect("Creating the storage audit log should not fail")
+        });
+        let event_publisher = event_sink.and_then(|sink| {
+            tracing::info!(
+                "Publishing events to {:?} at {}",
+                sink.backend,
+                sink.brokers.join(", ")
+            );
+            EventPublisher::start(&sink)
+                .map_err(|err| {
+                    tracing::error!(
+                        "Failed to start the event publisher, events will \
+                         not be published: {err}"
+                    );
+                })
+                .ok()
+        });
         let wl_storage = WlStorage {
             storage,
             write_log: WriteLog::default(),
@@ -579,11 +1045,30 @@ where
                 tx_wasm_compilation_cache as usize,
             ),
             storage_read_past_height_limit,
-            proposal_data: HashSet::new(),
+            archive_endpoints,
+            accept_misbehavior_evidence,
             // TODO: config event log params
             event_log: EventLog::default(),
+            epoch_cache,
+            crash_reports: CrashReports::default(),
+            write_hotspots: WriteHotspots::default(),
+            valset_proof_cache: ValsetProofCache::default(),
+            eth_oracle_paused: false,
+            eth_oracle_paused_by_operator: false,
+            eth_oracle_config: None,
+            storage_audit_log,
+            event_publisher,
+            attestation_lanes: AttestationLanes::default(),
+            last_heartbeat_epoch: None,
+            node_metrics,
         };
+        // Switch to the rotated keys that became active while the node was
+        // down, before signing anything with them
+        shell.activate_rotated_validator_keys();
+        shell.eth_oracle_paused_by_operator =
+            shell.eth_oracle_pause_file().exists();
         shell.update_eth_oracle(&Default::default());
+        startup_progress.enter_stage(StartupStage::Ready);
         shell
     }
 
@@ -599,6 +1084,55 @@ where
         &mut self.event_log
     }
 
+    /// Share the given [`CrashReports`] log with this [`Shell`], such that
+    /// crashes of its non-consensus subsystems are recorded in it.
+    pub fn with_crash_reports(mut self, crash_reports: CrashReports) -> Self {
+        self.crash_reports = crash_reports;
+        self
+    }
+
+    /// Return a reference to the [`CrashReports`] log.
+    #[inline]
+    pub fn crash_reports(&self) -> &CrashReports {
+        &self.crash_reports
+    }
+
+    /// Return a reference to the [`WriteHotspots`] record.
+    #[inline]
+    pub fn write_hotspots(&self) -> &WriteHotspots {
+        &self.write_hotspots
+    }
+
+    /// Return a reference to the [`NodeMetrics`] record.
+    #[inline]
+    pub fn node_metrics(&self) -> &NodeMetrics {
+        &self.node_metrics
+    }
+
+    /// Return a reference to the [`ValsetProofCache`].
+    #[inline]
+    pub fn valset_proof_cache(&self) -> &ValsetProofCache {
+        &self.valset_proof_cache
+    }
+
+    /// Look up the epoch of a given block height, using the cached epoch
+    /// index if it is up to date with storage.
+    pub fn get_epoch(&self, height: BlockHeight) -> Option<Epoch> {
+        let pred_epochs = &self.wl_storage.storage.block.pred_epochs;
+        if self.epoch_cache.is_synced_with(pred_epochs) {
+            self.epoch_cache.get_epoch(height)
+        } else {
+            pred_epochs.get_epoch(height)
+        }
+    }
+
+    /// Refresh the cached epoch index from storage.
+    #[inline]
+    fn refresh_epoch_cache(&mut self) {
+        self.epoch_cache
+            .refresh(&self.wl_storage.storage.block.pred_epochs);
+    }
+
     /// Iterate over the wrapper txs in order
     #[allow(dead_code)]
     fn iter_tx_queue(&mut self) -> impl Iterator<Item = &TxInQueue> {
@@ -693,44 +1227,68 @@ where
             // TODO: resolve this unwrap() better
             let pos_params = read_pos_params(&self.wl_storage).unwrap();
             let current_epoch = self.wl_storage.storage.block.epoch;
+            let current_height = self.wl_storage.storage.block.height;
+            let mut discarded = self.node_metrics.report().discarded_evidence;
             for evidence in byzantine_validators {
                 // dbg!(&evidence);
                 tracing::info!("Processing evidence {evidence:?}.");
                 let evidence_height = match u64::try_from(evidence.height) {
-                    Ok(height) => height,
+                    Ok(height) => BlockHeight(height),
                     Err(err) => {
                         tracing::error!(
                             "Unexpected evidence block height {}",
                             err
                         );
+                        discarded.invalid += 1;
                         continue;
                     }
                 };
-                let evidence_epoch = match self
-                    .wl_storage
-                    .storage
-                    .block
-                    .pred_epochs
-                    .get_epoch(BlockHeight(evidence_height))
-                {
+                let evidence_epoch = match self.get_epoch(evidence_height) {
                     Some(epoch) => epoch,
                     None => {
                         tracing::error!(
                             "Couldn't find epoch for evidence block height {}",
                             evidence_height
                         );
+                        discarded.invalid += 1;
                         continue;
                     }
                 };
+                // Disregard evidences older than the maximum evidence age
+                if proof_of_stake::is_evidence_expired(
+                    &pos_params,
+                    evidence_epoch,
+                    evidence_height,
+                    current_epoch,
+                    current_height,
+                ) {
+                    discarded.expired += 1;
+                    tracing::info!(
+                        %evidence_epoch,
+                        %evidence_height,
+                        total_expired_evidence = discarded.expired,
+                        "Skipping evidence older than the maximum evidence \
+                         age"
+                    );
+                    continue;
+                }
                 // Disregard evidences that should have already been processed
                 // at this time
-                if evidence_epoch + pos_params.slash_processing_epoch_offset()
-                    - pos_params.cubic_slashing_window_length
-                    <= current_epoch
+                if proof_of_stake::is_evidence_outdated(
+                    &self.wl_storage,
+                    &pos_params,
+                    evidence_epoch,
+                    evidence_height,
+                    current_epoch,
+                    current_height,
+                )
+                .expect("Must be able to read storage")
                 {
+                    discarded.outdated += 1;
                     tracing::info!(
-                        "Skipping outdated evidence from epoch \
-                         {evidence_epoch}"
+                        %evidence_epoch,
+                        total_outdated_evidence = discarded.outdated,
+                        "Skipping outdated evidence"
                     );
                     continue;
                 }
@@ -743,6 +1301,7 @@ where
                     }
                     MisbehaviorKind::Unknown => {
                         tracing::error!("Unknown evidence: {:#?}", evidence);
+                        discarded.invalid += 1;
                         continue;
                     }
                 };
@@ -762,6 +1321,7 @@ where
                                  hash {}",
                                 validator_raw_hash
                             );
+                            discarded.invalid += 1;
                             continue;
                         }
                     };
@@ -791,6 +1351,9 @@ where
                     tracing::error!("Error in slashing: {}", err);
                 }
             }
+            self.node_metrics.update(|metrics| {
+                metrics.discarded_evidence = discarded;
+            });
         }
     }
 
@@ -837,6 +1400,12 @@ where
             retain_height: tendermint::block::Height::from(0_u32),
             ..Default::default()
         };
+        if let Some(audit_log) = self.storage_audit_log.as_mut() {
+            audit_log.record_block_writes(
+                self.wl_storage.storage.block.height,
+                &self.wl_storage,
+            );
+        }
         // commit block's data from write log and store the in DB
         self.wl_storage.commit_block().unwrap_or_else(|e| {
             tracing::error!(
@@ -854,6 +1423,9 @@ where
         response.data = root.0.to_vec().into();
 
         self.bump_last_processed_eth_block();
+        // Invariant: Switch to the rotated keys of this validator before
+        // signing any protocol txs with them
+        self.activate_rotated_validator_keys();
         self.broadcast_queued_txs();
 
         response
@@ -897,6 +1469,7 @@ where
         if let ShellMode::Validator { .. } = &self.mode {
             self.broadcast_protocol_txs();
             self.broadcast_expired_txs();
+            self.broadcast_validator_heartbeat();
         }
     }
 
@@ -1007,6 +1580,77 @@ where
     /// if relevant storage changes have occurred. This includes deactivating
     /// and reactivating the bridge.
     fn update_eth_oracle(&mut self, changed_keys: &BTreeSet<Key>) {
+        let crash_reports = self.crash_reports.clone();
+        supervisor::isolate("Ethereum oracle control", &crash_reports, || {
+            self.update_eth_oracle_inner(changed_keys)
+        });
+    }
+
+    /// Send the latest Ethereum bridge configs to the oracle, if they have
+    /// changed in storage since they were last sent, e.g. due to
+    /// governance.
+    fn refresh_eth_oracle(&mut self, changed_keys: &BTreeSet<Key>) {
+        let has_oracle = matches!(
+            &self.mode,
+            ShellMode::Validator {
+                eth_oracle: Some(_),
+                ..
+            }
+        );
+        if !has_oracle {
+            return;
+        }
+        let config = EthereumOracleConfig::read(&self.wl_storage);
+        if config.is_some() && config != self.eth_oracle_config {
+            tracing::info!(
+                "The Ethereum bridge config has changed in storage, updating \
+                 the Ethereum oracle"
+            );
+            self.update_eth_oracle(changed_keys);
+        }
+    }
+
+    /// Pause the Ethereum oracle, e.g. after an exploit of the bridge
+    /// contracts. The oracle remains paused until it is resumed by the
+    /// node operator, regardless of the paused status set by governance.
+    pub fn pause_eth_oracle(&mut self) {
+        self.eth_oracle_paused_by_operator = true;
+        self.update_eth_oracle(&Default::default());
+    }
+
+    /// Resume the Ethereum oracle, after it has been paused by the node
+    /// operator. The oracle remains paused if governance has paused it.
+    pub fn resume_eth_oracle(&mut self) {
+        self.eth_oracle_paused_by_operator = false;
+        self.update_eth_oracle(&Default::default());
+    }
+
+    /// Get the path to the marker file created by the `ledger
+    /// pause-eth-oracle` command of the node operator.
+    fn eth_oracle_pause_file(&self) -> PathBuf {
+        self.base_dir
+            .join(self.chain_id.as_str())
+            .join(config::ETH_ORACLE_PAUSE_FILE)
+    }
+
+    /// Pause or resume the Ethereum oracle if the node operator has created
+    /// or removed the pause marker file since the last block.
+    fn check_eth_oracle_pause_file(&mut self) {
+        let paused = self.eth_oracle_pause_file().exists();
+        if paused == self.eth_oracle_paused_by_operator {
+            return;
+        }
+        if paused {
+            tracing::warn!("The node operator has paused the Ethereum oracle");
+            self.pause_eth_oracle();
+        } else {
+            tracing::info!("The node operator has resumed the Ethereum oracle");
+            self.resume_eth_oracle();
+        }
+    }
+
+    /// Send the latest Ethereum bridge configs to the oracle.
+    fn update_eth_oracle_inner(&mut self, changed_keys: &BTreeSet<Key>) {
         if let ShellMode::Validator {
             eth_oracle: Some(EthereumOracleChannels { control_sender, .. }),
             ..
@@ -1036,6 +1680,7 @@ where
                 tracing::info!("Not starting oracle as the Ethereum bridge config couldn't be found in storage");
                 return;
             };
+            self.eth_oracle_config = Some(config.clone());
             let active =
                 if !self.wl_storage.ethbridge_queries().is_bridge_active() {
                     if !changed_keys
@@ -1067,34 +1712,73 @@ where
                 "Found Ethereum height from which the Ethereum oracle should \
                  start"
             );
+            let paused =
+                config.oracle_paused || self.eth_oracle_paused_by_operator;
+            use namada::eth_bridge::oracle::config::ContractHandover;
             let config = namada::eth_bridge::oracle::config::Config {
                 min_confirmations: config.min_confirmations.into(),
                 bridge_contract: config.contracts.bridge.address,
+                nft_bridge_contract: config.nft_bridge_contract,
+                bridge_contract_handover: config.bridge_contract_rotation.map(
+                    |rotation| ContractHandover {
+                        new_contract: rotation.contract.address,
+                        handover_block: rotation.handover_height,
+                        validator_set_hashes: config
+                            .bridge_contract_rotation_valset
+                            .as_ref()
+                            .map(|valset| {
+                                (
+                                    valset.bridge_hash.clone(),
+                                    valset.governance_hash.clone(),
+                                )
+                            }),
+                    },
+                ),
                 start_block,
                 active,
+                reorg_safety_depth: config.reorg_safety_depth,
             };
             tracing::info!(
                 ?config,
+                paused,
                 "Starting the Ethereum oracle using values from block storage"
             );
-            if let Err(error) = control_sender
-                .try_send(oracle::control::Command::UpdateConfig(config))
-            {
-                match error {
-                    tokio::sync::mpsc::error::TrySendError::Full(_) => {
-                        panic!(
-                            "The Ethereum oracle communication channel is \
-                             full!"
-                        )
-                    }
-                    tokio::sync::mpsc::error::TrySendError::Closed(_) => {
-                        panic!(
-                            "The Ethereum oracle can no longer be \
-                             communicated with"
-                        )
+            // the config must be sent first, as the oracle will not do
+            // anything until it has been configured. The paused status is
+            // only sent when it changes, as the oracle starts out resumed.
+            let mut commands =
+                vec![oracle::control::Command::UpdateConfig(config)];
+            if paused != self.eth_oracle_paused {
+                commands.push(if paused {
+                    oracle::control::Command::Pause
+                } else {
+                    oracle::control::Command::Resume
+                });
+            }
+            for command in commands {
+                if let Err(error) = control_sender.try_send(command) {
+                    match error {
+                        tokio::sync::mpsc::error::TrySendError::Full(_) => {
+                            tracing::warn!(
+                                "The Ethereum oracle communication channel is \
+                                 full, the Ethereum bridge configs will be \
+                                 sent again in the next block"
+                            );
+                            // forget the configs, such that they are
+                            // considered changed in the next block
+                            self.eth_oracle_config = None;
+                            return;
+                        }
+                        tokio::sync::mpsc::error::TrySendError::Closed(_) => {
+                            panic!(
+                                "The Ethereum oracle can no longer be \
+                                 communicated with"
+                            )
+                        }
                     }
                 }
             }
+            self.eth_oracle_paused = paused;
         }
     }
 
@@ -1270,6 +1954,68 @@ where
                         response.priority = i64::MAX;
                     }
                 }
+                ProtocolTxType::AttestationVext => {
+                    let ext = try_vote_extension!(
+                        "attestation",
+                        response,
+                        ethereum_tx_data_variants::AttestationVext::try_from(
+                            &tx
+                        ),
+                    );
+                    if let Err(err) = self
+                        .validate_attestation_vext_and_get_it_back(
+                            ext,
+                            self.wl_storage.storage.get_last_block_height(),
+                        )
+                    {
+                        response.code = ErrorCodes::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid attestation vote \
+                             extension: {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
+                ProtocolTxType::MisbehaviorEvidence => {
+                    let evidence = try_vote_extension!(
+                        "misbehavior evidence",
+                        response,
+                        ethereum_tx_data_variants::MisbehaviorEvidence::try_from(
+                            &tx
+                        ),
+                    );
+                    if let Err(err) =
+                        self.validate_misbehavior_evidence(&evidence)
+                    {
+                        response.code = ErrorCodes::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid misbehavior evidence: \
+                             {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
+                ProtocolTxType::ValidatorHeartbeat => {
+                    let heartbeat = try_vote_extension!(
+                        "validator heartbeat",
+                        response,
+                        ethereum_tx_data_variants::ValidatorHeartbeat::try_from(
+                            &tx
+                        ),
+                    );
+                    if let Err(err) =
+                        self.validate_validator_heartbeat(&heartbeat)
+                    {
+                        response.code = ErrorCodes::InvalidVoteExtension.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Invalid validator heartbeat: {err}",
+                        );
+                    } else {
+                        response.log = String::from(VALID_MSG);
+                    }
+                }
                 _ => {
                     response.code = ErrorCodes::InvalidTx.into();
                     response.log = format!(
@@ -1304,6 +2050,56 @@ where
                     return response;
                 }
 
+                // Tx whitelist check, if the code of the inner tx is
+                // available in the clear
+                let code_hash = invariants::inner_tx_code_hash(&tx);
+                if let Some(code_hash) = code_hash {
+                    let whitelisted = parameters::is_tx_whitelisted(
+                        &self.wl_storage,
+                        &code_hash,
+                    )
+                    .expect("Error while reading the tx whitelist");
+                    if !whitelisted {
+                        response.code = ErrorCodes::TxNotWhitelisted.into();
+                        response.log = format!(
+                            "{INVALID_MSG}: Tx code with hash {code_hash} is \
+                             not whitelisted"
+                        );
+                        return response;
+                    }
+                }
+
+                // Halted chain check
+                if let Err(msg) = invariants::check_tx_allowed(
+                    &self.wl_storage,
+                    code_hash.as_ref(),
+                )
+                .expect("Error while reading the halted status of the chain")
+                {
+                    response.code = ErrorCodes::ChainHalted.into();
+                    response.log = format!("{INVALID_MSG}: {msg}");
+                    return response;
+                }
+
+                // Unjail tx check
+                if let Err(msg) = unjail::check_unjail_tx(&self.wl_storage, &tx)
+                    .expect("Error while reading the state of a validator")
+                {
+                    response.code = ErrorCodes::InvalidTx.into();
+                    response.log = format!("{INVALID_MSG}: {msg}");
+                    return response;
+                }
+
+                // Proposal tx check
+                if let Err(msg) =
+                    governance::check_init_proposal_tx(&self.wl_storage, &tx)
+                        .expect("Error while reading the governance parameters")
+                {
+                    response.code = ErrorCodes::InvalidTx.into();
+                    response.log = format!("{INVALID_MSG}: {msg}");
+                    return response;
+                }
+
                 // Replay protection check
                 let inner_tx_hash = tx.raw_header_hash();
                 if self
@@ -1363,6 +2159,13 @@ where
                      the mempool"
                 );
             }
+            TxType::View => {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = format!(
+                    "{INVALID_MSG}: View transactions can only be executed \
+                     via the view_tx query"
+                );
+            }
             TxType::Decrypted(_) => {
                 response.code = ErrorCodes::InvalidTx.into();
                 response.log = format!(
@@ -2267,7 +3070,7 @@ mod test_utils {
         proposer_address: Vec<u8>,
         votes: Vec<VoteInfo>,
         byzantine_validators: Option<Vec<Misbehavior>>,
-    ) {
+    ) -> abcipp_shim_types::shim::response::FinalizeBlock {
         // Let the header time be always ahead of the next epoch min start time
         let header = Header {
             time: shell
@@ -2286,18 +3089,24 @@ mod test_utils {
         if let Some(byz_vals) = byzantine_validators {
             req.byzantine_validators = byz_vals;
         }
-        shell.finalize_block(req).unwrap();
+        let response = shell.finalize_block(req).unwrap();
         shell.commit();
+        response
     }
 }
 
 #[cfg(test)]
 mod shell_tests {
     use namada::core::ledger::replay_protection;
+    use namada::ledger::storage_api::StorageWrite;
     use namada::proto::{
         Code, Data, Section, SignableEthMessage, Signature, Signed, Tx,
     };
-    use namada::types::ethereum_events::EthereumEvent;
+    use namada::types::ethereum_events::{
+        EthAddress, EthereumEvent, TransferToNamada,
+    };
+    use namada::types::hash::Hash;
+    use namada::types::keccak::KeccakHash;
     use namada::types::key::RefTo;
     use namada::types::storage::{BlockHeight, Epoch};
     use namada::types::transaction::protocol::{
@@ -2394,6 +3203,195 @@ mod shell_tests {
         );
     }
 
+    /// Test that the queue of Ethereum events is capped, leaving new
+    /// events with the oracle while it is full, and that outdated events
+    /// are evicted from it.
+    #[test]
+    fn test_ethereum_receiver_queue_limits() {
+        let event = |nonce: u64| EthereumEvent::TransfersToNamada {
+            nonce: nonce.into(),
+            transfers: vec![],
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let mut ethereum_receiver = EthereumReceiver::new(receiver);
+        ethereum_receiver.max_queued_events = 2;
+
+        for nonce in 0..3 {
+            sender.blocking_send(event(nonce)).unwrap();
+        }
+        ethereum_receiver.fill_queue(0.into(), |_| true);
+        assert_eq!(
+            ethereum_receiver.get_events(None, &Default::default()),
+            vec![event(0), event(1)]
+        );
+        assert_eq!(ethereum_receiver.metrics().full, 1);
+
+        // evict outdated events, making room for the event which was left
+        // with the oracle
+        ethereum_receiver.fill_queue(0.into(), |queued| queued != &event(0));
+        assert_eq!(
+            ethereum_receiver.get_events(None, &Default::default()),
+            vec![event(1), event(2)]
+        );
+        assert_eq!(ethereum_receiver.metrics().evicted, 1);
+        assert_eq!(ethereum_receiver.metrics().full, 2);
+
+        // no event is lost
+        ethereum_receiver.fill_queue(0.into(), |queued| queued == &event(2));
+        assert_eq!(
+            ethereum_receiver.get_events(None, &Default::default()),
+            vec![event(2)]
+        );
+        assert_eq!(ethereum_receiver.metrics().evicted, 2);
+    }
+
+    /// Test that the last Ethereum block processed by the oracle is only
+    /// recorded as its progress once all the events sent until then were
+    /// pulled into the queue.
+    #[test]
+    fn test_eth_oracle_progress_is_acknowledged() {
+        let event = |nonce: u64| EthereumEvent::TransfersToNamada {
+            nonce: nonce.into(),
+            transfers: vec![],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let progress =
+            ProgressStore::new(dir.path(), ChainId("chain".to_owned()));
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let (last_processed_block_sender, last_processed_block_receiver) =
+            last_processed_block::channel();
+        let (control_sender, _control_receiver) = oracle::control::channel();
+        let mut eth_oracle = EthereumOracleChannels::new(
+            receiver,
+            control_sender,
+            last_processed_block_receiver,
+        )
+        .with_progress_store(progress.clone());
+        eth_oracle.ethereum_receiver.max_queued_events = 1;
+
+        sender.blocking_send(event(0)).unwrap();
+        sender.blocking_send(event(1)).unwrap();
+        last_processed_block_sender.send_replace(Some(5u64.into()));
+
+        // an event of the processed blocks is still with the oracle
+        eth_oracle.pull_events(0.into(), |_| true);
+        assert_eq!(eth_oracle.acknowledged_block, None);
+        assert_eq!(progress.load(), None);
+
+        // all the events were pulled
+        eth_oracle.pull_events(0.into(), |queued| queued != &event(0));
+        assert_eq!(eth_oracle.acknowledged_block, Some(5u64.into()));
+        assert_eq!(progress.load(), Some(5u64.into()));
+    }
+
+    /// Test that Ethereum events which stay queued for too many blocks
+    /// are reported as stalled exactly once.
+    #[test]
+    fn test_ethereum_receiver_stalled_events() {
+        let event = |nonce: u64| EthereumEvent::TransfersToNamada {
+            nonce: nonce.into(),
+            transfers: vec![],
+        };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let mut ethereum_receiver = EthereumReceiver::new(receiver);
+        ethereum_receiver.stalled_after_blocks = 2;
+
+        sender.blocking_send(event(0)).unwrap();
+        ethereum_receiver.fill_queue(1.into(), |_| true);
+        sender.blocking_send(event(1)).unwrap();
+        ethereum_receiver.fill_queue(2.into(), |_| true);
+
+        assert!(ethereum_receiver.take_stalled_events(3.into()).is_empty());
+        assert_eq!(
+            ethereum_receiver.take_stalled_events(4.into()),
+            vec![(event(0), 1.into())]
+        );
+        assert_eq!(
+            ethereum_receiver.take_stalled_events(5.into()),
+            vec![(event(1), 2.into())]
+        );
+        assert!(ethereum_receiver.take_stalled_events(6.into()).is_empty());
+        assert_eq!(ethereum_receiver.metrics.stalled, 2);
+
+        // events are not reported when disabled
+        ethereum_receiver.stalled_after_blocks = 0;
+        sender.blocking_send(event(2)).unwrap();
+        ethereum_receiver.fill_queue(6.into(), |_| true);
+        assert!(ethereum_receiver.take_stalled_events(100.into()).is_empty());
+    }
+
+    /// Test that when more Ethereum events are queued than fit in a vote
+    /// extension, those with the nonces closest to the next ones of their
+    /// kind and the highest value are picked first, along with the oldest
+    /// validator set update.
+    #[test]
+    fn test_ethereum_receiver_event_priority() {
+        let event =
+            |nonce: u64, amount: u64| EthereumEvent::TransfersToNamada {
+                nonce: nonce.into(),
+                transfers: vec![TransferToNamada {
+                    amount: token::Amount::from(amount),
+                    asset: EthAddress([0; 20]),
+                    receiver: address::testing::established_address_1(),
+                }],
+            };
+        let (sender, receiver) = tokio::sync::mpsc::channel(10);
+        let mut ethereum_receiver = EthereumReceiver::new(receiver);
+        for (nonce, amount) in [(2, 100), (1, 1), (1, 10), (0, 1)] {
+            sender.blocking_send(event(nonce, amount)).unwrap();
+        }
+        ethereum_receiver.fill_queue(0.into(), |_| true);
+
+        let next_nonces = NextEthEventNonces::default();
+        assert_eq!(ethereum_receiver.get_events(None, &next_nonces).len(), 4);
+        assert_eq!(
+            ethereum_receiver.get_events(Some(5), &next_nonces).len(),
+            4
+        );
+        assert_eq!(
+            ethereum_receiver.get_events(Some(2), &next_nonces),
+            vec![event(0, 1), event(1, 10)]
+        );
+
+        // events of different kinds are compared by the distance of their
+        // nonces from the next nonce expected for their kind
+        let transfers_to_ethereum =
+            |nonce: u64| EthereumEvent::TransfersToEthereum {
+                nonce: nonce.into(),
+                transfers: vec![],
+                relayer: address::testing::established_address_1(),
+            };
+        sender.blocking_send(transfers_to_ethereum(7)).unwrap();
+        ethereum_receiver.fill_queue(0.into(), |_| true);
+        let next_nonces = NextEthEventNonces {
+            transfers_to_ethereum: 7.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            ethereum_receiver.get_events(Some(2), &next_nonces),
+            vec![event(0, 1), transfers_to_ethereum(7)]
+        );
+
+        // a slot is always reserved for the oldest validator set update
+        let valset_update = |nonce: u64| EthereumEvent::ValidatorSetUpdate {
+            nonce: nonce.into(),
+            bridge_validator_hash: KeccakHash([0; 32]),
+            governance_validator_hash: KeccakHash([0; 32]),
+        };
+        for nonce in [50, 51] {
+            sender.blocking_send(valset_update(nonce)).unwrap();
+        }
+        ethereum_receiver.fill_queue(0.into(), |_| true);
+        assert_eq!(
+            ethereum_receiver.get_events(Some(2), &next_nonces),
+            vec![event(0, 1), valset_update(50)]
+        );
+        assert_eq!(
+            ethereum_receiver.get_events(Some(1), &next_nonces),
+            vec![valset_update(50)]
+        );
+    }
+
     /// Test that Ethereum events with outdated nonces are
     /// not validated by `CheckTx`.
     #[test]
@@ -2904,6 +3902,50 @@ mod shell_tests {
         assert_eq!(result.code, ErrorCodes::AllocationError.into());
     }
 
+    /// Check that a wrapper whose inner tx code is not whitelisted gets
+    /// rejected
+    #[test]
+    fn test_tx_not_whitelisted() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let keypair = super::test_utils::gen_keypair();
+
+        let code = "wasm_code".as_bytes().to_owned();
+        let other_code_hash = Hash::sha256("other_wasm_code".as_bytes());
+        shell
+            .wl_storage
+            .write(
+                &parameters::storage::get_tx_whitelist_storage_key(),
+                vec![other_code_hash.to_string().to_lowercase()],
+            )
+            .expect("Test failed");
+
+        let mut wrapper =
+            Tx::from_type(TxType::Wrapper(Box::new(WrapperTx::new(
+                Fee {
+                    amount_per_gas_unit: 100.into(),
+                    token: shell.wl_storage.storage.native_token.clone(),
+                },
+                keypair.ref_to(),
+                Epoch(0),
+                GAS_LIMIT_MULTIPLIER.into(),
+                None,
+            ))));
+        wrapper.header.chain_id = shell.chain_id.clone();
+        wrapper.set_code(Code::new(code, None));
+        wrapper.set_data(Data::new("transaction data".as_bytes().to_owned()));
+        wrapper.add_section(Section::Signature(Signature::new(
+            wrapper.sechashes(),
+            [(0, keypair)].into_iter().collect(),
+            None,
+        )));
+
+        let result = shell.mempool_validate(
+            wrapper.to_bytes().as_ref(),
+            MempoolTxType::NewTransaction,
+        );
+        assert_eq!(result.code, ErrorCodes::TxNotWhitelisted.into());
+    }
+
     // Check that a tx requiring more gas than its limit gets rejected
     #[test]
     fn test_exceeding_gas_limit_tx() {
@@ -1,6 +1,5 @@
 //! Implementation of the [`RequestPrepareProposal`] ABCI++ method for the Shell
 
-use namada::core::hints;
 use namada::core::ledger::gas::TxGasMeter;
 use namada::ledger::pos::PosQueries;
 use namada::ledger::protocol::get_fee_unshielding_transaction;
@@ -16,11 +15,9 @@ use namada::vm::wasm::{TxCache, VpCache};
 use namada::vm::WasmCacheAccess;
 
 use super::super::*;
-use super::block_alloc::states::{
-    BuildingDecryptedTxBatch, BuildingProtocolTxBatch,
-    EncryptedTxBatchAllocator, NextState, TryAlloc,
-};
-use super::block_alloc::{AllocFailure, BlockAllocator, BlockResources};
+use super::block_alloc::states::EncryptedTxBatchAllocator;
+use super::block_alloc::{AllocFailure, ProposalAllocator, TxKind};
+use super::{governance, invariants, unjail};
 use crate::facade::tendermint_proto::google::protobuf::Timestamp;
 use crate::facade::tendermint_proto::v0_37::abci::RequestPrepareProposal;
 use crate::node::ledger::shell::ShellMode;
@@ -45,7 +42,7 @@ where
     ) -> response::PrepareProposal {
         let txs = if let ShellMode::Validator { .. } = self.mode {
             // start counting allotted space for txs
-            let alloc = self.get_encrypted_txs_allocator();
+            let mut alloc = self.get_encrypted_txs_allocator().into();
 
             // add encrypted txs
             let tm_raw_hash_string =
@@ -59,19 +56,19 @@ where
                 "Unable to find native validator address of block proposer \
                  from tendermint raw hash",
             );
-            let (encrypted_txs, alloc) = self.build_encrypted_txs(
-                alloc,
+            let mut txs = self.build_encrypted_txs(
+                &mut alloc,
                 &req.txs,
                 req.time,
                 &block_proposer,
             );
-            let mut txs = encrypted_txs;
             // decrypt the wrapper txs included in the previous block
-            let (mut decrypted_txs, alloc) = self.build_decrypted_txs(alloc);
+            let mut decrypted_txs = self.build_decrypted_txs(&mut alloc);
             txs.append(&mut decrypted_txs);
 
             // add vote extension protocol txs
-            let mut protocol_txs = self.build_protocol_txs(alloc, &req.txs);
+            let mut protocol_txs =
+                self.build_protocol_txs(&mut alloc, &req.txs);
             txs.append(&mut protocol_txs);
 
             txs
@@ -102,36 +99,26 @@ where
     /// [`EncryptedTxBatchAllocator::WithEncryptedTxs`] value.
     #[inline]
     fn get_encrypted_txs_allocator(&self) -> EncryptedTxBatchAllocator {
-        let pos_queries = self.wl_storage.pos_queries();
-
-        let is_2nd_height_off = pos_queries.is_deciding_offset_within_epoch(1);
-        let is_3rd_height_off = pos_queries.is_deciding_offset_within_epoch(2);
-
-        if hints::unlikely(is_2nd_height_off || is_3rd_height_off) {
+        let alloc = EncryptedTxBatchAllocator::from(&self.wl_storage);
+        if matches!(alloc, EncryptedTxBatchAllocator::WithoutEncryptedTxs(_)) {
+            let pos_queries = self.wl_storage.pos_queries();
             tracing::warn!(
-                proposal_height =
-                    ?pos_queries.get_current_decision_height(),
+                proposal_height = ?pos_queries.get_current_decision_height(),
                 "No mempool txs are being included in the current proposal"
             );
-            EncryptedTxBatchAllocator::WithoutEncryptedTxs(
-                (&self.wl_storage).into(),
-            )
-        } else {
-            EncryptedTxBatchAllocator::WithEncryptedTxs(
-                (&self.wl_storage).into(),
-            )
         }
+        alloc
     }
 
     /// Builds a batch of encrypted transactions, retrieved from
     /// Tendermint's mempool.
     fn build_encrypted_txs(
         &self,
-        mut alloc: EncryptedTxBatchAllocator,
+        alloc: &mut ProposalAllocator,
         txs: &[TxBytes],
         block_time: Option<Timestamp>,
         block_proposer: &Address,
-    ) -> (Vec<TxBytes>, BlockAllocator<BuildingDecryptedTxBatch>) {
+    ) -> Vec<TxBytes> {
         let pos_queries = self.wl_storage.pos_queries();
         let block_time = block_time.and_then(|block_time| {
             // If error in conversion, default to last block datetime, it's
@@ -142,8 +129,7 @@ where
         let mut vp_wasm_cache = self.vp_wasm_cache.clone();
         let mut tx_wasm_cache = self.tx_wasm_cache.clone();
 
-        let txs = txs
-            .iter()
+        txs.iter()
             .filter_map(|tx_bytes| {
                 match self.validate_wrapper_bytes(tx_bytes, block_time, &mut temp_wl_storage, &mut vp_wasm_cache, &mut tx_wasm_cache, block_proposer) {
                     Ok(gas) => {
@@ -156,41 +142,37 @@ where
                     }
                 }
             })
-            .take_while(|(tx_bytes, tx_gas)| {
-                alloc.try_alloc(BlockResources::new(&tx_bytes[..], tx_gas.to_owned()))
-                    .map_or_else(
-                        |status| match status {
-                            AllocFailure::Rejected { bin_resource_left} => {
-                                tracing::debug!(
-                                    ?tx_bytes,
-                                    bin_resource_left,
-                                    proposal_height =
-                                        ?pos_queries.get_current_decision_height(),
-                                    "Dropping encrypted tx from the current proposal",
-                                );
-                                false
-                            }
-                            AllocFailure::OverflowsBin { bin_resource} => {
-                                // TODO: handle tx whose size is greater
-                                // than bin size
-                                tracing::warn!(
-                                    ?tx_bytes,
-                                    bin_resource,
-                                    proposal_height =
-                                        ?pos_queries.get_current_decision_height(),
-                                    "Dropping large encrypted tx from the current proposal",
-                                );
-                                true
-                            }
-                        },
-                        |()| true,
-                    )
+            .map_while(|(tx_bytes, tx_gas)| {
+                let kind = TxKind::Wrapper { gas: tx_gas };
+                match alloc.try_alloc(&tx_bytes[..], kind) {
+                    Ok(()) => Some(Some(tx_bytes)),
+                    Err(AllocFailure::Rejected { bin_resource_left }) => {
+                        tracing::debug!(
+                            ?tx_bytes,
+                            bin_resource_left,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping encrypted tx from the current proposal",
+                        );
+                        None
+                    }
+                    Err(AllocFailure::OverflowsBin { bin_resource }) => {
+                        // TODO: handle tx whose size is greater
+                        // than bin size
+                        tracing::warn!(
+                            ?tx_bytes,
+                            bin_resource,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping large encrypted tx from the current \
+                             proposal",
+                        );
+                        Some(None)
+                    }
+                }
             })
-            .map(|(tx, _)| tx)
-            .collect();
-        let alloc = alloc.next_state();
-
-        (txs, alloc)
+            .flatten()
+            .collect()
     }
 
     /// Validity checks on a wrapper tx
@@ -226,6 +208,21 @@ where
             let mut tx_gas_meter = TxGasMeter::new(wrapper.gas_limit);
             tx_gas_meter.add_wrapper_gas(tx_bytes).map_err(|_| ())?;
 
+            invariants::check_tx_allowed(
+                temp_wl_storage,
+                invariants::inner_tx_code_hash(&tx).as_ref(),
+            )
+            .expect("Error while reading the halted status of the chain")
+            .map_err(|_| ())?;
+
+            unjail::check_unjail_tx(temp_wl_storage, &tx)
+                .expect("Error while reading the state of a validator")
+                .map_err(|_| ())?;
+
+            governance::check_init_proposal_tx(temp_wl_storage, &tx)
+                .expect("Error while reading the governance parameters")
+                .map_err(|_| ())?;
+
             self.replay_protection_checks(&tx, temp_wl_storage)
                 .map_err(|_| ())?;
 
@@ -257,11 +254,10 @@ where
     // - https://github.com/anoma/ferveo
     fn build_decrypted_txs(
         &self,
-        mut alloc: BlockAllocator<BuildingDecryptedTxBatch>,
-    ) -> (Vec<TxBytes>, BlockAllocator<BuildingProtocolTxBatch>) {
+        alloc: &mut ProposalAllocator,
+    ) -> Vec<TxBytes> {
         let pos_queries = self.wl_storage.pos_queries();
-        let txs = self
-            .wl_storage
+        self.wl_storage
             .storage
             .tx_queue
             .iter()
@@ -276,43 +272,44 @@ where
                 },
             )
             // TODO: make sure all decrypted txs are accepted
-            .take_while(|tx_bytes: &TxBytes| {
-                alloc.try_alloc(&tx_bytes[..]).map_or_else(
-                    |status| match status {
-                        AllocFailure::Rejected { bin_resource_left: bin_space_left } => {
-                            tracing::warn!(
-                                ?tx_bytes,
-                                bin_space_left,
-                                proposal_height =
-                                    ?pos_queries.get_current_decision_height(),
-                                "Dropping decrypted tx from the current proposal",
-                            );
-                            false
-                        }
-                        AllocFailure::OverflowsBin { bin_resource: bin_size } => {
-                            tracing::warn!(
-                                ?tx_bytes,
-                                bin_size,
-                                proposal_height =
-                                    ?pos_queries.get_current_decision_height(),
-                                "Dropping large decrypted tx from the current proposal",
-                            );
-                            true
-                        }
-                    },
-                    |()| true,
-                )
+            .map_while(|tx_bytes: TxBytes| {
+                match alloc.try_alloc(&tx_bytes[..], TxKind::Decrypted) {
+                    Ok(()) => Some(Some(tx_bytes)),
+                    Err(AllocFailure::Rejected {
+                        bin_resource_left: bin_space_left,
+                    }) => {
+                        tracing::warn!(
+                            ?tx_bytes,
+                            bin_space_left,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping decrypted tx from the current proposal",
+                        );
+                        None
+                    }
+                    Err(AllocFailure::OverflowsBin {
+                        bin_resource: bin_size,
+                    }) => {
+                        tracing::warn!(
+                            ?tx_bytes,
+                            bin_size,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping large decrypted tx from the current \
+                             proposal",
+                        );
+                        Some(None)
+                    }
+                }
             })
-            .collect();
-        let alloc = alloc.next_state();
-
-        (txs, alloc)
+            .flatten()
+            .collect()
     }
 
     /// Builds a batch of protocol transactions.
     fn build_protocol_txs(
         &self,
-        mut alloc: BlockAllocator<BuildingProtocolTxBatch>,
+        alloc: &mut ProposalAllocator,
         txs: &[TxBytes],
     ) -> Vec<TxBytes> {
         if self.wl_storage.storage.last_block.is_none() {
@@ -327,45 +324,45 @@ where
         let deserialized_iter = self.deserialize_vote_extensions(txs);
         let pos_queries = self.wl_storage.pos_queries();
 
-        deserialized_iter.take_while(|tx_bytes|
-            alloc.try_alloc(&tx_bytes[..])
-                .map_or_else(
-                    |status| match status {
-                        AllocFailure::Rejected { bin_resource_left} => {
-                            // TODO: maybe we should find a way to include
-                            // validator set updates all the time. for instance,
-                            // we could have recursive bins -> bin space within
-                            // a bin is partitioned into yet more bins. so, we
-                            // could have, say, 2/3 of the bin space available
-                            // for eth events, and 1/3 available for valset
-                            // upds. to be determined, as we implement CheckTx
-                            // changes (issue #367)
-                            tracing::debug!(
-                                ?tx_bytes,
-                                bin_resource_left,
-                                proposal_height =
-                                    ?pos_queries.get_current_decision_height(),
-                                "Dropping protocol tx from the current proposal",
-                            );
-                            false
-                        }
-                        AllocFailure::OverflowsBin { bin_resource} => {
-                            // TODO: handle tx whose size is greater
-                            // than bin size
-                            tracing::warn!(
-                                ?tx_bytes,
-                                bin_resource,
-                                proposal_height =
-                                    ?pos_queries.get_current_decision_height(),
-                                "Dropping large protocol tx from the current proposal",
-                            );
-                            true
-                        }
-                    },
-                    |()| true,
-                )
-        )
-        .collect()
+        deserialized_iter
+            .map_while(|tx_bytes| {
+                match alloc.try_alloc(&tx_bytes[..], TxKind::Protocol) {
+                    Ok(()) => Some(Some(tx_bytes)),
+                    Err(AllocFailure::Rejected { bin_resource_left }) => {
+                        // TODO: maybe we should find a way to include
+                        // validator set updates all the time. for instance,
+                        // we could have recursive bins -> bin space within
+                        // a bin is partitioned into yet more bins. so, we
+                        // could have, say, 2/3 of the bin space available
+                        // for eth events, and 1/3 available for valset
+                        // upds. to be determined, as we implement CheckTx
+                        // changes (issue #367)
+                        tracing::debug!(
+                            ?tx_bytes,
+                            bin_resource_left,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping protocol tx from the current proposal",
+                        );
+                        None
+                    }
+                    Err(AllocFailure::OverflowsBin { bin_resource }) => {
+                        // TODO: handle tx whose size is greater
+                        // than bin size
+                        tracing::warn!(
+                            ?tx_bytes,
+                            bin_resource,
+                            proposal_height =
+                                ?pos_queries.get_current_decision_height(),
+                            "Dropping large protocol tx from the current \
+                             proposal",
+                        );
+                        Some(None)
+                    }
+                }
+            })
+            .flatten()
+            .collect()
     }
 }
 
@@ -15,7 +15,8 @@ use namada::types::transaction::protocol::{
 };
 use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
-use super::block_alloc::{BlockSpace, EncryptedTxsBins};
+use super::block_alloc::states::EncryptedTxBatchAllocator;
+use super::block_alloc::{BlockSpace, ProposalAllocator, TxKind};
 use super::*;
 use crate::facade::tendermint_proto::v0_37::abci::RequestProcessProposal;
 use crate::node::ledger::shell::block_alloc::{AllocFailure, TxBin};
@@ -24,10 +25,10 @@ use crate::node::ledger::shims::abcipp_shim_types::shim::TxBytes;
 
 /// Validation metadata, to keep track of used resources or
 /// transaction numbers, in a block proposal.
-#[derive(Default)]
 pub struct ValidationMeta {
-    /// Space and gas utilized by encrypted txs.
-    pub encrypted_txs_bins: EncryptedTxsBins,
+    /// Space and gas utilized by each kind of tx, allocated the same way
+    /// as in `PrepareProposal`.
+    pub allocator: ProposalAllocator,
     /// Vote extension digest counters.
     /// Space utilized by all txs.
     pub txs_bin: TxBin<BlockSpace>,
@@ -49,15 +50,11 @@ where
     fn from(wl_storage: &WlStorage<D, H>) -> Self {
         let max_proposal_bytes =
             wl_storage.pos_queries().get_max_proposal_bytes().get();
-        let max_block_gas =
-            namada::core::ledger::gas::get_max_block_gas(wl_storage).unwrap();
-        let encrypted_txs_bin =
-            EncryptedTxsBins::new(max_proposal_bytes, max_block_gas);
         let txs_bin = TxBin::init(max_proposal_bytes);
         Self {
             decrypted_queue_has_remaining_txs: false,
             has_decrypted_txs: false,
-            encrypted_txs_bins: encrypted_txs_bin,
+            allocator: EncryptedTxBatchAllocator::from(wl_storage).into(),
             txs_bin,
         }
     }
@@ -343,7 +340,23 @@ where
                        not supported"
                     .into(),
             },
+            TxType::View => TxResult {
+                code: ErrorCodes::InvalidTx.into(),
+                info: "Transaction rejected: View transactions cannot be \
+                       included in blocks"
+                    .into(),
+            },
             TxType::Protocol(protocol_tx) => {
+                // try to allocate space for this protocol tx
+                if let Err(e) =
+                    metadata.allocator.try_alloc(tx_bytes, TxKind::Protocol)
+                {
+                    return TxResult {
+                        code: ErrorCodes::AllocationError.into(),
+                        info: describe_alloc_failure("protocol", e),
+                    };
+                }
+
                 // Tx chain id
                 if tx_chain_id != self.chain_id {
                     return TxResult {
@@ -458,6 +471,83 @@ where
                             }
                         })
                     }
+                    ProtocolTxType::AttestationVext => {
+                        ethereum_tx_data_variants::AttestationVext::try_from(
+                            &tx,
+                        )
+                        .map_err(|err| err.to_string())
+                        .and_then(|ext| {
+                            self.validate_attestation_vext_and_get_it_back(
+                                ext,
+                                self.wl_storage
+                                    .storage
+                                    .get_last_block_height(),
+                            )
+                            .map(|_| TxResult {
+                                code: ErrorCodes::Ok.into(),
+                                info: "Process Proposal accepted this \
+                                       transaction"
+                                    .into(),
+                            })
+                            .map_err(|err| err.to_string())
+                        })
+                        .unwrap_or_else(|err| TxResult {
+                            code: ErrorCodes::InvalidVoteExtension.into(),
+                            info: format!(
+                                "Process proposal rejected this proposal \
+                                 because one of the included attestation \
+                                 vote extensions was invalid: {err}"
+                            ),
+                        })
+                    }
+                    ProtocolTxType::MisbehaviorEvidence => {
+                        ethereum_tx_data_variants::MisbehaviorEvidence::try_from(
+                            &tx,
+                        )
+                        .map_err(|err| err.to_string())
+                        .and_then(|evidence| {
+                            self.validate_misbehavior_evidence(&evidence)
+                                .map(|_| TxResult {
+                                    code: ErrorCodes::Ok.into(),
+                                    info: "Process Proposal accepted this \
+                                           transaction"
+                                        .into(),
+                                })
+                                .map_err(|err| err.to_string())
+                        })
+                        .unwrap_or_else(|err| TxResult {
+                            code: ErrorCodes::InvalidVoteExtension.into(),
+                            info: format!(
+                                "Process proposal rejected this proposal \
+                                 because one of the included misbehavior \
+                                 evidence txs was invalid: {err}"
+                            ),
+                        })
+                    }
+                    ProtocolTxType::ValidatorHeartbeat => {
+                        ethereum_tx_data_variants::ValidatorHeartbeat::try_from(
+                            &tx,
+                        )
+                        .map_err(|err| err.to_string())
+                        .and_then(|heartbeat| {
+                            self.validate_validator_heartbeat(&heartbeat)
+                                .map(|_| TxResult {
+                                    code: ErrorCodes::Ok.into(),
+                                    info: "Process Proposal accepted this \
+                                           transaction"
+                                        .into(),
+                                })
+                                .map_err(|err| err.to_string())
+                        })
+                        .unwrap_or_else(|err| TxResult {
+                            code: ErrorCodes::InvalidVoteExtension.into(),
+                            info: format!(
+                                "Process proposal rejected this proposal \
+                                 because one of the included validator \
+                                 heartbeats was invalid: {err}"
+                            ),
+                        })
+                    }
                     ProtocolTxType::EthereumEvents => {
                         let digest =
                             ethereum_tx_data_variants::EthereumEvents::try_from(
@@ -523,6 +613,15 @@ where
             }
             TxType::Decrypted(tx_header) => {
                 metadata.has_decrypted_txs = true;
+                // try to allocate space for this decrypted tx
+                if let Err(e) =
+                    metadata.allocator.try_alloc(tx_bytes, TxKind::Decrypted)
+                {
+                    return TxResult {
+                        code: ErrorCodes::AllocationError.into(),
+                        info: describe_alloc_failure("decrypted", e),
+                    };
+                }
                 match tx_queue_iter.next() {
                     Some(wrapper) => {
                         if wrapper.tx.raw_header_hash() != tx.raw_header_hash()
@@ -568,12 +667,13 @@ where
                 // valid transaction and avoid wasting block
                 // resources (ABCI only)
                 let mut tx_gas_meter = TxGasMeter::new(wrapper.gas_limit);
+                let kind = TxKind::Wrapper {
+                    gas: u64::from(wrapper.gas_limit),
+                };
                 if tx_gas_meter.add_wrapper_gas(tx_bytes).is_err() {
                     // Account for the tx's resources even in case of an error.
                     // Ignore any allocation error
-                    let _ = metadata
-                        .encrypted_txs_bins
-                        .try_dump(tx_bytes, u64::from(wrapper.gas_limit));
+                    let _ = metadata.allocator.try_alloc(tx_bytes, kind);
 
                     return TxResult {
                         code: ErrorCodes::TxGasLimit.into(),
@@ -582,16 +682,6 @@ where
                     };
                 }
 
-                // try to allocate space and gas for this encrypted tx
-                if let Err(e) = metadata
-                    .encrypted_txs_bins
-                    .try_dump(tx_bytes, u64::from(wrapper.gas_limit))
-                {
-                    return TxResult {
-                        code: ErrorCodes::AllocationError.into(),
-                        info: e,
-                    };
-                }
                 // decrypted txs shouldn't show up before wrapper txs
                 if metadata.has_decrypted_txs {
                     return TxResult {
@@ -610,6 +700,14 @@ where
                     };
                 }
 
+                // try to allocate space and gas for this encrypted tx
+                if let Err(e) = metadata.allocator.try_alloc(tx_bytes, kind) {
+                    return TxResult {
+                        code: ErrorCodes::AllocationError.into(),
+                        info: describe_alloc_failure("wrapper", e),
+                    };
+                }
+
                 // ChainId check
                 if tx_chain_id != self.chain_id {
                     return TxResult {
@@ -635,6 +733,40 @@ where
                     }
                 }
 
+                // Halted chain check
+                if let Err(info) = invariants::check_tx_allowed(
+                    temp_wl_storage,
+                    invariants::inner_tx_code_hash(&tx).as_ref(),
+                )
+                .expect("Error while reading the halted status of the chain")
+                {
+                    return TxResult {
+                        code: ErrorCodes::ChainHalted.into(),
+                        info,
+                    };
+                }
+
+                // Unjail tx check
+                if let Err(info) = unjail::check_unjail_tx(temp_wl_storage, &tx)
+                    .expect("Error while reading the state of a validator")
+                {
+                    return TxResult {
+                        code: ErrorCodes::InvalidTx.into(),
+                        info,
+                    };
+                }
+
+                // Proposal tx check
+                if let Err(info) =
+                    governance::check_init_proposal_tx(temp_wl_storage, &tx)
+                        .expect("Error while reading the governance parameters")
+                {
+                    return TxResult {
+                        code: ErrorCodes::InvalidTx.into(),
+                        info,
+                    };
+                }
+
                 // Replay protection checks
                 if let Err(e) =
                     self.replay_protection_checks(&tx, temp_wl_storage)
@@ -686,6 +818,20 @@ where
     }
 }
 
+/// Describe the failure to allocate the resources of a tx of the given kind.
+fn describe_alloc_failure(tx_kind: &str, failure: AllocFailure) -> String {
+    match failure {
+        AllocFailure::Rejected { bin_resource_left } => format!(
+            "Not enough block resources left for {tx_kind} txs, only \
+             {bin_resource_left} left in their bin"
+        ),
+        AllocFailure::OverflowsBin { bin_resource } => format!(
+            "The given {tx_kind} tx requires more than the {bin_resource} \
+             resources allotted to its bin"
+        ),
+    }
+}
+
 /// We test the failure cases of [`process_proposal`]. The happy flows
 /// are covered by the e2e tests.
 #[cfg(test)]
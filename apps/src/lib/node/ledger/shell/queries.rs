@@ -1,8 +1,10 @@
 //! Shell methods for querying state
 
-use namada::ledger::dry_run_tx;
-use namada::ledger::queries::{RequestCtx, ResponseQuery};
+use namada::ledger::queries::{
+    PastHeightError, RequestCtx, ResponseQuery, PAST_HEIGHT_ERROR_CODE,
+};
 use namada::ledger::storage_api::token;
+use namada::ledger::{dry_run_tx, dry_run_tx_diff, view_tx};
 use namada::types::address::Address;
 
 use super::*;
@@ -24,11 +26,32 @@ where
             vp_wasm_cache: self.vp_wasm_cache.read_only(),
             tx_wasm_cache: self.tx_wasm_cache.read_only(),
             storage_read_past_height_limit: self.storage_read_past_height_limit,
+            crash_reports: self.crash_reports(),
+            write_hotspots: self.write_hotspots(),
+            node_metrics: self.node_metrics(),
+            valset_proof_cache: self.valset_proof_cache(),
+            archive_endpoints: &self.archive_endpoints,
+            oracle_last_processed_block: self
+                .mode
+                .get_oracle_last_processed_block(),
         };
 
         // Invoke the root RPC handler - returns borsh-encoded data on success
         let result = if query.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &query)
+        } else if query.path == "/shell/dry_run_tx_diff" {
+            dry_run_tx_diff(ctx, &query)
+        } else if query.path == "/shell/view_tx" {
+            view_tx(ctx, &query)
+        } else if query.path == "/shell/submit_evidence" {
+            self.submit_misbehavior_evidence(&query.data).map(|()| {
+                ResponseQuery {
+                    info: "The misbehavior evidence was broadcast to the \
+                           network"
+                        .into(),
+                    ..Default::default()
+                }
+            })
         } else {
             namada::ledger::queries::handle_path(ctx, &query)
         };
@@ -39,10 +62,18 @@ where
                 proof: proof.map(Into::into),
                 ..Default::default()
             },
-            Err(err) => response::Query {
-                code: 1.into(),
-                info: format!("RPC error: {}", err),
-                ..Default::default()
+            Err(err) => match err.downcast::<PastHeightError>() {
+                Ok(err) => response::Query {
+                    code: PAST_HEIGHT_ERROR_CODE.into(),
+                    info: format!("RPC error: {}", err),
+                    value: err.serialize_to_vec().into(),
+                    ..Default::default()
+                },
+                Err(err) => response::Query {
+                    code: 1.into(),
+                    info: format!("RPC error: {}", err),
+                    ..Default::default()
+                },
             },
         }
     }
@@ -68,10 +99,18 @@ where
 mod test_queries {
     use namada::core::ledger::storage::EPOCH_SWITCH_BLOCKS_DELAY;
     use namada::ledger::pos::PosQueries;
+    use namada::ledger::queries::{
+        AccountInfo, BatchQuery, BatchQueryResponse,
+    };
+    use namada::ledger::storage::LastBlock;
+    use namada::ledger::storage_api::StorageWrite;
     use namada::proof_of_stake::read_consensus_validator_set_addresses_with_stake;
     use namada::proof_of_stake::types::WeightedValidator;
     use namada::tendermint::abci::types::VoteInfo;
-    use namada::types::storage::Epoch;
+    use namada::types::account::Account;
+    use namada::types::address;
+    use namada::types::key::testing::{keypair_1, keypair_2};
+    use namada::types::storage::{Epoch, Epochs};
     use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
     use super::*;
@@ -201,4 +240,286 @@ mod test_queries {
             (2, 28, false),
         ],
     }
+
+    /// Test that queries of heights beyond the past height limit fail with
+    /// a structured error.
+    #[test]
+    fn test_read_past_height_limit() {
+        let (mut shell, _recv, _, _oracle_control_recv) =
+            test_utils::setup_at_height(10u64);
+        shell.storage_read_past_height_limit = Some(3);
+        shell.archive_endpoints = vec!["http://archive:26657".to_string()];
+        shell.wl_storage.storage.last_block = Some(LastBlock {
+            height: BlockHeight(10),
+            hash: Default::default(),
+            time: DateTimeUtc::now(),
+        });
+
+        let query = |height: u32| {
+            shell.query(request::Query {
+                data: Default::default(),
+                path: "/shell/value/test".to_string(),
+                height: height.into(),
+                prove: false,
+            })
+        };
+
+        // heights within the limit can be queried
+        let response = query(7);
+        assert!(response.code.is_ok());
+
+        let response = query(6);
+        assert_eq!(response.code, PAST_HEIGHT_ERROR_CODE.into());
+        let err = PastHeightError::try_from_slice(&response.value).unwrap();
+        assert_eq!(
+            err,
+            PastHeightError {
+                requested_height: BlockHeight(6),
+                earliest_height: BlockHeight(7),
+                archive_endpoints: vec!["http://archive:26657".to_string()],
+            }
+        );
+    }
+
+    /// Test that queries of heights whose state has been pruned from storage
+    /// fail with a structured error, even within the past height limit.
+    #[test]
+    fn test_read_pruned_height() {
+        let (mut shell, _recv, _, _oracle_control_recv) =
+            test_utils::setup_at_height(10u64);
+        shell.storage_read_past_height_limit = Some(100);
+        shell.wl_storage.storage.storage_read_past_height_limit = Some(3);
+        shell.wl_storage.storage.block.pred_epochs = Epochs {
+            first_block_heights: vec![
+                BlockHeight(1),
+                BlockHeight(5),
+                BlockHeight(9),
+            ],
+        };
+        shell.wl_storage.storage.last_block = Some(LastBlock {
+            height: BlockHeight(10),
+            hash: Default::default(),
+            time: DateTimeUtc::now(),
+        });
+
+        let query = |height: u32, prove: bool| {
+            shell.query(request::Query {
+                data: Default::default(),
+                path: "/shell/value/test".to_string(),
+                height: height.into(),
+                prove,
+            })
+        };
+
+        // the state is retained from the first height of the epoch of the
+        // oldest readable height
+        let response = query(5, false);
+        assert!(response.code.is_ok());
+
+        for prove in [false, true] {
+            let response = query(4, prove);
+            assert_eq!(response.code, PAST_HEIGHT_ERROR_CODE.into());
+            let err = PastHeightError::try_from_slice(&response.value).unwrap();
+            assert_eq!(err.requested_height, BlockHeight(4));
+            assert_eq!(err.earliest_height, BlockHeight(5));
+        }
+    }
+
+    /// Test that balances and accounts are read at the given committed
+    /// heights within the past height limit.
+    #[test]
+    fn test_balance_at_height() {
+        let (mut shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+        shell.storage_read_past_height_limit = Some(3);
+
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let owner = address::testing::established_address_1();
+        let balance_key = token::balance_key(&native_token, &owner);
+        let account = Address::from(&keypair_1().ref_to());
+        let amount = token::Amount::native_whole;
+
+        // commit different balances and accounts at several heights
+        for height in 1..=5 {
+            shell.wl_storage.storage.block.height = BlockHeight(height);
+            match height {
+                1 | 2 | 4 => shell
+                    .wl_storage
+                    .write(&balance_key, amount(height * 10))
+                    .expect("Test failed"),
+                _ => {}
+            }
+            match height {
+                2 => storage_api::account::init_account_storage(
+                    &mut shell.wl_storage,
+                    &account,
+                    &[keypair_1().ref_to()],
+                    1,
+                )
+                .expect("Test failed"),
+                4 => storage_api::account::init_account_storage(
+                    &mut shell.wl_storage,
+                    &account,
+                    &[keypair_1().ref_to(), keypair_2().ref_to()],
+                    2,
+                )
+                .expect("Test failed"),
+                _ => {}
+            }
+            shell.wl_storage.commit_block().expect("Test failed");
+        }
+
+        let query = |path: String| {
+            shell.query(request::Query {
+                data: Default::default(),
+                path,
+                height: Default::default(),
+                prove: false,
+            })
+        };
+        let balance_at = |height: u64| {
+            query(format!(
+                "/vp/token/balance/{native_token}/{owner}/{height}"
+            ))
+        };
+        let account_at = |height: u64| {
+            query(format!("/shell/account_at_height/{account}/{height}"))
+        };
+
+        // heights within the limit can be queried
+        for (height, balance) in [(2, 20), (3, 20), (4, 40), (5, 40)] {
+            let response = balance_at(height);
+            assert!(response.code.is_ok());
+            assert_eq!(
+                token::Amount::try_from_slice(&response.value).unwrap(),
+                amount(balance)
+            );
+        }
+        for (height, threshold, num_keys) in [(3, 1, 1), (4, 2, 2), (5, 2, 2)]
+        {
+            let response = account_at(height);
+            assert!(response.code.is_ok());
+            let account: Account =
+                Option::try_from_slice(&response.value).unwrap().unwrap();
+            assert_eq!(account.threshold, threshold);
+            assert_eq!(account.public_keys_map.idx_to_pk.len(), num_keys);
+        }
+
+        let response = balance_at(1);
+        assert_eq!(response.code, PAST_HEIGHT_ERROR_CODE.into());
+        let response = account_at(1);
+        assert_eq!(response.code, PAST_HEIGHT_ERROR_CODE.into());
+
+        // heights that are not committed yet can't be queried
+        for height in [0, 6] {
+            assert!(!balance_at(height).code.is_ok());
+            assert!(!account_at(height).code.is_ok());
+        }
+    }
+
+    /// Test that the queries of a batch are dispatched in order, with the
+    /// failure of a query not failing the rest of the batch.
+    #[test]
+    fn test_batch_query() {
+        let (shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+
+        let batch_query = |queries: Vec<BatchQuery>| {
+            shell.query(request::Query {
+                data: queries.serialize_to_vec().into(),
+                path: "/shell/batch".to_string(),
+                height: Default::default(),
+                prove: false,
+            })
+        };
+        let batched = |path: &str| BatchQuery {
+            path: path.to_string(),
+            data: vec![],
+            height: BlockHeight(0),
+        };
+
+        let response = batch_query(vec![
+            batched("/shell/epoch"),
+            batched("/shell/unknown"),
+            batched("/shell/native_token"),
+        ]);
+        assert!(response.code.is_ok());
+        let responses =
+            Vec::<BatchQueryResponse>::try_from_slice(&response.value).unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].code, 0);
+        assert_eq!(
+            Epoch::try_from_slice(&responses[0].data).unwrap(),
+            shell.wl_storage.storage.last_epoch
+        );
+        assert_ne!(responses[1].code, 0);
+        assert_eq!(responses[2].code, 0);
+        assert_eq!(
+            Address::try_from_slice(&responses[2].data).unwrap(),
+            shell.wl_storage.storage.native_token
+        );
+
+        // batches can't be nested, even with the optional trailing slash of
+        // a path
+        let response = batch_query(vec![batched("/shell/batch")]);
+        assert!(!response.code.is_ok());
+        let response = batch_query(vec![batched("/shell/batch/")]);
+        assert!(!response.code.is_ok());
+
+        // the queries dispatched by the shell are rejected by the router
+        let response = batch_query(vec![
+            batched("/shell/dry_run_tx"),
+            batched("/shell/view_tx/"),
+            batched("/shell/submit_evidence"),
+        ]);
+        assert!(response.code.is_ok());
+        let responses =
+            Vec::<BatchQueryResponse>::try_from_slice(&response.value).unwrap();
+        assert!(responses.iter().all(|response| response.code != 0));
+    }
+
+    /// Test that the info of an account aggregates its VP, public keys,
+    /// non-zero token balances and bonds.
+    #[test]
+    fn test_account_info() {
+        let (mut shell, _recv, _, _oracle_control_recv) = test_utils::setup();
+        let validator = shell.mode.get_validator_address().unwrap().clone();
+        let native_token = shell.wl_storage.storage.native_token.clone();
+        let btc_balance = token::Amount::native_whole(10);
+        for (token, balance) in [
+            (address::btc(), btc_balance),
+            (address::eth(), token::Amount::zero()),
+        ] {
+            shell
+                .wl_storage
+                .storage
+                .write(
+                    &token::balance_key(&token, &validator),
+                    balance.serialize_to_vec(),
+                )
+                .expect("Test failed");
+        }
+
+        let response = shell.query(request::Query {
+            data: Default::default(),
+            path: format!("/shell/account_info/{validator}"),
+            height: Default::default(),
+            prove: false,
+        });
+        assert!(response.code.is_ok());
+        let info = AccountInfo::try_from_slice(&response.value).unwrap();
+
+        assert!(info.vp_code_hash.is_some());
+        assert_eq!(
+            info.account.map(|account| account.address),
+            Some(validator.clone())
+        );
+        let native_balance =
+            token::read_balance(&shell.wl_storage, &native_token, &validator)
+                .expect("Test failed");
+        assert_eq!(info.native_balance, native_balance);
+        assert_eq!(info.balances.get(&address::btc()), Some(&btc_balance));
+        assert!(!info.balances.contains_key(&address::eth()));
+        assert!(info.balances.values().all(|balance| !balance.is_zero()));
+        assert_eq!(info.bonds.data, BTreeSet::from([validator]));
+        assert!(!info.bonds.bonds_total.is_zero());
+    }
 }
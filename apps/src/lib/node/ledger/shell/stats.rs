@@ -1,6 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Display;
 
+use namada::ledger::queries::WriteHotspots;
+use namada::types::storage::Key;
+
 #[derive(Debug, Default)]
 pub struct InternalStats {
     successful_tx: u64,
@@ -10,6 +13,7 @@ pub struct InternalStats {
     tx_cache_size: (usize, usize),
     tx_executed: HashMap<String, u64>,
     wrapper_txs: u64,
+    key_prefix_writes: HashMap<String, u64>,
 }
 
 impl InternalStats {
@@ -55,6 +59,19 @@ impl InternalStats {
     pub fn increment_wrapper_txs(&mut self) {
         self.wrapper_txs += 1;
     }
+
+    pub fn record_tx_writes(&mut self, keys: &BTreeSet<Key>) {
+        for key in keys {
+            *self
+                .key_prefix_writes
+                .entry(WriteHotspots::key_prefix(key))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn take_key_prefix_writes(&mut self) -> HashMap<String, u64> {
+        std::mem::take(&mut self.key_prefix_writes)
+    }
 }
 
 impl Display for InternalStats {
@@ -11,7 +11,6 @@ use itertools::Either;
 use lazy_static::lazy_static;
 use namada::core::types::ethereum_structs;
 use namada::eth_bridge::oracle::config::Config as OracleConfig;
-use namada::ledger::dry_run_tx;
 use namada::ledger::events::log::dumb_queries;
 use namada::ledger::queries::{
     EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
@@ -19,6 +18,7 @@ use namada::ledger::queries::{
 use namada::ledger::storage::{
     LastBlock, Sha256Hasher, EPOCH_SWITCH_BLOCKS_DELAY,
 };
+use namada::ledger::{dry_run_tx, view_tx};
 use namada::proof_of_stake::pos_queries::PosQueries;
 use namada::proof_of_stake::types::WeightedValidator;
 use namada::proof_of_stake::{
@@ -595,9 +595,17 @@ impl<'a> Client for &'a MockNode {
             vp_wasm_cache: borrowed.vp_wasm_cache.read_only(),
             tx_wasm_cache: borrowed.tx_wasm_cache.read_only(),
             storage_read_past_height_limit: None,
+            crash_reports: borrowed.crash_reports(),
+            write_hotspots: borrowed.write_hotspots(),
+            node_metrics: borrowed.node_metrics(),
+            valset_proof_cache: borrowed.valset_proof_cache(),
+            archive_endpoints: &[],
+            oracle_last_processed_block: None,
         };
         if request.path == "/shell/dry_run_tx" {
             dry_run_tx(ctx, &request)
+        } else if request.path == "/shell/view_tx" {
+            view_tx(ctx, &request)
         } else {
             rpc.handle(ctx, &request)
         }
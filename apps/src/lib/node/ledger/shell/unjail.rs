@@ -0,0 +1,123 @@
+//! Validation of unjail txs, ahead of their execution.
+//!
+//! A jailed validator may only rejoin the validator sets once it is
+//! jailed up to the pipeline epoch, and all of its enqueued slashes have
+//! been processed. Unjail txs that could not possibly succeed are rejected
+//! from the mempool and from block proposals, rather than paying for a
+//! failed execution. The validator is re-inserted into the validator sets
+//! at the pipeline epoch, hence the updated set is only communicated to
+//! Tendermint once that epoch is reached.
+
+use borsh::BorshDeserialize;
+use namada::ledger::storage_api::{self, StorageRead};
+use namada::proof_of_stake::check_can_unjail_validator;
+use namada::proto::Tx;
+use namada::types::address::Address;
+use namada::types::hash::Hash;
+use namada::types::storage::Key;
+use namada_sdk::tx::TX_UNJAIL_VALIDATOR_WASM;
+
+use super::invariants;
+
+/// Check if the inner tx of a wrapper may be included in a block, if it
+/// is an unjail tx whose code and data are available in the clear. The
+/// validator must be eligible for unjailing in the current epoch.
+pub fn check_unjail_tx<S>(
+    storage: &S,
+    tx: &Tx,
+) -> storage_api::Result<Result<(), String>>
+where
+    S: StorageRead,
+{
+    let Some(code_hash) = invariants::inner_tx_code_hash(tx) else {
+        return Ok(Ok(()));
+    };
+    let key = Key::wasm_code_name(TX_UNJAIL_VALIDATOR_WASM.to_owned());
+    if storage.read::<Hash>(&key)? != Some(code_hash) {
+        return Ok(Ok(()));
+    }
+    let Some(data) = tx.data() else {
+        return Ok(Err("Missing the data of the unjail tx".to_owned()));
+    };
+    let validator = match Address::try_from_slice(&data) {
+        Ok(validator) => validator,
+        Err(err) => {
+            return Ok(Err(format!(
+                "Failed to decode the validator of the unjail tx: {err}"
+            )));
+        }
+    };
+    let current_epoch = storage.get_block_epoch()?;
+    Ok(
+        check_can_unjail_validator(storage, &validator, current_epoch)?
+            .map_err(|err| err.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod test_unjail {
+    use borsh_ext::BorshSerializeExt;
+    use namada::ledger::storage_api::StorageWrite;
+    use namada::proof_of_stake::types::ValidatorState;
+    use namada::proof_of_stake::{read_pos_params, validator_state_handle};
+    use namada::proto::{Code, Data};
+    use namada::types::transaction::TxType;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Build an unjail tx of the given validator, with its code in the
+    /// clear.
+    fn unjail_tx(data: Vec<u8>) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(b"tx_unjail_validator".to_vec(), None));
+        tx.set_data(Data::new(data));
+        tx
+    }
+
+    /// Test that unjail txs are only accepted for jailed validators.
+    #[test]
+    fn test_check_unjail_tx() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let validator = shell
+            .mode
+            .get_validator_address()
+            .expect("Test failed")
+            .clone();
+        let tx = unjail_tx(validator.serialize_to_vec());
+        let code_hash = invariants::inner_tx_code_hash(&tx).unwrap();
+        let check = |shell: &test_utils::TestShell, tx: &Tx| {
+            check_unjail_tx(&shell.wl_storage, tx).expect("Test failed")
+        };
+
+        // any tx is accepted until the code of unjail txs is known
+        assert!(check(&shell, &tx).is_ok());
+        shell
+            .wl_storage
+            .write(
+                &Key::wasm_code_name(TX_UNJAIL_VALIDATOR_WASM.to_owned()),
+                code_hash,
+            )
+            .expect("Test failed");
+
+        // the validator is not jailed
+        assert!(check(&shell, &tx).is_err());
+        // malformed data
+        assert!(check(&shell, &unjail_tx(b"validator".to_vec())).is_err());
+
+        // jail the validator up to the pipeline epoch
+        let params = read_pos_params(&shell.wl_storage).unwrap();
+        let current_epoch = shell.wl_storage.storage.block.epoch;
+        for offset in 0..=params.pipeline_len {
+            validator_state_handle(&validator)
+                .set(
+                    &mut shell.wl_storage,
+                    ValidatorState::Jailed,
+                    current_epoch,
+                    offset,
+                )
+                .expect("Test failed");
+        }
+        assert!(check(&shell, &tx).is_ok());
+    }
+}
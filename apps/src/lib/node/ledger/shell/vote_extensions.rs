@@ -1,14 +1,19 @@
-//! Extend Tendermint votes with Ethereum bridge logic.
+//! Extend Tendermint votes with Ethereum bridge logic, and with data
+//! attested in the enabled attestation lanes.
 
+pub mod attestation_vext;
 pub mod bridge_pool_vext;
 pub mod eth_events;
+pub mod heartbeat;
+pub mod misbehavior;
 pub mod val_set_update;
 
 use namada::proto::{SignableEthMessage, Signed};
 use namada::types::keccak::keccak_hash;
 use namada::types::transaction::protocol::EthereumTxData;
 use namada::types::vote_extensions::{
-    bridge_pool_roots, ethereum_events, validator_set_update, VoteExtension,
+    attestations, bridge_pool_roots, ethereum_events, validator_set_update,
+    VoteExtension,
 };
 use namada_sdk::eth_bridge::{EthBridgeQueries, SendValsetUpd};
 
@@ -61,6 +66,29 @@ pub enum VoteExtensionError {
          not active"
     )]
     EthereumBridgeInactive,
+    #[error("The vote extension was issued for an unknown attestation lane")]
+    UnknownAttestationLane,
+    #[error(
+        "The vote extension was issued for an attestation lane which is \
+         currently not enabled"
+    )]
+    AttestationLaneDisabled,
+    #[error("The attested data is invalid: {0}")]
+    InvalidAttestation(String),
+    #[error("The attested data exceeds the maximum size of attestations")]
+    AttestationTooLarge,
+    #[error("The submitted evidence does not amount to misbehavior")]
+    NotMisbehavior,
+    #[error("The submitted evidence is too old to be slashed")]
+    OutdatedEvidence,
+    #[error("The misbehavior in the submitted evidence was already slashed")]
+    EvidenceAlreadyProcessed,
+    #[error("The validator is not part of the consensus validator set")]
+    NotConsensusValidator,
+    #[error("A heartbeat of the validator was already recorded in this epoch")]
+    HeartbeatAlreadyRecorded,
+    #[error("The build attested to by the heartbeat exceeds the size limits")]
+    HeartbeatBuildTooLarge,
 }
 
 impl<D, H> Shell<D, H>
@@ -77,6 +105,7 @@ where
             ethereum_events: self.extend_vote_with_ethereum_events(),
             bridge_pool_root: self.extend_vote_with_bp_roots(),
             validator_set_update: self.extend_vote_with_valset_update(),
+            attestations: self.extend_vote_with_attestations(),
         }
     }
 
@@ -257,6 +286,24 @@ where
                         .valset_upd_seen(ext.data.signing_epoch.next()))
                     .then(|| tx_bytes.clone())
                 }
+                EthereumTxData::AttestationVext(ext) => {
+                    // only propose attestations which are still valid,
+                    // e.g. whose lane has not been disabled since
+                    self.validate_attestation_vext_and_get_it_back(
+                        ext,
+                        self.wl_storage.storage.get_last_block_height(),
+                    )
+                    .is_ok()
+                    .then(|| tx_bytes.clone())
+                }
+                EthereumTxData::MisbehaviorEvidence(evidence) => self
+                    .validate_misbehavior_evidence(&evidence)
+                    .is_ok()
+                    .then(|| tx_bytes.clone()),
+                EthereumTxData::ValidatorHeartbeat(heartbeat) => self
+                    .validate_validator_heartbeat(&heartbeat)
+                    .is_ok()
+                    .then(|| tx_bytes.clone()),
                 _ => None,
             }
         })
@@ -272,6 +319,7 @@ pub fn iter_protocol_txs(
         ethereum_events,
         bridge_pool_root,
         validator_set_update,
+        attestations,
     } = ext;
     [
         ethereum_events.map(EthereumTxData::EthEventsVext),
@@ -280,4 +328,9 @@ pub fn iter_protocol_txs(
     ]
     .into_iter()
     .flatten()
+    .chain(
+        attestations
+            .into_iter()
+            .map(EthereumTxData::AttestationVext),
+    )
 }
@@ -0,0 +1,525 @@
+//! Extend Tendermint votes with data attested in the attestation lanes
+//! enabled by governance.
+//!
+//! An attestation lane is a pluggable feed of validator-attested data
+//! (e.g. price medians, or the headers of some external chain). Lanes are
+//! registered with the [`Shell`], and only become active once governance
+//! enables them, via their respective parameter key.
+//!
+//! N.B. the hooks of a lane take part in the validation and application
+//! of blocks, therefore all the nodes of a network must register the same
+//! lanes, and their hooks must be deterministic.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, WlStorage, DB};
+use namada::ledger::{parameters, storage_api};
+use namada::proto::Signed;
+use namada::types::address::Address;
+use namada::types::storage::BlockHeight;
+use namada::types::token;
+
+use super::*;
+use crate::node::ledger::shell::Shell;
+
+/// The maximum size in bytes of the data attested to in a single
+/// [`attestations::Vext`].
+pub const MAX_ATTESTATION_DATA_LEN: usize = 4 * 1024;
+
+/// Data attested by some validator, weighted by its voting power.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attestation {
+    /// The validator who attested to the data.
+    pub validator: Address,
+    /// The voting power of the validator, at the epoch in which the
+    /// data was attested.
+    pub voting_power: token::Amount,
+    /// The attested data.
+    pub data: Vec<u8>,
+}
+
+/// A lane of validator-attested data.
+pub trait AttestationLane<D, H>: Send + Sync
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// The unique id of this lane. Governance enables the lane by
+    /// writing to the parameter key derived from this id.
+    fn id(&self) -> &str;
+
+    /// Observe the data this validator attests to at the current block
+    /// height, or return [`None`] to abstain from voting.
+    fn observe(&self, wl_storage: &WlStorage<D, H>) -> Option<Vec<u8>>;
+
+    /// Validate data attested by some validator, in a lane specific
+    /// manner.
+    fn validate(
+        &self,
+        wl_storage: &WlStorage<D, H>,
+        data: &[u8],
+    ) -> std::result::Result<(), String>;
+
+    /// Aggregate the attestations included in a block, writing the result
+    /// to storage. At most one attestation per validator is given.
+    fn aggregate(
+        &self,
+        wl_storage: &mut WlStorage<D, H>,
+        attestations: Vec<Attestation>,
+    ) -> storage_api::Result<()>;
+}
+
+/// The [`AttestationLane`] instances registered with the [`Shell`],
+/// indexed by their ids.
+pub struct AttestationLanes<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    lanes: BTreeMap<String, Box<dyn AttestationLane<D, H>>>,
+}
+
+impl<D, H> Default for AttestationLanes<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            lanes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<D, H> fmt::Debug for AttestationLanes<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.lanes.keys()).finish()
+    }
+}
+
+impl<D, H> AttestationLanes<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Register a new [`AttestationLane`].
+    ///
+    /// Panics if another lane with the same id has already been
+    /// registered.
+    pub fn register(&mut self, lane: Box<dyn AttestationLane<D, H>>) {
+        let id = lane.id().to_owned();
+        if self.lanes.insert(id.clone(), lane).is_some() {
+            panic!("The attestation lane {id:?} was registered twice");
+        }
+    }
+
+    /// Look up the [`AttestationLane`] with the given id.
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&dyn AttestationLane<D, H>> {
+        self.lanes.get(id).map(|lane| lane.as_ref())
+    }
+
+    /// Iterate over the registered lanes, which are enabled in storage.
+    fn iter_enabled<'this>(
+        &'this self,
+        wl_storage: &'this WlStorage<D, H>,
+    ) -> impl Iterator<Item = &'this dyn AttestationLane<D, H>> + 'this {
+        self.lanes
+            .iter()
+            .filter(|(id, _)| is_lane_enabled(wl_storage, id))
+            .map(|(_, lane)| lane.as_ref())
+    }
+}
+
+/// Check if the attestation lane with the given id has been enabled by
+/// governance.
+fn is_lane_enabled<D, H>(wl_storage: &WlStorage<D, H>, lane: &str) -> bool
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    parameters::is_attestation_lane_enabled(wl_storage, lane)
+        .expect("Reading an attestation lane parameter shouldn't fail")
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Register a new [`AttestationLane`] with this [`Shell`].
+    ///
+    /// Panics if another lane with the same id has already been
+    /// registered.
+    pub fn register_attestation_lane(
+        &mut self,
+        lane: impl AttestationLane<D, H> + 'static,
+    ) {
+        self.attestation_lanes.register(Box::new(lane));
+    }
+
+    /// Extend PreCommit votes with [`attestations::Vext`] instances, one
+    /// for each enabled lane in which this validator attests to some data.
+    pub fn extend_vote_with_attestations(
+        &self,
+    ) -> Vec<Signed<attestations::Vext>> {
+        let validator_addr = self
+            .mode
+            .get_validator_address()
+            .expect(VALIDATOR_EXPECT_MSG);
+        let protocol_key =
+            self.mode.get_protocol_key().expect(VALIDATOR_EXPECT_MSG);
+        let block_height = self.wl_storage.storage.get_last_block_height();
+
+        self.attestation_lanes
+            .iter_enabled(&self.wl_storage)
+            .filter_map(|lane| {
+                let data = lane.observe(&self.wl_storage)?;
+                if data.len() > MAX_ATTESTATION_DATA_LEN {
+                    tracing::warn!(
+                        lane = lane.id(),
+                        len = data.len(),
+                        "Not attesting to data larger than the maximum size \
+                         of attestations"
+                    );
+                    return None;
+                }
+                let ext = attestations::Vext {
+                    lane: lane.id().to_owned(),
+                    block_height,
+                    validator_addr: validator_addr.clone(),
+                    data,
+                };
+                Some(ext.sign(protocol_key))
+            })
+            .collect()
+    }
+
+    /// Validates a vote extension issued at the provided block height,
+    /// attesting to some data in one of the attestation lanes.
+    ///
+    /// Checks that at epoch of the provided height:
+    ///  * The lane is registered with this node, and enabled in storage.
+    ///  * The inner Namada address corresponds to a consensus validator.
+    ///  * The validator correctly signed the extension.
+    ///  * The validator signed over the last block height inside of the
+    ///    extension, such that old attestations cannot be replayed.
+    ///  * The attested data is at most [`MAX_ATTESTATION_DATA_LEN`] bytes long,
+    ///    and passes the validation hook of the lane.
+    pub fn validate_attestation_vext_and_get_it_back(
+        &self,
+        ext: Signed<attestations::Vext>,
+        last_height: BlockHeight,
+    ) -> std::result::Result<
+        (token::Amount, Signed<attestations::Vext>),
+        VoteExtensionError,
+    > {
+        let lane_id = &ext.data.lane;
+        let Some(lane) = self.attestation_lanes.get(lane_id) else {
+            tracing::debug!(
+                lane = lane_id,
+                "Attestation vote extension issued for an unknown lane"
+            );
+            return Err(VoteExtensionError::UnknownAttestationLane);
+        };
+        if !is_lane_enabled(&self.wl_storage, lane_id) {
+            tracing::debug!(
+                lane = lane_id,
+                "Attestation vote extension issued for a disabled lane"
+            );
+            return Err(VoteExtensionError::AttestationLaneDisabled);
+        }
+
+        if ext.data.block_height != last_height {
+            tracing::debug!(
+                ext_height = ?ext.data.block_height,
+                ?last_height,
+                "Attestation vote extension issued for a block height \
+                 other than the chain's last height."
+            );
+            return Err(VoteExtensionError::UnexpectedBlockHeight);
+        }
+        if ext.data.block_height.0 == 0 {
+            tracing::debug!("Dropping vote extension issued at genesis");
+            return Err(VoteExtensionError::UnexpectedBlockHeight);
+        }
+        let Some(ext_height_epoch) = self.get_epoch(ext.data.block_height)
+        else {
+            tracing::debug!(
+                block_height = ?ext.data.block_height,
+                "The epoch of the attestation vote extension's block height \
+                 should always be known",
+            );
+            return Err(VoteExtensionError::UnexpectedEpoch);
+        };
+
+        if ext.data.data.len() > MAX_ATTESTATION_DATA_LEN {
+            tracing::debug!(
+                lane = lane_id,
+                len = ext.data.data.len(),
+                "Attestation vote extension with oversized data"
+            );
+            return Err(VoteExtensionError::AttestationTooLarge);
+        }
+
+        // get the public key associated with this validator
+        let validator = &ext.data.validator_addr;
+        let (voting_power, pk) = self
+            .wl_storage
+            .pos_queries()
+            .get_validator_from_address(validator, Some(ext_height_epoch))
+            .map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    %validator,
+                    "Could not get public key from Storage for some validator, \
+                     while validating attestation vote extension"
+                );
+                VoteExtensionError::PubKeyNotInStorage
+            })?;
+        // verify the signature of the vote extension
+        ext.verify(&pk).map_err(|err| {
+            tracing::debug!(
+                ?err,
+                ?ext.sig,
+                ?pk,
+                %validator,
+                "Failed to verify the signature of an attestation vote \
+                 extension issued by some validator"
+            );
+            VoteExtensionError::VerifySigFailed
+        })?;
+
+        lane.validate(&self.wl_storage, &ext.data.data)
+            .map_err(|err| {
+                tracing::debug!(
+                    lane = lane_id,
+                    %validator,
+                    %err,
+                    "Invalid data in attestation vote extension"
+                );
+                VoteExtensionError::InvalidAttestation(err)
+            })
+            .map(|()| (voting_power, ext))
+    }
+
+    /// Hand the attestations included in a block over to the aggregation
+    /// hooks of their respective lanes. Only attestations issued at the
+    /// last block height are accepted, and at most one of each validator
+    /// in each lane is kept.
+    pub fn aggregate_attestations(
+        &mut self,
+        attestations: impl IntoIterator<Item = Signed<attestations::Vext>>,
+    ) -> storage_api::Result<()> {
+        let last_height = self.wl_storage.storage.get_last_block_height();
+        let mut lanes: BTreeMap<String, BTreeMap<Address, _>> = BTreeMap::new();
+
+        for ext in attestations {
+            let (voting_power, ext) = match self
+                .validate_attestation_vext_and_get_it_back(ext, last_height)
+            {
+                Ok(valid) => valid,
+                Err(err) => {
+                    tracing::warn!(
+                        %err,
+                        "Dropping invalid attestation vote extension"
+                    );
+                    continue;
+                }
+            };
+            let attestations::Vext {
+                lane,
+                block_height,
+                validator_addr,
+                data,
+            } = ext.data;
+            let votes = lanes.entry(lane).or_default();
+            let is_newer = votes
+                .get(&validator_addr)
+                .map(|(height, _)| block_height > *height)
+                .unwrap_or(true);
+            if is_newer {
+                let attestation = Attestation {
+                    validator: validator_addr.clone(),
+                    voting_power,
+                    data,
+                };
+                votes.insert(validator_addr, (block_height, attestation));
+            }
+        }
+
+        for (lane_id, votes) in lanes {
+            let lane = self
+                .attestation_lanes
+                .lanes
+                .get(&lane_id)
+                .expect("Attestations of unknown lanes were dropped");
+            let votes = votes.into_values().map(|(_, vote)| vote).collect();
+            lane.aggregate(&mut self.wl_storage, votes)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_attestation_vote_extensions {
+    use namada::ledger::storage::mockdb::MockDB;
+    use namada::ledger::storage::Sha256Hasher;
+    use namada::ledger::storage_api::{StorageRead, StorageWrite};
+    use namada::types::storage::Key;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::*;
+
+    /// The id of the [`TestLane`].
+    const TEST_LANE: &str = "test-lane";
+
+    /// Storage key under which the [`TestLane`] writes its aggregate.
+    fn aggregate_key() -> Key {
+        Key::parse("test-lane-aggregate").expect("Test failed")
+    }
+
+    /// An [`AttestationLane`] in which validators attest to some byte,
+    /// which must be non-zero. The attested bytes are summed up, weighted
+    /// by voting power.
+    struct TestLane;
+
+    impl AttestationLane<MockDB, Sha256Hasher> for TestLane {
+        fn id(&self) -> &str {
+            TEST_LANE
+        }
+
+        fn observe(
+            &self,
+            _: &WlStorage<MockDB, Sha256Hasher>,
+        ) -> Option<Vec<u8>> {
+            Some(vec![1])
+        }
+
+        fn validate(
+            &self,
+            _: &WlStorage<MockDB, Sha256Hasher>,
+            data: &[u8],
+        ) -> std::result::Result<(), String> {
+            match data {
+                [byte] if *byte != 0 => Ok(()),
+                _ => Err("Expected a single non-zero byte".into()),
+            }
+        }
+
+        fn aggregate(
+            &self,
+            wl_storage: &mut WlStorage<MockDB, Sha256Hasher>,
+            attestations: Vec<Attestation>,
+        ) -> storage_api::Result<()> {
+            let total = attestations
+                .into_iter()
+                .map(|attestation| {
+                    attestation.voting_power * u64::from(attestation.data[0])
+                })
+                .sum::<token::Amount>();
+            wl_storage.write(&aggregate_key(), total)
+        }
+    }
+
+    /// Test that attestations are only issued and accepted in enabled
+    /// lanes, and that they are aggregated by the lane's hooks.
+    #[test]
+    fn test_attestation_lane_flow() {
+        let (mut shell, _broadcaster, _, _oracle_control_recv) =
+            setup_at_height(1u64);
+        shell.wl_storage.storage.block.height =
+            shell.wl_storage.storage.get_last_block_height();
+        shell.commit();
+        shell.register_attestation_lane(TestLane);
+        let last_height = shell.wl_storage.storage.get_last_block_height();
+
+        // the lane is registered, but not enabled yet
+        assert!(shell.extend_vote_with_attestations().is_empty());
+
+        parameters::update_attestation_lane_parameter(
+            &mut shell.wl_storage,
+            TEST_LANE,
+            true,
+        )
+        .expect("Test failed");
+        let exts = shell.extend_vote_with_attestations();
+        assert_eq!(exts.len(), 1);
+        let (voting_power, ext) = shell
+            .validate_attestation_vext_and_get_it_back(
+                exts[0].clone(),
+                last_height,
+            )
+            .expect("Test failed");
+
+        // attestations issued at past heights cannot be replayed
+        let past = attestations::Vext {
+            block_height: BlockHeight(last_height.0 - 1),
+            ..ext.data.clone()
+        }
+        .sign(shell.mode.get_protocol_key().expect("Test failed"));
+        assert!(matches!(
+            shell.validate_attestation_vext_and_get_it_back(past, last_height),
+            Err(VoteExtensionError::UnexpectedBlockHeight)
+        ));
+
+        // oversized data is rejected
+        let oversized = attestations::Vext {
+            data: vec![1; MAX_ATTESTATION_DATA_LEN + 1],
+            ..ext.data.clone()
+        }
+        .sign(shell.mode.get_protocol_key().expect("Test failed"));
+        assert!(matches!(
+            shell.validate_attestation_vext_and_get_it_back(
+                oversized,
+                last_height
+            ),
+            Err(VoteExtensionError::AttestationTooLarge)
+        ));
+
+        // invalid data is rejected by the lane's validation hook
+        let invalid = attestations::Vext {
+            data: vec![0],
+            ..ext.data.clone()
+        }
+        .sign(shell.mode.get_protocol_key().expect("Test failed"));
+        assert!(matches!(
+            shell.validate_attestation_vext_and_get_it_back(
+                invalid,
+                last_height
+            ),
+            Err(VoteExtensionError::InvalidAttestation(_))
+        ));
+
+        // duplicate attestations of the same validator are only
+        // counted once
+        shell
+            .aggregate_attestations([ext.clone(), ext.clone()])
+            .expect("Test failed");
+        let total: token::Amount = shell
+            .wl_storage
+            .read(&aggregate_key())
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(total, voting_power);
+
+        // attestations are rejected once the lane is disabled
+        parameters::update_attestation_lane_parameter(
+            &mut shell.wl_storage,
+            TEST_LANE,
+            false,
+        )
+        .expect("Test failed");
+        assert!(matches!(
+            shell.validate_attestation_vext_and_get_it_back(ext, last_height),
+            Err(VoteExtensionError::AttestationLaneDisabled)
+        ));
+    }
+}
@@ -51,11 +51,7 @@ where
     > {
         // NOTE: for ABCI++, we should pass
         // `last_height` here, instead of `ext.data.block_height`
-        let ext_height_epoch = match self
-            .wl_storage
-            .pos_queries()
-            .get_epoch(ext.data.block_height)
-        {
+        let ext_height_epoch = match self.get_epoch(ext.data.block_height) {
             Some(epoch) => epoch,
             _ => {
                 tracing::debug!(
@@ -2,20 +2,51 @@
 
 use std::collections::{BTreeMap, HashMap};
 
+use namada::eth_bridge::protocol::transactions::votes::{
+    EpochedVotingPower, EpochedVotingPowerExt,
+};
+use namada::eth_bridge::storage::vote_tallies;
+use namada::ledger::events::{Event, EventLevel, EventType};
 use namada::ledger::pos::PosQueries;
 use namada::ledger::storage::traits::StorageHasher;
 use namada::ledger::storage::{DBIter, DB};
+use namada::ledger::storage_api::StorageRead;
 use namada::proto::Signed;
-use namada::types::ethereum_events::EthereumEvent;
+use namada::types::ethereum_events::{EthereumEvent, Uint};
 use namada::types::storage::BlockHeight;
 use namada::types::token;
 use namada::types::vote_extensions::ethereum_events::{
     self, MultiSignedEthEvent,
 };
+use namada::types::voting_power::FractionalVotingPower;
 use namada_sdk::eth_bridge::EthBridgeQueries;
 
 use super::*;
-use crate::node::ledger::shell::{Shell, ShellMode};
+use crate::node::ledger::shell::{NextEthEventNonces, Shell, ShellMode};
+
+/// Return the kind of the given [`EthereumEvent`], along with its nonce.
+fn eth_event_kind_and_nonce(event: &EthereumEvent) -> (&'static str, Uint) {
+    match event {
+        EthereumEvent::TransfersToNamada { nonce, .. } => {
+            ("transfers_to_namada", *nonce)
+        }
+        EthereumEvent::TransfersToNamadaBatch { nonce, .. } => {
+            ("transfers_to_namada_batch", *nonce)
+        }
+        EthereumEvent::TransfersToEthereum { nonce, .. } => {
+            ("transfers_to_ethereum", *nonce)
+        }
+        EthereumEvent::ValidatorSetUpdate { nonce, .. } => {
+            ("validator_set_update", *nonce)
+        }
+        EthereumEvent::NftTransfersToNamada { nonce, .. } => {
+            ("nft_transfers_to_namada", *nonce)
+        }
+        EthereumEvent::BridgeContractHandover {
+            handover_height, ..
+        } => ("bridge_contract_handover", *handover_height),
+    }
+}
 
 impl<D, H> Shell<D, H>
 where
@@ -55,11 +86,7 @@ where
     > {
         // NOTE: for ABCI++, we should pass
         // `last_height` here, instead of `ext.data.block_height`
-        let ext_height_epoch = match self
-            .wl_storage
-            .pos_queries()
-            .get_epoch(ext.data.block_height)
-        {
+        let ext_height_epoch = match self.get_epoch(ext.data.block_height) {
             Some(epoch) => epoch,
             _ => {
                 tracing::debug!(
@@ -171,21 +198,109 @@ where
     /// the fullnode and retrieves all seen Ethereum events.
     pub fn new_ethereum_events(&mut self) -> Vec<EthereumEvent> {
         let queries = self.wl_storage.ethbridge_queries();
-        match &mut self.mode {
+        let max_events = queries
+            .get_max_vext_eth_events()
+            .map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+        // the next nonces are only needed to prioritize the events, if
+        // not all of them fit in a vote extension
+        let next_nonces = max_events
+            .map(|_| NextEthEventNonces {
+                transfers_to_namada: queries.get_next_nam_transfers_nonce(),
+                transfers_to_ethereum: queries.get_bridge_pool_nonce(),
+                nft_transfers_to_namada: queries.get_next_nft_transfers_nonce(),
+            })
+            .unwrap_or_default();
+        let last_height = self.wl_storage.storage.get_last_block_height();
+        let events = match &mut self.mode {
             ShellMode::Validator {
-                eth_oracle:
-                    Some(EthereumOracleChannels {
-                        ethereum_receiver, ..
-                    }),
+                eth_oracle: Some(eth_oracle),
                 ..
-            } => {
-                ethereum_receiver.fill_queue(|event| {
-                    queries.validate_eth_event_nonce(event)
-                });
-                ethereum_receiver.get_events()
-            }
+            } => supervisor::isolate(
+                "Ethereum oracle channels",
+                &self.crash_reports,
+                || {
+                    eth_oracle.pull_events(last_height, |event| {
+                        queries.validate_eth_event_nonce(event)
+                    });
+                    eth_oracle
+                        .ethereum_receiver
+                        .get_events(max_events, &next_nonces)
+                },
+            )
+            .unwrap_or_default(),
             _ => vec![],
+        };
+        self.update_eth_events_queue_metrics();
+        events
+    }
+
+    /// Publish the counters of the queue of Ethereum events with the
+    /// node-local metrics.
+    fn update_eth_events_queue_metrics(&self) {
+        if let Some(queue_metrics) = self.mode.eth_events_queue_metrics() {
+            self.node_metrics.update(|metrics| {
+                metrics.eth_events_queue = queue_metrics;
+            });
+        }
+    }
+
+    /// Report the Ethereum events which stayed queued up for inclusion in
+    /// vote extensions for too many blocks, without reaching a quorum of
+    /// stake on chain, such that bridge monitoring can alert operators.
+    ///
+    /// Each stalled event is logged as a warning, and returned as an
+    /// [`Event`] to be published off chain, along with the fraction of
+    /// stake that voted for it so far.
+    pub fn report_stalled_eth_events(&mut self) -> Vec<Event> {
+        let height = self.wl_storage.storage.get_last_block_height();
+        let stalled = self.mode.take_stalled_eth_events(height);
+        self.update_eth_events_queue_metrics();
+        let mut events = Vec::with_capacity(stalled.len());
+        for (eth_event, received_at_height) in stalled {
+            let keys = vote_tallies::Keys::from(&eth_event);
+            let seen = self
+                .wl_storage
+                .read::<bool>(&keys.seen())
+                .ok()
+                .flatten()
+                .unwrap_or(false);
+            if seen {
+                // the event will be evicted from the queue
+                continue;
+            }
+            let voting_power = self
+                .wl_storage
+                .read::<EpochedVotingPower>(&keys.voting_power())
+                .ok()
+                .flatten()
+                .map(|power| power.fractional_stake(&self.wl_storage))
+                .unwrap_or(FractionalVotingPower::NULL);
+            let (kind, nonce) = eth_event_kind_and_nonce(&eth_event);
+            let stalled_blocks = height.0 - received_at_height.0;
+            tracing::warn!(
+                kind,
+                %nonce,
+                ?eth_event,
+                %received_at_height,
+                stalled_blocks,
+                %voting_power,
+                "An Ethereum event has not reached a quorum of stake on \
+                 chain after staying queued for too many blocks"
+            );
+            let mut event = Event {
+                event_type: EventType::StalledEthEvent,
+                level: EventLevel::Block,
+                attributes: Default::default(),
+            };
+            event["kind"] = kind.to_owned();
+            event["nonce"] = nonce.to_string();
+            event["received_at_height"] = received_at_height.to_string();
+            event["stalled_blocks"] = stalled_blocks.to_string();
+            event["voting_power"] = voting_power.to_string();
+            event["height"] = height.to_string();
+            events.push(event);
         }
+        events
     }
 
     /// Takes an iterator over Ethereum events vote extension instances,
@@ -420,6 +535,38 @@ mod test_vote_extensions {
             .expect_err("Test failed");
     }
 
+    /// Test that batches of transfers to Namada whose last nonce
+    /// overflows are invalid.
+    #[test]
+    fn test_eth_event_validate_batch_nonce_overflow() {
+        let (shell, _, _, _) = setup();
+        let max_nonce = Uint([u64::MAX; 4]);
+
+        // a batch ending at the maximum nonce is valid
+        shell
+            .wl_storage
+            .ethbridge_queries()
+            .validate_eth_event_nonce(&EthereumEvent::TransfersToNamadaBatch {
+                nonce: max_nonce - 1,
+                batches: vec![vec![]; 2],
+            })
+            .then_some(())
+            .ok_or(())
+            .expect("Test failed");
+
+        // a batch going past the maximum nonce is invalid
+        shell
+            .wl_storage
+            .ethbridge_queries()
+            .validate_eth_event_nonce(&EthereumEvent::TransfersToNamadaBatch {
+                nonce: max_nonce - 1,
+                batches: vec![vec![]; 3],
+            })
+            .then_some(())
+            .ok_or(())
+            .expect_err("Test failed");
+    }
+
     /// Test that we successfully receive ethereum events
     /// from the channel to fullnode process
     ///
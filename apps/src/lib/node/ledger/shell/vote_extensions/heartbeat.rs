@@ -0,0 +1,306 @@
+//! Periodic heartbeats of validators, attesting to the build of the node
+//! software they run.
+//!
+//! Once per epoch, each validator node broadcasts a heartbeat signed with
+//! its protocol key, in a protocol tx. The builds attested to by the
+//! heartbeats included on chain are recorded per epoch in the PoS storage,
+//! where they can be queried.
+
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::proof_of_stake::{
+    is_validator_heartbeat_recorded, write_validator_heartbeat,
+};
+use namada::types::vote_extensions::heartbeat::{
+    BuildInfo, Heartbeat, SignedHeartbeat,
+};
+
+use super::*;
+use crate::node::ledger::shell::Shell;
+
+/// Return the build of the node software currently running.
+pub fn build_info() -> BuildInfo {
+    let features = [
+        ("mainnet", cfg!(feature = "mainnet")),
+        ("std", cfg!(feature = "std")),
+        ("testing", cfg!(feature = "testing")),
+        ("integration", cfg!(feature = "integration")),
+        ("event-sink-nats", cfg!(feature = "event-sink-nats")),
+        ("event-sink-kafka", cfg!(feature = "event-sink-kafka")),
+    ];
+    BuildInfo {
+        protocol_version: env!("CARGO_PKG_VERSION").to_owned(),
+        git_hash: crate::cli::namada_git_hash().map(ToOwned::to_owned),
+        features: features
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| feature.to_owned())
+            .collect(),
+    }
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Validate a [`SignedHeartbeat`].
+    ///
+    /// Checks that:
+    ///  * The heartbeat was issued in the current epoch.
+    ///  * The attested build fits within the size limits.
+    ///  * The heartbeat was sent by a consensus validator, who correctly signed
+    ///    it with its protocol key.
+    ///  * No heartbeat of the validator was recorded in the current epoch yet.
+    pub fn validate_validator_heartbeat(
+        &self,
+        heartbeat: &SignedHeartbeat,
+    ) -> std::result::Result<(), VoteExtensionError> {
+        let current_epoch = self.wl_storage.storage.get_current_epoch().0;
+        if heartbeat.data.epoch != current_epoch {
+            tracing::debug!(
+                heartbeat_epoch = %heartbeat.data.epoch,
+                %current_epoch,
+                "Validator heartbeat issued in an unexpected epoch"
+            );
+            return Err(VoteExtensionError::UnexpectedEpoch);
+        }
+        if !heartbeat.data.build.is_within_limits() {
+            return Err(VoteExtensionError::HeartbeatBuildTooLarge);
+        }
+
+        let validator = &heartbeat.data.validator_addr;
+        let (_, pk) = self
+            .wl_storage
+            .pos_queries()
+            .get_validator_from_address(validator, Some(current_epoch))
+            .map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    %validator,
+                    "Could not get the public key of the validator who sent \
+                     a heartbeat"
+                );
+                VoteExtensionError::NotConsensusValidator
+            })?;
+        heartbeat.verify(&pk).map_err(|err| {
+            tracing::debug!(
+                ?err,
+                ?heartbeat.sig,
+                ?pk,
+                %validator,
+                "Failed to verify the signature of a validator heartbeat"
+            );
+            VoteExtensionError::VerifySigFailed
+        })?;
+
+        let already_recorded = is_validator_heartbeat_recorded(
+            &self.wl_storage,
+            validator,
+            current_epoch,
+        )
+        .expect("Must be able to read the validator heartbeats");
+        if already_recorded {
+            return Err(VoteExtensionError::HeartbeatAlreadyRecorded);
+        }
+
+        Ok(())
+    }
+
+    /// Broadcast a heartbeat of this validator, attesting to the build of
+    /// the node software it runs, once per epoch.
+    pub fn broadcast_validator_heartbeat(&mut self) {
+        let current_epoch = self.wl_storage.storage.get_current_epoch().0;
+        if self.last_heartbeat_epoch == Some(current_epoch) {
+            return;
+        }
+        let (Some(validator_addr), Some(protocol_key)) = (
+            self.mode.get_validator_address(),
+            self.mode.get_protocol_key(),
+        ) else {
+            return;
+        };
+        let is_consensus_validator = self
+            .wl_storage
+            .pos_queries()
+            .get_validator_from_address(validator_addr, Some(current_epoch))
+            .is_ok();
+        if !is_consensus_validator {
+            return;
+        }
+        let already_recorded = is_validator_heartbeat_recorded(
+            &self.wl_storage,
+            validator_addr,
+            current_epoch,
+        )
+        .expect("Must be able to read the validator heartbeats");
+        if !already_recorded {
+            let heartbeat = Heartbeat {
+                validator_addr: validator_addr.clone(),
+                epoch: current_epoch,
+                build: build_info(),
+            }
+            .sign(protocol_key);
+            tracing::debug!(
+                epoch = %current_epoch,
+                build = ?heartbeat.data.build,
+                "Broadcasting a validator heartbeat"
+            );
+            let tx = EthereumTxData::ValidatorHeartbeat(heartbeat)
+                .sign(protocol_key, self.chain_id.clone())
+                .to_bytes();
+            self.mode.broadcast(tx);
+        }
+        self.last_heartbeat_epoch = Some(current_epoch);
+    }
+
+    /// Record the builds attested to by the validator heartbeats included
+    /// on chain, in the current block.
+    pub fn record_validator_heartbeats(
+        &mut self,
+        heartbeats: impl IntoIterator<Item = SignedHeartbeat>,
+    ) -> storage_api::Result<()> {
+        for heartbeat in heartbeats {
+            // NB: the heartbeat is validated again, in case the
+            // same validator's heartbeat was included more than once
+            if let Err(err) = self.validate_validator_heartbeat(&heartbeat) {
+                tracing::debug!(%err, "Dropping invalid validator heartbeat");
+                continue;
+            }
+            let Heartbeat {
+                validator_addr,
+                epoch,
+                build,
+            } = heartbeat.data;
+            tracing::info!(
+                validator = %validator_addr,
+                %epoch,
+                ?build,
+                "Recorded a validator heartbeat"
+            );
+            write_validator_heartbeat(
+                &mut self.wl_storage,
+                &validator_addr,
+                epoch,
+                build,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_heartbeat {
+    use namada::proof_of_stake::{
+        prune_validator_heartbeats, read_validator_heartbeats,
+    };
+    use namada::types::transaction::protocol::ethereum_tx_data_variants;
+    use namada::types::vote_extensions::heartbeat::MAX_BUILD_FEATURES;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Sign a heartbeat of the test validator, in the current epoch.
+    fn sign_heartbeat(shell: &test_utils::TestShell) -> SignedHeartbeat {
+        Heartbeat {
+            validator_addr: shell
+                .mode
+                .get_validator_address()
+                .expect("Test failed")
+                .clone(),
+            epoch: shell.wl_storage.storage.get_current_epoch().0,
+            build: build_info(),
+        }
+        .sign(shell.mode.get_protocol_key().expect("Test failed"))
+    }
+
+    /// Test that a heartbeat is recorded once per epoch and validator.
+    #[test]
+    fn test_record_validator_heartbeats() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let heartbeat = sign_heartbeat(&shell);
+        assert!(shell.validate_validator_heartbeat(&heartbeat).is_ok());
+
+        shell
+            .record_validator_heartbeats([heartbeat.clone(), heartbeat.clone()])
+            .expect("Test failed");
+        let epoch = shell.wl_storage.storage.get_current_epoch().0;
+        let recorded =
+            read_validator_heartbeats(&shell.wl_storage, epoch).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[&heartbeat.data.validator_addr], build_info());
+        assert!(matches!(
+            shell.validate_validator_heartbeat(&heartbeat),
+            Err(VoteExtensionError::HeartbeatAlreadyRecorded)
+        ));
+    }
+
+    /// Test that heartbeats issued in another epoch, or with an invalid
+    /// signature, are rejected.
+    #[test]
+    fn test_reject_invalid_heartbeats() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let mut heartbeat = sign_heartbeat(&shell);
+        heartbeat.data.epoch = heartbeat.data.epoch.next();
+        assert!(matches!(
+            shell.validate_validator_heartbeat(&heartbeat),
+            Err(VoteExtensionError::UnexpectedEpoch)
+        ));
+
+        let mut heartbeat = sign_heartbeat(&shell);
+        heartbeat.data.build.protocol_version = "0.0.0".into();
+        assert!(matches!(
+            shell.validate_validator_heartbeat(&heartbeat),
+            Err(VoteExtensionError::VerifySigFailed)
+        ));
+
+        let mut heartbeat = sign_heartbeat(&shell);
+        heartbeat.data.build.features =
+            vec!["feature".to_owned(); MAX_BUILD_FEATURES + 1];
+        assert!(matches!(
+            shell.validate_validator_heartbeat(&heartbeat),
+            Err(VoteExtensionError::HeartbeatBuildTooLarge)
+        ));
+    }
+
+    /// Test that only the heartbeats of the current and the last epoch are
+    /// kept in storage.
+    #[test]
+    fn test_prune_validator_heartbeats() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let heartbeat = sign_heartbeat(&shell);
+        let epoch = heartbeat.data.epoch;
+        shell
+            .record_validator_heartbeats([heartbeat.clone()])
+            .expect("Test failed");
+
+        prune_validator_heartbeats(&mut shell.wl_storage, epoch.next())
+            .expect("Test failed");
+        let recorded =
+            read_validator_heartbeats(&shell.wl_storage, epoch).unwrap();
+        assert!(recorded.contains_key(&heartbeat.data.validator_addr));
+
+        prune_validator_heartbeats(&mut shell.wl_storage, epoch.next().next())
+            .expect("Test failed");
+        let recorded =
+            read_validator_heartbeats(&shell.wl_storage, epoch).unwrap();
+        assert!(recorded.is_empty());
+    }
+
+    /// Test that validators broadcast a single heartbeat per epoch.
+    #[test]
+    fn test_broadcast_validator_heartbeat() {
+        let (mut shell, mut broadcast_recv, _, _) = test_utils::setup();
+        shell.broadcast_validator_heartbeat();
+        let tx = broadcast_recv.try_recv().expect("Test failed");
+        let tx = Tx::try_from(tx.as_ref()).expect("Test failed");
+        let heartbeat =
+            ethereum_tx_data_variants::ValidatorHeartbeat::try_from(&tx)
+                .expect("Test failed");
+        assert!(shell.validate_validator_heartbeat(&heartbeat).is_ok());
+
+        shell.broadcast_validator_heartbeat();
+        assert!(broadcast_recv.try_recv().is_err());
+    }
+}
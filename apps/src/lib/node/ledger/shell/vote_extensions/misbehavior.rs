@@ -0,0 +1,472 @@
+//! Validate evidence of validators misbehaving in their vote extensions,
+//! submitted by external watchtowers, and slash the offending validators
+//! once the evidence is included on chain.
+//!
+//! Evidence is submitted to validator nodes via the `/shell/submit_evidence`
+//! RPC path, and broadcast to the network as a protocol tx signed by the
+//! node's protocol key. As anyone may query this path, validators must opt
+//! into accepting evidence, via the `accept_misbehavior_evidence` config.
+
+use borsh::BorshDeserialize;
+use namada::ledger::pos::PosQueries;
+use namada::ledger::storage::traits::StorageHasher;
+use namada::ledger::storage::{DBIter, DB};
+use namada::ledger::storage_api::ResultExt;
+use namada::proof_of_stake::types::SlashType;
+use namada::proof_of_stake::{is_evidence_outdated, is_slash_enqueued};
+use namada::types::ethereum_events::Uint;
+use namada::types::keccak::KeccakHash;
+use namada::types::storage::{BlockHeight, Epoch};
+use namada::types::vote_extensions::misbehavior::MisbehaviorEvidence;
+
+use super::*;
+use crate::node::ledger::shell::Shell;
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Validate some [`MisbehaviorEvidence`], returning the epoch and
+    /// block height at which the infraction was committed.
+    ///
+    /// Checks that:
+    ///  * The evidence amounts to misbehavior of some validator.
+    ///  * The validator correctly signed the offending data.
+    ///  * The infraction can still be slashed, and it has not been slashed yet.
+    pub fn validate_misbehavior_evidence(
+        &self,
+        evidence: &MisbehaviorEvidence,
+    ) -> std::result::Result<(Epoch, BlockHeight), VoteExtensionError> {
+        if !evidence.is_well_formed() {
+            tracing::debug!(
+                ?evidence,
+                "The submitted evidence does not amount to misbehavior"
+            );
+            return Err(VoteExtensionError::NotMisbehavior);
+        }
+        let (evidence_epoch, evidence_height) = match evidence {
+            MisbehaviorEvidence::ConflictingValSetUpdateVexts {
+                first,
+                second,
+            } => self.validate_conflicting_valset_upd_vexts(first, second)?,
+            MisbehaviorEvidence::InvalidBridgePoolRoot {
+                vext,
+                root,
+                nonce,
+            } => self.validate_invalid_bp_root(
+                vext,
+                *root,
+                *nonce,
+                evidence
+                    .bridge_pool_root_message()
+                    .expect("The evidence is of an invalid Bridge pool root"),
+            )?,
+        };
+
+        let pos_params = read_pos_params(&self.wl_storage)
+            .expect("Must be able to read the PoS parameters");
+        let current_epoch = self.wl_storage.storage.block.epoch;
+        let current_height = self.wl_storage.storage.block.height;
+        let outdated = is_evidence_outdated(
+            &self.wl_storage,
+            &pos_params,
+            evidence_epoch,
+            evidence_height,
+            current_epoch,
+            current_height,
+        )
+        .expect("Must be able to read the cubic slashing window length");
+        if outdated {
+            tracing::debug!(
+                %evidence_epoch,
+                %current_epoch,
+                "The submitted evidence is outdated"
+            );
+            return Err(VoteExtensionError::OutdatedEvidence);
+        }
+        let already_slashed = is_slash_enqueued(
+            &self.wl_storage,
+            &pos_params,
+            evidence.validator(),
+            evidence_epoch,
+            evidence_height,
+            SlashType::InvalidVoteExtension,
+        )
+        .expect("Must be able to read the enqueued slashes");
+        if already_slashed {
+            return Err(VoteExtensionError::EvidenceAlreadyProcessed);
+        }
+
+        Ok((evidence_epoch, evidence_height))
+    }
+
+    /// Validate two validator set update vote extensions, signed
+    /// by the same validator over different validator sets.
+    fn validate_conflicting_valset_upd_vexts(
+        &self,
+        first: &validator_set_update::SignedVext,
+        second: &validator_set_update::SignedVext,
+    ) -> std::result::Result<(Epoch, BlockHeight), VoteExtensionError> {
+        let signing_epoch = first.data.signing_epoch;
+        if signing_epoch > self.wl_storage.storage.get_current_epoch().0 {
+            return Err(VoteExtensionError::UnexpectedEpoch);
+        }
+        let height = self
+            .wl_storage
+            .pos_queries()
+            .get_height(signing_epoch)
+            .ok_or(VoteExtensionError::UnexpectedEpoch)?;
+
+        let validator = &first.data.validator_addr;
+        let pk = self
+            .wl_storage
+            .pos_queries()
+            .read_validator_eth_hot_key(validator, Some(signing_epoch))
+            .ok_or(VoteExtensionError::PubKeyNotInStorage)?;
+        for ext in [first, second] {
+            ext.verify(&pk).map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    ?ext.sig,
+                    ?pk,
+                    %validator,
+                    "Failed to verify the signature of a conflicting valset \
+                     upd vote extension"
+                );
+                VoteExtensionError::VerifySigFailed
+            })?;
+        }
+
+        Ok((signing_epoch, height))
+    }
+
+    /// Validate a Bridge pool root vote extension, whose Ethereum
+    /// signature is over the given `root` and `nonce`.
+    fn validate_invalid_bp_root(
+        &self,
+        vext: &bridge_pool_roots::SignedVext,
+        root: KeccakHash,
+        nonce: Uint,
+        message: KeccakHash,
+    ) -> std::result::Result<(Epoch, BlockHeight), VoteExtensionError> {
+        let height = vext.data.block_height;
+        if height.0 == 0
+            || height > self.wl_storage.storage.get_last_block_height()
+        {
+            return Err(VoteExtensionError::UnexpectedBlockHeight);
+        }
+        let epoch = self
+            .get_epoch(height)
+            .ok_or(VoteExtensionError::UnexpectedEpoch)?;
+        if !self
+            .wl_storage
+            .ethbridge_queries()
+            .is_bridge_active_at(epoch)
+        {
+            return Err(VoteExtensionError::EthereumBridgeInactive);
+        }
+
+        // the protocol key signature binds the Ethereum
+        // signature to the block height of the vote extension
+        let validator = &vext.data.validator_addr;
+        let (_, pk) = self
+            .wl_storage
+            .pos_queries()
+            .get_validator_from_address(validator, Some(epoch))
+            .map_err(|_| VoteExtensionError::PubKeyNotInStorage)?;
+        vext.verify(&pk).map_err(|err| {
+            tracing::debug!(
+                ?err,
+                ?vext.sig,
+                ?pk,
+                %validator,
+                "Failed to verify the signature of a Bridge pool root's vote \
+                 extension submitted as evidence"
+            );
+            VoteExtensionError::VerifySigFailed
+        })?;
+        let hot_key = self
+            .wl_storage
+            .pos_queries()
+            .read_validator_eth_hot_key(validator, Some(epoch))
+            .ok_or(VoteExtensionError::PubKeyNotInStorage)?;
+        let signed = Signed::<_, SignableEthMessage>::new_from(
+            message,
+            vext.data.sig.clone(),
+        );
+        signed
+            .verify(&hot_key)
+            .map_err(|_| VoteExtensionError::InvalidBPRootSig)?;
+
+        let committed_root = self
+            .wl_storage
+            .ethbridge_queries()
+            .get_bridge_pool_root_at_height(height)
+            .ok_or(VoteExtensionError::UnexpectedBlockHeight)?;
+        let committed_nonce = self
+            .wl_storage
+            .ethbridge_queries()
+            .get_bridge_pool_nonce_at_height(height);
+        if root == committed_root && nonce == committed_nonce {
+            return Err(VoteExtensionError::NotMisbehavior);
+        }
+
+        Ok((epoch, height))
+    }
+
+    /// Validate some borsh encoded [`MisbehaviorEvidence`] submitted by
+    /// an external watchtower, and broadcast it to the network in a
+    /// protocol tx.
+    ///
+    /// Only validator nodes are able to sign protocol txs, therefore
+    /// evidence submitted to full nodes is rejected, and so is evidence
+    /// submitted to nodes that did not opt into accepting it.
+    pub fn submit_misbehavior_evidence(
+        &self,
+        data: &[u8],
+    ) -> storage_api::Result<()> {
+        if !self.accept_misbehavior_evidence {
+            return Err(storage_api::Error::new_const(
+                "This node does not accept misbehavior evidence",
+            ));
+        }
+        let evidence =
+            MisbehaviorEvidence::try_from_slice(data).into_storage_result()?;
+        self.validate_misbehavior_evidence(&evidence)
+            .into_storage_result()?;
+        let Some(protocol_key) = self.mode.get_protocol_key() else {
+            return Err(storage_api::Error::new_const(
+                "Only validator nodes accept misbehavior evidence",
+            ));
+        };
+        tracing::info!(
+            validator = %evidence.validator(),
+            "Broadcasting misbehavior evidence submitted by a watchtower"
+        );
+        let tx = EthereumTxData::MisbehaviorEvidence(evidence)
+            .sign(protocol_key, self.chain_id.clone())
+            .to_bytes();
+        self.mode.broadcast(tx);
+        Ok(())
+    }
+
+    /// Slash the validators whose misbehavior was included on chain,
+    /// in the current block.
+    pub fn slash_misbehaving_validators(
+        &mut self,
+        evidence: impl IntoIterator<Item = MisbehaviorEvidence>,
+    ) -> storage_api::Result<()> {
+        let pos_params = read_pos_params(&self.wl_storage)?;
+        let current_epoch = self.wl_storage.storage.block.epoch;
+
+        for evidence in evidence {
+            // NB: the evidence is validated again, in case
+            // the same infraction was included more than once
+            let (evidence_epoch, evidence_height) =
+                match self.validate_misbehavior_evidence(&evidence) {
+                    Ok(infraction) => infraction,
+                    Err(err) => {
+                        tracing::warn!(
+                            %err,
+                            "Dropping invalid misbehavior evidence"
+                        );
+                        continue;
+                    }
+                };
+            let validator = evidence.validator();
+            let validator_set_update_epoch =
+                self.get_validator_set_update_epoch(current_epoch);
+            tracing::info!(
+                "Slashing {} for {} in epoch {}, block height {} (current \
+                 epoch = {}, validator set update epoch = \
+                 {validator_set_update_epoch})",
+                validator,
+                SlashType::InvalidVoteExtension,
+                evidence_epoch,
+                evidence_height,
+                current_epoch
+            );
+            slash(
+                &mut self.wl_storage,
+                &pos_params,
+                current_epoch,
+                evidence_epoch,
+                evidence_height,
+                SlashType::InvalidVoteExtension,
+                validator,
+                validator_set_update_epoch,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_misbehavior {
+    use namada::types::ethereum_events::EthAddress;
+    use namada::types::token;
+    use namada::types::vote_extensions::validator_set_update::{
+        EthAddrBook, VotingPowersMap,
+    };
+    use namada_sdk::eth_bridge::EthBridgeQueries;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils;
+
+    /// Sign a validator set update vote extension over the given
+    /// voting powers, with the Ethereum hot key of the test validator.
+    fn sign_valset_upd(
+        shell: &test_utils::TestShell,
+        voting_powers: VotingPowersMap,
+    ) -> validator_set_update::SignedVext {
+        validator_set_update::Vext {
+            voting_powers,
+            validator_addr: shell
+                .mode
+                .get_validator_address()
+                .expect("Test failed")
+                .clone(),
+            signing_epoch: shell.wl_storage.storage.get_current_epoch().0,
+        }
+        .sign(shell.mode.get_eth_bridge_keypair().expect("Test failed"))
+    }
+
+    /// Test that validator set update vote extensions signed over
+    /// different validator sets are slashed, and only once.
+    #[test]
+    fn test_slash_conflicting_valset_upd_vexts() {
+        let (mut shell, _recv, _, _) = test_utils::setup();
+        let next_epoch = shell.wl_storage.storage.get_current_epoch().0.next();
+        let voting_powers: VotingPowersMap = shell
+            .wl_storage
+            .ethbridge_queries()
+            .get_consensus_eth_addresses(Some(next_epoch))
+            .iter()
+            .map(|(eth_addr_book, _, voting_power)| {
+                (eth_addr_book, voting_power)
+            })
+            .collect();
+        let mut forged_voting_powers = voting_powers.clone();
+        forged_voting_powers.insert(
+            EthAddrBook {
+                hot_key_addr: EthAddress([0xff; 20]),
+                cold_key_addr: EthAddress([0xff; 20]),
+            },
+            token::Amount::native_whole(1_000_000),
+        );
+
+        let evidence = MisbehaviorEvidence::ConflictingValSetUpdateVexts {
+            first: sign_valset_upd(&shell, voting_powers),
+            second: sign_valset_upd(&shell, forged_voting_powers),
+        };
+        assert!(shell.validate_misbehavior_evidence(&evidence).is_ok());
+
+        shell
+            .slash_misbehaving_validators([evidence.clone(), evidence.clone()])
+            .expect("Test failed");
+        assert!(matches!(
+            shell.validate_misbehavior_evidence(&evidence),
+            Err(VoteExtensionError::EvidenceAlreadyProcessed)
+        ));
+    }
+
+    /// Test that identical validator set update vote extensions
+    /// are not regarded as misbehavior.
+    #[test]
+    fn test_reject_non_conflicting_valset_upd_vexts() {
+        let (shell, _recv, _, _) = test_utils::setup();
+        let ext = sign_valset_upd(&shell, VotingPowersMap::new());
+        let evidence = MisbehaviorEvidence::ConflictingValSetUpdateVexts {
+            first: ext.clone(),
+            second: ext,
+        };
+        assert!(matches!(
+            shell.validate_misbehavior_evidence(&evidence),
+            Err(VoteExtensionError::NotMisbehavior)
+        ));
+    }
+
+    /// Sign a Bridge pool root vote extension at the last block height,
+    /// whose Ethereum signature is over the given root and nonce, and
+    /// return it as misbehavior evidence.
+    fn sign_bp_root(
+        shell: &test_utils::TestShell,
+        root: KeccakHash,
+        nonce: Uint,
+    ) -> MisbehaviorEvidence {
+        let message = keccak_hash([root.0, nonce.to_bytes()].concat());
+        let sig = Signed::<_, SignableEthMessage>::new(
+            shell.mode.get_eth_bridge_keypair().expect("Test failed"),
+            message,
+        )
+        .sig;
+        let vext = bridge_pool_roots::Vext {
+            block_height: shell.wl_storage.storage.get_last_block_height(),
+            validator_addr: shell
+                .mode
+                .get_validator_address()
+                .expect("Test failed")
+                .clone(),
+            sig,
+        }
+        .sign(shell.mode.get_protocol_key().expect("Test failed"));
+        MisbehaviorEvidence::InvalidBridgePoolRoot { vext, root, nonce }
+    }
+
+    /// Test that Bridge pool root vote extensions signed over a root
+    /// other than the committed one are slashed, and only once.
+    #[test]
+    fn test_slash_invalid_bp_root() {
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(1u64);
+        shell.wl_storage.storage.block.height =
+            shell.wl_storage.storage.get_last_block_height();
+        shell.commit();
+        let height = shell.wl_storage.storage.get_last_block_height();
+        let root = shell
+            .wl_storage
+            .ethbridge_queries()
+            .get_bridge_pool_root_at_height(height)
+            .expect("Test failed");
+        let nonce = shell
+            .wl_storage
+            .ethbridge_queries()
+            .get_bridge_pool_nonce_at_height(height);
+
+        // signing the committed root is not misbehavior
+        let evidence = sign_bp_root(&shell, root, nonce);
+        assert!(matches!(
+            shell.validate_misbehavior_evidence(&evidence),
+            Err(VoteExtensionError::NotMisbehavior)
+        ));
+
+        let evidence = sign_bp_root(&shell, KeccakHash([0xff; 32]), nonce);
+        assert!(shell.validate_misbehavior_evidence(&evidence).is_ok());
+
+        shell
+            .slash_misbehaving_validators([evidence.clone(), evidence.clone()])
+            .expect("Test failed");
+        assert!(matches!(
+            shell.validate_misbehavior_evidence(&evidence),
+            Err(VoteExtensionError::EvidenceAlreadyProcessed)
+        ));
+    }
+
+    /// Test that evidence is only accepted by the nodes that opted into
+    /// accepting it.
+    #[test]
+    fn test_submit_evidence_requires_opt_in() {
+        let (mut shell, _recv, _, _) = test_utils::setup_at_height(1u64);
+        shell.wl_storage.storage.block.height =
+            shell.wl_storage.storage.get_last_block_height();
+        shell.commit();
+        let evidence =
+            sign_bp_root(&shell, KeccakHash([0xff; 32]), Uint::from(0))
+                .serialize_to_vec();
+
+        assert!(shell.submit_misbehavior_evidence(&evidence).is_err());
+        shell.accept_misbehavior_evidence = true;
+        assert!(shell.submit_misbehavior_evidence(&evidence).is_ok());
+    }
+}
@@ -11,6 +11,7 @@ use namada::types::hash::Hash;
 use namada::types::key::tm_raw_hash_to_string;
 use namada::types::storage::{BlockHash, BlockHeight};
 use namada::types::transaction::hash_tx;
+use namada_sdk::queries::CrashReports;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::UnboundedSender;
 use tower::Service;
@@ -21,11 +22,12 @@ use crate::config;
 use crate::config::{Action, ActionAtHeight};
 use crate::facade::tendermint::v0_37::abci::response::DeliverTx;
 use crate::facade::tendermint::v0_37::abci::{
-    request, Request as Req, Response as Resp,
+    request, response, Request as Req, Response as Resp,
 };
 use crate::facade::tendermint_proto::v0_37::abci::ResponseDeliverTx;
 use crate::facade::tower_abci::BoxError;
 use crate::node::ledger::shell::{EthereumOracleChannels, Shell};
+use crate::node::ledger::startup::StartupProgress;
 
 /// The shim wraps the shell, which implements ABCI++.
 /// The shim makes a crude translation between the ABCI interface currently used
@@ -53,6 +55,8 @@ impl AbcippShim {
         db_cache: &rocksdb::Cache,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
+        crash_reports: CrashReports,
+        startup_progress: &StartupProgress,
     ) -> (Self, AbciService, broadcast::Sender<()>) {
         // We can use an unbounded channel here, because tower-abci limits the
         // the number of requests that can come in
@@ -62,7 +66,7 @@ impl AbcippShim {
         let action_at_height = config.shell.action_at_height.clone();
         (
             Self {
-                service: Shell::new(
+                service: Shell::new_with_startup_progress(
                     config,
                     wasm_dir,
                     broadcast_sender,
@@ -70,7 +74,9 @@ impl AbcippShim {
                     Some(db_cache),
                     vp_wasm_compilation_cache,
                     tx_wasm_compilation_cache,
-                ),
+                    startup_progress,
+                )
+                .with_crash_reports(crash_reports),
                 begin_block_request: None,
                 delivered_txs: vec![],
                 shell_recv,
@@ -220,6 +226,14 @@ pub struct AbciService {
 }
 
 impl AbciService {
+    /// Get a handle forwarding queries to the shell, independently of the
+    /// ABCI server.
+    pub fn query_handle(&self) -> QueryHandle {
+        QueryHandle {
+            shell_send: self.shell_send.clone(),
+        }
+    }
+
     /// Check if we are at a block height with a scheduled action.
     /// If so, perform the action.
     fn maybe_take_action(
@@ -368,3 +382,58 @@ impl Service<Req> for AbciService {
         }
     }
 }
+
+/// A handle forwarding queries to the [`AbcippShim`], used to serve them
+/// outside of the ABCI server.
+#[derive(Debug, Clone)]
+pub struct QueryHandle {
+    /// A channel for forwarding requests to the shell
+    shell_send: std::sync::mpsc::Sender<(
+        Req,
+        tokio::sync::oneshot::Sender<Result<Resp, BoxError>>,
+    )>,
+}
+
+impl Service<request::Query> for QueryHandle {
+    type Error = BoxError;
+    type Future = Pin<
+        Box<
+            dyn Future<Output = Result<response::Query, BoxError>>
+                + Send
+                + 'static,
+        >,
+    >;
+    type Response = response::Query;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // Nothing to check as the sender's channel is unbounded
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: request::Query) -> Self::Future {
+        let (resp_send, recv) = tokio::sync::oneshot::channel();
+        let result = self.shell_send.send((Req::Query(req), resp_send));
+
+        async move {
+            if let Err(err) = result {
+                // The shell has shut-down
+                return Err(err.into());
+            }
+            match recv.await {
+                Ok(Ok(Resp::Query(resp))) => Ok(resp),
+                Ok(Ok(_)) => Err(BoxError::from(
+                    "The shell responded to a query with another response",
+                )),
+                Ok(Err(err)) => Err(err),
+                Err(err) => {
+                    tracing::info!("ABCI response channel didn't respond");
+                    Err(err.into())
+                }
+            }
+        }
+        .boxed()
+    }
+}
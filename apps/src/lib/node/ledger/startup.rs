@@ -0,0 +1,242 @@
+//! Progress reporting during the startup of the ledger.
+//!
+//! Loading the last committed state of a node with a large DB, and warming
+//! up its caches, may take a long time. The [`StartupProgress`] of the
+//! ledger is logged periodically, and may optionally be served over HTTP
+//! with the [probes](super::probes) of the ledger, before the ABCI server is
+//! up, such that operators can tell a slow startup apart from a hung one.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use namada::ledger::storage::LoadStage;
+use serde::Serialize;
+
+/// The minimum interval between two consecutive logs of the progress
+/// made within the same startup stage.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The stages of the startup of the ledger, in the order they are
+/// reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupStage {
+    /// Opening the DB
+    OpeningDb,
+    /// Reading the last committed block from the DB
+    ReadingLastBlock,
+    /// Restoring the Merkle tree from the diffs in the DB
+    RebuildingMerkleTree,
+    /// Recovering from an interrupted block commit
+    RecoveringCommit,
+    /// Warming up the in-memory caches of the shell
+    WarmingUpCaches,
+    /// The shell is ready to serve ABCI requests
+    Ready,
+}
+
+impl fmt::Display for StartupStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self {
+            Self::OpeningDb => "opening the DB",
+            Self::ReadingLastBlock => "reading the last committed block",
+            Self::RebuildingMerkleTree => "rebuilding the Merkle tree",
+            Self::RecoveringCommit => "recovering an interrupted commit",
+            Self::WarmingUpCaches => "warming up the caches",
+            Self::Ready => "ready",
+        };
+        f.write_str(stage)
+    }
+}
+
+/// Snapshot of the [`StartupProgress`] of the ledger.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StartupStatus {
+    /// The current startup stage
+    pub stage: StartupStage,
+    /// The completion percentage of the current stage, if it is known
+    pub percentage: Option<f64>,
+    /// The estimated number of seconds left until the current stage
+    /// is completed, if it is known
+    pub eta_secs: Option<u64>,
+    /// The number of seconds elapsed since the startup began
+    pub elapsed_secs: u64,
+}
+
+/// Progress of the startup of the ledger, shared between the shell and
+/// the endpoint serving it.
+#[derive(Debug, Clone)]
+pub struct StartupProgress {
+    inner: Arc<Mutex<Progress>>,
+}
+
+#[derive(Debug)]
+struct Progress {
+    started: Instant,
+    stage: StartupStage,
+    stage_started: Instant,
+    /// Units of work completed within the current stage, out of a total
+    completed: Option<(u64, u64)>,
+    last_logged: Instant,
+}
+
+impl Default for StartupProgress {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            inner: Arc::new(Mutex::new(Progress {
+                started: now,
+                stage: StartupStage::OpeningDb,
+                stage_started: now,
+                completed: None,
+                last_logged: now,
+            })),
+        }
+    }
+}
+
+impl StartupProgress {
+    /// Move on to the given startup stage.
+    pub fn enter_stage(&self, stage: StartupStage) {
+        let mut progress = self.inner.lock().unwrap();
+        if progress.stage == stage {
+            return;
+        }
+        let now = Instant::now();
+        tracing::info!(
+            previous_stage = %progress.stage,
+            took = ?now.duration_since(progress.stage_started),
+            "Startup stage: {stage}"
+        );
+        if stage == StartupStage::Ready {
+            tracing::info!(
+                took = ?now.duration_since(progress.started),
+                "The ledger has finished starting up"
+            );
+        }
+        progress.stage = stage;
+        progress.stage_started = now;
+        progress.completed = None;
+        progress.last_logged = now;
+    }
+
+    /// Record that `completed` units of work out of `total` are done
+    /// within the current startup stage.
+    pub fn report(&self, completed: u64, total: u64) {
+        let mut progress = self.inner.lock().unwrap();
+        progress.completed = Some((completed, total));
+        let now = Instant::now();
+        if now.duration_since(progress.last_logged) < LOG_INTERVAL {
+            return;
+        }
+        progress.last_logged = now;
+        let status = progress.status(now);
+        tracing::info!(
+            stage = %status.stage,
+            completed,
+            total,
+            eta_secs = ?status.eta_secs,
+            "Startup progress: {:.1}%",
+            status.percentage.unwrap_or_default()
+        );
+    }
+
+    /// Record the given stage of loading the last committed state.
+    pub fn report_load_stage(&self, stage: LoadStage) {
+        match stage {
+            LoadStage::ReadingLastBlock => {
+                self.enter_stage(StartupStage::ReadingLastBlock)
+            }
+            LoadStage::RebuildingMerkleTree { restored, total } => {
+                self.enter_stage(StartupStage::RebuildingMerkleTree);
+                self.report(restored, total);
+            }
+            LoadStage::RecoveringCommit => {
+                self.enter_stage(StartupStage::RecoveringCommit)
+            }
+        }
+    }
+
+    /// Return a snapshot of the startup progress.
+    pub fn status(&self) -> StartupStatus {
+        self.inner.lock().unwrap().status(Instant::now())
+    }
+}
+
+impl Progress {
+    fn status(&self, now: Instant) -> StartupStatus {
+        let stage_elapsed = now.duration_since(self.stage_started);
+        let (percentage, eta_secs) = match self.completed {
+            Some((completed, total)) if total > 0 => {
+                let completed = completed.min(total);
+                let eta = (completed > 0).then(|| {
+                    stage_elapsed.as_secs_f64() * (total - completed) as f64
+                        / completed as f64
+                });
+                (
+                    Some(100.0 * completed as f64 / total as f64),
+                    eta.map(|eta| eta.ceil() as u64),
+                )
+            }
+            _ => (None, None),
+        };
+        StartupStatus {
+            stage: self.stage,
+            percentage,
+            eta_secs,
+            elapsed_secs: now.duration_since(self.started).as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_startup {
+    use super::*;
+
+    /// Test that the completion percentage and ETA of a startup stage
+    /// are derived from the work done so far.
+    #[test]
+    fn test_startup_status() {
+        let now = Instant::now();
+        let mut progress = Progress {
+            started: now,
+            stage: StartupStage::RebuildingMerkleTree,
+            stage_started: now,
+            completed: None,
+            last_logged: now,
+        };
+        let later = now + Duration::from_secs(10);
+        let status = progress.status(later);
+        assert_eq!(status.percentage, None);
+        assert_eq!(status.eta_secs, None);
+        assert_eq!(status.elapsed_secs, 10);
+
+        progress.completed = Some((0, 100));
+        assert_eq!(progress.status(later).percentage, Some(0.0));
+        assert_eq!(progress.status(later).eta_secs, None);
+
+        progress.completed = Some((25, 100));
+        let status = progress.status(later);
+        assert_eq!(status.percentage, Some(25.0));
+        assert_eq!(status.eta_secs, Some(30));
+    }
+
+    /// Test that moving on to a new stage resets the progress made.
+    #[test]
+    fn test_enter_stage() {
+        let progress = StartupProgress::default();
+        progress.report_load_stage(LoadStage::RebuildingMerkleTree {
+            restored: 1,
+            total: 2,
+        });
+        let status = progress.status();
+        assert_eq!(status.stage, StartupStage::RebuildingMerkleTree);
+        assert_eq!(status.percentage, Some(50.0));
+
+        progress.enter_stage(StartupStage::WarmingUpCaches);
+        let status = progress.status();
+        assert_eq!(status.stage, StartupStage::WarmingUpCaches);
+        assert_eq!(status.percentage, None);
+    }
+}
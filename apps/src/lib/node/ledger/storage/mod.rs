@@ -4,6 +4,7 @@
 mod rocksdb;
 
 use std::fmt;
+use std::path::Path;
 
 use arse_merkle_tree::blake2b::Blake2bHasher;
 use arse_merkle_tree::traits::Hasher;
@@ -19,6 +20,14 @@ pub type PersistentDB = rocksdb::RocksDB;
 
 pub type PersistentStorage = Storage<PersistentDB, PersistentStorageHasher>;
 
+/// Open the DB at the given path, failing rather than panicking if it
+/// cannot be opened, e.g. because it is locked by a running node.
+pub fn try_open_db(
+    db_path: impl AsRef<Path>,
+) -> namada::ledger::storage::Result<PersistentDB> {
+    rocksdb::open(db_path, None)
+}
+
 impl Hasher for PersistentStorageHasher {
     fn write_bytes(&mut self, h: &[u8]) {
         self.0.write_bytes(h)
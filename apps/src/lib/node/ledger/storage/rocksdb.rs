@@ -29,6 +29,13 @@
 //!     - `tree`: merkle tree
 //!       - `root`: root hash
 //!       - `store`: the tree's store
+//!   - `e`: for each epoch `e` at which the subtrees were persisted:
+//!     - `tree`: merkle subtrees
+//!       - `root`: root hash
+//!       - `chunks`: the height at which the tree's store was written, the
+//!         number of its chunks and its hash, written once all its chunks are
+//!       - `chunk/{h}/{i}`: the `i`-th chunk of the tree's store written at
+//!         height `h`
 //!     - `hash`: block hash
 //!     - `time`: block time
 //!     - `epoch`: block epoch
@@ -44,7 +51,7 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Mutex;
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use data_encoding::HEXLOWER;
 use itertools::Either;
@@ -57,10 +64,11 @@ use namada::ledger::storage::merkle_tree::{
 };
 use namada::ledger::storage::types::PrefixIterator;
 use namada::ledger::storage::{
-    types, BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error,
-    MerkleTreeStoresRead, Result, StoreType, DB,
+    types, BlockStateRead, BlockStateWrite, CommitCheckpoint, DBIter,
+    DBWriteBatch, Error, MerkleTreeStoresRead, Result, StoreType, DB,
 };
 use namada::types::ethereum_events::Uint;
+use namada::types::hash::Hash;
 use namada::types::internal::TxQueue;
 use namada::types::storage::{
     BlockHeight, BlockResults, Epoch, EthEventsQueue, Header, Key, KeySeg,
@@ -70,8 +78,9 @@ use namada::types::time::DateTimeUtc;
 use rayon::prelude::*;
 use rocksdb::{
     BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, Direction,
-    FlushOptions, IteratorMode, Options, ReadOptions, WriteBatch,
+    FlushOptions, IteratorMode, Options, ReadOptions, WriteBatch, WriteOptions,
 };
+use sha2::{Digest, Sha256};
 
 use crate::config::utils::num_of_threads;
 
@@ -87,6 +96,11 @@ const DIFFS_CF: &str = "diffs";
 const STATE_CF: &str = "state";
 const BLOCK_CF: &str = "block";
 const REPLAY_PROTECTION_CF: &str = "replay_protection";
+const INDEXED_TXS_CF: &str = "indexed_txs";
+
+/// The maximum size of each of the chunks in which the stores of the merkle
+/// subtrees are persisted
+const MERKLE_STORE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
 
 /// RocksDB handle
 #[derive(Debug)]
@@ -189,6 +203,18 @@ pub fn open(
         replay_protection_cf_opts,
     ));
 
+    // for the index of applied txs (insert-intensive)
+    let mut indexed_txs_cf_opts = Options::default();
+    indexed_txs_cf_opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+    indexed_txs_cf_opts.set_compression_options(0, 0, 0, 1024 * 1024);
+    indexed_txs_cf_opts
+        .set_compaction_style(rocksdb::DBCompactionStyle::Universal);
+    indexed_txs_cf_opts.set_block_based_table_factory(&table_opts);
+    cfs.push(ColumnFamilyDescriptor::new(
+        INDEXED_TXS_CF,
+        indexed_txs_cf_opts,
+    ));
+
     rocksdb::DB::open_cf_descriptors(&db_opts, path, cfs)
         .map(RocksDB)
         .map_err(|e| Error::DBError(e.into_string()))
@@ -201,12 +227,66 @@ impl Drop for RocksDB {
 }
 
 impl RocksDB {
+    /// Create a checkpoint of the DB in the directory at `path`, which must
+    /// not exist yet. The checkpoint is a consistent copy of the DB, which
+    /// shares its immutable files with it when both are on the same
+    /// filesystem.
+    pub fn checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
+        rocksdb::checkpoint::Checkpoint::new(&self.0)
+            .and_then(|checkpoint| checkpoint.create_checkpoint(path))
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
     fn get_column_family(&self, cf_name: &str) -> Result<&ColumnFamily> {
         self.0
             .cf_handle(cf_name)
             .ok_or(Error::DBError("No {cf_name} column family".to_string()))
     }
 
+    /// Read the encoded store of a merkle tree persisted under the given key
+    /// prefix. The store is either written in chunks, or as a single value
+    /// for the base tree and in DBs written by older versions.
+    fn read_merkle_tree_store(
+        &self,
+        cf: &ColumnFamily,
+        key_prefix: &Key,
+    ) -> Result<Option<Vec<u8>>> {
+        let chunks_key = store_chunks_key(key_prefix);
+        let Some(bytes) = self
+            .0
+            .get_cf(cf, chunks_key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?
+        else {
+            let store_key = key_prefix.clone().with_segment("store".to_owned());
+            return self
+                .0
+                .get_cf(cf, store_key.to_string())
+                .map_err(|e| Error::DBError(e.into_string()));
+        };
+        let chunks: StoreChunks =
+            types::decode(bytes).map_err(Error::CodingError)?;
+        let mut store = vec![];
+        for index in 0..chunks.num_chunks {
+            let chunk_key = store_chunk_key(key_prefix, chunks.height, index);
+            let chunk = self
+                .0
+                .get_cf(cf, chunk_key.to_string())
+                .map_err(|e| Error::DBError(e.into_string()))?
+                .ok_or_else(|| {
+                    Error::DBError(format!(
+                        "Missing merkle tree store chunk {chunk_key}"
+                    ))
+                })?;
+            store.extend_from_slice(&chunk);
+        }
+        if Hash::sha256(&store) != chunks.hash {
+            return Err(Error::DBError(format!(
+                "The merkle tree store under {key_prefix} is corrupted"
+            )));
+        }
+        Ok(Some(store))
+    }
+
     /// Persist the diff of an account subspace key-val under the height where
     /// it was changed.
     fn write_subspace_diff(
@@ -770,6 +850,10 @@ impl DB for RocksDB {
                                 ),
                                 Some(&"store") => merkle_tree_stores
                                     .set_store(st.decode_store(bytes)?),
+                                Some(&"chunks") | Some(&"chunk") => {
+                                    // the chunked subtree stores are
+                                    // restored below
+                                }
                                 _ => unknown_key_error(path)?,
                             }
                         }
@@ -824,11 +908,8 @@ impl DB for RocksDB {
                         types::decode(bytes).map_err(Error::CodingError)?,
                     );
                 }
-                let store_key = key_prefix.with_segment("store".to_owned());
-                if let Some(bytes) = self
-                    .0
-                    .get_cf(block_cf, &store_key.to_string())
-                    .map_err(|e| Error::DBError(e.into_string()))?
+                if let Some(bytes) =
+                    self.read_merkle_tree_store(block_cf, &key_prefix)?
                 {
                     merkle_tree_stores.set_store(st.decode_store(bytes)?);
                 }
@@ -1007,12 +1088,63 @@ impl DB for RocksDB {
                         root_key.to_string(),
                         types::encode(merkle_tree_stores.root(st)),
                     );
-                    let store_key = key_prefix.with_segment("store".to_owned());
-                    batch.0.put_cf(
-                        block_cf,
-                        store_key.to_string(),
-                        merkle_tree_stores.store(st).encode(),
-                    );
+                    if *st == StoreType::Base {
+                        let store_key =
+                            key_prefix.with_segment("store".to_owned());
+                        batch.0.put_cf(
+                            block_cf,
+                            store_key.to_string(),
+                            merkle_tree_stores.store(st).encode(),
+                        );
+                    } else {
+                        // The subtree stores may be arbitrarily large, so
+                        // rather than encoding them whole into the batch,
+                        // they are written to the DB chunk by chunk, each in
+                        // its own batch. The chunks are written under keys
+                        // versioned by the height, so that the chunks of the
+                        // currently committed store are left untouched. The
+                        // record of the new chunks is written in the block's
+                        // batch, which atomically switches to the new store,
+                        // together with the deletion of the old chunks.
+                        let prev_chunks = self
+                            .0
+                            .get_cf(
+                                block_cf,
+                                store_chunks_key(&key_prefix).to_string(),
+                            )
+                            .map_err(|e| Error::DBError(e.into_string()))?
+                            .map(|bytes| {
+                                types::decode::<StoreChunks>(bytes)
+                                    .map_err(Error::CodingError)
+                            })
+                            .transpose()?;
+                        let mut writer = StoreChunksWriter::new(
+                            &self.0,
+                            block_cf,
+                            key_prefix.clone(),
+                            height,
+                        );
+                        merkle_tree_stores
+                            .store(st)
+                            .encode_to(&mut writer)
+                            .map_err(|e| Error::DBError(e.to_string()))?;
+                        let chunks = writer.finish()?;
+                        if let Some(prev_chunks) = prev_chunks {
+                            if prev_chunks.height != height {
+                                delete_store_chunks(
+                                    &mut batch.0,
+                                    block_cf,
+                                    &key_prefix,
+                                    &prev_chunks,
+                                );
+                            }
+                        }
+                        batch.0.put_cf(
+                            block_cf,
+                            store_chunks_key(&key_prefix).to_string(),
+                            types::encode(&chunks),
+                        );
+                    }
                 }
             }
         }
@@ -1141,11 +1273,7 @@ impl DB for RocksDB {
                 None => return Ok(None),
             }
 
-            let store_key = key_prefix.with_segment("store".to_owned());
-            let bytes = self
-                .0
-                .get_cf(block_cf, store_key.to_string())
-                .map_err(|e| Error::DBError(e.into_string()))?;
+            let bytes = self.read_merkle_tree_store(block_cf, &key_prefix)?;
             match bytes {
                 Some(b) => {
                     merkle_tree_stores.set_store(st.decode_store(b)?);
@@ -1156,10 +1284,7 @@ impl DB for RocksDB {
         Ok(Some(merkle_tree_stores))
     }
 
-    fn has_replay_protection_entry(
-        &self,
-        hash: &namada::types::hash::Hash,
-    ) -> Result<bool> {
+    fn has_replay_protection_entry(&self, hash: &Hash) -> Result<bool> {
         let replay_protection_cf =
             self.get_column_family(REPLAY_PROTECTION_CF)?;
 
@@ -1355,6 +1480,73 @@ impl DB for RocksDB {
         self.exec_batch(batch.0)
     }
 
+    fn write_commit_checkpoint(
+        &mut self,
+        checkpoint: &CommitCheckpoint,
+    ) -> Result<()> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        // The checkpoint must hit the disk before the batch is applied
+        let mut write_opts = WriteOptions::default();
+        write_opts.set_sync(true);
+        self.0
+            .put_cf_opt(
+                state_cf,
+                "commit_checkpoint",
+                types::encode(checkpoint),
+                &write_opts,
+            )
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    fn read_commit_checkpoint(&self) -> Result<Option<CommitCheckpoint>> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        match self
+            .0
+            .get_cf(state_cf, "commit_checkpoint")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => {
+                Ok(Some(types::decode(bytes).map_err(Error::CodingError)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn delete_commit_checkpoint(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        batch.0.delete_cf(state_cf, "commit_checkpoint");
+        Ok(())
+    }
+
+    fn batch_write_commit_checksum(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        checksum: &Hash,
+    ) -> Result<()> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        batch
+            .0
+            .put_cf(state_cf, "commit_checksum", types::encode(checksum));
+        Ok(())
+    }
+
+    fn read_commit_checksum(&self) -> Result<Option<Hash>> {
+        let state_cf = self.get_column_family(STATE_CF)?;
+        match self
+            .0
+            .get_cf(state_cf, "commit_checksum")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => {
+                Ok(Some(types::decode(bytes).map_err(Error::CodingError)?))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn batch_write_subspace_val(
         &self,
         batch: &mut Self::WriteBatch,
@@ -1444,8 +1636,19 @@ impl DB for RocksDB {
         let key_prefix = subtree_key_prefix(store_type, epoch);
         let root_key = key_prefix.clone().with_segment("root".to_owned());
         batch.0.delete_cf(block_cf, root_key.to_string());
-        let store_key = key_prefix.with_segment("store".to_owned());
+        let store_key = key_prefix.clone().with_segment("store".to_owned());
         batch.0.delete_cf(block_cf, store_key.to_string());
+        let chunks_key = store_chunks_key(&key_prefix);
+        if let Some(bytes) = self
+            .0
+            .get_cf(block_cf, chunks_key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            let chunks: StoreChunks =
+                types::decode(bytes).map_err(Error::CodingError)?;
+            delete_store_chunks(&mut batch.0, block_cf, &key_prefix, &chunks);
+            batch.0.delete_cf(block_cf, chunks_key.to_string());
+        }
         Ok(())
     }
 
@@ -1493,6 +1696,24 @@ impl DB for RocksDB {
 
         Ok(())
     }
+
+    fn read_indexed_tx(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let indexed_txs_cf = self.get_column_family(INDEXED_TXS_CF)?;
+        self.0
+            .get_cf(indexed_txs_cf, hash.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    fn batch_write_indexed_tx(
+        &self,
+        batch: &mut Self::WriteBatch,
+        hash: &Hash,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        let indexed_txs_cf = self.get_column_family(INDEXED_TXS_CF)?;
+        batch.0.put_cf(indexed_txs_cf, hash.to_string(), value);
+        Ok(())
+    }
 }
 
 impl<'iter> DBIter<'iter> for RocksDB {
@@ -1568,6 +1789,127 @@ fn iter_subspace_prefix<'iter>(
     )
 }
 
+/// The record of the chunks of a merkle subtree store, written once all the
+/// chunks of the store are
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+struct StoreChunks {
+    /// The height at which the store was written, which versions the keys of
+    /// its chunks
+    height: BlockHeight,
+    /// The number of chunks of the store
+    num_chunks: u64,
+    /// The hash of the whole encoded store
+    hash: Hash,
+}
+
+/// Writer of the encoded store of a merkle subtree, which writes it to the DB
+/// in chunks of at most [`MERKLE_STORE_CHUNK_SIZE`] bytes, each in its own
+/// batch. At most a single chunk of a store is thus held in memory,
+/// regardless of the size of the store. The chunks are written under keys
+/// versioned by the given height, so they never overwrite the chunks of a
+/// store written at another height.
+struct StoreChunksWriter<'a> {
+    db: &'a rocksdb::DB,
+    cf: &'a ColumnFamily,
+    key_prefix: Key,
+    height: BlockHeight,
+    buffer: Vec<u8>,
+    hasher: Sha256,
+    num_chunks: u64,
+}
+
+impl<'a> StoreChunksWriter<'a> {
+    fn new(
+        db: &'a rocksdb::DB,
+        cf: &'a ColumnFamily,
+        key_prefix: Key,
+        height: BlockHeight,
+    ) -> Self {
+        Self {
+            db,
+            cf,
+            key_prefix,
+            height,
+            buffer: Vec::new(),
+            hasher: Sha256::new(),
+            num_chunks: 0,
+        }
+    }
+
+    /// Write the buffered chunk to the DB.
+    fn write_chunk(&mut self) -> Result<()> {
+        let chunk_key =
+            store_chunk_key(&self.key_prefix, self.height, self.num_chunks);
+        let mut batch = WriteBatch::default();
+        batch.put_cf(self.cf, chunk_key.to_string(), &self.buffer);
+        self.db
+            .write(batch)
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        self.hasher.update(&self.buffer);
+        self.buffer.clear();
+        self.num_chunks += 1;
+        Ok(())
+    }
+
+    /// Write the last chunk, and return the record of the written chunks.
+    fn finish(mut self) -> Result<StoreChunks> {
+        if !self.buffer.is_empty() {
+            self.write_chunk()?;
+        }
+        let digest = self.hasher.finalize();
+        Ok(StoreChunks {
+            height: self.height,
+            num_chunks: self.num_chunks,
+            hash: Hash(*digest.as_ref()),
+        })
+    }
+}
+
+impl Write for StoreChunksWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(MERKLE_STORE_CHUNK_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..len]);
+        if self.buffer.len() == MERKLE_STORE_CHUNK_SIZE {
+            self.write_chunk().map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::Other, e.to_string())
+            })?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Get the key of the record of the chunks of a merkle subtree store
+fn store_chunks_key(key_prefix: &Key) -> Key {
+    key_prefix.clone().with_segment("chunks".to_owned())
+}
+
+/// Get the key of the chunk at the given index of a merkle subtree store
+/// written at the given height
+fn store_chunk_key(key_prefix: &Key, height: BlockHeight, index: u64) -> Key {
+    key_prefix
+        .clone()
+        .with_segment("chunk".to_owned())
+        .with_segment(height.to_string())
+        .with_segment(index.to_string())
+}
+
+/// Delete the chunks of a merkle subtree store in the given batch
+fn delete_store_chunks(
+    batch: &mut WriteBatch,
+    cf: &ColumnFamily,
+    key_prefix: &Key,
+    chunks: &StoreChunks,
+) {
+    for index in 0..chunks.num_chunks {
+        let chunk_key = store_chunk_key(key_prefix, chunks.height, index);
+        batch.delete_cf(cf, chunk_key.to_string());
+    }
+}
+
 fn iter_diffs_prefix<'a>(
     db: &'a RocksDB,
     height: BlockHeight,
@@ -1654,7 +1996,11 @@ fn make_iter_read_opts(prefix: Option<String>) -> ReadOptions {
     read_opts
 }
 
-impl DBWriteBatch for RocksDBWriteBatch {}
+impl DBWriteBatch for RocksDBWriteBatch {
+    fn checksum(&self) -> Hash {
+        Hash::sha256(self.0.data())
+    }
+}
 
 fn unknown_key_error(key: &str) -> Result<()> {
     Err(Error::UnknownKey {
@@ -1766,6 +2112,91 @@ mod test {
             .expect("Block should have been written");
     }
 
+    /// Test that merkle subtree stores are written in bounded chunks, and
+    /// can be read back once the record of their chunks is written in the
+    /// batch of the block. Writing a new store doesn't affect the committed
+    /// one until the batch is executed.
+    #[test]
+    fn test_merkle_tree_store_chunks() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let key_prefix = subtree_key_prefix(&StoreType::Account, Epoch(1));
+
+        let store: Vec<u8> = (0..(MERKLE_STORE_CHUNK_SIZE * 5 / 2))
+            .map(|i| i as u8)
+            .collect();
+        let mut batch = RocksDB::batch();
+        {
+            let block_cf = db.get_column_family(BLOCK_CF).unwrap();
+            let mut writer = StoreChunksWriter::new(
+                &db.0,
+                block_cf,
+                key_prefix.clone(),
+                BlockHeight(1),
+            );
+            writer.write_all(&store).unwrap();
+            let chunks = writer.finish().unwrap();
+            assert_eq!(chunks.num_chunks, 3);
+            assert_eq!(chunks.hash, Hash::sha256(&store));
+            batch.0.put_cf(
+                block_cf,
+                store_chunks_key(&key_prefix).to_string(),
+                types::encode(&chunks),
+            );
+
+            // the store is not complete until the batch is executed
+            let read_store =
+                db.read_merkle_tree_store(block_cf, &key_prefix).unwrap();
+            assert_eq!(read_store, None);
+        }
+        db.exec_batch(batch.0).unwrap();
+
+        let block_cf = db.get_column_family(BLOCK_CF).unwrap();
+        let read_store = db
+            .read_merkle_tree_store(block_cf, &key_prefix)
+            .unwrap()
+            .expect("The store should have been written");
+        assert_eq!(read_store, store);
+
+        // the chunks of a new store written at another height, whose record
+        // is never committed, leave the committed store intact
+        let new_store = vec![1; MERKLE_STORE_CHUNK_SIZE * 2];
+        let mut writer = StoreChunksWriter::new(
+            &db.0,
+            block_cf,
+            key_prefix.clone(),
+            BlockHeight(2),
+        );
+        writer.write_all(&new_store).unwrap();
+        let new_chunks = writer.finish().unwrap();
+        let read_store = db
+            .read_merkle_tree_store(block_cf, &key_prefix)
+            .unwrap()
+            .expect("The store should have been written");
+        assert_eq!(read_store, store);
+
+        // once the new record is committed, the new store is read
+        let mut batch = RocksDB::batch();
+        batch.0.put_cf(
+            block_cf,
+            store_chunks_key(&key_prefix).to_string(),
+            types::encode(&new_chunks),
+        );
+        db.exec_batch(batch.0).unwrap();
+        let block_cf = db.get_column_family(BLOCK_CF).unwrap();
+        let read_store = db
+            .read_merkle_tree_store(block_cf, &key_prefix)
+            .unwrap()
+            .expect("The store should have been written");
+        assert_eq!(read_store, new_store);
+
+        // a store whose chunks were corrupted is detected
+        let chunk_key = store_chunk_key(&key_prefix, BlockHeight(2), 1);
+        db.0.put_cf(block_cf, chunk_key.to_string(), [0; 8])
+            .unwrap();
+        assert!(db.read_merkle_tree_store(block_cf, &key_prefix).is_err());
+    }
+
     #[test]
     fn test_read() {
         let dir = tempdir().unwrap();
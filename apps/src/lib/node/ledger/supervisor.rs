@@ -0,0 +1,171 @@
+//! Panic isolation for the ledger's non-consensus subsystems.
+//!
+//! Auxiliary subsystems, such as the [`Broadcaster`] or the maintenance of
+//! the [`EventLog`], must not be able to bring down a validator node. Panics
+//! raised by these subsystems are caught here, and recorded as
+//! [`CrashReport`] instances, which can be read back via the health queries.
+//!
+//! [`Broadcaster`]: super::broadcaster::Broadcaster
+//! [`EventLog`]: namada::ledger::events::log::EventLog
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+
+use futures::FutureExt;
+use namada::types::control_flow::time::{
+    self, Duration, ExponentialBackoff, SleepStrategy,
+};
+use namada::types::time::DateTimeUtc;
+use namada_sdk::queries::{CrashReport, CrashReports};
+
+/// The maximum number of times a supervised task is restarted,
+/// before giving up on it.
+pub const MAX_RESTARTS: u64 = 16;
+
+/// The upper bound on the backoff between consecutive restarts of
+/// a supervised task.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Run `task` to completion, catching any panics it may raise. Crashes are
+/// recorded in `crash_reports`, and the task is restarted with an exponential
+/// backoff, until it either terminates normally or [`MAX_RESTARTS`] is
+/// reached.
+///
+/// The state of the task is kept in `state`, such that it outlives crashes.
+pub async fn supervise<S, F>(
+    subsystem: &'static str,
+    crash_reports: &CrashReports,
+    state: &mut S,
+    mut task: F,
+) where
+    F: for<'s> FnMut(
+        &'s mut S,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 's>>,
+{
+    let strategy = ExponentialBackoff {
+        base: 2,
+        as_duration: Duration::from_secs,
+    }
+    .map(|backoff| backoff.min(MAX_BACKOFF));
+    let mut backoff_state = Default::default();
+    let mut restarts = 0;
+
+    loop {
+        let result = AssertUnwindSafe(task(state)).catch_unwind().await;
+        let Err(payload) = result else {
+            return;
+        };
+        if restarts == MAX_RESTARTS {
+            report_crash(crash_reports, subsystem, payload, restarts);
+            tracing::error!(
+                subsystem,
+                "Supervised subsystem crashed too many times, giving up on it"
+            );
+            return;
+        }
+        restarts += 1;
+        report_crash(crash_reports, subsystem, payload, restarts);
+
+        let backoff = strategy.backoff(&backoff_state);
+        strategy.next_state(&mut backoff_state);
+        tracing::warn!(
+            subsystem,
+            restarts,
+            ?backoff,
+            "Restarting supervised subsystem"
+        );
+        time::sleep(backoff).await;
+    }
+}
+
+/// Run `task`, catching any panics it may raise. Crashes are recorded in
+/// `crash_reports`, in which case [`None`] is returned.
+pub fn isolate<T, F>(
+    subsystem: &'static str,
+    crash_reports: &CrashReports,
+    task: F,
+) -> Option<T>
+where
+    F: FnOnce() -> T,
+{
+    match panic::catch_unwind(AssertUnwindSafe(task)) {
+        Ok(output) => Some(output),
+        Err(payload) => {
+            report_crash(crash_reports, subsystem, payload, 0);
+            None
+        }
+    }
+}
+
+/// Record a new [`CrashReport`] from a panic payload.
+fn report_crash(
+    crash_reports: &CrashReports,
+    subsystem: &'static str,
+    payload: Box<dyn Any + Send>,
+    restarts: u64,
+) {
+    let message = if let Some(msg) = payload.downcast_ref::<&'static str>() {
+        (*msg).to_owned()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "<unknown panic payload>".to_owned()
+    };
+    tracing::error!(subsystem, %message, "Supervised subsystem crashed");
+    crash_reports.record(CrashReport {
+        subsystem: subsystem.to_owned(),
+        message,
+        time: DateTimeUtc::now(),
+        restarts,
+    });
+}
+
+#[cfg(test)]
+mod test_supervisor {
+    use super::*;
+
+    /// Test that panics in isolated tasks are recorded.
+    #[test]
+    fn test_isolate_records_crashes() {
+        let crash_reports = CrashReports::default();
+
+        let output = isolate("Test", &crash_reports, || 1);
+        assert_eq!(output, Some(1));
+        assert!(crash_reports.snapshot().is_empty());
+
+        let output: Option<()> =
+            isolate("Test", &crash_reports, || panic!("boom"));
+        assert_eq!(output, None);
+        let reports = crash_reports.snapshot();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].subsystem, "Test");
+        assert_eq!(reports[0].message, "boom");
+        assert_eq!(reports[0].restarts, 0);
+    }
+
+    /// Test that supervised tasks are restarted after crashing, keeping
+    /// their state.
+    #[tokio::test]
+    async fn test_supervised_tasks_restart() {
+        let crash_reports = CrashReports::default();
+        let mut attempts = 0u64;
+
+        supervise("Test", &crash_reports, &mut attempts, |attempts| {
+            Box::pin(async move {
+                *attempts += 1;
+                if *attempts < 2 {
+                    panic!("attempt {attempts} failed");
+                }
+            })
+        })
+        .await;
+
+        assert_eq!(attempts, 2);
+        let reports = crash_reports.snapshot();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].message, "attempt 1 failed");
+        assert_eq!(reports[0].restarts, 1);
+    }
+}
@@ -54,6 +54,8 @@ pub fn gen_validator_keys(
     ValidatorKeys {
         protocol_keypair,
         eth_bridge_keypair,
+        next_protocol_keypair: None,
+        next_eth_bridge_keypair: None,
     }
 }
 
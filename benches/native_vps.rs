@@ -128,6 +128,7 @@ fn governance(c: &mut Criterion) {
                         voting_start_epoch,
                         voting_end_epoch: voting_start_epoch + 3_u64,
                         grace_epoch: voting_start_epoch + 9_u64,
+                        expedited: false,
                     },
                     None,
                     Some(vec![content_section]),
@@ -181,6 +182,7 @@ fn governance(c: &mut Criterion) {
                         voting_start_epoch,
                         voting_end_epoch: voting_start_epoch + 3_u64,
                         grace_epoch: voting_start_epoch + 9_u64,
+                        expedited: false,
                     },
                     None,
                     Some(vec![content_section, wasm_code_section]),
@@ -385,6 +385,7 @@ fn update_account(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::albert_keypair().ref_to()],
         threshold: None,
+        allowed_tx_codes: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -469,6 +470,7 @@ fn init_proposal(c: &mut Criterion) {
                                     voting_start_epoch: 12.into(),
                                     voting_end_epoch: 15.into(),
                                     grace_epoch: 18.into(),
+                                    expedited: false,
                                 },
                                 None,
                                 Some(vec![content_section]),
@@ -521,6 +523,7 @@ fn init_proposal(c: &mut Criterion) {
                                     voting_start_epoch: 12.into(),
                                     voting_end_epoch: 15.into(),
                                     grace_epoch: 18.into(),
+                                    expedited: false,
                                 },
                                 None,
                                 Some(vec![content_section, wasm_code_section]),
@@ -625,6 +628,8 @@ fn become_validator(c: &mut Criterion) {
         description: None,
         website: None,
         discord_handle: None,
+        moniker: None,
+        security_contact: None,
     };
     let tx = shell.generate_tx(
         TX_BECOME_VALIDATOR_WASM,
@@ -720,6 +725,8 @@ fn change_validator_metadata(c: &mut Criterion) {
         description: Some("I will change this piece of data".to_string()),
         website: None,
         discord_handle: None,
+        moniker: None,
+        security_contact: None,
         commission_rate: None,
     };
 
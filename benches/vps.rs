@@ -86,6 +86,7 @@ fn vp_user(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::albert_keypair().to_public()],
         threshold: None,
+        allowed_tx_codes: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -373,6 +374,7 @@ fn vp_validator(c: &mut Criterion) {
         )),
         public_keys: vec![defaults::validator_account_keypair().to_public()],
         threshold: None,
+        allowed_tx_codes: None,
     };
     let vp = shell.generate_tx(
         TX_UPDATE_ACCOUNT_WASM,
@@ -1,5 +1,6 @@
 //! Tools for accessing the storage subspaces of the Ethereum
 //! bridge pool
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryInto;
 
@@ -32,6 +33,7 @@ pub const BRIDGE_POOL_ADDRESS: Address =
 struct Segments {
     signed_root: &'static str,
     bridge_pool_nonce: &'static str,
+    cancellations: &'static str,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -76,6 +78,45 @@ pub fn get_nonce_key() -> Key {
     }
 }
 
+/// Get the storage key prefix of the requests to cancel transfers
+pub fn get_cancellations_prefix() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(BRIDGE_POOL_ADDRESS),
+            DbKeySeg::StringSeg(Segments::VALUES.cancellations.into()),
+        ],
+    }
+}
+
+/// Get the storage key for the request to cancel the transfer with
+/// the given hash
+pub fn get_cancellation_key(hash: &KeccakHash) -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(BRIDGE_POOL_ADDRESS),
+            DbKeySeg::StringSeg(Segments::VALUES.cancellations.into()),
+            hash.to_db_key(),
+        ],
+    }
+}
+
+/// Check if a key is for a request to cancel a transfer, returning the
+/// hash of the transfer
+pub fn is_cancellation_key(key: &Key) -> Option<KeccakHash> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(segment),
+            DbKeySeg::StringSeg(hash),
+        ] if addr == &BRIDGE_POOL_ADDRESS
+            && segment == Segments::VALUES.cancellations =>
+        {
+            KeccakHash::try_from(hash.as_str()).ok()
+        }
+        _ => None,
+    }
+}
+
 /// Check if a key belongs to the bridge pools sub-storage
 pub fn is_bridge_pool_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &BRIDGE_POOL_ADDRESS)
@@ -105,6 +146,12 @@ pub struct BridgePoolTree {
     root: KeccakHash,
     /// The underlying storage, containing hashes of [`PendingTransfer`]s.
     leaves: BTreeMap<KeccakHash, BlockHeight>,
+    /// The nodes of the tree, layer by layer from the leaves up to the
+    /// root. They are computed upon the first update of the tree, and
+    /// kept up to date afterwards, such that updates only rehash the
+    /// nodes right of the changed leaf, and proofs don't rehash the tree.
+    #[borsh(skip)]
+    layers: Vec<Vec<KeccakHash>>,
 }
 
 impl BridgePoolTree {
@@ -116,6 +163,7 @@ impl BridgePoolTree {
         Self {
             root,
             leaves: store,
+            layers: vec![],
         }
     }
 
@@ -148,41 +196,74 @@ impl BridgePoolTree {
         value: BlockHeight,
     ) -> Result<Hash, Error> {
         let hash = Self::parse_key(key)?;
-        _ = self.leaves.insert(hash, value);
-        self.root = self.compute_root();
+        let has_layers = self.has_layers();
+        if self.leaves.insert(hash.clone(), value).is_none() {
+            if has_layers {
+                let index = self.layers[0]
+                    .binary_search(&hash)
+                    .unwrap_or_else(|index| index);
+                self.layers[0].insert(index, hash);
+                rehash_layers(&mut self.layers, index);
+            } else {
+                self.layers = self.compute_layers();
+            }
+            self.update_root();
+        }
         Ok(self.root().into())
     }
 
     /// Delete a key from storage and update the root
     pub fn delete_key(&mut self, key: &Key) -> Result<(), Error> {
         let hash = Self::parse_key(key)?;
-        _ = self.leaves.remove(&hash);
-        self.root = self.compute_root();
+        let has_layers = self.has_layers();
+        if self.leaves.remove(&hash).is_some() {
+            if has_layers {
+                if let Ok(index) = self.layers[0].binary_search(&hash) {
+                    self.layers[0].remove(index);
+                    rehash_layers(&mut self.layers, index);
+                }
+            } else {
+                self.layers = self.compute_layers();
+            }
+            self.update_root();
+        }
         Ok(())
     }
 
-    /// Compute the root of the merkle tree
-    fn compute_root(&self) -> KeccakHash {
-        let mut hashes: Vec<KeccakHash> = self.leaves.keys().cloned().collect();
-        let mut prefix = POOL_ROOT_PREFIX_LEAF;
-        while hashes.len() > 1 {
-            let mut next_hashes = vec![];
-            for pair in hashes.chunks(2) {
-                let left = pair[0].clone();
-                let right = pair.get(1).cloned().unwrap_or_default();
-                next_hashes.push(hash_pair(left, right, prefix));
-            }
-            hashes = next_hashes;
-            prefix = POOL_ROOT_PREFIX_NON_LEAF;
-        }
+    /// Check if the cached layers of the tree match its leaves
+    fn has_layers(&self) -> bool {
+        matches!(
+            self.layers.first(),
+            Some(leaves) if leaves.len() == self.leaves.len()
+        )
+    }
 
-        if hashes.is_empty() {
-            Default::default()
+    /// Compute all the layers of the tree from its leaves
+    fn compute_layers(&self) -> Vec<Vec<KeccakHash>> {
+        let mut layers = vec![self.leaves.keys().cloned().collect()];
+        rehash_layers(&mut layers, 0);
+        layers
+    }
+
+    /// Get the layers of the tree, computing them if they are not cached
+    fn layers(&self) -> Cow<'_, [Vec<KeccakHash>]> {
+        if self.has_layers() {
+            Cow::Borrowed(&self.layers)
         } else {
-            hashes.remove(0)
+            Cow::Owned(self.compute_layers())
         }
     }
 
+    /// Set the root of the tree from its cached layers
+    fn update_root(&mut self) {
+        self.root = self
+            .layers
+            .last()
+            .and_then(|layer| layer.first())
+            .cloned()
+            .unwrap_or_default();
+    }
+
     /// Return the root as a [`struct@Hash`] type.
     pub fn root(&self) -> KeccakHash {
         self.root.clone()
@@ -212,65 +293,30 @@ impl BridgePoolTree {
         }
         let mut proof_hashes = vec![];
         let mut flags = vec![];
-        let mut hashes: Vec<_> = self
-            .leaves
-            .keys()
-            .cloned()
-            .map(|hash| {
-                if leaves.contains(&hash) {
-                    Node::OnPath(hash)
-                } else {
-                    Node::OffPath(hash)
-                }
-            })
+        let layers = self.layers();
+        // the positions of the nodes on a path from the root of the tree
+        // to one of the leaves being included in the proof
+        let mut on_path: BTreeSet<usize> = leaves
+            .iter()
+            .filter_map(|hash| layers[0].binary_search(hash).ok())
             .collect();
 
-        let mut prefix = POOL_ROOT_PREFIX_LEAF;
-
-        while hashes.len() > 1 {
-            let mut next_hashes = vec![];
-
-            for pair in hashes.chunks(2) {
-                let left = pair[0].clone();
-                let right = pair.get(1).cloned().unwrap_or_default();
-                match (left, right) {
-                    (Node::OnPath(left), Node::OnPath(right)) => {
-                        flags.push(true);
-                        next_hashes.push(Node::OnPath(hash_pair(
-                            left.clone(),
-                            right,
-                            prefix,
-                        )));
-                    }
-                    (Node::OnPath(hash), Node::OffPath(sib)) => {
-                        flags.push(false);
-                        proof_hashes.push(sib.clone());
-                        next_hashes.push(Node::OnPath(hash_pair(
-                            hash.clone(),
-                            sib,
-                            prefix,
-                        )));
-                    }
-                    (Node::OffPath(sib), Node::OnPath(hash)) => {
-                        flags.push(false);
-                        proof_hashes.push(sib.clone());
-                        next_hashes.push(Node::OnPath(hash_pair(
-                            hash,
-                            sib.clone(),
-                            prefix,
-                        )));
-                    }
-                    (Node::OffPath(left), Node::OffPath(right)) => {
-                        next_hashes.push(Node::OffPath(hash_pair(
-                            left.clone(),
-                            right,
-                            prefix,
-                        )));
-                    }
+        for nodes in layers.iter().take(layers.len() - 1) {
+            let mut next_on_path = BTreeSet::new();
+            let mut positions = on_path.iter().copied().peekable();
+            while let Some(position) = positions.next() {
+                let sibling = position ^ 1;
+                if position % 2 == 0 && positions.peek() == Some(&sibling) {
+                    _ = positions.next();
+                    flags.push(true);
+                } else {
+                    flags.push(false);
+                    proof_hashes
+                        .push(nodes.get(sibling).cloned().unwrap_or_default());
                 }
+                next_on_path.insert(position / 2);
             }
-            hashes = next_hashes;
-            prefix = POOL_ROOT_PREFIX_NON_LEAF;
+            on_path = next_on_path;
         }
         // add the root to the proof
         if flags.is_empty() && proof_hashes.is_empty() && leaves.is_empty() {
@@ -325,21 +371,29 @@ fn hash_pair(left: KeccakHash, right: KeccakHash, prefix: u8) -> KeccakHash {
     keccak_hash(buf)
 }
 
-/// Keeps track if a node is on a path from the
-/// root of the merkle tree to one of the leaves
-/// being included in a multi-proof.
-#[derive(Debug, Clone)]
-enum Node {
-    /// Node is on a path from root to leaf in proof
-    OnPath(KeccakHash),
-    /// Node is not on a path from root to leaf in proof
-    OffPath(KeccakHash),
-}
-
-impl Default for Node {
-    fn default() -> Self {
-        Self::OffPath(Default::default())
+/// Recompute the nodes of the upper layers of a tree, which are right of
+/// the parent of the node at `index` in its leaves layer.
+fn rehash_layers(layers: &mut Vec<Vec<KeccakHash>>, mut index: usize) {
+    let mut level = 0;
+    let mut prefix = POOL_ROOT_PREFIX_LEAF;
+    while layers[level].len() > 1 {
+        index /= 2;
+        if layers.len() == level + 1 {
+            layers.push(vec![]);
+        }
+        let (lower, upper) = layers.split_at_mut(level + 1);
+        let nodes = &lower[level];
+        let parents = &mut upper[0];
+        parents.truncate(index);
+        parents.extend((index..(nodes.len() + 1) / 2).map(|i| {
+            let left = nodes[2 * i].clone();
+            let right = nodes.get(2 * i + 1).cloned().unwrap_or_default();
+            hash_pair(left, right, prefix)
+        }));
+        level += 1;
+        prefix = POOL_ROOT_PREFIX_NON_LEAF;
     }
+    layers.truncate(level + 1);
 }
 
 /// A multi-leaf membership proof
@@ -1010,5 +1064,43 @@ mod test_bridge_pool_tree {
             let proof = tree.get_membership_proof(to_prove).expect("Test failed");
             assert!(proof.verify(tree.root()));
         }
+
+        /// Given a random tree and a subset of leaves to delete from it,
+        /// check that the incrementally updated layers of the tree match
+        /// the layers rebuilt from scratch, and so do the proofs built
+        /// from them.
+        #[test]
+        fn test_incremental_updates((transfers, to_delete) in arb_transfers_and_subset()) {
+            let mut tree = BridgePoolTree::default();
+            for transfer in &transfers {
+                let key = Key::from(transfer);
+                let _ = tree.insert_key(&key, BlockHeight(1)).expect("Test failed");
+            }
+            for transfer in &to_delete {
+                let key = Key::from(transfer);
+                tree.delete_key(&key).expect("Test failed");
+            }
+
+            let rebuilt = BridgePoolTree::new(tree.root(), tree.store().clone());
+            assert!(!rebuilt.has_layers());
+            let layers = rebuilt.compute_layers();
+            assert_eq!(tree.layers, layers);
+            let root = layers.last().and_then(|layer| layer.first()).cloned();
+            assert_eq!(tree.root(), root.unwrap_or_default());
+
+            let to_prove: Vec<_> = transfers
+                .into_iter()
+                .filter(|transfer| !to_delete.contains(transfer))
+                .collect();
+            let proof = tree
+                .get_membership_proof(to_prove.clone())
+                .expect("Test failed");
+            let rebuilt_proof = rebuilt
+                .get_membership_proof(to_prove)
+                .expect("Test failed");
+            assert_eq!(proof.proof, rebuilt_proof.proof);
+            assert_eq!(proof.flags, rebuilt_proof.flags);
+            assert!(proof.verify(tree.root()));
+        }
     }
 }
@@ -1,5 +1,7 @@
 //! Functionality for accessing the storage subspace
 pub mod bridge_pool;
+pub mod nft;
+pub mod relayer_rewards;
 pub mod whitelist;
 pub mod wrapped_erc20s;
 
@@ -15,6 +17,16 @@ pub fn prefix() -> Key {
     Key::from(ADDRESS.to_db_key())
 }
 
+/// Storage key for the Bridge and Governance validator sets that the new
+/// bridge contract of a scheduled rotation is initialized with. These are
+/// snapshotted when the proposal that scheduled the rotation entered its
+/// voting period, and removed once the rotation has been applied.
+pub fn bridge_contract_rotation_valset_key() -> Key {
+    prefix()
+        .push(&"bridge_contract_rotation_valset".to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Key for storing the initial Ethereum block height when
 /// events will first be extracted from.
 pub fn eth_start_height_key() -> Key {
@@ -53,6 +65,49 @@ pub fn min_confirmations_key() -> Key {
     get_min_confirmations_key_at_addr(PARAM_ADDRESS)
 }
 
+/// Storage key for the reward paid to relayers out of the PGF treasury,
+/// for each transfer they relay to Ethereum. This parameter is optional,
+/// and may be set by governance.
+pub fn relay_reward_key() -> Key {
+    get_relay_reward_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for the number of epochs validators must have been in the
+/// consensus set for, before their Ethereum events and Bridge pool root
+/// vote extensions count toward quorums. This parameter is optional, and
+/// may be set by governance.
+pub fn vext_min_consensus_epochs_key() -> Key {
+    get_vext_min_consensus_epochs_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for the maximum number of Ethereum events validators include
+/// in a single vote extension. This parameter is optional, and may be set
+/// by governance to a value of at least 1.
+pub fn max_vext_eth_events_key() -> Key {
+    get_max_vext_eth_events_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for the number of epochs a transfer must have sat unrelayed
+/// in the Bridge pool for, before its sender may cancel it. This parameter
+/// is optional, and transfers may not be cancelled unless governance sets
+/// it.
+pub fn bridge_pool_cancellation_delay_key() -> Key {
+    get_bridge_pool_cancellation_delay_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for the reorg safety depth parameter of the oracle. This
+/// parameter is optional, and may be set by governance.
+pub fn reorg_safety_depth_key() -> Key {
+    get_reorg_safety_depth_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for the paused status of the Ethereum oracle. This
+/// parameter is optional, and may be set by governance to halt the
+/// bridge without restarting the nodes.
+pub fn oracle_paused_key() -> Key {
+    get_oracle_paused_key_at_addr(PARAM_ADDRESS)
+}
+
 /// Storage key for the Ethereum address of wNam.
 pub fn native_erc20_key() -> Key {
     get_native_erc20_key_at_addr(PARAM_ADDRESS)
@@ -63,6 +118,20 @@ pub fn bridge_contract_key() -> Key {
     get_bridge_contract_address_key_at_addr(PARAM_ADDRESS)
 }
 
+/// Storage key for the Ethereum address of the NFT bridge contract. This
+/// parameter is optional, and only present if ERC-721 tokens may cross
+/// the bridge.
+pub fn nft_bridge_contract_key() -> Key {
+    get_nft_bridge_contract_address_key_at_addr(PARAM_ADDRESS)
+}
+
+/// Storage key for a rotation of the bridge contract to a new address,
+/// taking effect from some Ethereum block. This parameter is optional, and
+/// may be set by governance when the bridge contract is upgraded.
+pub fn bridge_contract_rotation_key() -> Key {
+    get_bridge_contract_rotation_key_at_addr(PARAM_ADDRESS)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,62 @@
+//! Storage keys of the ERC-721 tokens bridged from Ethereum.
+//!
+//! These storage keys should only ever be written to by the protocol,
+//! upon acting on confirmed Ethereum events.
+//!
+//! NFTs only cross the bridge from Ethereum to Namada. There is no return
+//! path: the bridge pool only relays fungible transfers, so a bridged NFT
+//! stays locked in the NFT bridge contract, and its owner on Namada cannot
+//! be changed by any tx.
+
+use super::prefix as ethbridge_key_prefix;
+use crate::types::ethereum_events::{EthAddress, Uint};
+use crate::types::storage::Key;
+
+/// The name of the main storage segment.
+const MAIN_SEGMENT: &str = "nft";
+
+/// Sub-key of the nonce of the next NFT transfers to Namada event.
+const NONCE_SEGMENT: &str = "nonce";
+
+/// Sub-key of the owners of the bridged ERC-721 tokens.
+const OWNERS_SEGMENT: &str = "owners";
+
+/// Sub-key of the NFT transfers to Namada events confirmed ahead of the
+/// events preceding them.
+const PENDING_SEGMENT: &str = "pending";
+
+/// Return the NFT storage key sub-space prefix.
+fn nft_prefix() -> Key {
+    ethbridge_key_prefix()
+        .push(&MAIN_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+}
+
+/// Storage key of the nonce of the next NFT transfers to Namada event.
+pub fn nonce_key() -> Key {
+    nft_prefix()
+        .push(&NONCE_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+}
+
+/// Storage key of the owner on Namada of the ERC-721 token with the given
+/// id, issued by the given contract.
+pub fn owner_key(contract: &EthAddress, token_id: &Uint) -> Key {
+    nft_prefix()
+        .push(&OWNERS_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+        .push(contract)
+        .expect("Should be able to push a storage key segment")
+        .push(&token_id.to_string())
+        .expect("Should be able to push a storage key segment")
+}
+
+/// Storage key of the transfers of the NFT transfers to Namada event with
+/// the given nonce, pending until the events preceding it are acted on.
+pub fn pending_transfers_key(nonce: &Uint) -> Key {
+    nft_prefix()
+        .push(&PENDING_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+        .push(&nonce.to_string())
+        .expect("Should be able to push a storage key segment")
+}
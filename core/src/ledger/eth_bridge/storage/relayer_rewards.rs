@@ -0,0 +1,23 @@
+//! Storage keys of the relaying statistics of the relayers of transfers
+//! to Ethereum.
+//!
+//! These storage keys should only ever be written to by the protocol,
+//! upon acting on confirmed Ethereum events.
+
+use super::prefix as ethbridge_key_prefix;
+use crate::types::address::Address;
+use crate::types::storage::Key;
+
+/// The name of the main storage segment.
+const MAIN_SEGMENT: &str = "relayer_rewards";
+
+/// Storage key of the [`RelayerStats`] of the given relayer.
+///
+/// [`RelayerStats`]: crate::types::eth_bridge_pool::RelayerStats
+pub fn stats_key(relayer: &Address) -> Key {
+    ethbridge_key_prefix()
+        .push(&MAIN_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+        .push(relayer)
+        .expect("Should be able to push a storage key segment")
+}
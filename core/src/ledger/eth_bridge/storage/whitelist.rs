@@ -62,9 +62,7 @@ pub struct Key {
 
 /// Return the whitelist storage key sub-space prefix.
 fn whitelist_prefix(asset: &EthAddress) -> storage::Key {
-    ethbridge_key_prefix()
-        .push(&segments::MAIN_SEGMENT.to_owned())
-        .expect("Should be able to push a storage key segment")
+    prefix()
         .push(&asset.to_canonical())
         .expect("Should be able to push a storage key segment")
 }
@@ -97,6 +95,28 @@ impl From<&Key> for storage::Key {
     }
 }
 
+/// Return the storage key prefix of the whole ERC20 whitelist.
+pub fn prefix() -> storage::Key {
+    ethbridge_key_prefix()
+        .push(&segments::MAIN_SEGMENT.to_owned())
+        .expect("Should be able to push a storage key segment")
+}
+
+/// Check if some [`storage::Key`] is an Ethereum bridge whitelist key
+/// of type [`KeyType::Whitelisted`], returning the ERC20 asset it refers to.
+pub fn is_whitelisted_key(key: &storage::Key) -> Option<EthAddress> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(s1), DbKeySeg::StringSeg(s2), DbKeySeg::StringSeg(s3), DbKeySeg::StringSeg(s4)]
+            if s1 == &BRIDGE_ADDRESS
+                && s2 == segments::MAIN_SEGMENT
+                && s4 == segments::VALUES.whitelisted =>
+        {
+            EthAddress::from_str(s3).ok()
+        }
+        _ => None,
+    }
+}
+
 /// Check if some [`storage::Key`] is an Ethereum bridge whitelist key
 /// of type [`KeyType::Cap`] or [`KeyType::Whitelisted`].
 pub fn is_cap_or_whitelisted_key(key: &storage::Key) -> bool {
@@ -163,4 +183,26 @@ mod tests {
         };
         assert!(!is_cap_or_whitelisted_key(&unexpected_key));
     }
+
+    /// Test that the asset of a "whitelisted" key can be recovered.
+    #[test]
+    fn test_is_whitelisted_key() {
+        let whitelisted_key: storage::Key = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Whitelisted,
+        }
+        .into();
+        assert!(whitelisted_key.split_prefix(&prefix()).is_some());
+        assert_eq!(
+            is_whitelisted_key(&whitelisted_key),
+            Some(DAI_ERC20_ETH_ADDRESS)
+        );
+
+        let cap_key: storage::Key = Key {
+            asset: DAI_ERC20_ETH_ADDRESS,
+            suffix: KeyType::Cap,
+        }
+        .into();
+        assert_eq!(is_whitelisted_key(&cap_key), None);
+    }
 }
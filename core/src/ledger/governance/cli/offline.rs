@@ -251,6 +251,11 @@ impl OfflineVote {
         self.vote.is_abstain()
     }
 
+    /// Check if the vote is a veto
+    pub fn is_veto(&self) -> bool {
+        self.vote.is_veto()
+    }
+
     /// Check if two votes are equal
     pub fn is_same_side(&self, other: &Self) -> bool {
         self.vote.is_same_side(&other.vote)
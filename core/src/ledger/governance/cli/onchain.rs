@@ -4,12 +4,16 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 use super::validation::{
-    is_valid_author_balance, is_valid_content, is_valid_default_proposal_data,
-    is_valid_end_epoch, is_valid_grace_epoch, is_valid_pgf_funding_data,
-    is_valid_pgf_stewards_data, is_valid_proposal_period, is_valid_start_epoch,
-    ProposalValidation,
+    is_valid_author_balance, is_valid_code_allowlist_data,
+    is_valid_consensus_params_data, is_valid_content,
+    is_valid_default_proposal_data, is_valid_parameter_change_data,
+    is_valid_pgf_funding_data, is_valid_pgf_stewards_data,
+    is_valid_proposal_epochs, is_valid_treasury_spend_data, ProposalValidation,
 };
 use crate::ledger::governance::parameters::GovernanceParameters;
+use crate::ledger::governance::storage::proposal::{
+    ConsensusParamsUpdate, TreasurySpend,
+};
 use crate::ledger::storage_api::token;
 use crate::types::address::Address;
 use crate::types::storage::Epoch;
@@ -31,6 +35,39 @@ pub struct OnChainProposal {
     pub voting_end_epoch: Epoch,
     /// The epoch from which this changes are executed
     pub grace_epoch: Epoch,
+    /// Whether the proposal is voted on in the expedited track
+    #[serde(default)]
+    pub expedited: bool,
+}
+
+impl OnChainProposal {
+    /// Validate the voting period of a proposal
+    fn validate_voting_period(
+        &self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+    ) -> Result<(), ProposalValidation> {
+        is_valid_proposal_epochs(
+            self.voting_start_epoch,
+            self.voting_end_epoch,
+            self.grace_epoch,
+            self.expedited,
+            current_epoch,
+            governance_parameters,
+        )
+    }
+
+    /// The minimum funds locked by the proposal
+    fn min_proposal_fund(
+        &self,
+        governance_parameters: &GovernanceParameters,
+    ) -> token::Amount {
+        if self.expedited {
+            governance_parameters.min_expedited_proposal_fund
+        } else {
+            governance_parameters.min_proposal_fund
+        }
+    }
 }
 
 /// Pgf default proposal
@@ -56,32 +93,11 @@ impl DefaultProposal {
         if force {
             return Ok(self);
         }
-        is_valid_start_epoch(
-            self.proposal.voting_start_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
-        )?;
-        is_valid_end_epoch(
-            self.proposal.voting_start_epoch,
-            self.proposal.voting_end_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.max_proposal_period,
-        )?;
-        is_valid_grace_epoch(
-            self.proposal.grace_epoch,
-            self.proposal.voting_end_epoch,
-            governance_parameters.min_proposal_grace_epochs,
-        )?;
-        is_valid_proposal_period(
-            self.proposal.voting_start_epoch,
-            self.proposal.grace_epoch,
-            governance_parameters.max_proposal_period,
-        )?;
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
         is_valid_author_balance(
             balance,
-            governance_parameters.min_proposal_fund,
+            self.proposal.min_proposal_fund(governance_parameters),
         )?;
         is_valid_content(
             &self.proposal.content,
@@ -134,32 +150,11 @@ impl PgfStewardProposal {
         if force {
             return Ok(self);
         }
-        is_valid_start_epoch(
-            self.proposal.voting_start_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
-        )?;
-        is_valid_end_epoch(
-            self.proposal.voting_start_epoch,
-            self.proposal.voting_end_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.max_proposal_period,
-        )?;
-        is_valid_grace_epoch(
-            self.proposal.grace_epoch,
-            self.proposal.voting_end_epoch,
-            governance_parameters.min_proposal_grace_epochs,
-        )?;
-        is_valid_proposal_period(
-            self.proposal.voting_start_epoch,
-            self.proposal.grace_epoch,
-            governance_parameters.max_proposal_period,
-        )?;
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
         is_valid_author_balance(
             balance,
-            governance_parameters.min_proposal_fund,
+            self.proposal.min_proposal_fund(governance_parameters),
         )?;
         is_valid_content(
             &self.proposal.content,
@@ -201,40 +196,281 @@ impl PgfFundingProposal {
         if force {
             return Ok(self);
         }
-        is_valid_start_epoch(
-            self.proposal.voting_start_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
         )?;
-        is_valid_end_epoch(
-            self.proposal.voting_start_epoch,
-            self.proposal.voting_end_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.min_proposal_voting_period,
-            governance_parameters.max_proposal_period,
+        is_valid_pgf_funding_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for PgfFundingProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// Parameter change proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct ParameterChangeProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The parameter change proposal extra data
+    pub data: Vec<ParameterChangeData>,
+}
+
+impl ParameterChangeProposal {
+    /// Validate a parameter change proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        is_valid_author_balance(
+            balance,
+            self.proposal.min_proposal_fund(governance_parameters),
         )?;
-        is_valid_grace_epoch(
-            self.proposal.grace_epoch,
-            self.proposal.voting_end_epoch,
-            governance_parameters.min_proposal_grace_epochs,
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
         )?;
-        is_valid_proposal_period(
-            self.proposal.voting_start_epoch,
-            self.proposal.grace_epoch,
-            governance_parameters.max_proposal_period,
+        is_valid_parameter_change_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for ParameterChangeProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// A change of a protocol parameter
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct ParameterChangeData {
+    /// The storage key of the parameter
+    pub key: String,
+    /// The new value of the parameter, Borsh-encoded then hex-encoded
+    pub value: String,
+}
+
+/// Code allowlist update proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct CodeAllowlistProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The code allowlist update proposal extra data
+    pub data: CodeAllowlistData,
+}
+
+impl CodeAllowlistProposal {
+    /// Validate a code allowlist update proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        is_valid_author_balance(
+            balance,
+            self.proposal.min_proposal_fund(governance_parameters),
         )?;
         is_valid_content(
             &self.proposal.content,
             governance_parameters.max_proposal_content_size,
         )?;
-        is_valid_pgf_funding_data(&self.data)?;
+        is_valid_code_allowlist_data(&self.data)?;
 
         Ok(self)
     }
 }
 
-impl TryFrom<&[u8]> for PgfFundingProposal {
+impl TryFrom<&[u8]> for CodeAllowlistProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// The hex-encoded code hashes added to and removed from the tx and VP
+/// allowlists
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct CodeAllowlistData {
+    /// The tx code hashes to allow
+    pub add_tx: Vec<String>,
+    /// The tx code hashes to disallow
+    pub remove_tx: Vec<String>,
+    /// The VP code hashes to allow
+    pub add_vp: Vec<String>,
+    /// The VP code hashes to disallow
+    pub remove_vp: Vec<String>,
+}
+
+/// Consensus params proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct ConsensusParamsProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The consensus params proposal extra data
+    pub data: ConsensusParamsUpdate,
+}
+
+impl ConsensusParamsProposal {
+    /// Validate a consensus params proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        is_valid_author_balance(
+            balance,
+            self.proposal.min_proposal_fund(governance_parameters),
+        )?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+        is_valid_consensus_params_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for ConsensusParamsProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// Treasury spend proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct TreasurySpendProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+    /// The treasury spend proposal extra data
+    pub data: TreasurySpend,
+}
+
+impl TreasurySpendProposal {
+    /// Validate a treasury spend proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        is_valid_author_balance(
+            balance,
+            self.proposal.min_proposal_fund(governance_parameters),
+        )?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+        is_valid_treasury_spend_data(&self.data)?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for TreasurySpendProposal {
+    type Error = serde_json::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice(value)
+    }
+}
+
+/// Text proposal
+#[derive(
+    Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct TextProposal {
+    /// The proposal data
+    pub proposal: OnChainProposal,
+}
+
+impl TextProposal {
+    /// Validate a text proposal
+    pub fn validate(
+        self,
+        governance_parameters: &GovernanceParameters,
+        current_epoch: Epoch,
+        balance: token::Amount,
+        force: bool,
+    ) -> Result<Self, ProposalValidation> {
+        if force {
+            return Ok(self);
+        }
+        self.proposal
+            .validate_voting_period(governance_parameters, current_epoch)?;
+        // text proposals lock lower funds, unless they are expedited
+        let min_proposal_fund = if self.proposal.expedited {
+            governance_parameters.min_expedited_proposal_fund
+        } else {
+            governance_parameters.min_text_proposal_fund
+        };
+        is_valid_author_balance(balance, min_proposal_fund)?;
+        is_valid_content(
+            &self.proposal.content,
+            governance_parameters.max_proposal_content_size,
+        )?;
+
+        Ok(self)
+    }
+}
+
+impl TryFrom<&[u8]> for TextProposal {
     type Error = serde_json::Error;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
@@ -330,6 +566,8 @@ pub enum ProposalVote {
     Nay,
     /// Represent an abstain proposal vote
     Abstain,
+    /// Represent a nay proposal vote with a veto
+    Veto,
 }
 
 impl TryFrom<String> for ProposalVote {
@@ -340,6 +578,7 @@ impl TryFrom<String> for ProposalVote {
             "yay" => Ok(ProposalVote::Yay),
             "nay" => Ok(ProposalVote::Nay),
             "abstain" => Ok(ProposalVote::Abstain),
+            "veto" => Ok(ProposalVote::Veto),
             _ => Err("invalid vote".to_string()),
         }
     }
@@ -361,6 +600,11 @@ impl ProposalVote {
         matches!(self, ProposalVote::Abstain)
     }
 
+    /// Check if the vote type is veto
+    pub fn is_veto(&self) -> bool {
+        matches!(self, ProposalVote::Veto)
+    }
+
     /// Check if two votes are equal
     pub fn is_same_side(&self, other: &Self) -> bool {
         std::mem::discriminant(self) == std::mem::discriminant(other)
@@ -1,8 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use thiserror::Error;
 
-use super::onchain::{PgfFunding, StewardsUpdate};
+use super::onchain::{
+    CodeAllowlistData, ParameterChangeData, PgfFunding, StewardsUpdate,
+};
+use crate::ledger::governance::parameters::GovernanceParameters;
+use crate::ledger::governance::storage::proposal::{
+    CodeAllowlistUpdate, ConsensusParamsUpdate, ParameterChange, TreasurySpend,
+};
+use crate::ledger::parameters::storage::is_protocol_parameter_key;
 use crate::types::address::Address;
 use crate::types::storage::Epoch;
 use crate::types::token;
@@ -19,6 +26,12 @@ pub enum ProposalValidation {
          {1} and a multiple of {2}"
     )]
     InvalidStartEpoch(Epoch, Epoch, u64),
+    /// The epochs of an expedited proposal are invalid
+    #[error(
+        "Invalid expedited proposal epochs: the voting start, end and grace \
+         epochs must all be the current epoch {0}"
+    )]
+    InvalidExpeditedEpochs(Epoch),
     /// The proposal difference between start and end epoch is invalid
     #[error(
         "Invalid proposal end epoch: difference between proposal start and \
@@ -69,6 +82,24 @@ pub enum ProposalValidation {
     /// The pgf funding data is not valid
     #[error("invalid proposal extra data: cannot be empty.")]
     InvalidPgfFundingExtraData,
+    /// The parameter change data is not valid
+    #[error("Invalid parameter change proposal extra data: {0}")]
+    InvalidParameterChangeExtraData(String),
+    /// The code allowlist update data is not valid
+    #[error("Invalid code allowlist proposal extra data: {0}")]
+    InvalidCodeAllowlistExtraData(String),
+    /// The consensus params data is not valid
+    #[error(
+        "Invalid consensus params proposal extra data: it must change some \
+         parameters to values accepted by CometBFT"
+    )]
+    InvalidConsensusParamsExtraData,
+    /// The treasury spend data is not valid
+    #[error(
+        "Invalid treasury spend proposal extra data: it must transfer a \
+         non-zero amount to an address other than the treasury"
+    )]
+    InvalidTreasurySpendExtraData,
 }
 
 pub fn is_valid_author_balance(
@@ -91,8 +122,10 @@ pub fn is_valid_start_epoch(
     proposal_epoch_multiplier: u64,
 ) -> Result<(), ProposalValidation> {
     let start_epoch_greater_than_current = proposal_start_epoch > current_epoch;
-    let start_epoch_is_multipler =
-        proposal_start_epoch.0 % proposal_epoch_multiplier == 0;
+    let start_epoch_is_multipler = proposal_start_epoch
+        .0
+        .checked_rem(proposal_epoch_multiplier)
+        == Some(0);
 
     if start_epoch_greater_than_current && start_epoch_is_multipler {
         Ok(())
@@ -113,12 +146,15 @@ pub fn is_valid_end_epoch(
     min_proposal_voting_period: u64,
     max_proposal_period: u64,
 ) -> Result<(), ProposalValidation> {
-    let voting_period = proposal_end_epoch.0 - proposal_start_epoch.0;
+    let voting_period = proposal_end_epoch
+        .0
+        .checked_sub(proposal_start_epoch.0)
+        .unwrap_or_default();
     let end_epoch_is_multipler =
-        proposal_end_epoch % proposal_epoch_multiplier == 0;
+        proposal_end_epoch.0.checked_rem(proposal_epoch_multiplier) == Some(0);
     let is_valid_voting_period = voting_period > 0
         && voting_period >= min_proposal_voting_period
-        && min_proposal_voting_period <= max_proposal_period;
+        && voting_period <= max_proposal_period;
 
     if end_epoch_is_multipler && is_valid_voting_period {
         Ok(())
@@ -135,7 +171,10 @@ pub fn is_valid_grace_epoch(
     proposal_end_epoch: Epoch,
     min_proposal_grace_epoch: u64,
 ) -> Result<(), ProposalValidation> {
-    let grace_period = proposal_grace_epoch.0 - proposal_end_epoch.0;
+    let grace_period = proposal_grace_epoch
+        .0
+        .checked_sub(proposal_end_epoch.0)
+        .unwrap_or_default();
 
     if grace_period > 0 && grace_period >= min_proposal_grace_epoch {
         Ok(())
@@ -152,7 +191,10 @@ pub fn is_valid_proposal_period(
     proposal_grace_epoch: Epoch,
     max_proposal_period: u64,
 ) -> Result<(), ProposalValidation> {
-    let proposal_period = proposal_grace_epoch.0 - proposal_start_epoch.0;
+    let proposal_period = proposal_grace_epoch
+        .0
+        .checked_sub(proposal_start_epoch.0)
+        .unwrap_or_default();
 
     if proposal_period > 0 && proposal_period <= max_proposal_period {
         Ok(())
@@ -164,6 +206,66 @@ pub fn is_valid_proposal_period(
     }
 }
 
+pub fn is_valid_expedited_epochs(
+    proposal_start_epoch: Epoch,
+    proposal_end_epoch: Epoch,
+    proposal_grace_epoch: Epoch,
+    current_epoch: Epoch,
+) -> Result<(), ProposalValidation> {
+    if proposal_start_epoch == current_epoch
+        && proposal_end_epoch == current_epoch
+        && proposal_grace_epoch == current_epoch
+    {
+        Ok(())
+    } else {
+        Err(ProposalValidation::InvalidExpeditedEpochs(current_epoch))
+    }
+}
+
+/// Validate the voting start, end and grace epochs of a proposal submitted
+/// at `current_epoch`. The voting window of an expedited proposal is set in
+/// blocks from its submission, so its epochs must all be the current epoch.
+pub fn is_valid_proposal_epochs(
+    voting_start_epoch: Epoch,
+    voting_end_epoch: Epoch,
+    grace_epoch: Epoch,
+    expedited: bool,
+    current_epoch: Epoch,
+    governance_parameters: &GovernanceParameters,
+) -> Result<(), ProposalValidation> {
+    if expedited {
+        return is_valid_expedited_epochs(
+            voting_start_epoch,
+            voting_end_epoch,
+            grace_epoch,
+            current_epoch,
+        );
+    }
+    is_valid_start_epoch(
+        voting_start_epoch,
+        current_epoch,
+        governance_parameters.min_proposal_voting_period,
+    )?;
+    is_valid_end_epoch(
+        voting_start_epoch,
+        voting_end_epoch,
+        current_epoch,
+        governance_parameters.min_proposal_voting_period,
+        governance_parameters.min_proposal_voting_period,
+        governance_parameters.max_proposal_period,
+    )?;
+    is_valid_grace_epoch(
+        grace_epoch,
+        voting_end_epoch,
+        governance_parameters.min_proposal_grace_epochs,
+    )?;
+    is_valid_proposal_period(
+        voting_start_epoch,
+        grace_epoch,
+        governance_parameters.max_proposal_period,
+    )
+}
+
 pub fn is_valid_content(
     proposal_content: &BTreeMap<String, String>,
     max_content_length: u64,
@@ -250,3 +352,70 @@ pub fn is_valid_pgf_funding_data(
         Err(ProposalValidation::InvalidPgfFundingExtraData)
     }
 }
+
+pub fn is_valid_parameter_change_data(
+    data: &[ParameterChangeData],
+) -> Result<(), ProposalValidation> {
+    if data.is_empty() {
+        return Err(ProposalValidation::InvalidParameterChangeExtraData(
+            "cannot be empty".to_string(),
+        ));
+    }
+    let mut keys = BTreeSet::new();
+    for change in data {
+        let ParameterChange { key, .. } = ParameterChange::try_from(change)
+            .map_err(|e| {
+                ProposalValidation::InvalidParameterChangeExtraData(
+                    e.to_string(),
+                )
+            })?;
+        if !is_protocol_parameter_key(&key) {
+            return Err(ProposalValidation::InvalidParameterChangeExtraData(
+                format!("{key} is not a protocol parameter"),
+            ));
+        }
+        if !keys.insert(key.clone()) {
+            return Err(ProposalValidation::InvalidParameterChangeExtraData(
+                format!("{key} is changed more than once"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn is_valid_code_allowlist_data(
+    data: &CodeAllowlistData,
+) -> Result<(), ProposalValidation> {
+    let update = CodeAllowlistUpdate::try_from(data).map_err(|e| {
+        ProposalValidation::InvalidCodeAllowlistExtraData(e.to_string())
+    })?;
+    if update.is_valid() {
+        Ok(())
+    } else {
+        Err(ProposalValidation::InvalidCodeAllowlistExtraData(
+            "it must change at least one code hash and each code hash at most \
+             once per allowlist"
+                .to_string(),
+        ))
+    }
+}
+
+pub fn is_valid_consensus_params_data(
+    data: &ConsensusParamsUpdate,
+) -> Result<(), ProposalValidation> {
+    if data.is_well_formed() {
+        Ok(())
+    } else {
+        Err(ProposalValidation::InvalidConsensusParamsExtraData)
+    }
+}
+
+pub fn is_valid_treasury_spend_data(
+    data: &TreasurySpend,
+) -> Result<(), ProposalValidation> {
+    if data.is_valid() {
+        Ok(())
+    } else {
+        Err(ProposalValidation::InvalidTreasurySpendExtraData)
+    }
+}
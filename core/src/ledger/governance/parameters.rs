@@ -1,8 +1,13 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use thiserror::Error;
 
+use super::cli::is_valid_proposal_epochs;
 use super::storage::keys as goverance_storage;
 use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::dec::Dec;
+use crate::types::storage::Epoch;
 use crate::types::token;
+use crate::types::voting_power::FractionalVotingPower;
 
 #[derive(
     Clone,
@@ -29,6 +34,22 @@ pub struct GovernanceParameters {
     pub max_proposal_content_size: u64,
     /// Minimum epochs between end and grace epochs
     pub min_proposal_grace_epochs: u64,
+    /// Minimum epochs between a proposal passing and its execution
+    pub min_proposal_execution_delay: u64,
+    /// Minimum amount of locked funds for an expedited proposal
+    pub min_expedited_proposal_fund: token::Amount,
+    /// Expedited proposal voting period in blocks
+    pub expedited_proposal_voting_period: u64,
+    /// Maximum gas that the code of a passed proposal may consume
+    pub max_proposal_execution_gas: u64,
+    /// Minimum amount of locked funds for a text proposal
+    pub min_text_proposal_fund: token::Amount,
+    /// Share of the locked funds refunded to the author of a withdrawn
+    /// proposal
+    pub proposal_withdrawal_refund: Dec,
+    /// Share of the voting power that voted on a proposal above which veto
+    /// votes reject it and burn its funds
+    pub veto_threshold: FractionalVotingPower,
 }
 
 impl Default for GovernanceParameters {
@@ -40,11 +61,107 @@ impl Default for GovernanceParameters {
             max_proposal_period: 27,
             max_proposal_content_size: 10_000,
             min_proposal_grace_epochs: 6,
+            min_proposal_execution_delay: 0,
+            min_expedited_proposal_fund: token::Amount::native_whole(5_000),
+            expedited_proposal_voting_period: 600,
+            max_proposal_execution_gas: 50_000_000,
+            min_text_proposal_fund: token::Amount::native_whole(50),
+            proposal_withdrawal_refund: Dec::new(9, 1)
+                .expect("Dec creation failed"),
+            veto_threshold: FractionalVotingPower::ONE_THIRD,
         }
     }
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ValidationError {
+    #[error("Expedited proposal voting period must be at least 1 block")]
+    ExpeditedVotingPeriodZero,
+    #[error(
+        "Minimum expedited proposal fund must be greater than the minimum \
+         proposal fund. Got expedited: {0}, regular: {1}"
+    )]
+    ExpeditedFundTooLow(String, String),
+    #[error(
+        "Proposal withdrawal refund must be between 0 and 1. Got: {0}"
+    )]
+    WithdrawalRefundOutOfRange(Dec),
+}
+
+/// The voting start, end and grace epochs of a proposal
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct ProposalEpochs {
+    /// The epoch from which voting is allowed
+    pub voting_start_epoch: Epoch,
+    /// The epoch from which voting is stopped
+    pub voting_end_epoch: Epoch,
+    /// The epoch from which the proposal is executed
+    pub grace_epoch: Epoch,
+}
+
 impl GovernanceParameters {
+    /// Validate the parameters values. Returns an empty list if the values
+    /// are valid.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        if self.expedited_proposal_voting_period == 0 {
+            errors.push(ValidationError::ExpeditedVotingPeriodZero);
+        }
+        // the higher deposit is what keeps the expedited track from being
+        // used for any proposal
+        if self.min_expedited_proposal_fund <= self.min_proposal_fund {
+            errors.push(ValidationError::ExpeditedFundTooLow(
+                self.min_expedited_proposal_fund.to_string_native(),
+                self.min_proposal_fund.to_string_native(),
+            ));
+        }
+        if self.proposal_withdrawal_refund.is_negative()
+            || self.proposal_withdrawal_refund > Dec::one()
+        {
+            errors.push(ValidationError::WithdrawalRefundOutOfRange(
+                self.proposal_withdrawal_refund,
+            ));
+        }
+        errors
+    }
+
+    /// The earliest epochs of a proposal with the shortest voting period and
+    /// grace period accepted when it is submitted at `current_epoch`, or
+    /// `None` if the parameters don't allow any proposal.
+    pub fn earliest_proposal_epochs(
+        &self,
+        current_epoch: Epoch,
+    ) -> Option<ProposalEpochs> {
+        let voting_period = self.min_proposal_voting_period;
+        let voting_start_epoch = current_epoch
+            .0
+            .checked_div(voting_period)?
+            .checked_add(1)?
+            .checked_mul(voting_period)?;
+        let voting_end_epoch = voting_start_epoch.checked_add(voting_period)?;
+        let grace_epoch = voting_end_epoch
+            .checked_add(self.min_proposal_grace_epochs.max(1))?;
+        let epochs = ProposalEpochs {
+            voting_start_epoch: Epoch(voting_start_epoch),
+            voting_end_epoch: Epoch(voting_end_epoch),
+            grace_epoch: Epoch(grace_epoch),
+        };
+        is_valid_proposal_epochs(
+            epochs.voting_start_epoch,
+            epochs.voting_end_epoch,
+            epochs.grace_epoch,
+            false,
+            current_epoch,
+            self,
+        )
+        .ok()
+        .map(|()| epochs)
+    }
+
     /// Initialize governance parameters into storage
     pub fn init_storage<S>(&self, storage: &mut S) -> storage_api::Result<()>
     where
@@ -57,6 +174,13 @@ impl GovernanceParameters {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            min_proposal_execution_delay,
+            min_expedited_proposal_fund,
+            expedited_proposal_voting_period,
+            max_proposal_execution_gas,
+            min_text_proposal_fund,
+            proposal_withdrawal_refund,
+            veto_threshold,
         } = self;
 
         let min_proposal_fund_key =
@@ -88,7 +212,105 @@ impl GovernanceParameters {
         storage
             .write(&min_proposal_grace_epoch_key, min_proposal_grace_epochs)?;
 
+        let min_proposal_execution_delay_key =
+            goverance_storage::get_min_proposal_execution_delay_key();
+        storage.write(
+            &min_proposal_execution_delay_key,
+            min_proposal_execution_delay,
+        )?;
+
+        let min_expedited_proposal_fund_key =
+            goverance_storage::get_min_expedited_proposal_fund_key();
+        storage.write(
+            &min_expedited_proposal_fund_key,
+            min_expedited_proposal_fund,
+        )?;
+
+        let expedited_proposal_voting_period_key =
+            goverance_storage::get_expedited_proposal_voting_period_key();
+        storage.write(
+            &expedited_proposal_voting_period_key,
+            expedited_proposal_voting_period,
+        )?;
+
+        let max_proposal_execution_gas_key =
+            goverance_storage::get_max_proposal_execution_gas_key();
+        storage.write(
+            &max_proposal_execution_gas_key,
+            max_proposal_execution_gas,
+        )?;
+
+        let min_text_proposal_fund_key =
+            goverance_storage::get_min_text_proposal_fund_key();
+        storage.write(&min_text_proposal_fund_key, min_text_proposal_fund)?;
+
+        let proposal_withdrawal_refund_key =
+            goverance_storage::get_proposal_withdrawal_refund_key();
+        storage.write(
+            &proposal_withdrawal_refund_key,
+            proposal_withdrawal_refund,
+        )?;
+
+        let veto_threshold_key = goverance_storage::get_veto_threshold_key();
+        storage.write(&veto_threshold_key, veto_threshold)?;
+
         let counter_key = goverance_storage::get_counter_key();
         storage.write(&counter_key, u64::MIN)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test that the expedited proposals track must have a non-empty voting
+    /// period and a higher deposit than the regular one.
+    #[test]
+    fn test_validate_expedited_params() {
+        let params = GovernanceParameters::default();
+        assert!(params.validate().is_empty());
+
+        let params = GovernanceParameters {
+            expedited_proposal_voting_period: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            params.validate().as_slice(),
+            [ValidationError::ExpeditedVotingPeriodZero]
+        ));
+
+        let defaults = GovernanceParameters::default();
+        let params = GovernanceParameters {
+            min_expedited_proposal_fund: defaults.min_proposal_fund,
+            ..defaults
+        };
+        assert!(matches!(
+            params.validate().as_slice(),
+            [ValidationError::ExpeditedFundTooLow(_, _)]
+        ));
+    }
+
+    /// Test that the share of the funds refunded on the withdrawal of a
+    /// proposal must be between 0 and 1.
+    #[test]
+    fn test_validate_withdrawal_refund() {
+        for refund in [Dec::zero(), Dec::one()] {
+            let params = GovernanceParameters {
+                proposal_withdrawal_refund: refund,
+                ..Default::default()
+            };
+            assert!(params.validate().is_empty());
+        }
+
+        for refund in [-Dec::one(), Dec::two()] {
+            let params = GovernanceParameters {
+                proposal_withdrawal_refund: refund,
+                ..Default::default()
+            };
+            assert!(matches!(
+                params.validate().as_slice(),
+                [ValidationError::WithdrawalRefundOutOfRange(_)]
+            ));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use namada_macros::StorageKeys;
 
+use crate::ledger::governance::storage::proposal::ProposalType;
 use crate::ledger::governance::ADDRESS;
 use crate::types::address::Address;
 use crate::types::storage::{DbKeySeg, Key, KeySeg};
@@ -24,9 +25,24 @@ struct Keys {
     max_period: &'static str,
     max_content: &'static str,
     min_grace_epoch: &'static str,
+    min_execution_delay: &'static str,
+    execution_epoch: &'static str,
+    min_expedited_fund: &'static str,
+    expedited_voting_period: &'static str,
+    voting_end_height: &'static str,
+    expedited_ending_height: &'static str,
+    starting_epoch: &'static str,
+    max_execution_gas: &'static str,
+    min_text_fund: &'static str,
+    withdrawal_refund: &'static str,
+    veto_threshold: &'static str,
+    execution_failed: &'static str,
+    stake_snapshot: &'static str,
+    bridge_valset_snapshot: &'static str,
     counter: &'static str,
     pending: &'static str,
     result: &'static str,
+    consensus_params: &'static str,
 }
 
 /// Check if key is inside governance address space
@@ -270,6 +286,125 @@ pub fn is_min_grace_epoch_key(key: &Key) -> bool {
                     && min_grace_epoch_param == Keys::VALUES.min_grace_epoch)
 }
 
+/// Check if key is a min proposal execution delay key
+pub fn is_min_proposal_execution_delay_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(min_execution_delay_param),
+    ] if addr == &ADDRESS
+        && min_execution_delay_param == Keys::VALUES.min_execution_delay
+    )
+}
+
+/// Check if key is a min expedited proposal fund key
+pub fn is_min_expedited_proposal_fund_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(min_expedited_fund_param),
+    ] if addr == &ADDRESS
+        && min_expedited_fund_param == Keys::VALUES.min_expedited_fund
+    )
+}
+
+/// Check if key is an expedited proposal voting period key
+pub fn is_expedited_proposal_voting_period_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(expedited_voting_period_param),
+    ] if addr == &ADDRESS
+        && expedited_voting_period_param
+            == Keys::VALUES.expedited_voting_period
+    )
+}
+
+/// Check if key is a max proposal execution gas key
+pub fn is_max_proposal_execution_gas_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(max_execution_gas_param),
+    ] if addr == &ADDRESS
+        && max_execution_gas_param == Keys::VALUES.max_execution_gas
+    )
+}
+
+/// Check if key is a min text proposal fund key
+pub fn is_min_text_proposal_fund_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(min_text_fund_param),
+    ] if addr == &ADDRESS
+        && min_text_fund_param == Keys::VALUES.min_text_fund
+    )
+}
+
+/// Check if key is a proposal withdrawal refund key
+pub fn is_proposal_withdrawal_refund_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(withdrawal_refund_param),
+    ] if addr == &ADDRESS
+        && withdrawal_refund_param == Keys::VALUES.withdrawal_refund
+    )
+}
+
+/// Check if key is a veto threshold key
+pub fn is_veto_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(veto_threshold_param),
+    ] if addr == &ADDRESS
+        && veto_threshold_param == Keys::VALUES.veto_threshold
+    )
+}
+
+/// Check if key is the voting end height key of an expedited proposal
+pub fn is_voting_end_height_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(voting_end_height),
+        ] if addr == &ADDRESS
+            && prefix == Keys::VALUES.proposal
+            && voting_end_height == Keys::VALUES.voting_end_height =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Check if key is an expedited proposal key, indexing the proposal by the
+/// block height at which its voting window closes
+pub fn is_expedited_proposal_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(prefix),
+        DbKeySeg::StringSeg(height_prefix),
+        DbKeySeg::StringSeg(_height),
+        DbKeySeg::StringSeg(_id),
+    ] if addr == &ADDRESS
+        && prefix == Keys::VALUES.proposal
+        && height_prefix == Keys::VALUES.expedited_ending_height
+    )
+}
+
+/// Check if key is a starting proposal key, indexing the proposal by the
+/// epoch at which its voting period starts
+pub fn is_starting_proposal_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(prefix),
+        DbKeySeg::StringSeg(epoch_prefix),
+        DbKeySeg::StringSeg(_epoch),
+        DbKeySeg::StringSeg(_id),
+    ] if addr == &ADDRESS
+        && prefix == Keys::VALUES.proposal
+        && epoch_prefix == Keys::VALUES.starting_epoch
+    )
+}
+
 /// Check if key is parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
     is_min_proposal_fund_key(key)
@@ -278,6 +413,13 @@ pub fn is_parameter_key(key: &Key) -> bool {
         || is_min_proposal_voting_period_key(key)
         || is_max_proposal_period_key(key)
         || is_min_grace_epoch_key(key)
+        || is_min_proposal_execution_delay_key(key)
+        || is_min_expedited_proposal_fund_key(key)
+        || is_expedited_proposal_voting_period_key(key)
+        || is_max_proposal_execution_gas_key(key)
+        || is_min_text_proposal_fund_key(key)
+        || is_proposal_withdrawal_refund_key(key)
+        || is_veto_threshold_key(key)
 }
 
 /// Check if key is start epoch or end epoch key
@@ -334,6 +476,77 @@ pub fn get_min_proposal_grace_epoch_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get min proposal execution delay key
+pub fn get_min_proposal_execution_delay_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.min_execution_delay.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get min expedited proposal fund key
+pub fn get_min_expedited_proposal_fund_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.min_expedited_fund.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get expedited proposal voting period key
+pub fn get_expedited_proposal_voting_period_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.expedited_voting_period.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get max proposal execution gas key
+pub fn get_max_proposal_execution_gas_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.max_execution_gas.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the min text proposal fund key
+pub fn get_min_text_proposal_fund_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.min_text_fund.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the proposal withdrawal refund key
+pub fn get_proposal_withdrawal_refund_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.withdrawal_refund.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the veto threshold key
+pub fn get_veto_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.veto_threshold.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the minimum funds locked by a proposal, which depend on its
+/// type and on whether it is expedited
+pub fn get_min_proposal_fund_key_for(
+    proposal_type: &ProposalType,
+    expedited: bool,
+) -> Key {
+    if expedited {
+        get_min_expedited_proposal_fund_key()
+    } else if proposal_type.is_text() {
+        get_min_text_proposal_fund_key()
+    } else {
+        get_min_proposal_fund_key()
+    }
+}
+
+/// Get key of the CometBFT consensus params of the chain
+pub fn get_consensus_params_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.consensus_params.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get key of proposal ids counter
 pub fn get_counter_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -404,6 +617,88 @@ pub fn get_grace_epoch_key(id: u64) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get the key of the epoch at which a passed proposal is scheduled to be
+/// executed
+pub fn get_proposal_execution_epoch_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.execution_epoch.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key marking a passed proposal whose code failed to execute
+pub fn get_proposal_execution_failed_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.execution_failed.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the voting power snapshot of a proposal
+pub fn get_proposal_stake_snapshot_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.stake_snapshot.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the snapshot of the Ethereum bridge validator sets of a
+/// proposal that changes the bridge
+pub fn get_proposal_bridge_valset_snapshot_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.bridge_valset_snapshot.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the block height at which the voting window of an
+/// expedited proposal closes
+pub fn get_voting_end_height_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&Keys::VALUES.voting_end_height.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the expedited proposals whose voting window closes at
+/// the given block height
+pub fn get_expedited_proposals_prefix(height: u64) -> Key {
+    proposal_prefix()
+        .push(&Keys::VALUES.expedited_ending_height.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&height.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the expedited proposal key
+pub fn get_expedited_proposals_key(id: u64, height: u64) -> Key {
+    get_expedited_proposals_prefix(height)
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the proposals whose voting period starts at the given
+/// epoch
+pub fn get_starting_proposals_prefix(epoch: u64) -> Key {
+    proposal_prefix()
+        .push(&Keys::VALUES.starting_epoch.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&epoch.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the starting proposal key
+pub fn get_starting_proposals_key(id: u64, epoch: u64) -> Key {
+    get_starting_proposals_prefix(epoch)
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get the proposal committing key prefix
 pub fn get_commiting_proposals_prefix(epoch: u64) -> Key {
     proposal_prefix()
@@ -501,6 +796,51 @@ pub fn get_commit_proposal_id(key: &Key) -> Option<u64> {
         None => None,
     }
 }
+
+/// Get the voting end height from an expedited proposal key
+pub fn get_expedited_proposal_height(key: &Key) -> Option<u64> {
+    if !is_expedited_proposal_key(key) {
+        return None;
+    }
+    match key.get_at(3) {
+        Some(DbKeySeg::StringSeg(res)) => res.parse::<u64>().ok(),
+        _ => None,
+    }
+}
+
+/// Get the proposal id from an expedited proposal key
+pub fn get_expedited_proposal_id(key: &Key) -> Option<u64> {
+    if !is_expedited_proposal_key(key) {
+        return None;
+    }
+    match key.get_at(4) {
+        Some(DbKeySeg::StringSeg(res)) => res.parse::<u64>().ok(),
+        _ => None,
+    }
+}
+
+/// Get the voting start epoch from a starting proposal key
+pub fn get_starting_proposal_epoch(key: &Key) -> Option<u64> {
+    if !is_starting_proposal_key(key) {
+        return None;
+    }
+    match key.get_at(3) {
+        Some(DbKeySeg::StringSeg(res)) => res.parse::<u64>().ok(),
+        _ => None,
+    }
+}
+
+/// Get the proposal id from a starting proposal key
+pub fn get_starting_proposal_id(key: &Key) -> Option<u64> {
+    if !is_starting_proposal_key(key) {
+        return None;
+    }
+    match key.get_at(4) {
+        Some(DbKeySeg::StringSeg(res)) => res.parse::<u64>().ok(),
+        _ => None,
+    }
+}
+
 /// Get the delegation address from vote key
 pub fn get_vote_delegation_address(key: &Key) -> Option<&Address> {
     match key.get_at(4) {
@@ -1,24 +1,34 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use data_encoding::HEXLOWER_PERMISSIVE;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::ledger::governance::cli::onchain::{
-    PgfAction, PgfContinous, PgfRetro, PgfSteward, StewardsUpdate,
+    CodeAllowlistData, ParameterChangeData, PgfAction, PgfContinous, PgfRetro,
+    PgfSteward, StewardsUpdate,
 };
 use crate::ledger::governance::utils::{ProposalStatus, TallyType};
+use crate::ledger::parameters::storage::is_eth_bridge_parameter_key;
+use crate::ledger::pgf::ADDRESS as PGF_ADDRESS;
 use crate::ledger::storage_api::token::Amount;
 use crate::types::address::Address;
 use crate::types::hash::Hash;
-use crate::types::storage::Epoch;
+use crate::types::storage::{BlockHeight, Epoch, Key};
+use crate::types::time::DurationSecs;
 
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum ProposalTypeError {
     #[error("Invalid proposal type.")]
     InvalidProposalType,
+    #[error("Invalid parameter change: {0}")]
+    InvalidParameterChange(String),
+    #[error("Invalid code allowlist update: {0}")]
+    InvalidCodeAllowlistUpdate(String),
 }
 
 /// Storage struture for pgf fundings
@@ -105,6 +115,212 @@ pub enum PGFAction {
     Retro(PGFTarget),
 }
 
+/// A change of the value of a protocol parameter, written when the proposal
+/// that carries it passes
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ParameterChange {
+    /// The storage key of the parameter
+    pub key: Key,
+    /// The new Borsh-encoded value of the parameter
+    pub value: Vec<u8>,
+}
+
+/// An update of the allowlists of tx and VP code hashes, applied when the
+/// proposal that carries it is executed
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct CodeAllowlistUpdate {
+    /// The changes to the allowed tx code hashes
+    pub tx: Vec<AddRemove<Hash>>,
+    /// The changes to the allowed VP code hashes
+    pub vp: Vec<AddRemove<Hash>>,
+}
+
+impl CodeAllowlistUpdate {
+    /// Check if the update doesn't change any allowlist
+    pub fn is_empty(&self) -> bool {
+        self.tx.is_empty() && self.vp.is_empty()
+    }
+
+    /// Return the number of code hashes added or removed by the update
+    pub fn len(&self) -> usize {
+        self.tx.len() + self.vp.len()
+    }
+
+    /// Check if the update changes some allowlist and changes each code hash
+    /// of an allowlist at most once
+    pub fn is_valid(&self) -> bool {
+        let changes_once = |changes: &[AddRemove<Hash>]| {
+            let mut hashes = HashSet::new();
+            changes.iter().all(|change| match change {
+                AddRemove::Add(hash) | AddRemove::Remove(hash) => {
+                    hashes.insert(hash)
+                }
+            })
+        };
+        !self.is_empty() && changes_once(&self.tx) && changes_once(&self.vp)
+    }
+}
+
+/// The maximum size of a block in bytes accepted by CometBFT
+pub const MAX_CONSENSUS_BLOCK_BYTES: u64 = 100 * 1024 * 1024;
+
+/// New values of the CometBFT block parameters
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct BlockParamsUpdate {
+    /// The maximum size of a block in bytes
+    pub max_bytes: u64,
+    /// The maximum gas of a block, or -1 for no limit
+    pub max_gas: i64,
+}
+
+/// New values of the CometBFT evidence parameters
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct EvidenceParamsUpdate {
+    /// The maximum age of evidence in blocks
+    pub max_age_num_blocks: u64,
+    /// The maximum age of evidence in time
+    pub max_age_duration: DurationSecs,
+    /// The maximum size of the evidence of a block in bytes
+    pub max_bytes: u64,
+}
+
+/// An update of the CometBFT consensus parameters, returned to CometBFT when
+/// the proposal that carries it is executed. Each group of parameters is
+/// replaced as a whole.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ConsensusParamsUpdate {
+    /// The new block parameters
+    pub block: Option<BlockParamsUpdate>,
+    /// The new evidence parameters
+    pub evidence: Option<EvidenceParamsUpdate>,
+}
+
+impl ConsensusParamsUpdate {
+    /// Check if the update changes some parameters, and that each of the new
+    /// values is in the range accepted by CometBFT
+    pub fn is_well_formed(&self) -> bool {
+        if self.block.is_none() && self.evidence.is_none() {
+            return false;
+        }
+        let is_valid_block = self.block.as_ref().map_or(true, |block| {
+            block.max_bytes > 0
+                && block.max_bytes <= MAX_CONSENSUS_BLOCK_BYTES
+                && block.max_gas >= -1
+        });
+        let is_valid_evidence =
+            self.evidence.as_ref().map_or(true, |evidence| {
+                evidence.max_age_num_blocks > 0
+                    && evidence.max_age_num_blocks <= i64::MAX as u64
+                    && evidence.max_age_duration.0 > 0
+                    && evidence.max_age_duration.0 <= i64::MAX as u64
+                    && evidence.max_bytes <= MAX_CONSENSUS_BLOCK_BYTES
+            });
+        is_valid_block && is_valid_evidence
+    }
+
+    /// Check if the update is well-formed, and that the consensus params it
+    /// results in from the current ones would be accepted by CometBFT
+    pub fn is_valid(&self, current: &ConsensusParams) -> bool {
+        let max_block_bytes = self
+            .block
+            .as_ref()
+            .map_or(current.block.max_bytes, |block| block.max_bytes);
+        let max_evidence_bytes = self
+            .evidence
+            .as_ref()
+            .map_or(current.evidence.max_bytes, |evidence| evidence.max_bytes);
+        self.is_well_formed() && max_evidence_bytes <= max_block_bytes
+    }
+
+    /// Apply the update to the given consensus params
+    pub fn apply(self, params: &mut ConsensusParams) {
+        if let Some(block) = self.block {
+            params.block = block;
+        }
+        if let Some(evidence) = self.evidence {
+            params.evidence = evidence;
+        }
+    }
+}
+
+/// The CometBFT consensus parameters of the chain which may be updated by
+/// governance, as set at genesis or by the last executed update
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct ConsensusParams {
+    /// The block parameters
+    pub block: BlockParamsUpdate,
+    /// The evidence parameters
+    pub evidence: EvidenceParamsUpdate,
+}
+
+/// A transfer of native tokens from the PGF treasury, executed when the
+/// proposal that carries it passes
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TreasurySpend {
+    /// The recipient of the funds
+    pub recipient: Address,
+    /// The amount of native tokens to transfer
+    pub amount: Amount,
+}
+
+impl TreasurySpend {
+    /// Check that the spend transfers a non-zero amount to an address other
+    /// than the treasury
+    pub fn is_valid(&self) -> bool {
+        !self.amount.is_zero() && self.recipient != PGF_ADDRESS
+    }
+}
+
 /// The type of a Proposal
 #[derive(
     Debug,
@@ -122,6 +338,16 @@ pub enum ProposalType {
     PGFSteward(HashSet<AddRemove<Address>>),
     /// PGF funding proposal
     PGFPayment(Vec<PGFAction>),
+    /// Protocol parameters change proposal
+    ParameterChange(Vec<ParameterChange>),
+    /// Tx and VP code allowlists update proposal
+    CodeAllowlist(CodeAllowlistUpdate),
+    /// CometBFT consensus parameters change proposal
+    ConsensusParams(ConsensusParamsUpdate),
+    /// Text proposal, only tallied to signal the support of its content
+    Text,
+    /// Treasury spend proposal
+    TreasurySpend(TreasurySpend),
 }
 
 impl ProposalType {
@@ -129,6 +355,25 @@ impl ProposalType {
     pub fn is_default(&self) -> bool {
         matches!(self, ProposalType::Default(_))
     }
+
+    /// Check if the proposal type is text
+    pub fn is_text(&self) -> bool {
+        matches!(self, ProposalType::Text)
+    }
+
+    /// Check if the proposal may change the parameters of the Ethereum
+    /// bridge, such as the address of its contracts. The code of a default
+    /// proposal may write any key, so whether it changes the bridge is only
+    /// known from the keys written when it is executed.
+    pub fn affects_eth_bridge(&self) -> bool {
+        match self {
+            ProposalType::ParameterChange(changes) => changes
+                .iter()
+                .any(|change| is_eth_bridge_parameter_key(&change.key)),
+            ProposalType::Default(Some(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 impl Display for ProposalType {
@@ -137,6 +382,11 @@ impl Display for ProposalType {
             ProposalType::Default(_) => write!(f, "Default"),
             ProposalType::PGFSteward(_) => write!(f, "Pgf steward"),
             ProposalType::PGFPayment(_) => write!(f, "Pgf funding"),
+            ProposalType::ParameterChange(_) => write!(f, "Parameter change"),
+            ProposalType::CodeAllowlist(_) => write!(f, "Code allowlist"),
+            ProposalType::ConsensusParams(_) => write!(f, "Consensus params"),
+            ProposalType::Text => write!(f, "Text"),
+            ProposalType::TreasurySpend(_) => write!(f, "Treasury spend"),
         }
     }
 }
@@ -157,6 +407,47 @@ impl TryFrom<StewardsUpdate> for HashSet<AddRemove<Address>> {
     }
 }
 
+impl TryFrom<&ParameterChangeData> for ParameterChange {
+    type Error = ProposalTypeError;
+
+    fn try_from(value: &ParameterChangeData) -> Result<Self, Self::Error> {
+        let key = Key::parse(&value.key).map_err(|e| {
+            ProposalTypeError::InvalidParameterChange(e.to_string())
+        })?;
+        let value =
+            HEXLOWER_PERMISSIVE.decode(value.value.as_bytes()).map_err(
+                |e| ProposalTypeError::InvalidParameterChange(e.to_string()),
+            )?;
+        Ok(ParameterChange { key, value })
+    }
+}
+
+impl TryFrom<&CodeAllowlistData> for CodeAllowlistUpdate {
+    type Error = ProposalTypeError;
+
+    fn try_from(value: &CodeAllowlistData) -> Result<Self, Self::Error> {
+        let parse = |hash: &String| {
+            Hash::from_str(hash).map_err(|e| {
+                ProposalTypeError::InvalidCodeAllowlistUpdate(e.to_string())
+            })
+        };
+        let changes = |add: &[String], remove: &[String]| {
+            add.iter()
+                .map(|hash| parse(hash).map(AddRemove::Add))
+                .chain(
+                    remove
+                        .iter()
+                        .map(|hash| parse(hash).map(AddRemove::Remove)),
+                )
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(CodeAllowlistUpdate {
+            tx: changes(&value.add_tx, &value.remove_tx)?,
+            vp: changes(&value.add_vp, &value.remove_vp)?,
+        })
+    }
+}
+
 impl TryFrom<PgfSteward> for AddRemove<Address> {
     type Error = ProposalTypeError;
 
@@ -217,6 +508,9 @@ pub struct StorageProposal {
     pub voting_end_epoch: Epoch,
     /// The epoch from which this changes are executed
     pub grace_epoch: Epoch,
+    /// The block height from which voting is stopped, if the proposal is
+    /// expedited
+    pub voting_end_height: Option<BlockHeight>,
 }
 
 impl StorageProposal {
@@ -239,7 +533,16 @@ impl StorageProposal {
 
     /// Return the type of tally for the proposal
     pub fn get_tally_type(&self, is_steward: bool) -> TallyType {
-        TallyType::from(self.r#type.clone(), is_steward)
+        if self.is_expedited() {
+            TallyType::ThreeQuarters
+        } else {
+            TallyType::from(self.r#type.clone(), is_steward)
+        }
+    }
+
+    /// Check if the proposal is expedited
+    pub fn is_expedited(&self) -> bool {
+        self.voting_end_height.is_some()
     }
 
     /// Return the status of a proposal
@@ -24,6 +24,16 @@ pub enum VoteType {
     PGFSteward,
     /// A vote for a PGF payment proposal
     PGFPayment,
+    /// A vote for a parameter change proposal
+    ParameterChange,
+    /// A vote for a code allowlist update proposal
+    CodeAllowlist,
+    /// A vote for a consensus parameters change proposal
+    ConsensusParams,
+    /// A vote for a treasury spend proposal
+    TreasurySpend,
+    /// A vote for a text proposal
+    Text,
 }
 
 #[derive(
@@ -44,6 +54,8 @@ pub enum StorageProposalVote {
     Nay,
     /// Abstain
     Abstain,
+    /// No, with a veto
+    Veto,
 }
 
 impl StorageProposalVote {
@@ -62,6 +74,11 @@ impl StorageProposalVote {
         matches!(self, StorageProposalVote::Abstain)
     }
 
+    /// Check if a vote is a veto
+    pub fn is_veto(&self) -> bool {
+        matches!(self, StorageProposalVote::Veto)
+    }
+
     /// Check if two votes are equal
     pub fn is_same_side(&self, other: &Self) -> bool {
         std::mem::discriminant(self) == std::mem::discriminant(other)
@@ -82,6 +99,7 @@ impl StorageProposalVote {
             StorageProposalVote::Yay(vote_type) => proposal_type.eq(vote_type),
             StorageProposalVote::Nay => true,
             StorageProposalVote::Abstain => true,
+            StorageProposalVote::Veto => true,
         }
     }
 
@@ -100,6 +118,21 @@ impl StorageProposalVote {
             (ProposalVote::Yay, ProposalType::PGFPayment(_)) => {
                 Some(StorageProposalVote::Yay(VoteType::PGFPayment))
             }
+            (ProposalVote::Yay, ProposalType::ParameterChange(_)) => {
+                Some(StorageProposalVote::Yay(VoteType::ParameterChange))
+            }
+            (ProposalVote::Yay, ProposalType::CodeAllowlist(_)) => {
+                Some(StorageProposalVote::Yay(VoteType::CodeAllowlist))
+            }
+            (ProposalVote::Yay, ProposalType::ConsensusParams(_)) => {
+                Some(StorageProposalVote::Yay(VoteType::ConsensusParams))
+            }
+            (ProposalVote::Yay, ProposalType::TreasurySpend(_)) => {
+                Some(StorageProposalVote::Yay(VoteType::TreasurySpend))
+            }
+            (ProposalVote::Yay, ProposalType::Text) => {
+                Some(StorageProposalVote::Yay(VoteType::Text))
+            }
             (ProposalVote::Nay, ProposalType::Default(_)) => {
                 Some(StorageProposalVote::Nay)
             }
@@ -109,6 +142,22 @@ impl StorageProposalVote {
             (ProposalVote::Nay, ProposalType::PGFPayment(_)) => {
                 Some(StorageProposalVote::Nay)
             }
+            (ProposalVote::Nay, ProposalType::ParameterChange(_)) => {
+                Some(StorageProposalVote::Nay)
+            }
+            (ProposalVote::Nay, ProposalType::CodeAllowlist(_)) => {
+                Some(StorageProposalVote::Nay)
+            }
+            (ProposalVote::Nay, ProposalType::ConsensusParams(_)) => {
+                Some(StorageProposalVote::Nay)
+            }
+            (ProposalVote::Nay, ProposalType::TreasurySpend(_)) => {
+                Some(StorageProposalVote::Nay)
+            }
+            (ProposalVote::Nay, ProposalType::Text) => {
+                Some(StorageProposalVote::Nay)
+            }
+            (ProposalVote::Veto, _) => Some(StorageProposalVote::Veto),
             _ => None,
         }
     }
@@ -120,11 +169,17 @@ impl Display for StorageProposalVote {
             StorageProposalVote::Yay(vote_type) => match vote_type {
                 VoteType::Default
                 | VoteType::PGFSteward
-                | VoteType::PGFPayment => write!(f, "yay"),
+                | VoteType::PGFPayment
+                | VoteType::ParameterChange
+                | VoteType::CodeAllowlist
+                | VoteType::ConsensusParams
+                | VoteType::TreasurySpend
+                | VoteType::Text => write!(f, "yay"),
             },
 
             StorageProposalVote::Nay => write!(f, "nay"),
             StorageProposalVote::Abstain => write!(f, "abstain"),
+            StorageProposalVote::Veto => write!(f, "veto"),
         }
     }
 }
@@ -141,6 +196,21 @@ impl PartialEq<VoteType> for ProposalType {
             Self::PGFPayment(_) => {
                 matches!(other, VoteType::PGFPayment)
             }
+            Self::ParameterChange(_) => {
+                matches!(other, VoteType::ParameterChange)
+            }
+            Self::CodeAllowlist(_) => {
+                matches!(other, VoteType::CodeAllowlist)
+            }
+            Self::ConsensusParams(_) => {
+                matches!(other, VoteType::ConsensusParams)
+            }
+            Self::TreasurySpend(_) => {
+                matches!(other, VoteType::TreasurySpend)
+            }
+            Self::Text => {
+                matches!(other, VoteType::Text)
+            }
         }
     }
 }
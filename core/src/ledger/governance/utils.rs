@@ -1,14 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
 use super::cli::offline::OfflineVote;
-use super::storage::proposal::ProposalType;
+use super::storage::proposal::{ProposalType, StorageProposal};
 use super::storage::vote::StorageProposalVote;
 use crate::types::address::Address;
 use crate::types::storage::Epoch;
 use crate::types::token;
+use crate::types::voting_power::FractionalVotingPower;
 
 /// Proposal status
 pub enum ProposalStatus {
@@ -62,6 +64,9 @@ pub enum TallyType {
     /// Represent a tally type for proposal requiring less than 1/2 of nay
     /// votes over at least 1/3 of the voting power
     LessOneHalfOverOneThirdNay,
+    /// Represent a tally type for expedited proposals requiring 3/4 of the
+    /// total voting power to be yay
+    ThreeQuarters,
 }
 
 impl TallyType {
@@ -76,6 +81,11 @@ impl TallyType {
             (ProposalType::PGFPayment(_), false) => {
                 TallyType::OneHalfOverOneThird
             }
+            (ProposalType::ParameterChange(_), _) => TallyType::TwoThirds,
+            (ProposalType::CodeAllowlist(_), _) => TallyType::TwoThirds,
+            (ProposalType::ConsensusParams(_), _) => TallyType::TwoThirds,
+            (ProposalType::TreasurySpend(_), _) => TallyType::TwoThirds,
+            (ProposalType::Text, _) => TallyType::OneHalfOverOneThird,
         }
     }
 }
@@ -131,12 +141,155 @@ impl TallyResult {
                     yay_voting_power > nay_voting_power;
                 less_one_third_voted || more_than_half_voted_yay
             }
+            TallyType::ThreeQuarters => {
+                yay_voting_power >= total_voting_power * 3 / 4
+            }
         };
 
         if passed { Self::Passed } else { Self::Rejected }
     }
 }
 
+/// Countdown to the delayed execution of a proposal that has passed
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize,
+)]
+pub struct ProposalExecutionCountdown {
+    /// The epoch at which the proposal is executed
+    pub execution_epoch: Epoch,
+    /// The number of epochs left until the proposal is executed
+    pub remaining_epochs: u64,
+}
+
+impl ProposalExecutionCountdown {
+    /// Compute the countdown to the execution of a proposal from the
+    /// current epoch
+    pub fn new(execution_epoch: Epoch, current_epoch: Epoch) -> Self {
+        Self {
+            execution_epoch,
+            remaining_epochs: execution_epoch.0.saturating_sub(current_epoch.0),
+        }
+    }
+
+    /// Check if the proposal has already been executed
+    pub fn is_executed(&self) -> bool {
+        self.remaining_epochs == 0
+    }
+}
+
+impl Display for ProposalExecutionCountdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_executed() {
+            write!(f, "executed at epoch {}", self.execution_epoch)
+        } else {
+            write!(
+                f,
+                "to be executed at epoch {} ({} epochs left)",
+                self.execution_epoch, self.remaining_epochs
+            )
+        }
+    }
+}
+
+/// The state of a proposal in its lifecycle, from its submission to its
+/// execution
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, BorshDeserialize, BorshSerialize,
+)]
+pub enum ProposalState {
+    /// The voting period of the proposal hasn't started yet
+    Pending,
+    /// The proposal is being voted on
+    Voting,
+    /// The voting period of the proposal has ended, and it's waiting to be
+    /// tallied
+    Tallying,
+    /// The proposal has passed and its execution is pending
+    Passed,
+    /// The proposal has been rejected
+    Rejected,
+    /// The proposal has passed and has been executed
+    Executed,
+    /// The proposal has passed, but its code failed to execute
+    ExecutionFailed,
+}
+
+impl Display for ProposalState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalState::Pending => write!(f, "pending"),
+            ProposalState::Voting => write!(f, "voting"),
+            ProposalState::Tallying => write!(f, "tallying"),
+            ProposalState::Passed => write!(f, "passed"),
+            ProposalState::Rejected => write!(f, "rejected"),
+            ProposalState::Executed => write!(f, "executed"),
+            ProposalState::ExecutionFailed => write!(f, "execution-failed"),
+        }
+    }
+}
+
+impl FromStr for ProposalState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pending" => Ok(ProposalState::Pending),
+            "voting" => Ok(ProposalState::Voting),
+            "tallying" => Ok(ProposalState::Tallying),
+            "passed" => Ok(ProposalState::Passed),
+            "rejected" => Ok(ProposalState::Rejected),
+            "executed" => Ok(ProposalState::Executed),
+            "execution-failed" => Ok(ProposalState::ExecutionFailed),
+            _ => Err(format!("invalid proposal state {}", s)),
+        }
+    }
+}
+
+/// The filters of a query for a list of proposals
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProposalsFilter {
+    /// Only include the proposals in this state
+    pub state: Option<ProposalState>,
+    /// Only include the proposals submitted by this author
+    pub author: Option<Address>,
+    /// Only include the proposals whose voting period ends at or after this
+    /// epoch
+    pub from_epoch: Option<Epoch>,
+    /// Only include the proposals whose voting period starts at or before
+    /// this epoch
+    pub to_epoch: Option<Epoch>,
+}
+
+impl ProposalsFilter {
+    /// Check if a proposal in the given state matches the filters
+    pub fn matches(
+        &self,
+        proposal: &StorageProposal,
+        state: ProposalState,
+    ) -> bool {
+        self.state.map_or(true, |filter| filter == state)
+            && self
+                .author
+                .as_ref()
+                .map_or(true, |author| author == &proposal.author)
+            && self
+                .from_epoch
+                .map_or(true, |epoch| proposal.voting_end_epoch >= epoch)
+            && self
+                .to_epoch
+                .map_or(true, |epoch| proposal.voting_start_epoch <= epoch)
+    }
+}
+
+/// A page of proposals, ordered by their id
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+pub struct ProposalsPage {
+    /// The proposals on this page, with their state
+    pub proposals: Vec<(StorageProposal, ProposalState)>,
+    /// The id to query the next page after, if there may be more proposals
+    pub next: Option<u64>,
+}
+
 /// The result with votes of a proposal
 #[derive(Clone, Copy, BorshDeserialize, BorshSerialize)]
 pub struct ProposalResult {
@@ -150,6 +303,8 @@ pub struct ProposalResult {
     pub total_nay_power: VotePower,
     /// The total voting power from abstained votes
     pub total_abstain_power: VotePower,
+    /// The total voting power from veto votes
+    pub total_veto_power: VotePower,
 }
 
 impl Display for ProposalResult {
@@ -161,10 +316,11 @@ impl Display for ProposalResult {
 
         write!(
             f,
-            "{} with {} yay votes and {} nay votes ({:.2}%)",
+            "{} with {} yay votes, {} nay votes and {} veto votes ({:.2}%)",
             self.result,
             self.total_yay_power.to_string_native(),
             self.total_nay_power.to_string_native(),
+            self.total_veto_power.to_string_native(),
             percentage
                 .checked_mul(token::Amount::from_u64(100))
                 .unwrap_or_default()
@@ -175,18 +331,32 @@ impl Display for ProposalResult {
 
 impl ProposalResult {
     /// Return true if at least 1/3 of the total voting power voted and at least
-    /// two third of the non-abstained voting power voted nay
+    /// two third of the non-abstained voting power voted nay. Veto votes count
+    /// as nay votes.
     pub fn two_thirds_nay_over_two_thirds_total(&self) -> bool {
-        let at_least_two_thirds_voted = self.total_yay_power
-            + self.total_nay_power
-            + self.total_abstain_power
-            >= self.total_voting_power * 2 / 3;
+        let total_nay_power = self.total_nay_power + self.total_veto_power;
 
-        let at_least_two_thirds_nay = self.total_nay_power
-            >= (self.total_nay_power + self.total_yay_power) * 2 / 3;
+        let at_least_two_thirds_voted =
+            self.total_yay_power + total_nay_power + self.total_abstain_power
+                >= self.total_voting_power * 2 / 3;
+
+        let at_least_two_thirds_nay =
+            total_nay_power >= (total_nay_power + self.total_yay_power) * 2 / 3;
 
         at_least_two_thirds_voted && at_least_two_thirds_nay
     }
+
+    /// Return true if more than the `veto_threshold` share of the voting
+    /// power that voted on the proposal vetoed it. A vetoed proposal is
+    /// rejected and its funds are burned.
+    pub fn is_vetoed(&self, veto_threshold: FractionalVotingPower) -> bool {
+        let voted_power = self.total_yay_power
+            + self.total_nay_power
+            + self.total_abstain_power
+            + self.total_veto_power;
+
+        self.total_veto_power > veto_threshold * voted_power
+    }
 }
 
 /// /// General rappresentation of a vote
@@ -234,6 +404,14 @@ impl TallyVote {
         }
     }
 
+    /// Check if a vote is a veto
+    pub fn is_veto(&self) -> bool {
+        match self {
+            TallyVote::OnChain(vote) => vote.is_veto(),
+            TallyVote::Offline(vote) => vote.is_veto(),
+        }
+    }
+
     /// Check if two votes are equal, returns an error if the variants of the
     /// two instances are different
     pub fn is_same_side(
@@ -252,6 +430,18 @@ impl TallyVote {
     }
 }
 
+/// The voting power at the epoch a proposal entered its voting period, which
+/// its votes are tallied with
+#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+pub struct ProposalStakeSnapshot {
+    /// The epoch at which the snapshot was taken
+    pub epoch: Epoch,
+    /// The total voting power
+    pub total_voting_power: VotePower,
+    /// Map from validator address to their voting power
+    pub validator_voting_power: BTreeMap<Address, VotePower>,
+}
+
 /// Proposal structure holding votes information necessary to compute the
 /// outcome
 pub struct ProposalVotes {
@@ -259,21 +449,25 @@ pub struct ProposalVotes {
     pub validators_vote: HashMap<Address, TallyVote>,
     /// Map from validator to their voting power
     pub validator_voting_power: HashMap<Address, VotePower>,
-    /// Map from delegation address to their vote
-    pub delegators_vote: HashMap<Address, TallyVote>,
+    /// Map from delegator address to their vote for each of the validators
+    /// they delegated to, overriding the vote of these validators
+    pub delegators_vote: HashMap<Address, HashMap<Address, TallyVote>>,
     /// Map from delegator address to the corresponding validator voting power
     pub delegator_voting_power: HashMap<Address, HashMap<Address, VotePower>>,
 }
 
-/// Compute the result of a proposal
+/// Compute the result of a proposal. It is rejected if it is vetoed by more
+/// than the `veto_threshold` share of the voting power that voted on it.
 pub fn compute_proposal_result(
     votes: ProposalVotes,
     total_voting_power: VotePower,
     tally_at: TallyType,
+    veto_threshold: FractionalVotingPower,
 ) -> ProposalResult {
     let mut yay_voting_power = VotePower::default();
     let mut nay_voting_power = VotePower::default();
     let mut abstain_voting_power = VotePower::default();
+    let mut veto_voting_power = VotePower::default();
 
     for (address, vote_power) in votes.validator_voting_power {
         let vote_type = votes.validators_vote.get(&address);
@@ -284,16 +478,24 @@ pub fn compute_proposal_result(
                 nay_voting_power += vote_power;
             } else if vote.is_abstain() {
                 abstain_voting_power += vote_power;
+            } else if vote.is_veto() {
+                veto_voting_power += vote_power;
             }
         }
     }
 
     for (delegator, delegations) in votes.delegator_voting_power {
-        let delegator_vote = match votes.delegators_vote.get(&delegator) {
-            Some(vote) => vote,
+        let delegator_votes = match votes.delegators_vote.get(&delegator) {
+            Some(votes) => votes,
             None => continue,
         };
         for (validator, voting_power) in delegations {
+            // A delegator may only override the vote of some of its
+            // validators
+            let delegator_vote = match delegator_votes.get(&validator) {
+                Some(vote) => vote,
+                None => continue,
+            };
             let validator_vote = votes.validators_vote.get(&validator);
             if let Some(validator_vote) = validator_vote {
                 let validator_vote_is_same_side =
@@ -314,31 +516,33 @@ pub fn compute_proposal_result(
                                 total_yay_power: VotePower::default(),
                                 total_nay_power: VotePower::default(),
                                 total_abstain_power: VotePower::default(),
+                                total_veto_power: VotePower::default(),
                             };
                         }
                     };
                 if !validator_vote_is_same_side {
+                    // The delegator's vote overrides the vote of its
+                    // validator for the delegated voting power
+                    let validator_voting_power = if validator_vote.is_yay() {
+                        &mut yay_voting_power
+                    } else if validator_vote.is_nay() {
+                        &mut nay_voting_power
+                    } else if validator_vote.is_abstain() {
+                        &mut abstain_voting_power
+                    } else {
+                        &mut veto_voting_power
+                    };
+                    *validator_voting_power = validator_voting_power
+                        .checked_sub(voting_power)
+                        .unwrap_or_default();
                     if delegator_vote.is_yay() {
                         yay_voting_power += voting_power;
-                        if validator_vote.is_nay() {
-                            nay_voting_power -= voting_power;
-                        } else if validator_vote.is_abstain() {
-                            abstain_voting_power -= voting_power;
-                        }
                     } else if delegator_vote.is_nay() {
                         nay_voting_power += voting_power;
-                        if validator_vote.is_yay() {
-                            yay_voting_power -= voting_power;
-                        } else if validator_vote.is_abstain() {
-                            abstain_voting_power -= voting_power;
-                        }
                     } else if delegator_vote.is_abstain() {
                         abstain_voting_power += voting_power;
-                        if validator_vote.is_yay() {
-                            yay_voting_power -= voting_power;
-                        } else if validator_vote.is_nay() {
-                            nay_voting_power -= voting_power;
-                        }
+                    } else if delegator_vote.is_veto() {
+                        veto_voting_power += voting_power;
                     }
                 }
             } else if delegator_vote.is_yay() {
@@ -347,25 +551,33 @@ pub fn compute_proposal_result(
                 nay_voting_power += voting_power;
             } else if delegator_vote.is_abstain() {
                 abstain_voting_power += voting_power;
+            } else if delegator_vote.is_veto() {
+                veto_voting_power += voting_power;
             }
         }
     }
 
+    // Veto votes count as nay votes
     let tally_result = TallyResult::new(
         &tally_at,
         yay_voting_power,
-        nay_voting_power,
+        nay_voting_power + veto_voting_power,
         abstain_voting_power,
         total_voting_power,
     );
 
-    ProposalResult {
+    let mut proposal_result = ProposalResult {
         result: tally_result,
         total_voting_power,
         total_yay_power: yay_voting_power,
         total_nay_power: nay_voting_power,
         total_abstain_power: abstain_voting_power,
+        total_veto_power: veto_voting_power,
+    };
+    if proposal_result.is_vetoed(veto_threshold) {
+        proposal_result.result = TallyResult::Rejected;
     }
+    proposal_result
 }
 
 /// Calculate the valid voting window for validator given a proposal epoch
@@ -383,3 +595,27 @@ pub fn is_valid_validator_voting_period(
         current_epoch <= voting_start_epoch + two_third_duration
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test that a proposal is only vetoed by strictly more than 1/3 of the
+    /// voting power that voted on it, with the default veto threshold.
+    #[test]
+    fn test_veto_threshold_boundary() {
+        let result_with_veto = |veto: u64| ProposalResult {
+            result: TallyResult::Passed,
+            total_voting_power: token::Amount::from_u64(3_000_000_000_001),
+            total_yay_power: token::Amount::from_u64(2_000_000_000_000),
+            total_nay_power: token::Amount::zero(),
+            total_abstain_power: token::Amount::zero(),
+            total_veto_power: token::Amount::from_u64(veto),
+        };
+        let threshold = FractionalVotingPower::ONE_THIRD;
+
+        // exactly 1/3 of the 3e12 voted voting power
+        assert!(!result_with_veto(1_000_000_000_000).is_vetoed(threshold));
+        assert!(result_with_veto(1_000_000_000_001).is_vetoed(threshold));
+    }
+}
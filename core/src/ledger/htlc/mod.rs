@@ -0,0 +1,111 @@
+//! Hashed time-locked transfers library code
+//!
+//! Tokens locked in a [`TimeLock`] are held by the HTLC internal address,
+//! until they are either claimed by the receiver or refunded to the source.
+//! Locks with a hashlock may be claimed with the preimage of the hash
+//! before their timelock expires, and are refunded to the source at the
+//! start of the epoch at which it expires. Locks without a hashlock are
+//! released to the receiver at the start of that epoch instead.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::hash::Hash;
+use crate::types::storage::Epoch;
+use crate::types::token;
+
+/// HTLC storage
+pub mod storage;
+
+/// The HTLC internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Htlc);
+
+/// The status of a time lock
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum TimeLockStatus {
+    /// The locked tokens are held by the HTLC address
+    Pending,
+    /// The locked tokens were claimed by the receiver with the given
+    /// preimage of the hashlock
+    Claimed {
+        /// The preimage of the hashlock
+        preimage: Vec<u8>,
+    },
+    /// The timelock expired and the locked tokens were released to the
+    /// receiver
+    Released,
+    /// The locked tokens were refunded to the source
+    Refunded,
+}
+
+/// Tokens locked until they are claimed by the receiver or refunded to the
+/// source
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TimeLock {
+    /// The address that locked the tokens
+    pub source: Address,
+    /// The address that may claim the tokens
+    pub receiver: Address,
+    /// The locked token
+    pub token: Address,
+    /// The locked amount
+    pub amount: token::Amount,
+    /// The SHA-256 hash of the preimage with which the receiver may claim
+    /// the tokens. If [`None`], the tokens are released to the receiver
+    /// once the timelock expires.
+    pub hashlock: Option<Hash>,
+    /// The epoch at which the timelock expires
+    pub timelock: Epoch,
+    /// The status of the lock
+    pub status: TimeLockStatus,
+}
+
+impl TimeLock {
+    /// Check if the tokens are still held by the HTLC address.
+    pub fn is_pending(&self) -> bool {
+        matches!(self.status, TimeLockStatus::Pending)
+    }
+
+    /// Check if the given preimage unlocks the hashlock of this lock.
+    pub fn is_valid_preimage(&self, preimage: &[u8]) -> bool {
+        matches!(&self.hashlock, Some(hash) if *hash == Hash::sha256(preimage))
+    }
+
+    /// Return the address credited with the locked tokens upon the expiry
+    /// of the timelock.
+    pub fn expiry_beneficiary(&self) -> &Address {
+        if self.hashlock.is_some() {
+            &self.source
+        } else {
+            &self.receiver
+        }
+    }
+
+    /// Return the status of the lock upon the expiry of the timelock.
+    pub fn expiry_status(&self) -> TimeLockStatus {
+        if self.hashlock.is_some() {
+            TimeLockStatus::Refunded
+        } else {
+            TimeLockStatus::Released
+        }
+    }
+}
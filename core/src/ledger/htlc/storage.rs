@@ -0,0 +1,99 @@
+//! HTLC storage keys
+
+use namada_macros::StorageKeys;
+
+use crate::ledger::htlc::ADDRESS;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+
+/// Storage keys for the HTLC internal address.
+#[derive(StorageKeys)]
+struct Keys {
+    counter: &'static str,
+    locks: &'static str,
+    expiry: &'static str,
+}
+
+/// Check if the given key is in the HTLC storage subspace.
+pub fn is_htlc_key(key: &Key) -> bool {
+    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
+}
+
+/// Get the key of the counter of the ids of time locks.
+pub fn get_counter_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.counter.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key is the key of the counter of time locks.
+pub fn is_counter_key(key: &Key) -> bool {
+    matches!(
+        &key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(counter)]
+            if addr == &ADDRESS && counter == Keys::VALUES.counter
+    )
+}
+
+/// Get the prefix of the keys of all the time locks.
+pub fn get_locks_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.locks.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the time lock with the given id.
+pub fn get_lock_key(id: u64) -> Key {
+    get_locks_prefix()
+        .push(&id)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key is the key of a time lock. If it is, returns
+/// the id of the lock.
+pub fn is_lock_key(key: &Key) -> Option<u64> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(locks),
+            DbKeySeg::StringSeg(id),
+        ] if addr == &ADDRESS && locks == Keys::VALUES.locks => {
+            u64::parse(id.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Get the prefix of the keys of the time locks expiring at the given
+/// epoch.
+pub fn get_expiry_prefix(epoch: Epoch) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&Keys::VALUES.expiry.to_owned())
+        .and_then(|key| key.push(&epoch))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key indexing the time lock with the given id under the epoch at
+/// which it expires. It holds the id of the lock.
+pub fn get_expiry_key(epoch: Epoch, id: u64) -> Key {
+    get_expiry_prefix(epoch)
+        .push(&id)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given key indexes a time lock under the epoch at which it
+/// expires. If it does, returns the epoch and the id of the lock.
+pub fn is_expiry_key(key: &Key) -> Option<(Epoch, u64)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(expiry),
+            DbKeySeg::StringSeg(epoch),
+            DbKeySeg::StringSeg(id),
+        ] if addr == &ADDRESS && expiry == Keys::VALUES.expiry => {
+            let epoch = Epoch::parse(epoch.clone()).ok()?;
+            let id = u64::parse(id.clone()).ok()?;
+            Some((epoch, id))
+        }
+        _ => None,
+    }
+}
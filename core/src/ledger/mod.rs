@@ -3,6 +3,7 @@
 pub mod eth_bridge;
 pub mod gas;
 pub mod governance;
+pub mod htlc;
 pub mod ibc;
 pub mod inflation;
 pub mod masp_conversions;
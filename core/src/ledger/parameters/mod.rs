@@ -410,6 +410,89 @@ where
         .into_storage_result()
 }
 
+/// Enable or disable the attestation lane with the given id.
+pub fn update_attestation_lane_parameter<S>(
+    storage: &mut S,
+    lane: &str,
+    enabled: bool,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_attestation_lane_key(lane);
+    storage.write(&key, enabled)
+}
+
+/// Check if the attestation lane with the given id has been enabled.
+pub fn is_attestation_lane_enabled<S>(
+    storage: &S,
+    lane: &str,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    let key = storage::get_attestation_lane_key(lane);
+    Ok(storage.read(&key)?.unwrap_or(false))
+}
+
+/// Check if the tx code with the given hash is in the tx whitelist. An
+/// empty whitelist allows any tx code.
+pub fn is_tx_whitelisted<S>(
+    storage: &S,
+    code_hash: &Hash,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    let key = storage::get_tx_whitelist_storage_key();
+    let tx_whitelist: Vec<String> = storage.read(&key)?.unwrap_or_default();
+    Ok(tx_whitelist.is_empty()
+        || tx_whitelist.contains(&code_hash.to_string().to_lowercase()))
+}
+
+/// Read the names of the invariants checked by the ledger.
+pub fn read_invariant_checks<S>(storage: &S) -> storage_api::Result<Vec<String>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_invariant_checks_key();
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
+/// Read the number of blocks between invariant checks, if configured.
+/// Otherwise, invariants are checked at the first block of each epoch.
+pub fn read_invariant_checks_interval<S>(
+    storage: &S,
+) -> storage_api::Result<Option<u64>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_invariant_checks_interval_key();
+    storage.read(&key)
+}
+
+/// Read the reason the chain has been halted for, if it has been halted.
+pub fn read_chain_halted<S>(storage: &S) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    let key = storage::get_chain_halted_key();
+    storage.read(&key)
+}
+
+/// Halt the chain, for the given reason. Only governance may resume the
+/// chain, by deleting the halted status from storage.
+pub fn write_chain_halted<S>(
+    storage: &mut S,
+    reason: String,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::get_chain_halted_key();
+    storage.write(&key, reason)
+}
+
 /// Read the cost per unit of gas for the provided token
 pub fn read_gas_cost<S>(
     storage: &S,
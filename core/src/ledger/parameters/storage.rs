@@ -23,6 +23,31 @@ struct Keys {
     native_erc20: &'static str,
     /// Sub-lkey for storing the Ethereum address of the bridge contract.
     bridge_contract_address: &'static str,
+    /// Sub-key for storing the Ethereum address of the NFT bridge
+    /// contract.
+    nft_bridge_contract_address: &'static str,
+    /// Sub-key for storing a rotation of the bridge contract scheduled
+    /// by governance.
+    bridge_contract_rotation: &'static str,
+    /// Sub-key for storing the number of processed Ethereum blocks the
+    /// oracle re-checks for reorgs, overriding the node's own config.
+    reorg_safety_depth: &'static str,
+    /// Sub-key for storing whether the Ethereum oracle has been paused
+    /// by governance.
+    oracle_paused: &'static str,
+    /// Sub-key for storing the amount of native tokens paid to the
+    /// relayers of transfers to Ethereum, for each relayed transfer.
+    relay_reward: &'static str,
+    /// Sub-key for storing the number of epochs validators must have been
+    /// in the consensus set for, before their Ethereum events and Bridge
+    /// pool root vote extensions count toward quorums.
+    vext_min_consensus_epochs: &'static str,
+    /// Sub-key for storing the maximum number of Ethereum events included
+    /// in a single vote extension.
+    max_vext_eth_events: &'static str,
+    /// Sub-key for storing the number of epochs a transfer must have sat
+    /// unrelayed in the Bridge pool for, before its sender may cancel it.
+    bridge_pool_cancellation_delay: &'static str,
     // ========================================
     // PoS parameters
     // ========================================
@@ -46,8 +71,15 @@ struct Keys {
     fee_unshielding_gas_limit: &'static str,
     fee_unshielding_descriptions_limit: &'static str,
     max_signatures_per_transaction: &'static str,
+    invariant_checks: &'static str,
+    invariant_checks_interval: &'static str,
+    chain_halted: &'static str,
 }
 
+/// Sub-key under which the attestation lanes enabled by governance are
+/// stored.
+const ATTESTATION_LANES_SEG: &str = "attestation_lanes";
+
 /// Returns if the key is a parameter key.
 pub fn is_parameter_key(key: &Key) -> bool {
     matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
@@ -61,11 +93,69 @@ pub fn is_protocol_parameter_key(key: &Key) -> bool {
         {
             segment.as_str()
         }
-        _ => return false,
+        _ => return is_attestation_lane_key(key).is_some(),
     };
     Keys::ALL.binary_search(&segment).is_ok()
 }
 
+/// Returns if the key is an Ethereum bridge parameter key.
+pub fn is_eth_bridge_parameter_key(key: &Key) -> bool {
+    let Keys {
+        eth_start_height,
+        active_status,
+        min_confirmations,
+        native_erc20,
+        bridge_contract_address,
+        nft_bridge_contract_address,
+        bridge_contract_rotation,
+        reorg_safety_depth,
+        oracle_paused,
+        relay_reward,
+        vext_min_consensus_epochs,
+        max_vext_eth_events,
+        bridge_pool_cancellation_delay,
+        ..
+    } = Keys::VALUES;
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(segment)]
+            if addr == &ADDRESS =>
+        {
+            [
+                eth_start_height,
+                active_status,
+                min_confirmations,
+                native_erc20,
+                bridge_contract_address,
+                nft_bridge_contract_address,
+                bridge_contract_rotation,
+                reorg_safety_depth,
+                oracle_paused,
+                relay_reward,
+                vext_min_consensus_epochs,
+                max_vext_eth_events,
+                bridge_pool_cancellation_delay,
+            ]
+            .contains(&segment.as_str())
+        }
+        _ => false,
+    }
+}
+
+/// Returns the id of the attestation lane, if the key is an attestation
+/// lane key.
+pub fn is_attestation_lane_key(key: &Key) -> Option<&str> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(lane),
+        ] if addr == &ADDRESS && prefix == ATTESTATION_LANES_SEG => {
+            Some(lane.as_str())
+        }
+        _ => None,
+    }
+}
+
 /// Returns if the key is an epoch storage key.
 pub fn is_epoch_duration_storage_key(key: &Key) -> bool {
     is_epoch_duration_key_at_addr(key, &ADDRESS)
@@ -210,3 +300,30 @@ pub fn get_gas_cost_key() -> Key {
 pub fn get_max_signatures_per_transaction_key() -> Key {
     get_max_signatures_per_transaction_key_at_addr(ADDRESS)
 }
+
+/// Storage key used for the names of the invariants checked each block
+pub fn get_invariant_checks_key() -> Key {
+    get_invariant_checks_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the number of blocks between invariant checks
+pub fn get_invariant_checks_interval_key() -> Key {
+    get_invariant_checks_interval_key_at_addr(ADDRESS)
+}
+
+/// Storage key used for the reason the chain has been halted, if any
+pub fn get_chain_halted_key() -> Key {
+    get_chain_halted_key_at_addr(ADDRESS)
+}
+
+/// Storage key used to enable or disable the attestation lane with the
+/// given id
+pub fn get_attestation_lane_key(lane: &str) -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(ATTESTATION_LANES_SEG.to_owned()),
+            DbKeySeg::StringSeg(lane.to_owned()),
+        ],
+    }
+}
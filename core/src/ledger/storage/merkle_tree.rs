@@ -160,6 +160,21 @@ impl<'a> StoreRef<'a> {
             Self::BridgePool(store) => store.serialize_to_vec(),
         }
     }
+
+    /// Borsh serialize the backing stores of our Merkle tree into the given
+    /// writer, without buffering the whole encoding in memory.
+    pub fn encode_to<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        match self {
+            Self::Base(store) => store.serialize(writer),
+            Self::Account(store) => store.serialize(writer),
+            Self::Ibc(store) => store.serialize(writer),
+            Self::PoS(store) => store.serialize(writer),
+            Self::BridgePool(store) => store.serialize(writer),
+        }
+    }
 }
 
 impl StoreType {
@@ -394,6 +409,11 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         }
     }
 
+    /// Get the Bridge pool sub-tree
+    pub fn bridge_pool_tree(&self) -> &BridgePoolTree {
+        &self.bridge_pool
+    }
+
     fn tree(&self, store_type: &StoreType) -> Box<dyn SubTreeRead + '_> {
         match store_type {
             StoreType::Base => Box::new(&self.base),
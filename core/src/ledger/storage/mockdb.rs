@@ -14,7 +14,8 @@ use super::merkle_tree::{
     base_tree_key_prefix, subtree_key_prefix, MerkleTreeStoresRead, StoreType,
 };
 use super::{
-    BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error, Result, DB,
+    BlockStateRead, BlockStateWrite, CommitCheckpoint, DBIter, DBWriteBatch,
+    Error, Result, DB,
 };
 use crate::ledger::masp_conversions::ConversionState;
 use crate::ledger::storage::types::{self, KVBytes, PrefixIterator};
@@ -469,13 +470,39 @@ impl DB for MockDB {
     fn read_subspace_val_with_height(
         &self,
         key: &Key,
-        _height: BlockHeight,
-        _last_height: BlockHeight,
+        height: BlockHeight,
+        last_height: BlockHeight,
     ) -> Result<Option<Vec<u8>>> {
-        tracing::warn!(
-            "read_subspace_val_with_height is not implemented, will read \
-             subspace value from latest height"
-        );
+        let diff_key = |height: BlockHeight, diff: &str| {
+            Key::from(height.to_db_key())
+                .push(&diff.to_string().to_db_key())
+                .map(|prefix| prefix.join(key).to_string())
+                .map_err(Error::KeyError)
+        };
+        {
+            let db = self.0.borrow();
+            // If it has a "new" val, it was written at this height
+            if let Some(new_val) = db.get(&diff_key(height, "new")?) {
+                return Ok(Some(new_val.clone()));
+            }
+            // If it has an "old" val, it was deleted at this height
+            if db.contains_key(&diff_key(height, "old")?) {
+                return Ok(None);
+            }
+            // If the value didn't change at the given height, we look for
+            // it at successor heights, up to the `last_height`
+            for raw_height in (height.0 + 1)..=last_height.0 {
+                let height = BlockHeight(raw_height);
+                if let Some(old_val) = db.get(&diff_key(height, "old")?) {
+                    return Ok(Some(old_val.clone()));
+                }
+                // If it was created at this height instead, it wasn't
+                // present before
+                if db.contains_key(&diff_key(height, "new")?) {
+                    return Ok(None);
+                }
+            }
+        }
         self.read_subspace_val(key)
     }
 
@@ -508,6 +535,53 @@ impl DB for MockDB {
         Ok(())
     }
 
+    fn write_commit_checkpoint(
+        &mut self,
+        checkpoint: &CommitCheckpoint,
+    ) -> Result<()> {
+        self.0
+            .borrow_mut()
+            .insert("commit_checkpoint".into(), types::encode(checkpoint));
+        Ok(())
+    }
+
+    fn read_commit_checkpoint(&self) -> Result<Option<CommitCheckpoint>> {
+        match self.0.borrow().get("commit_checkpoint") {
+            Some(bytes) => {
+                Ok(Some(types::decode(bytes).map_err(Error::CodingError)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn delete_commit_checkpoint(
+        &mut self,
+        _batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        self.0.borrow_mut().remove("commit_checkpoint");
+        Ok(())
+    }
+
+    fn batch_write_commit_checksum(
+        &mut self,
+        _batch: &mut Self::WriteBatch,
+        checksum: &Hash,
+    ) -> Result<()> {
+        self.0
+            .borrow_mut()
+            .insert("commit_checksum".into(), types::encode(checksum));
+        Ok(())
+    }
+
+    fn read_commit_checksum(&self) -> Result<Option<Hash>> {
+        match self.0.borrow().get("commit_checksum") {
+            Some(bytes) => {
+                Ok(Some(types::decode(bytes).map_err(Error::CodingError)?))
+            }
+            None => Ok(None),
+        }
+    }
+
     fn batch_write_subspace_val(
         &self,
         _batch: &mut Self::WriteBatch,
@@ -627,6 +701,22 @@ impl DB for MockDB {
 
         Ok(())
     }
+
+    fn read_indexed_tx(&self, hash: &Hash) -> Result<Option<Vec<u8>>> {
+        Ok(self.0.borrow().get(&format!("indexed_txs/{hash}")).cloned())
+    }
+
+    fn batch_write_indexed_tx(
+        &self,
+        _batch: &mut Self::WriteBatch,
+        hash: &Hash,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        self.0
+            .borrow_mut()
+            .insert(format!("indexed_txs/{hash}"), value.as_ref().to_vec());
+        Ok(())
+    }
 }
 
 impl<'iter> DBIter<'iter> for MockDB {
@@ -765,7 +855,12 @@ impl Iterator for PrefixIterator<MockIterator> {
     }
 }
 
-impl DBWriteBatch for MockDBWriteBatch {}
+impl DBWriteBatch for MockDBWriteBatch {
+    fn checksum(&self) -> Hash {
+        // The writes are not buffered in the batch
+        Hash::default()
+    }
+}
 
 fn unknown_key_error(key: &str) -> Result<()> {
     Err(Error::UnknownKey {
@@ -11,7 +11,9 @@ pub mod write_log;
 
 use core::fmt::Debug;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::format;
+use std::sync::Arc;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
@@ -25,7 +27,9 @@ pub use wl_storage::{
 };
 
 use super::gas::MEMORY_ACCESS_GAS_PER_BYTE;
-use crate::ledger::eth_bridge::storage::bridge_pool::is_pending_transfer_key;
+use crate::ledger::eth_bridge::storage::bridge_pool::{
+    is_pending_transfer_key, BridgePoolTree,
+};
 use crate::ledger::gas::{
     STORAGE_ACCESS_GAS_PER_BYTE, STORAGE_WRITE_GAS_PER_BYTE,
 };
@@ -57,6 +61,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// it has 2 blocks delay on validator set update.
 pub const EPOCH_SWITCH_BLOCKS_DELAY: u32 = 2;
 
+/// The number of recently committed Bridge pool merkle trees kept in memory.
+pub const BRIDGE_POOL_TREES_CACHE_LEN: usize = 8;
+
 /// The storage data
 #[derive(Debug)]
 pub struct Storage<D, H>
@@ -113,6 +120,14 @@ where
     pub eth_events_queue: EthEventsQueue,
     /// How many block heights in the past can the storage be queried
     pub storage_read_past_height_limit: Option<u64>,
+    /// The Bridge pool merkle trees of the most recently committed blocks,
+    /// cached between commits to construct relay proofs without rebuilding
+    /// the trees from the DB
+    pub bridge_pool_trees: BTreeMap<BlockHeight, Arc<BridgePoolTree>>,
+    /// The encoded results of the txs applied in the current block, to be
+    /// written to the index of applied txs by their hash when the block is
+    /// committed. This index is local to the node and not part of the state.
+    pub indexed_txs: Vec<(Hash, Vec<u8>)>,
 }
 
 /// Last committed block
@@ -172,6 +187,11 @@ pub enum Error {
     NoMerkleTree { height: BlockHeight },
     #[error("Code hash error: {0}")]
     InvalidCodeHash(HashError),
+    #[error(
+        "Cannot recover from the interrupted commit of the block at height \
+         {height}: {error}"
+    )]
+    InterruptedCommit { height: BlockHeight, error: String },
 }
 
 /// The block's state as stored in the database.
@@ -209,6 +229,36 @@ pub struct BlockStateRead {
     pub eth_events_queue: EthEventsQueue,
 }
 
+/// Recovery record persisted right before the write batch of a block commit
+/// is applied, and deleted atomically with the application of the batch. Its
+/// presence on startup means that the last block commit was interrupted.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct CommitCheckpoint {
+    /// Height of the block being committed
+    pub height: BlockHeight,
+    /// Expected merkle root once the block is committed
+    pub merkle_root: Hash,
+    /// Checksum of the contents of the write batch
+    pub batch_checksum: Hash,
+}
+
+/// The stages of loading the last committed state from the DB, reported by
+/// [`Storage::load_last_state_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStage {
+    /// Reading the last committed block from the DB
+    ReadingLastBlock,
+    /// Restoring the Merkle tree from the diffs in the DB
+    RebuildingMerkleTree {
+        /// Number of block heights whose diffs have been applied
+        restored: u64,
+        /// Total number of block heights whose diffs must be applied
+        total: u64,
+    },
+    /// Recovering from an interrupted block commit
+    RecoveringCommit,
+}
+
 /// The block's state to write into the database.
 pub struct BlockStateWrite<'a> {
     /// Merkle tree stores
@@ -329,6 +379,33 @@ pub trait DB: std::fmt::Debug {
     /// Execute write batch.
     fn exec_batch(&mut self, batch: Self::WriteBatch) -> Result<()>;
 
+    /// Durably persist a [`CommitCheckpoint`], before applying the write
+    /// batch of a block commit.
+    fn write_commit_checkpoint(
+        &mut self,
+        checkpoint: &CommitCheckpoint,
+    ) -> Result<()>;
+
+    /// Read the [`CommitCheckpoint`] of an interrupted block commit, if any.
+    fn read_commit_checkpoint(&self) -> Result<Option<CommitCheckpoint>>;
+
+    /// Batch delete the [`CommitCheckpoint`].
+    fn delete_commit_checkpoint(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()>;
+
+    /// Batch write the checksum of the write batch of a block commit, to be
+    /// checked against its [`CommitCheckpoint`] on recovery.
+    fn batch_write_commit_checksum(
+        &mut self,
+        batch: &mut Self::WriteBatch,
+        checksum: &Hash,
+    ) -> Result<()>;
+
+    /// Read the checksum of the write batch of the last block commit, if any.
+    fn read_commit_checksum(&self) -> Result<Option<Hash>>;
+
     /// Batch write the value with the given height and account subspace key to
     /// the DB. Returns the size difference from previous value, if any, or
     /// the size of the value otherwise.
@@ -378,6 +455,19 @@ pub trait DB: std::fmt::Debug {
         batch: &mut Self::WriteBatch,
         key: &Key,
     ) -> Result<()>;
+
+    /// Read the encoded result of the applied tx with the given hash from the
+    /// index of applied txs, if any
+    fn read_indexed_tx(&self, hash: &Hash) -> Result<Option<Vec<u8>>>;
+
+    /// Batch write the encoded result of the applied tx with the given hash
+    /// to the index of applied txs
+    fn batch_write_indexed_tx(
+        &self,
+        batch: &mut Self::WriteBatch,
+        hash: &Hash,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()>;
 }
 
 /// A database prefix iterator.
@@ -415,7 +505,10 @@ pub trait DBIter<'iter> {
 }
 
 /// Atomic batch write.
-pub trait DBWriteBatch {}
+pub trait DBWriteBatch {
+    /// Checksum of the contents of the batch.
+    fn checksum(&self) -> Hash;
+}
 
 impl<D, H> Storage<D, H>
 where
@@ -459,12 +552,33 @@ where
             ethereum_height: None,
             eth_events_queue: EthEventsQueue::default(),
             storage_read_past_height_limit,
+            bridge_pool_trees: BTreeMap::new(),
+            indexed_txs: Vec::new(),
         }
     }
 
     /// Load the full state at the last committed height, if any. Returns the
     /// Merkle root hash and the height of the committed block.
     pub fn load_last_state(&mut self) -> Result<()> {
+        self.load_last_state_with_progress(|_| {})
+    }
+
+    /// Load the full state at the last committed height, if any, reporting
+    /// each [`LoadStage`] to `progress` as it is reached.
+    pub fn load_last_state_with_progress(
+        &mut self,
+        mut progress: impl FnMut(LoadStage),
+    ) -> Result<()> {
+        progress(LoadStage::ReadingLastBlock);
+        let checkpoint = self.db.read_commit_checkpoint()?;
+        let last_block =
+            self.db.read_last_block().map_err(|err| match &checkpoint {
+                Some(checkpoint) => Error::InterruptedCommit {
+                    height: checkpoint.height,
+                    error: format!("Failed to read the last block: {err}"),
+                },
+                None => err,
+            })?;
         if let Some(BlockStateRead {
             merkle_tree_stores,
             hash,
@@ -481,7 +595,7 @@ where
             tx_queue,
             ethereum_height,
             eth_events_queue,
-        }) = self.db.read_last_block()?
+        }) = last_block
         {
             self.block.hash = hash.clone();
             self.block.height = height;
@@ -495,8 +609,13 @@ where
             self.update_epoch_blocks_delay = update_epoch_blocks_delay;
             self.address_gen = address_gen;
             // Rebuild Merkle tree
-            self.block.tree = MerkleTree::new(merkle_tree_stores)
-                .or_else(|_| self.rebuild_full_merkle_tree(height))?;
+            let mut report_restored = |restored, total| {
+                progress(LoadStage::RebuildingMerkleTree { restored, total })
+            };
+            self.block.tree =
+                MerkleTree::new(merkle_tree_stores).or_else(|_| {
+                    self.rebuild_full_merkle_tree(height, &mut report_restored)
+                })?;
             self.conversion_state = conversion_state;
             self.tx_queue = tx_queue;
             self.ethereum_height = ethereum_height;
@@ -505,9 +624,64 @@ where
         } else {
             tracing::info!("No state could be found");
         }
+        if let Some(checkpoint) = checkpoint {
+            progress(LoadStage::RecoveringCommit);
+            self.recover_interrupted_commit(checkpoint)?;
+        }
         Ok(())
     }
 
+    /// Recover from a block commit that was interrupted before completion,
+    /// as recorded by the given [`CommitCheckpoint`]. If the write batch of
+    /// the commit was not applied, the block will simply be replayed.
+    /// Otherwise, the committed state and the checksum of the applied batch
+    /// must match the checkpoint.
+    fn recover_interrupted_commit(
+        &mut self,
+        checkpoint: CommitCheckpoint,
+    ) -> Result<()> {
+        let last_height = self.get_last_block_height();
+        if last_height == checkpoint.height {
+            let merkle_root = Hash(self.block.tree.root().0);
+            if merkle_root != checkpoint.merkle_root {
+                return Err(Error::InterruptedCommit {
+                    height: checkpoint.height,
+                    error: format!(
+                        "Expected the merkle root {}, but found {}",
+                        checkpoint.merkle_root, merkle_root
+                    ),
+                });
+            }
+            let batch_checksum = self.db.read_commit_checksum()?;
+            if batch_checksum.as_ref() != Some(&checkpoint.batch_checksum) {
+                return Err(Error::InterruptedCommit {
+                    height: checkpoint.height,
+                    error: format!(
+                        "Expected the batch checksum {}, but found {:?}",
+                        checkpoint.batch_checksum, batch_checksum
+                    ),
+                });
+            }
+        } else if last_height.next_height() != checkpoint.height {
+            return Err(Error::InterruptedCommit {
+                height: checkpoint.height,
+                error: format!(
+                    "The last committed block height {last_height} does not \
+                     precede the height of the interrupted commit"
+                ),
+            });
+        }
+        tracing::warn!(
+            height = %checkpoint.height,
+            batch_checksum = %checkpoint.batch_checksum,
+            applied = last_height == checkpoint.height,
+            "Recovered from an interrupted block commit"
+        );
+        let mut batch = D::batch();
+        self.db.delete_commit_checkpoint(&mut batch)?;
+        self.db.exec_batch(batch)
+    }
+
     /// Returns the Merkle root hash and the height of the committed block. If
     /// no block exists, returns None.
     pub fn get_state(&self) -> Option<(MerkleRoot, u64)> {
@@ -575,7 +749,64 @@ where
             // prune old merkle tree stores
             self.prune_merkle_tree_stores(&mut batch)?;
         }
-        self.db.exec_batch(batch)
+        for (hash, indexed_tx) in std::mem::take(&mut self.indexed_txs) {
+            self.db
+                .batch_write_indexed_tx(&mut batch, &hash, indexed_tx)?;
+        }
+        // Persist a checkpoint to be able to recover, should the process die
+        // before the batch is applied
+        let checkpoint = CommitCheckpoint {
+            height: self.block.height,
+            merkle_root: Hash(self.block.tree.root().0),
+            batch_checksum: batch.checksum(),
+        };
+        self.db.write_commit_checkpoint(&checkpoint)?;
+        // The checksum is written with the batch, to verify on recovery that
+        // the applied batch is the one recorded by the checkpoint
+        self.db.batch_write_commit_checksum(
+            &mut batch,
+            &checkpoint.batch_checksum,
+        )?;
+        self.db.delete_commit_checkpoint(&mut batch)?;
+        self.db.exec_batch(batch)?;
+        self.cache_bridge_pool_tree();
+        Ok(())
+    }
+
+    /// Cache the Bridge pool merkle tree of the block that was just
+    /// committed, evicting the oldest cached tree if the cache is full. The
+    /// tree of the previous block is shared if the pool didn't change.
+    fn cache_bridge_pool_tree(&mut self) {
+        let tree = self.block.tree.bridge_pool_tree();
+        let tree = match self.bridge_pool_trees.last_key_value() {
+            Some((_, last)) if last.root() == tree.root() => last.clone(),
+            _ => Arc::new(tree.clone()),
+        };
+        self.bridge_pool_trees.insert(self.block.height, tree);
+        while self.bridge_pool_trees.len() > BRIDGE_POOL_TREES_CACHE_LEN {
+            _ = self.bridge_pool_trees.pop_first();
+        }
+    }
+
+    /// Get the Bridge pool merkle tree at the given height, from the cache
+    /// of recently committed trees if possible. The heights at which the
+    /// transfers were added to the pool are only accurate if the tree is
+    /// read from the DB, therefore the returned tree should only be used to
+    /// construct proofs.
+    pub fn get_bridge_pool_tree(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Arc<BridgePoolTree>> {
+        let height = if height == BlockHeight(0) {
+            self.get_last_block_height()
+        } else {
+            height
+        };
+        if let Some(tree) = self.bridge_pool_trees.get(&height) {
+            return Ok(tree.clone());
+        }
+        let tree = self.get_merkle_tree(height, Some(StoreType::BridgePool))?;
+        Ok(Arc::new(tree.bridge_pool_tree().clone()))
     }
 
     /// Find the root hash of the merkle tree
@@ -778,8 +1009,9 @@ where
     fn rebuild_full_merkle_tree(
         &self,
         height: BlockHeight,
+        progress: &mut dyn FnMut(u64, u64),
     ) -> Result<MerkleTree<H>> {
-        self.get_merkle_tree(height, None)
+        self.restore_merkle_tree(height, None, progress)
     }
 
     /// Rebuild Merkle tree with diffs in the DB.
@@ -789,6 +1021,19 @@ where
         &self,
         height: BlockHeight,
         store_type: Option<StoreType>,
+    ) -> Result<MerkleTree<H>> {
+        self.restore_merkle_tree(height, store_type, &mut |_, _| {})
+    }
+
+    /// Rebuild Merkle tree with diffs in the DB, as in
+    /// [`Storage::get_merkle_tree`]. After the diffs of each block height
+    /// are applied, `progress` is called with the number of restored
+    /// heights and the total number of heights to restore.
+    fn restore_merkle_tree(
+        &self,
+        height: BlockHeight,
+        store_type: Option<StoreType>,
+        progress: &mut dyn FnMut(u64, u64),
     ) -> Result<MerkleTree<H>> {
         // `0` means last committed height
         let height = if height == BlockHeight(0) {
@@ -818,6 +1063,7 @@ where
             None => MerkleTree::<H>::new(stores).expect("invalid stores"),
         };
         // Restore the tree state with diffs
+        let total = height.0.saturating_sub(epoch_start_height.0);
         let mut target_height = epoch_start_height;
         while target_height < height {
             target_height = target_height.next_height();
@@ -894,6 +1140,7 @@ where
                     (None, None) => break,
                 }
             }
+            progress(target_height.0 - epoch_start_height.0, total);
         }
         if let Some(st) = store_type {
             // Add the base tree with the given height
@@ -1215,6 +1462,19 @@ where
             .unwrap_or_default()
     }
 
+    /// Get the oldest height whose state is retained. The Merkle tree stores
+    /// are pruned up to the epoch before the oldest epoch, so the state is
+    /// only retained from the first height of the oldest epoch.
+    pub fn get_oldest_height(&self) -> BlockHeight {
+        if self.storage_read_past_height_limit.is_none() {
+            return BlockHeight::default();
+        }
+        self.block
+            .pred_epochs
+            .get_start_height_of_epoch(self.get_oldest_epoch())
+            .unwrap_or_default()
+    }
+
     /// Get oldest epoch which has the valid signed nonce of the bridge pool
     pub fn get_oldest_epoch_with_valid_nonce(&self) -> Result<Epoch> {
         let last_height = self.get_last_block_height();
@@ -1338,6 +1598,8 @@ pub mod testing {
                 ethereum_height: None,
                 eth_events_queue: EthEventsQueue::default(),
                 storage_read_past_height_limit: Some(1000),
+                bridge_pool_trees: BTreeMap::new(),
+                indexed_txs: Vec::new(),
             }
         }
     }
@@ -1578,4 +1840,74 @@ mod tests {
             assert_eq!(wl_storage.storage.block.epoch, epoch_before.next());
         }
     }
+
+    /// Test that interrupted block commits are recovered from on startup.
+    #[test]
+    fn test_interrupted_commit_recovery() {
+        let mut storage = TestStorage::default();
+        storage.block.height = BlockHeight(1);
+        storage.commit_block(mockdb::MockDB::batch()).unwrap();
+        // A completed commit leaves no checkpoint behind
+        assert!(storage.db.read_commit_checkpoint().unwrap().is_none());
+
+        // The commit of the next block was interrupted before its batch
+        // was applied, so the block will be replayed
+        let checkpoint = CommitCheckpoint {
+            height: BlockHeight(2),
+            merkle_root: Hash::default(),
+            batch_checksum: Hash::default(),
+        };
+        storage.db.write_commit_checkpoint(&checkpoint).unwrap();
+        storage.load_last_state().unwrap();
+        assert_eq!(storage.get_last_block_height(), BlockHeight(1));
+        assert!(storage.db.read_commit_checkpoint().unwrap().is_none());
+
+        // The batch of the last block was applied, but the committed state
+        // doesn't match the checkpoint
+        let checkpoint = CommitCheckpoint {
+            height: BlockHeight(1),
+            merkle_root: Hash([1; 32]),
+            ..checkpoint
+        };
+        storage.db.write_commit_checkpoint(&checkpoint).unwrap();
+        assert!(matches!(
+            storage.load_last_state(),
+            Err(Error::InterruptedCommit { .. })
+        ));
+
+        // The committed state matches the checkpoint, but the applied batch
+        // is not the one it recorded
+        let merkle_root = Hash(storage.block.tree.root().0);
+        let checkpoint = CommitCheckpoint {
+            merkle_root,
+            batch_checksum: Hash([2; 32]),
+            ..checkpoint
+        };
+        storage.db.write_commit_checkpoint(&checkpoint).unwrap();
+        assert!(matches!(
+            storage.load_last_state(),
+            Err(Error::InterruptedCommit { .. })
+        ));
+
+        // The applied batch matches the checkpoint
+        let checkpoint = CommitCheckpoint {
+            batch_checksum: Hash::default(),
+            ..checkpoint
+        };
+        storage.db.write_commit_checkpoint(&checkpoint).unwrap();
+        storage.load_last_state().unwrap();
+        assert_eq!(storage.get_last_block_height(), BlockHeight(1));
+        assert!(storage.db.read_commit_checkpoint().unwrap().is_none());
+
+        // The checkpoint is inconsistent with the last committed block
+        let checkpoint = CommitCheckpoint {
+            height: BlockHeight(5),
+            ..checkpoint
+        };
+        storage.db.write_commit_checkpoint(&checkpoint).unwrap();
+        assert!(matches!(
+            storage.load_last_state(),
+            Err(Error::InterruptedCommit { .. })
+        ));
+    }
 }
@@ -394,6 +394,13 @@ impl WriteLog {
         self.tx_write_log.keys().cloned().collect()
     }
 
+    /// Get the storage keys changed and accounts keys initialized in the
+    /// current block, by all the transactions committed so far as well as
+    /// by the protocol.
+    pub fn get_block_keys(&self) -> BTreeSet<storage::Key> {
+        self.block_write_log.keys().cloned().collect()
+    }
+
     /// Get the storage keys changed and accounts keys initialized in the
     /// current transaction and precommit. The account keys point to the
     /// validity predicates of the newly created accounts.
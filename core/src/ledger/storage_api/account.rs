@@ -1,8 +1,11 @@
 //! Cryptographic signature keys storage API
 
+use std::collections::BTreeSet;
+
 use super::*;
 use crate::types::account::AccountPublicKeysMap;
 use crate::types::address::Address;
+use crate::types::hash::Hash;
 use crate::types::key::*;
 use crate::types::storage::Key;
 
@@ -105,3 +108,37 @@ where
     }
     Ok(())
 }
+
+/// Get the hashes of the tx codes allowed to act on the storage of an
+/// account. If [`None`], any tx code is allowed.
+pub fn allowed_tx_codes<S>(
+    storage: &S,
+    owner: &Address,
+) -> Result<Option<BTreeSet<Hash>>>
+where
+    S: StorageRead,
+{
+    storage.read(&allowed_tx_codes_key(owner))
+}
+
+/// Restrict the tx codes allowed to act on the storage of an account to
+/// the given code hashes
+pub fn set_allowed_tx_codes<S>(
+    storage: &mut S,
+    owner: &Address,
+    code_hashes: BTreeSet<Hash>,
+) -> Result<()>
+where
+    S: StorageWrite,
+{
+    storage.write(&allowed_tx_codes_key(owner), code_hashes)
+}
+
+/// Lift the restriction on the tx codes allowed to act on the storage of
+/// an account
+pub fn clear_allowed_tx_codes<S>(storage: &mut S, owner: &Address) -> Result<()>
+where
+    S: StorageWrite,
+{
+    storage.delete(&allowed_tx_codes_key(owner))
+}
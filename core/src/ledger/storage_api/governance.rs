@@ -1,6 +1,6 @@
 //! Governance
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use borsh::BorshDeserialize;
 
@@ -11,14 +11,21 @@ use crate::ledger::governance::storage::proposal::{
     ProposalType, StorageProposal,
 };
 use crate::ledger::governance::storage::vote::StorageProposalVote;
-use crate::ledger::governance::utils::Vote;
+use crate::ledger::governance::utils::{
+    ProposalResult, ProposalStakeSnapshot, ProposalState, ProposalsFilter,
+    ProposalsPage, TallyResult, Vote,
+};
 use crate::ledger::governance::ADDRESS as governance_address;
+use crate::ledger::pgf::ADDRESS as pgf_address;
 use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
 use crate::types::address::Address;
-use crate::types::storage::Epoch;
+use crate::types::dec::Dec;
+use crate::types::storage::{BlockHeight, Epoch};
 use crate::types::transaction::governance::{
-    InitProposalData, VoteProposalData,
+    InitProposalData, VoteProposalData, WithdrawProposalData,
 };
+use crate::types::vote_extensions::validator_set_update::ValidatorSetSnapshot;
+use crate::types::voting_power::FractionalVotingPower;
 
 /// A proposal creation transaction.
 pub fn init_proposal<S>(
@@ -62,6 +69,12 @@ where
         governance_keys::get_voting_start_epoch_key(proposal_id);
     storage.write(&voting_start_epoch_key, data.voting_start_epoch)?;
 
+    let starting_proposals_key = governance_keys::get_starting_proposals_key(
+        proposal_id,
+        data.voting_start_epoch.0,
+    );
+    storage.write(&starting_proposals_key, ())?;
+
     let voting_end_epoch_key =
         governance_keys::get_voting_end_epoch_key(proposal_id);
     storage.write(&voting_end_epoch_key, data.voting_end_epoch)?;
@@ -79,20 +92,40 @@ where
 
     storage.write(&counter_key, proposal_id + 1)?;
 
-    let min_proposal_funds_key = governance_keys::get_min_proposal_fund_key();
+    let min_proposal_funds_key = governance_keys::get_min_proposal_fund_key_for(
+        &data.r#type,
+        data.expedited,
+    );
     let min_proposal_funds: token::Amount =
         storage.read(&min_proposal_funds_key)?.unwrap();
 
     let funds_key = governance_keys::get_funds_key(proposal_id);
     storage.write(&funds_key, min_proposal_funds)?;
 
-    // this key must always be written for each proposal
-    let committing_proposals_key =
-        governance_keys::get_committing_proposals_key(
-            proposal_id,
-            data.grace_epoch.0,
-        );
-    storage.write(&committing_proposals_key, ())?;
+    if data.expedited {
+        // expedited proposals are voted on for a number of blocks from their
+        // submission, and tallied when their voting window closes
+        let voting_period = get_expedited_proposal_voting_period(storage)?;
+        let voting_end_height = storage.get_block_height()? + voting_period;
+        let voting_end_height_key =
+            governance_keys::get_voting_end_height_key(proposal_id);
+        storage.write(&voting_end_height_key, voting_end_height)?;
+
+        let expedited_proposals_key =
+            governance_keys::get_expedited_proposals_key(
+                proposal_id,
+                voting_end_height.0,
+            );
+        storage.write(&expedited_proposals_key, ())?;
+    } else {
+        // this key must always be written for each non-expedited proposal
+        let committing_proposals_key =
+            governance_keys::get_committing_proposals_key(
+                proposal_id,
+                data.grace_epoch.0,
+            );
+        storage.write(&committing_proposals_key, ())?;
+    }
 
     token::transfer(
         storage,
@@ -122,6 +155,75 @@ where
     Ok(())
 }
 
+/// A proposal withdrawal transaction. The proposal is removed from storage
+/// and its locked funds are refunded to its author, minus the share that is
+/// not refunded according to the `proposal_withdrawal_refund` parameter, which
+/// is transferred to the PGF treasury.
+pub fn withdraw_proposal<S>(
+    storage: &mut S,
+    data: WithdrawProposalData,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let proposal = get_proposal_by_id(storage, data.id)?
+        .ok_or(storage_api::Error::new_const("Proposal does not exist"))?;
+    if proposal.author != data.author {
+        return Err(storage_api::Error::new_const(
+            "Only the author of a proposal can withdraw it",
+        ));
+    }
+    // NOTE: expedited proposals can never be withdrawn, as their voting
+    // period starts in the epoch in which they are submitted
+    if storage.get_block_epoch()? >= proposal.voting_start_epoch {
+        return Err(storage_api::Error::new_const(
+            "The voting period of the proposal has already started",
+        ));
+    }
+
+    let funds_key = governance_keys::get_funds_key(data.id);
+    let funds: token::Amount = storage.read(&funds_key)?.unwrap_or_default();
+    let refund = get_proposal_withdrawal_refund(storage)?;
+    let refunded_funds = std::cmp::min(refund * funds, funds);
+
+    for key in [
+        governance_keys::get_content_key(data.id),
+        governance_keys::get_author_key(data.id),
+        governance_keys::get_proposal_type_key(data.id),
+        governance_keys::get_proposal_code_key(data.id),
+        governance_keys::get_voting_start_epoch_key(data.id),
+        governance_keys::get_voting_end_epoch_key(data.id),
+        governance_keys::get_grace_epoch_key(data.id),
+        governance_keys::get_committing_proposals_key(
+            data.id,
+            proposal.grace_epoch.0,
+        ),
+        governance_keys::get_starting_proposals_key(
+            data.id,
+            proposal.voting_start_epoch.0,
+        ),
+        funds_key,
+    ] {
+        storage.delete(&key)?;
+    }
+
+    let native_token = storage.get_native_token()?;
+    token::transfer(
+        storage,
+        &native_token,
+        &governance_address,
+        &proposal.author,
+        refunded_funds,
+    )?;
+    token::transfer(
+        storage,
+        &native_token,
+        &governance_address,
+        &pgf_address,
+        funds - refunded_funds,
+    )
+}
+
 /// Read a proposal by id from storage
 pub fn get_proposal_by_id<S>(
     storage: &S,
@@ -136,6 +238,7 @@ where
     let end_epoch_key = governance_keys::get_voting_end_epoch_key(id);
     let grace_epoch_key = governance_keys::get_grace_epoch_key(id);
     let proposal_type_key = governance_keys::get_proposal_type_key(id);
+    let voting_end_height_key = governance_keys::get_voting_end_height_key(id);
 
     let author: Option<Address> = storage.read(&author_key)?;
     let content: Option<BTreeMap<String, String>> = storage.read(&content)?;
@@ -144,6 +247,8 @@ where
     let grace_epoch: Option<Epoch> = storage.read(&grace_epoch_key)?;
     let proposal_type: Option<ProposalType> =
         storage.read(&proposal_type_key)?;
+    let voting_end_height: Option<BlockHeight> =
+        storage.read(&voting_end_height_key)?;
 
     let proposal = proposal_type.map(|proposal_type| StorageProposal {
         id,
@@ -153,6 +258,7 @@ where
         voting_start_epoch: voting_start_epoch.unwrap(),
         voting_end_epoch: voting_end_epoch.unwrap(),
         grace_epoch: grace_epoch.unwrap(),
+        voting_end_height,
     });
 
     Ok(proposal)
@@ -247,6 +353,28 @@ where
 
     let max_proposal_period: u64 = get_max_proposal_period(storage)?;
 
+    let min_proposal_execution_delay: u64 =
+        get_min_proposal_execution_delay(storage)?;
+
+    let key = governance_keys::get_min_expedited_proposal_fund_key();
+    let min_expedited_proposal_fund: token::Amount =
+        storage.read(&key)?.expect("Parameter should be defined.");
+
+    let expedited_proposal_voting_period: u64 =
+        get_expedited_proposal_voting_period(storage)?;
+
+    let max_proposal_execution_gas: u64 =
+        get_max_proposal_execution_gas(storage)?;
+
+    let key = governance_keys::get_min_text_proposal_fund_key();
+    let min_text_proposal_fund: token::Amount =
+        storage.read(&key)?.expect("Parameter should be defined.");
+
+    let proposal_withdrawal_refund: Dec =
+        get_proposal_withdrawal_refund(storage)?;
+
+    let veto_threshold: FractionalVotingPower = get_veto_threshold(storage)?;
+
     Ok(GovernanceParameters {
         min_proposal_fund,
         max_proposal_code_size,
@@ -254,6 +382,13 @@ where
         max_proposal_period,
         max_proposal_content_size,
         min_proposal_grace_epochs,
+        min_proposal_execution_delay,
+        min_expedited_proposal_fund,
+        expedited_proposal_voting_period,
+        max_proposal_execution_gas,
+        min_text_proposal_fund,
+        proposal_withdrawal_refund,
+        veto_threshold,
     })
 }
 
@@ -267,3 +402,263 @@ where
         storage.read(&key)?.expect("Parameter should be defined.");
     Ok(max_proposal_period)
 }
+
+/// Get governance "min_proposal_execution_delay" parameter
+pub fn get_min_proposal_execution_delay<S>(
+    storage: &S,
+) -> storage_api::Result<u64>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_min_proposal_execution_delay_key();
+    let min_proposal_execution_delay: u64 =
+        storage.read(&key)?.expect("Parameter should be defined.");
+    Ok(min_proposal_execution_delay)
+}
+
+/// Get the epoch at which a passed proposal is scheduled to be executed, if
+/// its execution has been delayed
+pub fn get_proposal_execution_epoch<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<Option<Epoch>>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_proposal_execution_epoch_key(proposal_id);
+    storage.read(&key)
+}
+
+/// Get governance "expedited_proposal_voting_period" parameter
+pub fn get_expedited_proposal_voting_period<S>(
+    storage: &S,
+) -> storage_api::Result<u64>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_expedited_proposal_voting_period_key();
+    let expedited_proposal_voting_period: u64 =
+        storage.read(&key)?.expect("Parameter should be defined.");
+    Ok(expedited_proposal_voting_period)
+}
+
+/// Get governance "max_proposal_execution_gas" parameter
+pub fn get_max_proposal_execution_gas<S>(
+    storage: &S,
+) -> storage_api::Result<u64>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_max_proposal_execution_gas_key();
+    let max_proposal_execution_gas: u64 =
+        storage.read(&key)?.expect("Parameter should be defined.");
+    Ok(max_proposal_execution_gas)
+}
+
+/// Get governance "proposal_withdrawal_refund" parameter
+pub fn get_proposal_withdrawal_refund<S>(
+    storage: &S,
+) -> storage_api::Result<Dec>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_proposal_withdrawal_refund_key();
+    let proposal_withdrawal_refund: Dec =
+        storage.read(&key)?.expect("Parameter should be defined.");
+    Ok(proposal_withdrawal_refund)
+}
+
+/// Get governance "veto_threshold" parameter
+pub fn get_veto_threshold<S>(
+    storage: &S,
+) -> storage_api::Result<FractionalVotingPower>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_veto_threshold_key();
+    let veto_threshold: FractionalVotingPower =
+        storage.read(&key)?.expect("Parameter should be defined.");
+    Ok(veto_threshold)
+}
+
+/// Check if the code of a passed proposal failed to execute
+pub fn is_proposal_execution_failed<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<bool>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_proposal_execution_failed_key(proposal_id);
+    storage.has_key(&key)
+}
+
+/// Get the snapshot of the voting power taken when a proposal entered its
+/// voting period, if any
+pub fn get_proposal_stake_snapshot<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<Option<ProposalStakeSnapshot>>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_proposal_stake_snapshot_key(proposal_id);
+    storage.read(&key)
+}
+
+/// Get the snapshot of the Ethereum bridge validator sets taken when a
+/// proposal that changes the bridge entered its voting period, if any
+pub fn get_proposal_bridge_valset_snapshot<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<Option<ValidatorSetSnapshot>>
+where
+    S: storage_api::StorageRead,
+{
+    let key =
+        governance_keys::get_proposal_bridge_valset_snapshot_key(proposal_id);
+    storage.read(&key)
+}
+
+/// Get the ids of the proposals committed to the given epoch, which are
+/// tallied or executed at the start of the following epoch
+pub fn get_committed_proposal_ids<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<BTreeSet<u64>>
+where
+    S: storage_api::StorageRead,
+{
+    let prefix = governance_keys::get_commiting_proposals_prefix(epoch.0);
+    let mut ids = BTreeSet::new();
+    for entry in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _) = entry?;
+        // NOTE: the prefix of an epoch also matches greater epochs starting
+        // with the same digits
+        if governance_keys::get_commit_proposal_epoch(&key) != Some(epoch.0) {
+            continue;
+        }
+        if let Some(id) = governance_keys::get_commit_proposal_id(&key) {
+            ids.insert(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Get the ids of the proposals whose voting period starts at the given epoch
+pub fn get_proposal_ids_starting_at<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<u64>>
+where
+    S: storage_api::StorageRead,
+{
+    let prefix = governance_keys::get_starting_proposals_prefix(epoch.0);
+    let mut ids = BTreeSet::new();
+    for entry in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _) = entry?;
+        // NOTE: the prefix of an epoch also matches greater epochs starting
+        // with the same digits
+        if governance_keys::get_starting_proposal_epoch(&key) != Some(epoch.0)
+        {
+            continue;
+        }
+        if let Some(id) = governance_keys::get_starting_proposal_id(&key) {
+            ids.insert(id);
+        }
+    }
+    Ok(ids.into_iter().collect())
+}
+
+/// Get the block height from which voting on a proposal is stopped, if the
+/// proposal is expedited
+pub fn get_proposal_voting_end_height<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<Option<BlockHeight>>
+where
+    S: storage_api::StorageRead,
+{
+    let key = governance_keys::get_voting_end_height_key(proposal_id);
+    storage.read(&key)
+}
+
+/// Get the state of a proposal in its lifecycle at the given epoch
+pub fn get_proposal_state<S>(
+    storage: &S,
+    proposal: &StorageProposal,
+    current_epoch: Epoch,
+) -> storage_api::Result<ProposalState>
+where
+    S: storage_api::StorageRead,
+{
+    let result_key = governance_keys::get_proposal_result_key(proposal.id);
+    let result: Option<ProposalResult> = storage.read(&result_key)?;
+    // expedited proposals stop being voted on at a block height
+    let voting_ended = current_epoch >= proposal.voting_end_epoch
+        || match proposal.voting_end_height {
+            Some(height) => storage.get_block_height()? >= height,
+            None => false,
+        };
+    let state = match result.map(|result| result.result) {
+        Some(TallyResult::Rejected) => ProposalState::Rejected,
+        Some(TallyResult::Passed) => {
+            // passed proposals without a delayed execution are executed
+            // right after their tally
+            match get_proposal_execution_epoch(storage, proposal.id)? {
+                Some(execution_epoch) if execution_epoch > current_epoch => {
+                    ProposalState::Passed
+                }
+                _ if is_proposal_execution_failed(storage, proposal.id)? => {
+                    ProposalState::ExecutionFailed
+                }
+                _ => ProposalState::Executed,
+            }
+        }
+        None if proposal.voting_start_epoch > current_epoch => {
+            ProposalState::Pending
+        }
+        None if voting_ended => ProposalState::Tallying,
+        None => ProposalState::Voting,
+    };
+    Ok(state)
+}
+
+/// Get a page of the proposals matching the given filters, ordered by their
+/// id, starting after the `after` id, if any
+pub fn get_proposals_page<S>(
+    storage: &S,
+    current_epoch: Epoch,
+    filter: &ProposalsFilter,
+    after: Option<u64>,
+    limit: usize,
+) -> storage_api::Result<ProposalsPage>
+where
+    S: storage_api::StorageRead,
+{
+    let counter_key = governance_keys::get_counter_key();
+    let last_id: u64 = storage.read(&counter_key)?.unwrap_or_default();
+    let first_id = after.map(|id| id + 1).unwrap_or_default();
+
+    let mut proposals = Vec::new();
+    let mut next = None;
+    for id in first_id..last_id {
+        let Some(proposal) = get_proposal_by_id(storage, id)? else {
+            continue;
+        };
+        let state = get_proposal_state(storage, &proposal, current_epoch)?;
+        if !filter.matches(&proposal, state) {
+            continue;
+        }
+        proposals.push((proposal, state));
+        // stop once the page is full, continuing after its last proposal
+        if proposals.len() >= limit {
+            if id + 1 < last_id {
+                next = Some(id);
+            }
+            break;
+        }
+    }
+
+    Ok(ProposalsPage { proposals, next })
+}
@@ -0,0 +1,189 @@
+//! Hashed time-locked transfers
+
+use super::token;
+use crate::ledger::htlc::{
+    storage as htlc_keys, TimeLock, TimeLockStatus, ADDRESS,
+};
+use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::Address;
+use crate::types::storage::Epoch;
+use crate::types::transaction::htlc::LockTransfer;
+
+/// Read the time lock with the given id.
+pub fn read_time_lock<S>(
+    storage: &S,
+    id: u64,
+) -> storage_api::Result<Option<TimeLock>>
+where
+    S: StorageRead,
+{
+    storage.read(&htlc_keys::get_lock_key(id))
+}
+
+/// Read the pending time lock with the given id.
+fn read_pending_time_lock<S>(
+    storage: &S,
+    id: u64,
+) -> storage_api::Result<TimeLock>
+where
+    S: StorageRead,
+{
+    match read_time_lock(storage, id)? {
+        Some(lock) if lock.is_pending() => Ok(lock),
+        Some(_) => Err(storage_api::Error::new_const(
+            "The time lock has already been settled",
+        )),
+        None => {
+            Err(storage_api::Error::new_const("The time lock doesn't exist"))
+        }
+    }
+}
+
+/// Read the time locks which the given address is either the source or the
+/// receiver of, along with their ids.
+pub fn read_time_locks_of<S>(
+    storage: &S,
+    owner: &Address,
+) -> storage_api::Result<Vec<(u64, TimeLock)>>
+where
+    S: StorageRead,
+{
+    let prefix = htlc_keys::get_locks_prefix();
+    let mut locks = vec![];
+    for item in storage_api::iter_prefix::<TimeLock>(storage, &prefix)? {
+        let (key, lock) = item?;
+        let Some(id) = htlc_keys::is_lock_key(&key) else {
+            continue;
+        };
+        if &lock.source == owner || &lock.receiver == owner {
+            locks.push((id, lock));
+        }
+    }
+    Ok(locks)
+}
+
+/// A time-locked transfer transaction. Returns the id of the new lock.
+pub fn lock_transfer<S>(
+    storage: &mut S,
+    data: LockTransfer,
+) -> storage_api::Result<u64>
+where
+    S: StorageRead + StorageWrite,
+{
+    if data.timelock <= storage.get_block_epoch()? {
+        return Err(storage_api::Error::new_const(
+            "The timelock must expire at a future epoch",
+        ));
+    }
+    let counter_key = htlc_keys::get_counter_key();
+    let id: u64 = storage.read(&counter_key)?.unwrap_or_default();
+    token::transfer(storage, &data.token, &data.source, &ADDRESS, data.amount)?;
+    let lock = TimeLock {
+        source: data.source,
+        receiver: data.receiver,
+        token: data.token,
+        amount: data.amount,
+        hashlock: data.hashlock,
+        timelock: data.timelock,
+        status: TimeLockStatus::Pending,
+    };
+    storage.write(&htlc_keys::get_lock_key(id), lock)?;
+    storage.write(&htlc_keys::get_expiry_key(data.timelock, id), id)?;
+    storage.write(&counter_key, id + 1)?;
+    Ok(id)
+}
+
+/// A transaction claiming the tokens of a time lock with the preimage of
+/// its hashlock, before its timelock expires.
+pub fn claim_time_lock<S>(
+    storage: &mut S,
+    id: u64,
+    preimage: Vec<u8>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let lock = read_pending_time_lock(storage, id)?;
+    if lock.timelock <= storage.get_block_epoch()? {
+        return Err(storage_api::Error::new_const("The timelock has expired"));
+    }
+    if !lock.is_valid_preimage(&preimage) {
+        return Err(storage_api::Error::new_const(
+            "The preimage doesn't match the hashlock",
+        ));
+    }
+    let receiver = lock.receiver.clone();
+    settle_time_lock(
+        storage,
+        id,
+        lock,
+        &receiver,
+        TimeLockStatus::Claimed { preimage },
+    )
+}
+
+/// A transaction refunding the tokens of a time lock to its source. It must
+/// be authorized by the receiver.
+pub fn refund_time_lock<S>(storage: &mut S, id: u64) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let lock = read_pending_time_lock(storage, id)?;
+    let source = lock.source.clone();
+    settle_time_lock(storage, id, lock, &source, TimeLockStatus::Refunded)
+}
+
+/// Settle the pending time locks whose timelock expires at the given epoch.
+/// Hashlocked tokens are refunded to their source, while the others are
+/// released to their receiver. A lock which fails to be settled is skipped,
+/// so as not to hold up the settlement of the others. Returns the ids of the
+/// settled locks.
+pub fn settle_expired_time_locks<S>(
+    storage: &mut S,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<u64>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let prefix = htlc_keys::get_expiry_prefix(epoch);
+    let ids = storage_api::iter_prefix::<u64>(storage, &prefix)?
+        .map(|item| item.map(|(_, id)| id))
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    let mut settled = Vec::with_capacity(ids.len());
+    for id in ids {
+        let result = read_pending_time_lock(storage, id).and_then(|lock| {
+            let beneficiary = lock.expiry_beneficiary().clone();
+            let status = lock.expiry_status();
+            settle_time_lock(storage, id, lock, &beneficiary, status)
+        });
+        match result {
+            Ok(()) => settled.push(id),
+            Err(err) => {
+                tracing::warn!(
+                    id,
+                    %err,
+                    "Failed to settle an expired time lock, skipping it"
+                );
+            }
+        }
+    }
+    Ok(settled)
+}
+
+/// Transfer the tokens of a pending time lock to the `beneficiary`, and
+/// update its status.
+fn settle_time_lock<S>(
+    storage: &mut S,
+    id: u64,
+    mut lock: TimeLock,
+    beneficiary: &Address,
+    status: TimeLockStatus,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    token::transfer(storage, &lock.token, &ADDRESS, beneficiary, lock.amount)?;
+    storage.delete(&htlc_keys::get_expiry_key(lock.timelock, id))?;
+    lock.status = status;
+    storage.write(&htlc_keys::get_lock_key(id), lock)
+}
@@ -5,6 +5,7 @@ pub mod account;
 pub mod collections;
 mod error;
 pub mod governance;
+pub mod htlc;
 pub mod key;
 pub mod pgf;
 pub mod token;
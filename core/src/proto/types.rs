@@ -1420,7 +1420,7 @@ impl Tx {
             // we extract the signed data, but don't check the signature
             TxType::Decrypted(_) => Ok(None),
             // return as is
-            TxType::Raw => Ok(None),
+            TxType::Raw | TxType::View => Ok(None),
         }
     }
 
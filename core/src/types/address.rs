@@ -124,6 +124,7 @@ impl From<raw::Address<'_, raw::Validated>> for Address {
                 InternalAddress::IbcToken(IbcTokenHash(*raw_addr.data())),
             ),
             raw::Discriminant::Masp => Address::Internal(InternalAddress::Masp),
+            raw::Discriminant::Htlc => Address::Internal(InternalAddress::Htlc),
         }
     }
 }
@@ -218,6 +219,11 @@ impl<'addr> From<&'addr Address> for raw::Address<'addr, raw::Validated> {
                     .validate()
                     .expect("This raw address is valid")
             }
+            Address::Internal(InternalAddress::Htlc) => {
+                raw::Address::from_discriminant(raw::Discriminant::Htlc)
+                    .validate()
+                    .expect("This raw address is valid")
+            }
         }
     }
 }
@@ -543,6 +549,8 @@ pub enum InternalAddress {
     Pgf,
     /// Masp
     Masp,
+    /// Hashed time-locked transfers
+    Htlc,
 }
 
 impl Display for InternalAddress {
@@ -564,6 +572,7 @@ impl Display for InternalAddress {
                 Self::Multitoken => "Multitoken".to_string(),
                 Self::Pgf => "PublicGoodFundings".to_string(),
                 Self::Masp => "MASP".to_string(),
+                Self::Htlc => "HTLC".to_string(),
             }
         )
     }
@@ -579,6 +588,7 @@ impl InternalAddress {
             "bridgepool" => Some(InternalAddress::EthBridgePool),
             "governance" => Some(InternalAddress::Governance),
             "masp" => Some(InternalAddress::Masp),
+            "htlc" => Some(InternalAddress::Htlc),
             _ => None,
         }
     }
@@ -864,6 +874,7 @@ pub mod testing {
             InternalAddress::Nut(_) => {}
             InternalAddress::Pgf => {}
             InternalAddress::Masp => {}
+            InternalAddress::Htlc => {}
             InternalAddress::Multitoken => {} /* Add new addresses in the
                                                * `prop_oneof` below. */
         };
@@ -881,6 +892,7 @@ pub mod testing {
             Just(InternalAddress::Multitoken),
             Just(InternalAddress::Pgf),
             Just(InternalAddress::Masp),
+            Just(InternalAddress::Htlc),
         ]
     }
 
@@ -65,6 +65,8 @@ pub enum Discriminant {
     IbcToken = 13,
     /// MASP raw address.
     Masp = 14,
+    /// Hashed time-locked transfers raw address.
+    Htlc = 15,
 }
 
 /// Raw address representation.
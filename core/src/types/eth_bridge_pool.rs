@@ -15,6 +15,7 @@ use crate::types::ethereum_events::{
     EthAddress, TransferToEthereum as TransferToEthereumEvent,
 };
 use crate::types::hash::Hash as HashDigest;
+use crate::types::keccak::KeccakHash;
 use crate::types::storage::{DbKeySeg, Key};
 use crate::types::token::Amount;
 
@@ -293,6 +294,45 @@ pub struct GasFee {
     pub token: Address,
 }
 
+/// Request of the sender of a transfer in the Bridge pool to cancel it.
+///
+/// A transfer may only be cancelled once it has sat unrelayed in the
+/// Bridge pool for the number of epochs set by governance. Since the
+/// signed Merkle roots covering the transfer may still be relayed to
+/// Ethereum, its escrowed tokens are not refunded right away, but once
+/// the nonce of the Bridge pool is bumped or at the start of the next
+/// epoch, whichever comes first, unless it was relayed in the meantime.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct CancelTransfer {
+    /// The hash of the transfer to cancel.
+    pub transfer_hash: KeccakHash,
+}
+
+/// Statistics of the transfers to Ethereum relayed by some relayer,
+/// and of the protocol rewards accrued for relaying them.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct RelayerStats {
+    /// The number of batches of transfers relayed.
+    pub relayed_batches: u64,
+    /// The number of transfers relayed.
+    pub relayed_transfers: u64,
+    /// The total amount of native tokens accrued as rewards.
+    pub rewards: Amount,
+}
+
 #[cfg(test)]
 mod test_eth_bridge_pool_types {
     use super::*;
@@ -10,11 +10,12 @@ use borsh_ext::BorshSerializeExt;
 use ethabi::ethereum_types::{H160, U256 as ethUint};
 use ethabi::Token;
 use eyre::{eyre, Context};
+use num256::Uint256;
 use serde::{Deserialize, Serialize};
 
 use crate::types::address::Address;
 use crate::types::eth_abi::Encode;
-use crate::types::ethereum_structs::Erc20Transfer;
+use crate::types::ethereum_structs::{self, Erc20Transfer};
 use crate::types::hash::Hash;
 use crate::types::keccak::KeccakHash;
 use crate::types::storage::{DbKeySeg, KeySeg};
@@ -68,6 +69,12 @@ impl Uint {
     pub fn checked_increment(self) -> Option<Self> {
         ethUint::from(self).checked_add(1.into()).map(Self::from)
     }
+
+    /// Try to add `rhs` to this [`Uint`], whilst checking
+    /// for overflows.
+    pub fn checked_add(self, rhs: u64) -> Option<Self> {
+        ethUint::from(self).checked_add(rhs.into()).map(Self::from)
+    }
 }
 
 impl Display for Uint {
@@ -106,6 +113,13 @@ impl From<u64> for Uint {
     }
 }
 
+impl From<&ethereum_structs::BlockHeight> for Uint {
+    fn from(height: &ethereum_structs::BlockHeight) -> Self {
+        let height: Uint256 = height.clone().into();
+        ethUint::from_big_endian(&height.0.to_bytes_be()).into()
+    }
+}
+
 impl Add<u64> for Uint {
     type Output = Self;
 
@@ -245,6 +259,27 @@ impl GetEventNonce for TransfersToNamada {
     }
 }
 
+impl TransfersToNamada {
+    /// Split the batches of transfers of an
+    /// [`EthereumEvent::TransfersToNamadaBatch`] into the events they
+    /// were emitted in, in ascending order of nonce. The batches whose
+    /// nonce would overflow are dropped.
+    pub fn from_batch(
+        nonce: Uint,
+        batches: Vec<Vec<TransferToNamada>>,
+    ) -> impl Iterator<Item = Self> {
+        batches
+            .into_iter()
+            .enumerate()
+            .map_while(move |(i, transfers)| {
+                Some(Self {
+                    nonce: nonce.checked_add(i as u64)?,
+                    transfers,
+                })
+            })
+    }
+}
+
 impl From<TransfersToNamada> for EthereumEvent {
     #[inline]
     fn from(event: TransfersToNamada) -> Self {
@@ -265,6 +300,8 @@ impl From<TransfersToNamada> for EthereumEvent {
     BorshSerialize,
     BorshDeserialize,
     BorshSchema,
+    Serialize,
+    Deserialize,
 )]
 pub enum EthereumEvent {
     /// Event transferring batches of ether or Ethereum based ERC20 tokens
@@ -304,6 +341,43 @@ pub enum EthereumEvent {
         #[allow(dead_code)]
         governance_validator_hash: KeccakHash,
     },
+    /// Event transferring batches of ERC-721 tokens from the NFT bridge
+    /// contract on Ethereum to Namada. Bridging NFTs is one-way, there is
+    /// no event transferring them back to Ethereum
+    NftTransfersToNamada {
+        /// Monotonically increasing nonce
+        #[allow(dead_code)]
+        nonce: Uint,
+        /// The batch of transfers
+        #[allow(dead_code)]
+        transfers: Vec<NftTransferToNamada>,
+    },
+    /// Consecutive [`EthereumEvent::TransfersToNamada`] events emitted
+    /// in the same Ethereum block, which are voted on and confirmed
+    /// together
+    TransfersToNamadaBatch {
+        /// The nonce of the first event in the batch
+        #[allow(dead_code)]
+        nonce: Uint,
+        /// The batch of transfers of each event, in ascending order
+        /// of nonce
+        #[allow(dead_code)]
+        batches: Vec<Vec<TransferToNamada>>,
+    },
+    /// Attestation that the handover block of a rotation of the bridge
+    /// contract scheduled by governance has been processed. It is not
+    /// emitted by any contract, but by the oracle itself, such that the
+    /// rotation is applied once enough validators have switched over to
+    /// the new contract
+    BridgeContractHandover {
+        /// The address of the new bridge contract
+        #[allow(dead_code)]
+        new_contract: EthAddress,
+        /// The first Ethereum block whose events are taken from the new
+        /// bridge contract
+        #[allow(dead_code)]
+        handover_height: Uint,
+    },
 }
 
 impl EthereumEvent {
@@ -326,6 +400,8 @@ impl EthereumEvent {
     BorshSerialize,
     BorshDeserialize,
     BorshSchema,
+    Serialize,
+    Deserialize,
 )]
 pub struct TransferToNamada {
     /// Quantity of the ERC20 token in the transfer
@@ -336,6 +412,30 @@ pub struct TransferToNamada {
     pub receiver: Address,
 }
 
+/// An event transferring an ERC-721 token from Ethereum to Namada
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Hash,
+    Ord,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct NftTransferToNamada {
+    /// Address of the ERC-721 contract issuing the token
+    pub contract: EthAddress,
+    /// The id of the token within its contract
+    pub token_id: Uint,
+    /// The address receiving the token on Namada
+    pub receiver: Address,
+}
+
 /// An event transferring some kind of value from Namada to Ethereum
 #[derive(
     Clone,
@@ -394,6 +494,28 @@ pub mod tests {
 
     use super::*;
 
+    /// Test that splitting a batch of transfers to Namada drops the
+    /// batches whose nonce overflows.
+    #[test]
+    fn test_transfers_to_namada_from_batch_overflow() {
+        let nonce = Uint([u64::MAX; 4]) - 1;
+        let events: Vec<_> =
+            TransfersToNamada::from_batch(nonce, vec![vec![]; 3]).collect();
+        assert_eq!(
+            events.iter().map(|event| event.nonce).collect::<Vec<_>>(),
+            vec![nonce, nonce + 1],
+        );
+    }
+
+    /// Test the conversion of Ethereum block heights to [`Uint`].
+    #[test]
+    fn test_uint_from_eth_block_height() {
+        for height in [0, 1, 255, 256, 17_000_000, u64::MAX] {
+            let eth_height = ethereum_structs::BlockHeight::from(height);
+            assert_eq!(Uint::from(&eth_height), Uint::from(height));
+        }
+    }
+
     #[test]
     fn test_eth_address_to_canonical() {
         let canonical = testing::DAI_ERC20_ETH_ADDRESS.to_canonical();
@@ -31,6 +31,7 @@ struct Keys {
     public_keys: &'static str,
     threshold: &'static str,
     protocol_public_keys: &'static str,
+    allowed_tx_codes: &'static str,
 }
 
 /// Obtain a storage key for user's public key.
@@ -110,6 +111,30 @@ pub fn is_protocol_pk_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Obtain the storage key of the hashes of the tx codes allowed to act on
+/// the storage of an account.
+pub fn allowed_tx_codes_key(owner: &Address) -> storage::Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(owner.to_owned()),
+            DbKeySeg::StringSeg(Keys::VALUES.allowed_tx_codes.to_string()),
+        ],
+    }
+}
+
+/// Check if the given storage key is the key of the tx codes allowed to act
+/// on the storage of an account. If it is, returns the owner.
+pub fn is_allowed_tx_codes_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key.as_str() == Keys::VALUES.allowed_tx_codes =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
 /// Represents an error in signature verification
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
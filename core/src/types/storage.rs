@@ -1383,6 +1383,16 @@ impl<E: GetEventNonce> InnerEthEventsQueue<E> {
         }
     }
 
+    /// Check if an event with the given nonce has already been processed,
+    /// or is queued to be processed.
+    pub fn contains_nonce(&self, nonce: Uint) -> bool {
+        nonce < self.next_nonce_to_process
+            || self
+                .inner
+                .binary_search_by_key(&nonce, GetEventNonce::get_event_nonce)
+                .is_ok()
+    }
+
     /// Provide a reference to the earliest event stored in the queue.
     #[inline]
     fn peek_event_nonce(&self) -> Option<Uint> {
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -49,4 +51,7 @@ pub struct UpdateAccount {
     pub public_keys: Vec<common::PublicKey>,
     /// The account signature threshold
     pub threshold: Option<u8>,
+    /// The hashes of the tx codes allowed to act on the storage of the
+    /// account. An empty set lifts the restriction.
+    pub allowed_tx_codes: Option<BTreeSet<Hash>>,
 }
@@ -5,10 +5,13 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::ledger::governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    CodeAllowlistProposal, ConsensusParamsProposal, DefaultProposal,
+    ParameterChangeProposal, PgfFundingProposal, PgfStewardProposal,
+    TextProposal, TreasurySpendProposal,
 };
 use crate::ledger::governance::storage::proposal::{
-    AddRemove, PGFAction, PGFTarget, ProposalType,
+    AddRemove, CodeAllowlistUpdate, PGFAction, PGFTarget, ParameterChange,
+    ProposalType,
 };
 use crate::ledger::governance::storage::vote::StorageProposalVote;
 use crate::types::address::Address;
@@ -47,6 +50,9 @@ pub struct InitProposalData {
     pub voting_end_epoch: Epoch,
     /// The epoch from which this changes are executed
     pub grace_epoch: Epoch,
+    /// Whether the proposal is voted on in the expedited track, for a
+    /// number of blocks from its submission
+    pub expedited: bool,
 }
 
 impl InitProposalData {
@@ -80,6 +86,23 @@ pub struct VoteProposalData {
     pub delegations: Vec<Address>,
 }
 
+/// A tx data type to hold the data to withdraw a proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct WithdrawProposalData {
+    /// The proposal id
+    pub id: u64,
+    /// The proposal author address
+    pub author: Address,
+}
+
 impl TryFrom<DefaultProposal> for InitProposalData {
     type Error = ProposalError;
 
@@ -92,6 +115,7 @@ impl TryFrom<DefaultProposal> for InitProposalData {
             voting_start_epoch: value.proposal.voting_start_epoch,
             voting_end_epoch: value.proposal.voting_end_epoch,
             grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
         })
     }
 }
@@ -111,6 +135,7 @@ impl TryFrom<PgfStewardProposal> for InitProposalData {
             voting_start_epoch: value.proposal.voting_start_epoch,
             voting_end_epoch: value.proposal.voting_end_epoch,
             grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
         })
     }
 }
@@ -161,6 +186,102 @@ impl TryFrom<PgfFundingProposal> for InitProposalData {
             voting_start_epoch: value.proposal.voting_start_epoch,
             voting_end_epoch: value.proposal.voting_end_epoch,
             grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
+        })
+    }
+}
+
+impl TryFrom<ParameterChangeProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: ParameterChangeProposal) -> Result<Self, Self::Error> {
+        let changes = value
+            .data
+            .iter()
+            .map(ParameterChange::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProposalError::InvalidProposalData)?;
+
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::ParameterChange(changes),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
+        })
+    }
+}
+
+impl TryFrom<CodeAllowlistProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: CodeAllowlistProposal) -> Result<Self, Self::Error> {
+        let update = CodeAllowlistUpdate::try_from(&value.data)
+            .map_err(|_| ProposalError::InvalidProposalData)?;
+
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::CodeAllowlist(update),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
+        })
+    }
+}
+
+impl TryFrom<ConsensusParamsProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: ConsensusParamsProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::ConsensusParams(value.data),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
+        })
+    }
+}
+
+impl TryFrom<TreasurySpendProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: TreasurySpendProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::TreasurySpend(value.data),
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
+        })
+    }
+}
+
+impl TryFrom<TextProposal> for InitProposalData {
+    type Error = ProposalError;
+
+    fn try_from(value: TextProposal) -> Result<Self, Self::Error> {
+        Ok(InitProposalData {
+            id: value.proposal.id,
+            content: Hash::default(),
+            author: value.proposal.author,
+            r#type: ProposalType::Text,
+            voting_start_epoch: value.proposal.voting_start_epoch,
+            voting_end_epoch: value.proposal.voting_end_epoch,
+            grace_epoch: value.proposal.grace_epoch,
+            expedited: value.proposal.expedited,
         })
     }
 }
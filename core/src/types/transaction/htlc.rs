@@ -0,0 +1,70 @@
+//! Txs to manage hashed time-locked transfers
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::Address;
+use crate::types::hash::Hash;
+use crate::types::storage::Epoch;
+use crate::types::token;
+
+/// A tx data type to lock tokens until they are claimed by the receiver or
+/// refunded to the source
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct LockTransfer {
+    /// The address locking the tokens
+    pub source: Address,
+    /// The address that may claim the tokens
+    pub receiver: Address,
+    /// The token to lock
+    pub token: Address,
+    /// The amount to lock
+    pub amount: token::Amount,
+    /// The SHA-256 hash of the preimage with which the receiver may claim
+    /// the tokens, if any
+    pub hashlock: Option<Hash>,
+    /// The epoch at which the timelock expires
+    pub timelock: Epoch,
+}
+
+/// A tx data type to claim the tokens of a time lock with the preimage of
+/// its hashlock
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ClaimTimeLock {
+    /// The id of the time lock
+    pub id: u64,
+    /// The preimage of the hashlock
+    pub preimage: Vec<u8>,
+}
+
+/// A tx data type for the receiver of a time lock to refund its tokens to
+/// the source
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct RefundTimeLock {
+    /// The id of the time lock
+    pub id: u64,
+}
@@ -7,6 +7,8 @@ pub mod account;
 pub mod decrypted;
 /// txs to manage governance
 pub mod governance;
+/// txs to manage hashed time-locked transfers
+pub mod htlc;
 /// txs to manage pgf
 pub mod pgf;
 /// txs to manage pos
@@ -16,7 +18,7 @@ pub mod protocol;
 /// wrapper txs with encrypted payloads
 pub mod wrapper;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -62,6 +64,16 @@ impl TxResult {
     }
 }
 
+/// Result of executing a read-only view transaction
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct ViewTxResult {
+    /// Total gas used by the view transaction
+    pub gas_used: Gas,
+    /// Values written by the view transaction to temporary storage, which
+    /// make up its output. These are discarded after the execution.
+    pub output: BTreeMap<storage::Key, Vec<u8>>,
+}
+
 /// Result of checking a transaction with validity predicates
 // TODO derive BorshSchema after <https://github.com/near/borsh-rs/issues/82>
 #[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
@@ -151,6 +163,9 @@ pub enum TxType {
     Decrypted(DecryptedTx),
     /// Txs issued by validators as part of internal protocols
     Protocol(Box<ProtocolTx>),
+    /// A read-only tx, which may only be executed against committed state
+    /// via the `view_tx` query. These txs are never included in blocks.
+    View,
 }
 
 impl TxType {
@@ -44,6 +44,10 @@ pub struct BecomeValidator {
     pub website: Option<String>,
     /// The validator's discord handle
     pub discord_handle: Option<String>,
+    /// The validator's moniker
+    pub moniker: Option<String>,
+    /// The validator's security contact
+    pub security_contact: Option<String>,
 }
 
 /// A bond is a validator's self-bond or a delegation from non-validator to a
@@ -73,6 +77,42 @@ pub struct Bond {
 /// An unbond of a bond.
 pub type Unbond = Bond;
 
+/// An unbond of a bond, along with its options. The data of an unbond tx is
+/// encoded as a plain [`Unbond`] when all the options are left unset, such
+/// that its encoding remains unchanged.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct UnbondWithOptions {
+    /// The unbond
+    pub unbond: Unbond,
+    /// Automatically withdraw the unbonded tokens back to the source at the
+    /// start of the epoch in which they become withdrawable
+    pub auto_withdraw: bool,
+}
+
+impl UnbondWithOptions {
+    /// Decode the data of an unbond tx, which is either a plain [`Unbond`]
+    /// or an [`UnbondWithOptions`].
+    pub fn decode(data: &[u8]) -> std::io::Result<Self> {
+        Unbond::try_from_slice(data)
+            .map(|unbond| Self {
+                unbond,
+                auto_withdraw: false,
+            })
+            .or_else(|_| Self::try_from_slice(data))
+    }
+}
+
 /// A withdrawal of an unbond.
 #[derive(
     Debug,
@@ -115,6 +155,54 @@ pub struct ClaimRewards {
     pub source: Option<Address>,
 }
 
+/// A change of the automatic compounding of the rewards of a bond.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct AutoCompound {
+    /// Validator address
+    pub validator: Address,
+    /// Source address for delegations. For self-bonds, the validator is
+    /// also the source
+    pub source: Option<Address>,
+    /// Whether the rewards of the bond are automatically added to the bond
+    /// at the start of every epoch
+    pub enabled: bool,
+}
+
+/// A change of the slashing insurance of a bond.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct SlashingInsurance {
+    /// Validator address
+    pub validator: Address,
+    /// Source address for delegations. For self-bonds, the validator is
+    /// also the source
+    pub source: Option<Address>,
+    /// Whether the bond is insured against slashing by the insurance pool,
+    /// paying a premium out of its rewards
+    pub enabled: bool,
+}
+
 /// A redelegation of bonded tokens from one validator to another.
 #[derive(
     Debug,
@@ -183,6 +271,10 @@ pub struct MetaDataChange {
     pub website: Option<String>,
     /// Validator's discord handle
     pub discord_handle: Option<String>,
+    /// Validator's moniker
+    pub moniker: Option<String>,
+    /// Validator's security contact
+    pub security_contact: Option<String>,
     /// Validator's commission rate
     pub commission_rate: Option<Dec>,
 }
@@ -206,3 +298,45 @@ pub struct ConsensusKeyChange {
     /// The new consensus key
     pub consensus_key: common::PublicKey,
 }
+
+/// A change to the validator's protocol key.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ProtocolKeyChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new protocol key
+    pub protocol_key: common::PublicKey,
+}
+
+/// A change to the validator's Ethereum bridge hot and/or cold keys.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct EthBridgeKeysChange {
+    /// Validator address
+    pub validator: Address,
+    /// The new Eth bridge hot signing key, if it is changed
+    pub eth_hot_key: Option<secp256k1::PublicKey>,
+    /// The new Eth bridge governance key, if it is changed
+    pub eth_cold_key: Option<secp256k1::PublicKey>,
+}
@@ -10,7 +10,8 @@ use crate::types::chain::ChainId;
 use crate::types::key::*;
 use crate::types::transaction::{Digest, Sha256, TxType};
 use crate::types::vote_extensions::{
-    bridge_pool_roots, ethereum_events, validator_set_update,
+    attestations, bridge_pool_roots, ethereum_events, heartbeat, misbehavior,
+    validator_set_update,
 };
 
 #[derive(
@@ -131,6 +132,14 @@ ethereum_tx_data_declare! {
         BridgePoolVext(bridge_pool_roots::SignedVext),
         /// Validator set update signed by some validator
         ValSetUpdateVext(validator_set_update::SignedVext),
+        /// Data attested by some validator in an attestation lane
+        AttestationVext(attestations::SignedVext),
+        /// Evidence of some validator having misbehaved in its
+        /// vote extensions
+        MisbehaviorEvidence(misbehavior::MisbehaviorEvidence),
+        /// Periodic heartbeat of some validator, attesting to the
+        /// build of the node software it runs
+        ValidatorHeartbeat(heartbeat::SignedHeartbeat),
     }
 }
 
@@ -192,6 +201,9 @@ impl EthereumTxData {
             EthEventsVext,
             BridgePoolVext,
             ValSetUpdateVext,
+            AttestationVext,
+            MisbehaviorEvidence,
+            ValidatorHeartbeat,
         }
     }
 
@@ -225,6 +237,18 @@ impl EthereumTxData {
                 BorshDeserialize::try_from_slice(data)
                     .map(EthereumTxData::ValSetUpdateVext)
             },
+            ProtocolTxType::AttestationVext => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::AttestationVext)
+            },
+            ProtocolTxType::MisbehaviorEvidence => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::MisbehaviorEvidence)
+            },
+            ProtocolTxType::ValidatorHeartbeat => |data| {
+                BorshDeserialize::try_from_slice(data)
+                    .map(EthereumTxData::ValidatorHeartbeat)
+            },
         };
         deserialize(data)
             .map_err(|err| TxError::Deserialization(err.to_string()))
@@ -257,6 +281,14 @@ pub enum ProtocolTxType {
     BridgePoolVext,
     /// Validator set update signed by some validator
     ValSetUpdateVext,
+    /// Data attested by some validator in an attestation lane
+    AttestationVext,
+    /// Evidence of some validator having misbehaved in its
+    /// vote extensions
+    MisbehaviorEvidence,
+    /// Periodic heartbeat of some validator, attesting to the
+    /// build of the node software it runs
+    ValidatorHeartbeat,
 }
 
 impl ProtocolTxType {
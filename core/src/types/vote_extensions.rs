@@ -1,7 +1,10 @@
 //! This module contains types necessary for processing vote extensions.
 
+pub mod attestations;
 pub mod bridge_pool_roots;
 pub mod ethereum_events;
+pub mod heartbeat;
+pub mod misbehavior;
 pub mod validator_set_update;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
@@ -20,4 +23,6 @@ pub struct VoteExtension {
     pub bridge_pool_root: Option<bridge_pool_roots::SignedVext>,
     /// Vote extension data related with validator set updates.
     pub validator_set_update: Option<validator_set_update::SignedVext>,
+    /// Data attested in each of the enabled attestation lanes.
+    pub attestations: Vec<attestations::SignedVext>,
 }
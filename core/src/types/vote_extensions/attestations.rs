@@ -0,0 +1,46 @@
+//! Contains types necessary for processing generic data attestations
+//! in vote extensions.
+//!
+//! Attestations are grouped in lanes, each of which carries a different
+//! kind of validator-attested data (e.g. price medians or the headers of
+//! some external chain). The data of each lane is opaque to the protocol,
+//! and is only interpreted by the validation and aggregation hooks of the
+//! lane itself.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use crate::proto::Signed;
+use crate::types::address::Address;
+use crate::types::key::common;
+use crate::types::storage::BlockHeight;
+
+/// Type alias for an [`AttestationVext`].
+pub type Vext = AttestationVext;
+
+/// Represents a [`Vext`] signed by some validator, with
+/// a Namada protocol key.
+pub type SignedVext = Signed<Vext>;
+
+/// Data attested by some validator, in one of the attestation lanes
+/// enabled by governance.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct AttestationVext {
+    /// The id of the attestation lane this [`Vext`] belongs to.
+    pub lane: String,
+    /// The block height for which this [`Vext`] was made.
+    pub block_height: BlockHeight,
+    /// The address of the validator who attested to the data.
+    pub validator_addr: Address,
+    /// The attested data, encoded in a lane specific format.
+    pub data: Vec<u8>,
+}
+
+impl Vext {
+    /// Sign a [`Vext`] with a validator's `signing_key`,
+    /// and return the signed data.
+    pub fn sign(self, signing_key: &common::SecretKey) -> Signed<Self> {
+        Signed::new(signing_key, self)
+    }
+}
@@ -0,0 +1,89 @@
+//! Contains types necessary for the periodic heartbeats of validators,
+//! attesting to the build of the node software they run.
+//!
+//! Each validator signs a heartbeat with its protocol key once per epoch,
+//! and broadcasts it in a protocol tx. The heartbeats included on chain
+//! let the network see which software versions validators actually run,
+//! e.g. ahead of a coordinated upgrade.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::proto::Signed;
+use crate::types::address::Address;
+use crate::types::key::common;
+use crate::types::storage::Epoch;
+
+/// Represents a [`Heartbeat`] signed by some validator, with
+/// a Namada protocol key.
+pub type SignedHeartbeat = Signed<Heartbeat>;
+
+/// The maximum length of the protocol version and of the git hash in a
+/// [`BuildInfo`].
+pub const MAX_BUILD_STRING_LEN: usize = 64;
+
+/// The maximum number of features in a [`BuildInfo`].
+pub const MAX_BUILD_FEATURES: usize = 16;
+
+/// The maximum length of each feature in a [`BuildInfo`].
+pub const MAX_BUILD_FEATURE_LEN: usize = 32;
+
+/// The build of the node software run by some validator.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct BuildInfo {
+    /// The version of the protocol implemented by the node.
+    pub protocol_version: String,
+    /// The hash of the git commit the node was built from, if known.
+    pub git_hash: Option<String>,
+    /// The optional features the node was built with.
+    pub features: Vec<String>,
+}
+
+/// A heartbeat of some validator, attesting to the build of the node
+/// software it runs in some epoch.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct Heartbeat {
+    /// The address of the validator who sent the heartbeat.
+    pub validator_addr: Address,
+    /// The epoch in which the heartbeat was sent.
+    pub epoch: Epoch,
+    /// The build of the node software run by the validator.
+    pub build: BuildInfo,
+}
+
+impl BuildInfo {
+    /// Check that the fields of the build fit within the size limits, such
+    /// that heartbeats can't be used to bloat the storage.
+    pub fn is_within_limits(&self) -> bool {
+        self.protocol_version.len() <= MAX_BUILD_STRING_LEN
+            && self
+                .git_hash
+                .as_ref()
+                .map_or(true, |hash| hash.len() <= MAX_BUILD_STRING_LEN)
+            && self.features.len() <= MAX_BUILD_FEATURES
+            && self
+                .features
+                .iter()
+                .all(|feature| feature.len() <= MAX_BUILD_FEATURE_LEN)
+    }
+}
+
+impl Heartbeat {
+    /// Sign a [`Heartbeat`] with a validator's `signing_key`,
+    /// and return the signed data.
+    pub fn sign(self, signing_key: &common::SecretKey) -> SignedHeartbeat {
+        Signed::new(signing_key, self)
+    }
+}
@@ -0,0 +1,156 @@
+//! Contains types necessary for reporting misbehavior of validators in
+//! their vote extensions, which CometBFT itself is unable to detect.
+//!
+//! Evidence is collected by external watchtowers, and submitted to the
+//! ledger, which validates it and includes it on chain as a protocol tx,
+//! such that the offending validator can be slashed.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use super::{bridge_pool_roots, validator_set_update};
+use crate::proto::Signable;
+use crate::types::address::Address;
+use crate::types::ethereum_events::Uint;
+use crate::types::keccak::{keccak_hash, KeccakHash};
+
+/// Evidence of some validator having signed conflicting or invalid
+/// data in its vote extensions.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum MisbehaviorEvidence {
+    /// Two validator set update vote extensions, signed by the same
+    /// validator for the same epoch, over different validator sets.
+    ConflictingValSetUpdateVexts {
+        /// The first of the conflicting vote extensions.
+        first: validator_set_update::SignedVext,
+        /// The second of the conflicting vote extensions.
+        second: validator_set_update::SignedVext,
+    },
+    /// A Bridge pool root vote extension, whose Ethereum signature
+    /// is over a root and nonce other than the ones committed to
+    /// at the block height of the vote extension.
+    InvalidBridgePoolRoot {
+        /// The offending vote extension.
+        vext: bridge_pool_roots::SignedVext,
+        /// The Bridge pool root signed over in the vote extension.
+        root: KeccakHash,
+        /// The Bridge pool nonce signed over in the vote extension.
+        nonce: Uint,
+    },
+}
+
+impl MisbehaviorEvidence {
+    /// Return the address of the validator who misbehaved.
+    pub fn validator(&self) -> &Address {
+        match self {
+            Self::ConflictingValSetUpdateVexts { first, .. } => {
+                &first.data.validator_addr
+            }
+            Self::InvalidBridgePoolRoot { vext, .. } => {
+                &vext.data.validator_addr
+            }
+        }
+    }
+
+    /// Check if this [`MisbehaviorEvidence`] is well formed, i.e. if
+    /// the data signed by the validator, assuming the signatures are
+    /// valid, amounts to misbehavior.
+    ///
+    /// Validating the signatures, and checking the signed data against
+    /// the state of the chain, is left to the ledger.
+    pub fn is_well_formed(&self) -> bool {
+        match self {
+            Self::ConflictingValSetUpdateVexts { first, second } => {
+                first.data.validator_addr == second.data.validator_addr
+                    && first.data.signing_epoch == second.data.signing_epoch
+                    && signable_valset_upd(first) != signable_valset_upd(second)
+            }
+            Self::InvalidBridgePoolRoot { .. } => true,
+        }
+    }
+
+    /// Return the message signed by the validator's Ethereum hot key,
+    /// in a [`MisbehaviorEvidence::InvalidBridgePoolRoot`].
+    pub fn bridge_pool_root_message(&self) -> Option<KeccakHash> {
+        match self {
+            Self::InvalidBridgePoolRoot { root, nonce, .. } => {
+                Some(keccak_hash([root.0, nonce.to_bytes()].concat()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Return the data signed over in a validator set update vote extension.
+#[inline]
+fn signable_valset_upd(ext: &validator_set_update::SignedVext) -> KeccakHash {
+    validator_set_update::SerializeWithAbiEncode::as_signable(&ext.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::types::ethereum_events::EthAddress;
+    use crate::types::key::testing::keypair_1;
+    use crate::types::storage::Epoch;
+    use crate::types::token;
+    use crate::types::vote_extensions::validator_set_update::EthAddrBook;
+
+    /// Test that only validator set update vote extensions signed by the
+    /// same validator, at the same epoch, over different validator sets,
+    /// are considered conflicting.
+    #[test]
+    fn test_conflicting_valset_upd_vexts() {
+        let sk = keypair_1();
+        let ext = validator_set_update::Vext {
+            voting_powers: HashMap::new(),
+            validator_addr: established_address_1(),
+            signing_epoch: Epoch(1),
+        };
+        let conflicting = validator_set_update::Vext {
+            voting_powers: HashMap::from([(
+                EthAddrBook {
+                    hot_key_addr: EthAddress([1; 20]),
+                    cold_key_addr: EthAddress([2; 20]),
+                },
+                token::Amount::native_whole(100),
+            )]),
+            ..ext.clone()
+        };
+        let evidence =
+            |first: &validator_set_update::Vext,
+             second: &validator_set_update::Vext| {
+                MisbehaviorEvidence::ConflictingValSetUpdateVexts {
+                    first: first.sign(&sk),
+                    second: second.sign(&sk),
+                }
+            };
+
+        assert!(evidence(&ext, &conflicting).is_well_formed());
+        assert!(!evidence(&ext, &ext).is_well_formed());
+        assert!(
+            !evidence(
+                &ext,
+                &validator_set_update::Vext {
+                    validator_addr: established_address_2(),
+                    ..conflicting.clone()
+                }
+            )
+            .is_well_formed()
+        );
+        assert!(
+            !evidence(
+                &ext,
+                &validator_set_update::Vext {
+                    signing_epoch: Epoch(2),
+                    ..conflicting
+                }
+            )
+            .is_well_formed()
+        );
+    }
+}
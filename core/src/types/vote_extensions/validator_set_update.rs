@@ -150,6 +150,52 @@ pub struct EthAddrBook {
 /// Provides a mapping between [`EthAddress`] and [`token::Amount`] instances.
 pub type VotingPowersMap = HashMap<EthAddrBook, token::Amount>;
 
+/// The voting power of some validator, along with its value normalized
+/// to the fixed-point encoding used by the Ethereum bridge smart contracts.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct NormalizedVotingPower {
+    /// The Ethereum addresses of the validator.
+    pub addr_book: EthAddrBook,
+    /// The voting power of the validator in Namada.
+    pub voting_power: token::Amount,
+    /// The voting power of the validator, as stored in the Ethereum bridge
+    /// smart contracts.
+    pub normalized: EthBridgeVotingPower,
+}
+
+/// The total voting power of the consensus validator set at some epoch.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct TotalVotingPower {
+    /// The total voting power in Namada.
+    pub voting_power: token::Amount,
+    /// The sum of the normalized voting powers of all consensus
+    /// validators, as computed by the Ethereum bridge smart contracts.
+    ///
+    /// Due to rounding, this value may slightly diverge from
+    /// [`EthBridgeVotingPower::MAX`].
+    pub normalized: u128,
+}
+
 /// This trait contains additional methods for a [`VotingPowersMap`], related
 /// with validator set update vote extensions logic.
 pub trait VotingPowersMapExt {
@@ -157,24 +203,18 @@ pub trait VotingPowersMapExt {
     /// sorted in descending order by voting power.
     fn get_sorted(&self) -> Vec<(&EthAddrBook, &token::Amount)>;
 
-    /// Returns the list of Ethereum validator hot and cold addresses and their
-    /// respective voting powers (in this order), with an Ethereum ABI
-    /// compatible encoding. Implementations of this method must be
-    /// deterministic based on `self`. In addition, the returned `Vec`s must be
-    /// sorted in descending order by voting power, as this is more efficient to
-    /// deal with on the Ethereum side when working out if there is enough
-    /// voting power for a given validator set update.
-    fn get_abi_encoded(&self) -> (Vec<Token>, Vec<Token>) {
-        let sorted = self.get_sorted();
-
-        let total_voting_power: token::Amount =
-            sorted.iter().map(|&(_, &voting_power)| voting_power).sum();
-
-        // split the vec into two portions
-        sorted
+    /// Returns the voting powers in this map, normalized against
+    /// `total_voting_power` to the fixed-point encoding used by the
+    /// Ethereum bridge smart contracts. The returned `Vec` is sorted
+    /// in descending order by voting power.
+    fn get_normalized(
+        &self,
+        total_voting_power: token::Amount,
+    ) -> Vec<NormalizedVotingPower> {
+        self.get_sorted()
             .into_iter()
             .map(|(addr_book, &voting_power)| {
-                let voting_power: EthBridgeVotingPower =
+                let normalized: EthBridgeVotingPower =
                     FractionalVotingPower::new(
                         voting_power.into(),
                         total_voting_power.into(),
@@ -184,11 +224,39 @@ pub trait VotingPowersMapExt {
                          voting power",
                     )
                     .into();
+                NormalizedVotingPower {
+                    addr_book: addr_book.clone(),
+                    voting_power,
+                    normalized,
+                }
+            })
+            .collect()
+    }
 
-                let &EthAddrBook {
-                    hot_key_addr,
-                    cold_key_addr,
-                } = addr_book;
+    /// Returns the list of Ethereum validator hot and cold addresses and their
+    /// respective voting powers (in this order), with an Ethereum ABI
+    /// compatible encoding. Implementations of this method must be
+    /// deterministic based on `self`. In addition, the returned `Vec`s must be
+    /// sorted in descending order by voting power, as this is more efficient to
+    /// deal with on the Ethereum side when working out if there is enough
+    /// voting power for a given validator set update.
+    fn get_abi_encoded(&self) -> (Vec<Token>, Vec<Token>) {
+        let total_voting_power: token::Amount =
+            self.get_sorted().into_iter().map(|(_, &power)| power).sum();
+
+        // split the vec into two portions
+        self.get_normalized(total_voting_power)
+            .into_iter()
+            .map(|normalized_power| {
+                let NormalizedVotingPower {
+                    addr_book:
+                        EthAddrBook {
+                            hot_key_addr,
+                            cold_key_addr,
+                        },
+                    normalized: voting_power,
+                    ..
+                } = normalized_power;
 
                 (
                     Token::FixedBytes(
@@ -368,6 +436,34 @@ impl Encode<1> for ValidatorSetArgs {
     }
 }
 
+/// The Bridge and Governance validator sets of some [`Epoch`], captured
+/// when a governance proposal that changes the Ethereum bridge enters its
+/// voting period.
+///
+/// The hashes are the same ones validators sign over in the validator set
+/// update vote extensions of the previous epoch, such that the artifacts
+/// relayed to Ethereum can be matched against the snapshot.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct ValidatorSetSnapshot {
+    /// The Bridge validator set.
+    pub bridge: ValidatorSetArgs,
+    /// The Governance validator set.
+    pub governance: ValidatorSetArgs,
+    /// The keccak hash of the Bridge validator set.
+    pub bridge_hash: KeccakHash,
+    /// The keccak hash of the Governance validator set.
+    pub governance_hash: KeccakHash,
+}
+
 // this is only here so we don't pollute the
 // outer namespace with serde traits
 mod tag {
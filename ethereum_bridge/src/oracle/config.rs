@@ -3,6 +3,7 @@ use std::num::NonZeroU64;
 
 use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::ethereum_structs;
+use namada_core::types::keccak::KeccakHash;
 
 /// Configuration for an oracle.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -12,10 +13,51 @@ pub struct Config {
     pub min_confirmations: NonZeroU64,
     /// The Ethereum address of the current bridge contract.
     pub bridge_contract: EthAddress,
+    /// The Ethereum address of the NFT bridge contract, if ERC-721 tokens
+    /// may be bridged to Namada.
+    pub nft_bridge_contract: Option<EthAddress>,
     /// The earliest Ethereum block from which events may be processed.
     pub start_block: ethereum_structs::BlockHeight,
     /// The status of the Ethereum bridge (active / inactive)
     pub active: bool,
+    /// The number of already processed Ethereum blocks whose events are
+    /// held back and re-checked for reorgs, as set by governance. If [`None`], the oracle falls
+    /// back to the value in the node's config.
+    pub reorg_safety_depth: Option<u64>,
+    /// A handover of the bridge contract to a new address, scheduled by
+    /// governance.
+    pub bridge_contract_handover: Option<ContractHandover>,
+}
+
+/// A handover of the bridge contract to a new address, from some Ethereum
+/// block onwards.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ContractHandover {
+    /// The Ethereum address of the new bridge contract.
+    pub new_contract: EthAddress,
+    /// The first Ethereum block whose events are taken from the new
+    /// bridge contract.
+    pub handover_block: ethereum_structs::BlockHeight,
+    /// The hashes of the Bridge and Governance validator sets that the new
+    /// bridge contract is initialized with, if they were snapshotted when
+    /// the proposal that scheduled the handover entered its voting period.
+    pub validator_set_hashes: Option<(KeccakHash, KeccakHash)>,
+}
+
+impl Config {
+    /// The Ethereum address of the bridge contract whose events are
+    /// processed in the given Ethereum block.
+    pub fn bridge_contract_at(
+        &self,
+        block: &ethereum_structs::BlockHeight,
+    ) -> EthAddress {
+        match &self.bridge_contract_handover {
+            Some(handover) if *block >= handover.handover_block => {
+                handover.new_contract
+            }
+            _ => self.bridge_contract,
+        }
+    }
 }
 
 // TODO: this production Default implementation is temporary, there should be no
@@ -27,8 +69,38 @@ impl std::default::Default for Config {
             // value that is >= 1
             min_confirmations: unsafe { NonZeroU64::new_unchecked(100) },
             bridge_contract: EthAddress([0; 20]),
+            nft_bridge_contract: None,
             start_block: 0.into(),
             active: true,
+            reorg_safety_depth: None,
+            bridge_contract_handover: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the events of the new bridge contract are processed from
+    /// the handover block onwards.
+    #[test]
+    fn test_bridge_contract_at() {
+        let old_contract = EthAddress([1; 20]);
+        let new_contract = EthAddress([2; 20]);
+        let mut config = Config {
+            bridge_contract: old_contract,
+            ..Default::default()
+        };
+        assert_eq!(config.bridge_contract_at(&100u64.into()), old_contract);
+
+        config.bridge_contract_handover = Some(ContractHandover {
+            new_contract,
+            handover_block: 100u64.into(),
+            validator_set_hashes: None,
+        });
+        assert_eq!(config.bridge_contract_at(&99u64.into()), old_contract);
+        assert_eq!(config.bridge_contract_at(&100u64.into()), new_contract);
+        assert_eq!(config.bridge_contract_at(&101u64.into()), new_contract);
+    }
+}
@@ -12,6 +12,7 @@ use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::ethereum_structs;
 use namada_core::types::storage::Key;
 use namada_core::types::token::{DenominatedAmount, NATIVE_MAX_DECIMAL_PLACES};
+use namada_core::types::vote_extensions::validator_set_update::ValidatorSetSnapshot;
 use serde::{Deserialize, Serialize};
 
 use crate::storage::eth_bridge_queries::{
@@ -118,6 +119,28 @@ pub struct UpgradeableContract {
     pub version: ContractVersion,
 }
 
+/// A rotation of the bridge contract to a new address, scheduled by
+/// governance. The events emitted by the current bridge contract are
+/// processed up until the handover block, after which only those emitted
+/// by the new contract are.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct ContractRotation {
+    /// The new bridge contract.
+    pub contract: UpgradeableContract,
+    /// The first Ethereum block whose events are taken from the new
+    /// contract.
+    pub handover_height: ethereum_structs::BlockHeight,
+}
+
 /// Represents all the Ethereum contracts that need to be directly know about by
 /// validators.
 #[derive(
@@ -260,6 +283,19 @@ pub struct EthereumOracleConfig {
     /// The addresses of the Ethereum contracts that need to be directly known
     /// by validators.
     pub contracts: Contracts,
+    /// The number of processed Ethereum blocks whose events the oracle
+    /// holds back and re-checks for reorgs, if overridden by governance.
+    pub reorg_safety_depth: Option<u64>,
+    /// Whether the oracle has been paused by governance.
+    pub oracle_paused: bool,
+    /// The Ethereum address of the NFT bridge contract, if ERC-721 tokens
+    /// may be bridged to Namada.
+    pub nft_bridge_contract: Option<EthAddress>,
+    /// A rotation of the bridge contract scheduled by governance, if any.
+    pub bridge_contract_rotation: Option<ContractRotation>,
+    /// The validator sets that the new bridge contract of the scheduled
+    /// rotation is initialized with, if they were snapshotted.
+    pub bridge_contract_rotation_valset: Option<ValidatorSetSnapshot>,
 }
 
 impl From<EthereumBridgeParams> for EthereumOracleConfig {
@@ -274,6 +310,11 @@ impl From<EthereumBridgeParams> for EthereumOracleConfig {
             eth_start_height,
             min_confirmations,
             contracts,
+            reorg_safety_depth: None,
+            oracle_paused: false,
+            nft_bridge_contract: None,
+            bridge_contract_rotation: None,
+            bridge_contract_rotation_valset: None,
         }
     }
 }
@@ -311,6 +352,35 @@ impl EthereumOracleConfig {
         let native_erc20 = must_read_key(wl_storage, &native_erc20_key);
         let bridge_contract = must_read_key(wl_storage, &bridge_contract_key);
         let eth_start_height = must_read_key(wl_storage, &eth_start_height_key);
+        // this parameter is optional, and only present in storage if
+        // governance has overridden the oracle's own config
+        let reorg_safety_depth = StorageRead::read(
+            wl_storage,
+            &bridge_storage::reorg_safety_depth_key(),
+        )
+        .expect("Reading the reorg safety depth shouldn't fail");
+        let oracle_paused =
+            StorageRead::read(wl_storage, &bridge_storage::oracle_paused_key())
+                .expect("Reading the oracle paused status shouldn't fail")
+                .unwrap_or_default();
+        let nft_bridge_contract = StorageRead::read(
+            wl_storage,
+            &bridge_storage::nft_bridge_contract_key(),
+        )
+        .expect("Reading the NFT bridge contract address shouldn't fail");
+        let bridge_contract_rotation = StorageRead::read(
+            wl_storage,
+            &bridge_storage::bridge_contract_rotation_key(),
+        )
+        .expect("Reading the bridge contract rotation shouldn't fail");
+        let bridge_contract_rotation_valset = StorageRead::read(
+            wl_storage,
+            &bridge_storage::bridge_contract_rotation_valset_key(),
+        )
+        .expect(
+            "Reading the validator sets of the bridge contract rotation \
+             shouldn't fail",
+        );
 
         Some(Self {
             eth_start_height,
@@ -319,6 +389,11 @@ impl EthereumOracleConfig {
                 native_erc20,
                 bridge: bridge_contract,
             },
+            reorg_safety_depth,
+            oracle_paused,
+            nft_bridge_contract,
+            bridge_contract_rotation,
+            bridge_contract_rotation_valset,
         })
     }
 }
@@ -425,6 +500,40 @@ mod tests {
         assert_eq!(config, read);
     }
 
+    /// Test that a rotation of the bridge contract scheduled by
+    /// governance is part of the oracle's config.
+    #[test]
+    fn test_ethereum_bridge_config_contract_rotation() {
+        let mut wl_storage = TestWlStorage::default();
+        let config = EthereumBridgeParams {
+            erc20_whitelist: vec![],
+            eth_start_height: Default::default(),
+            min_confirmations: MinimumConfirmations::default(),
+            contracts: Contracts {
+                native_erc20: EthAddress([42; 20]),
+                bridge: UpgradeableContract {
+                    address: EthAddress([23; 20]),
+                    version: ContractVersion::default(),
+                },
+            },
+        };
+        config.init_storage(&mut wl_storage);
+        let rotation = ContractRotation {
+            contract: UpgradeableContract {
+                address: EthAddress([24; 20]),
+                version: ContractVersion::default(),
+            },
+            handover_height: 100u64.into(),
+        };
+        wl_storage
+            .write(&bridge_storage::bridge_contract_rotation_key(), &rotation)
+            .unwrap();
+
+        let read = EthereumOracleConfig::read(&wl_storage).unwrap();
+
+        assert_eq!(read.bridge_contract_rotation, Some(rotation));
+    }
+
     #[test]
     fn test_ethereum_bridge_config_uninitialized() {
         let wl_storage = TestWlStorage::default();
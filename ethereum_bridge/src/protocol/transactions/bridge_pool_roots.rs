@@ -42,7 +42,7 @@ where
         "Applying state updates derived from signatures of the Ethereum \
          bridge pool root and nonce."
     );
-    let voting_powers = utils::get_voting_powers(wl_storage, &vext)?;
+    let voting_powers = utils::get_eligible_voting_powers(wl_storage, &vext)?;
     let root_height = vext.iter().next().unwrap().data.block_height;
     let (partial_proof, seen_by) = parse_vexts(wl_storage, vext);
 
@@ -7,29 +7,35 @@ use borsh::BorshDeserialize;
 use eyre::{Result, WrapErr};
 use namada_core::hints;
 use namada_core::ledger::eth_bridge::storage::bridge_pool::{
-    get_nonce_key, is_pending_transfer_key, BRIDGE_POOL_ADDRESS,
+    get_cancellation_key, get_cancellations_prefix, get_key_from_hash,
+    get_nonce_key, is_cancellation_key, is_pending_transfer_key,
+    BRIDGE_POOL_ADDRESS,
 };
 use namada_core::ledger::eth_bridge::storage::{
-    self as bridge_storage, wrapped_erc20s,
+    self as bridge_storage, nft, relayer_rewards, wrapped_erc20s,
 };
 use namada_core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
 use namada_core::ledger::parameters::read_epoch_duration_parameter;
+use namada_core::ledger::pgf;
 use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, WlStorage, DB};
-use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
+use namada_core::ledger::storage_api::token::{read_balance, transfer};
+use namada_core::ledger::storage_api::{self, StorageRead, StorageWrite};
 use namada_core::types::address::Address;
+use namada_core::types::eth_abi::Encode;
 use namada_core::types::eth_bridge_pool::{
-    PendingTransfer, TransferToEthereumKind,
+    PendingTransfer, RelayerStats, TransferToEthereumKind,
 };
 use namada_core::types::ethereum_events::{
-    EthAddress, EthereumEvent, TransferToEthereum, TransferToNamada,
-    TransfersToNamada,
+    EthAddress, EthereumEvent, NftTransferToNamada, TransferToEthereum,
+    TransferToNamada, TransfersToNamada, Uint,
 };
-use namada_core::types::storage::{BlockHeight, Key, KeySeg};
+use namada_core::types::keccak::KeccakHash;
+use namada_core::types::storage::{BlockHeight, Epoch, Key, KeySeg};
 use namada_core::types::token;
 use namada_core::types::token::{balance_key, minted_balance_key};
 
-use crate::parameters::read_native_erc20_address;
+use crate::parameters::{read_native_erc20_address, ContractRotation};
 use crate::protocol::transactions::update;
 use crate::storage::eth_bridge_queries::{EthAssetMint, EthBridgeQueries};
 
@@ -48,7 +54,13 @@ where
         EthereumEvent::TransfersToNamada { transfers, nonce } => {
             act_on_transfers_to_namada(
                 wl_storage,
-                TransfersToNamada { transfers, nonce },
+                [TransfersToNamada { transfers, nonce }],
+            )
+        }
+        EthereumEvent::TransfersToNamadaBatch { nonce, batches } => {
+            act_on_transfers_to_namada(
+                wl_storage,
+                TransfersToNamada::from_batch(nonce, batches),
             )
         }
         EthereumEvent::TransfersToEthereum {
@@ -56,6 +68,17 @@ where
             ref relayer,
             ..
         } => act_on_transfers_to_eth(wl_storage, transfers, relayer),
+        EthereumEvent::NftTransfersToNamada { nonce, transfers } => {
+            act_on_nft_transfers_to_namada(wl_storage, nonce, &transfers)
+        }
+        EthereumEvent::BridgeContractHandover {
+            new_contract,
+            handover_height,
+        } => act_on_bridge_contract_handover(
+            wl_storage,
+            &new_contract,
+            &handover_height,
+        ),
         _ => {
             tracing::debug!(?event, "No actions taken for Ethereum event");
             Ok(BTreeSet::default())
@@ -63,34 +86,191 @@ where
     }
 }
 
+/// Queue the given confirmed transfers to Namada events, and mint the
+/// assets transferred in the events which are next in line to be
+/// processed. Events which were already processed, or queued, e.g. as
+/// part of a [`EthereumEvent::TransfersToNamadaBatch`], are ignored.
 fn act_on_transfers_to_namada<'tx, D, H>(
     wl_storage: &mut WlStorage<D, H>,
-    transfer_event: TransfersToNamada,
+    transfer_events: impl IntoIterator<Item = TransfersToNamada>,
 ) -> Result<BTreeSet<Key>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    tracing::debug!(?transfer_event, "Acting on transfers to Namada");
     let mut changed_keys = BTreeSet::new();
-    // we need to collect the events into a separate
-    // buffer because of rust's borrowing rules :|
-    let confirmed_events: Vec<_> = wl_storage
-        .storage
-        .eth_events_queue
-        .transfers_to_namada
-        .push_and_iter(transfer_event)
-        .collect();
-    for TransfersToNamada { transfers, .. } in confirmed_events {
-        update_transfers_to_namada_state(
-            wl_storage,
-            &mut changed_keys,
-            transfers.iter(),
-        )?;
+    for transfer_event in transfer_events {
+        let queue = &mut wl_storage.storage.eth_events_queue;
+        if queue
+            .transfers_to_namada
+            .contains_nonce(transfer_event.nonce)
+        {
+            tracing::debug!(
+                ?transfer_event,
+                "Ignoring transfers to Namada which were already processed"
+            );
+            continue;
+        }
+        tracing::debug!(?transfer_event, "Acting on transfers to Namada");
+        // we need to collect the events into a separate
+        // buffer because of rust's borrowing rules :|
+        let confirmed_events: Vec<_> = queue
+            .transfers_to_namada
+            .push_and_iter(transfer_event)
+            .collect();
+        for TransfersToNamada { transfers, .. } in confirmed_events {
+            update_transfers_to_namada_state(
+                wl_storage,
+                &mut changed_keys,
+                transfers.iter(),
+            )?;
+        }
+    }
+    Ok(changed_keys)
+}
+
+/// Apply the rotation of the bridge contract scheduled by governance, once
+/// a confirmed [`EthereumEvent::BridgeContractHandover`] attests that its
+/// handover block was processed. The new contract replaces the bridge
+/// contract in storage, and the rotation is cleared along with the
+/// validator sets snapshotted for it. Handovers that don't match the
+/// scheduled rotation are ignored.
+fn act_on_bridge_contract_handover<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    new_contract: &EthAddress,
+    handover_height: &Uint,
+) -> Result<BTreeSet<Key>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut changed_keys = BTreeSet::new();
+    let rotation_key = bridge_storage::bridge_contract_rotation_key();
+    let Some(rotation) = wl_storage.read::<ContractRotation>(&rotation_key)?
+    else {
+        tracing::debug!(
+            ?new_contract,
+            "Ignoring a handover to a bridge contract which is not scheduled"
+        );
+        return Ok(changed_keys);
+    };
+    if &rotation.contract.address != new_contract
+        || &Uint::from(&rotation.handover_height) != handover_height
+    {
+        tracing::debug!(
+            ?new_contract,
+            ?rotation,
+            "Ignoring a handover which doesn't match the scheduled rotation \
+             of the bridge contract"
+        );
+        return Ok(changed_keys);
+    }
+    tracing::info!(
+        ?rotation,
+        "Handed the Ethereum bridge over to a new bridge contract"
+    );
+    let bridge_contract_key = bridge_storage::bridge_contract_key();
+    wl_storage.write(&bridge_contract_key, rotation.contract)?;
+    changed_keys.insert(bridge_contract_key);
+    wl_storage.delete(&rotation_key)?;
+    changed_keys.insert(rotation_key);
+    let rotation_valset_key =
+        bridge_storage::bridge_contract_rotation_valset_key();
+    if wl_storage.has_key(&rotation_valset_key)? {
+        wl_storage.delete(&rotation_valset_key)?;
+        changed_keys.insert(rotation_valset_key);
     }
     Ok(changed_keys)
 }
 
+/// Record the new owners of the ERC-721 tokens bridged to Namada in a
+/// confirmed [`EthereumEvent::NftTransfersToNamada`]. Events with a nonce
+/// lower than the next expected one have already been acted on, and are
+/// ignored. Events with a higher nonce are kept pending, until the events
+/// preceding them have been acted on. The recorded owners are final, since
+/// NFTs cannot be bridged back to Ethereum.
+fn act_on_nft_transfers_to_namada<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    nonce: Uint,
+    transfers: &[NftTransferToNamada],
+) -> Result<BTreeSet<Key>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut changed_keys = BTreeSet::new();
+    let nonce_key = nft::nonce_key();
+    let mut next_nonce: Uint = wl_storage.read(&nonce_key)?.unwrap_or_default();
+    if nonce < next_nonce {
+        tracing::debug!(
+            ?nonce,
+            ?next_nonce,
+            "Ignoring NFT transfers to Namada which were already processed"
+        );
+        return Ok(changed_keys);
+    }
+    if nonce > next_nonce {
+        tracing::debug!(
+            ?nonce,
+            ?next_nonce,
+            "Keeping NFT transfers to Namada pending until the preceding \
+             events are processed"
+        );
+        let pending_key = nft::pending_transfers_key(&nonce);
+        wl_storage.write(&pending_key, transfers.to_vec())?;
+        changed_keys.insert(pending_key);
+        return Ok(changed_keys);
+    }
+    let mut transfers = transfers.to_vec();
+    loop {
+        record_nft_owners(wl_storage, &mut changed_keys, &transfers)?;
+        next_nonce = next_nonce
+            .checked_increment()
+            .ok_or_else(|| eyre::eyre!("NFT transfers nonce overflow"))?;
+        // act on the pending events which directly follow
+        let pending_key = nft::pending_transfers_key(&next_nonce);
+        match wl_storage.read(&pending_key)? {
+            Some(pending) => {
+                wl_storage.delete(&pending_key)?;
+                changed_keys.insert(pending_key);
+                transfers = pending;
+            }
+            None => break,
+        }
+    }
+    wl_storage.write(&nonce_key, next_nonce)?;
+    changed_keys.insert(nonce_key);
+    Ok(changed_keys)
+}
+
+/// Record the receivers of the given NFT transfers to Namada as the new
+/// owners of the bridged ERC-721 tokens.
+fn record_nft_owners<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    changed_keys: &mut BTreeSet<Key>,
+    transfers: &[NftTransferToNamada],
+) -> Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    for transfer in transfers {
+        let NftTransferToNamada {
+            contract,
+            token_id,
+            receiver,
+        } = transfer;
+        let owner_key = nft::owner_key(contract, token_id);
+        wl_storage.write(&owner_key, receiver)?;
+        tracing::info!(
+            "Bridged ERC-721 token - (contract - {contract}, token id - \
+             {token_id}, receiver - {receiver})",
+        );
+        changed_keys.insert(owner_key);
+    }
+    Ok(())
+}
+
 fn update_transfers_to_namada_state<'tx, D, H>(
     wl_storage: &mut WlStorage<D, H>,
     changed_keys: &mut BTreeSet<Key>,
@@ -297,6 +477,73 @@ where
     Ok((asset_count, changed_keys))
 }
 
+/// Pay the protocol rewards of the relayer of a batch of transfers
+/// to Ethereum, on top of the gas fees it collected, and update its
+/// relaying statistics.
+///
+/// Rather than being minted, the rewards are paid out of the PGF
+/// treasury, such that governance controls how much the relayers are
+/// paid in total. If the treasury falls short, the relayer is only
+/// paid what is left in it.
+///
+/// Relayers of validator set updates are not rewarded, since
+/// [`EthereumEvent::ValidatorSetUpdate`] events do not carry the
+/// address of their relayer, so there is no one to attribute them to.
+fn reward_relayer<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    relayer: &Address,
+    relayed_transfers: u64,
+) -> Result<BTreeSet<Key>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut changed_keys = BTreeSet::default();
+    let reward_per_transfer: token::Amount = wl_storage
+        .read(&bridge_storage::relay_reward_key())?
+        .unwrap_or_default();
+    let owed_reward = reward_per_transfer
+        .checked_mul(relayed_transfers.into())
+        .ok_or_else(|| eyre::eyre!("Relayer reward overflow"))?;
+    let native_token = wl_storage.storage.native_token.clone();
+    let treasury_balance =
+        read_balance(wl_storage, &native_token, &pgf::ADDRESS)?;
+    let reward = std::cmp::min(owed_reward, treasury_balance);
+    if reward < owed_reward {
+        tracing::warn!(
+            %relayer,
+            ?owed_reward,
+            ?treasury_balance,
+            "The PGF treasury cannot cover the rewards of a relayer of \
+             transfers to Ethereum"
+        );
+    }
+    if !reward.is_zero() {
+        tracing::debug!(
+            %relayer,
+            ?reward,
+            "Paying the rewards of a relayer of transfers to Ethereum"
+        );
+        transfer(wl_storage, &native_token, &pgf::ADDRESS, relayer, reward)?;
+        _ = changed_keys.insert(balance_key(&native_token, &pgf::ADDRESS));
+        _ = changed_keys.insert(balance_key(&native_token, relayer));
+    }
+
+    let stats_key = relayer_rewards::stats_key(relayer);
+    let mut stats: RelayerStats =
+        wl_storage.read(&stats_key)?.unwrap_or_default();
+    stats.relayed_batches += 1;
+    stats.relayed_transfers += relayed_transfers;
+    stats.rewards = stats
+        .rewards
+        .checked_add(reward)
+        .ok_or_else(|| eyre::eyre!("Relayer rewards overflow"))?;
+    wl_storage.write(&stats_key, stats)?;
+    _ = changed_keys.insert(stats_key);
+
+    Ok(changed_keys)
+}
+
 fn act_on_transfers_to_eth<D, H>(
     wl_storage: &mut WlStorage<D, H>,
     transfers: &[TransferToEthereum],
@@ -363,6 +610,35 @@ where
         _ = changed_keys.insert(key);
         _ = changed_keys.insert(pool_balance_key);
         _ = changed_keys.insert(relayer_rewards_key);
+        // a transfer that was relayed can no longer be cancelled
+        let cancellation_key =
+            get_cancellation_key(&pending_transfer.keccak256());
+        if wl_storage.has_key(&cancellation_key)? {
+            wl_storage.delete(&cancellation_key)?;
+            _ = changed_keys.insert(cancellation_key);
+        }
+    }
+    changed_keys.append(&mut reward_relayer(
+        wl_storage,
+        relayer,
+        transfers.len() as u64,
+    )?);
+
+    // Refund the transfers cancelled by their senders. Now that the
+    // nonce was incremented, these can no longer be relayed.
+    let mut cancelled_keys = vec![];
+    for key in pending_keys.iter() {
+        let transfer: PendingTransfer = wl_storage
+            .read(key)?
+            .expect("The pending transfer should exist");
+        if wl_storage.has_key(&get_cancellation_key(&transfer.keccak256()))? {
+            cancelled_keys.push(key.clone());
+        }
+    }
+    for key in cancelled_keys {
+        tracing::debug!(%key, "Refunding a cancelled transfer to Ethereum");
+        _ = pending_keys.remove(&key);
+        changed_keys.append(&mut refund_transfer(wl_storage, key)?);
     }
 
     if pending_keys.is_empty() {
@@ -409,6 +685,46 @@ where
     Ok(())
 }
 
+/// Refund the transfers in the Bridge pool which their senders cancelled
+/// before the given epoch, without waiting for the nonce of the Bridge
+/// pool to be incremented by a relay.
+pub fn refund_cancelled_transfers<D, H>(
+    wl_storage: &mut WlStorage<D, H>,
+    current_epoch: Epoch,
+) -> Result<BTreeSet<Key>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut changed_keys = BTreeSet::default();
+    let cancelled: Vec<(Key, KeccakHash)> = storage_api::iter_prefix::<Epoch>(
+        wl_storage,
+        &get_cancellations_prefix(),
+    )?
+    .filter_map(|result| match result {
+        Ok((key, epoch)) if epoch < current_epoch => {
+            is_cancellation_key(&key).map(|hash| Ok((key, hash)))
+        }
+        Ok(_) => None,
+        Err(err) => Some(Err(err)),
+    })
+    .collect::<storage_api::Result<_>>()?;
+    for (cancellation_key, transfer_hash) in cancelled {
+        let pending_key = get_key_from_hash(&transfer_hash);
+        if wl_storage.has_key(&pending_key)? {
+            tracing::debug!(
+                %transfer_hash,
+                "Refunding a cancelled transfer to Ethereum"
+            );
+            changed_keys.append(&mut refund_transfer(wl_storage, pending_key)?);
+        } else {
+            wl_storage.delete(&cancellation_key)?;
+            _ = changed_keys.insert(cancellation_key);
+        }
+    }
+    Ok(changed_keys)
+}
+
 fn refund_transfer<D, H>(
     wl_storage: &mut WlStorage<D, H>,
     key: Key,
@@ -426,9 +742,15 @@ where
     changed_keys.append(&mut refund_transfer_fees(wl_storage, &transfer)?);
     changed_keys.append(&mut refund_transferred_assets(wl_storage, &transfer)?);
 
-    // Delete the key from the bridge pool
+    // Delete the key from the bridge pool, along with any request to
+    // cancel the transfer
     wl_storage.delete(&key)?;
     _ = changed_keys.insert(key);
+    let cancellation_key = get_cancellation_key(&transfer.keccak256());
+    if wl_storage.has_key(&cancellation_key)? {
+        wl_storage.delete(&cancellation_key)?;
+        _ = changed_keys.insert(cancellation_key);
+    }
 
     Ok(changed_keys)
 }
@@ -584,6 +906,7 @@ mod tests {
     use namada_core::types::{address, eth_bridge_pool};
 
     use super::*;
+    use crate::parameters::UpgradeableContract;
     use crate::test_utils::{self, stored_keys_count};
 
     fn init_storage(wl_storage: &mut TestWlStorage) {
@@ -841,6 +1164,196 @@ mod tests {
         );
     }
 
+    #[test]
+    /// Test that acting on a TransfersToNamadaBatch event processes each
+    /// of its events, and that events which were already processed as
+    /// part of a batch are ignored
+    fn test_act_on_transfers_to_namada_batch() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        let transfer = TransferToNamada {
+            amount: Amount::from(100),
+            asset: DAI_ERC20_ETH_ADDRESS,
+            receiver: address::testing::established_address_1(),
+        };
+        let event = EthereumEvent::TransfersToNamadaBatch {
+            nonce: arbitrary_nonce(),
+            batches: vec![vec![transfer.clone()], vec![transfer.clone()]],
+        };
+
+        let changed_keys = act_on(&mut wl_storage, event).unwrap();
+
+        assert!(!changed_keys.is_empty());
+        assert_eq!(
+            wl_storage
+                .ethbridge_queries()
+                .get_next_nam_transfers_nonce(),
+            arbitrary_nonce() + 2
+        );
+
+        // an event already processed as part of the batch has no effect
+        let event = EthereumEvent::TransfersToNamada {
+            nonce: arbitrary_nonce() + 1,
+            transfers: vec![transfer],
+        };
+        let changed_keys = act_on(&mut wl_storage, event).unwrap();
+        assert!(changed_keys.is_empty());
+    }
+
+    #[test]
+    /// Test that acting on an NftTransfersToNamada event records the new
+    /// owners of the bridged tokens, and that replayed events are ignored
+    fn test_act_on_nft_transfers_to_namada() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        let contract = EthAddress([1; 20]);
+        let token_id = Uint::from(42);
+        let event = |receiver: Address| EthereumEvent::NftTransfersToNamada {
+            nonce: 0.into(),
+            transfers: vec![NftTransferToNamada {
+                contract,
+                token_id,
+                receiver,
+            }],
+        };
+        let receiver = address::testing::established_address_1();
+
+        let changed_keys =
+            act_on(&mut wl_storage, event(receiver.clone())).unwrap();
+
+        let owner_key = nft::owner_key(&contract, &token_id);
+        assert_eq!(
+            changed_keys,
+            BTreeSet::from([owner_key.clone(), nft::nonce_key()])
+        );
+        let owner: Option<Address> = wl_storage.read(&owner_key).unwrap();
+        assert_eq!(owner, Some(receiver.clone()));
+        let next_nonce: Option<Uint> =
+            wl_storage.read(&nft::nonce_key()).unwrap();
+        assert_eq!(next_nonce, Some(1.into()));
+
+        // replaying the event has no effect
+        let other_receiver = address::testing::established_address_2();
+        let changed_keys =
+            act_on(&mut wl_storage, event(other_receiver)).unwrap();
+        assert!(changed_keys.is_empty());
+        let owner: Option<Address> = wl_storage.read(&owner_key).unwrap();
+        assert_eq!(owner, Some(receiver));
+    }
+
+    /// Test that NFT transfers to Namada confirmed ahead of the events
+    /// preceding them are kept pending, and acted on in order of nonce.
+    #[test]
+    fn test_act_on_out_of_order_nft_transfers_to_namada() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        let contract = EthAddress([1; 20]);
+        let token_id = Uint::from(42);
+        let event = |nonce: u64, receiver: Address| {
+            EthereumEvent::NftTransfersToNamada {
+                nonce: nonce.into(),
+                transfers: vec![NftTransferToNamada {
+                    contract,
+                    token_id,
+                    receiver,
+                }],
+            }
+        };
+        let first_receiver = address::testing::established_address_1();
+        let second_receiver = address::testing::established_address_2();
+        let owner_key = nft::owner_key(&contract, &token_id);
+        let pending_key = nft::pending_transfers_key(&1.into());
+
+        // the second event is kept pending
+        let changed_keys =
+            act_on(&mut wl_storage, event(1, second_receiver.clone())).unwrap();
+        assert_eq!(changed_keys, BTreeSet::from([pending_key.clone()]));
+        let owner: Option<Address> = wl_storage.read(&owner_key).unwrap();
+        assert_eq!(owner, None);
+        let next_nonce: Option<Uint> =
+            wl_storage.read(&nft::nonce_key()).unwrap();
+        assert_eq!(next_nonce, None);
+
+        // the first event releases the second one
+        let changed_keys =
+            act_on(&mut wl_storage, event(0, first_receiver)).unwrap();
+        assert_eq!(
+            changed_keys,
+            BTreeSet::from([
+                owner_key.clone(),
+                pending_key.clone(),
+                nft::nonce_key()
+            ])
+        );
+        let owner: Option<Address> = wl_storage.read(&owner_key).unwrap();
+        assert_eq!(owner, Some(second_receiver));
+        let next_nonce: Option<Uint> =
+            wl_storage.read(&nft::nonce_key()).unwrap();
+        assert_eq!(next_nonce, Some(2.into()));
+        assert!(!wl_storage.has_key(&pending_key).unwrap());
+    }
+
+    /// Test that a confirmed bridge contract handover replaces the bridge
+    /// contract with the scheduled one, and clears the rotation.
+    #[test]
+    fn test_act_on_bridge_contract_handover() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        let rotation = ContractRotation {
+            contract: UpgradeableContract {
+                address: EthAddress([7; 20]),
+                version: Default::default(),
+            },
+            handover_height: 100u64.into(),
+        };
+        let rotation_key = bridge_storage::bridge_contract_rotation_key();
+        let rotation_valset_key =
+            bridge_storage::bridge_contract_rotation_valset_key();
+        wl_storage.write(&rotation_key, &rotation).unwrap();
+        wl_storage
+            .write_bytes(&rotation_valset_key, [0u8; 1])
+            .unwrap();
+        wl_storage.commit_block().expect("Test failed");
+
+        // handovers which don't match the rotation are ignored
+        for (new_contract, handover_height) in
+            [(EthAddress([8; 20]), 100u64), (EthAddress([7; 20]), 101u64)]
+        {
+            let event = EthereumEvent::BridgeContractHandover {
+                new_contract,
+                handover_height: handover_height.into(),
+            };
+            assert!(act_on(&mut wl_storage, event).unwrap().is_empty());
+        }
+        assert!(wl_storage.has_key(&rotation_key).unwrap());
+
+        let event = EthereumEvent::BridgeContractHandover {
+            new_contract: EthAddress([7; 20]),
+            handover_height: 100u64.into(),
+        };
+        let changed_keys = act_on(&mut wl_storage, event.clone()).unwrap();
+        assert_eq!(
+            changed_keys,
+            BTreeSet::from([
+                bridge_storage::bridge_contract_key(),
+                rotation_key.clone(),
+                rotation_valset_key.clone(),
+            ])
+        );
+        let bridge_contract: Option<UpgradeableContract> = wl_storage
+            .read(&bridge_storage::bridge_contract_key())
+            .unwrap();
+        assert_eq!(bridge_contract, Some(rotation.contract));
+        assert!(!wl_storage.has_key(&rotation_key).unwrap());
+        assert!(!wl_storage.has_key(&rotation_valset_key).unwrap());
+
+        // replaying the handover has no effect
+        assert!(act_on(&mut wl_storage, event).unwrap().is_empty());
+    }
+
     /// Parameters to test minting DAI in Namada.
     struct TestMintDai {
         /// The token cap of DAI.
@@ -1060,6 +1573,7 @@ mod tests {
         assert!(changed_keys.remove(&pool_nam_balance_key));
         assert!(changed_keys.remove(&pool_erc_balance_key));
         assert!(changed_keys.remove(&get_nonce_key()));
+        assert!(changed_keys.remove(&relayer_rewards::stats_key(&relayer)));
         assert!(changed_keys.iter().all(|k| pending_keys.contains(k)));
 
         let prefix = BRIDGE_POOL_ADDRESS.to_db_key().into();
@@ -1112,6 +1626,72 @@ mod tests {
         assert_eq!(bp_erc_balance_post, Amount::from(0));
     }
 
+    #[test]
+    /// Test that the relayer of a batch of transfers to Ethereum is
+    /// paid the relay reward set by governance, for each transfer, out
+    /// of the PGF treasury, and that its relaying statistics are updated.
+    /// The rewards must be capped by the balance of the treasury.
+    fn test_relayer_rewards() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        init_storage(&mut wl_storage);
+        wl_storage
+            .write(&bridge_storage::relay_reward_key(), Amount::from(100))
+            .expect("Test failed");
+        let treasury_balance_key = balance_key(&nam(), &pgf::ADDRESS);
+        wl_storage
+            .write(&treasury_balance_key, Amount::from(150))
+            .expect("Test failed");
+        let pending_transfers = init_bridge_pool_transfers(
+            &mut wl_storage,
+            [
+                (EthAddress([0xff; 20]), TransferData::default()),
+                (EthAddress([0xee; 20]), TransferData::default()),
+            ],
+        );
+        init_balance(&mut wl_storage, &pending_transfers);
+        let relayer = gen_established_address("random");
+        let event = EthereumEvent::TransfersToEthereum {
+            nonce: arbitrary_nonce(),
+            transfers: pending_transfers
+                .iter()
+                .map(TransferToEthereum::from)
+                .collect(),
+            relayer: relayer.clone(),
+        };
+        let nam_supply_pre: Amount = wl_storage
+            .read(&minted_balance_key(&nam()))
+            .expect("Test failed")
+            .unwrap_or_default();
+
+        let changed_keys = act_on(&mut wl_storage, event).unwrap();
+
+        assert!(changed_keys.contains(&treasury_balance_key));
+        let treasury_balance: Amount = wl_storage
+            .read(&treasury_balance_key)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(treasury_balance, Amount::zero());
+        let nam_supply_post: Amount = wl_storage
+            .read(&minted_balance_key(&nam()))
+            .expect("Test failed")
+            .unwrap_or_default();
+        assert_eq!(nam_supply_post, nam_supply_pre);
+        let stats: RelayerStats = wl_storage
+            .read(&relayer_rewards::stats_key(&relayer))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(
+            stats,
+            RelayerStats {
+                relayed_batches: 1,
+                relayed_transfers: 2,
+                rewards: Amount::from(150),
+            }
+        );
+    }
+
     #[test]
     /// Test that the transfers time out in the bridge pool then the refund when
     /// we act on a TransfersToEthereum
@@ -1229,6 +1809,118 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that cancelled transfers are refunded when we act on a
+    /// TransfersToEthereum, while other transfers remain pending
+    fn test_act_on_cancelled_transfers_to_eth() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        init_storage(&mut wl_storage);
+        let pending_transfers = init_bridge_pool(&mut wl_storage);
+        init_balance(&mut wl_storage, &pending_transfers);
+        wl_storage
+            .storage
+            .commit_block(MockDBWriteBatch)
+            .expect("Test failed");
+        wl_storage.storage.block.height += 1;
+
+        let cancelled = &pending_transfers[0];
+        let pending = &pending_transfers[1];
+        let cancellation_key = get_cancellation_key(&cancelled.keccak256());
+        let current_epoch = wl_storage.storage.block.epoch;
+        wl_storage
+            .write(&cancellation_key, current_epoch)
+            .expect("Test failed");
+
+        let event = EthereumEvent::TransfersToEthereum {
+            nonce: arbitrary_nonce(),
+            transfers: vec![],
+            relayer: gen_implicit_address(),
+        };
+        let changed_keys = act_on(&mut wl_storage, event).unwrap();
+
+        let cancelled_key = get_pending_key(cancelled);
+        assert!(changed_keys.contains(&cancelled_key));
+        assert!(changed_keys.contains(&cancellation_key));
+        assert!(!wl_storage.has_key(&cancelled_key).expect("Test failed"));
+        assert!(!wl_storage.has_key(&cancellation_key).expect("Test failed"));
+        assert!(
+            wl_storage
+                .has_key(&get_pending_key(pending))
+                .expect("Test failed")
+        );
+
+        // the escrowed tokens of the cancelled transfer were refunded
+        let token = cancelled.token_address();
+        let sender_key = balance_key(&token, &cancelled.transfer.sender);
+        let sender_balance: Amount = wl_storage
+            .read(&sender_key)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(sender_balance, cancelled.transfer.amount);
+        let payer_key = balance_key(
+            &cancelled.gas_fee.token,
+            &address::testing::established_address_2(),
+        );
+        let payer_balance: Amount = wl_storage
+            .read(&payer_key)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(payer_balance, cancelled.gas_fee.amount);
+    }
+
+    #[test]
+    /// Test that cancelled transfers are refunded in the epoch following
+    /// their cancellation, even if no transfers were relayed
+    fn test_refund_cancelled_transfers() {
+        let mut wl_storage = TestWlStorage::default();
+        test_utils::bootstrap_ethereum_bridge(&mut wl_storage);
+        wl_storage.commit_block().expect("Test failed");
+        init_storage(&mut wl_storage);
+        let pending_transfers = init_bridge_pool(&mut wl_storage);
+        init_balance(&mut wl_storage, &pending_transfers);
+
+        let cancelled = &pending_transfers[0];
+        let pending = &pending_transfers[1];
+        let cancelled_key = get_pending_key(cancelled);
+        let cancellation_key = get_cancellation_key(&cancelled.keccak256());
+        let cancellation_epoch = wl_storage.storage.block.epoch;
+        wl_storage
+            .write(&cancellation_key, cancellation_epoch)
+            .expect("Test failed");
+
+        // the transfer is not refunded in the epoch it was cancelled in
+        let changed_keys =
+            refund_cancelled_transfers(&mut wl_storage, cancellation_epoch)
+                .expect("Test failed");
+        assert!(changed_keys.is_empty());
+        assert!(wl_storage.has_key(&cancelled_key).expect("Test failed"));
+
+        let changed_keys = refund_cancelled_transfers(
+            &mut wl_storage,
+            cancellation_epoch.next(),
+        )
+        .expect("Test failed");
+        assert!(changed_keys.contains(&cancelled_key));
+        assert!(changed_keys.contains(&cancellation_key));
+        assert!(!wl_storage.has_key(&cancelled_key).expect("Test failed"));
+        assert!(!wl_storage.has_key(&cancellation_key).expect("Test failed"));
+        assert!(
+            wl_storage
+                .has_key(&get_pending_key(pending))
+                .expect("Test failed")
+        );
+        let sender_balance: Amount = wl_storage
+            .read(&balance_key(
+                &cancelled.token_address(),
+                &cancelled.transfer.sender,
+            ))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(sender_balance, cancelled.transfer.amount);
+    }
+
     #[test]
     fn test_redeem_native_token() -> Result<()> {
         let mut wl_storage = TestWlStorage::default();
@@ -3,15 +3,16 @@
 mod eth_msgs;
 mod events;
 
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use borsh::BorshDeserialize;
 use eth_msgs::EthMsgUpdate;
+pub use events::refund_cancelled_transfers;
 use eyre::Result;
 use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, WlStorage, DB};
 use namada_core::types::address::Address;
-use namada_core::types::ethereum_events::EthereumEvent;
+use namada_core::types::ethereum_events::{EthereumEvent, TransfersToNamada};
 use namada_core::types::internal::ExpiredTx;
 use namada_core::types::storage::{BlockHeight, Epoch, Key};
 use namada_core::types::token::Amount;
@@ -64,7 +65,7 @@ where
          protocol transaction"
     );
 
-    let updates = events
+    let updates = split_batched_events(events)
         .into_iter()
         .filter_map(|multisigned| {
             // NB: discard events with outdated nonces
@@ -75,7 +76,8 @@ where
         })
         .collect();
 
-    let voting_powers = utils::get_voting_powers(wl_storage, &updates)?;
+    let voting_powers =
+        utils::get_eligible_voting_powers(wl_storage, &updates)?;
 
     changed_keys.append(&mut apply_updates(
         wl_storage,
@@ -89,6 +91,35 @@ where
     })
 }
 
+/// Split the [`EthereumEvent::TransfersToNamadaBatch`] events voted on by
+/// validators into the [`EthereumEvent::TransfersToNamada`] events they are
+/// made of, merging the signers of identical events. Votes are thus tallied
+/// per nonce, regardless of how the oracle of each validator batched the
+/// events it saw.
+fn split_batched_events(
+    events: Vec<MultiSignedEthEvent>,
+) -> Vec<MultiSignedEthEvent> {
+    let mut split: BTreeMap<EthereumEvent, BTreeSet<(Address, BlockHeight)>> =
+        BTreeMap::new();
+    for MultiSignedEthEvent { event, signers } in events {
+        match event {
+            EthereumEvent::TransfersToNamadaBatch { nonce, batches } => {
+                for event in TransfersToNamada::from_batch(nonce, batches) {
+                    split
+                        .entry(event.into())
+                        .or_default()
+                        .extend(signers.iter().cloned());
+                }
+            }
+            event => split.entry(event).or_default().extend(signers),
+        }
+    }
+    split
+        .into_iter()
+        .map(|(event, signers)| MultiSignedEthEvent { event, signers })
+        .collect()
+}
+
 /// Apply votes to Ethereum events in storage and act on any events which are
 /// confirmed.
 ///
@@ -616,6 +647,106 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    /// Test that validators whose oracles batched the same transfers to
+    /// Namada events differently have their votes tallied per nonce, such
+    /// that the events are confirmed.
+    fn test_apply_derived_tx_differently_batched_events() {
+        let validator_a = address::testing::established_address_2();
+        let validator_b = address::testing::established_address_3();
+        let (mut wl_storage, _) = test_utils::setup_storage_with_validators(
+            HashMap::from_iter(vec![
+                (validator_a.clone(), Amount::native_whole(100)),
+                (validator_b.clone(), Amount::native_whole(100)),
+            ]),
+        );
+        test_utils::whitelist_tokens(
+            &mut wl_storage,
+            [(
+                DAI_ERC20_ETH_ADDRESS,
+                test_utils::WhitelistMeta {
+                    cap: Amount::max(),
+                    denom: 18,
+                },
+            )],
+        );
+        let receiver = address::testing::established_address_1();
+        let transfer = |amount: u64| TransferToNamada {
+            amount: Amount::from(amount),
+            asset: DAI_ERC20_ETH_ADDRESS,
+            receiver: receiver.clone(),
+        };
+
+        // validator A saw nonces 0 and 1 in a single batch, while validator
+        // B saw them as separate events
+        let result = apply_derived_tx(
+            &mut wl_storage,
+            vec![
+                MultiSignedEthEvent {
+                    event: EthereumEvent::TransfersToNamadaBatch {
+                        nonce: 0.into(),
+                        batches: vec![vec![transfer(100)], vec![transfer(50)]],
+                    },
+                    signers: BTreeSet::from([(
+                        validator_a.clone(),
+                        BlockHeight(100),
+                    )]),
+                },
+                MultiSignedEthEvent {
+                    event: EthereumEvent::TransfersToNamada {
+                        nonce: 0.into(),
+                        transfers: vec![transfer(100)],
+                    },
+                    signers: BTreeSet::from([(
+                        validator_b.clone(),
+                        BlockHeight(100),
+                    )]),
+                },
+                MultiSignedEthEvent {
+                    event: EthereumEvent::TransfersToNamada {
+                        nonce: 1.into(),
+                        transfers: vec![transfer(50)],
+                    },
+                    signers: BTreeSet::from([(
+                        validator_b.clone(),
+                        BlockHeight(100),
+                    )]),
+                },
+            ],
+        );
+        assert!(result.is_ok());
+
+        for (nonce, amount) in [(0u64, 100u64), (1, 50)] {
+            let event = EthereumEvent::TransfersToNamada {
+                nonce: nonce.into(),
+                transfers: vec![transfer(amount)],
+            };
+            let eth_msg_keys = vote_tallies::Keys::from(&event);
+            let seen: bool = wl_storage
+                .read(&eth_msg_keys.seen())
+                .expect("Test failed")
+                .expect("Test failed");
+            assert!(seen, "The event with nonce {nonce} should be seen");
+            let seen_by: Votes = wl_storage
+                .read(&eth_msg_keys.seen_by())
+                .expect("Test failed")
+                .expect("Test failed");
+            assert_eq!(
+                seen_by,
+                Votes::from([
+                    (validator_a.clone(), BlockHeight(100)),
+                    (validator_b.clone(), BlockHeight(100)),
+                ])
+            );
+        }
+        let dai_token = wrapped_erc20s::token(&DAI_ERC20_ETH_ADDRESS);
+        let balance: Amount = wl_storage
+            .read(&balance_key(&dai_token, &receiver))
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(balance, Amount::from(150));
+    }
+
     #[test]
     /// Assert we don't return anything if we try to get the votes for an empty
     /// set of updates
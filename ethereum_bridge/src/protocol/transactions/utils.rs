@@ -2,11 +2,18 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use eyre::eyre;
 use itertools::Itertools;
+use namada_core::ledger::eth_bridge::storage as bridge_storage;
 use namada_core::ledger::storage::{DBIter, StorageHasher, WlStorage, DB};
+use namada_core::ledger::storage_api::StorageRead;
 use namada_core::types::address::Address;
-use namada_core::types::storage::BlockHeight;
+use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::token;
+use namada_core::types::voting_power::FractionalVotingPower;
+use namada_proof_of_stake::epoched::{
+    EpochOffset, OffsetMaxProposalPeriodPlus,
+};
 use namada_proof_of_stake::pos_queries::PosQueries;
+use namada_proof_of_stake::read_pos_params;
 use namada_proof_of_stake::types::WeightedValidator;
 
 /// Proof of some arbitrary tally whose voters can be queried.
@@ -53,6 +60,132 @@ where
     Ok(voting_powers)
 }
 
+/// Like [`get_voting_powers`], but the voting power of the validators
+/// who are not yet eligible to take part in the quorums of Ethereum events
+/// and Bridge pool roots is set to zero, such that their votes are
+/// recorded, but do not count toward any quorum.
+///
+/// Validators are eligible once they have been in the consensus set for
+/// the number of epochs set by governance, prior to the epoch they voted
+/// in. Epochs before genesis are regarded as being spent in the consensus
+/// set. The number of epochs looked back at is capped at the number of past
+/// epochs whose consensus sets are retained in storage.
+///
+/// Quorums are measured against the total stake of the consensus set, so
+/// if the eligible validators of an epoch hold no more than 2/3 of its
+/// total stake, no quorum could ever be reached by them alone. As a
+/// fallback, every consensus validator of such an epoch is then regarded as
+/// eligible, such that the bridge does not halt.
+pub(super) fn get_eligible_voting_powers<D, H, P>(
+    wl_storage: &WlStorage<D, H>,
+    proof: P,
+) -> eyre::Result<HashMap<(Address, BlockHeight), token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    P: GetVoters,
+{
+    let mut voting_powers = get_voting_powers(wl_storage, proof)?;
+    let min_consensus_epochs: u64 = wl_storage
+        .read(&bridge_storage::vext_min_consensus_epochs_key())?
+        .unwrap_or_default();
+    if min_consensus_epochs == 0 {
+        return Ok(voting_powers);
+    }
+    let params = read_pos_params(wl_storage)?;
+    let min_consensus_epochs =
+        min_consensus_epochs.min(OffsetMaxProposalPeriodPlus::value(&params));
+
+    let mut consensus_sets: HashMap<Epoch, BTreeSet<Address>> = HashMap::new();
+    let mut eligible_sets: HashMap<Epoch, Option<BTreeSet<Address>>> =
+        HashMap::new();
+    for ((validator, height), voting_power) in voting_powers.iter_mut() {
+        let epoch = wl_storage.pos_queries().get_epoch(*height).expect(
+            "The epoch of the height of a vote extension should be known",
+        );
+        let eligible = eligible_sets
+            .entry(epoch)
+            .or_insert_with(|| {
+                get_eligible_validators(
+                    wl_storage,
+                    epoch,
+                    min_consensus_epochs,
+                    &mut consensus_sets,
+                )
+            })
+            .as_ref()
+            .map(|eligible| eligible.contains(validator))
+            .unwrap_or(true);
+        if !eligible {
+            tracing::debug!(
+                %validator,
+                %epoch,
+                min_consensus_epochs,
+                "Ignoring the vote of a validator which has not been in the \
+                 consensus set for long enough"
+            );
+            *voting_power = token::Amount::zero();
+        }
+    }
+    Ok(voting_powers)
+}
+
+/// Get the consensus validators of the given `epoch` which have been in the
+/// consensus set for the `min_consensus_epochs` preceding it, or [`None`] if
+/// they hold no more than 2/3 of the total stake of the consensus set, in
+/// which case every consensus validator is regarded as eligible.
+fn get_eligible_validators<D, H>(
+    wl_storage: &WlStorage<D, H>,
+    epoch: Epoch,
+    min_consensus_epochs: u64,
+    consensus_sets: &mut HashMap<Epoch, BTreeSet<Address>>,
+) -> Option<BTreeSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let first_epoch = epoch.0.saturating_sub(min_consensus_epochs);
+    let mut eligible = BTreeSet::new();
+    let mut eligible_stake = token::Amount::zero();
+    for validator in wl_storage
+        .pos_queries()
+        .get_consensus_validators(Some(epoch))
+        .iter()
+    {
+        let is_eligible = (first_epoch..epoch.0).all(|past_epoch| {
+            consensus_sets
+                .entry(Epoch(past_epoch))
+                .or_insert_with_key(|&past_epoch| {
+                    wl_storage
+                        .pos_queries()
+                        .get_consensus_validators(Some(past_epoch))
+                        .iter()
+                        .map(|weighted| weighted.address)
+                        .collect()
+                })
+                .contains(&validator.address)
+        });
+        if is_eligible {
+            eligible_stake += validator.bonded_stake;
+            eligible.insert(validator.address);
+        }
+    }
+    let total_stake =
+        wl_storage.pos_queries().get_total_voting_power(Some(epoch));
+    if eligible_stake <= FractionalVotingPower::TWO_THIRDS * total_stake {
+        tracing::warn!(
+            %epoch,
+            ?eligible_stake,
+            ?total_stake,
+            "The validators eligible to vote on Ethereum events and Bridge \
+             pool roots cannot reach a quorum on their own, so every \
+             consensus validator is regarded as eligible"
+        );
+        return None;
+    }
+    Some(eligible)
+}
+
 // TODO: we might be able to remove allocation here
 pub(super) fn get_consensus_validators<D, H>(
     wl_storage: &WlStorage<D, H>,
@@ -123,11 +256,14 @@ mod tests {
     use std::collections::HashSet;
 
     use assert_matches::assert_matches;
+    use namada_core::ledger::storage_api::StorageWrite;
     use namada_core::types::address;
     use namada_core::types::ethereum_events::testing::arbitrary_bonded_stake;
-    use namada_core::types::voting_power::FractionalVotingPower;
+    use namada_proof_of_stake::parameters::OwnedPosParams;
+    use namada_proof_of_stake::write_pos_params;
 
     use super::*;
+    use crate::test_utils;
 
     #[test]
     /// Test getting the voting power for the sole consensus validator from the
@@ -254,4 +390,95 @@ mod tests {
             Some(v) if *v == expected_stake
         );
     }
+
+    impl GetVoters for HashSet<(Address, BlockHeight)> {
+        fn get_voters(self) -> HashSet<(Address, BlockHeight)> {
+            self
+        }
+    }
+
+    /// Test that the votes of validators who have not been in the consensus
+    /// set for long enough do not count toward quorums.
+    #[test]
+    fn test_get_eligible_voting_powers() {
+        let validator_1 = address::testing::established_address_1();
+        let validator_2 = address::testing::established_address_2();
+        let stake_1 = token::Amount::native_whole(300);
+        let stake = token::Amount::native_whole(100);
+
+        // start epoch 0 with validator 1, and insert validator 2 at epoch 1
+        let (mut wl_storage, _) = test_utils::setup_storage_with_validators(
+            HashMap::from([(validator_1.clone(), stake_1)]),
+        );
+        let params = OwnedPosParams {
+            pipeline_len: 1,
+            ..Default::default()
+        };
+        write_pos_params(&mut wl_storage, &params).expect("Test failed");
+        test_utils::append_validators_to_storage(
+            &mut wl_storage,
+            HashMap::from([(validator_2.clone(), stake)]),
+        );
+        wl_storage.storage.block.pred_epochs.new_epoch(10.into());
+
+        let voters = HashSet::from([
+            (validator_1.clone(), BlockHeight(10)),
+            (validator_2.clone(), BlockHeight(10)),
+        ]);
+
+        // every validator is eligible by default
+        let voting_powers =
+            get_eligible_voting_powers(&wl_storage, voters.clone())
+                .expect("Test failed");
+        assert_eq!(
+            voting_powers[&(validator_2.clone(), BlockHeight(10))],
+            stake
+        );
+
+        // validator 2 has yet to spend an epoch in the consensus set
+        wl_storage
+            .write(&bridge_storage::vext_min_consensus_epochs_key(), 1u64)
+            .expect("Test failed");
+        let voting_powers = get_eligible_voting_powers(&wl_storage, voters)
+            .expect("Test failed");
+        assert_eq!(voting_powers[&(validator_1, BlockHeight(10))], stake_1);
+        assert!(voting_powers[&(validator_2, BlockHeight(10))].is_zero());
+    }
+
+    /// Test that every consensus validator is regarded as eligible if the
+    /// eligible validators cannot reach a quorum on their own.
+    #[test]
+    fn test_get_eligible_voting_powers_fallback() {
+        let validator_1 = address::testing::established_address_1();
+        let validator_2 = address::testing::established_address_2();
+        let stake = token::Amount::native_whole(100);
+
+        // start epoch 0 with validator 1, and insert validator 2 at epoch 1,
+        // such that the ineligible validator 2 holds half of the stake
+        let (mut wl_storage, _) = test_utils::setup_storage_with_validators(
+            HashMap::from([(validator_1.clone(), stake)]),
+        );
+        let params = OwnedPosParams {
+            pipeline_len: 1,
+            ..Default::default()
+        };
+        write_pos_params(&mut wl_storage, &params).expect("Test failed");
+        test_utils::append_validators_to_storage(
+            &mut wl_storage,
+            HashMap::from([(validator_2.clone(), stake)]),
+        );
+        wl_storage.storage.block.pred_epochs.new_epoch(10.into());
+        wl_storage
+            .write(&bridge_storage::vext_min_consensus_epochs_key(), 1u64)
+            .expect("Test failed");
+
+        let voters = HashSet::from([
+            (validator_1.clone(), BlockHeight(10)),
+            (validator_2.clone(), BlockHeight(10)),
+        ]);
+        let voting_powers = get_eligible_voting_powers(&wl_storage, voters)
+            .expect("Test failed");
+        assert_eq!(voting_powers[&(validator_1, BlockHeight(10))], stake);
+        assert_eq!(voting_powers[&(validator_2, BlockHeight(10))], stake);
+    }
 }
@@ -1,7 +1,8 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada_core::hints;
 use namada_core::ledger::eth_bridge::storage::{
-    active_key, bridge_pool, whitelist,
+    active_key, bridge_contract_rotation_key, bridge_pool,
+    max_vext_eth_events_key, nft, whitelist,
 };
 use namada_core::ledger::storage;
 use namada_core::ledger::storage::{StoreType, WlStorage};
@@ -16,16 +17,15 @@ use namada_core::types::keccak::KeccakHash;
 use namada_core::types::storage::{BlockHeight, Epoch, Key as StorageKey};
 use namada_core::types::token;
 use namada_core::types::vote_extensions::validator_set_update::{
-    EthAddrBook, ValidatorSetArgs, VotingPowersMap, VotingPowersMapExt,
-};
-use namada_core::types::voting_power::{
-    EthBridgeVotingPower, FractionalVotingPower,
+    EthAddrBook, NormalizedVotingPower, TotalVotingPower, ValidatorSetArgs,
+    ValidatorSetSnapshot, VotingPowersMap, VotingPowersMapExt,
 };
 use namada_proof_of_stake::pos_queries::{ConsensusValidators, PosQueries};
 use namada_proof_of_stake::{
     validator_eth_cold_key_handle, validator_eth_hot_key_handle,
 };
 
+use crate::parameters::ContractRotation;
 use crate::storage::proof::BridgePoolRootProof;
 use crate::storage::vote_tallies;
 
@@ -191,6 +191,34 @@ where
             .get_event_nonce()
     }
 
+    /// Get the rotation of the bridge contract scheduled by governance, if
+    /// it has not been handed over yet.
+    pub fn get_bridge_contract_rotation(self) -> Option<ContractRotation> {
+        self.wl_storage
+            .read(&bridge_contract_rotation_key())
+            .expect(
+                "Reading the Ethereum bridge contract rotation shouldn't fail.",
+            )
+    }
+
+    /// Get the maximum number of Ethereum events to include in a single
+    /// vote extension, if governance has set any.
+    pub fn get_max_vext_eth_events(self) -> Option<u64> {
+        self.wl_storage.read(&max_vext_eth_events_key()).expect(
+            "Reading the max Ethereum events per vote extension shouldn't \
+             fail.",
+        )
+    }
+
+    /// Get the nonce of the next NFT transfers to Namada event to be
+    /// processed.
+    pub fn get_next_nft_transfers_nonce(self) -> Uint {
+        self.wl_storage
+            .read(&nft::nonce_key())
+            .expect("Reading the NFT transfers nonce shouldn't fail.")
+            .unwrap_or_default()
+    }
+
     /// Get the latest nonce for the Ethereum bridge
     /// pool.
     pub fn get_bridge_pool_nonce(self) -> Uint {
@@ -382,18 +410,11 @@ where
         let total_power = self
             .wl_storage
             .pos_queries()
-            .get_total_voting_power(Some(epoch))
-            .into();
+            .get_total_voting_power(Some(epoch));
         let (validators, voting_powers) = voting_powers_map
-            .get_sorted()
+            .get_normalized(total_power)
             .into_iter()
-            .map(|(addr_book, &power)| {
-                let voting_power: EthBridgeVotingPower =
-                    FractionalVotingPower::new(power.into(), total_power)
-                        .expect("Fractional voting power should be >1")
-                        .into();
-                (select_validator(addr_book), voting_power)
-            })
+            .map(|power| (select_validator(&power.addr_book), power.normalized))
             .unzip();
 
         (
@@ -406,6 +427,50 @@ where
         )
     }
 
+    /// Query the voting powers of the consensus validators at the given
+    /// [`Epoch`], normalized to the fixed-point encoding used by the
+    /// Ethereum bridge smart contracts. Validators are sorted in the same
+    /// order as in the [`ValidatorSetArgs`] of that [`Epoch`].
+    pub fn get_normalized_voting_powers(
+        self,
+        epoch: Option<Epoch>,
+    ) -> Vec<NormalizedVotingPower> {
+        let epoch = epoch
+            .unwrap_or_else(|| self.wl_storage.storage.get_current_epoch().0);
+        let total_power = self
+            .wl_storage
+            .pos_queries()
+            .get_total_voting_power(Some(epoch));
+        self.get_consensus_eth_addresses(Some(epoch))
+            .iter()
+            .map(|(addr_book, _, power)| (addr_book, power))
+            .collect::<VotingPowersMap>()
+            .get_normalized(total_power)
+    }
+
+    /// Query the [`TotalVotingPower`] of the consensus validators at the
+    /// given [`Epoch`].
+    pub fn get_total_normalized_voting_power(
+        self,
+        epoch: Option<Epoch>,
+    ) -> TotalVotingPower {
+        let epoch = epoch
+            .unwrap_or_else(|| self.wl_storage.storage.get_current_epoch().0);
+        let voting_power = self
+            .wl_storage
+            .pos_queries()
+            .get_total_voting_power(Some(epoch));
+        let normalized = self
+            .get_normalized_voting_powers(Some(epoch))
+            .into_iter()
+            .map(|power| u128::from(power.normalized))
+            .sum();
+        TotalVotingPower {
+            voting_power,
+            normalized,
+        }
+    }
+
     /// Query the Bridge [`ValidatorSetArgs`] at the given [`Epoch`].
     /// Also returns a map of each validator's voting power.
     #[inline]
@@ -432,6 +497,27 @@ where
         )
     }
 
+    /// Capture a [`ValidatorSetSnapshot`] of the Bridge and Governance
+    /// validator sets at the given [`Epoch`].
+    pub fn get_validator_set_snapshot(
+        self,
+        epoch: Option<Epoch>,
+    ) -> ValidatorSetSnapshot {
+        let epoch = epoch
+            .unwrap_or_else(|| self.wl_storage.storage.get_current_epoch().0);
+        let (bridge, voting_powers_map) =
+            self.get_bridge_validator_set(Some(epoch));
+        let (governance, _) = self.get_governance_validator_set(Some(epoch));
+        let (bridge_hash, governance_hash) =
+            voting_powers_map.get_bridge_and_gov_hashes(epoch);
+        ValidatorSetSnapshot {
+            bridge,
+            governance,
+            bridge_hash,
+            governance_hash,
+        }
+    }
+
     /// Check if the token at the given [`EthAddress`] is whitelisted.
     pub fn is_token_whitelisted(self, &token: &EthAddress) -> bool {
         let key = whitelist::Key {
@@ -563,6 +649,25 @@ where
     /// For a transfers to Namada event to be considered valid,
     /// the nonce of this kind of event must not be lower than
     /// the one stored in Namada.
+    ///
+    /// ## Batches of transfers to Namada
+    ///
+    /// A batch of transfers to Namada events is considered valid
+    /// as long as it is not empty, and the nonce of its last event
+    /// does not overflow, and is not lower than the one stored in
+    /// Namada. Events in the batch which were already processed are
+    /// ignored.
+    ///
+    /// ## NFT transfers to Namada
+    ///
+    /// Likewise, the nonce of an NFT transfers to Namada event
+    /// must not be lower than the one stored in Namada.
+    ///
+    /// ## Bridge contract handovers
+    ///
+    /// A bridge contract handover is only valid if it matches the
+    /// rotation of the bridge contract scheduled in storage, which
+    /// is cleared once the handover is applied.
     pub fn validate_eth_event_nonce(&self, event: &EthereumEvent) -> bool {
         match event {
             EthereumEvent::TransfersToEthereum {
@@ -582,6 +687,42 @@ where
                     return false;
                 }
             }
+            EthereumEvent::TransfersToNamadaBatch { nonce, batches } => {
+                let Some(last_nonce) =
+                    batches.len().checked_sub(1).and_then(|last_offset| {
+                        nonce.checked_add(last_offset as u64)
+                    })
+                else {
+                    return false;
+                };
+                let next_nam_transfers_nonce =
+                    self.get_next_nam_transfers_nonce();
+                if next_nam_transfers_nonce > last_nonce {
+                    return false;
+                }
+            }
+            EthereumEvent::NftTransfersToNamada {
+                nonce: ext_nonce, ..
+            } => {
+                let next_nft_transfers_nonce =
+                    self.get_next_nft_transfers_nonce();
+                if &next_nft_transfers_nonce > ext_nonce {
+                    return false;
+                }
+            }
+            EthereumEvent::BridgeContractHandover {
+                new_contract,
+                handover_height,
+            } => {
+                let Some(rotation) = self.get_bridge_contract_rotation() else {
+                    return false;
+                };
+                if &rotation.contract.address != new_contract
+                    || &Uint::from(&rotation.handover_height) != handover_height
+                {
+                    return false;
+                }
+            }
             // consider other ethereum event kinds valid
             _ => {}
         }
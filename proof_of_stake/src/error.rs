@@ -47,6 +47,11 @@ pub enum BondError {
     InactiveValidator(Address),
     #[error("Voting power overflow: {0}")]
     VotingPowerOverflow(TryFromIntError),
+    #[error(
+        "The bond would raise the stake of validator {0} to {1} of the total \
+         stake, exceeding the maximum share of {2}"
+    )]
+    StakeCapExceeded(Address, Dec, Dec),
 }
 
 #[allow(missing_docs)]
@@ -168,6 +173,11 @@ pub enum ReactivationError {
 pub enum MetadataError {
     #[error("The validator email cannot be removed")]
     CannotRemoveEmail,
+    #[error(
+        "The validator {0} is {1} bytes long, exceeding the maximum length of \
+         {2} bytes"
+    )]
+    TooLong(&'static str, usize, usize),
 }
 
 #[allow(missing_docs)]
@@ -177,6 +187,17 @@ pub enum ConsensusKeyChangeError {
     MustBeEd25519,
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ValidatorKeyChangeError {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error("The Ethereum bridge {0} key is required to be a Secp256k1 key")]
+    EthKeyMustBeSecp256k1(&'static str),
+    #[error("At least one of the Ethereum bridge keys must be changed")]
+    NoEthBridgeKeyChange,
+}
+
 impl From<BecomeValidatorError> for storage_api::Error {
     fn from(err: BecomeValidatorError) -> Self {
         Self::new(err)
@@ -248,3 +269,9 @@ impl From<ConsensusKeyChangeError> for storage_api::Error {
         Self::new(err)
     }
 }
+
+impl From<ValidatorKeyChangeError> for storage_api::Error {
+    fn from(err: ValidatorKeyChangeError) -> Self {
+        Self::new(err)
+    }
+}
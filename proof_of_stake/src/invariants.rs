@@ -0,0 +1,211 @@
+//! Checks of the invariants of the PoS system, which must hold at the end of
+//! every block. A violation points to a bug in the PoS state transitions,
+//! which may go on to split consensus between nodes, so the ledger can be
+//! built to run these checks after each block and halt on a violation.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::token;
+use thiserror::Error;
+
+use crate::storage::{bonds_prefix, is_bond_key};
+use crate::types::WeightedValidator;
+use crate::{
+    bond_handle, read_all_validator_addresses,
+    read_below_capacity_validator_set_addresses_with_stake,
+    read_consensus_validator_set_addresses_with_stake, read_pos_params,
+    total_bonded_handle, total_deltas_handle, validator_deltas_handle,
+};
+
+/// A violation of an invariant of the PoS system.
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum InvariantViolation {
+    #[error(
+        "The total bonded to validator {validator} at epoch {epoch} is \
+         {total_bonded}, but the sum of its bonds net of unbonds is \
+         {sum_of_bonds}"
+    )]
+    TotalBondedMismatch {
+        validator: Address,
+        epoch: Epoch,
+        total_bonded: String,
+        sum_of_bonds: String,
+    },
+    #[error(
+        "The stake deltas of validator {validator} at epoch {epoch} sum up to \
+         a negative value of {sum}"
+    )]
+    NegativeValidatorDeltas {
+        validator: Address,
+        epoch: Epoch,
+        sum: token::Change,
+    },
+    #[error(
+        "The total stake deltas at epoch {epoch} sum up to a negative value \
+         of {sum}"
+    )]
+    NegativeTotalDeltas { epoch: Epoch, sum: token::Change },
+    #[error(
+        "Validator {validator} is in the {set} validator set at epoch {epoch} \
+         with a stake of {set_stake}, but its stake is {stake}"
+    )]
+    ValidatorSetStakeMismatch {
+        validator: Address,
+        epoch: Epoch,
+        set: &'static str,
+        set_stake: String,
+        stake: String,
+    },
+    #[error(
+        "The lowest stake of the consensus validator set at epoch {epoch} is \
+         {min_consensus_stake}, less than the greatest stake of the \
+         below-capacity validator set of {max_below_capacity_stake}"
+    )]
+    ValidatorSetsOutOfOrder {
+        epoch: Epoch,
+        min_consensus_stake: String,
+        max_below_capacity_stake: String,
+    },
+}
+
+/// Check the invariants of the PoS system from the current epoch up to the
+/// pipeline epoch. Returns an empty list if they all hold.
+///
+/// The checked invariants are:
+///  * The total bonded to each validator is the sum of its bonds, net of the
+///    unbonded tokens.
+///  * The stake deltas of each validator, and the total stake deltas, never sum
+///    up to a negative value.
+///  * The validators of the consensus and below-capacity validator sets are
+///    sorted by their actual stake, and every consensus validator has at least
+///    the stake of every below-capacity validator.
+pub fn check_invariants<S>(
+    storage: &S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Vec<InvariantViolation>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+
+    // The sources of the bonds to each validator
+    let mut bond_sources: BTreeMap<Address, BTreeSet<Address>> =
+        BTreeMap::new();
+    for iter_result in storage_api::iter_prefix_bytes(storage, &bonds_prefix())?
+    {
+        let (key, _bond_bytes) = iter_result?;
+        if let Some((bond_id, _start)) = is_bond_key(&key) {
+            bond_sources
+                .entry(bond_id.validator)
+                .or_default()
+                .insert(bond_id.source);
+        }
+    }
+
+    let mut violations = vec![];
+    for epoch in Epoch::iter_bounds_inclusive(
+        current_epoch,
+        current_epoch + params.pipeline_len,
+    ) {
+        let total_deltas = total_deltas_handle()
+            .get_sum(storage, epoch, &params)?
+            .unwrap_or_default();
+        if total_deltas.is_negative() {
+            violations.push(InvariantViolation::NegativeTotalDeltas {
+                epoch,
+                sum: total_deltas,
+            });
+        }
+
+        // The stakes of the validators, which are only known if their
+        // deltas sum up to a non-negative value
+        let mut stakes: BTreeMap<Address, token::Amount> = BTreeMap::new();
+        for validator in read_all_validator_addresses(storage, epoch)? {
+            let total_bonded = total_bonded_handle(&validator)
+                .get_sum(storage, epoch, &params)?
+                .unwrap_or_default();
+            let mut sum_of_bonds = token::Amount::zero();
+            for source in bond_sources.get(&validator).into_iter().flatten() {
+                sum_of_bonds += bond_handle(source, &validator)
+                    .get_sum(storage, epoch, &params)?
+                    .unwrap_or_default();
+            }
+            if total_bonded != sum_of_bonds {
+                violations.push(InvariantViolation::TotalBondedMismatch {
+                    validator: validator.clone(),
+                    epoch,
+                    total_bonded: total_bonded.to_string_native(),
+                    sum_of_bonds: sum_of_bonds.to_string_native(),
+                });
+            }
+
+            let deltas = validator_deltas_handle(&validator)
+                .get_sum(storage, epoch, &params)?
+                .unwrap_or_default();
+            if deltas.is_negative() {
+                violations.push(InvariantViolation::NegativeValidatorDeltas {
+                    validator,
+                    epoch,
+                    sum: deltas,
+                });
+            } else {
+                stakes.insert(validator, token::Amount::from_change(deltas));
+            }
+        }
+
+        let consensus_set =
+            read_consensus_validator_set_addresses_with_stake(storage, epoch)?;
+        let below_capacity_set =
+            read_below_capacity_validator_set_addresses_with_stake(
+                storage, epoch,
+            )?;
+        for (set, validators) in [
+            ("consensus", &consensus_set),
+            ("below-capacity", &below_capacity_set),
+        ] {
+            for WeightedValidator {
+                bonded_stake,
+                address,
+            } in validators
+            {
+                let Some(&stake) = stakes.get(address) else {
+                    // A negative stake is already reported
+                    continue;
+                };
+                if stake != *bonded_stake {
+                    violations.push(
+                        InvariantViolation::ValidatorSetStakeMismatch {
+                            validator: address.clone(),
+                            epoch,
+                            set,
+                            set_stake: bonded_stake.to_string_native(),
+                            stake: stake.to_string_native(),
+                        },
+                    );
+                }
+            }
+        }
+
+        let min_consensus_stake =
+            consensus_set.iter().map(|val| val.bonded_stake).min();
+        let max_below_capacity_stake =
+            below_capacity_set.iter().map(|val| val.bonded_stake).max();
+        if let (Some(min_consensus_stake), Some(max_below_capacity_stake)) =
+            (min_consensus_stake, max_below_capacity_stake)
+        {
+            if min_consensus_stake < max_below_capacity_stake {
+                violations.push(InvariantViolation::ValidatorSetsOutOfOrder {
+                    epoch,
+                    min_consensus_stake: min_consensus_stake.to_string_native(),
+                    max_below_capacity_stake: max_below_capacity_stake
+                        .to_string_native(),
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
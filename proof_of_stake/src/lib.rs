@@ -7,6 +7,7 @@
 #![deny(rustdoc::private_intra_doc_links)]
 
 pub mod epoched;
+pub mod invariants;
 pub mod parameters;
 pub mod pos_queries;
 pub mod rewards;
@@ -21,9 +22,11 @@ mod tests;
 use core::fmt::Debug;
 use std::cmp::{self, Reverse};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::RangeInclusive;
 
 use borsh::BorshDeserialize;
 pub use error::*;
+use namada_core::ledger::parameters::storage as params_storage;
 use namada_core::ledger::storage_api::collections::lazy_map::{
     Collectable, LazyMap, NestedMap, NestedSubKey, SubKey,
 };
@@ -36,38 +39,49 @@ use namada_core::types::dec::Dec;
 use namada_core::types::key::{
     common, protocol_pk_key, tm_consensus_key_raw_hash, PublicKeyTmRawHash,
 };
-use namada_core::types::storage::BlockHeight;
+use namada_core::types::storage::{BlockHeight, DbKeySeg};
 pub use namada_core::types::storage::{Epoch, Key, KeySeg};
+use namada_core::types::vote_extensions::heartbeat::BuildInfo;
 use once_cell::unsync::Lazy;
-pub use parameters::{OwnedPosParams, PosParams};
+pub use parameters::{
+    BridgeLivenessParams, InsuranceParams, OwnedPosParams, PosParams,
+    SlashingParamsChange,
+};
 use rewards::PosRewardsCalculator;
 use storage::{
-    bonds_for_source_prefix, bonds_prefix, consensus_keys_key,
-    get_validator_address_from_bond, is_bond_key, is_unbond_key,
+    block_proposer_key, bonds_for_source_prefix, bonds_prefix,
+    consensus_keys_key, get_validator_address_from_bond,
+    insurance_premiums_key, is_bond_key, is_unbond_key,
     is_validator_slashes_key, last_block_proposer_key,
     last_pos_reward_claim_epoch_key, params_key, rewards_counter_key,
-    slashes_prefix, unbonds_for_source_prefix, unbonds_prefix,
-    validator_address_raw_hash_key, validator_description_key,
+    rewards_counter_source_prefix, slashes_prefix, unbonds_for_source_prefix,
+    unbonds_prefix, validator_address_raw_hash_key, validator_description_key,
     validator_discord_key, validator_email_key, validator_last_slash_key,
-    validator_max_commission_rate_change_key, validator_website_key,
+    validator_max_commission_rate_change_key, validator_moniker_key,
+    validator_security_contact_key, validator_total_slashed_key,
+    validator_website_key,
 };
 use types::{
-    into_tm_voting_power, BelowCapacityValidatorSet,
-    BelowCapacityValidatorSets, BondDetails, BondId, Bonds,
-    BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionRates,
+    into_tm_voting_power, AutoCompoundBonds, AutoWithdrawals,
+    BelowCapacityValidatorSet, BelowCapacityValidatorSets, BondDetails, BondId,
+    Bonds, BondsAndUnbondsDetail, BondsAndUnbondsDetails, BridgeBlocks,
+    BridgeParticipation, BridgeVextHeights, CommissionHistory, CommissionRates,
     ConsensusValidator, ConsensusValidatorSet, ConsensusValidatorSets,
-    DelegatorRedelegatedBonded, DelegatorRedelegatedUnbonded,
-    EagerRedelegatedBondsMap, EpochedSlashes, IncomingRedelegations,
-    LivenessMissedVotes, LivenessSumMissedVotes, OutgoingRedelegations,
-    Position, RedelegatedBondsOrUnbonds, RedelegatedTokens,
+    CubicSlashingWindowLengths, DelegationsPage, DelegatorRedelegatedBonded,
+    DelegatorRedelegatedUnbonded, EagerRedelegatedBondsMap, EpochedSlashes,
+    IncomingRedelegations, InsuredBonds, LivenessMissedVotes,
+    LivenessSumMissedVotes, OutgoingRedelegations, PendingRedelegation,
+    Position, ProjectedRewards, RedelegatedBondsOrUnbonds, RedelegatedTokens,
     ReverseOrdTokenAmount, RewardsAccumulator, RewardsProducts, Slash,
     SlashType, SlashedAmount, Slashes, TotalConsensusStakes, TotalDeltas,
     TotalRedelegatedBonded, TotalRedelegatedUnbonded, UnbondDetails, Unbonds,
-    ValidatorAddresses, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorEthColdKeys, ValidatorEthHotKeys, ValidatorMetaData,
-    ValidatorPositionAddresses, ValidatorProtocolKeys, ValidatorSetPositions,
+    ValidatorAddresses, ValidatorBridgeParticipation,
+    ValidatorBridgeVextHeights, ValidatorConsensusKeys, ValidatorDelegators,
+    ValidatorDeltas, ValidatorEthColdKeys, ValidatorEthHotKeys,
+    ValidatorHeartbeats, ValidatorMetaData, ValidatorPositionAddresses,
+    ValidatorProtocolKeys, ValidatorSetDiff, ValidatorSetPositions,
     ValidatorSetUpdate, ValidatorState, ValidatorStates,
-    ValidatorTotalUnbonded, VoteInfo, WeightedValidator,
+    ValidatorTotalUnbonded, VoteInfo, WeightedValidator, WithdrawableUnbond,
 };
 
 /// Address of the PoS account implemented as a native VP
@@ -135,6 +149,12 @@ pub fn total_consensus_stake_key_handle() -> TotalConsensusStakes {
     TotalConsensusStakes::open(key)
 }
 
+/// Get the storage handle to the epoched cubic slashing window length
+pub fn cubic_slashing_window_length_handle() -> CubicSlashingWindowLengths {
+    let key = storage::cubic_slashing_window_length_key();
+    CubicSlashingWindowLengths::open(key)
+}
+
 /// Get the storage handle to a PoS validator's state
 pub fn validator_state_handle(validator: &Address) -> ValidatorStates {
     let key = storage::validator_state_key(validator);
@@ -167,6 +187,15 @@ pub fn validator_commission_rate_handle(
     CommissionRates::open(key)
 }
 
+/// Get the storage handle to the history of a PoS validator's commission
+/// rate
+pub fn validator_commission_history_handle(
+    validator: &Address,
+) -> CommissionHistory {
+    let key = storage::validator_commission_history_key(validator);
+    CommissionHistory::open(key)
+}
+
 /// Get the storage handle to a bond, which is dynamically updated with when
 /// unbonding
 pub fn bond_handle(source: &Address, validator: &Address) -> Bonds {
@@ -235,6 +264,12 @@ pub fn validator_rewards_products_handle(
     RewardsProducts::open(key)
 }
 
+/// Get the storage handle to the set of a validator's delegators
+pub fn validator_delegators_handle(validator: &Address) -> ValidatorDelegators {
+    let key = storage::validator_delegators_key(validator);
+    ValidatorDelegators::open(key)
+}
+
 /// Get the storage handle to a validator's incoming redelegations
 pub fn validator_incoming_redelegations_handle(
     validator: &Address,
@@ -295,6 +330,62 @@ pub fn liveness_sum_missed_votes_handle() -> LivenessSumMissedVotes {
     LivenessSumMissedVotes::open(key)
 }
 
+/// Get the storage handle to the bonds whose unbonds are automatically
+/// withdrawn
+pub fn auto_withdrawals_handle() -> AutoWithdrawals {
+    let key = storage::auto_withdrawals_key();
+    AutoWithdrawals::open(key)
+}
+
+/// Get the storage handle to the bonds whose rewards are automatically
+/// compounded
+pub fn auto_compound_bonds_handle() -> AutoCompoundBonds {
+    let key = storage::auto_compound_key();
+    AutoCompoundBonds::open(key)
+}
+
+/// Get the storage handle to the bonds insured against slashing by the
+/// insurance pool
+pub fn insured_bonds_handle() -> InsuredBonds {
+    let key = storage::insured_bonds_key();
+    InsuredBonds::open(key)
+}
+
+/// Get the storage handle to the builds attested to by the heartbeats of
+/// validators
+pub fn validator_heartbeats_handle() -> ValidatorHeartbeats {
+    let key = storage::validator_heartbeats_key();
+    ValidatorHeartbeats::open(key)
+}
+
+/// Get the storage handle to the number of heights at which each validator
+/// signed a bridge vote extension
+pub fn validator_bridge_participation_handle() -> ValidatorBridgeParticipation {
+    let key = storage::bridge_participation_key();
+    ValidatorBridgeParticipation::open(key)
+}
+
+/// Get the storage handle to the number of heights at which bridge vote
+/// extensions were signed
+pub fn bridge_blocks_handle() -> BridgeBlocks {
+    let key = storage::bridge_blocks_key();
+    BridgeBlocks::open(key)
+}
+
+/// Get the storage handle to the heights at which bridge vote extensions
+/// were signed
+pub fn bridge_vext_heights_handle() -> BridgeVextHeights {
+    let key = storage::bridge_vext_heights_key();
+    BridgeVextHeights::open(key)
+}
+
+/// Get the storage handle to the heights at which each validator signed
+/// bridge vote extensions
+pub fn validator_bridge_vext_heights_handle() -> ValidatorBridgeVextHeights {
+    let key = storage::validator_bridge_vext_heights_key();
+    ValidatorBridgeVextHeights::open(key)
+}
+
 /// Init genesis. Requires that the governance parameters are initialized.
 pub fn init_genesis<S>(
     storage: &mut S,
@@ -311,6 +402,11 @@ where
     below_capacity_validator_set_handle().init(storage, current_epoch)?;
     validator_set_positions_handle().init(storage, current_epoch)?;
     validator_addresses_handle().init(storage, current_epoch)?;
+    cubic_slashing_window_length_handle().init_at_genesis(
+        storage,
+        params.cubic_slashing_window_length,
+        current_epoch,
+    )?;
     tracing::debug!("Finished genesis");
     Ok(())
 }
@@ -454,6 +550,19 @@ where
     storage.write(&key, epoch)
 }
 
+/// Read the total amount of tokens slashed from the stake of the given
+/// validator, over all of its processed slashes
+pub fn read_validator_total_slashed<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = validator_total_slashed_key(validator);
+    Ok(storage.read(&key)?.unwrap_or_default())
+}
+
 /// Read last block proposer address.
 pub fn read_last_block_proposer_address<S>(
     storage: &S,
@@ -477,6 +586,31 @@ where
     storage.write(&key, address)
 }
 
+/// Read the address of the proposer of the block at the given height.
+pub fn read_block_proposer_address<S>(
+    storage: &S,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Address>>
+where
+    S: StorageRead,
+{
+    let key = block_proposer_key(height);
+    storage.read(&key)
+}
+
+/// Write the address of the proposer of the block at the given height.
+pub fn write_block_proposer_address<S>(
+    storage: &mut S,
+    height: BlockHeight,
+    address: Address,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = block_proposer_key(height);
+    storage.write(&key, address)
+}
+
 /// Read PoS validator's delta value.
 pub fn read_validator_deltas_value<S>(
     storage: &S,
@@ -641,6 +775,44 @@ where
         .collect()
 }
 
+/// Find the changes of the consensus validator set from `from_epoch` to
+/// `to_epoch`, i.e. the validators that joined or left the set and those
+/// whose stake changed.
+pub fn consensus_validator_set_diff<S>(
+    storage: &S,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> storage_api::Result<ValidatorSetDiff>
+where
+    S: StorageRead,
+{
+    let stakes = |epoch| -> storage_api::Result<BTreeMap<_, _>> {
+        let validators =
+            read_consensus_validator_set_addresses_with_stake(storage, epoch)?;
+        Ok(validators
+            .into_iter()
+            .map(|validator| (validator.address, validator.bonded_stake))
+            .collect())
+    };
+    let mut old_stakes = stakes(from_epoch)?;
+    let new_stakes = stakes(to_epoch)?;
+
+    let mut diff = ValidatorSetDiff::default();
+    for (address, new_stake) in new_stakes {
+        match old_stakes.remove(&address) {
+            None => {
+                diff.joined.insert(address, new_stake);
+            }
+            Some(old_stake) if old_stake != new_stake => {
+                diff.changed.insert(address, (old_stake, new_stake));
+            }
+            Some(_) => {}
+        }
+    }
+    diff.left = old_stakes;
+    Ok(diff)
+}
+
 /// Count the number of consensus validators
 pub fn get_num_consensus_validators<S>(
     storage: &S,
@@ -780,6 +952,47 @@ where
     }
 }
 
+/// Read the maximum share of the total stake that a single validator may
+/// hold, if governance has set one.
+pub fn read_max_validator_stake_share<S>(
+    storage: &S,
+) -> storage_api::Result<Option<Dec>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::max_validator_stake_share_key())
+}
+
+/// Check that bonding the given `amount` to a validator keeps its stake
+/// within the maximum share of the total stake at the given `epoch`, if
+/// governance has set one.
+pub fn check_validator_stake_cap<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    amount: token::Amount,
+    epoch: Epoch,
+) -> storage_api::Result<Result<(), BondError>>
+where
+    S: StorageRead,
+{
+    let Some(max_share) = read_max_validator_stake_share(storage)? else {
+        return Ok(Ok(()));
+    };
+    let stake =
+        read_validator_stake(storage, params, validator, epoch)? + amount;
+    let total_stake = read_total_stake(storage, params, epoch)? + amount;
+    let share = Dec::from(stake) / Dec::from(total_stake);
+    if share > max_share {
+        return Ok(Err(BondError::StakeCapExceeded(
+            validator.clone(),
+            share,
+            max_share,
+        )));
+    }
+    Ok(Ok(()))
+}
+
 /// Self-bond tokens to a validator when `source` is `None` or equal to
 /// the `validator` address, or delegate tokens from the `source` to the
 /// `validator`.
@@ -827,6 +1040,15 @@ where
         return Err(BondError::NotAValidator(validator.clone()).into());
     }
 
+    // Check that the bond keeps the validator within the stake cap
+    check_validator_stake_cap(
+        storage,
+        &params,
+        validator,
+        amount,
+        offset_epoch,
+    )??;
+
     let bond_handle = bond_handle(source, validator);
     let total_bonded_handle = total_bonded_handle(validator);
 
@@ -838,6 +1060,7 @@ where
     // Initialize or update the bond at the pipeline offset
     bond_handle.add(storage, amount, current_epoch, offset)?;
     total_bonded_handle.add(storage, amount, current_epoch, offset)?;
+    validator_delegators_handle(validator).insert(storage, source.clone())?;
 
     if tracing::level_enabled!(tracing::Level::DEBUG) {
         let bonds = find_bonds(storage, source, validator)?;
@@ -1834,6 +2057,10 @@ where
     if let Some((bond_epoch, new_bond_amount)) = bonds_to_unbond.new_entry {
         bonds_handle.set(storage, new_bond_amount, bond_epoch, 0)?;
     }
+    // The source is no longer a delegator once all of its bonds are unbonded
+    if bonds_handle.get_data_handler().is_empty(storage)? {
+        validator_delegators_handle(validator).remove(storage, source)?;
+    }
 
     // `updatedUnbonded`
     // Update the unbonds in storage using the eager map computed above
@@ -2828,6 +3055,11 @@ where
         current_epoch,
         offset,
     )?;
+    validator_commission_history_handle(address).insert(
+        storage,
+        pipeline_epoch,
+        commission_rate,
+    )?;
     validator_deltas_handle(address).set(
         storage,
         token::Change::zero(),
@@ -2894,6 +3126,90 @@ where
     Ok(())
 }
 
+/// Change the protocol key of a validator. The new key is set at the pipeline
+/// epoch, such that the current key remains valid until then, and the vote
+/// extensions and protocol txs signed with it in the meantime are still
+/// accepted.
+pub fn change_protocol_key<S>(
+    storage: &mut S,
+    validator: &Address,
+    protocol_key: &common::PublicKey,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!("Changing protocol key for validator {}", validator);
+
+    if !is_validator(storage, validator)? {
+        return Err(
+            ValidatorKeyChangeError::NotAValidator(validator.clone()).into()
+        );
+    }
+
+    let params = read_pos_params(storage)?;
+    validator_protocol_key_handle(validator).set(
+        storage,
+        protocol_key.clone(),
+        current_epoch,
+        params.pipeline_len,
+    )
+}
+
+/// Change the Ethereum bridge hot and/or cold keys of a validator. Like for
+/// the protocol key, the new keys are set at the pipeline epoch, until when
+/// the current keys remain valid.
+pub fn change_eth_bridge_keys<S>(
+    storage: &mut S,
+    validator: &Address,
+    eth_hot_key: Option<&common::PublicKey>,
+    eth_cold_key: Option<&common::PublicKey>,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    tracing::debug!(
+        "Changing Ethereum bridge keys for validator {}",
+        validator
+    );
+
+    if !is_validator(storage, validator)? {
+        return Err(
+            ValidatorKeyChangeError::NotAValidator(validator.clone()).into()
+        );
+    }
+    if eth_hot_key.is_none() && eth_cold_key.is_none() {
+        return Err(ValidatorKeyChangeError::NoEthBridgeKeyChange.into());
+    }
+    for (kind, key) in [("hot", eth_hot_key), ("cold", eth_cold_key)] {
+        if let Some(common::PublicKey::Ed25519(_)) = key {
+            return Err(
+                ValidatorKeyChangeError::EthKeyMustBeSecp256k1(kind).into()
+            );
+        }
+    }
+
+    let params = read_pos_params(storage)?;
+    if let Some(eth_hot_key) = eth_hot_key {
+        validator_eth_hot_key_handle(validator).set(
+            storage,
+            eth_hot_key.clone(),
+            current_epoch,
+            params.pipeline_len,
+        )?;
+    }
+    if let Some(eth_cold_key) = eth_cold_key {
+        validator_eth_cold_key_handle(validator).set(
+            storage,
+            eth_cold_key.clone(),
+            current_epoch,
+            params.pipeline_len,
+        )?;
+    }
+    Ok(())
+}
+
 /// Withdraw tokens from those that have been unbonded from proof-of-stake
 pub fn withdraw_tokens<S>(
     storage: &mut S,
@@ -3033,92 +3349,896 @@ where
     Ok(withdrawable_amount)
 }
 
-/// Change the commission rate of a validator
-pub fn change_validator_commission_rate<S>(
+/// Schedule the tokens unbonded from a bond in the `current_epoch` to be
+/// automatically withdrawn to the source of the bond, once they become
+/// withdrawable. Returns the epoch at which they are withdrawn.
+pub fn schedule_auto_withdrawal<S>(
     storage: &mut S,
+    source: Option<&Address>,
     validator: &Address,
-    new_rate: Dec,
     current_epoch: Epoch,
-) -> storage_api::Result<()>
+) -> storage_api::Result<Epoch>
 where
     S: StorageRead + StorageWrite,
 {
-    if new_rate.is_negative() {
-        return Err(CommissionRateChangeError::NegativeRate(
-            new_rate,
-            validator.clone(),
-        )
-        .into());
-    }
-
-    if new_rate > Dec::one() {
-        return Err(CommissionRateChangeError::LargerThanOne(
-            new_rate,
-            validator.clone(),
-        )
-        .into());
-    }
-
-    let max_change =
-        read_validator_max_commission_rate_change(storage, validator)?;
-    if max_change.is_none() {
-        return Err(CommissionRateChangeError::NoMaxSetInStorage(
-            validator.clone(),
-        )
-        .into());
-    }
-
     let params = read_pos_params(storage)?;
-    let commission_handle = validator_commission_rate_handle(validator);
-    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let source = source.unwrap_or(validator);
+    let withdrawable_epoch = current_epoch + params.withdrawable_epoch_offset();
+    tracing::debug!(
+        "Scheduling the automatic withdrawal of the unbonds of {source} from \
+         {validator} at epoch {withdrawable_epoch}"
+    );
 
-    let rate_at_pipeline = commission_handle
-        .get(storage, pipeline_epoch, &params)?
-        .expect("Could not find a rate in given epoch");
-    if new_rate == rate_at_pipeline {
-        return Ok(());
-    }
-    let rate_before_pipeline = commission_handle
-        .get(storage, pipeline_epoch.prev(), &params)?
-        .expect("Could not find a rate in given epoch");
+    let scheduled = auto_withdrawals_handle().at(&withdrawable_epoch);
+    let mut validators = scheduled.get(storage, source)?.unwrap_or_default();
+    validators.insert(validator.clone());
+    scheduled.insert(storage, source.clone(), validators)?;
+    Ok(withdrawable_epoch)
+}
 
-    let change_from_prev = new_rate.abs_diff(&rate_before_pipeline);
-    if change_from_prev > max_change.unwrap() {
-        return Err(CommissionRateChangeError::RateChangeTooLarge(
-            change_from_prev,
-            validator.clone(),
-        )
-        .into());
+/// Withdraw the unbonds scheduled for automatic withdrawal at the
+/// `current_epoch`. This must be called at the start of every epoch, after
+/// the enqueued slashes have been processed.
+///
+/// Since all the withdrawable unbonds of a bond are withdrawn at once, any
+/// other unbond of the same bond that has become withdrawable is withdrawn
+/// too. Returns the amount withdrawn from each bond.
+pub fn process_auto_withdrawals<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Vec<(BondId, token::Amount)>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let handle = auto_withdrawals_handle();
+    let scheduled = handle
+        .at(&current_epoch)
+        .iter(storage)?
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    handle.remove_all(storage, &current_epoch)?;
+
+    let mut withdrawn = Vec::new();
+    for (source, validators) in scheduled {
+        for validator in validators {
+            // The unbonds may have been withdrawn by the source in the
+            // meantime
+            if unbond_handle(&source, &validator).is_empty(storage)? {
+                continue;
+            }
+            let amount = withdraw_tokens(
+                storage,
+                Some(&source),
+                &validator,
+                current_epoch,
+            )?;
+            let bond_id = BondId {
+                source: source.clone(),
+                validator,
+            };
+            withdrawn.push((bond_id, amount));
+        }
     }
-
-    commission_handle.set(storage, new_rate, current_epoch, params.pipeline_len)
+    Ok(withdrawn)
 }
 
-/// Check if the given consensus key is already being used to ensure uniqueness.
-///
-/// If it's not being used, it will be inserted into the set that's being used
-/// for this. If it's already used, this will return an Error.
-pub fn try_insert_consensus_key<S>(
+/// Enable or disable the automatic compounding of the rewards of a
+/// self-bond, when `source` is `None` or equal to the `validator` address,
+/// or of a delegation from the `source` to the `validator`.
+pub fn set_auto_compound<S>(
     storage: &mut S,
-    consensus_key: &common::PublicKey,
+    source: Option<&Address>,
+    validator: &Address,
+    enabled: bool,
 ) -> storage_api::Result<()>
 where
     S: StorageRead + StorageWrite,
 {
-    let key = consensus_keys_key();
-    LazySet::open(key).try_insert(storage, consensus_key.clone())
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    if let Some(source) = source {
+        if source != validator && is_validator(storage, source)? {
+            return Err(
+                BondError::SourceMustNotBeAValidator(source.clone()).into()
+            );
+        }
+    }
+    let source = source.unwrap_or(validator);
+    tracing::debug!(
+        "Setting the automatic compounding of the rewards of {source} from \
+         {validator} to {enabled}"
+    );
+
+    let handle = auto_compound_bonds_handle();
+    let mut validators = handle.get(storage, source)?.unwrap_or_default();
+    if enabled {
+        validators.insert(validator.clone());
+    } else {
+        validators.remove(validator);
+    }
+    if validators.is_empty() {
+        handle.remove(storage, source)?;
+    } else {
+        handle.insert(storage, source.clone(), validators)?;
+    }
+    Ok(())
 }
 
-/// Get the unique set of consensus keys in storage
-pub fn get_consensus_key_set<S>(
+/// Check if the rewards of a bond are automatically compounded.
+pub fn is_auto_compound<S>(
     storage: &S,
-) -> storage_api::Result<BTreeSet<common::PublicKey>>
+    bond_id: &BondId,
+) -> storage_api::Result<bool>
 where
     S: StorageRead,
 {
-    let key = consensus_keys_key();
-    let lazy_set = LazySet::<common::PublicKey>::open(key);
-    Ok(lazy_set.iter(storage)?.map(Result::unwrap).collect())
+    Ok(auto_compound_bonds_handle()
+        .get(storage, &bond_id.source)?
+        .map(|validators| validators.contains(&bond_id.validator))
+        .unwrap_or_default())
+}
+
+/// Claim the rewards of the bonds for which automatic compounding is
+/// enabled, and bond them to the same validators. This must be called at
+/// the start of every epoch, after the inflation of the previous epoch has
+/// been applied.
+///
+/// Returns the amount of rewards added to each bond.
+pub fn process_auto_compounding<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Vec<(BondId, token::Amount)>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let bonds = auto_compound_bonds_handle()
+        .iter(storage)?
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let mut compounded = Vec::new();
+    for (source, validators) in bonds {
+        // The source may have become a validator in the meantime, in which
+        // case it can no longer delegate tokens
+        let source_is_validator = is_validator(storage, &source)?;
+        for validator in validators {
+            if source_is_validator && source != validator {
+                continue;
+            }
+            let amount = claim_reward_tokens(
+                storage,
+                Some(&source),
+                &validator,
+                current_epoch,
+            )?;
+            if amount.is_zero() {
+                continue;
+            }
+            // The claimed rewards are left in the balance of the source if
+            // bonding them would exceed the stake cap of the validator
+            if let Err(err) = check_validator_stake_cap(
+                storage,
+                &params,
+                &validator,
+                amount,
+                pipeline_epoch,
+            )? {
+                tracing::info!(
+                    "Skipping the compounding of the rewards of {source} to \
+                     {validator}: {err}"
+                );
+                continue;
+            }
+            bond_tokens(
+                storage,
+                Some(&source),
+                &validator,
+                amount,
+                current_epoch,
+                None,
+            )?;
+            let bond_id = BondId {
+                source: source.clone(),
+                validator,
+            };
+            compounded.push((bond_id, amount));
+        }
+    }
+    Ok(compounded)
+}
+
+/// Read the parameters of the slashing insurance pool. The defaults apply
+/// until governance changes them.
+pub fn read_insurance_params<S>(
+    storage: &S,
+) -> storage_api::Result<InsuranceParams>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&storage::insurance_params_key())?
+        .unwrap_or_default())
+}
+
+/// Read the amount of tokens held by the slashing insurance pool. The tokens
+/// are kept in the PoS account.
+pub fn read_insurance_pool_balance<S>(
+    storage: &S,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    Ok(storage
+        .read(&storage::insurance_pool_key())?
+        .unwrap_or_default())
+}
+
+/// Write the amount of tokens held by the slashing insurance pool.
+fn write_insurance_pool_balance<S>(
+    storage: &mut S,
+    balance: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    storage.write(&storage::insurance_pool_key(), balance)
+}
+
+/// Opt a self-bond, when `source` is `None` or equal to the `validator`
+/// address, or a delegation from the `source` to the `validator` into or out
+/// of the slashing insurance pool.
+///
+/// A bond opted in during the `current_epoch` is only insured after the
+/// slash processing epoch offset, such that the pool only covers the slashes
+/// of infractions committed after it was opted in. Opting out takes effect
+/// immediately.
+pub fn set_slashing_insurance<S>(
+    storage: &mut S,
+    source: Option<&Address>,
+    validator: &Address,
+    enabled: bool,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if !is_validator(storage, validator)? {
+        return Err(BondError::NotAValidator(validator.clone()).into());
+    }
+    if let Some(source) = source {
+        if source != validator && is_validator(storage, source)? {
+            return Err(
+                BondError::SourceMustNotBeAValidator(source.clone()).into()
+            );
+        }
+    }
+    let source = source.unwrap_or(validator);
+    tracing::debug!(
+        "Setting the slashing insurance of the bond of {source} to \
+         {validator} to {enabled}"
+    );
+
+    let params = read_pos_params(storage)?;
+    let handle = insured_bonds_handle();
+    let mut validators = handle.get(storage, source)?.unwrap_or_default();
+    if enabled {
+        // Opting in again keeps the epoch from which the bond is insured
+        validators.entry(validator.clone()).or_insert(
+            current_epoch + params.slash_processing_epoch_offset() + 1,
+        );
+    } else {
+        validators.remove(validator);
+    }
+    if validators.is_empty() {
+        handle.remove(storage, source)?;
+    } else {
+        handle.insert(storage, source.clone(), validators)?;
+    }
+    Ok(())
+}
+
+/// Check if a bond is insured against slashing by the insurance pool in the
+/// given epoch.
+pub fn is_insured<S>(
+    storage: &S,
+    bond_id: &BondId,
+    epoch: Epoch,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    Ok(insured_bonds_handle()
+        .get(storage, &bond_id.source)?
+        .and_then(|validators| validators.get(&bond_id.validator).copied())
+        .map(|insured_from| insured_from <= epoch)
+        .unwrap_or_default())
+}
+
+/// Charge the premiums of the bonds insured in the last epoch against their
+/// rewards for that epoch, paying them into the insurance pool. The premiums
+/// are deducted from the rewards of the bonds once these are claimed. This
+/// must be called at the start of every epoch, after the inflation of the
+/// previous epoch has been applied.
+pub fn charge_insurance_premiums<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if current_epoch == Epoch::default() {
+        // No rewards were distributed yet
+        return Ok(());
+    }
+    let last_epoch = current_epoch.prev();
+    let insured_bonds = insured_bonds_handle()
+        .iter(storage)?
+        .collect::<storage_api::Result<Vec<_>>>()?;
+    if insured_bonds.is_empty() {
+        return Ok(());
+    }
+    let params = read_insurance_params(storage)?;
+    let mut pool_balance = read_insurance_pool_balance(storage)?;
+
+    for (source, validators) in insured_bonds {
+        for (validator, insured_from) in validators {
+            if insured_from > last_epoch {
+                continue;
+            }
+            let bond_id = BondId {
+                source: source.clone(),
+                validator,
+            };
+            let bond_amount = bond_amounts_for_rewards(
+                storage, &bond_id, last_epoch, last_epoch,
+            )?
+            .remove(&last_epoch)
+            .unwrap_or_default();
+            let rewards_product =
+                validator_rewards_products_handle(&bond_id.validator)
+                    .get(storage, &last_epoch)?
+                    .unwrap_or_default();
+            let premium = params.premium_rate * (rewards_product * bond_amount);
+            if premium.is_zero() {
+                continue;
+            }
+            let key =
+                insurance_premiums_key(&bond_id.source, &bond_id.validator);
+            let premiums =
+                storage.read::<token::Amount>(&key)?.unwrap_or_default();
+            storage.write(&key, premiums + premium)?;
+            pool_balance += premium;
+        }
+    }
+    write_insurance_pool_balance(storage, pool_balance)
+}
+
+/// Read the premiums charged against the rewards of a bond by the insurance
+/// pool, which are yet to be deducted from its claimed rewards.
+fn read_insurance_premiums<S>(
+    storage: &S,
+    source: &Address,
+    validator: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let key = insurance_premiums_key(source, validator);
+    Ok(storage.read::<token::Amount>(&key)?.unwrap_or_default())
+}
+
+/// Take the premiums charged against the rewards of a bond by the insurance
+/// pool. Deletes the record after reading.
+fn take_insurance_premiums<S>(
+    storage: &mut S,
+    source: &Address,
+    validator: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = insurance_premiums_key(source, validator);
+    let premiums = storage.read::<token::Amount>(&key)?.unwrap_or_default();
+    storage.delete(&key)?;
+    Ok(premiums)
+}
+
+/// Read the amounts of the insured bonds to the validators whose slashes are
+/// processed in the current epoch. This must be called before the slashes
+/// are processed, such that the losses of the bonds can be compensated with
+/// [`pay_insurance_claims`] after.
+pub fn insured_bond_amounts<S>(
+    storage: &S,
+    current_epoch: Epoch,
+) -> storage_api::Result<BTreeMap<BondId, token::Amount>>
+where
+    S: StorageRead,
+{
+    let mut slashed_validators = BTreeSet::new();
+    for enqueued_slash in
+        enqueued_slashes_handle().at(&current_epoch).iter(storage)?
+    {
+        let (
+            NestedSubKey::Data {
+                key: validator,
+                nested_sub_key: _,
+            },
+            _slash,
+        ) = enqueued_slash?;
+        slashed_validators.insert(validator);
+    }
+
+    let mut amounts = BTreeMap::new();
+    if slashed_validators.is_empty() {
+        return Ok(amounts);
+    }
+    for insured in insured_bonds_handle().iter(storage)? {
+        let (source, validators) = insured?;
+        for (validator, insured_from) in validators {
+            if insured_from > current_epoch
+                || !slashed_validators.contains(&validator)
+            {
+                continue;
+            }
+            let bond_id = BondId {
+                source: source.clone(),
+                validator,
+            };
+            let amount = bond_amount(storage, &bond_id, current_epoch)?;
+            amounts.insert(bond_id, amount);
+        }
+    }
+    Ok(amounts)
+}
+
+/// Compensate the insured bonds for the tokens slashed from them in the
+/// current epoch, given their amounts read with [`insured_bond_amounts`]
+/// before the slashes were processed. The pool pays back the insured
+/// fraction of each loss to the source of the bond. If it doesn't hold enough
+/// tokens to cover all the claims, its balance is instead shared out between
+/// them pro rata. This must be called at the start of an epoch, right after
+/// the slashes have been processed.
+///
+/// Returns the amount paid out to each bond.
+pub fn pay_insurance_claims<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+    insured_bond_amounts: BTreeMap<BondId, token::Amount>,
+) -> storage_api::Result<Vec<(BondId, token::Amount)>>
+where
+    S: StorageRead + StorageWrite,
+{
+    if insured_bond_amounts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let params = read_insurance_params(storage)?;
+    let mut pool_balance = read_insurance_pool_balance(storage)?;
+    let staking_token = staking_token_address(storage);
+
+    let mut claims = Vec::new();
+    let mut total_claims = token::Amount::zero();
+    for (bond_id, amount_before) in insured_bond_amounts {
+        let amount_after = bond_amount(storage, &bond_id, current_epoch)?;
+        let loss = amount_before.checked_sub(amount_after).unwrap_or_default();
+        let claim = params.coverage_rate * loss;
+        if claim.is_zero() {
+            continue;
+        }
+        total_claims = total_claims.checked_add(claim).ok_or_else(|| {
+            storage_api::Error::new_const("Insurance claims overflow")
+        })?;
+        claims.push((bond_id, claim));
+    }
+
+    let available = pool_balance;
+    let mut payouts = Vec::new();
+    for (bond_id, claim) in claims {
+        let payout = if total_claims <= available {
+            claim
+        } else {
+            // the pool is short, so every claim is paid the same fraction of
+            // the pool's balance, rounded down
+            let (payout, _rem) = claim
+                .raw_amount()
+                .mul_div(available.raw_amount(), total_claims.raw_amount());
+            token::Amount::from_uint(payout, 0)
+                .expect("A payout cannot exceed its claim")
+        };
+        if payout.is_zero() {
+            continue;
+        }
+        token::transfer(
+            storage,
+            &staking_token,
+            &ADDRESS,
+            &bond_id.source,
+            payout,
+        )?;
+        pool_balance -= payout;
+        payouts.push((bond_id, payout));
+    }
+    write_insurance_pool_balance(storage, pool_balance)?;
+    Ok(payouts)
+}
+
+/// Record the build of the node software attested to by the heartbeat of
+/// a validator in the given epoch.
+pub fn write_validator_heartbeat<S>(
+    storage: &mut S,
+    validator: &Address,
+    epoch: Epoch,
+    build: BuildInfo,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    validator_heartbeats_handle().at(&epoch).insert(
+        storage,
+        validator.clone(),
+        build,
+    )?;
+    Ok(())
+}
+
+/// Check if the heartbeat of a validator was recorded in the given epoch.
+pub fn is_validator_heartbeat_recorded<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    validator_heartbeats_handle()
+        .at(&epoch)
+        .contains(storage, validator)
+}
+
+/// Remove the heartbeats of validators older than the last epoch. Every
+/// validator sends a new heartbeat each epoch, so older ones are only
+/// kept around for the queries of the last epoch.
+pub fn prune_validator_heartbeats<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if let Some(prune_epoch) = current_epoch.checked_sub(Epoch(2)) {
+        validator_heartbeats_handle().remove_all(storage, &prune_epoch)?;
+    }
+    Ok(())
+}
+
+/// Read the builds of the node software attested to by the heartbeats of
+/// validators in the given epoch.
+pub fn read_validator_heartbeats<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<BTreeMap<Address, BuildInfo>>
+where
+    S: StorageRead,
+{
+    validator_heartbeats_handle()
+        .at(&epoch)
+        .iter(storage)?
+        .collect()
+}
+
+/// Record the validators who signed an Ethereum bridge vote extension that
+/// was included in a block of the given epoch, along with the block heights
+/// at which they signed them. Each validator is counted at most once per
+/// signed height, regardless of the blocks its vote extensions are included
+/// in, such that a proposer which leaves out the vote extension of a
+/// validator cannot have it count as missed, as long as a later proposer
+/// includes it. Heights without bridge vote extensions are not counted.
+pub fn record_bridge_participation<S>(
+    storage: &mut S,
+    epoch: Epoch,
+    signers: &BTreeSet<(Address, BlockHeight)>,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let participation = validator_bridge_participation_handle();
+    for (signer, height) in signers {
+        let height_seen = bridge_vext_heights_handle()
+            .at(&epoch)
+            .insert(storage, height.0)?;
+        if !height_seen {
+            bridge_blocks_handle().update(storage, epoch, |blocks| {
+                blocks.unwrap_or_default() + 1
+            })?;
+        }
+        let signer_seen = validator_bridge_vext_heights_handle()
+            .at(&epoch)
+            .at(signer)
+            .insert(storage, height.0)?;
+        if !signer_seen {
+            participation.at(&epoch).update(
+                storage,
+                signer.clone(),
+                |signed| signed.unwrap_or_default() + 1,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Read the participation of the validators in the Ethereum bridge vote
+/// extensions in the given epoch.
+pub fn read_bridge_participation<S>(
+    storage: &S,
+    epoch: Epoch,
+) -> storage_api::Result<BridgeParticipation>
+where
+    S: StorageRead,
+{
+    let bridge_blocks = bridge_blocks_handle()
+        .get(storage, &epoch)?
+        .unwrap_or_default();
+    let signed_blocks = validator_bridge_participation_handle()
+        .at(&epoch)
+        .iter(storage)?
+        .collect::<storage_api::Result<_>>()?;
+    Ok(BridgeParticipation {
+        bridge_blocks,
+        signed_blocks,
+    })
+}
+
+/// Read the parameters of the jailing of validators for bridge liveness
+/// failures. Validators are not jailed for it until governance sets them.
+pub fn read_bridge_liveness_params<S>(
+    storage: &S,
+) -> storage_api::Result<Option<BridgeLivenessParams>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::bridge_liveness_params_key())
+}
+
+/// Jail the consensus validators of the last epoch who signed the Ethereum
+/// bridge vote extensions at too few of its heights, if governance has set
+/// the bridge liveness parameters. Must be called in the first block of a
+/// new epoch. Returns the newly jailed validators, with their participation
+/// rate in the last epoch. The participation data older than the last epoch
+/// is pruned.
+///
+/// The validators with the lowest participation are jailed first, and no
+/// more validators are jailed once the stake of the jailed ones would reach
+/// a third of the total stake of the consensus set, such that the remaining
+/// validators can still reach a quorum.
+pub fn jail_for_bridge_liveness<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    jail_epoch: Epoch,
+) -> storage_api::Result<BTreeMap<Address, Dec>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut jailed = BTreeMap::new();
+    let Some(last_epoch) = current_epoch.checked_sub(Epoch(1)) else {
+        return Ok(jailed);
+    };
+
+    if let Some(liveness_params) = read_bridge_liveness_params(storage)? {
+        let participation = read_bridge_participation(storage, last_epoch)?;
+        if participation.bridge_blocks >= liveness_params.min_bridge_blocks {
+            let validators = read_consensus_validator_set_addresses_with_stake(
+                storage, last_epoch,
+            )?;
+            let total_stake = validators
+                .iter()
+                .map(|validator| validator.bonded_stake)
+                .sum::<token::Amount>();
+            // The stake of the validators already jailed in the jail epoch,
+            // e.g. for downtime, counts toward the cap
+            let mut jailed_stake = token::Amount::zero();
+            for validator in &validators {
+                let state_jail_epoch =
+                    validator_state_handle(&validator.address)
+                        .get(storage, jail_epoch, params)?;
+                if state_jail_epoch == Some(ValidatorState::Jailed) {
+                    jailed_stake += validator.bonded_stake;
+                }
+            }
+            // Sort the candidates by their participation rate, lowest first
+            let mut candidates = Vec::new();
+            for validator in validators {
+                // Skip the validators whose rate is unknown
+                let Some(rate) =
+                    participation.participation_rate(&validator.address)
+                else {
+                    continue;
+                };
+                if rate >= liveness_params.min_participation {
+                    continue;
+                }
+                let state_jail_epoch =
+                    validator_state_handle(&validator.address)
+                        .get(storage, jail_epoch, params)?
+                        .expect(
+                            "Validator should have a state for the jail epoch",
+                        );
+                if state_jail_epoch == ValidatorState::Jailed {
+                    continue;
+                }
+                candidates.push((rate, validator));
+            }
+            candidates.sort_by(
+                |(rate_a, validator_a), (rate_b, validator_b)| {
+                    rate_a.cmp(rate_b).then_with(|| {
+                        validator_a.address.cmp(&validator_b.address)
+                    })
+                },
+            );
+            for (
+                rate,
+                WeightedValidator {
+                    address: validator,
+                    bonded_stake,
+                },
+            ) in candidates
+            {
+                if (jailed_stake + bonded_stake) * 3_u64 >= total_stake {
+                    tracing::warn!(
+                        "Not jailing validator {} for its bridge liveness \
+                         failure in epoch {}, as it would leave too little \
+                         stake unjailed to reach a quorum",
+                        validator,
+                        last_epoch,
+                    );
+                    continue;
+                }
+                tracing::info!(
+                    "Jailing validator {} starting in epoch {} for signing \
+                     the bridge vote extensions at only {} of the heights of \
+                     epoch {}",
+                    validator,
+                    jail_epoch,
+                    rate,
+                    last_epoch,
+                );
+                jail_validator(
+                    storage,
+                    params,
+                    &validator,
+                    current_epoch,
+                    jail_epoch,
+                )?;
+                jailed_stake += bonded_stake;
+                jailed.insert(validator, rate);
+            }
+        }
+    }
+
+    // Prune the participation data older than the last epoch
+    if let Some(prune_epoch) = last_epoch.checked_sub(Epoch(1)) {
+        bridge_blocks_handle().remove(storage, &prune_epoch)?;
+        validator_bridge_participation_handle()
+            .remove_all(storage, &prune_epoch)?;
+        bridge_vext_heights_handle().remove_all(storage, &prune_epoch)?;
+        validator_bridge_vext_heights_handle()
+            .remove_all(storage, &prune_epoch)?;
+    }
+
+    Ok(jailed)
+}
+
+/// Change the commission rate of a validator
+pub fn change_validator_commission_rate<S>(
+    storage: &mut S,
+    validator: &Address,
+    new_rate: Dec,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    if new_rate.is_negative() {
+        return Err(CommissionRateChangeError::NegativeRate(
+            new_rate,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    if new_rate > Dec::one() {
+        return Err(CommissionRateChangeError::LargerThanOne(
+            new_rate,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    let max_change =
+        read_validator_max_commission_rate_change(storage, validator)?;
+    if max_change.is_none() {
+        return Err(CommissionRateChangeError::NoMaxSetInStorage(
+            validator.clone(),
+        )
+        .into());
+    }
+
+    let params = read_pos_params(storage)?;
+    let commission_handle = validator_commission_rate_handle(validator);
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let rate_at_pipeline = commission_handle
+        .get(storage, pipeline_epoch, &params)?
+        .expect("Could not find a rate in given epoch");
+    if new_rate == rate_at_pipeline {
+        return Ok(());
+    }
+    let rate_before_pipeline = commission_handle
+        .get(storage, pipeline_epoch.prev(), &params)?
+        .expect("Could not find a rate in given epoch");
+
+    let change_from_prev = new_rate.abs_diff(&rate_before_pipeline);
+    if change_from_prev > max_change.unwrap() {
+        return Err(CommissionRateChangeError::RateChangeTooLarge(
+            change_from_prev,
+            validator.clone(),
+        )
+        .into());
+    }
+
+    commission_handle.set(
+        storage,
+        new_rate,
+        current_epoch,
+        params.pipeline_len,
+    )?;
+    validator_commission_history_handle(validator).insert(
+        storage,
+        pipeline_epoch,
+        new_rate,
+    )?;
+    Ok(())
+}
+
+/// Read the history of a validator's commission rate, keyed by the epoch at
+/// which each rate takes effect
+pub fn read_validator_commission_history<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<BTreeMap<Epoch, Dec>>
+where
+    S: StorageRead,
+{
+    validator_commission_history_handle(validator)
+        .iter(storage)?
+        .collect()
+}
+
+/// Check if the given consensus key is already being used to ensure uniqueness.
+///
+/// If it's not being used, it will be inserted into the set that's being used
+/// for this. If it's already used, this will return an Error.
+pub fn try_insert_consensus_key<S>(
+    storage: &mut S,
+    consensus_key: &common::PublicKey,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = consensus_keys_key();
+    LazySet::open(key).try_insert(storage, consensus_key.clone())
+}
+
+/// Get the unique set of consensus keys in storage
+pub fn get_consensus_key_set<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeSet<common::PublicKey>>
+where
+    S: StorageRead,
+{
+    let key = consensus_keys_key();
+    let lazy_set = LazySet::<common::PublicKey>::open(key);
+    Ok(lazy_set.iter(storage)?.map(Result::unwrap).collect())
 }
 
 /// Check if the given consensus key is already being used to ensure uniqueness.
@@ -3667,6 +4787,87 @@ where
     Ok(delegations)
 }
 
+/// Find a page of the delegations to the given `validator`, including its
+/// self-bond, with their amount at the given epoch. The delegations are
+/// ordered by the address of the delegator, starting after the `after`
+/// address, if any. Delegations with no bonded tokens are skipped.
+pub fn find_validator_delegations_page<S>(
+    storage: &S,
+    validator: &Address,
+    epoch: Epoch,
+    after: Option<&Address>,
+    limit: usize,
+) -> storage_api::Result<DelegationsPage>
+where
+    S: StorageRead,
+{
+    let delegators = validator_delegators_handle(validator)
+        .iter(storage)?
+        .collect::<storage_api::Result<BTreeSet<Address>>>()?;
+    delegations_page(storage, delegators, epoch, after, limit, |delegator| {
+        bond_handle(delegator, validator)
+    })
+}
+
+/// Find a page of the delegations of the given `owner` (or source), with
+/// their amount at the given epoch. The delegations are ordered by the
+/// address of the validator, starting after the `after` address, if any.
+/// Delegations with no bonded tokens are skipped.
+pub fn find_delegations_page<S>(
+    storage: &S,
+    owner: &Address,
+    epoch: Epoch,
+    after: Option<&Address>,
+    limit: usize,
+) -> storage_api::Result<DelegationsPage>
+where
+    S: StorageRead,
+{
+    let validators = find_delegation_validators(storage, owner)?
+        .into_iter()
+        .collect();
+    delegations_page(storage, validators, epoch, after, limit, |validator| {
+        bond_handle(owner, validator)
+    })
+}
+
+/// Collect a page of the delegations with the given counterparties, whose
+/// bonds are given by `bond_handle`.
+fn delegations_page<S>(
+    storage: &S,
+    counterparties: BTreeSet<Address>,
+    epoch: Epoch,
+    after: Option<&Address>,
+    limit: usize,
+    bond_handle: impl Fn(&Address) -> Bonds,
+) -> storage_api::Result<DelegationsPage>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let mut remaining = counterparties
+        .into_iter()
+        .filter(|address| after.map_or(true, |after| address > after))
+        .peekable();
+    let mut delegations = vec![];
+    while delegations.len() < limit {
+        let Some(address) = remaining.next() else {
+            break;
+        };
+        let amount = bond_handle(&address)
+            .get_sum(storage, epoch, &params)?
+            .unwrap_or_default();
+        if !amount.is_zero() {
+            delegations.push((address, amount));
+        }
+    }
+    let next = match remaining.peek() {
+        Some(_) => delegations.last().map(|(address, _)| address.clone()),
+        None => None,
+    };
+    Ok(DelegationsPage { delegations, next })
+}
+
 /// Find if the given source address has any bonds.
 pub fn has_bonds<S>(storage: &S, source: &Address) -> storage_api::Result<bool>
 where
@@ -3731,6 +4932,102 @@ where
         .collect()
 }
 
+/// Find the redelegations of the given delegator whose tokens may still be
+/// slashed for an infraction of their source validator at the given epoch,
+/// and which therefore cannot be redelegated again yet.
+pub fn find_pending_redelegations<S>(
+    storage: &S,
+    delegator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<PendingRedelegation>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    // the epoch from which the redelegated tokens that started contributing
+    // to the destination validator at `start` may be redelegated again, as
+    // in `redelegate_tokens`
+    let chainable_from =
+        |start: Epoch| start.prev() + params.slash_processing_epoch_offset();
+
+    let mut pending: BTreeMap<(Address, Address, Epoch), token::Amount> =
+        BTreeMap::new();
+    let redelegated_bonds = delegator_redelegated_bonds_handle(delegator);
+    for res in redelegated_bonds.iter(storage)? {
+        let (
+            NestedSubKey::Data {
+                key: dest_validator,
+                nested_sub_key:
+                    NestedSubKey::Data {
+                        key: start,
+                        nested_sub_key:
+                            NestedSubKey::Data {
+                                key: src_validator,
+                                nested_sub_key: SubKey::Data(_bond_start),
+                            },
+                    },
+            },
+            amount,
+        ) = res?;
+        if chainable_from(start) > epoch {
+            let total = pending
+                .entry((src_validator, dest_validator, start))
+                .or_default();
+            *total += amount;
+        }
+    }
+
+    Ok(pending
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|((src_validator, dest_validator, start), amount)| {
+            PendingRedelegation {
+                src_validator,
+                dest_validator,
+                start,
+                amount,
+                chainable_from: chainable_from(start),
+            }
+        })
+        .collect())
+}
+
+/// Find the unbonds of the given delegator that have not been withdrawn
+/// yet, along with the number of epochs left at the given epoch until they
+/// can be withdrawn. The unbonds are sorted by their withdrawable epoch.
+pub fn find_withdrawable_unbonds<S>(
+    storage: &S,
+    delegator: &Address,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<WithdrawableUnbond>>
+where
+    S: StorageRead,
+{
+    let mut unbonds = vec![];
+    for (bond_id, details) in
+        bonds_and_unbonds(storage, Some(delegator.clone()), None)?
+    {
+        for unbond in details.unbonds {
+            let slashed = unbond.slashed_amount.unwrap_or_default();
+            unbonds.push(WithdrawableUnbond {
+                validator: bond_id.validator.clone(),
+                start: unbond.start,
+                withdraw: unbond.withdraw,
+                epochs_left: unbond.withdraw.0.saturating_sub(epoch.0),
+                amount: unbond.amount.checked_sub(slashed).unwrap_or_default(),
+            });
+        }
+    }
+    unbonds.sort_by(|a, b| {
+        (a.withdraw, &a.validator, a.start).cmp(&(
+            b.withdraw,
+            &b.validator,
+            b.start,
+        ))
+    });
+    Ok(unbonds)
+}
+
 /// Collect the details of all bonds and unbonds that match the source and
 /// validator arguments. If either source or validator is `None`, then grab the
 /// information for all sources or validators, respectively.
@@ -3748,12 +5045,36 @@ where
         (Some(source), Some(validator)) => {
             find_bonds_and_unbonds_details(storage, &params, source, validator)
         }
-        _ => {
-            get_multiple_bonds_and_unbonds(storage, &params, source, validator)
-        }
+        _ => get_multiple_bonds_and_unbonds(
+            storage, &params, source, validator, None,
+        ),
     }
 }
 
+/// Collect the details of the bonds and unbonds of all sources and validators
+/// that take effect in the given range of epochs (inclusive), i.e. the bonds
+/// that start contributing to the stake of a validator and the unbonds that
+/// stop contributing to it in one of these epochs. Note that bonds and unbonds
+/// take effect at the pipeline offset from the epoch in which they are
+/// submitted.
+pub fn bonds_and_unbonds_in_epochs<S>(
+    storage: &S,
+    from_epoch: Epoch,
+    to_epoch: Epoch,
+) -> storage_api::Result<BondsAndUnbondsDetails>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    get_multiple_bonds_and_unbonds(
+        storage,
+        &params,
+        None,
+        None,
+        Some(from_epoch..=to_epoch),
+    )
+}
+
 /// Collect the details of all of the enqueued slashes to be processed in future
 /// epochs into a nested map
 pub fn find_all_enqueued_slashes<S>(
@@ -3831,6 +5152,7 @@ fn get_multiple_bonds_and_unbonds<S>(
     params: &PosParams,
     source: Option<Address>,
     validator: Option<Address>,
+    effective_epochs: Option<RangeInclusive<Epoch>>,
 ) -> storage_api::Result<BondsAndUnbondsDetails>
 where
     S: StorageRead,
@@ -3866,6 +5188,11 @@ where
                     {
                         return None;
                     }
+                    if let Some(epochs) = effective_epochs.as_ref() {
+                        if !epochs.contains(&start) {
+                            return None;
+                        }
+                    }
                     let change: token::Amount =
                         BorshDeserialize::try_from_slice(&val_bytes).ok()?;
                     if change.is_zero() {
@@ -3908,6 +5235,15 @@ where
                         }
                         _ => {}
                     }
+                    if let Some(epochs) = effective_epochs.as_ref() {
+                        // The first epoch in which the unbond stops
+                        // contributing to the stake
+                        let end = withdraw - params.withdrawable_epoch_offset()
+                            + params.pipeline_len;
+                        if !epochs.contains(&end) {
+                            return None;
+                        }
+                    }
                     let amount: token::Amount =
                         BorshDeserialize::try_from_slice(&val_bytes).ok()?;
                     return Some((bond_id, start, withdraw, amount));
@@ -4437,62 +5773,330 @@ where
     Ok(cubic_rate)
 }
 
-/// Record a slash for a misbehavior that has been received from Tendermint and
-/// then jail the validator, removing it from the validator set. The slash rate
-/// will be computed at a later epoch.
-#[allow(clippy::too_many_arguments)]
-pub fn slash<S>(
-    storage: &mut S,
+/// Record a slash for a misbehavior that has been received from Tendermint and
+/// then jail the validator, removing it from the validator set. The slash rate
+/// will be computed at a later epoch.
+#[allow(clippy::too_many_arguments)]
+pub fn slash<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    evidence_epoch: Epoch,
+    evidence_block_height: impl Into<u64>,
+    slash_type: SlashType,
+    validator: &Address,
+    validator_set_update_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let evidence_block_height: u64 = evidence_block_height.into();
+    let slash = Slash {
+        epoch: evidence_epoch,
+        block_height: evidence_block_height,
+        r#type: slash_type,
+        rate: Dec::zero(), // Let the rate be 0 initially before processing
+    };
+    // Need `+1` because we process at the beginning of a new epoch
+    let processing_epoch =
+        evidence_epoch + params.slash_processing_epoch_offset();
+
+    // Add the slash to the list of enqueued slashes to be processed at a later
+    // epoch
+    enqueued_slashes_handle()
+        .get_data_handler()
+        .at(&processing_epoch)
+        .at(validator)
+        .push(storage, slash)?;
+
+    // Update the most recent slash (infraction) epoch for the validator
+    let last_slash_epoch = read_validator_last_slash_epoch(storage, validator)?;
+    if last_slash_epoch.is_none()
+        || evidence_epoch.0 > last_slash_epoch.unwrap_or_default().0
+    {
+        write_validator_last_slash_epoch(storage, validator, evidence_epoch)?;
+    }
+
+    // Jail the validator and update validator sets
+    jail_validator(
+        storage,
+        params,
+        validator,
+        current_epoch,
+        validator_set_update_epoch,
+    )?;
+
+    // No other actions are performed here until the epoch in which the slash is
+    // processed.
+
+    Ok(())
+}
+
+/// Check if a slash of the given type, for an infraction committed by the
+/// given validator at some block height, has already been enqueued.
+pub fn is_slash_enqueued<S>(
+    storage: &S,
+    params: &PosParams,
+    validator: &Address,
+    evidence_epoch: Epoch,
+    evidence_block_height: impl Into<u64>,
+    slash_type: SlashType,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    let evidence_block_height: u64 = evidence_block_height.into();
+    let processing_epoch =
+        evidence_epoch + params.slash_processing_epoch_offset();
+    for slash in enqueued_slashes_handle()
+        .get_data_handler()
+        .at(&processing_epoch)
+        .at(validator)
+        .iter(storage)?
+    {
+        let slash = slash?;
+        if slash.block_height == evidence_block_height
+            && slash.r#type == slash_type
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Read the cubic slashing window length that was in effect at the given
+/// epoch. Falls back to the current parameters for epochs preceding the
+/// recorded history.
+pub fn read_cubic_slashing_window_length<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> storage_api::Result<u64>
+where
+    S: StorageRead,
+{
+    Ok(cubic_slashing_window_length_handle()
+        .get(storage, epoch, params)?
+        .unwrap_or(params.cubic_slashing_window_length))
+}
+
+/// Check if the evidence of an infraction committed at the given epoch and
+/// block height is older than the maximum evidence age allowed by the PoS
+/// parameters. Like in CometBFT, the evidence only expires once it is older
+/// than both the maximum age in epochs and the maximum age in blocks.
+pub fn is_evidence_expired(
     params: &PosParams,
+    evidence_epoch: Epoch,
+    evidence_height: BlockHeight,
     current_epoch: Epoch,
+    current_height: BlockHeight,
+) -> bool {
+    current_epoch.0.saturating_sub(evidence_epoch.0)
+        > params.max_evidence_age_epochs
+        && current_height.0.saturating_sub(evidence_height.0)
+            > params.max_evidence_age_blocks
+}
+
+/// Check if the evidence of an infraction committed at the given epoch and
+/// block height is outdated, i.e. it should have already been processed or
+/// it is older than the maximum evidence age. Uses the cubic slashing window
+/// length that was in effect at the evidence epoch.
+pub fn is_evidence_outdated<S>(
+    storage: &S,
+    params: &PosParams,
     evidence_epoch: Epoch,
-    evidence_block_height: impl Into<u64>,
-    slash_type: SlashType,
-    validator: &Address,
-    validator_set_update_epoch: Epoch,
-) -> storage_api::Result<()>
+    evidence_height: BlockHeight,
+    current_epoch: Epoch,
+    current_height: BlockHeight,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    if is_evidence_expired(
+        params,
+        evidence_epoch,
+        evidence_height,
+        current_epoch,
+        current_height,
+    ) {
+        return Ok(true);
+    }
+    let window_length =
+        read_cubic_slashing_window_length(storage, params, evidence_epoch)?;
+    Ok(evidence_epoch + params.slash_processing_epoch_offset()
+        <= current_epoch + window_length)
+}
+
+/// Apply the change of the slashing parameters passed by governance, if
+/// any, from the current epoch. This function is called upon a new epoch.
+/// Returns the applied change.
+pub fn apply_pending_slashing_params_change<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<SlashingParamsChange>>
 where
     S: StorageRead + StorageWrite,
 {
-    let evidence_block_height: u64 = evidence_block_height.into();
-    let slash = Slash {
-        epoch: evidence_epoch,
-        block_height: evidence_block_height,
-        r#type: slash_type,
-        rate: Dec::zero(), // Let the rate be 0 initially before processing
+    let key = storage::pending_slashing_params_key();
+    let Some(change) = storage.read::<SlashingParamsChange>(&key)? else {
+        return Ok(None);
     };
-    // Need `+1` because we process at the beginning of a new epoch
-    let processing_epoch =
-        evidence_epoch + params.slash_processing_epoch_offset();
+    storage.delete(&key)?;
+    let params = read_pos_params(storage)?;
+    let errors = change.validate(&params.owned);
+    if !errors.is_empty() {
+        tracing::error!(
+            ?change,
+            ?errors,
+            "Ignoring an invalid change of the slashing parameters"
+        );
+        return Ok(None);
+    }
 
-    // Add the slash to the list of enqueued slashes to be processed at a later
-    // epoch
-    enqueued_slashes_handle()
-        .get_data_handler()
-        .at(&processing_epoch)
-        .at(validator)
-        .push(storage, slash)?;
+    if let Some(window_length) = change.cubic_slashing_window_length {
+        let handle = cubic_slashing_window_length_handle();
+        if handle.get(storage, current_epoch, &params)?.is_none() {
+            // Record the window length in effect until now, if it hasn't
+            // been recorded at genesis
+            handle.set(
+                storage,
+                params.cubic_slashing_window_length,
+                Epoch::default(),
+                0,
+            )?;
+        }
+        handle.set(storage, window_length, current_epoch, 0)?;
+    }
+    let mut owned = params.owned;
+    change.apply(&mut owned);
+    write_pos_params(storage, &owned)?;
+    Ok(Some(change))
+}
 
-    // Update the most recent slash (infraction) epoch for the validator
-    let last_slash_epoch = read_validator_last_slash_epoch(storage, validator)?;
-    if last_slash_epoch.is_none()
-        || evidence_epoch.0 > last_slash_epoch.unwrap_or_default().0
-    {
-        write_validator_last_slash_epoch(storage, validator, evidence_epoch)?;
+/// Apply the change of the maximum number of consensus validators passed by
+/// governance, if any. The validator sets at the pipeline epoch are resized
+/// to the new maximum, such that the validators leaving or entering the
+/// consensus set are communicated to Tendermint once the pipeline epoch is
+/// reached. This function is called upon a new epoch, after the validator
+/// sets have been copied into the pipeline epoch. Returns the new maximum.
+pub fn apply_pending_max_validator_slots_change<S>(
+    storage: &mut S,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<u64>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let key = storage::pending_max_validator_slots_key();
+    let Some(max_validator_slots) = storage.read::<u64>(&key)? else {
+        return Ok(None);
+    };
+    storage.delete(&key)?;
+    let mut params = read_pos_params(storage)?;
+    params.owned.max_validator_slots = max_validator_slots;
+    let errors = params.owned.validate();
+    if !errors.is_empty() {
+        tracing::error!(
+            max_validator_slots,
+            ?errors,
+            "Ignoring an invalid change of the maximum number of consensus \
+             validators"
+        );
+        return Ok(None);
     }
 
-    // Jail the validator and update validator sets
-    jail_validator(
+    resize_consensus_validator_set(
         storage,
-        params,
-        validator,
+        &params,
         current_epoch,
-        validator_set_update_epoch,
+        params.pipeline_len,
     )?;
+    write_pos_params(storage, &params.owned)?;
+    Ok(Some(max_validator_slots))
+}
 
-    // No other actions are performed here until the epoch in which the slash is
-    // processed.
+/// Demote the lowest consensus validators to the below-capacity set, or
+/// promote the greatest below-capacity validators to the consensus set, at
+/// the given offset from the current epoch, until the size of the consensus
+/// set matches the maximum number of consensus validators in `params`.
+fn resize_consensus_validator_set<S>(
+    storage: &mut S,
+    params: &PosParams,
+    current_epoch: Epoch,
+    offset: u64,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let epoch = current_epoch + offset;
+    let consensus_val_handle = consensus_validator_set_handle().at(&epoch);
+    let below_capacity_val_handle =
+        below_capacity_validator_set_handle().at(&epoch);
+    let mut num_consensus_validators =
+        get_num_consensus_validators(storage, epoch)?;
+
+    while num_consensus_validators > params.max_validator_slots {
+        let min_consensus_amount =
+            get_min_consensus_validator_amount(&consensus_val_handle, storage)?;
+        let consensus_vals_min = consensus_val_handle.at(&min_consensus_amount);
+        let last_position = find_last_position(&consensus_vals_min, storage)?
+            .expect("The consensus validator set must not be empty");
+        let demoted = consensus_vals_min
+            .remove(storage, &last_position)?
+            .expect("Must have been removed");
+        tracing::debug!(
+            "Demoting validator {demoted} to the below-capacity set at epoch \
+             {epoch}"
+        );
+        insert_validator_into_set(
+            &below_capacity_val_handle.at(&min_consensus_amount.into()),
+            storage,
+            &epoch,
+            &demoted,
+        )?;
+        validator_state_handle(&demoted).set(
+            storage,
+            ValidatorState::BelowCapacity,
+            current_epoch,
+            offset,
+        )?;
+        num_consensus_validators -= 1;
+    }
 
+    while num_consensus_validators < params.max_validator_slots {
+        let Some(max_below_capacity_amount) =
+            get_max_below_capacity_validator_amount(
+                &below_capacity_val_handle,
+                storage,
+            )?
+        else {
+            break;
+        };
+        let below_capacity_vals_max =
+            below_capacity_val_handle.at(&max_below_capacity_amount.into());
+        let first_position =
+            find_first_position(&below_capacity_vals_max, storage)?
+                .expect("The below-capacity validator set must not be empty");
+        let promoted = below_capacity_vals_max
+            .remove(storage, &first_position)?
+            .expect("Must have been removed");
+        tracing::debug!(
+            "Promoting validator {promoted} to the consensus set at epoch \
+             {epoch}"
+        );
+        insert_validator_into_set(
+            &consensus_val_handle.at(&max_below_capacity_amount),
+            storage,
+            &epoch,
+            &promoted,
+        )?;
+        validator_state_handle(&promoted).set(
+            storage,
+            ValidatorState::Consensus,
+            current_epoch,
+            offset,
+        )?;
+        num_consensus_validators += 1;
+    }
     Ok(())
 }
 
@@ -4641,6 +6245,12 @@ where
             )?;
         }
 
+        // Record the slashed amount for the history of the validator
+        let total_slashed =
+            read_validator_total_slashed(storage, &validator)? + slash_acc;
+        storage
+            .write(&validator_total_slashed_key(&validator), total_slashed)?;
+
         // TODO: should we clear some storage here as is done in Quint??
         // Possibly make the `unbonded` LazyMaps epoched so that it is done
         // automatically?
@@ -5145,14 +6755,16 @@ where
     Ok(cmp::min(amount_due, slashable_amount))
 }
 
-/// Unjail a validator that is currently jailed.
-pub fn unjail_validator<S>(
-    storage: &mut S,
+/// Check if a validator that is currently jailed may be unjailed in the
+/// current epoch, i.e. that it is jailed up to the pipeline epoch and that
+/// all of its enqueued slashes have been processed.
+pub fn check_can_unjail_validator<S>(
+    storage: &S,
     validator: &Address,
     current_epoch: Epoch,
-) -> storage_api::Result<()>
+) -> storage_api::Result<Result<(), UnjailValidatorError>>
 where
-    S: StorageRead + StorageWrite,
+    S: StorageRead,
 {
     let params = read_pos_params(storage)?;
 
@@ -5162,16 +6774,15 @@ where
             validator_state_handle(validator).get(storage, epoch, &params)?;
         if let Some(state) = state {
             if state != ValidatorState::Jailed {
-                return Err(UnjailValidatorError::NotJailed(
+                return Ok(Err(UnjailValidatorError::NotJailed(
                     validator.clone(),
                     epoch,
-                )
-                .into());
+                )));
             }
         } else {
-            return Err(
-                UnjailValidatorError::NotAValidator(validator.clone()).into()
-            );
+            return Ok(Err(UnjailValidatorError::NotAValidator(
+                validator.clone(),
+            )));
         }
     }
 
@@ -5182,14 +6793,27 @@ where
         let eligible_epoch =
             last_slash_epoch + params.slash_processing_epoch_offset();
         if current_epoch < eligible_epoch {
-            return Err(UnjailValidatorError::NotEligible(
+            return Ok(Err(UnjailValidatorError::NotEligible(
                 validator.clone(),
                 eligible_epoch,
                 current_epoch,
-            )
-            .into());
+            )));
         }
     }
+    Ok(Ok(()))
+}
+
+/// Unjail a validator that is currently jailed.
+pub fn unjail_validator<S>(
+    storage: &mut S,
+    validator: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    check_can_unjail_validator(storage, validator, current_epoch)??;
+    let params = read_pos_params(storage)?;
 
     // Re-insert the validator into the validator set and update its state
     let pipeline_epoch = current_epoch + params.pipeline_len;
@@ -5361,6 +6985,17 @@ where
         amount_after_slashing.to_string_native()
     );
 
+    // Check that the redelegation keeps the dest validator within the stake
+    // cap. The redelegated tokens were already deducted from the total stake
+    // by the unbonding above.
+    check_validator_stake_cap(
+        storage,
+        &params,
+        dest_validator,
+        amount_after_slashing,
+        pipeline_epoch,
+    )??;
+
     // Add incoming redelegated bonds to the dest validator.
     // `updatedRedelegatedBonds` with updates to delegatorState
     // `redelegatedBonded`
@@ -5389,6 +7024,8 @@ where
             current_epoch,
             params.pipeline_len,
         )?;
+        validator_delegators_handle(dest_validator)
+            .insert(storage, delegator.clone())?;
         // `updatedDestValidator` --> `with("totalVBonded")`
         // Add the amount to the dest validator total bonded
         let dest_total_bonded = total_bonded_handle(dest_validator);
@@ -5802,13 +7439,15 @@ where
     Ok(())
 }
 
-/// Jail validators who failed to match the liveness threshold
+/// Jail validators who failed to match the liveness threshold. Returns the
+/// newly jailed validators, with the number of votes they missed in the
+/// liveness window.
 pub fn jail_for_liveness<S>(
     storage: &mut S,
     params: &PosParams,
     current_epoch: Epoch,
     jail_epoch: Epoch,
-) -> storage_api::Result<()>
+) -> storage_api::Result<BTreeMap<Address, u64>>
 where
     S: StorageRead + StorageWrite,
 {
@@ -5832,15 +7471,16 @@ where
             // Check if validator failed to match the threshold and jail
             // them
             if missed_votes >= missing_votes_threshold {
-                Some(address)
+                Some((address, missed_votes))
             } else {
                 None
             }
         })
-        .collect::<HashSet<_>>();
+        .collect::<BTreeMap<_, _>>();
 
-    for validator in &validators_to_jail {
-        let state_jail_epoch = validator_state_handle(validator)
+    let mut jailed = BTreeMap::new();
+    for (validator, missed_votes) in validators_to_jail {
+        let state_jail_epoch = validator_state_handle(&validator)
             .get(storage, jail_epoch, params)?
             .expect("Validator should have a state for the jail epoch");
         if state_jail_epoch == ValidatorState::Jailed {
@@ -5852,10 +7492,11 @@ where
             validator,
             jail_epoch,
         );
-        jail_validator(storage, params, validator, current_epoch, jail_epoch)?;
+        jail_validator(storage, params, &validator, current_epoch, jail_epoch)?;
+        jailed.insert(validator, missed_votes);
     }
 
-    Ok(())
+    Ok(jailed)
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -5951,6 +7592,26 @@ pub mod test_utils {
     }
 }
 
+/// The maximum length in bytes of each of the fields of a validator's
+/// metadata.
+pub const MAX_VALIDATOR_METADATA_LEN: usize = 1024;
+
+/// Check that a field of a validator's metadata doesn't exceed
+/// [`MAX_VALIDATOR_METADATA_LEN`].
+fn check_validator_metadata_len(
+    field: &'static str,
+    value: &str,
+) -> Result<(), MetadataError> {
+    if value.len() > MAX_VALIDATOR_METADATA_LEN {
+        return Err(MetadataError::TooLong(
+            field,
+            value.len(),
+            MAX_VALIDATOR_METADATA_LEN,
+        ));
+    }
+    Ok(())
+}
+
 /// Read PoS validator's email.
 pub fn read_validator_email<S>(
     storage: &S,
@@ -5972,6 +7633,7 @@ pub fn write_validator_email<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    check_validator_metadata_len("email", email)?;
     let key = validator_email_key(validator);
     if email.is_empty() {
         Err(MetadataError::CannotRemoveEmail.into())
@@ -6001,6 +7663,7 @@ pub fn write_validator_description<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    check_validator_metadata_len("description", description)?;
     let key = validator_description_key(validator);
     if description.is_empty() {
         storage.delete(&key)
@@ -6030,6 +7693,7 @@ pub fn write_validator_website<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    check_validator_metadata_len("website", website)?;
     let key = validator_website_key(validator);
     if website.is_empty() {
         storage.delete(&key)
@@ -6059,6 +7723,7 @@ pub fn write_validator_discord_handle<S>(
 where
     S: StorageRead + StorageWrite,
 {
+    check_validator_metadata_len("discord handle", discord_handle)?;
     let key = validator_discord_key(validator);
     if discord_handle.is_empty() {
         storage.delete(&key)
@@ -6067,6 +7732,88 @@ where
     }
 }
 
+/// Read PoS validator's moniker.
+pub fn read_validator_moniker<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    storage.read(&validator_moniker_key(validator))
+}
+
+/// Write PoS validator's moniker. If the provided arg is an empty string,
+/// remove the data.
+pub fn write_validator_moniker<S>(
+    storage: &mut S,
+    validator: &Address,
+    moniker: &String,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    check_validator_metadata_len("moniker", moniker)?;
+    let key = validator_moniker_key(validator);
+    if moniker.is_empty() {
+        storage.delete(&key)
+    } else {
+        storage.write(&key, moniker)
+    }
+}
+
+/// Read PoS validator's security contact.
+pub fn read_validator_security_contact<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Option<String>>
+where
+    S: StorageRead,
+{
+    storage.read(&validator_security_contact_key(validator))
+}
+
+/// Write PoS validator's security contact. If the provided arg is an empty
+/// string, remove the data.
+pub fn write_validator_security_contact<S>(
+    storage: &mut S,
+    validator: &Address,
+    security_contact: &String,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    check_validator_metadata_len("security contact", security_contact)?;
+    let key = validator_security_contact_key(validator);
+    if security_contact.is_empty() {
+        storage.delete(&key)
+    } else {
+        storage.write(&key, security_contact)
+    }
+}
+
+/// Read PoS validator's metadata, if any. The email is the only required
+/// field of the metadata, hence `None` is returned in its absence.
+pub fn read_validator_metadata<S>(
+    storage: &S,
+    validator: &Address,
+) -> storage_api::Result<Option<ValidatorMetaData>>
+where
+    S: StorageRead,
+{
+    let Some(email) = read_validator_email(storage, validator)? else {
+        return Ok(None);
+    };
+    Ok(Some(ValidatorMetaData {
+        email,
+        description: read_validator_description(storage, validator)?,
+        website: read_validator_website(storage, validator)?,
+        discord_handle: read_validator_discord_handle(storage, validator)?,
+        moniker: read_validator_moniker(storage, validator)?,
+        security_contact: read_validator_security_contact(storage, validator)?,
+    }))
+}
+
 /// Write validator's metadata.
 pub fn write_validator_metadata<S>(
     storage: &mut S,
@@ -6088,6 +7835,12 @@ where
     if let Some(discord) = metadata.discord_handle.as_ref() {
         write_validator_discord_handle(storage, validator, discord)?;
     }
+    if let Some(moniker) = metadata.moniker.as_ref() {
+        write_validator_moniker(storage, validator, moniker)?;
+    }
+    if let Some(security_contact) = metadata.security_contact.as_ref() {
+        write_validator_security_contact(storage, validator, security_contact)?;
+    }
     Ok(())
 }
 
@@ -6102,6 +7855,8 @@ pub fn change_validator_metadata<S>(
     description: Option<String>,
     website: Option<String>,
     discord_handle: Option<String>,
+    moniker: Option<String>,
+    security_contact: Option<String>,
     commission_rate: Option<Dec>,
     current_epoch: Epoch,
 ) -> storage_api::Result<()>
@@ -6120,6 +7875,16 @@ where
     if let Some(discord) = discord_handle {
         write_validator_discord_handle(storage, validator, &discord)?;
     }
+    if let Some(moniker) = moniker {
+        write_validator_moniker(storage, validator, &moniker)?;
+    }
+    if let Some(security_contact) = security_contact {
+        write_validator_security_contact(
+            storage,
+            validator,
+            &security_contact,
+        )?;
+    }
     if let Some(commission_rate) = commission_rate {
         change_validator_commission_rate(
             storage,
@@ -6217,6 +7982,11 @@ where
     // Update the last claim epoch in storage
     write_last_reward_claim_epoch(storage, &source, validator, current_epoch)?;
 
+    // Deduct the premiums charged against the rewards of an insured bond,
+    // which were already paid into the insurance pool
+    let premiums = take_insurance_premiums(storage, &source, validator)?;
+    reward_tokens = reward_tokens.checked_sub(premiums).unwrap_or_default();
+
     // Transfer the bonded tokens from PoS to the source
     let staking_token = staking_token_address(storage);
     token::transfer(storage, &staking_token, &ADDRESS, &source, reward_tokens)?;
@@ -6224,7 +7994,8 @@ where
     Ok(reward_tokens)
 }
 
-/// Query the amount of available reward tokens for a given bond.
+/// Query the amount of available reward tokens for a given bond. The premiums
+/// charged against the rewards of an insured bond are left out.
 pub fn query_reward_tokens<S>(
     storage: &S,
     source: Option<&Address>,
@@ -6245,7 +8016,99 @@ where
     let rewards_from_counter =
         read_rewards_counter(storage, &source, validator)?;
 
-    Ok(rewards_from_bonds + rewards_from_counter)
+    let premiums = read_insurance_premiums(storage, &source, validator)?;
+    Ok((rewards_from_bonds + rewards_from_counter)
+        .checked_sub(premiums)
+        .unwrap_or_default())
+}
+
+/// Query the amounts of reward tokens that a `source` can claim, for each
+/// validator it has bonded to. Validators with nothing to claim are left out.
+pub fn query_claimable_rewards<S>(
+    storage: &S,
+    source: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<BTreeMap<Address, token::Amount>>
+where
+    S: StorageRead,
+{
+    // The rewards counter may hold rewards from bonds that have been fully
+    // unbonded since
+    let mut validators: BTreeSet<Address> =
+        find_delegation_validators(storage, source)?
+            .into_iter()
+            .collect();
+    for iter_result in storage_api::iter_prefix_bytes(
+        storage,
+        &rewards_counter_source_prefix(source),
+    )? {
+        let (key, _amount_bytes) = iter_result?;
+        if let Some(DbKeySeg::AddressSeg(validator)) = key.segments.last() {
+            validators.insert(validator.clone());
+        }
+    }
+
+    let mut claimable = BTreeMap::new();
+    for validator in validators {
+        let amount = query_reward_tokens(
+            storage,
+            Some(source),
+            &validator,
+            current_epoch,
+        )?;
+        if !amount.is_zero() {
+            claimable.insert(validator, amount);
+        }
+    }
+    Ok(claimable)
+}
+
+/// Project the staking rewards of bonding the given `amount` of tokens to a
+/// validator in the `current_epoch`, over a year. The projection uses the
+/// last PoS inflation amount, and the total stake and the validator's
+/// commission rate at the pipeline epoch, from which the bond contributes to
+/// stake. It assumes that these remain the same for a year, and that the
+/// rewards are distributed in proportion to stake. Returns `None` if the
+/// given address is not a validator.
+pub fn projected_rewards<S>(
+    storage: &S,
+    validator: &Address,
+    amount: token::Amount,
+    current_epoch: Epoch,
+) -> storage_api::Result<Option<ProjectedRewards>>
+where
+    S: StorageRead,
+{
+    let params = read_pos_params(storage)?;
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let Some(commission_rate) = validator_commission_rate_handle(validator)
+        .get(storage, pipeline_epoch, &params)?
+    else {
+        return Ok(None);
+    };
+    let epochs_per_year: u64 = storage
+        .read(&params_storage::get_epochs_per_year_key())?
+        .ok_or_else(|| {
+            storage_api::Error::new_const(
+                "Epochs per year should exist in storage",
+            )
+        })?;
+    let inflation: token::Amount = storage
+        .read(&params_storage::get_pos_inflation_amount_key())?
+        .unwrap_or_default();
+
+    let total_stake =
+        read_total_stake(storage, &params, pipeline_epoch)? + amount;
+    let gross_apr = (Dec::from(inflation) * epochs_per_year)
+        .trunc_div(&Dec::from(total_stake))
+        .unwrap_or_default();
+    let net_apr = gross_apr * (Dec::one() - commission_rate);
+    Ok(Some(ProjectedRewards {
+        gross_apr,
+        commission_rate,
+        net_apr,
+        annual_rewards: net_apr * amount,
+    }))
 }
 
 /// Get the last epoch in which rewards were claimed from storage, if any
@@ -64,6 +64,16 @@ pub struct OwnedPosParams {
     /// The minimum required activity of consesus validators, in percentage,
     /// over the `liveness_window_check`
     pub liveness_threshold: Dec,
+    /// The maximum age, in epochs, of the evidence of an infraction that
+    /// may still lead to a slash. Evidence older than both this and
+    /// `max_evidence_age_blocks` is discarded. Must be at least the
+    /// `unbonding_len`, such that no evidence is discarded while the stake
+    /// of the validator can still be slashed.
+    pub max_evidence_age_epochs: u64,
+    /// The maximum age, in blocks, of the evidence of an infraction that
+    /// may still lead to a slash. Evidence older than both this and
+    /// `max_evidence_age_epochs` is discarded.
+    pub max_evidence_age_blocks: u64,
 }
 
 impl Default for PosParams {
@@ -101,6 +111,8 @@ impl Default for OwnedPosParams {
             validator_stake_threshold: token::Amount::native_whole(1_u64),
             liveness_window_check: 10_000,
             liveness_threshold: Dec::new(9, 1).expect("Test failed"),
+            max_evidence_age_epochs: 21,
+            max_evidence_age_blocks: 100_000,
         }
     }
 }
@@ -115,6 +127,8 @@ pub enum ValidationError {
     TotalVotingPowerTooLarge(Uint),
     #[error("Votes per token cannot be greater than 1, got {0}")]
     VotesPerTokenGreaterThanOne(Dec),
+    #[error("Maximum number of consensus validators must be > 0")]
+    NoValidatorSlots,
     #[error("Pipeline length must be >= 2, got {0}")]
     PipelineLenTooShort(u64),
     #[error(
@@ -122,6 +136,160 @@ pub enum ValidationError {
          pipeline: {1}"
     )]
     UnbondingLenTooShort(u64, u64),
+    #[error("Slash rate must be between 0 and 1, got {0}")]
+    SlashRateOutOfRange(Dec),
+    #[error(
+        "Cubic slashing window length must not decrease. Got {0}, current \
+         length: {1}"
+    )]
+    CubicSlashingWindowDecreased(u64, u64),
+    #[error("Insurance rate must be between 0 and 1, got {0}")]
+    InsuranceRateOutOfRange(Dec),
+    #[error("Bridge participation must be between 0 and 1, got {0}")]
+    BridgeParticipationOutOfRange(Dec),
+    #[error("The minimum number of bridge blocks must be at least 1, got {0}")]
+    MinBridgeBlocksZero(u64),
+    #[error(
+        "Maximum evidence age in epochs must be >= unbonding length. Got \
+         maximum evidence age: {0}, unbonding: {1}"
+    )]
+    MaxEvidenceAgeTooShort(u64, u64),
+    #[error("Maximum evidence age in blocks must be at least 1")]
+    MaxEvidenceAgeBlocksZero,
+}
+
+/// A change of the slashing parameters, passed by governance. The change is
+/// applied at the start of the next epoch. The parameters that are not set
+/// are left unchanged.
+///
+/// The cubic slashing window length may only increase, as a shorter window
+/// would let the slashes enqueued with the current window, which are
+/// processed at an epoch offset that depends on its length, be skipped.
+#[derive(
+    Debug, Clone, Default, PartialEq, BorshDeserialize, BorshSerialize,
+)]
+pub struct SlashingParamsChange {
+    /// The new fraction of a validator's stake slashed on a duplicate vote
+    pub duplicate_vote_min_slash_rate: Option<Dec>,
+    /// The new fraction of a validator's stake slashed on a light client
+    /// attack
+    pub light_client_attack_min_slash_rate: Option<Dec>,
+    /// The new number of epochs above and below the infraction epoch to
+    /// consider when doing cubic slashing
+    pub cubic_slashing_window_length: Option<u64>,
+}
+
+impl SlashingParamsChange {
+    /// Validate the changed values against the current parameters. Returns
+    /// an empty list if the values are valid.
+    #[must_use]
+    pub fn validate(&self, params: &OwnedPosParams) -> Vec<ValidationError> {
+        let mut errors: Vec<_> = [
+            self.duplicate_vote_min_slash_rate,
+            self.light_client_attack_min_slash_rate,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|rate| rate.is_negative() || *rate > Dec::one())
+        .map(ValidationError::SlashRateOutOfRange)
+        .collect();
+        if let Some(window_length) = self.cubic_slashing_window_length {
+            if window_length < params.cubic_slashing_window_length {
+                errors.push(ValidationError::CubicSlashingWindowDecreased(
+                    window_length,
+                    params.cubic_slashing_window_length,
+                ));
+            }
+        }
+        errors
+    }
+
+    /// Apply the change to the given parameters.
+    pub fn apply(&self, params: &mut OwnedPosParams) {
+        if let Some(rate) = self.duplicate_vote_min_slash_rate {
+            params.duplicate_vote_min_slash_rate = rate;
+        }
+        if let Some(rate) = self.light_client_attack_min_slash_rate {
+            params.light_client_attack_min_slash_rate = rate;
+        }
+        if let Some(window_length) = self.cubic_slashing_window_length {
+            params.cubic_slashing_window_length = window_length;
+        }
+    }
+}
+
+/// The parameters of the opt-in slashing insurance pool, controlled by
+/// governance. The pool is funded by a cut of the rewards claimed from the
+/// insured bonds, and compensates them for the tokens slashed due to the
+/// faults of their validators.
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct InsuranceParams {
+    /// The fraction of the rewards claimed from an insured bond that is paid
+    /// into the pool
+    pub premium_rate: Dec,
+    /// The fraction of the tokens slashed from an insured bond that the pool
+    /// pays back to the source of the bond
+    pub coverage_rate: Dec,
+}
+
+impl Default for InsuranceParams {
+    fn default() -> Self {
+        Self {
+            // 2%
+            premium_rate: Dec::new(2, 2).expect("Test failed"),
+            // 50%
+            coverage_rate: Dec::new(5, 1).expect("Test failed"),
+        }
+    }
+}
+
+impl InsuranceParams {
+    /// Validate the parameters values. Returns an empty list if the values
+    /// are valid.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationError> {
+        [self.premium_rate, self.coverage_rate]
+            .into_iter()
+            .filter(|rate| rate.is_negative() || *rate > Dec::one())
+            .map(ValidationError::InsuranceRateOutOfRange)
+            .collect()
+    }
+}
+
+/// The parameters of the jailing of consensus validators who rarely sign the
+/// Ethereum bridge vote extensions, controlled by governance. Validators are
+/// only jailed for bridge liveness failures while these parameters are set.
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct BridgeLivenessParams {
+    /// The minimum fraction of the blocks with bridge vote extensions in an
+    /// epoch that a consensus validator must have signed one in, to avoid
+    /// being jailed
+    pub min_participation: Dec,
+    /// The minimum number of blocks with bridge vote extensions in an epoch
+    /// for the participation of the validators to be checked
+    pub min_bridge_blocks: u64,
+}
+
+impl BridgeLivenessParams {
+    /// Validate the parameters values. Returns an empty list if the values
+    /// are valid.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        if self.min_participation.is_negative()
+            || self.min_participation > Dec::one()
+        {
+            errors.push(ValidationError::BridgeParticipationOutOfRange(
+                self.min_participation,
+            ));
+        }
+        if self.min_bridge_blocks == 0 {
+            errors.push(ValidationError::MinBridgeBlocksZero(
+                self.min_bridge_blocks,
+            ));
+        }
+        errors
+    }
 }
 
 /// The number of fundamental units per whole token of the native staking token
@@ -140,6 +308,10 @@ impl OwnedPosParams {
     pub fn validate(&self) -> Vec<ValidationError> {
         let mut errors = vec![];
 
+        if self.max_validator_slots == 0 {
+            errors.push(ValidationError::NoValidatorSlots);
+        }
+
         if self.pipeline_len < 2 {
             errors
                 .push(ValidationError::PipelineLenTooShort(self.pipeline_len));
@@ -152,6 +324,20 @@ impl OwnedPosParams {
             ))
         }
 
+        // Evidence is slashable until it is `slash_processing_epoch_offset()
+        // - cubic_slashing_window_length - 1` epochs old, i.e. for the
+        // `unbonding_len`, so it must not expire any earlier
+        if self.max_evidence_age_epochs < self.unbonding_len {
+            errors.push(ValidationError::MaxEvidenceAgeTooShort(
+                self.max_evidence_age_epochs,
+                self.unbonding_len,
+            ))
+        }
+
+        if self.max_evidence_age_blocks == 0 {
+            errors.push(ValidationError::MaxEvidenceAgeBlocksZero)
+        }
+
         // Check maximum total voting power cannot get larger than what
         // Tendermint allows
         let max_total_voting_power = (self.tm_votes_per_token
@@ -293,6 +293,22 @@ where
             .expect("The block height of the current epoch should be known")
     }
 
+    /// Get a validator's protocol key from storage, at the given epoch, or the
+    /// last one, if none is provided.
+    pub fn read_validator_protocol_key(
+        self,
+        validator: &Address,
+        epoch: Option<Epoch>,
+    ) -> Option<key::common::PublicKey> {
+        let epoch = epoch
+            .unwrap_or_else(|| self.wl_storage.storage.get_current_epoch().0);
+        let params = self.get_pos_params();
+        crate::validator_protocol_key_handle(validator)
+            .get(self.wl_storage, epoch, &params)
+            .ok()
+            .flatten()
+    }
+
     /// Get a validator's Ethereum hot key from storage, at the given epoch, or
     /// the last one, if none is provided.
     pub fn read_validator_eth_hot_key(
@@ -2,13 +2,20 @@
 
 use namada_core::ledger::storage_api::collections::{lazy_map, lazy_vec};
 use namada_core::types::address::Address;
-use namada_core::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use namada_core::types::key::protocol_pk_key;
+use namada_core::types::storage::{BlockHeight, DbKeySeg, Epoch, Key, KeySeg};
 
 use super::ADDRESS;
 use crate::epoched::LAZY_MAP_SUB_KEY;
 use crate::types::BondId;
 
 const PARAMS_STORAGE_KEY: &str = "params";
+const PENDING_SLASHING_PARAMS_STORAGE_KEY: &str = "pending_slashing_params";
+const PENDING_MAX_VALIDATOR_SLOTS_STORAGE_KEY: &str =
+    "pending_max_validator_slots";
+const MAX_VALIDATOR_STAKE_SHARE_STORAGE_KEY: &str = "max_validator_stake_share";
+const CUBIC_SLASHING_WINDOW_LENGTH_STORAGE_KEY: &str =
+    "cubic_slashing_window_length";
 const VALIDATOR_ADDRESSES_KEY: &str = "validator_addresses";
 #[allow(missing_docs)]
 pub const VALIDATOR_STORAGE_PREFIX: &str = "validator";
@@ -19,6 +26,7 @@ const VALIDATOR_ETH_HOT_KEY_STORAGE_KEY: &str = "eth_hot_key";
 const VALIDATOR_STATE_STORAGE_KEY: &str = "state";
 const VALIDATOR_DELTAS_STORAGE_KEY: &str = "deltas";
 const VALIDATOR_COMMISSION_RATE_STORAGE_KEY: &str = "commission_rate";
+const VALIDATOR_COMMISSION_HISTORY_STORAGE_KEY: &str = "commission_history";
 const VALIDATOR_MAX_COMMISSION_CHANGE_STORAGE_KEY: &str =
     "max_commission_rate_change";
 const VALIDATOR_REWARDS_PRODUCT_KEY: &str = "validator_rewards_product";
@@ -27,6 +35,7 @@ const VALIDATOR_LAST_KNOWN_PRODUCT_EPOCH_KEY: &str =
 const SLASHES_PREFIX: &str = "slash";
 const ENQUEUED_SLASHES_KEY: &str = "enqueued_slashes";
 const VALIDATOR_LAST_SLASH_EPOCH: &str = "last_slash_epoch";
+const VALIDATOR_TOTAL_SLASHED: &str = "total_slashed";
 const BOND_STORAGE_KEY: &str = "bond";
 const UNBOND_STORAGE_KEY: &str = "unbond";
 const VALIDATOR_TOTAL_BONDED_STORAGE_KEY: &str = "total_bonded";
@@ -39,10 +48,12 @@ const TOTAL_DELTAS_STORAGE_KEY: &str = "total_deltas";
 const VALIDATOR_SET_POSITIONS_KEY: &str = "validator_set_positions";
 const CONSENSUS_KEYS: &str = "consensus_keys";
 const LAST_BLOCK_PROPOSER_STORAGE_KEY: &str = "last_block_proposer";
+const BLOCK_PROPOSERS_STORAGE_PREFIX: &str = "block_proposers";
 const CONSENSUS_VALIDATOR_SET_ACCUMULATOR_STORAGE_KEY: &str =
     "validator_rewards_accumulator";
 const LAST_REWARD_CLAIM_EPOCH: &str = "last_reward_claim_epoch";
 const REWARDS_COUNTER_KEY: &str = "validator_rewards_commissions";
+const VALIDATOR_DELEGATORS_KEY: &str = "delegators";
 const VALIDATOR_INCOMING_REDELEGATIONS_KEY: &str = "incoming_redelegations";
 const VALIDATOR_OUTGOING_REDELEGATIONS_KEY: &str = "outgoing_redelegations";
 const VALIDATOR_TOTAL_REDELEGATED_BONDED_KEY: &str = "total_redelegated_bonded";
@@ -54,6 +65,20 @@ const VALIDATOR_EMAIL_KEY: &str = "email";
 const VALIDATOR_DESCRIPTION_KEY: &str = "description";
 const VALIDATOR_WEBSITE_KEY: &str = "website";
 const VALIDATOR_DISCORD_KEY: &str = "discord_handle";
+const VALIDATOR_MONIKER_KEY: &str = "moniker";
+const VALIDATOR_SECURITY_CONTACT_KEY: &str = "security_contact";
+const AUTO_WITHDRAWALS_KEY: &str = "auto_withdrawals";
+const AUTO_COMPOUND_KEY: &str = "auto_compound";
+const INSURED_BONDS_KEY: &str = "insured_bonds";
+const INSURANCE_POOL_KEY: &str = "insurance_pool";
+const INSURANCE_PREMIUMS_KEY: &str = "insurance_premiums";
+const INSURANCE_PARAMS_KEY: &str = "insurance_params";
+const VALIDATOR_HEARTBEATS_KEY: &str = "validator_heartbeats";
+const BRIDGE_LIVENESS_PARAMS_KEY: &str = "bridge_liveness_params";
+const BRIDGE_PARTICIPATION_KEY: &str = "bridge_participation";
+const BRIDGE_BLOCKS_KEY: &str = "bridge_blocks";
+const BRIDGE_VEXT_HEIGHTS_KEY: &str = "bridge_vext_heights";
+const VALIDATOR_BRIDGE_VEXT_HEIGHTS_KEY: &str = "validator_bridge_vext_heights";
 const LIVENESS_PREFIX: &str = "liveness";
 const LIVENESS_MISSED_VOTES: &str = "missed_votes";
 const LIVENESS_MISSED_VOTES_SUM: &str = "sum_missed_votes";
@@ -78,6 +103,65 @@ pub fn is_params_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == PARAMS_STORAGE_KEY)
 }
 
+/// Storage key for a change of the slashing parameters passed by governance,
+/// which is applied at the start of the next epoch.
+pub fn pending_slashing_params_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PENDING_SLASHING_PARAMS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for a pending change of the slashing parameters?
+pub fn is_pending_slashing_params_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                DbKeySeg::AddressSeg(addr),
+                DbKeySeg::StringSeg(key)
+            ] if addr == &ADDRESS && key == PENDING_SLASHING_PARAMS_STORAGE_KEY)
+}
+
+/// Storage key for a change of the maximum number of consensus validators
+/// passed by governance, which is applied at the start of the next epoch.
+pub fn pending_max_validator_slots_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PENDING_MAX_VALIDATOR_SLOTS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for a pending change of the maximum number of consensus
+/// validators?
+pub fn is_pending_max_validator_slots_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                DbKeySeg::AddressSeg(addr),
+                DbKeySeg::StringSeg(key)
+            ] if addr == &ADDRESS
+                && key == PENDING_MAX_VALIDATOR_SLOTS_STORAGE_KEY)
+}
+
+/// Storage key for the maximum share of the total stake that a single
+/// validator may hold, which is controlled by governance.
+pub fn max_validator_stake_share_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&MAX_VALIDATOR_STAKE_SHARE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the maximum share of the total stake that a single
+/// validator may hold?
+pub fn is_max_validator_stake_share_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                DbKeySeg::AddressSeg(addr),
+                DbKeySeg::StringSeg(key)
+            ] if addr == &ADDRESS
+                && key == MAX_VALIDATOR_STAKE_SHARE_STORAGE_KEY)
+}
+
+/// Storage key for the epoched cubic slashing window length.
+pub fn cubic_slashing_window_length_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&CUBIC_SLASHING_WINDOW_LENGTH_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key prefix for validator data.
 fn validator_prefix(validator: &Address) -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -187,6 +271,40 @@ pub fn is_validator_eth_hot_key_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Is storage key for validator's eth hot or cold key, or any of their epoched
+/// data? Returns the validator's address if so.
+pub fn is_validator_eth_key_subkey(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(validator),
+            DbKeySeg::StringSeg(key),
+            ..,
+        ] if addr == &ADDRESS
+            && prefix == VALIDATOR_STORAGE_PREFIX
+            && (key == VALIDATOR_ETH_HOT_KEY_STORAGE_KEY
+                || key == VALIDATOR_ETH_COLD_KEY_STORAGE_KEY) =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
+/// Is storage key for validator's protocol key, or any of its epoched data?
+/// Returns the validator's address if so.
+pub fn is_validator_protocol_key_subkey(key: &Key) -> Option<&Address> {
+    match key.segments.first() {
+        Some(DbKeySeg::AddressSeg(validator))
+            if key.split_prefix(&protocol_pk_key(validator)).is_some() =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator's commission rate.
 pub fn validator_commission_rate_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -221,6 +339,39 @@ pub fn is_validator_commission_rate_key(
     }
 }
 
+/// Storage key for the history of a validator's commission rate.
+pub fn validator_commission_history_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_COMMISSION_HISTORY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the history of a validator's commission rate? Returns
+/// the validator and the epoch at which the recorded rate takes effect if it
+/// is.
+pub fn is_validator_commission_history_key(
+    key: &Key,
+) -> Option<(&Address, Epoch)> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(validator),
+            DbKeySeg::StringSeg(key),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::StringSeg(epoch),
+        ] if addr == &ADDRESS
+            && prefix == VALIDATOR_STORAGE_PREFIX
+            && key == VALIDATOR_COMMISSION_HISTORY_STORAGE_KEY
+            && data == lazy_map::DATA_SUBKEY =>
+        {
+            let epoch = Epoch::parse(epoch.clone()).ok()?;
+            Some((validator, epoch))
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for validator's maximum commission rate change per epoch.
 pub fn validator_max_commission_rate_change_key(validator: &Address) -> Key {
     validator_prefix(validator)
@@ -264,6 +415,8 @@ pub fn is_validator_metadata_key(key: &Key) -> Option<&Address> {
                     | VALIDATOR_DESCRIPTION_KEY
                     | VALIDATOR_WEBSITE_KEY
                     | VALIDATOR_DISCORD_KEY
+                    | VALIDATOR_MONIKER_KEY
+                    | VALIDATOR_SECURITY_CONTACT_KEY
             ) =>
         {
             Some(validator)
@@ -304,11 +457,16 @@ pub fn rewards_counter_prefix() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
-/// Storage key for rewards counter.
-pub fn rewards_counter_key(source: &Address, validator: &Address) -> Key {
+/// Storage key prefix for the rewards counters of a source.
+pub fn rewards_counter_source_prefix(source: &Address) -> Key {
     rewards_counter_prefix()
         .push(&source.to_db_key())
         .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for rewards counter.
+pub fn rewards_counter_key(source: &Address, validator: &Address) -> Key {
+    rewards_counter_source_prefix(source)
         .push(&validator.to_db_key())
         .expect("Cannot obtain a storage key")
 }
@@ -321,6 +479,13 @@ pub fn validator_incoming_redelegations_key(validator: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the set of the delegators of a validator.
+pub fn validator_delegators_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_DELEGATORS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key for a validator's outgoing redelegations, where the prefixed
 /// validator is the source validator.
 pub fn validator_outgoing_redelegations_key(validator: &Address) -> Key {
@@ -484,6 +649,157 @@ pub fn enqueued_slashes_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the bonds whose unbonds are automatically withdrawn.
+pub fn auto_withdrawals_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&AUTO_WITHDRAWALS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the bonds whose rewards are automatically compounded.
+pub fn auto_compound_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&AUTO_COMPOUND_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the auto-compounded bonds of a source? Returns the
+/// source address if it is.
+pub fn is_auto_compound_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(source),
+        ] if addr == &ADDRESS
+            && prefix == AUTO_COMPOUND_KEY
+            && data == lazy_map::DATA_SUBKEY =>
+        {
+            Some(source)
+        }
+        _ => None,
+    }
+}
+
+/// Storage key for the bonds insured against slashing by the insurance pool.
+pub fn insured_bonds_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&INSURED_BONDS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the insured bonds of a source? Returns the source
+/// address if it is.
+pub fn is_insured_bonds_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(data),
+            DbKeySeg::AddressSeg(source),
+        ] if addr == &ADDRESS
+            && prefix == INSURED_BONDS_KEY
+            && data == lazy_map::DATA_SUBKEY =>
+        {
+            Some(source)
+        }
+        _ => None,
+    }
+}
+
+/// Storage key for the balance of the slashing insurance pool.
+pub fn insurance_pool_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&INSURANCE_POOL_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the premiums charged against the rewards of a bond by the
+/// slashing insurance pool, which are yet to be deducted from its claimed
+/// rewards.
+pub fn insurance_premiums_key(source: &Address, validator: &Address) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&INSURANCE_PREMIUMS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&source.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the parameters of the slashing insurance pool, which are
+/// controlled by governance.
+pub fn insurance_params_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&INSURANCE_PARAMS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the parameters of the slashing insurance pool?
+pub fn is_insurance_params_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                DbKeySeg::AddressSeg(addr),
+                DbKeySeg::StringSeg(key)
+            ] if addr == &ADDRESS && key == INSURANCE_PARAMS_KEY)
+}
+
+/// Storage key for the builds attested to by the heartbeats of validators.
+pub fn validator_heartbeats_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_HEARTBEATS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the parameters of the jailing of validators for bridge
+/// liveness failures, which are controlled by governance.
+pub fn bridge_liveness_params_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BRIDGE_LIVENESS_PARAMS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the parameters of the jailing of validators for bridge
+/// liveness failures?
+pub fn is_bridge_liveness_params_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                DbKeySeg::AddressSeg(addr),
+                DbKeySeg::StringSeg(key)
+            ] if addr == &ADDRESS && key == BRIDGE_LIVENESS_PARAMS_KEY)
+}
+
+/// Storage key for the number of heights at which each validator signed a
+/// bridge vote extension, per epoch.
+pub fn bridge_participation_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BRIDGE_PARTICIPATION_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the number of heights at which bridge vote extensions
+/// were signed, per epoch.
+pub fn bridge_blocks_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BRIDGE_BLOCKS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the heights at which the bridge vote extensions included
+/// in the blocks of an epoch were signed, per epoch.
+pub fn bridge_vext_heights_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BRIDGE_VEXT_HEIGHTS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the heights at which each validator signed the bridge
+/// vote extensions included in the blocks of an epoch, per epoch.
+pub fn validator_bridge_vext_heights_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VALIDATOR_BRIDGE_VEXT_HEIGHTS_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key for validator's slashes.
 pub fn validator_slashes_key(validator: &Address) -> Key {
     slashes_prefix()
@@ -522,6 +838,14 @@ pub fn validator_last_slash_key(validator: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for the total amount of tokens slashed from the stake of a
+/// given validator
+pub fn validator_total_slashed_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_TOTAL_SLASHED.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage key prefix for all bonds.
 pub fn bonds_prefix() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -735,6 +1059,36 @@ pub fn is_last_block_proposer_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == LAST_BLOCK_PROPOSER_STORAGE_KEY)
 }
 
+/// Storage prefix for the block proposer addresses of all blocks.
+pub fn block_proposers_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BLOCK_PROPOSERS_STORAGE_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for the block proposer address of the block at the given
+/// height.
+pub fn block_proposer_key(height: BlockHeight) -> Key {
+    block_proposers_prefix()
+        .push(&height)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Is storage key for the block proposer address of some block? Returns
+/// the height of the block, if so.
+pub fn is_block_proposer_key(key: &Key) -> Option<BlockHeight> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(height),
+        ] if addr == &ADDRESS && prefix == BLOCK_PROPOSERS_STORAGE_PREFIX => {
+            BlockHeight::parse(height.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
 /// Storage key for the consensus validator set rewards accumulator.
 pub fn consensus_validator_rewards_accumulator_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -830,6 +1184,20 @@ pub fn validator_discord_key(validator: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Storage key for a validator's moniker
+pub fn validator_moniker_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_MONIKER_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key for a validator's security contact
+pub fn validator_security_contact_key(validator: &Address) -> Key {
+    validator_prefix(validator)
+        .push(&VALIDATOR_SECURITY_CONTACT_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Storage prefix for the liveness data of the cosnensus validator set.
 pub fn liveness_data_prefix() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -10,13 +10,14 @@ use std::ops::{Deref, Range};
 use std::str::FromStr;
 
 use assert_matches::assert_matches;
+use namada_core::ledger::parameters::storage as params_storage;
 use namada_core::ledger::storage::testing::TestWlStorage;
 use namada_core::ledger::storage_api::collections::lazy_map::{
     self, Collectable, NestedMap,
 };
 use namada_core::ledger::storage_api::collections::LazyCollection;
 use namada_core::ledger::storage_api::token::{credit_tokens, read_balance};
-use namada_core::ledger::storage_api::StorageRead;
+use namada_core::ledger::storage_api::{StorageRead, StorageWrite};
 use namada_core::types::address::testing::{
     address_from_simple_seed, arb_established_address, established_address_1,
     established_address_2, established_address_3,
@@ -39,46 +40,64 @@ use proptest::test_runner::Config;
 use test_log::test;
 
 use crate::epoched::DEFAULT_NUM_PAST_EPOCHS;
+use crate::invariants::{check_invariants, InvariantViolation};
 use crate::parameters::testing::arb_pos_params;
-use crate::parameters::{OwnedPosParams, PosParams};
+use crate::parameters::{
+    BridgeLivenessParams, InsuranceParams, OwnedPosParams, PosParams,
+    SlashingParamsChange, ValidationError,
+};
 use crate::rewards::PosRewardsCalculator;
+use crate::storage::{
+    bridge_liveness_params_key, max_validator_stake_share_key,
+    pending_max_validator_slots_key, pending_slashing_params_key,
+};
 use crate::test_utils::test_init_genesis;
 use crate::types::{
     into_tm_voting_power, BondDetails, BondId, BondsAndUnbondsDetails,
-    ConsensusValidator, EagerRedelegatedBondsMap, GenesisValidator, Position,
-    RedelegatedTokens, ReverseOrdTokenAmount, Slash, SlashType, UnbondDetails,
+    ConsensusValidator, DelegationsPage, EagerRedelegatedBondsMap,
+    GenesisValidator, PendingRedelegation, Position, RedelegatedTokens,
+    ReverseOrdTokenAmount, Slash, SlashType, UnbondDetails, ValidatorMetaData,
     ValidatorSetUpdate, ValidatorState, VoteInfo, WeightedValidator,
 };
 use crate::{
-    apply_list_slashes, become_validator, below_capacity_validator_set_handle,
-    bond_handle, bond_tokens, bonds_and_unbonds, change_consensus_key,
-    compute_amount_after_slashing_unbond,
+    apply_list_slashes, apply_pending_max_validator_slots_change,
+    apply_pending_slashing_params_change, become_validator,
+    below_capacity_validator_set_handle, bond_handle, bond_tokens,
+    bonds_and_unbonds, bonds_and_unbonds_in_epochs, change_consensus_key,
+    check_validator_stake_cap, compute_amount_after_slashing_unbond,
     compute_amount_after_slashing_withdraw,
     compute_and_store_total_consensus_stake, compute_bond_at_epoch,
     compute_modified_redelegation, compute_new_redelegated_unbonds,
     compute_slash_bond_at_epoch, compute_slashable_amount,
-    consensus_validator_set_handle, copy_validator_sets_and_positions,
+    consensus_validator_set_diff, consensus_validator_set_handle,
+    copy_validator_sets_and_positions, deactivate_validator,
     delegator_redelegated_bonds_handle, delegator_redelegated_unbonds_handle,
-    find_bonds_to_remove, find_validator_by_raw_hash,
-    fold_and_slash_redelegated_bonds, get_consensus_key_set,
-    get_num_consensus_validators, insert_validator_into_validator_set,
-    is_validator, process_slashes,
+    find_bonds_to_remove, find_delegations_page, find_validator_by_raw_hash,
+    find_validator_delegations_page, fold_and_slash_redelegated_bonds,
+    get_consensus_key_set, get_num_consensus_validators,
+    insert_validator_into_validator_set, is_evidence_expired,
+    is_evidence_outdated, is_validator, jail_for_bridge_liveness,
+    process_slashes, projected_rewards, reactivate_validator,
     read_below_capacity_validator_set_addresses_with_stake,
-    read_below_threshold_validator_set_addresses,
-    read_consensus_validator_set_addresses_with_stake, read_total_stake,
-    read_validator_deltas_value, read_validator_stake, slash,
-    slash_redelegation, slash_validator, slash_validator_redelegation,
-    staking_token_address, total_bonded_handle, total_deltas_handle,
-    total_unbonded_handle, unbond_handle, unbond_tokens, unjail_validator,
-    update_validator_deltas, update_validator_set,
-    validator_consensus_key_handle, validator_incoming_redelegations_handle,
+    read_below_threshold_validator_set_addresses, read_bridge_participation,
+    read_consensus_validator_set_addresses_with_stake,
+    read_cubic_slashing_window_length, read_pos_params, read_total_stake,
+    read_validator_deltas_value, read_validator_metadata, read_validator_stake,
+    record_bridge_participation, slash, slash_redelegation, slash_validator,
+    slash_validator_redelegation, staking_token_address, total_bonded_handle,
+    total_deltas_handle, total_unbonded_handle, unbond_handle, unbond_tokens,
+    unjail_validator, update_validator_deltas, update_validator_set,
+    validator_consensus_key_handle, validator_delegators_handle,
+    validator_deltas_handle, validator_incoming_redelegations_handle,
     validator_outgoing_redelegations_handle, validator_set_positions_handle,
     validator_set_update_tendermint, validator_slashes_handle,
     validator_state_handle, validator_total_redelegated_bonded_handle,
     validator_total_redelegated_unbonded_handle, withdraw_tokens,
-    write_pos_params, write_validator_address_raw_hash, BecomeValidator,
+    write_pos_params, write_validator_address_raw_hash,
+    write_validator_metadata, write_validator_moniker,
+    write_validator_security_contact, BecomeValidator, BondError,
     EagerRedelegatedUnbonds, FoldRedelegatedBondsResult, ModifiedRedelegation,
-    RedelegationError,
+    RedelegationError, MAX_VALIDATOR_METADATA_LEN,
 };
 
 proptest! {
@@ -326,6 +345,70 @@ proptest! {
     }
 }
 
+proptest! {
+    // Generate arb valid input for `test_auto_withdrawals`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_auto_withdrawals(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_auto_withdrawals_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_auto_compounding`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_auto_compounding(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_auto_compounding_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_withdrawable_unbonds`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_withdrawable_unbonds(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_withdrawable_unbonds_aux(genesis_validators)
+    }
+}
+
+proptest! {
+    // Generate arb valid input for `test_slashing_insurance`
+    #![proptest_config(Config {
+        cases: 10,
+        .. Config::default()
+    })]
+    #[test]
+    fn test_slashing_insurance(
+
+    genesis_validators in arb_genesis_validators(1..2, None),
+
+    ) {
+        test_slashing_insurance_aux(genesis_validators)
+    }
+}
+
 fn arb_params_and_genesis_validators(
     num_max_validator_slots: Option<u64>,
     val_size: Range<usize>,
@@ -634,6 +717,65 @@ fn test_bonds_aux(params: OwnedPosParams, validators: Vec<GenesisValidator>) {
         amount_del
     );
 
+    // Check the pages of delegations to the validator and of the delegator
+    let first_page = find_validator_delegations_page(
+        &s,
+        &validator.address,
+        pipeline_epoch,
+        None,
+        1,
+    )
+    .unwrap();
+    assert_eq!(first_page.delegations.len(), 1);
+    assert_eq!(first_page.next.as_ref(), Some(&first_page.delegations[0].0));
+    let second_page = find_validator_delegations_page(
+        &s,
+        &validator.address,
+        pipeline_epoch,
+        first_page.next.as_ref(),
+        1,
+    )
+    .unwrap();
+    assert_eq!(second_page.next, None);
+    let delegations: BTreeMap<_, _> = first_page
+        .delegations
+        .into_iter()
+        .chain(second_page.delegations)
+        .collect();
+    assert_eq!(
+        delegations,
+        BTreeMap::from([
+            (
+                validator.address.clone(),
+                validator.tokens + amount_self_bond
+            ),
+            (delegator.clone(), amount_del),
+        ])
+    );
+    let page = find_validator_delegations_page(
+        &s,
+        &validator.address,
+        pipeline_epoch.prev(),
+        None,
+        10,
+    )
+    .unwrap();
+    assert_eq!(
+        page.delegations,
+        vec![(
+            validator.address.clone(),
+            validator.tokens + amount_self_bond
+        )]
+    );
+    assert_eq!(
+        find_delegations_page(&s, &delegator, pipeline_epoch, None, 10)
+            .unwrap(),
+        DelegationsPage {
+            delegations: vec![(validator.address.clone(), amount_del)],
+            next: None,
+        }
+    );
+
     // Check delegation bonds details after delegation
     let delegation_bond_id = BondId {
         source: delegator.clone(),
@@ -856,6 +998,31 @@ fn test_bonds_aux(params: OwnedPosParams, validators: Vec<GenesisValidator>) {
         bonds_and_unbonds(&s, None, Some(validator.address.clone())).unwrap(),
     );
 
+    // Only the bonds and unbonds taking effect in the given epochs are
+    // collected
+    let all_details = bonds_and_unbonds(&s, None, None).unwrap();
+    let effective_details =
+        bonds_and_unbonds_in_epochs(&s, pipeline_epoch, pipeline_epoch)
+            .unwrap();
+    assert_eq!(
+        effective_details[&self_bond_id].unbonds,
+        all_details[&self_bond_id].unbonds
+    );
+    assert!(
+        effective_details
+            .values()
+            .flat_map(|details| &details.bonds)
+            .all(|bond| bond.start == pipeline_epoch)
+    );
+    let earlier_details =
+        bonds_and_unbonds_in_epochs(&s, start_epoch, pipeline_epoch.prev())
+            .unwrap();
+    assert!(
+        earlier_details
+            .values()
+            .all(|details| details.unbonds.is_empty())
+    );
+
     // Unbond delegation
     let amount_undel = token::Amount::from_uint(1_000_000, 0).unwrap();
     unbond_tokens(
@@ -1128,6 +1295,9 @@ fn test_become_validator_aux(
     let pipeline_epoch = current_epoch + params.pipeline_len;
     let delta = bond_handle.get_delta_val(&s, pipeline_epoch).unwrap();
     assert_eq!(delta, Some(amount));
+    assert!(validator_delegators_handle(&new_validator)
+        .contains(&s, &new_validator)
+        .unwrap());
 
     // Check the validator in the validator set -
     // If the consensus validator slots are full and all the genesis validators
@@ -1165,6 +1335,21 @@ fn test_become_validator_aux(
                 address == new_validator && bonded_stake == amount
             }
         ));
+
+        // The new validator joins the consensus set at the pipeline epoch
+        let diff = consensus_validator_set_diff(
+            &s,
+            pipeline_epoch.prev(),
+            pipeline_epoch,
+        )
+        .unwrap();
+        assert_eq!(diff.joined.get(&new_validator), Some(&amount));
+        assert!(!diff.changed.contains_key(&new_validator));
+        assert!(
+            consensus_validator_set_diff(&s, pipeline_epoch, pipeline_epoch)
+                .unwrap()
+                .is_empty()
+        );
     }
 
     // Advance to epoch 3
@@ -1173,6 +1358,10 @@ fn test_become_validator_aux(
     // Unbond the self-bond
     unbond_tokens(&mut s, None, &new_validator, amount, current_epoch, false)
         .unwrap();
+    // The validator is no longer its own delegator
+    assert!(validator_delegators_handle(&new_validator)
+        .is_empty(&s)
+        .unwrap());
 
     let withdrawable_offset = params.unbonding_len + params.pipeline_len;
 
@@ -1342,462 +1531,1056 @@ fn test_slashes_with_unbonding_aux(
     // assert_eq!(expected_slash_pool, slash_pool_balance);
 }
 
+/// Test that the metadata of a validator is written and read back in full,
+/// and that fields exceeding the maximum length are rejected.
 #[test]
-fn test_validator_raw_hash() {
+fn test_validator_metadata() {
     let mut storage = TestWlStorage::default();
-    let address = address::testing::established_address_1();
-    let consensus_sk = key::testing::keypair_1();
-    let consensus_pk = consensus_sk.to_public();
-    let expected_raw_hash = key::tm_consensus_key_raw_hash(&consensus_pk);
+    let validator = established_address_1();
+    assert_eq!(read_validator_metadata(&storage, &validator).unwrap(), None);
+
+    let metadata = ValidatorMetaData {
+        email: "validator@namada.net".to_string(),
+        description: Some("A validator".to_string()),
+        website: None,
+        discord_handle: None,
+        moniker: Some("validator-1".to_string()),
+        security_contact: Some("security@namada.net".to_string()),
+    };
+    write_validator_metadata(&mut storage, &validator, &metadata).unwrap();
+    assert_eq!(
+        read_validator_metadata(&storage, &validator).unwrap(),
+        Some(metadata.clone())
+    );
 
+    let too_long = "a".repeat(MAX_VALIDATOR_METADATA_LEN + 1);
     assert!(
-        find_validator_by_raw_hash(&storage, &expected_raw_hash)
+        write_validator_moniker(&mut storage, &validator, &too_long).is_err()
+    );
+    assert!(
+        write_validator_security_contact(&mut storage, &validator, &too_long)
+            .is_err()
+    );
+    assert_eq!(
+        read_validator_metadata(&storage, &validator).unwrap(),
+        Some(metadata)
+    );
+}
+
+/// Test that a change of the slashing parameters passed by governance is
+/// applied, and that evidence expiry uses the cubic slashing window length
+/// in effect at the evidence epoch.
+#[test]
+fn test_slashing_params_change() {
+    let mut storage = TestWlStorage::default();
+    let owned = OwnedPosParams {
+        cubic_slashing_window_length: 1,
+        ..Default::default()
+    };
+    let params =
+        test_init_genesis(&mut storage, owned, std::iter::empty(), Epoch(0))
+            .unwrap();
+    assert_eq!(
+        apply_pending_slashing_params_change(&mut storage, Epoch(1)).unwrap(),
+        None
+    );
+
+    // An invalid change is dropped
+    let invalid = SlashingParamsChange {
+        duplicate_vote_min_slash_rate: Some(Dec::two()),
+        ..Default::default()
+    };
+    assert_eq!(invalid.validate(&params.owned).len(), 1);
+    storage
+        .write(&pending_slashing_params_key(), &invalid)
+        .unwrap();
+    assert_eq!(
+        apply_pending_slashing_params_change(&mut storage, Epoch(1)).unwrap(),
+        None
+    );
+    assert_eq!(
+        read_pos_params(&storage)
             .unwrap()
-            .is_none()
+            .duplicate_vote_min_slash_rate,
+        params.duplicate_vote_min_slash_rate
     );
-    write_validator_address_raw_hash(&mut storage, &address, &consensus_pk)
+
+    let change = SlashingParamsChange {
+        duplicate_vote_min_slash_rate: Some(Dec::new(2, 2).unwrap()),
+        light_client_attack_min_slash_rate: None,
+        cubic_slashing_window_length: Some(3),
+    };
+    assert!(change.validate(&params.owned).is_empty());
+    storage
+        .write(&pending_slashing_params_key(), &change)
         .unwrap();
-    let found =
-        find_validator_by_raw_hash(&storage, &expected_raw_hash).unwrap();
-    assert_eq!(found, Some(address));
+    assert_eq!(
+        apply_pending_slashing_params_change(&mut storage, Epoch(5)).unwrap(),
+        Some(change)
+    );
+    assert!(!storage.has_key(&pending_slashing_params_key()).unwrap());
+
+    let new_params = read_pos_params(&storage).unwrap();
+    assert_eq!(
+        new_params.duplicate_vote_min_slash_rate,
+        Dec::new(2, 2).unwrap()
+    );
+    assert_eq!(
+        new_params.light_client_attack_min_slash_rate,
+        params.light_client_attack_min_slash_rate
+    );
+    assert_eq!(new_params.cubic_slashing_window_length, 3);
+    for (epoch, window_length) in [(0, 1), (4, 1), (5, 3), (9, 3)] {
+        assert_eq!(
+            read_cubic_slashing_window_length(
+                &storage,
+                &new_params,
+                Epoch(epoch)
+            )
+            .unwrap(),
+            window_length
+        );
+    }
+
+    // Evidence from before the change expires earlier than evidence from
+    // after it
+    let offset = new_params.slash_processing_epoch_offset();
+    let expiry = |evidence_epoch: Epoch| {
+        (0..)
+            .map(Epoch)
+            .find(|current_epoch| {
+                is_evidence_outdated(
+                    &storage,
+                    &new_params,
+                    evidence_epoch,
+                    BlockHeight(0),
+                    *current_epoch,
+                    BlockHeight(0),
+                )
+                .unwrap()
+            })
+            .unwrap()
+    };
+    assert_eq!(expiry(Epoch(4)), Epoch(4 + offset - 1));
+    assert_eq!(expiry(Epoch(5)), Epoch(5 + offset - 3));
+
+    // The cubic slashing window length may not decrease
+    let decrease = SlashingParamsChange {
+        cubic_slashing_window_length: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(decrease.validate(&new_params.owned).len(), 1);
+    storage
+        .write(&pending_slashing_params_key(), &decrease)
+        .unwrap();
+    assert_eq!(
+        apply_pending_slashing_params_change(&mut storage, Epoch(6)).unwrap(),
+        None
+    );
+    assert_eq!(
+        read_pos_params(&storage)
+            .unwrap()
+            .cubic_slashing_window_length,
+        3
+    );
 }
 
 #[test]
-fn test_validator_sets() {
+fn test_max_evidence_age() {
+    let mut storage = TestWlStorage::default();
+    let owned = OwnedPosParams {
+        unbonding_len: 3,
+        cubic_slashing_window_length: 1,
+        max_evidence_age_epochs: 3,
+        max_evidence_age_blocks: 100,
+        ..Default::default()
+    };
+    assert!(owned.validate().is_empty());
+    // The evidence must not expire before the stake can no longer be slashed
+    let too_short = OwnedPosParams {
+        max_evidence_age_epochs: 2,
+        ..owned.clone()
+    };
+    assert!(matches!(
+        too_short.validate().as_slice(),
+        [ValidationError::MaxEvidenceAgeTooShort(2, 3)]
+    ));
+    let params =
+        test_init_genesis(&mut storage, owned, std::iter::empty(), Epoch(0))
+            .unwrap();
+    let outdated = |evidence_epoch, evidence_height, epoch, height| {
+        is_evidence_outdated(
+            &storage,
+            &params,
+            Epoch(evidence_epoch),
+            BlockHeight(evidence_height),
+            Epoch(epoch),
+            BlockHeight(height),
+        )
+        .unwrap()
+    };
+
+    // Evidence within both limits is still processed
+    assert!(!outdated(2, 20, 5, 120));
+    assert!(!is_evidence_expired(
+        &params,
+        Epoch(2),
+        BlockHeight(20),
+        Epoch(5),
+        BlockHeight(120)
+    ));
+    // Evidence that is too many blocks old, but recent enough in epochs, is
+    // still processed
+    assert!(!outdated(2, 20, 5, 121));
+    assert!(!is_evidence_expired(
+        &params,
+        Epoch(2),
+        BlockHeight(20),
+        Epoch(5),
+        BlockHeight(121)
+    ));
+    // Evidence that is too many epochs old, but recent enough in blocks,
+    // does not expire, although it is outdated by the slash processing offset
+    assert!(!is_evidence_expired(
+        &params,
+        Epoch(2),
+        BlockHeight(20),
+        Epoch(6),
+        BlockHeight(120)
+    ));
+    assert!(outdated(2, 20, 6, 120));
+    // Evidence older than both limits is discarded
+    assert!(outdated(2, 20, 6, 121));
+    assert!(is_evidence_expired(
+        &params,
+        Epoch(2),
+        BlockHeight(20),
+        Epoch(6),
+        BlockHeight(121)
+    ));
+}
+
+/// Test that a change of the maximum number of consensus validators passed
+/// by governance resizes the validator sets at the pipeline epoch, and that
+/// the validators leaving or entering the consensus set are communicated to
+/// Tendermint.
+#[test]
+fn test_max_validator_slots_change() {
     let mut s = TestWlStorage::default();
-    // Only 3 consensus validator slots
     let params = OwnedPosParams {
         max_validator_slots: 3,
         ..Default::default()
     };
     let addr_seed = "seed";
     let mut address_gen = EstablishedAddressGen::new(addr_seed);
-    let mut sk_seed = 0;
-    let mut gen_validator = || {
-        let res = (
-            address_gen.generate_address(addr_seed),
-            key::testing::common_sk_from_simple_seed(sk_seed).to_public(),
-        );
-        // bump the sk seed
-        sk_seed += 1;
-        res
-    };
-
-    // Create genesis validators
-    let ((val1, pk1), stake1) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val2, pk2), stake2) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val3, pk3), stake3) =
-        (gen_validator(), token::Amount::native_whole(10));
-    let ((val4, pk4), stake4) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val5, pk5), stake5) =
-        (gen_validator(), token::Amount::native_whole(100));
-    let ((val6, pk6), stake6) =
-        (gen_validator(), token::Amount::native_whole(1));
-    let ((val7, pk7), stake7) =
-        (gen_validator(), token::Amount::native_whole(1));
-    println!("\nval1: {val1}, {pk1}, {}", stake1.to_string_native());
-    println!("val2: {val2}, {pk2}, {}", stake2.to_string_native());
-    println!("val3: {val3}, {pk3}, {}", stake3.to_string_native());
-    println!("val4: {val4}, {pk4}, {}", stake4.to_string_native());
-    println!("val5: {val5}, {pk5}, {}", stake5.to_string_native());
-    println!("val6: {val6}, {pk6}, {}", stake6.to_string_native());
-    println!("val7: {val7}, {pk7}, {}", stake7.to_string_native());
-
-    let start_epoch = Epoch::default();
-    let epoch = start_epoch;
-
-    let protocol_sk_1 = common_sk_from_simple_seed(0);
-    let protocol_sk_2 = common_sk_from_simple_seed(1);
-
+    let validators: Vec<_> = (1..=4_u64)
+        .map(|stake| GenesisValidator {
+            address: address_gen.generate_address(addr_seed),
+            tokens: token::Amount::native_whole(stake),
+            consensus_key: common_sk_from_simple_seed(stake).to_public(),
+            protocol_key: common_sk_from_simple_seed(stake + 10).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(5, 2).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 2).expect("Test failed"),
+            metadata: Default::default(),
+        })
+        .collect();
     let params = test_init_genesis(
         &mut s,
         params,
-        [
-            GenesisValidator {
-                address: val1.clone(),
-                tokens: stake1,
-                consensus_key: pk1.clone(),
-                protocol_key: protocol_sk_1.to_public(),
-                eth_hot_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                eth_cold_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
-                max_commission_rate_change: Dec::new(1, 1)
-                    .expect("Dec creation failed"),
-                metadata: Default::default(),
-            },
-            GenesisValidator {
-                address: val2.clone(),
-                tokens: stake2,
-                consensus_key: pk2.clone(),
-                protocol_key: protocol_sk_2.to_public(),
-                eth_hot_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                eth_cold_key: key::common::PublicKey::Secp256k1(
-                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
-                        .ref_to(),
-                ),
-                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
-                max_commission_rate_change: Dec::new(1, 1)
-                    .expect("Dec creation failed"),
-                metadata: Default::default(),
-            },
-        ]
-        .into_iter(),
-        epoch,
+        validators.clone().into_iter(),
+        Epoch::default(),
     )
     .unwrap();
-
-    // A helper to insert a non-genesis validator
-    let insert_validator = |s: &mut TestWlStorage,
-                            addr,
-                            pk: &PublicKey,
-                            stake: token::Amount,
-                            epoch: Epoch| {
-        insert_validator_into_validator_set(
-            s,
-            &params,
-            addr,
-            stake,
-            epoch,
-            params.pipeline_len,
-        )
-        .unwrap();
-
-        update_validator_deltas(s, &params, addr, stake.change(), epoch, None)
-            .unwrap();
-
-        // Set their consensus key (needed for
-        // `validator_set_update_tendermint` fn)
-        validator_consensus_key_handle(addr)
-            .set(s, pk.clone(), epoch, params.pipeline_len)
-            .unwrap();
+    let state = |s: &TestWlStorage, validator: &GenesisValidator, epoch| {
+        validator_state_handle(&validator.address)
+            .get(s, epoch, &params)
+            .unwrap()
     };
 
-    // Advance to EPOCH 1
-    //
-    // We cannot call `get_tendermint_set_updates` for the genesis state as
-    // `validator_set_update_tendermint` is only called 2 blocks before the
-    // start of an epoch and so we need to give it a predecessor epoch (see
-    // `get_tendermint_set_updates`), which we cannot have on the first
-    // epoch. In any way, the initial validator set is given to Tendermint
-    // from InitChain, so `validator_set_update_tendermint` is
-    // not being used for it.
+    // An invalid change is dropped
     let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
-
-    // Insert another validator with the greater stake 10 NAM
-    insert_validator(&mut s, &val3, &pk3, stake3, epoch);
-    // Insert validator with stake 1 NAM
-    insert_validator(&mut s, &val4, &pk4, stake4, epoch);
-
-    // Validator `val3` and `val4` will be added at pipeline offset (2) - epoch
-    // 3
-    let val3_and_4_epoch = pipeline_epoch;
-
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
-
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val1 && stake == &stake1 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
+    s.write(&pending_max_validator_slots_key(), 0_u64).unwrap();
+    assert_eq!(
+        apply_pending_max_validator_slots_change(&mut s, epoch).unwrap(),
+        None
+    );
+    assert_eq!(read_pos_params(&s).unwrap().max_validator_slots, 3);
 
-    // Check tendermint validator set updates - there should be none
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
+    // Shrink the consensus set, demoting the lowest consensus validator at
+    // the pipeline epoch
+    s.write(&pending_max_validator_slots_key(), 2_u64).unwrap();
+    assert_eq!(
+        apply_pending_max_validator_slots_change(&mut s, epoch).unwrap(),
+        Some(2)
+    );
+    assert!(!s.has_key(&pending_max_validator_slots_key()).unwrap());
+    let params = read_pos_params(&s).unwrap();
+    assert_eq!(params.max_validator_slots, 2);
+    let pipeline_epoch = epoch + params.pipeline_len;
+    assert_eq!(
+        get_num_consensus_validators(&s, pipeline_epoch.prev()).unwrap(),
+        3
+    );
+    assert_eq!(get_num_consensus_validators(&s, pipeline_epoch).unwrap(), 2);
+    assert_eq!(
+        state(&s, &validators[1], pipeline_epoch.prev()),
+        Some(ValidatorState::Consensus)
+    );
+    assert_eq!(
+        state(&s, &validators[1], pipeline_epoch),
+        Some(ValidatorState::BelowCapacity)
+    );
+    let tm_updates = get_tendermint_set_updates(&s, &params, pipeline_epoch);
+    assert_eq!(
+        tm_updates,
+        vec![ValidatorSetUpdate::Deactivated(
+            validators[1].consensus_key.clone()
+        )]
+    );
 
-    // Advance to EPOCH 2
+    // Grow the consensus set, promoting all the below-capacity validators at
+    // the pipeline epoch
     let epoch = advance_epoch(&mut s, &params);
+    s.write(&pending_max_validator_slots_key(), 5_u64).unwrap();
+    assert_eq!(
+        apply_pending_max_validator_slots_change(&mut s, epoch).unwrap(),
+        Some(5)
+    );
+    let params = read_pos_params(&s).unwrap();
     let pipeline_epoch = epoch + params.pipeline_len;
+    assert_eq!(get_num_consensus_validators(&s, pipeline_epoch).unwrap(), 4);
+    for validator in &validators[..2] {
+        assert_eq!(
+            state(&s, validator, pipeline_epoch),
+            Some(ValidatorState::Consensus)
+        );
+    }
+    let tm_updates = get_tendermint_set_updates(&s, &params, pipeline_epoch);
+    assert_eq!(tm_updates.len(), 2);
+    for validator in &validators[..2] {
+        assert!(tm_updates.contains(&ValidatorSetUpdate::Consensus(
+            ConsensusValidator {
+                consensus_key: validator.consensus_key.clone(),
+                bonded_stake: validator.tokens,
+            }
+        )));
+    }
+}
 
-    // Insert another validator with a greater stake still 1000 NAM. It should
-    // replace 2nd consensus validator with stake 1, which should become
-    // below-capacity
-    insert_validator(&mut s, &val5, &pk5, stake5, epoch);
-    // Validator `val5` will be added at pipeline offset (2) - epoch 4
-    let val5_epoch = pipeline_epoch;
-
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
-
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val1 && stake == &stake1 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
-
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
+/// Test that a validator may leave the consensus set by deactivating and
+/// rejoin it by reactivating, with the changes communicated to Tendermint at
+/// the pipeline epoch and its bonds kept intact.
+#[test]
+fn test_deactivate_and_reactivate_validator() {
+    let mut s = TestWlStorage::default();
+    let params = OwnedPosParams {
+        max_validator_slots: 2,
+        ..Default::default()
+    };
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let validators: Vec<_> = (1..=3_u64)
+        .map(|stake| GenesisValidator {
+            address: address_gen.generate_address(addr_seed),
+            tokens: token::Amount::native_whole(stake),
+            consensus_key: common_sk_from_simple_seed(stake).to_public(),
+            protocol_key: common_sk_from_simple_seed(stake + 10).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(5, 2).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 2).expect("Test failed"),
+            metadata: Default::default(),
+        })
         .collect();
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        Epoch::default(),
+    )
+    .unwrap();
+    let state = |s: &TestWlStorage, validator: &GenesisValidator, epoch| {
+        validator_state_handle(&validator.address)
+            .get(s, epoch, &params)
+            .unwrap()
+    };
+    let (lowest, highest) = (&validators[0], &validators[2]);
 
-    assert_eq!(below_capacity_vals.len(), 2);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-
-    // Advance to EPOCH 3
+    // Deactivate the validator with the highest stake, promoting the
+    // below-capacity validator at the pipeline epoch
     let epoch = advance_epoch(&mut s, &params);
+    deactivate_validator(&mut s, &highest.address, epoch).unwrap();
     let pipeline_epoch = epoch + params.pipeline_len;
-
-    // Check tendermint validator set updates
     assert_eq!(
-        val3_and_4_epoch, epoch,
-        "val3 and val4 are in the validator sets now"
+        state(&s, highest, pipeline_epoch.prev()),
+        Some(ValidatorState::Consensus)
     );
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    // `val4` is newly added below-capacity, must be skipped in updated in TM
-    assert_eq!(tm_updates.len(), 1);
     assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk3,
-            bonded_stake: stake3,
-        })
+        state(&s, highest, pipeline_epoch),
+        Some(ValidatorState::Inactive)
+    );
+    assert_eq!(
+        state(&s, lowest, pipeline_epoch),
+        Some(ValidatorState::Consensus)
     );
+    let tm_updates = get_tendermint_set_updates(&s, &params, pipeline_epoch);
+    assert_eq!(tm_updates.len(), 2);
+    assert!(tm_updates.contains(&ValidatorSetUpdate::Deactivated(
+        highest.consensus_key.clone()
+    )));
+    assert!(tm_updates.contains(&ValidatorSetUpdate::Consensus(
+        ConsensusValidator {
+            consensus_key: lowest.consensus_key.clone(),
+            bonded_stake: lowest.tokens,
+        }
+    )));
 
-    // Insert another validator with a stake 1 NAM. It should be added to the
-    // below-capacity set
-    insert_validator(&mut s, &val6, &pk6, stake6, epoch);
-    // Validator `val6` will be added at pipeline offset (2) - epoch 5
-    let val6_epoch = pipeline_epoch;
+    // The validator cannot be deactivated twice, nor reactivated before its
+    // deactivation takes effect
+    assert!(deactivate_validator(&mut s, &highest.address, epoch).is_err());
+    assert!(reactivate_validator(&mut s, &highest.address, epoch).is_err());
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    // The bonds of the inactive validator are kept intact
+    for _ in 0..params.pipeline_len {
+        advance_epoch(&mut s, &params);
+    }
+    let epoch = s.storage.block.epoch;
+    assert_eq!(
+        bond_handle(&highest.address, &highest.address)
+            .get_sum(&s, epoch, &params)
+            .unwrap(),
+        Some(highest.tokens)
+    );
+    assert_eq!(
+        read_validator_stake(&s, &params, &highest.address, epoch).unwrap(),
+        highest.tokens
+    );
 
-    assert_eq!(below_capacity_vals.len(), 3);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
-    ));
+    // Reactivate the validator, which takes back its consensus slot at the
+    // pipeline epoch
+    reactivate_validator(&mut s, &highest.address, epoch).unwrap();
+    let pipeline_epoch = epoch + params.pipeline_len;
+    assert_eq!(
+        state(&s, highest, pipeline_epoch),
+        Some(ValidatorState::Consensus)
+    );
+    assert_eq!(
+        state(&s, lowest, pipeline_epoch),
+        Some(ValidatorState::BelowCapacity)
+    );
+    let tm_updates = get_tendermint_set_updates(&s, &params, pipeline_epoch);
+    assert_eq!(tm_updates.len(), 2);
+    assert!(tm_updates.contains(&ValidatorSetUpdate::Consensus(
+        ConsensusValidator {
+            consensus_key: highest.consensus_key.clone(),
+            bonded_stake: highest.tokens,
+        }
+    )));
+    assert!(tm_updates.contains(&ValidatorSetUpdate::Deactivated(
+        lowest.consensus_key.clone()
+    )));
+}
 
-    // Advance to EPOCH 4
+/// Test that bonds raising the stake of a validator above the maximum share
+/// of the total stake set by governance are rejected.
+#[test]
+fn test_validator_stake_cap() {
+    let mut s = TestWlStorage::default();
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let validators: Vec<_> = (1..=3_u64)
+        .map(|stake| GenesisValidator {
+            address: address_gen.generate_address(addr_seed),
+            tokens: token::Amount::native_whole(stake),
+            consensus_key: common_sk_from_simple_seed(stake).to_public(),
+            protocol_key: common_sk_from_simple_seed(stake + 10).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(5, 2).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 2).expect("Test failed"),
+            metadata: Default::default(),
+        })
+        .collect();
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.clone().into_iter(),
+        Epoch::default(),
+    )
+    .unwrap();
     let epoch = advance_epoch(&mut s, &params);
     let pipeline_epoch = epoch + params.pipeline_len;
 
-    // Check tendermint validator set updates
-    assert_eq!(val5_epoch, epoch, "val5 is in the validator sets now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert_eq!(tm_updates.len(), 2);
-    assert_eq!(
-        tm_updates[0],
-        ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk5,
-            bonded_stake: stake5,
-        })
+    let delegator = address::testing::gen_implicit_address();
+    let staking_token = staking_token_address(&s);
+    let amount = token::Amount::native_whole(1);
+    credit_tokens(&mut s, &staking_token, &delegator, amount * 2).unwrap();
+    let (lowest, highest) = (&validators[0], &validators[2]);
+
+    // Without a cap, any bond is within it
+    assert!(
+        check_validator_stake_cap(
+            &s,
+            &params,
+            &highest.address,
+            amount * 100,
+            pipeline_epoch
+        )
+        .unwrap()
+        .is_ok()
     );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk2));
 
-    // Unbond some stake from val1, it should be be swapped with the greatest
-    // below-capacity validator val2 into the below-capacity set. The stake of
-    // val1 will go below 1 NAM, which is the validator_stake_threshold, so it
-    // will enter the below-threshold validator set.
-    let unbond = token::Amount::from_uint(500_000, 0).unwrap();
-    let stake1 = stake1 - unbond;
-    println!("val1 {val1} new stake {}", stake1.to_string_native());
-    // Because `update_validator_set` and `update_validator_deltas` are
-    // effective from pipeline offset, we use pipeline epoch for the rest of the
-    // checks
-    update_validator_set(&mut s, &params, &val1, -unbond.change(), epoch, None)
+    // Cap the stake of a validator at half of the total stake. The validator
+    // with 3 out of the 6 tokens cannot receive another one.
+    s.write(&max_validator_stake_share_key(), Dec::new(5, 1).unwrap())
         .unwrap();
-    update_validator_deltas(
+    assert_matches!(
+        check_validator_stake_cap(
+            &s,
+            &params,
+            &highest.address,
+            amount,
+            pipeline_epoch
+        )
+        .unwrap(),
+        Err(BondError::StakeCapExceeded(..))
+    );
+    let result = bond_tokens(
         &mut s,
-        &params,
-        &val1,
-        -unbond.change(),
+        Some(&delegator),
+        &highest.address,
+        amount,
+        epoch,
+        None,
+    );
+    assert!(result.is_err());
+
+    // Bonds within the cap are accepted
+    bond_tokens(
+        &mut s,
+        Some(&delegator),
+        &lowest.address,
+        amount,
         epoch,
         None,
     )
     .unwrap();
-    // Epoch 6
-    let val1_unbond_epoch = pipeline_epoch;
-
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
-        .collect();
+    assert_eq!(
+        read_validator_stake(&s, &params, &lowest.address, pipeline_epoch)
+            .unwrap(),
+        lowest.tokens + amount
+    );
 
-    assert_eq!(consensus_vals.len(), 3);
-    assert!(matches!(
-        &consensus_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
-    ));
-    assert!(matches!(
-        &consensus_vals[2],
-        (lazy_map::NestedSubKey::Data {
-                key: stake,
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
-    ));
+    // Redelegations are subject to the cap too
+    let mut epoch = epoch;
+    for _ in 0..params.pipeline_len {
+        epoch = advance_epoch(&mut s, &params);
+    }
+    let result = super::redelegate_tokens(
+        &mut s,
+        &delegator,
+        &lowest.address,
+        &highest.address,
+        epoch,
+        amount,
+    );
+    assert!(result.is_err());
+}
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
+/// Test that the consensus validators who rarely signed the bridge vote
+/// extensions in an epoch are jailed, only once governance has set the bridge
+/// liveness parameters, and never so many of them that the remaining ones
+/// cannot reach a quorum.
+#[test]
+fn test_jail_for_bridge_liveness() {
+    let mut s = TestWlStorage::default();
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let validators: Vec<_> = (1..=3_u64)
+        .map(|stake| GenesisValidator {
+            address: address_gen.generate_address(addr_seed),
+            tokens: token::Amount::native_whole(stake),
+            consensus_key: common_sk_from_simple_seed(stake).to_public(),
+            protocol_key: common_sk_from_simple_seed(stake + 10).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(5, 2).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 2).expect("Test failed"),
+            metadata: Default::default(),
+        })
         .collect();
+    let params = test_init_genesis(
+        &mut s,
+        OwnedPosParams::default(),
+        validators.clone().into_iter(),
+        Epoch::default(),
+    )
+    .unwrap();
+    let addresses: Vec<_> =
+        validators.iter().map(|v| v.address.clone()).collect();
 
-    assert_eq!(below_capacity_vals.len(), 2);
-    assert!(matches!(
-        &below_capacity_vals[0],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
-    ));
-    assert!(matches!(
-        &below_capacity_vals[1],
-        (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
-                nested_sub_key: lazy_map::SubKey::Data(position),
-        }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
-    ));
-
-    let below_threshold_vals =
-        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
-            .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
-
-    assert_eq!(below_threshold_vals.len(), 1);
-    assert_eq!(&below_threshold_vals[0], &val1);
+    // In epoch 1, the last validator signs the bridge vote extensions at
+    // every height, the second one at half of them and the first one never
+    let epoch = advance_epoch(&mut s, &params);
+    let record_blocks = |s: &mut TestWlStorage, epoch: Epoch| {
+        for signers in [
+            BTreeSet::from([
+                (addresses[2].clone(), BlockHeight(1)),
+                (addresses[1].clone(), BlockHeight(1)),
+            ]),
+            BTreeSet::from([(addresses[2].clone(), BlockHeight(2))]),
+            BTreeSet::new(),
+            // A vote extension left out by the proposer of its block is
+            // only counted once when included by a later one
+            BTreeSet::from([(addresses[2].clone(), BlockHeight(2))]),
+        ] {
+            record_bridge_participation(s, epoch, &signers).unwrap();
+        }
+    };
+    record_blocks(&mut s, epoch);
+    let participation = read_bridge_participation(&s, epoch).unwrap();
+    // The block without bridge vote extensions is not counted
+    assert_eq!(participation.bridge_blocks, 2);
+    assert_eq!(
+        participation.participation_rate(&addresses[2]),
+        Some(Dec::one())
+    );
+    assert_eq!(
+        participation.participation_rate(&addresses[1]),
+        Some(Dec::new(5, 1).unwrap())
+    );
+    assert_eq!(
+        participation.participation_rate(&addresses[0]),
+        Some(Dec::zero())
+    );
 
-    // Advance to EPOCH 5
+    // Without the bridge liveness parameters, nobody is jailed
     let epoch = advance_epoch(&mut s, &params);
-    let pipeline_epoch = epoch + params.pipeline_len;
+    let jailed =
+        jail_for_bridge_liveness(&mut s, &params, epoch, epoch.next()).unwrap();
+    assert!(jailed.is_empty());
+
+    // Require a participation of at least a half
+    s.write(
+        &bridge_liveness_params_key(),
+        BridgeLivenessParams {
+            min_participation: Dec::new(5, 1).unwrap(),
+            min_bridge_blocks: 2,
+        },
+    )
+    .unwrap();
+    record_blocks(&mut s, epoch);
+    let epoch = advance_epoch(&mut s, &params);
+    let jailed =
+        jail_for_bridge_liveness(&mut s, &params, epoch, epoch.next()).unwrap();
+    assert_eq!(
+        jailed,
+        BTreeMap::from([(addresses[0].clone(), Dec::zero())])
+    );
+    for (address, expected_state) in addresses.iter().zip([
+        ValidatorState::Jailed,
+        ValidatorState::Consensus,
+        ValidatorState::Consensus,
+    ]) {
+        let state = validator_state_handle(address)
+            .get(&s, epoch.next(), &params)
+            .unwrap();
+        assert_eq!(state, Some(expected_state));
+    }
 
-    // Check tendermint validator set updates
-    assert_eq!(val6_epoch, epoch, "val6 is in the validator sets now");
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
+    // The participation data older than the last epoch was pruned
+    assert_eq!(
+        read_bridge_participation(&s, Epoch(1)).unwrap(),
+        Default::default()
+    );
+    assert_eq!(
+        read_bridge_participation(&s, Epoch(2))
+            .unwrap()
+            .bridge_blocks,
+        2
+    );
 
-    // Insert another validator with stake 1 - it should be added to below
-    // capacity set
-    insert_validator(&mut s, &val7, &pk7, stake7, epoch);
-    // Epoch 7
-    let val7_epoch = pipeline_epoch;
+    // Too few blocks with bridge vote extensions to check the participation
+    record_bridge_participation(
+        &mut s,
+        epoch,
+        &BTreeSet::from([(addresses[2].clone(), BlockHeight(1))]),
+    )
+    .unwrap();
+    let epoch = advance_epoch(&mut s, &params);
+    let jailed =
+        jail_for_bridge_liveness(&mut s, &params, epoch, epoch.next()).unwrap();
+    assert!(jailed.is_empty());
+
+    // The second validator misses every height, but jailing it would leave
+    // too little stake to reach a quorum
+    for height in 1..=2 {
+        record_bridge_participation(
+            &mut s,
+            epoch,
+            &BTreeSet::from([(addresses[2].clone(), BlockHeight(height))]),
+        )
+        .unwrap();
+    }
+    let epoch = advance_epoch(&mut s, &params);
+    let jailed =
+        jail_for_bridge_liveness(&mut s, &params, epoch, epoch.next()).unwrap();
+    assert!(jailed.is_empty());
+}
 
-    let consensus_vals: Vec<_> = consensus_validator_set_handle()
-        .at(&pipeline_epoch)
-        .iter(&s)
-        .unwrap()
-        .map(Result::unwrap)
+/// Test that the PoS invariants hold across bonding, and that corruptions
+/// of the bonds and the validator deltas are reported.
+#[test]
+fn test_check_invariants() {
+    let mut s = TestWlStorage::default();
+    let params = OwnedPosParams {
+        max_validator_slots: 3,
+        ..Default::default()
+    };
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let validators: Vec<_> = (1..=4_u64)
+        .map(|stake| GenesisValidator {
+            address: address_gen.generate_address(addr_seed),
+            tokens: token::Amount::native_whole(stake),
+            consensus_key: common_sk_from_simple_seed(stake).to_public(),
+            protocol_key: common_sk_from_simple_seed(stake + 10).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(5, 2).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 2).expect("Test failed"),
+            metadata: Default::default(),
+        })
+        .collect();
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        validators.clone().into_iter(),
+        Epoch::default(),
+    )
+    .unwrap();
+    let epoch = advance_epoch(&mut s, &params);
+    assert_eq!(check_invariants(&s, epoch).unwrap(), vec![]);
+
+    // Bonding keeps the invariants
+    let delegator = address::testing::gen_implicit_address();
+    let staking_token = staking_token_address(&s);
+    let amount = token::Amount::native_whole(10);
+    credit_tokens(&mut s, &staking_token, &delegator, amount).unwrap();
+    bond_tokens(
+        &mut s,
+        Some(&delegator),
+        &validators[0].address,
+        amount,
+        epoch,
+        None,
+    )
+    .unwrap();
+    assert_eq!(check_invariants(&s, epoch).unwrap(), vec![]);
+
+    // A bond without a matching change of the validator's total bonded
+    bond_handle(&delegator, &validators[1].address)
+        .add(&mut s, amount, epoch, params.pipeline_len)
+        .unwrap();
+    let violations = check_invariants(&s, epoch).unwrap();
+    assert_eq!(violations.len(), 1);
+    assert_matches!(
+        &violations[0],
+        InvariantViolation::TotalBondedMismatch { validator, .. }
+            if validator == &validators[1].address
+    );
+
+    // A consensus validator whose deltas no longer match its stake in the
+    // validator set, and a validator whose deltas are negative
+    validator_deltas_handle(&validators[3].address)
+        .add(&mut s, -token::Amount::native_whole(1).change(), epoch, 0)
+        .unwrap();
+    validator_deltas_handle(&validators[2].address)
+        .add(&mut s, -token::Amount::native_whole(4).change(), epoch, 0)
+        .unwrap();
+    let violations = check_invariants(&s, epoch).unwrap();
+    assert!(violations.iter().any(|violation| matches!(
+        violation,
+        InvariantViolation::ValidatorSetStakeMismatch { validator, set, .. }
+            if validator == &validators[3].address && *set == "consensus"
+    )));
+    assert!(violations.iter().any(|violation| matches!(
+        violation,
+        InvariantViolation::NegativeValidatorDeltas { validator, .. }
+            if validator == &validators[2].address
+    )));
+}
+
+/// Test the projection of the staking rewards of a bond over a year.
+#[test]
+fn test_projected_rewards() {
+    let mut storage = TestWlStorage::default();
+    let validator = established_address_1();
+    test_init_genesis(
+        &mut storage,
+        OwnedPosParams::default(),
+        [GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::native_whole(1_000),
+            consensus_key: common_sk_from_simple_seed(0).to_public(),
+            protocol_key: common_sk_from_simple_seed(1).to_public(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            commission_rate: Dec::new(1, 1).expect("Test failed"),
+            max_commission_rate_change: Dec::new(1, 1).expect("Test failed"),
+            metadata: Default::default(),
+        }]
+        .into_iter(),
+        Epoch::default(),
+    )
+    .unwrap();
+    // Mint 1 NAM of inflation per epoch, over 365 epochs per year
+    storage
+        .write(&params_storage::get_epochs_per_year_key(), 365_u64)
+        .unwrap();
+    storage
+        .write(
+            &params_storage::get_pos_inflation_amount_key(),
+            token::Amount::native_whole(1),
+        )
+        .unwrap();
+
+    // Bonding 1000 NAM doubles the total stake to 2000 NAM
+    let projection = projected_rewards(
+        &storage,
+        &validator,
+        token::Amount::native_whole(1_000),
+        Epoch::default(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(projection.gross_apr, Dec::new(1825, 4).unwrap());
+    assert_eq!(projection.commission_rate, Dec::new(1, 1).unwrap());
+    assert_eq!(projection.net_apr, Dec::new(16425, 5).unwrap());
+    assert_eq!(
+        projection.annual_rewards,
+        token::Amount::from_uint(164_250_000, 0).unwrap()
+    );
+
+    // There are no rewards to project for a non-validator
+    assert_eq!(
+        projected_rewards(
+            &storage,
+            &established_address_2(),
+            token::Amount::native_whole(1_000),
+            Epoch::default(),
+        )
+        .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_validator_raw_hash() {
+    let mut storage = TestWlStorage::default();
+    let address = address::testing::established_address_1();
+    let consensus_sk = key::testing::keypair_1();
+    let consensus_pk = consensus_sk.to_public();
+    let expected_raw_hash = key::tm_consensus_key_raw_hash(&consensus_pk);
+
+    assert!(
+        find_validator_by_raw_hash(&storage, &expected_raw_hash)
+            .unwrap()
+            .is_none()
+    );
+    write_validator_address_raw_hash(&mut storage, &address, &consensus_pk)
+        .unwrap();
+    let found =
+        find_validator_by_raw_hash(&storage, &expected_raw_hash).unwrap();
+    assert_eq!(found, Some(address));
+}
+
+#[test]
+fn test_validator_sets() {
+    let mut s = TestWlStorage::default();
+    // Only 3 consensus validator slots
+    let params = OwnedPosParams {
+        max_validator_slots: 3,
+        ..Default::default()
+    };
+    let addr_seed = "seed";
+    let mut address_gen = EstablishedAddressGen::new(addr_seed);
+    let mut sk_seed = 0;
+    let mut gen_validator = || {
+        let res = (
+            address_gen.generate_address(addr_seed),
+            key::testing::common_sk_from_simple_seed(sk_seed).to_public(),
+        );
+        // bump the sk seed
+        sk_seed += 1;
+        res
+    };
+
+    // Create genesis validators
+    let ((val1, pk1), stake1) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val2, pk2), stake2) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val3, pk3), stake3) =
+        (gen_validator(), token::Amount::native_whole(10));
+    let ((val4, pk4), stake4) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val5, pk5), stake5) =
+        (gen_validator(), token::Amount::native_whole(100));
+    let ((val6, pk6), stake6) =
+        (gen_validator(), token::Amount::native_whole(1));
+    let ((val7, pk7), stake7) =
+        (gen_validator(), token::Amount::native_whole(1));
+    println!("\nval1: {val1}, {pk1}, {}", stake1.to_string_native());
+    println!("val2: {val2}, {pk2}, {}", stake2.to_string_native());
+    println!("val3: {val3}, {pk3}, {}", stake3.to_string_native());
+    println!("val4: {val4}, {pk4}, {}", stake4.to_string_native());
+    println!("val5: {val5}, {pk5}, {}", stake5.to_string_native());
+    println!("val6: {val6}, {pk6}, {}", stake6.to_string_native());
+    println!("val7: {val7}, {pk7}, {}", stake7.to_string_native());
+
+    let start_epoch = Epoch::default();
+    let epoch = start_epoch;
+
+    let protocol_sk_1 = common_sk_from_simple_seed(0);
+    let protocol_sk_2 = common_sk_from_simple_seed(1);
+
+    let params = test_init_genesis(
+        &mut s,
+        params,
+        [
+            GenesisValidator {
+                address: val1.clone(),
+                tokens: stake1,
+                consensus_key: pk1.clone(),
+                protocol_key: protocol_sk_1.to_public(),
+                eth_hot_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                eth_cold_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+                max_commission_rate_change: Dec::new(1, 1)
+                    .expect("Dec creation failed"),
+                metadata: Default::default(),
+            },
+            GenesisValidator {
+                address: val2.clone(),
+                tokens: stake2,
+                consensus_key: pk2.clone(),
+                protocol_key: protocol_sk_2.to_public(),
+                eth_hot_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                eth_cold_key: key::common::PublicKey::Secp256k1(
+                    key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                        .ref_to(),
+                ),
+                commission_rate: Dec::new(1, 1).expect("Dec creation failed"),
+                max_commission_rate_change: Dec::new(1, 1)
+                    .expect("Dec creation failed"),
+                metadata: Default::default(),
+            },
+        ]
+        .into_iter(),
+        epoch,
+    )
+    .unwrap();
+
+    // A helper to insert a non-genesis validator
+    let insert_validator = |s: &mut TestWlStorage,
+                            addr,
+                            pk: &PublicKey,
+                            stake: token::Amount,
+                            epoch: Epoch| {
+        insert_validator_into_validator_set(
+            s,
+            &params,
+            addr,
+            stake,
+            epoch,
+            params.pipeline_len,
+        )
+        .unwrap();
+
+        update_validator_deltas(s, &params, addr, stake.change(), epoch, None)
+            .unwrap();
+
+        // Set their consensus key (needed for
+        // `validator_set_update_tendermint` fn)
+        validator_consensus_key_handle(addr)
+            .set(s, pk.clone(), epoch, params.pipeline_len)
+            .unwrap();
+    };
+
+    // Advance to EPOCH 1
+    //
+    // We cannot call `get_tendermint_set_updates` for the genesis state as
+    // `validator_set_update_tendermint` is only called 2 blocks before the
+    // start of an epoch and so we need to give it a predecessor epoch (see
+    // `get_tendermint_set_updates`), which we cannot have on the first
+    // epoch. In any way, the initial validator set is given to Tendermint
+    // from InitChain, so `validator_set_update_tendermint` is
+    // not being used for it.
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
+
+    // Insert another validator with the greater stake 10 NAM
+    insert_validator(&mut s, &val3, &pk3, stake3, epoch);
+    // Insert validator with stake 1 NAM
+    insert_validator(&mut s, &val4, &pk4, stake4, epoch);
+
+    // Validator `val3` and `val4` will be added at pipeline offset (2) - epoch
+    // 3
+    let val3_and_4_epoch = pipeline_epoch;
+
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
         .collect();
 
     assert_eq!(consensus_vals.len(), 3);
@@ -1807,7 +2590,7 @@ fn test_validator_sets() {
                 key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val4 && stake == &stake4 && *position == Position(0)
+        if address == &val1 && stake == &stake1 && *position == Position(0)
     ));
     assert!(matches!(
         &consensus_vals[1],
@@ -1815,7 +2598,7 @@ fn test_validator_sets() {
                 key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val3 && stake == &stake3 && *position == Position(0)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
     ));
     assert!(matches!(
         &consensus_vals[2],
@@ -1823,81 +2606,181 @@ fn test_validator_sets() {
                 key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val5 && stake == &stake5 && *position == Position(0)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
     ));
 
-    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+    // Check tendermint validator set updates - there should be none
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
+
+    // Advance to EPOCH 2
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
+
+    // Insert another validator with a greater stake still 1000 NAM. It should
+    // replace 2nd consensus validator with stake 1, which should become
+    // below-capacity
+    insert_validator(&mut s, &val5, &pk5, stake5, epoch);
+    // Validator `val5` will be added at pipeline offset (2) - epoch 4
+    let val5_epoch = pipeline_epoch;
+
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
         .at(&pipeline_epoch)
         .iter(&s)
         .unwrap()
         .map(Result::unwrap)
         .collect();
 
-    assert_eq!(below_capacity_vals.len(), 3);
+    assert_eq!(consensus_vals.len(), 3);
     assert!(matches!(
-        &below_capacity_vals[0],
+        &consensus_vals[0],
         (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
+                key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val2 && stake == &stake2 && *position == Position(1)
+        if address == &val1 && stake == &stake1 && *position == Position(0)
     ));
     assert!(matches!(
-        &below_capacity_vals[1],
+        &consensus_vals[1],
         (lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
+                key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(2)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
     ));
     assert!(matches!(
-        &below_capacity_vals[2],
-        (
-            lazy_map::NestedSubKey::Data {
-                key: ReverseOrdTokenAmount(stake),
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
-            },
-            address
-        )
-        if address == &val7 && stake == &stake7 && *position == Position(3)
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
     ));
 
-    let below_threshold_vals =
-        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
-            .unwrap()
-            .into_iter()
-            .collect::<Vec<_>>();
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
 
-    assert_eq!(below_threshold_vals.len(), 1);
-    assert_eq!(&below_threshold_vals[0], &val1);
+    assert_eq!(below_capacity_vals.len(), 2);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
 
-    // Advance to EPOCH 6
+    // Advance to EPOCH 3
     let epoch = advance_epoch(&mut s, &params);
     let pipeline_epoch = epoch + params.pipeline_len;
 
     // Check tendermint validator set updates
-    assert_eq!(val1_unbond_epoch, epoch, "val1's unbond is applied now");
+    assert_eq!(
+        val3_and_4_epoch, epoch,
+        "val3 and val4 are in the validator sets now"
+    );
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    // `val4` is newly added below-capacity, must be skipped in updated in TM
+    assert_eq!(tm_updates.len(), 1);
+    assert_eq!(
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk3,
+            bonded_stake: stake3,
+        })
+    );
+
+    // Insert another validator with a stake 1 NAM. It should be added to the
+    // below-capacity set
+    insert_validator(&mut s, &val6, &pk6, stake6, epoch);
+    // Validator `val6` will be added at pipeline offset (2) - epoch 5
+    let val6_epoch = pipeline_epoch;
+
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(below_capacity_vals.len(), 3);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
+    ));
+
+    // Advance to EPOCH 4
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
+
+    // Check tendermint validator set updates
+    assert_eq!(val5_epoch, epoch, "val5 is in the validator sets now");
     let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
     assert_eq!(tm_updates.len(), 2);
     assert_eq!(
         tm_updates[0],
         ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk4.clone(),
-            bonded_stake: stake4,
+            consensus_key: pk5,
+            bonded_stake: stake5,
         })
     );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk1));
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk2));
 
-    // Bond some stake to val6, it should be be swapped with the lowest
-    // consensus validator val2 into the consensus set
-    let bond = token::Amount::from_uint(500_000, 0).unwrap();
-    let stake6 = stake6 + bond;
-    println!("val6 {val6} new stake {}", stake6.to_string_native());
-    update_validator_set(&mut s, &params, &val6, bond.change(), epoch, None)
-        .unwrap();
-    update_validator_deltas(&mut s, &params, &val6, bond.change(), epoch, None)
+    // Unbond some stake from val1, it should be be swapped with the greatest
+    // below-capacity validator val2 into the below-capacity set. The stake of
+    // val1 will go below 1 NAM, which is the validator_stake_threshold, so it
+    // will enter the below-threshold validator set.
+    let unbond = token::Amount::from_uint(500_000, 0).unwrap();
+    let stake1 = stake1 - unbond;
+    println!("val1 {val1} new stake {}", stake1.to_string_native());
+    // Because `update_validator_set` and `update_validator_deltas` are
+    // effective from pipeline offset, we use pipeline epoch for the rest of the
+    // checks
+    update_validator_set(&mut s, &params, &val1, -unbond.change(), epoch, None)
         .unwrap();
-    let val6_bond_epoch = pipeline_epoch;
+    update_validator_deltas(
+        &mut s,
+        &params,
+        &val1,
+        -unbond.change(),
+        epoch,
+        None,
+    )
+    .unwrap();
+    // Epoch 6
+    let val1_unbond_epoch = pipeline_epoch;
 
     let consensus_vals: Vec<_> = consensus_validator_set_handle()
         .at(&pipeline_epoch)
@@ -1913,7 +2796,89 @@ fn test_validator_sets() {
                 key: stake,
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val6 && stake == &stake6 && *position == Position(0)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
+
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(below_capacity_vals.len(), 2);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
+    ));
+
+    let below_threshold_vals =
+        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+    assert_eq!(below_threshold_vals.len(), 1);
+    assert_eq!(&below_threshold_vals[0], &val1);
+
+    // Advance to EPOCH 5
+    let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
+
+    // Check tendermint validator set updates
+    assert_eq!(val6_epoch, epoch, "val6 is in the validator sets now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
+
+    // Insert another validator with stake 1 - it should be added to below
+    // capacity set
+    insert_validator(&mut s, &val7, &pk7, stake7, epoch);
+    // Epoch 7
+    let val7_epoch = pipeline_epoch;
+
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val4 && stake == &stake4 && *position == Position(0)
     ));
     assert!(matches!(
         &consensus_vals[1],
@@ -1940,7 +2905,6 @@ fn test_validator_sets() {
         .collect();
 
     assert_eq!(below_capacity_vals.len(), 3);
-    dbg!(&below_capacity_vals);
     assert!(matches!(
         &below_capacity_vals[0],
         (lazy_map::NestedSubKey::Data {
@@ -1955,7 +2919,7 @@ fn test_validator_sets() {
                 key: ReverseOrdTokenAmount(stake),
                 nested_sub_key: lazy_map::SubKey::Data(position),
         }, address)
-        if address == &val7 && stake == &stake7 && *position == Position(3)
+        if address == &val6 && stake == &stake6 && *position == Position(2)
     ));
     assert!(matches!(
         &below_capacity_vals[2],
@@ -1966,7 +2930,7 @@ fn test_validator_sets() {
             },
             address
         )
-        if address == &val4 && stake == &stake4 && *position == Position(4)
+        if address == &val7 && stake == &stake7 && *position == Position(3)
     ));
 
     let below_threshold_vals =
@@ -1978,39 +2942,146 @@ fn test_validator_sets() {
     assert_eq!(below_threshold_vals.len(), 1);
     assert_eq!(&below_threshold_vals[0], &val1);
 
-    // Advance to EPOCH 7
-    let epoch = advance_epoch(&mut s, &params);
-    assert_eq!(val7_epoch, epoch, "val6 is in the validator sets now");
-
-    // Check tendermint validator set updates
-    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    assert!(tm_updates.is_empty());
-
-    // Advance to EPOCH 8
+    // Advance to EPOCH 6
     let epoch = advance_epoch(&mut s, &params);
+    let pipeline_epoch = epoch + params.pipeline_len;
 
     // Check tendermint validator set updates
-    assert_eq!(val6_bond_epoch, epoch, "val5's bond is applied now");
+    assert_eq!(val1_unbond_epoch, epoch, "val1's unbond is applied now");
     let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
-    dbg!(&tm_updates);
     assert_eq!(tm_updates.len(), 2);
     assert_eq!(
         tm_updates[0],
         ValidatorSetUpdate::Consensus(ConsensusValidator {
-            consensus_key: pk6,
-            bonded_stake: stake6,
+            consensus_key: pk4.clone(),
+            bonded_stake: stake4,
         })
     );
-    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk4));
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk1));
 
-    // Check that the below-capacity validator set was purged for the old epochs
-    // but that the consensus_validator_set was not
-    let last_epoch = epoch;
-    for e in Epoch::iter_bounds_inclusive(
-        start_epoch,
-        last_epoch
-            .sub_or_default(Epoch(DEFAULT_NUM_PAST_EPOCHS))
-            .sub_or_default(Epoch(1)),
+    // Bond some stake to val6, it should be be swapped with the lowest
+    // consensus validator val2 into the consensus set
+    let bond = token::Amount::from_uint(500_000, 0).unwrap();
+    let stake6 = stake6 + bond;
+    println!("val6 {val6} new stake {}", stake6.to_string_native());
+    update_validator_set(&mut s, &params, &val6, bond.change(), epoch, None)
+        .unwrap();
+    update_validator_deltas(&mut s, &params, &val6, bond.change(), epoch, None)
+        .unwrap();
+    let val6_bond_epoch = pipeline_epoch;
+
+    let consensus_vals: Vec<_> = consensus_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(consensus_vals.len(), 3);
+    assert!(matches!(
+        &consensus_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val6 && stake == &stake6 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val3 && stake == &stake3 && *position == Position(0)
+    ));
+    assert!(matches!(
+        &consensus_vals[2],
+        (lazy_map::NestedSubKey::Data {
+                key: stake,
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val5 && stake == &stake5 && *position == Position(0)
+    ));
+
+    let below_capacity_vals: Vec<_> = below_capacity_validator_set_handle()
+        .at(&pipeline_epoch)
+        .iter(&s)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(below_capacity_vals.len(), 3);
+    dbg!(&below_capacity_vals);
+    assert!(matches!(
+        &below_capacity_vals[0],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val2 && stake == &stake2 && *position == Position(1)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[1],
+        (lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+        }, address)
+        if address == &val7 && stake == &stake7 && *position == Position(3)
+    ));
+    assert!(matches!(
+        &below_capacity_vals[2],
+        (
+            lazy_map::NestedSubKey::Data {
+                key: ReverseOrdTokenAmount(stake),
+                nested_sub_key: lazy_map::SubKey::Data(position),
+            },
+            address
+        )
+        if address == &val4 && stake == &stake4 && *position == Position(4)
+    ));
+
+    let below_threshold_vals =
+        read_below_threshold_validator_set_addresses(&s, pipeline_epoch)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+    assert_eq!(below_threshold_vals.len(), 1);
+    assert_eq!(&below_threshold_vals[0], &val1);
+
+    // Advance to EPOCH 7
+    let epoch = advance_epoch(&mut s, &params);
+    assert_eq!(val7_epoch, epoch, "val6 is in the validator sets now");
+
+    // Check tendermint validator set updates
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    assert!(tm_updates.is_empty());
+
+    // Advance to EPOCH 8
+    let epoch = advance_epoch(&mut s, &params);
+
+    // Check tendermint validator set updates
+    assert_eq!(val6_bond_epoch, epoch, "val5's bond is applied now");
+    let tm_updates = get_tendermint_set_updates(&s, &params, epoch);
+    dbg!(&tm_updates);
+    assert_eq!(tm_updates.len(), 2);
+    assert_eq!(
+        tm_updates[0],
+        ValidatorSetUpdate::Consensus(ConsensusValidator {
+            consensus_key: pk6,
+            bonded_stake: stake6,
+        })
+    );
+    assert_eq!(tm_updates[1], ValidatorSetUpdate::Deactivated(pk4));
+
+    // Check that the below-capacity validator set was purged for the old epochs
+    // but that the consensus_validator_set was not
+    let last_epoch = epoch;
+    for e in Epoch::iter_bounds_inclusive(
+        start_epoch,
+        last_epoch
+            .sub_or_default(Epoch(DEFAULT_NUM_PAST_EPOCHS))
+            .sub_or_default(Epoch(1)),
     ) {
         assert!(
             !consensus_validator_set_handle()
@@ -5399,6 +6470,23 @@ fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
         .unwrap_or_default();
     assert_eq!(src_total_unbonded, redel_amount_1);
 
+    // The redelegation should be pending until it can be redelegated again
+    let epoch_can_redel =
+        redel_end.prev() + params.slash_processing_epoch_offset();
+    let pending =
+        super::find_pending_redelegations(&storage, &delegator, current_epoch)
+            .unwrap();
+    assert_eq!(
+        pending,
+        vec![PendingRedelegation {
+            src_validator: src_validator.clone(),
+            dest_validator: dest_validator.clone(),
+            start: redel_end,
+            amount: redel_amount_1,
+            chainable_from: epoch_can_redel,
+        }]
+    );
+
     // Attempt to redelegate in epoch 3 to dest_validator
     current_epoch = advance_epoch(&mut storage, &params);
     super::process_slashes(&mut storage, current_epoch).unwrap();
@@ -5418,8 +6506,6 @@ fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
 
     // Advance to right before the redelegation can be redelegated again
     assert_eq!(redel_end, current_epoch);
-    let epoch_can_redel =
-        redel_end.prev() + params.slash_processing_epoch_offset();
     loop {
         current_epoch = advance_epoch(&mut storage, &params);
         super::process_slashes(&mut storage, current_epoch).unwrap();
@@ -5457,6 +6543,22 @@ fn test_chain_redelegations_aux(mut validators: Vec<GenesisValidator>) {
     let redel_2_start = current_epoch;
     let redel_2_end = current_epoch + params.pipeline_len;
 
+    // Only the second redelegation should be pending now
+    let pending =
+        super::find_pending_redelegations(&storage, &delegator, current_epoch)
+            .unwrap();
+    assert_eq!(
+        pending,
+        vec![PendingRedelegation {
+            src_validator: dest_validator.clone(),
+            dest_validator: dest_validator_2.clone(),
+            start: redel_2_end,
+            amount: redel_amount_2,
+            chainable_from: redel_2_end.prev()
+                + params.slash_processing_epoch_offset(),
+        }]
+    );
+
     // Checks -----------------------------------
 
     // Both the dest validator and dest validator 2 should have incoming
@@ -6330,76 +7432,464 @@ fn test_update_rewards_products_aux(validators: Vec<GenesisValidator>) {
     let gov_balance_pre =
         read_balance(&s, &staking_token, &address::GOV).unwrap();
 
-    let num_consensus_validators = consensus_set.len() as u64;
-    let accum_val = Dec::one() / num_consensus_validators;
-    let num_blocks_in_last_epoch = 1000;
+    let num_consensus_validators = consensus_set.len() as u64;
+    let accum_val = Dec::one() / num_consensus_validators;
+    let num_blocks_in_last_epoch = 1000;
+
+    // Assign some reward accumulator values to consensus validator
+    for validator in &consensus_set {
+        crate::rewards_accumulator_handle()
+            .insert(
+                &mut s,
+                validator.clone(),
+                accum_val * num_blocks_in_last_epoch,
+            )
+            .unwrap();
+    }
+
+    // Distribute inflation into rewards
+    let last_epoch = current_epoch.prev();
+    let inflation = token::Amount::native_whole(10_000_000);
+    crate::update_rewards_products_and_mint_inflation(
+        &mut s,
+        &params,
+        last_epoch,
+        num_blocks_in_last_epoch,
+        inflation,
+        &staking_token,
+    )
+    .unwrap();
+
+    let pos_balance_post =
+        read_balance(&s, &staking_token, &address::POS).unwrap();
+    let gov_balance_post =
+        read_balance(&s, &staking_token, &address::GOV).unwrap();
+
+    assert_eq!(
+        pos_balance_pre + gov_balance_pre + inflation,
+        pos_balance_post + gov_balance_post,
+        "Expected inflation to be minted to PoS and left-over amount to Gov"
+    );
+
+    let pos_credit = pos_balance_post - pos_balance_pre;
+    let gov_credit = gov_balance_post - gov_balance_pre;
+    assert!(
+        pos_credit > gov_credit,
+        "PoS must receive more tokens than Gov, but got {} in PoS and {} in \
+         Gov",
+        pos_credit.to_string_native(),
+        gov_credit.to_string_native()
+    );
+
+    // Rewards accumulator must be cleared out
+    let rewards_handle = crate::rewards_accumulator_handle();
+    assert!(rewards_handle.is_empty(&s).unwrap());
+}
+
+fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+
+    let init_tot_stake = validators
+        .clone()
+        .into_iter()
+        .fold(token::Amount::zero(), |acc, v| acc + v.tokens);
+    let val1_init_stake = validators[0].tokens;
+
+    let mut validators = validators;
+    validators[0].tokens = (init_tot_stake - val1_init_stake) / 30;
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.clone().into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    let validator1 = validators[0].address.clone();
+    let validator2 = validators[1].address.clone();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        10_000.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        1_342.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        1_875.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        584.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch to 1
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Unbond some from validator 1
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        144.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        3_448.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        699.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance an epoch to ep 2
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Bond to validator 1
+    super::bond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator1,
+        4_384.into(),
+        current_epoch,
+        None,
+    )
+    .unwrap();
+
+    // Redelegate some from validator 1 -> 2
+    super::redelegate_tokens(
+        &mut storage,
+        &delegator,
+        &validator1,
+        &validator2,
+        current_epoch,
+        1_008.into(),
+    )
+    .unwrap();
+
+    // Unbond some from validator 2
+    super::unbond_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator2,
+        3_500.into(),
+        current_epoch,
+        false,
+    )
+    .unwrap();
+
+    // Advance two epochs to ep 4
+    for _ in 0..2 {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    // Find some slashes committed in various epochs
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(1),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(2),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(2),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+    super::slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        Epoch(3),
+        1_u64,
+        SlashType::DuplicateVote,
+        &validator1,
+        current_epoch,
+    )
+    .unwrap();
+
+    // Advance such that these slashes are all processed
+    for _ in 0..params.slash_processing_epoch_offset() {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+
+    let del_bond_amount = crate::bond_amount(
+        &storage,
+        &BondId {
+            source: delegator.clone(),
+            validator: validator1.clone(),
+        },
+        pipeline_epoch,
+    )
+    .unwrap_or_default();
+
+    let self_bond_amount = crate::bond_amount(
+        &storage,
+        &BondId {
+            source: validator1.clone(),
+            validator: validator1.clone(),
+        },
+        pipeline_epoch,
+    )
+    .unwrap_or_default();
+
+    let val_stake = crate::read_validator_stake(
+        &storage,
+        &params,
+        &validator1,
+        pipeline_epoch,
+    )
+    .unwrap();
+    // dbg!(&val_stake);
+    // dbg!(&del_bond_amount);
+    // dbg!(&self_bond_amount);
+
+    let diff = val_stake - self_bond_amount - del_bond_amount;
+    assert!(diff <= 2.into());
+}
+
+fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
+    assert_eq!(validators.len(), 1);
+
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+    let validator = validators[0].address.clone();
+
+    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
+    let mut storage = TestWlStorage::default();
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
+        &mut storage,
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
+
+    // Check that there is one consensus key in the network
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 1);
+    let ck = consensus_keys.first().cloned().unwrap();
+    let og_ck = validator_consensus_key_handle(&validator)
+        .get(&storage, current_epoch, &params)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ck, og_ck);
+
+    // Attempt to change to a new secp256k1 consensus key (disallowed)
+    let secp_ck = gen_keypair::<key::secp256k1::SigScheme>();
+    let secp_ck = key::common::SecretKey::Secp256k1(secp_ck).ref_to();
+    let res =
+        change_consensus_key(&mut storage, &validator, &secp_ck, current_epoch);
+    assert!(res.is_err());
+
+    // Change consensus keys
+    let ck_2 = common_sk_from_simple_seed(1).ref_to();
+    change_consensus_key(&mut storage, &validator, &ck_2, current_epoch)
+        .unwrap();
+
+    // Check that there is a new consensus key
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 2);
+
+    for epoch in current_epoch.iter_range(params.pipeline_len) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ck, og_ck);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let ck = validator_consensus_key_handle(&validator)
+        .get(&storage, pipeline_epoch, &params)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ck, ck_2);
+
+    // Advance to the pipeline epoch
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        if current_epoch == pipeline_epoch {
+            break;
+        }
+    }
+
+    // Check the consensus keys again
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 2);
 
-    // Assign some reward accumulator values to consensus validator
-    for validator in &consensus_set {
-        crate::rewards_accumulator_handle()
-            .insert(
-                &mut s,
-                validator.clone(),
-                accum_val * num_blocks_in_last_epoch,
-            )
+    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
+            .unwrap()
             .unwrap();
+        assert_eq!(ck, ck_2);
     }
 
-    // Distribute inflation into rewards
-    let last_epoch = current_epoch.prev();
-    let inflation = token::Amount::native_whole(10_000_000);
-    crate::update_rewards_products_and_mint_inflation(
-        &mut s,
-        &params,
-        last_epoch,
-        num_blocks_in_last_epoch,
-        inflation,
-        &staking_token,
+    // Now change the consensus key again and bond in the same epoch
+    let ck_3 = common_sk_from_simple_seed(3).ref_to();
+    change_consensus_key(&mut storage, &validator, &ck_3, current_epoch)
+        .unwrap();
+
+    let staking_token = storage.storage.native_token.clone();
+    let amount_del = token::Amount::native_whole(5);
+    credit_tokens(&mut storage, &staking_token, &validator, amount_del)
+        .unwrap();
+    bond_tokens(
+        &mut storage,
+        None,
+        &validator,
+        token::Amount::native_whole(1),
+        current_epoch,
+        None,
     )
     .unwrap();
 
-    let pos_balance_post =
-        read_balance(&s, &staking_token, &address::POS).unwrap();
-    let gov_balance_post =
-        read_balance(&s, &staking_token, &address::GOV).unwrap();
-
-    assert_eq!(
-        pos_balance_pre + gov_balance_pre + inflation,
-        pos_balance_post + gov_balance_post,
-        "Expected inflation to be minted to PoS and left-over amount to Gov"
-    );
+    // Check consensus keys again
+    let consensus_keys = get_consensus_key_set(&storage).unwrap();
+    assert_eq!(consensus_keys.len(), 3);
 
-    let pos_credit = pos_balance_post - pos_balance_pre;
-    let gov_credit = gov_balance_post - gov_balance_pre;
-    assert!(
-        pos_credit > gov_credit,
-        "PoS must receive more tokens than Gov, but got {} in PoS and {} in \
-         Gov",
-        pos_credit.to_string_native(),
-        gov_credit.to_string_native()
-    );
+    for epoch in current_epoch.iter_range(params.pipeline_len) {
+        let ck = validator_consensus_key_handle(&validator)
+            .get(&storage, epoch, &params)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ck, ck_2);
+    }
+    let pipeline_epoch = current_epoch + params.pipeline_len;
+    let ck = validator_consensus_key_handle(&validator)
+        .get(&storage, pipeline_epoch, &params)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ck, ck_3);
 
-    // Rewards accumulator must be cleared out
-    let rewards_handle = crate::rewards_accumulator_handle();
-    assert!(rewards_handle.is_empty(&s).unwrap());
+    // Advance to the pipeline epoch to ensure that the validator set updates to
+    // tendermint will work
+    loop {
+        current_epoch = advance_epoch(&mut storage, &params);
+        if current_epoch == pipeline_epoch {
+            break;
+        }
+    }
+    assert_eq!(current_epoch.0, 2 * params.pipeline_len);
 }
 
-fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
+fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
+    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    let validator1 = validators[0].address.clone();
+    let validator2 = validators[1].address.clone();
+
     let mut storage = TestWlStorage::default();
     let params = OwnedPosParams {
         unbonding_len: 4,
         ..Default::default()
     };
 
-    let init_tot_stake = validators
-        .clone()
-        .into_iter()
-        .fold(token::Amount::zero(), |acc, v| acc + v.tokens);
-    let val1_init_stake = validators[0].tokens;
-
-    let mut validators = validators;
-    validators[0].tokens = (init_tot_stake - val1_init_stake) / 30;
-
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
@@ -6411,473 +7901,670 @@ fn test_slashed_bond_amount_aux(validators: Vec<GenesisValidator>) {
     .unwrap();
     storage.commit_block().unwrap();
 
-    let validator1 = validators[0].address.clone();
-    let validator2 = validators[1].address.clone();
-
-    // Get a delegator with some tokens
+    // Get delegators with some tokens
     let staking_token = staking_token_address(&storage);
-    let delegator = address::testing::gen_implicit_address();
-    let del_balance = token::Amount::from_uint(1_000_000, 0).unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+    let delegator1 = address::testing::gen_implicit_address();
+    let delegator2 = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator1, del_balance)
+        .unwrap();
+    credit_tokens(&mut storage, &staking_token, &delegator2, del_balance)
         .unwrap();
 
-    // Bond to validator 1
+    // Advance to epoch 1
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+
+    // Delegate in epoch 1 to validator1
+    let del1_epoch = current_epoch;
     super::bond_tokens(
         &mut storage,
-        Some(&delegator),
+        Some(&delegator1),
         &validator1,
-        10_000.into(),
+        1000.into(),
         current_epoch,
         None,
     )
     .unwrap();
 
-    // Unbond some from validator 1
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        1_342.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
-
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
-        current_epoch,
-        1_875.into(),
-    )
-    .unwrap();
-
-    // Unbond some from validator 2
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator2,
-        584.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
-
-    // Advance an epoch to 1
+    // Advance to epoch 2
     current_epoch = advance_epoch(&mut storage, &params);
     super::process_slashes(&mut storage, current_epoch).unwrap();
 
-    // Bond to validator 1
+    // Delegate in epoch 2 to validator2
+    let del2_epoch = current_epoch;
     super::bond_tokens(
         &mut storage,
-        Some(&delegator),
-        &validator1,
-        384.into(),
+        Some(&delegator2),
+        &validator2,
+        1000.into(),
         current_epoch,
         None,
     )
     .unwrap();
 
-    // Unbond some from validator 1
-    super::unbond_tokens(
-        &mut storage,
-        Some(&delegator),
-        &validator1,
-        144.into(),
-        current_epoch,
-        false,
-    )
-    .unwrap();
+    // Checks
+    assert!(super::is_validator(&storage, &validator1).unwrap());
+    assert!(super::is_validator(&storage, &validator2).unwrap());
+    assert!(!super::is_delegator(&storage, &validator1, None).unwrap());
+    assert!(!super::is_delegator(&storage, &validator2, None).unwrap());
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
-        &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
-        current_epoch,
-        3_448.into(),
-    )
-    .unwrap();
+    assert!(!super::is_validator(&storage, &delegator1).unwrap());
+    assert!(!super::is_validator(&storage, &delegator2).unwrap());
+    assert!(super::is_delegator(&storage, &delegator1, None).unwrap());
+    assert!(super::is_delegator(&storage, &delegator2, None).unwrap());
 
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    for epoch in Epoch::default().iter_range(del1_epoch.0 + params.pipeline_len)
+    {
+        assert!(
+            !super::is_delegator(&storage, &delegator1, Some(epoch)).unwrap()
+        );
+    }
+    assert!(
+        super::is_delegator(
+            &storage,
+            &delegator1,
+            Some(del1_epoch + params.pipeline_len)
+        )
+        .unwrap()
+    );
+    for epoch in Epoch::default().iter_range(del2_epoch.0 + params.pipeline_len)
+    {
+        assert!(
+            !super::is_delegator(&storage, &delegator2, Some(epoch)).unwrap()
+        );
+    }
+    assert!(
+        super::is_delegator(
+            &storage,
+            &delegator2,
+            Some(del2_epoch + params.pipeline_len)
+        )
+        .unwrap()
+    );
+}
+
+fn test_auto_withdrawals_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        Some(&delegator),
-        &validator2,
-        699.into(),
+        params,
+        validators.into_iter(),
         current_epoch,
-        false,
     )
     .unwrap();
+    storage.commit_block().unwrap();
 
-    // Advance an epoch to ep 2
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
 
-    // Bond to validator 1
+    // Delegate and advance past the pipeline
     super::bond_tokens(
         &mut storage,
         Some(&delegator),
-        &validator1,
-        4_384.into(),
+        &validator,
+        del_balance,
         current_epoch,
         None,
     )
     .unwrap();
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
 
-    // Redelegate some from validator 1 -> 2
-    super::redelegate_tokens(
+    // Unbond part of the delegation and schedule its automatic withdrawal
+    let unbond_amount = token::Amount::native_whole(400);
+    super::unbond_tokens(
         &mut storage,
-        &delegator,
-        &validator1,
-        &validator2,
+        Some(&delegator),
+        &validator,
+        unbond_amount,
         current_epoch,
-        1_008.into(),
+        false,
     )
     .unwrap();
-
-    // Unbond some from validator 2
-    super::unbond_tokens(
+    let withdrawal_epoch = super::schedule_auto_withdrawal(
         &mut storage,
         Some(&delegator),
-        &validator2,
-        3_500.into(),
+        &validator,
         current_epoch,
-        false,
     )
     .unwrap();
+    assert_eq!(
+        withdrawal_epoch,
+        current_epoch + params.withdrawable_epoch_offset()
+    );
 
-    // Advance two epochs to ep 4
-    for _ in 0..2 {
+    // Nothing is withdrawn before the unbond becomes withdrawable
+    while current_epoch.next() < withdrawal_epoch {
         current_epoch = advance_epoch(&mut storage, &params);
         super::process_slashes(&mut storage, current_epoch).unwrap();
+        let withdrawn =
+            super::process_auto_withdrawals(&mut storage, current_epoch)
+                .unwrap();
+        assert!(withdrawn.is_empty());
     }
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert!(balance.is_zero());
 
-    // Find some slashes committed in various epochs
-    super::slash(
+    // The unbond is withdrawn at the start of the withdrawable epoch
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    let withdrawn =
+        super::process_auto_withdrawals(&mut storage, current_epoch).unwrap();
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
+    assert_eq!(withdrawn, vec![(bond_id, unbond_amount)]);
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert_eq!(balance, unbond_amount);
+    assert!(
+        super::unbond_handle(&delegator, &validator)
+            .is_empty(&storage)
+            .unwrap()
+    );
+    assert!(
+        super::auto_withdrawals_handle()
+            .at(&current_epoch)
+            .is_empty(&storage)
+            .unwrap()
+    );
+}
+
+fn test_withdrawable_unbonds_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
+
+    let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams {
+        unbonding_len: 4,
+        ..Default::default()
+    };
+
+    // Genesis
+    let mut current_epoch = storage.storage.block.epoch;
+    let params = test_init_genesis(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(1),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        params,
+        validators.into_iter(),
         current_epoch,
     )
     .unwrap();
-    super::slash(
+    storage.commit_block().unwrap();
+
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
+        .unwrap();
+
+    // Delegate and advance past the pipeline
+    super::bond_tokens(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(2),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        Some(&delegator),
+        &validator,
+        del_balance,
         current_epoch,
+        None,
     )
     .unwrap();
-    super::slash(
+    for _ in 0..params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
+        super::process_slashes(&mut storage, current_epoch).unwrap();
+    }
+    assert!(
+        super::find_withdrawable_unbonds(&storage, &delegator, current_epoch)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Unbond in two consecutive epochs
+    let unbond_amount = token::Amount::native_whole(400);
+    let first_unbond_epoch = current_epoch;
+    super::unbond_tokens(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(2),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        Some(&delegator),
+        &validator,
+        unbond_amount,
         current_epoch,
+        false,
     )
     .unwrap();
-    super::slash(
+    current_epoch = advance_epoch(&mut storage, &params);
+    super::process_slashes(&mut storage, current_epoch).unwrap();
+    super::unbond_tokens(
         &mut storage,
-        &params,
-        current_epoch,
-        Epoch(3),
-        1_u64,
-        SlashType::DuplicateVote,
-        &validator1,
+        Some(&delegator),
+        &validator,
+        unbond_amount,
         current_epoch,
+        false,
     )
     .unwrap();
 
-    // Advance such that these slashes are all processed
-    for _ in 0..params.slash_processing_epoch_offset() {
+    // The unbonds are sorted by their withdrawable epoch and count down to
+    // it, staying at zero once they can be withdrawn
+    let first_withdraw_epoch =
+        first_unbond_epoch + params.withdrawable_epoch_offset();
+    let last_withdraw_epoch = first_withdraw_epoch.next();
+    while current_epoch <= last_withdraw_epoch {
+        let unbonds = super::find_withdrawable_unbonds(
+            &storage,
+            &delegator,
+            current_epoch,
+        )
+        .unwrap();
+        assert_eq!(unbonds.len(), 2);
+        for (unbond, withdraw_epoch) in
+            unbonds.iter().zip([first_withdraw_epoch, last_withdraw_epoch])
+        {
+            assert_eq!(unbond.validator, validator);
+            assert_eq!(unbond.withdraw, withdraw_epoch);
+            assert_eq!(
+                unbond.epochs_left,
+                withdraw_epoch.0.saturating_sub(current_epoch.0)
+            );
+            assert_eq!(unbond.amount, unbond_amount);
+        }
         current_epoch = advance_epoch(&mut storage, &params);
         super::process_slashes(&mut storage, current_epoch).unwrap();
     }
 
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-
-    let del_bond_amount = crate::bond_amount(
-        &storage,
-        &BondId {
-            source: delegator.clone(),
-            validator: validator1.clone(),
-        },
-        pipeline_epoch,
-    )
-    .unwrap_or_default();
-
-    let self_bond_amount = crate::bond_amount(
-        &storage,
-        &BondId {
-            source: validator1.clone(),
-            validator: validator1.clone(),
-        },
-        pipeline_epoch,
-    )
-    .unwrap_or_default();
-
-    let val_stake = crate::read_validator_stake(
-        &storage,
-        &params,
-        &validator1,
-        pipeline_epoch,
+    // Withdrawn unbonds are no longer listed
+    super::withdraw_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
     )
     .unwrap();
-    // dbg!(&val_stake);
-    // dbg!(&del_bond_amount);
-    // dbg!(&self_bond_amount);
-
-    let diff = val_stake - self_bond_amount - del_bond_amount;
-    assert!(diff <= 2.into());
+    assert!(
+        super::find_withdrawable_unbonds(&storage, &delegator, current_epoch)
+            .unwrap()
+            .is_empty()
+    );
 }
 
-fn test_consensus_key_change_aux(validators: Vec<GenesisValidator>) {
-    assert_eq!(validators.len(), 1);
-
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
+fn test_auto_compounding_aux(validators: Vec<GenesisValidator>) {
     let validator = validators[0].address.clone();
 
-    println!("\nTest inputs: {params:?}, genesis validators: {validators:#?}");
     let mut storage = TestWlStorage::default();
+    let params = OwnedPosParams::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
         &mut storage,
-        params,
-        validators.into_iter(),
-        current_epoch,
-    )
-    .unwrap();
-    storage.commit_block().unwrap();
-
-    // Check that there is one consensus key in the network
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 1);
-    let ck = consensus_keys.first().cloned().unwrap();
-    let og_ck = validator_consensus_key_handle(&validator)
-        .get(&storage, current_epoch, &params)
-        .unwrap()
-        .unwrap();
-    assert_eq!(ck, og_ck);
-
-    // Attempt to change to a new secp256k1 consensus key (disallowed)
-    let secp_ck = gen_keypair::<key::secp256k1::SigScheme>();
-    let secp_ck = key::common::SecretKey::Secp256k1(secp_ck).ref_to();
-    let res =
-        change_consensus_key(&mut storage, &validator, &secp_ck, current_epoch);
-    assert!(res.is_err());
-
-    // Change consensus keys
-    let ck_2 = common_sk_from_simple_seed(1).ref_to();
-    change_consensus_key(&mut storage, &validator, &ck_2, current_epoch)
-        .unwrap();
-
-    // Check that there is a new consensus key
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 2);
-
-    for epoch in current_epoch.iter_range(params.pipeline_len) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
-            .unwrap();
-        assert_eq!(ck, og_ck);
-    }
-    let pipeline_epoch = current_epoch + params.pipeline_len;
-    let ck = validator_consensus_key_handle(&validator)
-        .get(&storage, pipeline_epoch, &params)
-        .unwrap()
-        .unwrap();
-    assert_eq!(ck, ck_2);
-
-    // Advance to the pipeline epoch
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        if current_epoch == pipeline_epoch {
-            break;
-        }
-    }
-
-    // Check the consensus keys again
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 2);
-
-    for epoch in current_epoch.iter_range(params.pipeline_len + 1) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
-            .unwrap();
-        assert_eq!(ck, ck_2);
-    }
+        params,
+        validators.into_iter(),
+        current_epoch,
+    )
+    .unwrap();
+    storage.commit_block().unwrap();
 
-    // Now change the consensus key again and bond in the same epoch
-    let ck_3 = common_sk_from_simple_seed(3).ref_to();
-    change_consensus_key(&mut storage, &validator, &ck_3, current_epoch)
+    // Get a delegator with some tokens
+    let staking_token = staking_token_address(&storage);
+    let delegator = address::testing::gen_implicit_address();
+    let del_balance = token::Amount::native_whole(1000);
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
         .unwrap();
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
 
-    let staking_token = storage.storage.native_token.clone();
-    let amount_del = token::Amount::native_whole(5);
-    credit_tokens(&mut storage, &staking_token, &validator, amount_del)
-        .unwrap();
-    bond_tokens(
+    // Delegate and opt into auto-compounding
+    super::bond_tokens(
         &mut storage,
-        None,
+        Some(&delegator),
         &validator,
-        token::Amount::native_whole(1),
+        del_balance,
         current_epoch,
         None,
     )
     .unwrap();
+    super::set_auto_compound(&mut storage, Some(&delegator), &validator, true)
+        .unwrap();
+    assert!(super::is_auto_compound(&storage, &bond_id).unwrap());
 
-    // Check consensus keys again
-    let consensus_keys = get_consensus_key_set(&storage).unwrap();
-    assert_eq!(consensus_keys.len(), 3);
+    // A delegator cannot enable auto-compounding for a non-validator
+    let non_validator = address::testing::gen_established_address();
+    assert!(
+        super::set_auto_compound(
+            &mut storage,
+            Some(&delegator),
+            &non_validator,
+            true
+        )
+        .is_err()
+    );
 
-    for epoch in current_epoch.iter_range(params.pipeline_len) {
-        let ck = validator_consensus_key_handle(&validator)
-            .get(&storage, epoch, &params)
-            .unwrap()
-            .unwrap();
-        assert_eq!(ck, ck_2);
+    // Advance until the delegation contributes to the validator's stake
+    for _ in 0..=params.pipeline_len {
+        current_epoch = advance_epoch(&mut storage, &params);
     }
+
+    // Distribute the inflation of the last epoch into rewards
+    let num_blocks_in_last_epoch = 1000;
+    crate::rewards_accumulator_handle()
+        .insert(
+            &mut storage,
+            validator.clone(),
+            Dec::from(num_blocks_in_last_epoch),
+        )
+        .unwrap();
+    crate::update_rewards_products_and_mint_inflation(
+        &mut storage,
+        &params,
+        current_epoch.prev(),
+        num_blocks_in_last_epoch,
+        token::Amount::native_whole(10_000_000),
+        &staking_token,
+    )
+    .unwrap();
+    let rewards = super::query_reward_tokens(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(!rewards.is_zero());
+
+    // The rewards are added to the delegation at the pipeline offset, and
+    // the validator's own rewards are left unclaimed
     let pipeline_epoch = current_epoch + params.pipeline_len;
-    let ck = validator_consensus_key_handle(&validator)
-        .get(&storage, pipeline_epoch, &params)
+    let compounded =
+        super::process_auto_compounding(&mut storage, current_epoch).unwrap();
+    assert_eq!(compounded, vec![(bond_id.clone(), rewards)]);
+    let delegation = bond_handle(&delegator, &validator);
+    assert_eq!(
+        delegation
+            .get_sum(&storage, pipeline_epoch, &params)
+            .unwrap()
+            .unwrap_or_default(),
+        del_balance + rewards
+    );
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert!(balance.is_zero());
+    assert!(
+        super::query_reward_tokens(
+            &storage,
+            Some(&delegator),
+            &validator,
+            current_epoch,
+        )
         .unwrap()
-        .unwrap();
-    assert_eq!(ck, ck_3);
+        .is_zero()
+    );
+    assert!(
+        !super::query_reward_tokens(&storage, None, &validator, current_epoch)
+            .unwrap()
+            .is_zero()
+    );
 
-    // Advance to the pipeline epoch to ensure that the validator set updates to
-    // tendermint will work
-    loop {
-        current_epoch = advance_epoch(&mut storage, &params);
-        if current_epoch == pipeline_epoch {
-            break;
-        }
-    }
-    assert_eq!(current_epoch.0, 2 * params.pipeline_len);
+    // Nothing is compounded once auto-compounding is disabled
+    super::set_auto_compound(&mut storage, Some(&delegator), &validator, false)
+        .unwrap();
+    assert!(!super::is_auto_compound(&storage, &bond_id).unwrap());
+    assert!(
+        super::auto_compound_bonds_handle()
+            .is_empty(&storage)
+            .unwrap()
+    );
+    current_epoch = advance_epoch(&mut storage, &params);
+    let compounded =
+        super::process_auto_compounding(&mut storage, current_epoch).unwrap();
+    assert!(compounded.is_empty());
 }
 
-fn test_is_delegator_aux(mut validators: Vec<GenesisValidator>) {
-    validators.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-
-    let validator1 = validators[0].address.clone();
-    let validator2 = validators[1].address.clone();
+fn test_slashing_insurance_aux(validators: Vec<GenesisValidator>) {
+    let validator = validators[0].address.clone();
 
     let mut storage = TestWlStorage::default();
-    let params = OwnedPosParams {
-        unbonding_len: 4,
-        ..Default::default()
-    };
+    let params = OwnedPosParams::default();
 
     // Genesis
     let mut current_epoch = storage.storage.block.epoch;
     let params = test_init_genesis(
         &mut storage,
         params,
-        validators.clone().into_iter(),
+        validators.into_iter(),
         current_epoch,
     )
     .unwrap();
     storage.commit_block().unwrap();
 
-    // Get delegators with some tokens
+    // Get a delegator with some tokens
     let staking_token = staking_token_address(&storage);
-    let delegator1 = address::testing::gen_implicit_address();
-    let delegator2 = address::testing::gen_implicit_address();
+    let delegator = address::testing::gen_implicit_address();
     let del_balance = token::Amount::native_whole(1000);
-    credit_tokens(&mut storage, &staking_token, &delegator1, del_balance)
-        .unwrap();
-    credit_tokens(&mut storage, &staking_token, &delegator2, del_balance)
+    credit_tokens(&mut storage, &staking_token, &delegator, del_balance)
         .unwrap();
+    let bond_id = BondId {
+        source: delegator.clone(),
+        validator: validator.clone(),
+    };
 
-    // Advance to epoch 1
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Delegate in epoch 1 to validator1
-    let del1_epoch = current_epoch;
+    // Delegate and insure the delegation
     super::bond_tokens(
         &mut storage,
-        Some(&delegator1),
-        &validator1,
-        1000.into(),
+        Some(&delegator),
+        &validator,
+        del_balance,
         current_epoch,
         None,
     )
     .unwrap();
-
-    // Advance to epoch 2
-    current_epoch = advance_epoch(&mut storage, &params);
-    super::process_slashes(&mut storage, current_epoch).unwrap();
-
-    // Delegate in epoch 2 to validator2
-    let del2_epoch = current_epoch;
-    super::bond_tokens(
+    super::set_slashing_insurance(
         &mut storage,
-        Some(&delegator2),
-        &validator2,
-        1000.into(),
+        Some(&delegator),
+        &validator,
+        true,
         current_epoch,
-        None,
     )
     .unwrap();
 
-    // Checks
-    assert!(super::is_validator(&storage, &validator1).unwrap());
-    assert!(super::is_validator(&storage, &validator2).unwrap());
-    assert!(!super::is_delegator(&storage, &validator1, None).unwrap());
-    assert!(!super::is_delegator(&storage, &validator2, None).unwrap());
+    // The delegation is only insured after the slash processing epoch offset
+    let insured_from =
+        current_epoch + params.slash_processing_epoch_offset() + 1;
+    assert!(
+        !super::is_insured(&storage, &bond_id, insured_from.prev()).unwrap()
+    );
+    assert!(super::is_insured(&storage, &bond_id, insured_from).unwrap());
 
-    assert!(!super::is_validator(&storage, &delegator1).unwrap());
-    assert!(!super::is_validator(&storage, &delegator2).unwrap());
-    assert!(super::is_delegator(&storage, &delegator1, None).unwrap());
-    assert!(super::is_delegator(&storage, &delegator2, None).unwrap());
+    // A delegator cannot insure a bond to a non-validator
+    let non_validator = address::testing::gen_established_address();
+    assert!(
+        super::set_slashing_insurance(
+            &mut storage,
+            Some(&delegator),
+            &non_validator,
+            true,
+            current_epoch,
+        )
+        .is_err()
+    );
 
-    for epoch in Epoch::default().iter_range(del1_epoch.0 + params.pipeline_len)
-    {
-        assert!(
-            !super::is_delegator(&storage, &delegator1, Some(epoch)).unwrap()
-        );
+    // Insurance rates must be between 0 and 1
+    let insurance_params = super::read_insurance_params(&storage).unwrap();
+    assert_eq!(insurance_params, InsuranceParams::default());
+    assert!(insurance_params.validate().is_empty());
+    let invalid = InsuranceParams {
+        coverage_rate: Dec::two(),
+        ..insurance_params.clone()
+    };
+    assert_eq!(invalid.validate().len(), 1);
+
+    // Advance until the delegation has been insured for an epoch
+    while current_epoch <= insured_from {
+        current_epoch = advance_epoch(&mut storage, &params);
     }
-    assert!(
-        super::is_delegator(
-            &storage,
-            &delegator1,
-            Some(del1_epoch + params.pipeline_len)
+
+    // Opting in again does not delay the insurance
+    super::set_slashing_insurance(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        true,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(super::is_insured(&storage, &bond_id, current_epoch).unwrap());
+
+    // Distribute the inflation of the last epoch into rewards
+    let num_blocks_in_last_epoch = 1000;
+    crate::rewards_accumulator_handle()
+        .insert(
+            &mut storage,
+            validator.clone(),
+            Dec::from(num_blocks_in_last_epoch),
         )
-        .unwrap()
+        .unwrap();
+    crate::update_rewards_products_and_mint_inflation(
+        &mut storage,
+        &params,
+        current_epoch.prev(),
+        num_blocks_in_last_epoch,
+        token::Amount::native_whole(10_000_000),
+        &staking_token,
+    )
+    .unwrap();
+
+    // The premium of the insured delegation is charged against its rewards
+    // of the last epoch, paid into the pool and left out of the queried
+    // rewards
+    super::charge_insurance_premiums(&mut storage, current_epoch).unwrap();
+    let pool_balance = super::read_insurance_pool_balance(&storage).unwrap();
+    assert!(!pool_balance.is_zero());
+    let rewards = super::query_reward_tokens(
+        &storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(!rewards.is_zero());
+    let claimed = super::claim_reward_tokens(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        current_epoch,
+    )
+    .unwrap();
+    assert_eq!(claimed, rewards);
+    assert_eq!(
+        pool_balance,
+        insurance_params.premium_rate * (claimed + pool_balance)
     );
-    for epoch in Epoch::default().iter_range(del2_epoch.0 + params.pipeline_len)
-    {
-        assert!(
-            !super::is_delegator(&storage, &delegator2, Some(epoch)).unwrap()
-        );
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert_eq!(balance, claimed);
+
+    // Slash the validator and advance to the epoch of processing the slash
+    let evidence_epoch = current_epoch;
+    slash(
+        &mut storage,
+        &params,
+        current_epoch,
+        evidence_epoch,
+        0u64,
+        SlashType::DuplicateVote,
+        &validator,
+        current_epoch.next(),
+    )
+    .unwrap();
+    let processing_epoch =
+        evidence_epoch + params.slash_processing_epoch_offset();
+    while current_epoch < processing_epoch {
+        current_epoch = advance_epoch(&mut storage, &params);
     }
+
+    // The pool compensates the loss of the insured delegation, up to its
+    // balance
+    let amount_before =
+        super::bond_amount(&storage, &bond_id, current_epoch).unwrap();
+    let insured_bonds =
+        super::insured_bond_amounts(&storage, current_epoch).unwrap();
+    assert_eq!(
+        insured_bonds,
+        BTreeMap::from([(bond_id.clone(), amount_before)])
+    );
+    process_slashes(&mut storage, current_epoch).unwrap();
+    let amount_after =
+        super::bond_amount(&storage, &bond_id, current_epoch).unwrap();
+    assert!(amount_after < amount_before);
+    let payouts =
+        super::pay_insurance_claims(&mut storage, current_epoch, insured_bonds)
+            .unwrap();
+    let expected_payout = min(
+        insurance_params.coverage_rate * (amount_before - amount_after),
+        pool_balance,
+    );
+    assert_eq!(payouts, vec![(bond_id.clone(), expected_payout)]);
+    assert_eq!(
+        super::read_insurance_pool_balance(&storage).unwrap(),
+        pool_balance - expected_payout
+    );
+    let balance = read_balance(&storage, &staking_token, &delegator).unwrap();
+    assert_eq!(balance, claimed + expected_payout);
+
+    // Nothing is insured once the delegation is opted out
+    super::set_slashing_insurance(
+        &mut storage,
+        Some(&delegator),
+        &validator,
+        false,
+        current_epoch,
+    )
+    .unwrap();
+    assert!(!super::is_insured(&storage, &bond_id, current_epoch).unwrap());
+    assert!(super::insured_bonds_handle().is_empty(&storage).unwrap());
+
+    // When the pool is short, it is shared out between the claims pro rata,
+    // instead of being drained by the first claimant. The losses of the two
+    // bonds are of 100 and 300 tokens, with claims of 50 and 150 tokens.
+    let other_bond_id = BondId {
+        source: address::testing::gen_implicit_address(),
+        validator: validator.clone(),
+    };
+    let insured_bonds = BTreeMap::from([
+        (bond_id.clone(), amount_after + token::Amount::native_whole(100)),
+        (other_bond_id.clone(), token::Amount::native_whole(300)),
+    ]);
+    super::write_insurance_pool_balance(
+        &mut storage,
+        token::Amount::native_whole(100),
+    )
+    .unwrap();
+    let payouts =
+        super::pay_insurance_claims(&mut storage, current_epoch, insured_bonds)
+            .unwrap();
+    let expected_payouts = BTreeMap::from([
+        (bond_id, token::Amount::native_whole(25)),
+        (other_bond_id, token::Amount::native_whole(75)),
+    ]);
+    assert_eq!(
+        payouts.into_iter().collect::<BTreeMap<_, _>>(),
+        expected_payouts
+    );
     assert!(
-        super::is_delegator(
-            &storage,
-            &delegator2,
-            Some(del2_epoch + params.pipeline_len)
-        )
-        .unwrap()
+        super::read_insurance_pool_balance(&storage)
+            .unwrap()
+            .is_zero()
     );
 }
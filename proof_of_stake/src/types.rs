@@ -3,7 +3,7 @@
 mod rev_order;
 
 use core::fmt::Debug;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::hash::Hash;
@@ -20,6 +20,7 @@ use namada_core::types::key::common;
 use namada_core::types::storage::{Epoch, KeySeg};
 use namada_core::types::token;
 use namada_core::types::token::Amount;
+use namada_core::types::vote_extensions::heartbeat::BuildInfo;
 pub use rev_order::ReverseOrdTokenAmount;
 use serde::{Deserialize, Serialize};
 
@@ -99,6 +100,13 @@ pub type TotalConsensusStakes = crate::epoched::Epoched<
     crate::epoched::OffsetMaxU64,
 >;
 
+/// Epoched cubic slashing window length, which may be changed by governance
+pub type CubicSlashingWindowLengths = crate::epoched::Epoched<
+    u64,
+    crate::epoched::OffsetZero,
+    crate::epoched::OffsetMaxU64,
+>;
+
 /// Epoched validator's deltas.
 pub type ValidatorDeltas = crate::epoched::EpochedDelta<
     token::Change,
@@ -120,6 +128,11 @@ pub type CommissionRates = crate::epoched::Epoched<
     crate::epoched::OffsetDefaultNumPastEpochs,
 >;
 
+/// The history of a validator's commission rate, keyed by the epoch at which
+/// each rate takes effect. Unlike the epoched [`CommissionRates`], the
+/// history is never pruned.
+pub type CommissionHistory = LazyMap<Epoch, Dec>;
+
 /// Epoched validator's bonds
 pub type Bonds = crate::epoched::EpochedDelta<
     token::Amount,
@@ -166,6 +179,10 @@ pub type ConsensusKeys = LazySet<common::PublicKey>;
 pub type ValidatorTotalUnbonded =
     NestedMap<Epoch, LazyMap<Epoch, token::Amount>>;
 
+/// The delegators of a validator, i.e. the sources of its bonds, including
+/// the validator itself when it has a self-bond
+pub type ValidatorDelegators = LazySet<Address>;
+
 /// A validator's incoming redelegations, where the key is the bond owner
 /// address and the value is the redelegation end epoch
 pub type IncomingRedelegations = LazyMap<Address, Epoch>;
@@ -282,6 +299,46 @@ pub struct CommissionPair {
     pub max_commission_change_per_epoch: Dec,
 }
 
+/// Bonds whose unbonds are automatically withdrawn once they become
+/// withdrawable. Keyed by the epoch at which the unbonds become withdrawable,
+/// then by the source of the bonds, with the set of validators of the bonds
+/// as values.
+pub type AutoWithdrawals =
+    NestedMap<Epoch, LazyMap<Address, BTreeSet<Address>>>;
+
+/// Bonds whose rewards are automatically added to the bond at the start of
+/// every epoch. Keyed by the source of the bonds, with the set of validators
+/// of the bonds as values.
+pub type AutoCompoundBonds = LazyMap<Address, BTreeSet<Address>>;
+
+/// Bonds insured against slashing by the insurance pool. Keyed by the source
+/// of the bonds, with the validators of the bonds as values, each with the
+/// epoch from which its bond is insured.
+pub type InsuredBonds = LazyMap<Address, BTreeMap<Address, Epoch>>;
+
+/// The builds of the node software attested to by the heartbeats of
+/// validators. Keyed by the epoch of the heartbeats, then by validator.
+pub type ValidatorHeartbeats = NestedMap<Epoch, LazyMap<Address, BuildInfo>>;
+
+/// The number of block heights at which each validator signed an Ethereum
+/// bridge vote extension that was included in a block of the epoch. Keyed by
+/// epoch, then by validator.
+pub type ValidatorBridgeParticipation = NestedMap<Epoch, LazyMap<Address, u64>>;
+
+/// The number of block heights at which the Ethereum bridge vote extensions
+/// included in the blocks of an epoch were signed, per epoch.
+pub type BridgeBlocks = LazyMap<Epoch, u64>;
+
+/// The block heights at which the Ethereum bridge vote extensions included
+/// in the blocks of an epoch were signed, per epoch.
+pub type BridgeVextHeights = NestedMap<Epoch, LazySet<u64>>;
+
+/// The block heights at which each validator signed the Ethereum bridge vote
+/// extensions included in the blocks of an epoch. Keyed by epoch, then by
+/// validator.
+pub type ValidatorBridgeVextHeights =
+    NestedMap<Epoch, NestedMap<Address, LazySet<u64>>>;
+
 /// Epoched rewards products
 pub type RewardsProducts = LazyMap<Epoch, Dec>;
 
@@ -362,6 +419,10 @@ pub struct ValidatorMetaData {
     pub website: Option<String>,
     /// Validator's discord handle
     pub discord_handle: Option<String>,
+    /// Validator's moniker, i.e. its human-readable name
+    pub moniker: Option<String>,
+    /// Validator's contact for reporting security issues
+    pub security_contact: Option<String>,
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -372,6 +433,8 @@ impl Default for ValidatorMetaData {
             description: Default::default(),
             website: Default::default(),
             discord_handle: Default::default(),
+            moniker: Default::default(),
+            security_contact: Default::default(),
         }
     }
 }
@@ -578,6 +641,8 @@ pub enum SlashType {
     DuplicateVote,
     /// Light client attack.
     LightClientAttack,
+    /// Conflicting or invalid data signed in a vote extension.
+    InvalidVoteExtension,
 }
 
 /// VoteInfo inspired from tendermint for validators whose signature was
@@ -636,6 +701,139 @@ pub struct UnbondDetails {
     pub slashed_amount: Option<token::Amount>,
 }
 
+/// A redelegation whose tokens may still be slashed for an infraction of
+/// the source validator, such that they cannot be redelegated again yet
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq,
+)]
+pub struct PendingRedelegation {
+    /// The validator the tokens were redelegated from
+    pub src_validator: Address,
+    /// The validator the tokens were redelegated to
+    pub dest_validator: Address,
+    /// The first epoch in which the redelegated tokens contribute to the
+    /// stake of the destination validator
+    pub start: Epoch,
+    /// Redelegated token amount, after slashing
+    pub amount: token::Amount,
+    /// The first epoch in which the redelegated tokens may be redelegated
+    /// again
+    pub chainable_from: Epoch,
+}
+
+/// An unbond of a delegator, with the epoch from which its tokens may be
+/// withdrawn
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq,
+)]
+pub struct WithdrawableUnbond {
+    /// The validator the tokens were unbonded from
+    pub validator: Address,
+    /// The first epoch in which the source bond of this unbond contributed to
+    /// a stake
+    pub start: Epoch,
+    /// The first epoch in which this unbond can be withdrawn
+    pub withdraw: Epoch,
+    /// The number of epochs left until this unbond can be withdrawn, zero if
+    /// it already can be
+    pub epochs_left: u64,
+    /// Unbonded token amount, after slashing
+    pub amount: token::Amount,
+}
+
+/// A page of delegations, ordered by the address of their counterparty
+/// (the delegator for the delegations to a validator, or the validator for
+/// the delegations of a delegator)
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq,
+)]
+pub struct DelegationsPage {
+    /// The delegations on this page, with their bonded amount
+    pub delegations: Vec<(Address, token::Amount)>,
+    /// The address to query the next page after, if there may be more
+    /// delegations
+    pub next: Option<Address>,
+}
+
+/// The staking rewards projected for a bond to a validator over a year
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq,
+)]
+pub struct ProjectedRewards {
+    /// The annual percentage rate of the rewards, before the validator's
+    /// commission
+    pub gross_apr: Dec,
+    /// The validator's commission rate
+    pub commission_rate: Dec,
+    /// The annual percentage rate of the rewards, net of the validator's
+    /// commission
+    pub net_apr: Dec,
+    /// The amount of rewards projected for the bond over a year
+    pub annual_rewards: token::Amount,
+}
+
+/// The changes of the consensus validator set between two epochs
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+)]
+pub struct ValidatorSetDiff {
+    /// The validators that joined the consensus set, with their new stake
+    pub joined: BTreeMap<Address, token::Amount>,
+    /// The validators that left the consensus set, with their old stake
+    pub left: BTreeMap<Address, token::Amount>,
+    /// The validators that remained in the consensus set with a changed
+    /// stake, with their old and new stake
+    pub changed: BTreeMap<Address, (token::Amount, token::Amount)>,
+}
+
+impl ValidatorSetDiff {
+    /// Check if the consensus validator set did not change
+    pub fn is_empty(&self) -> bool {
+        self.joined.is_empty()
+            && self.left.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// The participation of the validators in the Ethereum bridge vote extensions
+/// in an epoch.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+)]
+pub struct BridgeParticipation {
+    /// The number of block heights at which the bridge vote extensions
+    /// included in the epoch were signed
+    pub bridge_blocks: u64,
+    /// The number of block heights at which each validator signed a bridge
+    /// vote extension included in the epoch
+    pub signed_blocks: BTreeMap<Address, u64>,
+}
+
+impl BridgeParticipation {
+    /// Get the fraction of the block heights with bridge vote extensions at
+    /// which the given validator signed one. Returns `None` if there were no
+    /// such heights.
+    pub fn participation_rate(&self, validator: &Address) -> Option<Dec> {
+        if self.bridge_blocks == 0 {
+            return None;
+        }
+        let signed = self.signed_blocks.get(validator).copied().unwrap_or(0);
+        Some(Dec::from(signed) / Dec::from(self.bridge_blocks))
+    }
+}
+
 impl Display for BondId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -655,6 +853,9 @@ impl SlashType {
             SlashType::LightClientAttack => {
                 params.light_client_attack_min_slash_rate
             }
+            SlashType::InvalidVoteExtension => {
+                params.duplicate_vote_min_slash_rate
+            }
         }
     }
 }
@@ -664,6 +865,9 @@ impl Display for SlashType {
         match self {
             SlashType::DuplicateVote => write!(f, "Duplicate vote"),
             SlashType::LightClientAttack => write!(f, "Light client attack"),
+            SlashType::InvalidVoteExtension => {
+                write!(f, "Invalid vote extension")
+            }
         }
     }
 }
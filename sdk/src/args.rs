@@ -5,8 +5,11 @@ use std::path::PathBuf;
 use std::time::Duration as StdDuration;
 
 use namada_core::ledger::governance::cli::onchain::{
-    DefaultProposal, PgfFundingProposal, PgfStewardProposal,
+    CodeAllowlistProposal, ConsensusParamsProposal, DefaultProposal,
+    ParameterChangeProposal, PgfFundingProposal, PgfStewardProposal,
+    TextProposal, TreasurySpendProposal,
 };
+use namada_core::ledger::governance::utils::ProposalState;
 use namada_core::types::address::Address;
 use namada_core::types::chain::ChainId;
 use namada_core::types::dec::Dec;
@@ -14,7 +17,7 @@ use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::keccak::KeccakHash;
 use namada_core::types::key::{common, SchemeType};
 use namada_core::types::masp::MaspValue;
-use namada_core::types::storage::Epoch;
+use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::time::DateTimeUtc;
 use namada_core::types::transaction::GasLimit;
 use namada_core::types::{storage, token};
@@ -419,6 +422,16 @@ pub struct InitProposal<C: NamadaTypes = SdkTypes> {
     pub is_pgf_stewards: bool,
     /// Flag if proposal is of type Pgf funding
     pub is_pgf_funding: bool,
+    /// Flag if proposal is of type parameter change
+    pub is_parameter_change: bool,
+    /// Flag if proposal is of type code allowlist
+    pub is_code_allowlist: bool,
+    /// Flag if proposal is of type consensus params
+    pub is_consensus_params: bool,
+    /// Flag if proposal is of type treasury spend
+    pub is_treasury_spend: bool,
+    /// Flag if proposal is of type text
+    pub is_text: bool,
     /// Path to the tx WASM file
     pub tx_code_path: PathBuf,
 }
@@ -473,6 +486,43 @@ impl<C: NamadaTypes> InitProposal<C> {
         }
     }
 
+    /// Flag if proposal is of type parameter change
+    pub fn is_parameter_change(self, is_parameter_change: bool) -> Self {
+        Self {
+            is_parameter_change,
+            ..self
+        }
+    }
+
+    /// Flag if proposal is of type code allowlist
+    pub fn is_code_allowlist(self, is_code_allowlist: bool) -> Self {
+        Self {
+            is_code_allowlist,
+            ..self
+        }
+    }
+
+    /// Flag if proposal is of type consensus params
+    pub fn is_consensus_params(self, is_consensus_params: bool) -> Self {
+        Self {
+            is_consensus_params,
+            ..self
+        }
+    }
+
+    /// Flag if proposal is of type treasury spend
+    pub fn is_treasury_spend(self, is_treasury_spend: bool) -> Self {
+        Self {
+            is_treasury_spend,
+            ..self
+        }
+    }
+
+    /// Flag if proposal is of type text
+    pub fn is_text(self, is_text: bool) -> Self {
+        Self { is_text, ..self }
+    }
+
     /// Path to the tx WASM file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -536,6 +586,144 @@ impl InitProposal {
                 })?;
 
             tx::build_pgf_stewards_proposal(context, self, proposal).await
+        } else if self.is_parameter_change {
+            let proposal = ParameterChangeProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_parameter_change_proposal(context, self, proposal).await
+        } else if self.is_code_allowlist {
+            let proposal = CodeAllowlistProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_code_allowlist_proposal(context, self, proposal).await
+        } else if self.is_consensus_params {
+            let proposal = ConsensusParamsProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_consensus_params_proposal(context, self, proposal).await
+        } else if self.is_treasury_spend {
+            let proposal = TreasurySpendProposal::try_from(
+                self.proposal_data.as_ref(),
+            )
+            .map_err(|e| {
+                crate::error::TxError::FailedGovernaneProposalDeserialize(
+                    e.to_string(),
+                )
+            })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_treasury_spend_proposal(context, self, proposal).await
+        } else if self.is_text {
+            let proposal = TextProposal::try_from(self.proposal_data.as_ref())
+                .map_err(|e| {
+                    crate::error::TxError::FailedGovernaneProposalDeserialize(
+                        e.to_string(),
+                    )
+                })?;
+            let nam_address = context.native_token();
+            let author_balance = rpc::get_token_balance(
+                context.client(),
+                &nam_address,
+                &proposal.proposal.author,
+            )
+            .await?;
+            let proposal = proposal
+                .validate(
+                    &governance_parameters,
+                    current_epoch,
+                    author_balance,
+                    self.tx.force,
+                )
+                .map_err(|e| {
+                    crate::error::TxError::InvalidProposal(e.to_string())
+                })?;
+
+            tx::build_text_proposal(context, self, proposal).await
         } else {
             let proposal = DefaultProposal::try_from(
                 self.proposal_data.as_ref(),
@@ -651,6 +839,59 @@ impl VoteProposal {
     }
 }
 
+/// Transaction to withdraw a proposal
+#[derive(Clone, Debug)]
+pub struct WithdrawProposal<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Proposal id
+    pub proposal_id: u64,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for WithdrawProposal<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        WithdrawProposal {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> WithdrawProposal<C> {
+    /// Proposal id
+    pub fn proposal_id(self, proposal_id: u64) -> Self {
+        Self {
+            proposal_id,
+            ..self
+        }
+    }
+
+    /// Path to the TX WASM code file
+    pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
+        Self {
+            tx_code_path,
+            ..self
+        }
+    }
+}
+
+impl WithdrawProposal {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        let current_epoch = rpc::query_epoch(context.client()).await?;
+        tx::build_withdraw_proposal(context, self, current_epoch).await
+    }
+}
+
 /// Transaction to initialize a new account
 #[derive(Clone, Debug)]
 pub struct TxInitAccount<C: NamadaTypes = SdkTypes> {
@@ -752,6 +993,10 @@ pub struct TxBecomeValidator<C: NamadaTypes = SdkTypes> {
     pub website: Option<String>,
     /// The validator's discord handle
     pub discord_handle: Option<String>,
+    /// The validator's moniker
+    pub moniker: Option<String>,
+    /// The validator's security contact
+    pub security_contact: Option<String>,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
     /// Don't encrypt the keypair
@@ -789,6 +1034,10 @@ pub struct TxInitValidator<C: NamadaTypes = SdkTypes> {
     pub website: Option<String>,
     /// The validator's discord handle
     pub discord_handle: Option<String>,
+    /// The validator's moniker
+    pub moniker: Option<String>,
+    /// The validator's security contact
+    pub security_contact: Option<String>,
     /// Path to the VP WASM code file
     pub validator_vp_code_path: PathBuf,
     /// Path to the TX WASM code file
@@ -814,6 +1063,9 @@ pub struct TxUpdateAccount<C: NamadaTypes = SdkTypes> {
     pub public_keys: Vec<C::PublicKey>,
     /// The account threshold
     pub threshold: Option<u8>,
+    /// Paths to the WASM code files of the txs allowed to act on the
+    /// storage of the account. An empty list lifts the restriction.
+    pub allowed_tx_code_paths: Option<Vec<PathBuf>>,
 }
 
 impl<C: NamadaTypes> TxBuilder<C> for TxUpdateAccount<C> {
@@ -865,6 +1117,18 @@ impl<C: NamadaTypes> TxUpdateAccount<C> {
             ..self
         }
     }
+
+    /// Paths to the WASM code files of the txs allowed to act on the
+    /// storage of the account
+    pub fn allowed_tx_code_paths(
+        self,
+        allowed_tx_code_paths: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            allowed_tx_code_paths: Some(allowed_tx_code_paths),
+            ..self
+        }
+    }
 }
 
 impl TxUpdateAccount {
@@ -968,6 +1232,8 @@ pub struct Unbond<C: NamadaTypes = SdkTypes> {
     /// Source address for unbonding from delegations. For unbonding from
     /// self-bonds, the validator is also the source
     pub source: Option<C::Address>,
+    /// Automatically withdraw the unbonded tokens once withdrawable
+    pub auto_withdraw: bool,
     /// Path to the TX WASM code file
     pub tx_code_path: PathBuf,
 }
@@ -1019,6 +1285,14 @@ impl<C: NamadaTypes> Unbond<C> {
         }
     }
 
+    /// Automatically withdraw the unbonded tokens once withdrawable
+    pub fn auto_withdraw(self, auto_withdraw: bool) -> Self {
+        Self {
+            auto_withdraw,
+            ..self
+        }
+    }
+
     /// Path to the TX WASM code file
     pub fn tx_code_path(self, tx_code_path: PathBuf) -> Self {
         Self {
@@ -1144,6 +1418,16 @@ pub struct QueryProposal<C: NamadaTypes = SdkTypes> {
     pub query: Query<C>,
     /// Proposal id
     pub proposal_id: Option<u64>,
+    /// Only list the proposals in this state
+    pub state: Option<ProposalState>,
+    /// Only list the proposals submitted by this author
+    pub author: Option<C::Address>,
+    /// Only list the proposals whose voting period ends at or after this
+    /// epoch
+    pub from_epoch: Option<Epoch>,
+    /// Only list the proposals whose voting period starts at or before this
+    /// epoch
+    pub to_epoch: Option<Epoch>,
 }
 
 /// Query protocol parameters
@@ -1258,6 +1542,114 @@ impl ClaimRewards {
     }
 }
 
+/// Auto-compound arguments
+#[derive(Clone, Debug)]
+pub struct AutoCompound<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Source address for delegations. For self-bonds, the validator is
+    /// also the source
+    pub source: Option<C::Address>,
+    /// Whether the rewards of the bond are automatically compounded
+    pub enabled: bool,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for AutoCompound<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        AutoCompound {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> AutoCompound<C> {
+    /// Source address for delegations
+    pub fn source(self, source: C::Address) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// Whether the rewards of the bond are automatically compounded
+    pub fn enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+}
+
+impl AutoCompound {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_auto_compound(context, self).await
+    }
+}
+
+/// Slashing insurance arguments
+#[derive(Clone, Debug)]
+pub struct SlashingInsurance<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address
+    pub validator: C::Address,
+    /// Source address for delegations. For self-bonds, the validator is
+    /// also the source
+    pub source: Option<C::Address>,
+    /// Whether the bond is insured against slashing
+    pub enabled: bool,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for SlashingInsurance<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        SlashingInsurance {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> SlashingInsurance<C> {
+    /// Source address for delegations
+    pub fn source(self, source: C::Address) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// Whether the bond is insured against slashing
+    pub fn enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+}
+
+impl SlashingInsurance {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        tx::build_slashing_insurance(context, self).await
+    }
+}
+
 /// Query asset conversions
 #[derive(Clone, Debug)]
 pub struct QueryConversions<C: NamadaTypes = SdkTypes> {
@@ -1276,6 +1668,9 @@ pub struct QueryAccount<C: NamadaTypes = SdkTypes> {
     pub query: Query<C>,
     /// Address of an owner
     pub owner: C::Address,
+    /// The height at which to query the account, if not the last committed
+    /// height
+    pub height: Option<BlockHeight>,
 }
 
 /// Query token balance(s)
@@ -1289,6 +1684,9 @@ pub struct QueryBalance<C: NamadaTypes = SdkTypes> {
     pub token: Option<C::Address>,
     /// Whether not to convert balances
     pub no_conversions: bool,
+    /// The height at which to query the transparent balance, if not the
+    /// last committed height
+    pub height: Option<BlockHeight>,
 }
 
 /// Query historical transfer(s)
@@ -1404,6 +1802,34 @@ pub struct ConsensusKeyChange<C: NamadaTypes = SdkTypes> {
     pub tx_code_path: PathBuf,
 }
 
+#[derive(Clone, Debug)]
+/// Protocol key change args
+pub struct ProtocolKeyChange<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address (should be self)
+    pub validator: C::Address,
+    /// New protocol key
+    pub protocol_key: Option<C::PublicKey>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+/// Ethereum bridge keys change args
+pub struct EthBridgeKeysChange<C: NamadaTypes = SdkTypes> {
+    /// Common tx arguments
+    pub tx: Tx<C>,
+    /// Validator address (should be self)
+    pub validator: C::Address,
+    /// New Eth bridge hot key
+    pub eth_hot_key: Option<C::PublicKey>,
+    /// New Eth bridge cold key
+    pub eth_cold_key: Option<C::PublicKey>,
+    /// Path to the TX WASM code file
+    pub tx_code_path: PathBuf,
+}
+
 // impl<C: NamadaTypes> TxBuilder<C> for ConsensusKeyChange<C> {
 //     fn tx<F>(self, func: F) -> Self
 //     where
@@ -1465,6 +1891,10 @@ pub struct MetaDataChange<C: NamadaTypes = SdkTypes> {
     pub website: Option<String>,
     /// New validator discord handle
     pub discord_handle: Option<String>,
+    /// New validator moniker
+    pub moniker: Option<String>,
+    /// New validator security contact
+    pub security_contact: Option<String>,
     /// New validator commission rate
     pub commission_rate: Option<Dec>,
     /// Path to the TX WASM code file
@@ -2298,6 +2728,55 @@ impl EthereumBridgePool {
     }
 }
 
+/// The cancellation of a transfer in the Ethereum bridge pool by its sender.
+#[derive(Clone, Debug)]
+pub struct CancelBridgePoolTransfer<C: NamadaTypes = SdkTypes> {
+    /// The args for building a tx to cancel the transfer
+    pub tx: Tx<C>,
+    /// The hash of the transfer to cancel
+    pub transfer_hash: KeccakHash,
+    /// Path to the tx WASM code file
+    pub code_path: PathBuf,
+}
+
+impl<C: NamadaTypes> TxBuilder<C> for CancelBridgePoolTransfer<C> {
+    fn tx<F>(self, func: F) -> Self
+    where
+        F: FnOnce(Tx<C>) -> Tx<C>,
+    {
+        CancelBridgePoolTransfer {
+            tx: func(self.tx),
+            ..self
+        }
+    }
+}
+
+impl<C: NamadaTypes> CancelBridgePoolTransfer<C> {
+    /// The hash of the transfer to cancel
+    pub fn transfer_hash(self, transfer_hash: KeccakHash) -> Self {
+        Self {
+            transfer_hash,
+            ..self
+        }
+    }
+
+    /// Path to the tx WASM code file
+    pub fn code_path(self, code_path: PathBuf) -> Self {
+        Self { code_path, ..self }
+    }
+}
+
+impl CancelBridgePoolTransfer {
+    /// Build a transaction from this builder
+    pub async fn build(
+        &self,
+        context: &impl Namada,
+    ) -> crate::error::Result<(crate::proto::Tx, SigningTxData, Option<Epoch>)>
+    {
+        bridge_pool::build_cancel_bridge_pool_tx(context, self).await
+    }
+}
+
 /// Bridge pool proof arguments.
 #[derive(Debug, Clone)]
 pub struct BridgePoolProof<C: NamadaTypes = SdkTypes> {
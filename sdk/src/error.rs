@@ -4,6 +4,7 @@ use namada_core::proto::Tx;
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::ethereum_events::EthAddress;
+use namada_core::types::keccak::KeccakHash;
 use namada_core::types::storage;
 use namada_core::types::storage::Epoch;
 use prost::EncodeError;
@@ -267,6 +268,11 @@ pub enum TxError {
     /// The proposal can't be found
     #[error("Proposal {0} can't be found")]
     ProposalDoesNotExist(u64),
+    /// The proposal can't be withdrawn
+    #[error(
+        "Proposal {0} can't be withdrawn after its voting period has started"
+    )]
+    InvalidProposalWithdrawalPeriod(u64),
     /// Updating an VP of an implicit account
     #[error(
         "A validity predicate of an implicit address cannot be directly \
@@ -324,6 +330,12 @@ pub enum TxError {
     /// An empty string was provided as a new email
     #[error("An empty string cannot be provided as a new email")]
     InvalidEmail,
+    /// A field of the validator metadata is too long
+    #[error(
+        "The validator {0} is {1} bytes long, exceeding the maximum length of \
+         {2} bytes"
+    )]
+    MetadataTooLong(String, usize, usize),
     /// The consensus key is not Ed25519
     #[error("The consensus key must be an ed25519 key")]
     ConsensusKeyNotEd25519,
@@ -353,6 +365,12 @@ pub enum EthereumBridgeError {
     /// Error querying transfer to Ethereum progress.
     #[error("Failed to query transfer to Ethereum progress: {0}")]
     TransferToEthProgress(String),
+    /// Error estimating the gas fee of a transfer to Ethereum.
+    #[error("Failed to estimate the Bridge pool gas fee: {0}")]
+    EstimateBridgePoolFee(String),
+    /// Error querying the last processed Ethereum block.
+    #[error("Failed to query the last processed Ethereum block: {0}")]
+    QueryEthereumHeight(String),
     /// Error querying Ethereum voting powers.
     #[error("Failed to query Ethereum voting powers: {0}")]
     QueryVotingPowers(String),
@@ -385,6 +403,9 @@ pub enum EthereumBridgeError {
     /// Transfer already in pool error.
     #[error("An identical transfer is already present in the Bridge pool")]
     TransferAlreadyInPool,
+    /// Transfer not in pool error.
+    #[error("The transfer {0} is not in the Bridge pool")]
+    TransferNotInPool(KeccakHash),
 }
 
 /// Checks if the given error is an invalid viewing key
@@ -5,16 +5,20 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use ethbridge_bridge_contract::Bridge;
 use ethers::providers::Middleware;
 use futures::future::FutureExt;
-use namada_core::ledger::eth_bridge::storage::bridge_pool::get_pending_key;
+use namada_core::ledger::eth_bridge::storage::bridge_pool::{
+    get_key_from_hash, get_pending_key,
+};
 use namada_core::ledger::eth_bridge::storage::wrapped_erc20s;
 use namada_core::types::address::{Address, InternalAddress};
 use namada_core::types::eth_abi::Encode;
 use namada_core::types::eth_bridge_pool::{
-    GasFee, PendingTransfer, TransferToEthereum, TransferToEthereumKind,
+    CancelTransfer, GasFee, PendingTransfer, TransferToEthereum,
+    TransferToEthereumKind,
 };
 use namada_core::types::ethereum_events::EthAddress;
 use namada_core::types::keccak::KeccakHash;
@@ -35,10 +39,13 @@ use crate::internal_macros::echo_error;
 use crate::io::Io;
 use crate::proto::Tx;
 use crate::queries::{
-    Client, GenBridgePoolProofReq, GenBridgePoolProofRsp, TransferToErcArgs,
-    RPC,
+    BridgePoolFeeEstimate, Client, EthereumHeights, GenBridgePoolProofReq,
+    GenBridgePoolProofRsp, TransferToErcArgs, RPC,
+};
+use crate::rpc::{
+    query_storage_value, query_storage_value_bytes, query_wasm_code_hash,
+    validate_amount,
 };
-use crate::rpc::{query_storage_value, query_wasm_code_hash, validate_amount};
 use crate::signing::aux_signing_data;
 use crate::tx::prepare_tx;
 use crate::{
@@ -111,6 +118,70 @@ pub async fn build_bridge_pool_tx(
     Ok((tx, signing_data, epoch))
 }
 
+/// Craft a transaction that cancels a transfer in the Ethereum bridge pool,
+/// on behalf of its sender.
+pub async fn build_cancel_bridge_pool_tx(
+    context: &impl Namada,
+    args::CancelBridgePoolTransfer {
+        tx: tx_args,
+        transfer_hash,
+        code_path,
+    }: &args::CancelBridgePoolTransfer,
+) -> Result<(Tx, SigningTxData, Option<Epoch>), Error> {
+    let (maybe_transfer, _) = query_storage_value_bytes(
+        context.client(),
+        &get_key_from_hash(transfer_hash),
+        None,
+        false,
+    )
+    .await?;
+    let transfer = maybe_transfer
+        .map(|bytes| PendingTransfer::try_from_slice(&bytes))
+        .transpose()
+        .map_err(|e| Error::Encode(EncodingError::Decoding(e.to_string())))?
+        .ok_or(Error::EthereumBridge(
+            EthereumBridgeError::TransferNotInPool(transfer_hash.clone()),
+        ))?;
+    let sender = transfer.transfer.sender;
+
+    let (tx_code_hash, signing_data) = futures::try_join!(
+        query_wasm_code_hash(context, code_path.to_string_lossy()),
+        aux_signing_data(
+            context,
+            tx_args,
+            // token owner
+            Some(sender.clone()),
+            // tx signer
+            Some(sender),
+        ),
+    )?;
+
+    let chain_id = tx_args
+        .chain_id
+        .clone()
+        .ok_or_else(|| Error::Other("No chain id available".into()))?;
+
+    let mut tx = Tx::new(chain_id, tx_args.expiration);
+    tx.add_code_from_hash(
+        tx_code_hash,
+        Some(code_path.to_string_lossy().into_owned()),
+    )
+    .add_data(CancelTransfer {
+        transfer_hash: transfer_hash.clone(),
+    });
+
+    let epoch = prepare_tx(
+        context,
+        tx_args,
+        &mut tx,
+        signing_data.fee_payer.clone(),
+        None,
+    )
+    .await?;
+
+    Ok((tx, signing_data, epoch))
+}
+
 /// Perform client validation checks on a Bridge pool transfer.
 #[allow(clippy::too_many_arguments)]
 async fn validate_bridge_pool_tx(
@@ -416,6 +487,64 @@ pub async fn query_relay_progress(
     Ok(())
 }
 
+/// Suggest a gas fee, paid in `gas_token`, for a new transfer to Ethereum,
+/// based on the contents of the Ethereum bridge pool and on the transfers
+/// being relayed.
+///
+/// Prints a json payload.
+pub async fn query_fee_estimate(
+    client: &(impl Client + Sync),
+    io: &impl Io,
+    gas_token: &Address,
+) -> Result<BridgePoolFeeEstimate, Error> {
+    let estimate = RPC
+        .shell()
+        .eth_bridge()
+        .estimate_bridge_pool_fee(client, gas_token)
+        .await
+        .map_err(|e| {
+            Error::EthereumBridge(EthereumBridgeError::EstimateBridgePoolFee(
+                e.to_string(),
+            ))
+        })?;
+    display_line!(
+        io,
+        "{}",
+        serde_json::to_string_pretty(&estimate)
+            .map_err(|e| EncodingError::Serde(e.to_string()))?
+    );
+    Ok(estimate)
+}
+
+/// Query the last Ethereum block processed as of the last committed
+/// block, along with the last Ethereum block processed by the oracle of
+/// the queried node, if it runs one. Relayers may use these to tell how
+/// far the view of Ethereum of the chain has advanced.
+///
+/// Prints a json payload.
+pub async fn query_ethereum_height(
+    client: &(impl Client + Sync),
+    io: &impl Io,
+) -> Result<EthereumHeights, Error> {
+    let heights = RPC
+        .shell()
+        .eth_bridge()
+        .read_ethereum_heights(client)
+        .await
+        .map_err(|e| {
+            Error::EthereumBridge(EthereumBridgeError::QueryEthereumHeight(
+                e.to_string(),
+            ))
+        })?;
+    display_line!(
+        io,
+        "{}",
+        serde_json::to_string_pretty(&heights)
+            .map_err(|e| EncodingError::Serde(e.to_string()))?
+    );
+    Ok(heights)
+}
+
 /// Internal methdod to construct a proof that a set of transfers are in the
 /// bridge pool.
 async fn construct_bridge_pool_proof(
@@ -0,0 +1,99 @@
+//! The index of applied txs, persisted by a node to look up the results of
+//! txs by their hash.
+
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::types::hash::Hash;
+use namada_core::types::storage::BlockHeight;
+
+use crate::events::{Event, EventLevel, EventType};
+
+/// The result of a tx, as indexed by its hash
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct IndexedTx {
+    /// The hash of the tx
+    pub hash: Hash,
+    /// The height of the block that included the tx
+    pub height: BlockHeight,
+    /// The result code of the tx, where `0` means success
+    pub code: u32,
+    /// The gas used by the tx
+    pub gas_used: u64,
+    /// The info of the tx result
+    pub info: String,
+    /// The hash of the wrapper of an inner tx, if any
+    pub wrapper_hash: Option<Hash>,
+    /// The hash of the inner tx of a wrapper, if any
+    pub inner_tx_hash: Option<Hash>,
+    /// The events emitted by the tx, ending with the event of its result
+    pub events: Vec<Event>,
+}
+
+impl IndexedTx {
+    /// Check if this is the result of a wrapper tx, as opposed to the result
+    /// of an applied inner or protocol tx.
+    pub fn is_wrapper(&self) -> bool {
+        self.events
+            .last()
+            .map(|event| event.event_type == EventType::Accepted)
+            .unwrap_or_default()
+    }
+
+    /// Check if the tx was successful.
+    pub fn is_successful(&self) -> bool {
+        self.code == 0
+    }
+}
+
+/// Index the results of the txs of a block at the given height from the
+/// events they emitted, in the order they were emitted. The events of each
+/// tx must be followed by the event of its result, i.e. an accepted event for
+/// a wrapper tx or an applied event otherwise. Results whose event doesn't
+/// have a valid hash are skipped. The txs are not linked to their wrapper or
+/// inner tx.
+pub fn index_block_txs(
+    height: BlockHeight,
+    events: &[Event],
+) -> Vec<IndexedTx> {
+    let mut indexed_txs = Vec::new();
+    let mut tx_events = Vec::new();
+    for event in events {
+        tx_events.push(event.clone());
+        let is_tx_result = event.level == EventLevel::Tx
+            && matches!(
+                event.event_type,
+                EventType::Accepted | EventType::Applied
+            );
+        if !is_tx_result {
+            continue;
+        }
+        let events = std::mem::take(&mut tx_events);
+        let Some(hash) = event
+            .get("hash")
+            .and_then(|hash| Hash::try_from(hash.as_str()).ok())
+        else {
+            continue;
+        };
+        indexed_txs.push(IndexedTx {
+            hash,
+            height,
+            code: parse_attr(event, "code"),
+            gas_used: parse_attr(event, "gas_used"),
+            info: event.get("info").cloned().unwrap_or_default(),
+            wrapper_hash: None,
+            inner_tx_hash: None,
+            events,
+        });
+    }
+    indexed_txs
+}
+
+/// Parse the attribute of an event with the given key, defaulting to zero if
+/// it's missing or invalid
+fn parse_attr<T: FromStr + Default>(event: &Event, key: &str) -> T {
+    event
+        .get(key)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
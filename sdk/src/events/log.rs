@@ -5,10 +5,16 @@
 //! old events are pruned.
 
 use std::default::Default;
+use std::fmt::Display;
+use std::str::FromStr;
 
+use borsh::{BorshDeserialize, BorshSerialize};
 use circular_queue::CircularQueue;
+use namada_core::types::address::Address;
+use namada_core::types::hash::Hash;
+use namada_core::types::storage::BlockHeight;
 
-use crate::events::Event;
+use crate::events::{Event, EventType};
 
 pub mod dumb_queries;
 
@@ -35,7 +41,94 @@ impl Default for Params {
 /// `FinalizeBlock` calls, in the ledger.
 #[derive(Debug)]
 pub struct EventLog {
-    queue: CircularQueue<Event>,
+    queue: CircularQueue<(EventId, Event)>,
+}
+
+/// The id of a logged [`Event`], given by the height of the block that
+/// emitted it and its index among the events of that block. Unlike the
+/// order in which events are logged, it doesn't depend on the node.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct EventId {
+    /// The height of the block that emitted the event
+    pub height: BlockHeight,
+    /// The index of the event among the events of its block
+    pub index: u64,
+}
+
+impl Display for EventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.height, self.index)
+    }
+}
+
+impl FromStr for EventId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (height, index) = s
+            .split_once('.')
+            .ok_or_else(|| format!("invalid event id {}", s))?;
+        Ok(Self {
+            height: height.parse().map_err(|err| {
+                format!("invalid block height of event id {}: {}", s, err)
+            })?,
+            index: index.parse().map_err(|err| {
+                format!("invalid index of event id {}: {}", s, err)
+            })?,
+        })
+    }
+}
+
+/// Filters of the events of an [`EventLog`] page.
+#[derive(Debug, Clone, Default)]
+pub struct EventsFilter {
+    /// Only match events of this type
+    pub event_type: Option<EventType>,
+    /// Only match events emitted by the transaction with this hash
+    pub tx_hash: Option<Hash>,
+    /// Only match events with an attribute set to this address
+    pub account: Option<Address>,
+}
+
+impl EventsFilter {
+    /// Check if the event matches all the filters.
+    pub fn matches(&self, event: &Event) -> bool {
+        if matches!(&self.event_type, Some(event_type) if event_type != &event.event_type)
+        {
+            return false;
+        }
+        if let Some(tx_hash) = &self.tx_hash {
+            if event.attributes.get("hash") != Some(&tx_hash.to_string()) {
+                return false;
+            }
+        }
+        if let Some(account) = &self.account {
+            let account = account.to_string();
+            if !event.attributes.values().any(|value| value == &account) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A page of the events of an [`EventLog`], ordered from the oldest
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct EventsPage {
+    /// The events on this page, with their id
+    pub events: Vec<(EventId, Event)>,
+    /// The id to query the next page after, if there may be more events
+    pub next: Option<EventId>,
 }
 
 impl Default for EventLog {
@@ -52,14 +145,15 @@ impl EventLog {
         }
     }
 
-    /// Log a new batch of events into the event log.
-    pub fn log_events<E>(&mut self, events: E)
+    /// Log the events emitted by the block at the given height into the
+    /// event log. All the events of a block must be logged at once.
+    pub fn log_events<E>(&mut self, height: BlockHeight, events: E)
     where
         E: IntoIterator<Item = Event>,
     {
         let mut num_entries = 0;
-        for event in events.into_iter() {
-            self.queue.push(event);
+        for (index, event) in (0..).zip(events) {
+            self.queue.push((EventId { height, index }, event));
             num_entries += 1;
         }
         tracing::debug!(num_entries, "Added new entries to the event log");
@@ -68,7 +162,7 @@ impl EventLog {
     /// Returns a new iterator over this [`EventLog`].
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &Event> {
-        self.queue.iter()
+        self.queue.iter().map(|(_, event)| event)
     }
 
     /// Returns a filtering iterator over this [`EventLog`].
@@ -77,9 +171,35 @@ impl EventLog {
         &self,
         matcher: dumb_queries::QueryMatcher,
     ) -> impl Iterator<Item = &Event> {
-        self.queue
-            .iter()
-            .filter(move |&event| matcher.matches(event))
+        self.iter().filter(move |&event| matcher.matches(event))
+    }
+
+    /// Returns a page of at most `limit` events matching the given filter,
+    /// from the oldest event logged after the event with the `after` id, if
+    /// any. Events pruned from the log are skipped.
+    pub fn get_page(
+        &self,
+        filter: &EventsFilter,
+        after: Option<EventId>,
+        limit: usize,
+    ) -> EventsPage {
+        let mut events = Vec::new();
+        let mut next = None;
+        let ids_and_events = self
+            .queue
+            .asc_iter()
+            .filter(|(id, _)| after.map(|after| *id > after).unwrap_or(true));
+        for (id, event) in ids_and_events {
+            if !filter.matches(event) {
+                continue;
+            }
+            if events.len() == limit {
+                next = events.last().map(|(id, _)| *id);
+                break;
+            }
+            events.push((*id, event.clone()));
+        }
+        EventsPage { events, next }
     }
 }
 
@@ -134,8 +254,8 @@ mod tests {
         // add new events to the log
         let events = mock_tx_events(HASH);
 
-        for _ in 0..NUM_HEIGHTS {
-            log.log_events(events.clone());
+        for height in 0..NUM_HEIGHTS {
+            log.log_events(BlockHeight(height as u64), events.clone());
         }
 
         // inspect log
@@ -173,8 +293,8 @@ mod tests {
         let events = mock_tx_events(HASH);
         assert_eq!(events.len(), 2);
 
-        for _ in 0..(LOG_CAP / 2) {
-            log.log_events(events.clone());
+        for height in 0..(LOG_CAP / 2) {
+            log.log_events(BlockHeight(height as u64), events.clone());
         }
 
         // inspect log - it should be full
@@ -189,7 +309,7 @@ mod tests {
 
         // add a new APPLIED event to the log,
         // pruning the first ACCEPTED event we added
-        log.log_events(Some(events[1].clone()));
+        log.log_events(BlockHeight(LOG_CAP as u64), Some(events[1].clone()));
 
         let events_in_log: Vec<_> =
             log.iter_with_matcher(accepted!(HASH)).cloned().collect();
@@ -201,4 +321,83 @@ mod tests {
             assert_eq!(events[0], event);
         }
     }
+    /// Test paging through the events of the log with filters.
+    #[test]
+    fn test_log_get_page() {
+        const OTHER_HASH: &str =
+            "CAFEBABECAFEBABECAFEBABECAFEBABECAFEBABECAFEBABECAFEBABECAFEBABE";
+
+        let mut log = EventLog::new(Params { max_log_events: 6 });
+        let no_filter = EventsFilter::default();
+        assert!(log.get_page(&no_filter, None, 10).events.is_empty());
+
+        // log the events of a couple of txs, with the first events of the
+        // log being pruned
+        for (height, hash) in (1..).zip([OTHER_HASH, HASH, OTHER_HASH, HASH]) {
+            log.log_events(BlockHeight(height), mock_tx_events(hash));
+        }
+        let id = |height: u64, index: u64| EventId {
+            height: BlockHeight(height),
+            index,
+        };
+        let ids = |page: &EventsPage| -> Vec<EventId> {
+            page.events.iter().map(|(id, _)| *id).collect()
+        };
+
+        // page through all the events in the log, from the oldest
+        let page = log.get_page(&no_filter, None, 4);
+        assert_eq!(ids(&page), vec![id(2, 0), id(2, 1), id(3, 0), id(3, 1)]);
+        assert_eq!(page.next, Some(id(3, 1)));
+        let page = log.get_page(&no_filter, page.next, 4);
+        assert_eq!(ids(&page), vec![id(4, 0), id(4, 1)]);
+        assert_eq!(page.next, None);
+        assert_eq!(
+            page.events,
+            vec![
+                (id(4, 0), mock_tx_events(HASH)[0].clone()),
+                (id(4, 1), mock_tx_events(HASH)[1].clone()),
+            ]
+        );
+
+        // the ids of the events round-trip through their string encoding
+        // in the queries' paths
+        assert_eq!(id(4, 1).to_string(), "4.1");
+        assert_eq!("4.1".parse::<EventId>(), Ok(id(4, 1)));
+        assert!("4".parse::<EventId>().is_err());
+
+        // filter the events by type and tx hash
+        let filter = EventsFilter {
+            event_type: Some(EventType::Accepted),
+            ..Default::default()
+        };
+        let page = log.get_page(&filter, None, 4);
+        assert_eq!(ids(&page), vec![id(2, 0), id(3, 0), id(4, 0)]);
+        let filter = EventsFilter {
+            event_type: Some(EventType::Applied),
+            tx_hash: Some(Hash::try_from(HASH).unwrap()),
+            ..Default::default()
+        };
+        let page = log.get_page(&filter, None, 1);
+        assert_eq!(ids(&page), vec![id(2, 1)]);
+        assert_eq!(page.next, Some(id(2, 1)));
+        let page = log.get_page(&filter, page.next, 1);
+        assert_eq!(ids(&page), vec![id(4, 1)]);
+        assert_eq!(page.next, None);
+
+        // filter the events by an account attribute
+        let account =
+            namada_core::types::address::testing::established_address_1();
+        let mut event = mock_tx_events(HASH).remove(0);
+        event
+            .attributes
+            .insert("source".to_string(), account.to_string());
+        log.log_events(BlockHeight(5), Some(event.clone()));
+        let filter = EventsFilter {
+            account: Some(account),
+            ..Default::default()
+        };
+        let page = log.get_page(&filter, None, 4);
+        assert_eq!(page.events, vec![(id(5, 0), event)]);
+        assert_eq!(page.next, None);
+    }
 }
@@ -1,4 +1,5 @@
 //! Logic to do with events emitted by the ledger.
+pub mod index;
 pub mod log;
 
 use std::collections::HashMap;
@@ -52,6 +53,34 @@ pub enum EventType {
     Proposal,
     /// The pgf payment
     PgfPayment,
+    /// The unbonded tokens automatically withdrawn at the start of an epoch
+    AutoWithdrawal,
+    /// The rewards automatically added to a bond at the start of an epoch
+    AutoCompound,
+    /// An Ethereum event which stayed queued up for inclusion in the vote
+    /// extensions of a validator for too many blocks
+    StalledEthEvent,
+    /// A validator jailed for missing too many votes in the liveness window
+    ValidatorJailed,
+    /// The tokens paid out by the slashing insurance pool to an insured bond
+    InsurancePayout,
+    /// A validator joining or leaving the consensus validator set, or
+    /// remaining in it with a changed stake, in the upcoming epoch
+    ValidatorSetChange,
+    /// A governance proposal was submitted
+    ProposalSubmitted,
+    /// The voting period of a governance proposal started
+    ProposalVotingStarted,
+    /// A vote was cast on a governance proposal
+    ProposalVote,
+    /// The votes of a governance proposal were tallied
+    ProposalTallied,
+    /// The payload of a passed governance proposal was executed
+    ProposalExecuted,
+    /// A governance proposal was withdrawn by its author
+    ProposalWithdrawn,
+    /// The treasury transfer of a passed treasury spend proposal
+    TreasurySpend,
 }
 
 impl Display for EventType {
@@ -62,6 +91,21 @@ impl Display for EventType {
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
             EventType::PgfPayment => write!(f, "pgf_payment"),
+            EventType::AutoWithdrawal => write!(f, "auto_withdrawal"),
+            EventType::AutoCompound => write!(f, "auto_compound"),
+            EventType::StalledEthEvent => write!(f, "stalled_eth_event"),
+            EventType::ValidatorJailed => write!(f, "validator_jailed"),
+            EventType::InsurancePayout => write!(f, "insurance_payout"),
+            EventType::ValidatorSetChange => write!(f, "validator_set_change"),
+            EventType::ProposalSubmitted => write!(f, "proposal_submitted"),
+            EventType::ProposalVotingStarted => {
+                write!(f, "proposal_voting_started")
+            }
+            EventType::ProposalVote => write!(f, "proposal_vote"),
+            EventType::ProposalTallied => write!(f, "proposal_tallied"),
+            EventType::ProposalExecuted => write!(f, "proposal_executed"),
+            EventType::ProposalWithdrawn => write!(f, "proposal_withdrawn"),
+            EventType::TreasurySpend => write!(f, "treasury_spend"),
         }?;
         Ok(())
     }
@@ -76,6 +120,19 @@ impl FromStr for EventType {
             "applied" => Ok(EventType::Applied),
             "proposal" => Ok(EventType::Proposal),
             "pgf_payments" => Ok(EventType::PgfPayment),
+            "auto_withdrawal" => Ok(EventType::AutoWithdrawal),
+            "auto_compound" => Ok(EventType::AutoCompound),
+            "stalled_eth_event" => Ok(EventType::StalledEthEvent),
+            "validator_jailed" => Ok(EventType::ValidatorJailed),
+            "insurance_payout" => Ok(EventType::InsurancePayout),
+            "validator_set_change" => Ok(EventType::ValidatorSetChange),
+            "proposal_submitted" => Ok(EventType::ProposalSubmitted),
+            "proposal_voting_started" => Ok(EventType::ProposalVotingStarted),
+            "proposal_vote" => Ok(EventType::ProposalVote),
+            "proposal_tallied" => Ok(EventType::ProposalTallied),
+            "proposal_executed" => Ok(EventType::ProposalExecuted),
+            "proposal_withdrawn" => Ok(EventType::ProposalWithdrawn),
+            "treasury_spend" => Ok(EventType::TreasurySpend),
             // IBC
             "update_client" => Ok(EventType::Ibc("update_client".to_string())),
             "send_packet" => Ok(EventType::Ibc("send_packet".to_string())),
@@ -39,6 +39,7 @@ use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_core::types::address::Address;
 use namada_core::types::dec::Dec;
 use namada_core::types::ethereum_events::EthAddress;
+use namada_core::types::keccak::KeccakHash;
 use namada_core::types::key::*;
 use namada_core::types::masp::{TransferSource, TransferTarget};
 use namada_core::types::token;
@@ -55,15 +56,18 @@ use crate::rpc::{
 use crate::signing::SigningTxData;
 use crate::token::DenominatedAmount;
 use crate::tx::{
-    ProcessTxResponse, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
-    TX_BRIDGE_POOL_WASM, TX_CHANGE_COMMISSION_WASM,
-    TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
+    ProcessTxResponse, TX_AUTO_COMPOUND_WASM, TX_BECOME_VALIDATOR_WASM,
+    TX_BOND_WASM, TX_BRIDGE_POOL_CANCEL_WASM, TX_BRIDGE_POOL_WASM,
+    TX_CHANGE_COMMISSION_WASM,
+    TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_ETH_BRIDGE_KEYS_WASM,
+    TX_CHANGE_METADATA_WASM, TX_CHANGE_PROTOCOL_KEY_WASM,
     TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
     TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
-    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK, TX_TRANSFER_WASM,
-    TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
-    TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM,
-    VP_USER_WASM,
+    TX_REDELEGATE_WASM, TX_RESIGN_STEWARD, TX_REVEAL_PK,
+    TX_SLASHING_INSURANCE_WASM, TX_TRANSFER_WASM, TX_UNBOND_WASM,
+    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM,
+    TX_UPDATE_STEWARD_COMMISSION, TX_VOTE_PROPOSAL, TX_WITHDRAW_PROPOSAL,
+    TX_WITHDRAW_WASM, VP_USER_WASM,
 };
 use crate::wallet::{Wallet, WalletIo, WalletStorage};
 
@@ -214,6 +218,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             validator,
             amount,
             source: None,
+            auto_withdraw: false,
             tx: self.tx_builder(),
             tx_code_path: PathBuf::from(TX_UNBOND_WASM),
         }
@@ -274,6 +279,11 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             is_offline: false,
             is_pgf_stewards: false,
             is_pgf_funding: false,
+            is_parameter_change: false,
+            is_code_allowlist: false,
+            is_consensus_params: false,
+            is_treasury_spend: false,
+            is_text: false,
             tx_code_path: PathBuf::from(TX_INIT_PROPOSAL),
             tx: self.tx_builder(),
         }
@@ -286,6 +296,7 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             vp_code_path: None,
             public_keys: vec![],
             threshold: None,
+            allowed_tx_code_paths: None,
             tx_code_path: PathBuf::from(TX_UPDATE_ACCOUNT_WASM),
             tx: self.tx_builder(),
         }
@@ -308,6 +319,19 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a WithdrawProposal builder from the given minimum set of
+    /// arguments
+    fn new_withdraw_proposal(
+        &self,
+        proposal_id: u64,
+    ) -> args::WithdrawProposal {
+        args::WithdrawProposal {
+            proposal_id,
+            tx_code_path: PathBuf::from(TX_WITHDRAW_PROPOSAL),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a CommissionRateChange builder from the given minimum set of
     /// arguments
     fn new_change_commission_rate(
@@ -336,6 +360,34 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make ProtocolKeyChange builder from the given minimum set of arguments
+    fn new_change_protocol_key(
+        &self,
+        validator: Address,
+    ) -> args::ProtocolKeyChange {
+        args::ProtocolKeyChange {
+            validator,
+            protocol_key: None,
+            tx_code_path: PathBuf::from(TX_CHANGE_PROTOCOL_KEY_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make EthBridgeKeysChange builder from the given minimum set of
+    /// arguments
+    fn new_change_eth_bridge_keys(
+        &self,
+        validator: Address,
+    ) -> args::EthBridgeKeysChange {
+        args::EthBridgeKeysChange {
+            validator,
+            eth_hot_key: None,
+            eth_cold_key: None,
+            tx_code_path: PathBuf::from(TX_CHANGE_ETH_BRIDGE_KEYS_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a CommissionRateChange builder from the given minimum set of
     /// arguments
     #[allow(clippy::too_many_arguments)]
@@ -346,6 +398,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         description: Option<String>,
         website: Option<String>,
         discord_handle: Option<String>,
+        moniker: Option<String>,
+        security_contact: Option<String>,
         commission_rate: Option<Dec>,
     ) -> args::MetaDataChange {
         args::MetaDataChange {
@@ -354,6 +408,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
             commission_rate,
             tx_code_path: PathBuf::from(TX_CHANGE_METADATA_WASM),
             tx: self.tx_builder(),
@@ -384,6 +440,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             description: None,
             website: None,
             discord_handle: None,
+            moniker: None,
+            security_contact: None,
         }
     }
 
@@ -415,6 +473,8 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
             description: None,
             website: None,
             discord_handle: None,
+            moniker: None,
+            security_contact: None,
         }
     }
 
@@ -476,6 +536,37 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make an Auto-compound builder from the given minimum set of arguments
+    fn new_auto_compound(
+        &self,
+        validator: Address,
+        enabled: bool,
+    ) -> args::AutoCompound {
+        args::AutoCompound {
+            validator,
+            source: None,
+            enabled,
+            tx_code_path: PathBuf::from(TX_AUTO_COMPOUND_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
+    /// Make a Slashing-insurance builder from the given minimum set of
+    /// arguments
+    fn new_slashing_insurance(
+        &self,
+        validator: Address,
+        enabled: bool,
+    ) -> args::SlashingInsurance {
+        args::SlashingInsurance {
+            validator,
+            source: None,
+            enabled,
+            tx_code_path: PathBuf::from(TX_SLASHING_INSURANCE_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a Withdraw builder from the given minimum set of arguments
     fn new_add_erc20_transfer(
         &self,
@@ -501,6 +592,19 @@ pub trait Namada: Sized + MaybeSync + MaybeSend {
         }
     }
 
+    /// Make a builder to cancel the transfer with the given hash in the
+    /// Ethereum bridge pool
+    fn new_cancel_erc20_transfer(
+        &self,
+        transfer_hash: KeccakHash,
+    ) -> args::CancelBridgePoolTransfer {
+        args::CancelBridgePoolTransfer {
+            transfer_hash,
+            code_path: PathBuf::from(TX_BRIDGE_POOL_CANCEL_WASM),
+            tx: self.tx_builder(),
+        }
+    }
+
     /// Make a ResignSteward builder from the given minimum set of arguments
     fn new_resign_steward(&self, steward: Address) -> args::ResignSteward {
         args::ResignSteward {
@@ -6,17 +6,32 @@ use namada_core::ledger::storage::traits::StorageHasher;
 use namada_core::ledger::storage::{DBIter, DB};
 use namada_core::ledger::storage_api;
 use namada_core::types::storage::BlockHeight;
-pub use shell::Shell;
 use shell::SHELL;
+pub use shell::{
+    AccountInfo, BatchQuery, BatchQueryResponse, Shell, StateDiff,
+    StateDiffValues, MAX_BATCH_QUERIES, MAX_BATCH_READ_KEYS,
+    MAX_BATCH_RESPONSE_BYTES, MAX_EVENTS_PER_PAGE, MAX_STATE_DIFF_BLOCKS,
+};
 pub use types::{
-    EncodedResponseQuery, Error, RequestCtx, RequestQuery, ResponseQuery,
-    Router,
+    EncodedResponseQuery, Error, PastHeightError, RequestCtx, RequestQuery,
+    ResponseQuery, Router, Shared, PAST_HEIGHT_ERROR_CODE,
 };
 use vp::{Vp, VP};
 
 pub use self::shell::eth_bridge::{
-    Erc20FlowControl, GenBridgePoolProofReq, GenBridgePoolProofRsp,
-    TransferToErcArgs,
+    BridgePoolFeeEstimate, Erc20FlowControl, EthereumHeights,
+    GenBridgePoolProofReq, GenBridgePoolProofRsp, TransferToErcArgs,
+    ValsetProofCache,
+};
+pub use self::shell::health::{CrashReport, CrashReports, MAX_CRASH_REPORTS};
+pub use self::shell::stats::{
+    DiscardedEvidenceMetrics, EthEventsQueueMetrics, EthRpcThrottleMetrics,
+    KeyPrefixWrites, NodeMetrics, NodeMetricsReport, WriteHotspots,
+    WriteHotspotsReport, MAX_WRITE_HOTSPOTS, WRITE_HOTSPOT_PREFIX_SEGMENTS,
+};
+pub use self::shell::tx_diff::{
+    AccountDiff, DryRunDiff, StateChange, TxStateDiff, BALANCES_SUBSPACE,
+    BONDS_SUBSPACE, UNBONDS_SUBSPACE,
 };
 use crate::{MaybeSend, MaybeSync};
 
@@ -118,6 +133,17 @@ mod testing {
         pub wl_storage: TestWlStorage,
         /// event log
         pub event_log: EventLog,
+        /// crash reports
+        pub crash_reports: CrashReports,
+        /// storage write hotspots
+        pub write_hotspots: WriteHotspots,
+        /// node-local metrics
+        pub node_metrics: NodeMetrics,
+        /// validator set update proofs cache
+        pub valset_proof_cache: ValsetProofCache,
+        /// last Ethereum block processed by the oracle
+        pub oracle_last_processed_block:
+            Option<namada_core::types::ethereum_structs::BlockHeight>,
     }
 
     impl<RPC> TestClient<RPC>
@@ -150,6 +176,11 @@ mod testing {
                 rpc,
                 wl_storage,
                 event_log,
+                crash_reports: CrashReports::default(),
+                write_hotspots: WriteHotspots::default(),
+                node_metrics: NodeMetrics::default(),
+                valset_proof_cache: ValsetProofCache::default(),
+                oracle_last_processed_block: None,
             }
         }
     }
@@ -189,6 +220,14 @@ mod testing {
                 vp_wasm_cache: (),
                 tx_wasm_cache: (),
                 storage_read_past_height_limit: None,
+                crash_reports: &self.crash_reports,
+                write_hotspots: &self.write_hotspots,
+                node_metrics: &self.node_metrics,
+                valset_proof_cache: &self.valset_proof_cache,
+                archive_endpoints: &[],
+                oracle_last_processed_block: self
+                    .oracle_last_processed_block
+                    .clone(),
             };
             // TODO: this is a hack to propagate errors to the caller, we should
             // really permit error types other than [`std::io::Error`]
@@ -470,6 +509,12 @@ impl<C: tendermint_rpc::client::Client + std::marker::Sync> Client for C {
                 info: response.info,
                 proof: response.proof,
             }),
+            Code::Err(code) if code.get() == PAST_HEIGHT_ERROR_CODE => {
+                match borsh::BorshDeserialize::try_from_slice(&response.value) {
+                    Ok(err) => Err(Error::PastHeight(err)),
+                    Err(_) => Err(Error::Query(response.info, code.into())),
+                }
+            }
             Code::Err(code) => Err(Error::Query(response.info, code.into())),
         }
     }
@@ -1029,6 +1029,12 @@ mod test {
             vp_wasm_cache: (),
             tx_wasm_cache: (),
             storage_read_past_height_limit: None,
+            crash_reports: &client.crash_reports,
+            write_hotspots: &client.write_hotspots,
+            node_metrics: &client.node_metrics,
+            valset_proof_cache: &client.valset_proof_cache,
+            archive_endpoints: &[],
+            oracle_last_processed_block: None,
         };
         let result = TEST_RPC.handle(ctx, &request);
         assert!(result.is_err());
@@ -1,34 +1,54 @@
-use std::collections::BTreeMap;
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub(super) mod eth_bridge;
+pub(super) mod health;
+pub(super) mod stats;
+pub(super) mod tx_diff;
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
 use namada_core::hints;
+use namada_core::ledger::eth_bridge::storage::{whitelist, wrapped_erc20s};
+use namada_core::ledger::ibc::storage::{
+    ibc_denom_key_prefix, is_ibc_denom_key,
+};
 use namada_core::ledger::storage::traits::StorageHasher;
-use namada_core::ledger::storage::{DBIter, LastBlock, DB};
+use namada_core::ledger::storage::{DBIter, LastBlock, WlStorage, DB};
 use namada_core::ledger::storage_api::{self, ResultExt, StorageRead};
 use namada_core::types::account::{Account, AccountPublicKeysMap};
-use namada_core::types::address::Address;
+use namada_core::types::address::{Address, InternalAddress};
 use namada_core::types::hash::Hash;
+use namada_core::types::ibc::IbcTokenHash;
+use namada_core::types::key::{self, common};
 use namada_core::types::storage::{
-    self, BlockHeight, BlockResults, Epoch, KeySeg, PrefixValue,
+    self, BlockHeight, BlockResults, Epoch, PrefixValue,
 };
-use namada_core::types::token::MaspDenom;
+use namada_core::types::token::{self, MaspDenom};
 #[cfg(any(test, feature = "async-client"))]
-use namada_core::types::transaction::TxResult;
+use namada_core::types::transaction::{TxResult, ViewTxResult};
 
 use self::eth_bridge::{EthBridge, ETH_BRIDGE};
-use crate::events::log::dumb_queries;
+use self::health::{Health, HEALTH};
+use self::stats::{Stats, STATS};
+#[cfg(any(test, feature = "async-client"))]
+use self::tx_diff::DryRunDiff;
+use crate::events::index::IndexedTx;
+use crate::events::log::{dumb_queries, EventId, EventsFilter, EventsPage};
 use crate::events::{Event, EventType};
 use crate::ibc::core::host::types::identifiers::{
     ChannelId, ClientId, PortId, Sequence,
 };
-use crate::queries::types::{RequestCtx, RequestQuery};
-use crate::queries::{require_latest_height, EncodedResponseQuery};
+use crate::queries::types::{
+    PastHeightError, RequestCtx, RequestQuery, PAST_HEIGHT_ERROR_CODE,
+};
+use crate::queries::vp::pos::{bonds_summary, BondsSummary};
+use crate::queries::{
+    require_latest_height, require_no_proof, EncodedResponseQuery, Router, RPC,
+};
 use crate::tendermint::merkle::proof::ProofOps;
 
 type ConversionWithoutPath = (
@@ -45,12 +65,100 @@ type Conversion = (
     MerklePath<Node>,
 );
 
+/// The maximum number of blocks that may be spanned by a single state diff
+/// query.
+pub const MAX_STATE_DIFF_BLOCKS: u64 = 1_000;
+
+/// The maximum number of storage keys that may be read by a single batched
+/// storage read query.
+pub const MAX_BATCH_READ_KEYS: usize = 256;
+
+/// The maximum number of queries that may be dispatched by a single batched
+/// query.
+pub const MAX_BATCH_QUERIES: usize = 64;
+
+/// The maximum total size in bytes of the responses to the queries of a
+/// single batched query.
+pub const MAX_BATCH_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// The maximum number of events that may be returned by a single page of an
+/// events query.
+pub const MAX_EVENTS_PER_PAGE: u64 = 100;
+
+/// A query dispatched as part of a batched query.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct BatchQuery {
+    /// The path of the query
+    pub path: String,
+    /// The request data of the query
+    pub data: Vec<u8>,
+    /// The height of the query, where `0` is the last committed height
+    pub height: BlockHeight,
+}
+
+/// The response to a [`BatchQuery`], in the same order as the queries of
+/// the batch.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct BatchQueryResponse {
+    /// The ABCI response code, where `0` means success
+    pub code: u32,
+    /// The borsh-encoded response data
+    pub data: Vec<u8>,
+    /// The log of the query execution
+    pub info: String,
+}
+
+/// A storage key whose value changed between two block heights.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StateDiff {
+    /// The changed key
+    pub key: storage::Key,
+    /// The values of the key at both heights, if they were requested
+    pub values: Option<StateDiffValues>,
+}
+
+/// The values of a key at both ends of a [`StateDiff`]. A value of [`None`]
+/// means that the key was not present at that height.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StateDiffValues {
+    /// The value at the lower height
+    pub old: Option<Vec<u8>>,
+    /// The value at the higher height
+    pub new: Option<Vec<u8>>,
+}
+
+/// The info of an account, aggregated from its storage and from the storage
+/// of the native VPs.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct AccountInfo {
+    /// The hash of the code of the validity predicate of the account, if it
+    /// exists. Implicit accounts have the VP from the protocol parameters.
+    pub vp_code_hash: Option<Hash>,
+    /// The public keys and threshold of the account, if it exists
+    pub account: Option<Account>,
+    /// The balance of the native token
+    pub native_balance: token::Amount,
+    /// The non-zero balances of the known tokens held by the account,
+    /// including the native token. Other user-defined tokens have to be
+    /// queried by their balance keys.
+    pub balances: BTreeMap<Address, token::Amount>,
+    /// A summary of the bonds and unbonds of the account, in the last
+    /// committed epoch
+    pub bonds: BondsSummary,
+}
+
 router! {SHELL,
     // Shell provides storage read access, block metadata and can dry-run a tx
 
     // Ethereum bridge specific queries
     ( "eth_bridge" ) = (sub ETH_BRIDGE),
 
+    // Node health queries
+    ( "health" ) = (sub HEALTH),
+
+    // Node statistics queries
+    ( "stats" ) = (sub STATS),
+
     // Epoch of the last committed block
     ( "epoch" ) -> Epoch = epoch,
 
@@ -67,9 +175,25 @@ router! {SHELL,
     ( "value" / [storage_key: storage::Key] )
         -> Vec<u8> = (with_options storage_value),
 
+    // Raw storage access - read the values of a batch of keys, which must be
+    // passed in the request data
+    ( "values" ) -> Vec<Option<Vec<u8>>> = (with_options storage_values),
+
+    // Dispatch a batch of queries, which must be passed in the request data,
+    // and return their responses in the same order. Batches can't be nested
+    // and the queries dispatched by the ledger's shell fail when batched.
+    ( "batch" ) -> Vec<BatchQueryResponse> = (with_options batch),
+
     // Dry run a transaction
     ( "dry_run_tx" ) -> TxResult = (with_options dry_run_tx),
 
+    // Dry run a transaction, returning a diff of its storage changes grouped
+    // by account and subspace
+    ( "dry_run_tx_diff" ) -> DryRunDiff = (with_options dry_run_tx_diff),
+
+    // Execute a read-only view transaction against committed state
+    ( "view_tx" ) -> ViewTxResult = (with_options view_tx),
+
     // Raw storage access - prefix iterator
     ( "prefix" / [storage_key: storage::Key] )
         -> Vec<PrefixValue> = (with_options storage_prefix),
@@ -78,6 +202,10 @@ router! {SHELL,
     ( "has_key" / [storage_key: storage::Key] )
         -> bool = storage_has_key,
 
+    // Raw storage access - keys changed between two retained heights
+    ( "state_diff" / [from: BlockHeight] / [to: BlockHeight] / [with_values: bool] )
+        -> Vec<StateDiff> = state_diff,
+
     // Conversion state access - read conversion
     ( "conv" / [asset_type: AssetType] ) -> Conversion = read_conversion,
 
@@ -93,9 +221,21 @@ router! {SHELL,
     // was the transaction applied?
     ( "applied" / [tx_hash: Hash] ) -> Option<Event> = applied,
 
+    // The result of a tx from the index of applied txs, resolving a wrapper
+    // to the result of its inner tx once it's applied
+    ( "tx" / [tx_hash: Hash] ) -> Option<IndexedTx> = indexed_tx,
+
     // Query account subspace
     ( "account" / [owner: Address] ) -> Option<Account> = account,
 
+    // Query the account of an owner at the end of the block at the given
+    // height
+    ( "account_at_height" / [owner: Address] / [height: BlockHeight] ) -> Option<Account> = account_at_height,
+
+    // Query the VP, public keys, token balances and bonds of an account at
+    // once
+    ( "account_info" / [owner: Address] ) -> AccountInfo = account_info,
+
     // Query public key revealad
     ( "revealed" / [owner: Address] ) -> bool = revealed,
 
@@ -104,19 +244,155 @@ router! {SHELL,
 
     // IBC packet event
     ( "ibc_packet" / [event_type: EventType] / [source_port: PortId] / [source_channel: ChannelId] / [destination_port: PortId] / [destination_channel: ChannelId] / [sequence: Sequence]) -> Option<Event> = ibc_packet,
+
+    // A page of the logged events matching the given filters, starting after
+    // the event with the `after` id, if any
+    ( "events" / [limit: u64] / [after: opt EventId] / "type" / [event_type: opt EventType] / "tx" / [tx_hash: opt Hash] / "account" / [account: opt Address] )
+        -> EventsPage = events_page,
 }
 
 // Handlers:
 
+fn batch<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let invalid_input = |msg: String| {
+        storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            msg,
+        ))
+    };
+    require_no_proof(request)?;
+    let queries: Vec<BatchQuery> =
+        BorshDeserialize::try_from_slice(&request.data)
+            .into_storage_result()?;
+    if queries.len() > MAX_BATCH_QUERIES {
+        return Err(invalid_input(format!(
+            "Cannot dispatch more than {MAX_BATCH_QUERIES} queries at once, \
+             but {} were requested.",
+            queries.len()
+        )));
+    }
+    // NB: the batched queries only need read access to the state, so the
+    // wasm caches are left out of their context
+    let sub_ctx = || RequestCtx {
+        wl_storage: ctx.wl_storage,
+        event_log: ctx.event_log,
+        vp_wasm_cache: (),
+        tx_wasm_cache: (),
+        storage_read_past_height_limit: ctx.storage_read_past_height_limit,
+        crash_reports: ctx.crash_reports,
+        write_hotspots: ctx.write_hotspots,
+        node_metrics: ctx.node_metrics,
+        valset_proof_cache: ctx.valset_proof_cache,
+        archive_endpoints: ctx.archive_endpoints,
+        oracle_last_processed_block: ctx.oracle_last_processed_block.clone(),
+    };
+
+    let mut responses = Vec::with_capacity(queries.len());
+    let mut response_bytes = 0_usize;
+    for BatchQuery { path, data, height } in queries {
+        // nested batches would let a request recurse as deep as its size
+        // allows. As the router accepts an optional trailing slash, the path
+        // is compared without it.
+        if path.trim_end_matches('/') == RPC.shell().batch_path() {
+            return Err(invalid_input("Batches can't be nested.".into()));
+        }
+        let Ok(height) = height.try_into() else {
+            return Err(invalid_input(format!("Invalid height {height}.")));
+        };
+        let query = RequestQuery {
+            data: data.into(),
+            path,
+            height,
+            prove: false,
+        };
+        let response = match RPC.handle(sub_ctx(), &query) {
+            Ok(response) => BatchQueryResponse {
+                code: 0,
+                data: response.data,
+                info: response.info,
+            },
+            Err(err) => match err.downcast::<PastHeightError>() {
+                Ok(err) => BatchQueryResponse {
+                    code: PAST_HEIGHT_ERROR_CODE,
+                    info: format!("RPC error: {}", err),
+                    data: err.serialize_to_vec(),
+                },
+                Err(err) => BatchQueryResponse {
+                    code: 1,
+                    info: format!("RPC error: {}", err),
+                    data: vec![],
+                },
+            },
+        };
+        response_bytes += response.data.len() + response.info.len();
+        if response_bytes > MAX_BATCH_RESPONSE_BYTES {
+            return Err(invalid_input(format!(
+                "The responses of the batched queries exceed the limit of \
+                 {MAX_BATCH_RESPONSE_BYTES} bytes."
+            )));
+        }
+        responses.push(response);
+    }
+    Ok(EncodedResponseQuery {
+        data: responses.serialize_to_vec(),
+        ..Default::default()
+    })
+}
+
+/// Reject a query that has to be dispatched by the ledger's shell, because
+/// it needs to run wasm. Only the routes of these queries are defined by the
+/// router, so that they can be called by the client, and a request that
+/// reaches the router, e.g. as part of a batch, fails.
+fn dispatched_by_shell(
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery> {
+    Err(storage_api::Error::new(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!(
+            "The query {} can only be dispatched by the ledger's shell.",
+            request.path
+        ),
+    )))
+}
+
 fn dry_run_tx<D, H, V, T>(
     _ctx: RequestCtx<'_, D, H, V, T>,
-    _request: &RequestQuery,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    dispatched_by_shell(request)
+}
+
+fn dry_run_tx_diff<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    dispatched_by_shell(request)
+}
+
+fn view_tx<D, H, V, T>(
+    _ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
 ) -> storage_api::Result<EncodedResponseQuery>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    unimplemented!("Dry running tx requires \"wasm-runtime\" feature.")
+    dispatched_by_shell(request)
 }
 
 /// Query to read block results from storage
@@ -278,18 +554,7 @@ where
         }
     };
 
-    if let Some(past_height_limit) = ctx.storage_read_past_height_limit {
-        if queried_height + past_height_limit < last_committed_height {
-            return Err(storage_api::Error::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                format!(
-                    "Cannot query more than {past_height_limit} blocks in the \
-                     past (configured via \
-                     `shell.storage_read_past_height_limit`)."
-                ),
-            )));
-        }
-    }
+    check_past_height_limit(&ctx, queried_height)?;
 
     match ctx
         .wl_storage
@@ -334,6 +599,79 @@ where
     }
 }
 
+/// Read the values of a batch of storage keys, passed as borsh-encoded
+/// request data. The values are returned in the same order as the keys, with
+/// `None` for keys that are not present. When a proof is requested, the
+/// response contains the concatenated proof ops of every key, in order.
+fn storage_values<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let storage_keys: Vec<storage::Key> =
+        BorshDeserialize::try_from_slice(&request.data)
+            .into_storage_result()?;
+    if storage_keys.len() > MAX_BATCH_READ_KEYS {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Cannot read more than {MAX_BATCH_READ_KEYS} keys at once, \
+                 but {} were requested.",
+                storage_keys.len()
+            ),
+        )));
+    }
+
+    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
+    let queried_height = {
+        let height: BlockHeight = request.height.into();
+        let is_last_height_query = height.0 == 0;
+
+        if hints::likely(is_last_height_query) {
+            last_committed_height
+        } else {
+            height
+        }
+    };
+
+    check_past_height_limit(&ctx, queried_height)?;
+
+    let mut values = Vec::with_capacity(storage_keys.len());
+    let mut ops = vec![];
+    for storage_key in &storage_keys {
+        let (value, _gas) = ctx
+            .wl_storage
+            .storage
+            .read_with_height(storage_key, queried_height)
+            .into_storage_result()?;
+        if request.prove {
+            let storage = &ctx.wl_storage.storage;
+            let mut proof = match &value {
+                Some(value) => storage.get_existence_proof(
+                    storage_key,
+                    value,
+                    queried_height,
+                ),
+                None => {
+                    storage.get_non_existence_proof(storage_key, queried_height)
+                }
+            }
+            .into_storage_result()?;
+            ops.append(&mut proof.ops);
+        }
+        values.push(value);
+    }
+    let proof = request.prove.then_some(ProofOps { ops });
+    Ok(EncodedResponseQuery {
+        data: values.serialize_to_vec(),
+        proof,
+        ..Default::default()
+    })
+}
+
 fn storage_prefix<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     request: &RequestQuery,
@@ -345,7 +683,8 @@ where
 {
     require_latest_height(&ctx, request)?;
 
-    let iter = storage_api::iter_prefix_bytes(ctx.wl_storage, &storage_key)?;
+    let snapshot = ctx.committed_snapshot();
+    let iter = storage_api::iter_prefix_bytes(&snapshot, &storage_key)?;
     let data: storage_api::Result<Vec<PrefixValue>> = iter
         .map(|iter_result| {
             let (key, value) = iter_result?;
@@ -367,6 +706,7 @@ where
                 height
             }
         };
+        check_past_height_limit(&ctx, queried_height)?;
         let mut ops = vec![];
         for PrefixValue { key, value } in &data {
             let mut proof = ctx
@@ -398,10 +738,147 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let data = StorageRead::has_key(ctx.wl_storage, &storage_key)?;
+    let data = StorageRead::has_key(&ctx.committed_snapshot(), &storage_key)?;
     Ok(data)
 }
 
+/// Find the keys whose values changed after the block at height `from`,
+/// up to and including the block at height `to`.
+fn state_diff<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from: BlockHeight,
+    to: BlockHeight,
+    with_values: bool,
+) -> storage_api::Result<Vec<StateDiff>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let invalid_input = |msg: String| {
+        storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            msg,
+        ))
+    };
+    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
+    if from > to || to > last_committed_height {
+        return Err(invalid_input(format!(
+            "Invalid height range from {from} to {to}. The last committed \
+             height is {last_committed_height}."
+        )));
+    }
+    if to.0 - from.0 > MAX_STATE_DIFF_BLOCKS {
+        return Err(invalid_input(format!(
+            "Cannot query the state diff of more than {MAX_STATE_DIFF_BLOCKS} \
+             blocks at once."
+        )));
+    }
+    check_past_height_limit(&ctx, from)?;
+
+    // The values of each changed key before the first change and after the
+    // last change within the range
+    let mut diffs: BTreeMap<storage::Key, StateDiffValues> = BTreeMap::new();
+    let db = &ctx.wl_storage.storage.db;
+    // the empty key matches all the diffs written at a given height
+    let all_keys = storage::Key::default();
+    for height in (from.0 + 1)..=to.0 {
+        let height = BlockHeight(height);
+        let mut old_values: BTreeMap<storage::Key, Vec<u8>> = BTreeMap::new();
+        for (key, value, _gas) in db.iter_old_diffs(height, Some(&all_keys)) {
+            let key = storage::Key::parse(key).into_storage_result()?;
+            old_values.insert(key, value);
+        }
+        let mut new_values: BTreeMap<storage::Key, Vec<u8>> = BTreeMap::new();
+        for (key, value, _gas) in db.iter_new_diffs(height, Some(&all_keys)) {
+            let key = storage::Key::parse(key).into_storage_result()?;
+            new_values.insert(key, value);
+        }
+        let changed_keys: BTreeSet<storage::Key> = old_values
+            .keys()
+            .chain(new_values.keys())
+            .cloned()
+            .collect();
+        for key in changed_keys {
+            let new = new_values.remove(&key);
+            diffs
+                .entry(key)
+                .and_modify(|values| values.new = new.clone())
+                .or_insert_with_key(|key| StateDiffValues {
+                    old: old_values.remove(key),
+                    new,
+                });
+        }
+    }
+
+    Ok(diffs
+        .into_iter()
+        // keys which were changed and then restored are left out
+        .filter(|(_, values)| values.old != values.new)
+        .map(|(key, values)| StateDiff {
+            key,
+            values: with_values.then_some(values),
+        })
+        .collect())
+}
+
+/// Check that the state at `queried_height` is still available, i.e. that it
+/// is within the `storage_read_past_height_limit` of the last committed
+/// height and that it hasn't been pruned from storage. Otherwise, a
+/// [`PastHeightError`] is returned.
+fn check_past_height_limit<D, H, V, T>(
+    ctx: &RequestCtx<'_, D, H, V, T>,
+    queried_height: BlockHeight,
+) -> storage_api::Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
+    // the state below the oldest retained height may have been pruned
+    let oldest_retained_height = ctx.wl_storage.storage.get_oldest_height();
+    let earliest_height = match ctx.storage_read_past_height_limit {
+        Some(past_height_limit) => cmp::max(
+            oldest_retained_height,
+            BlockHeight(
+                last_committed_height.0.saturating_sub(past_height_limit),
+            ),
+        ),
+        None => oldest_retained_height,
+    };
+    if queried_height >= earliest_height {
+        return Ok(());
+    }
+    Err(storage_api::Error::new(PastHeightError {
+        requested_height: queried_height,
+        earliest_height,
+        archive_endpoints: ctx.archive_endpoints.to_vec(),
+    }))
+}
+
+/// Check that the given `height` has been committed, and that its state is
+/// still available, to read the state at the end of the block at `height`
+/// from the stored diffs.
+pub(crate) fn check_committed_height<D, H, V, T>(
+    ctx: &RequestCtx<'_, D, H, V, T>,
+    height: BlockHeight,
+) -> storage_api::Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let last_committed_height = ctx.wl_storage.storage.get_last_block_height();
+    if height.0 == 0 || height > last_committed_height {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid height {height}. The last committed height is \
+                 {last_committed_height}."
+            ),
+        )));
+    }
+    check_past_height_limit(ctx, height)
+}
+
 fn accepted<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     tx_hash: Hash,
@@ -436,6 +913,37 @@ where
         .cloned())
 }
 
+fn indexed_tx<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    tx_hash: Hash,
+) -> storage_api::Result<Option<IndexedTx>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let read_indexed_tx = |hash: &Hash| -> storage_api::Result<_> {
+        let bytes = ctx
+            .wl_storage
+            .storage
+            .db
+            .read_indexed_tx(hash)
+            .into_storage_result()?;
+        bytes
+            .map(|bytes| IndexedTx::try_from_slice(&bytes))
+            .transpose()
+            .into_storage_result()
+    };
+    let Some(indexed_tx) = read_indexed_tx(&tx_hash)? else {
+        return Ok(None);
+    };
+    if let Some(inner_tx_hash) = &indexed_tx.inner_tx_hash {
+        if let Some(inner_tx) = read_indexed_tx(inner_tx_hash)? {
+            return Ok(Some(inner_tx));
+        }
+    }
+    Ok(Some(indexed_tx))
+}
+
 fn ibc_client_update<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     client_id: ClientId,
@@ -486,6 +994,37 @@ where
         .cloned())
 }
 
+/// A page of the logged events matching the given filters, ordered from the
+/// oldest, starting after the `after` id, if any
+fn events_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    limit: u64,
+    after: Option<EventId>,
+    event_type: Option<EventType>,
+    tx_hash: Option<Hash>,
+    account: Option<Address>,
+) -> storage_api::Result<EventsPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if limit == 0 || limit > MAX_EVENTS_PER_PAGE {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid page size {limit}. Between 1 and \
+                 {MAX_EVENTS_PER_PAGE} events may be queried at once."
+            ),
+        )));
+    }
+    let filter = EventsFilter {
+        event_type,
+        tx_hash,
+        account,
+    };
+    Ok(ctx.event_log.get_page(&filter, after, limit as usize))
+}
+
 fn account<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     owner: Address,
@@ -512,6 +1051,143 @@ where
     }
 }
 
+/// Get the account of the given `owner` at the end of the block at the given
+/// `height`, read from the stored diffs of its VP, public keys and threshold.
+/// The height must be within the `storage_read_past_height_limit` of the last
+/// committed height.
+fn account_at_height<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Account>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_committed_height(&ctx, height)?;
+    let read = |key: &storage::Key| {
+        ctx.wl_storage
+            .storage
+            .read_with_height(key, height)
+            .into_storage_result()
+            .map(|(value, _gas)| value)
+    };
+
+    let account_exists = match &owner {
+        Address::Established(_) => {
+            read(&storage::Key::validity_predicate(&owner))?.is_some()
+        }
+        Address::Implicit(_) => true,
+        Address::Internal(_) => false,
+    };
+    if !account_exists {
+        return Ok(None);
+    }
+    // the public keys of an account are indexed from 0, without gaps
+    let pks = key::pks_handle(&owner);
+    let mut public_keys = vec![];
+    for index in 0..=u8::MAX {
+        let Some(bytes) = read(&pks.get_data_key(&index))? else {
+            break;
+        };
+        public_keys.push(
+            common::PublicKey::try_from_slice(&bytes).into_storage_result()?,
+        );
+    }
+    let threshold = read(&key::threshold_key(&owner))?
+        .map(|bytes| u8::try_from_slice(&bytes).into_storage_result())
+        .transpose()?;
+
+    Ok(Some(Account {
+        public_keys_map: AccountPublicKeysMap::from_iter(public_keys),
+        address: owner,
+        threshold: threshold.unwrap_or(1),
+    }))
+}
+
+fn account_info<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> storage_api::Result<AccountInfo>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let wl_storage = ctx.wl_storage;
+    let (vp_code_hash, _gas) = wl_storage
+        .storage
+        .validity_predicate(&owner)
+        .into_storage_result()?;
+
+    let mut balances = BTreeMap::new();
+    for token in known_tokens(wl_storage)? {
+        let balance =
+            storage_api::token::read_balance(wl_storage, &token, &owner)?;
+        if !balance.is_zero() {
+            balances.insert(token, balance);
+        }
+    }
+    let native_balance = balances
+        .get(&wl_storage.storage.native_token)
+        .copied()
+        .unwrap_or_default();
+
+    let bonds =
+        bonds_summary(wl_storage, &owner, wl_storage.storage.last_epoch)?;
+    let account = account(ctx, owner)?;
+
+    Ok(AccountInfo {
+        vp_code_hash,
+        account,
+        native_balance,
+        balances,
+        bonds,
+    })
+}
+
+/// Collect the tokens whose balances are reported by [`account_info`], so
+/// that an owner's balances can be read key by key instead of scanning the
+/// balances of every account. These are the native token, the genesis tokens
+/// with MASP rewards, the wrapped ERC20s (and their NUTs) of the Ethereum
+/// bridge whitelist and the tokens received over IBC.
+fn known_tokens<D, H>(
+    wl_storage: &WlStorage<D, H>,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut tokens = BTreeSet::from([wl_storage.storage.native_token.clone()]);
+    tokens.extend(wl_storage.storage.conversion_state.tokens.values().cloned());
+
+    let whitelisted = storage_api::iter_prefix_with_filter::<bool, _>(
+        wl_storage,
+        &whitelist::prefix(),
+        |key| whitelist::is_whitelisted_key(key).is_some(),
+    )?;
+    for result in whitelisted {
+        let (key, _) = result?;
+        if let Some(asset) = whitelist::is_whitelisted_key(&key) {
+            tokens.insert(wrapped_erc20s::token(&asset));
+            tokens.insert(wrapped_erc20s::nut(&asset));
+        }
+    }
+
+    let ibc_denoms = storage_api::iter_prefix_with_filter::<String, _>(
+        wl_storage,
+        &ibc_denom_key_prefix(None),
+        |key| is_ibc_denom_key(key).is_some(),
+    )?;
+    for result in ibc_denoms {
+        let (key, _) = result?;
+        if let Some((_, hash)) = is_ibc_denom_key(&key) {
+            let hash: IbcTokenHash = hash.parse().into_storage_result()?;
+            tokens.insert(Address::Internal(InternalAddress::IbcToken(hash)));
+        }
+    }
+    Ok(tokens)
+}
+
 fn revealed<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     owner: Address,
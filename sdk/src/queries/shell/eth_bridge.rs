@@ -1,32 +1,37 @@
 //! Ethereum bridge related shell queries.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use borsh_ext::BorshSerializeExt;
-use namada_core::ledger::eth_bridge::storage::bridge_pool::get_key_from_hash;
+use namada_core::ledger::eth_bridge::storage::bridge_pool::{
+    get_cancellation_key, get_key_from_hash,
+};
+use namada_core::ledger::eth_bridge::storage::relayer_rewards;
 use namada_core::ledger::storage::merkle_tree::StoreRef;
-use namada_core::ledger::storage::{DBIter, StorageHasher, StoreType, DB};
+use namada_core::ledger::storage::{
+    DBIter, StorageHasher, StoreType, WlStorage, DB,
+};
 use namada_core::ledger::storage_api::{
     self, CustomError, ResultExt, StorageRead,
 };
 use namada_core::types::address::Address;
 use namada_core::types::eth_abi::{Encode, EncodeCell};
 use namada_core::types::eth_bridge_pool::{
-    PendingTransfer, PendingTransferAppendix,
+    PendingTransfer, PendingTransferAppendix, RelayerStats,
 };
 use namada_core::types::ethereum_events::{
-    EthAddress, EthereumEvent, TransferToEthereum,
+    EthAddress, EthereumEvent, TransferToEthereum, TransferToNamada,
 };
 use namada_core::types::ethereum_structs;
 use namada_core::types::keccak::KeccakHash;
-use namada_core::types::storage::MembershipProof::BridgePool;
 use namada_core::types::storage::{BlockHeight, DbKeySeg, Epoch, Key};
 use namada_core::types::token::Amount;
 use namada_core::types::vote_extensions::validator_set_update::{
-    ValidatorSetArgs, VotingPowersMap,
+    NormalizedVotingPower, TotalVotingPower, ValidatorSetArgs,
+    ValidatorSetSnapshot, VotingPowersMap,
 };
 use namada_core::types::voting_power::FractionalVotingPower;
 use namada_ethereum_bridge::parameters::UpgradeableContract;
@@ -37,12 +42,14 @@ use namada_ethereum_bridge::storage::eth_bridge_queries::EthBridgeQueries;
 use namada_ethereum_bridge::storage::proof::{sort_sigs, EthereumProof};
 use namada_ethereum_bridge::storage::vote_tallies::{eth_msgs_prefix, Keys};
 use namada_ethereum_bridge::storage::{
-    bridge_contract_key, native_erc20_key, vote_tallies,
+    bridge_contract_key, bridge_contract_rotation_valset_key, native_erc20_key,
+    vote_tallies,
 };
 use namada_proof_of_stake::pos_queries::PosQueries;
+use serde::Serialize;
 
 use crate::eth_bridge::ethers::abi::AbiDecode;
-use crate::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
+use crate::queries::{EncodedResponseQuery, RequestCtx, RequestQuery, Shared};
 
 /// Contains information about the flow control of some ERC20
 /// wrapped asset.
@@ -108,6 +115,117 @@ impl GenBridgePoolProofRsp {
     }
 }
 
+/// Gas fee suggested for a new transfer to Ethereum, returned by
+/// `estimate_bridge_pool_fee`.
+#[derive(
+    Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize, Serialize,
+)]
+pub struct BridgePoolFeeEstimate {
+    /// The number of transfers in the Bridge pool paying gas fees in the
+    /// requested token, which are not being relayed yet.
+    pub pending_transfers: u64,
+    /// The number of transfers paying gas fees in the requested token,
+    /// which have been relayed to Ethereum, and are awaiting to be
+    /// confirmed by Namada.
+    pub relayed_transfers: u64,
+    /// The suggested gas fee, or [`None`] if no transfer pays gas fees
+    /// in the requested token.
+    pub suggested_fee: Option<Amount>,
+}
+
+/// The status of a transfer to Ethereum in the Bridge pool.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub enum TransferToEthereumStatus {
+    /// The transfer is not covered by the latest signed Merkle root of
+    /// the Bridge pool yet.
+    Pending,
+    /// The transfer is covered by the latest signed Merkle root of the
+    /// Bridge pool, such that it can be relayed to Ethereum.
+    Signed,
+    /// The transfer has been relayed to Ethereum, and is awaiting to be
+    /// confirmed by Namada.
+    Relayed,
+    /// The transfer was cancelled by its sender, and will be refunded
+    /// once the nonce of the Bridge pool is incremented.
+    Cancelled,
+}
+
+/// The transfers across the Ethereum bridge relevant to some address,
+/// which have not completed yet, returned by `read_bridge_transfers`.
+#[derive(
+    Debug, Clone, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct BridgeTransfers {
+    /// The transfers to Ethereum in the Bridge pool sent, or paid for,
+    /// by the address, along with their status.
+    pub to_ethereum: Vec<(PendingTransfer, TransferToEthereumStatus)>,
+    /// The transfers from Ethereum to the address which have not been
+    /// confirmed by Namada yet, along with the voting power backing
+    /// them.
+    pub to_namada: Vec<(TransferToNamada, FractionalVotingPower)>,
+}
+
+/// The maximum number of validator set update proofs held by a
+/// [`ValsetProofCache`].
+pub const MAX_CACHED_VALSET_PROOFS: usize = 16;
+
+/// A validator set update proof, as returned by `read_valset_upd_proof`.
+pub type ValsetUpdProof = EncodeCell<EthereumProof<(Epoch, VotingPowersMap)>>;
+
+/// Shared cache of the complete validator set update proofs, keyed by the
+/// epoch of the validator set they sign off on.
+///
+/// Once a validator set update is seen by a quorum of validators, its
+/// proof no longer changes, such that relayer queries can be served from
+/// memory rather than by re-reading and re-encoding the proof. Only the
+/// proofs of the latest [`MAX_CACHED_VALSET_PROOFS`] epochs are kept.
+#[derive(Debug, Clone, Default)]
+pub struct ValsetProofCache {
+    inner: Shared<BTreeMap<Epoch, ValsetUpdProof>>,
+}
+
+impl ValsetProofCache {
+    /// Get the cached proof of the validator set update of the given
+    /// epoch, if any.
+    pub fn get(&self, epoch: Epoch) -> Option<ValsetUpdProof> {
+        self.inner.lock().get(&epoch).cloned()
+    }
+
+    /// Cache the complete proof of the validator set update of the given
+    /// epoch, evicting the proofs of the oldest epochs if the cache is
+    /// full.
+    pub fn insert(&self, epoch: Epoch, proof: ValsetUpdProof) {
+        let mut proofs = self.inner.lock();
+        proofs.insert(epoch, proof);
+        while proofs.len() > MAX_CACHED_VALSET_PROOFS {
+            proofs.pop_first();
+        }
+    }
+}
+
+/// How far the view of Ethereum has advanced, returned by
+/// `read_ethereum_heights`.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    Serialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct EthereumHeights {
+    /// The last Ethereum block processed by the Ethereum oracle of the
+    /// queried node, as of the last committed block.
+    pub committed: Option<ethereum_structs::BlockHeight>,
+    /// The last Ethereum block processed by the Ethereum oracle of the
+    /// queried node, if it runs one. This may be ahead of `committed`.
+    pub oracle: Option<ethereum_structs::BlockHeight>,
+}
+
 router! {ETH_BRIDGE,
     // Get the current contents of the Ethereum bridge pool
     ( "pool" / "contents" )
@@ -129,13 +247,36 @@ router! {ETH_BRIDGE,
         -> HashMap<PendingTransfer, FractionalVotingPower>
         = transfer_to_ethereum_progress,
 
+    // Suggest a gas fee, paid in the given token, for a new transfer to
+    // Ethereum, based on the contents of the Ethereum bridge pool and on
+    // the transfers being relayed.
+    ( "pool" / "fee_estimate" / [gas_token: Address] )
+        -> BridgePoolFeeEstimate = estimate_bridge_pool_fee,
+
+    // Read the number of relayed batches and transfers, along with the
+    // rewards earned, of the given relayer of transfers to Ethereum.
+    ( "pool" / "relayer_stats" / [relayer: Address] )
+        -> Option<RelayerStats> = read_relayer_stats,
+
+    // Read the transfers to Ethereum in the Bridge pool sent, or paid
+    // for, by the given address, and the transfers from Ethereum to the
+    // given address which have not been confirmed yet, along with their
+    // status.
+    ( "transfers" / [owner: Address] )
+        -> BridgeTransfers = read_bridge_transfers,
+
+    // Get the last Ethereum block processed as of the last committed
+    // block, along with the last Ethereum block processed by the oracle
+    // of the queried node, if it runs one.
+    ( "ethereum_height" )
+        -> EthereumHeights = read_ethereum_heights,
+
     // Request a proof of a validator set signed off for
     // the given epoch.
     //
     // The request may fail if a proof is not considered complete yet.
     ( "validator_set" / "proof" / [epoch: Epoch] )
-        -> EncodeCell<EthereumProof<(Epoch, VotingPowersMap)>>
-        = read_valset_upd_proof,
+        -> ValsetUpdProof = read_valset_upd_proof,
 
     // Request the set of bridge validators at the given epoch.
     //
@@ -154,6 +295,12 @@ router! {ETH_BRIDGE,
     ( "contracts" / "bridge" )
         -> UpgradeableContract = read_bridge_contract,
 
+    // Read the Bridge and Governance validator sets that the new
+    // Bridge smart contract of a scheduled rotation is initialized
+    // with, if any.
+    ( "contracts" / "rotation_valset" )
+        -> Option<ValidatorSetSnapshot> = read_bridge_rotation_valset,
+
     // Read the address of the Ethereum bridge's native ERC20
     // smart contract.
     ( "contracts" / "native_erc20" )
@@ -169,6 +316,18 @@ router! {ETH_BRIDGE,
     ( "voting_powers" / "epoch" / [epoch: Epoch] )
         -> VotingPowersMap = voting_powers_at_epoch,
 
+    // Read the total voting power of the consensus validator set at the
+    // given epoch, both in Namada and normalized to the fixed-point
+    // encoding of the Ethereum bridge smart contracts.
+    ( "voting_powers" / "total" / [epoch: Epoch] )
+        -> TotalVotingPower = total_voting_power_at_epoch,
+
+    // Read the voting powers of the consensus validators at the given
+    // epoch, normalized to the fixed-point encoding of the Ethereum
+    // bridge smart contracts, in the order used by the contracts.
+    ( "voting_powers" / "normalized" / [epoch: Epoch] )
+        -> Vec<NormalizedVotingPower> = normalized_voting_powers_at_epoch,
+
     // Read the total supply and respective cap of some wrapped
     // ERC20 token in Namada.
     ( "erc20" / "flow_control" / [asset: EthAddress] )
@@ -232,6 +391,20 @@ where
     read_contract(&bridge_contract_key(), ctx)
 }
 
+/// Read the Bridge and Governance validator sets that the new Bridge
+/// smart contract of a scheduled rotation is initialized with, as
+/// snapshotted by the proposal that scheduled the rotation.
+#[inline]
+fn read_bridge_rotation_valset<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<ValidatorSetSnapshot>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    StorageRead::read(ctx.wl_storage, &bridge_contract_rotation_valset_key())
+}
+
 /// Read the address of the Ethereum bridge's native ERC20
 /// smart contract.
 #[inline]
@@ -245,6 +418,33 @@ where
     read_contract(&native_erc20_key(), ctx)
 }
 
+/// Read the statistics of the given relayer of transfers to Ethereum.
+fn read_relayer_stats<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    relayer: Address,
+) -> storage_api::Result<Option<RelayerStats>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    ctx.wl_storage.read(&relayer_rewards::stats_key(&relayer))
+}
+
+/// Read how far the view of Ethereum has advanced, as of the last
+/// committed block and according to the Ethereum oracle of this node.
+fn read_ethereum_heights<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<EthereumHeights>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Ok(EthereumHeights {
+        committed: ctx.wl_storage.storage.ethereum_height.clone(),
+        oracle: ctx.oracle_last_processed_block,
+    })
+}
+
 /// Read the current contents of the Ethereum bridge
 /// pool.
 fn read_ethereum_bridge_pool<D, H, V, T>(
@@ -256,7 +456,7 @@ where
 {
     Ok(read_ethereum_bridge_pool_at_height(
         ctx.wl_storage.storage.get_last_block_height(),
-        ctx,
+        ctx.wl_storage,
     ))
 }
 
@@ -278,13 +478,13 @@ where
             "No signed root for the Ethereum bridge pool exists in storage.",
         ))
         .into_storage_result()?;
-    Ok(read_ethereum_bridge_pool_at_height(height, ctx))
+    Ok(read_ethereum_bridge_pool_at_height(height, ctx.wl_storage))
 }
 
 /// Read the Ethereum bridge pool contents at a specified height.
-fn read_ethereum_bridge_pool_at_height<D, H, V, T>(
+fn read_ethereum_bridge_pool_at_height<D, H>(
     height: BlockHeight,
-    ctx: RequestCtx<'_, D, H, V, T>,
+    wl_storage: &WlStorage<D, H>,
 ) -> Vec<PendingTransfer>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -292,8 +492,7 @@ where
 {
     // get the backing store of the merkle tree corresponding
     // at the specified height.
-    let merkle_tree = ctx
-        .wl_storage
+    let merkle_tree = wl_storage
         .storage
         .get_merkle_tree(height, Some(StoreType::BridgePool))
         .expect("We should always be able to read the database");
@@ -306,8 +505,7 @@ where
     let transfers: Vec<PendingTransfer> = store
         .keys()
         .map(|hash| {
-            let value = ctx
-                .wl_storage
+            let value = wl_storage
                 .storage
                 .read_with_height(&get_key_from_hash(hash), height)
                 .unwrap()
@@ -361,27 +559,28 @@ where
             )));
         }
 
-        // get the merkle tree corresponding to the above root.
+        // get the merkle tree corresponding to the above root, which is
+        // usually cached in memory
         let tree = ctx
             .wl_storage
             .storage
-            .get_merkle_tree(height, Some(StoreType::BridgePool))
+            .get_bridge_pool_tree(height)
             .into_storage_result()?;
         // from the hashes of the transfers, get the actual values.
         let mut missing_hashes = vec![];
-        let (keys, values): (Vec<_>, Vec<_>) = transfer_hashes
+        let values: Vec<_> = transfer_hashes
             .iter()
             .filter_map(|hash| {
                 let key = get_key_from_hash(hash);
                 match ctx.wl_storage.read_bytes(&key) {
-                    Ok(Some(bytes)) => Some((key, bytes)),
+                    Ok(Some(bytes)) => Some(bytes),
                     _ => {
                         missing_hashes.push(hash);
                         None
                     }
                 }
             })
-            .unzip();
+            .collect();
         if !missing_hashes.is_empty() {
             return Err(storage_api::Error::Custom(CustomError(
                 format!(
@@ -392,25 +591,27 @@ where
                 .into(),
             )));
         }
-        let (transfers, appendices) = values.iter().fold(
+        let pending_transfers: Vec<_> = values
+            .iter()
+            .map(|bytes| {
+                PendingTransfer::try_from_slice(bytes)
+                    .expect("Deserializing storage shouldn't fail")
+            })
+            .collect();
+        let (transfers, appendices) = pending_transfers.iter().fold(
             (vec![], vec![]),
-            |(mut transfers, mut appendices), bytes| {
-                let pending = PendingTransfer::try_from_slice(bytes)
-                    .expect("Deserializing storage shouldn't fail");
-                let eth_transfer = (&pending).into();
+            |(mut transfers, mut appendices), pending| {
+                let eth_transfer = pending.into();
                 if with_appendix {
-                    appendices.push(pending.into_appendix());
+                    appendices.push(pending.clone().into_appendix());
                 }
                 transfers.push(eth_transfer);
                 (transfers, appendices)
             },
         );
         // get the membership proof
-        match tree.get_sub_tree_existence_proof(
-            &keys,
-            values.iter().map(|v| v.as_slice()).collect(),
-        ) {
-            Ok(BridgePool(proof)) => {
+        match tree.get_membership_proof(pending_transfers) {
+            Ok(proof) => {
                 let (validator_args, voting_powers) = ctx
                     .wl_storage
                     .ethbridge_queries()
@@ -441,7 +642,6 @@ where
                     ..Default::default()
                 })
             }
-            Ok(_) => unreachable!(),
             Err(e) => Err(storage_api::Error::new(e)),
         }
     } else {
@@ -454,16 +654,53 @@ where
 /// Iterates over all ethereum events
 /// and returns the amount of voting power
 /// backing each `TransferToEthereum` event.
+#[inline]
 fn transfer_to_ethereum_progress<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
 ) -> storage_api::Result<HashMap<PendingTransfer, FractionalVotingPower>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_transfer_to_ethereum_progress(ctx.wl_storage)
+}
+
+/// Read the amount of voting power backing each `TransferToEthereum`
+/// event which has not been seen yet.
+fn read_transfer_to_ethereum_progress<D, H>(
+    wl_storage: &WlStorage<D, H>,
+) -> storage_api::Result<HashMap<PendingTransfer, FractionalVotingPower>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
     let mut pending_events = HashMap::new();
-    for (mut key, value) in ctx
-        .wl_storage
+    for (event, voting_power) in read_unseen_ethereum_events(wl_storage)? {
+        if let EthereumEvent::TransfersToEthereum { transfers, .. } = event {
+            for transfer in transfers {
+                let key = get_key_from_hash(&transfer.keccak256());
+                let transfer = wl_storage
+                    .read::<PendingTransfer>(&key)
+                    .into_storage_result()?
+                    .expect("The transfer must be present in storage");
+                pending_events.insert(transfer, voting_power);
+            }
+        }
+    }
+    Ok(pending_events)
+}
+
+/// Read the Ethereum events which have not been seen yet, along with
+/// the amount of voting power backing each of them.
+fn read_unseen_ethereum_events<D, H>(
+    wl_storage: &WlStorage<D, H>,
+) -> storage_api::Result<Vec<(EthereumEvent, FractionalVotingPower)>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let mut unseen_events = vec![];
+    for (mut key, value) in wl_storage
         .iter_prefix(&eth_msgs_prefix())?
         .filter_map(|(k, v, _)| {
             let key = Key::from_str(&k).expect(
@@ -484,44 +721,148 @@ where
         *key.segments.last_mut().unwrap() =
             DbKeySeg::StringSeg(Keys::segments().seen.into());
         // check if the event has been seen
-        let is_seen = ctx
-            .wl_storage
-            .read::<bool>(&key)
-            .into_storage_result()?
-            .expect(
+        let is_seen =
+            wl_storage.read::<bool>(&key).into_storage_result()?.expect(
                 "Iterating over storage should not yield keys without values.",
             );
         if is_seen {
             continue;
         }
 
-        if let Ok(EthereumEvent::TransfersToEthereum { transfers, .. }) =
-            EthereumEvent::try_from_slice(&value)
-        {
+        if let Ok(event) = EthereumEvent::try_from_slice(&value) {
             // read the voting power behind the event
             *key.segments.last_mut().unwrap() =
                 DbKeySeg::StringSeg(Keys::segments().voting_power.into());
-            let voting_power = ctx
-                .wl_storage
+            let voting_power = wl_storage
                 .read::<EpochedVotingPower>(&key)
                 .into_storage_result()?
                 .expect(
                     "Iterating over storage should not yield keys without \
                      values.",
                 )
-                .fractional_stake(ctx.wl_storage);
-            for transfer in transfers {
-                let key = get_key_from_hash(&transfer.keccak256());
-                let transfer = ctx
-                    .wl_storage
-                    .read::<PendingTransfer>(&key)
-                    .into_storage_result()?
-                    .expect("The transfer must be present in storage");
-                pending_events.insert(transfer, voting_power);
-            }
+                .fractional_stake(wl_storage);
+            unseen_events.push((event, voting_power));
         }
     }
-    Ok(pending_events)
+    Ok(unseen_events)
+}
+
+/// Read the transfers across the Ethereum bridge relevant to `owner`,
+/// which have not completed yet.
+///
+/// Transfers to Ethereum are relevant if they were sent, or paid for,
+/// by `owner`. Transfers to Namada are relevant if they are received
+/// by `owner`.
+fn read_bridge_transfers<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> storage_api::Result<BridgeTransfers>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let relayed = read_transfer_to_ethereum_progress(ctx.wl_storage)?;
+    let signed: HashSet<_> = ctx
+        .wl_storage
+        .ethbridge_queries()
+        .get_signed_bridge_pool_root()
+        .map(|(_, height)| {
+            read_ethereum_bridge_pool_at_height(height, ctx.wl_storage)
+                .into_iter()
+                .map(|transfer| transfer.keccak256())
+                .collect()
+        })
+        .unwrap_or_default();
+    let to_ethereum = read_ethereum_bridge_pool_at_height(
+        ctx.wl_storage.storage.get_last_block_height(),
+        ctx.wl_storage,
+    )
+    .into_iter()
+    .filter(|transfer| {
+        transfer.transfer.sender == owner || transfer.gas_fee.payer == owner
+    })
+    .map(|transfer| {
+        let hash = transfer.keccak256();
+        let status = if relayed.contains_key(&transfer) {
+            TransferToEthereumStatus::Relayed
+        } else if ctx.wl_storage.has_key(&get_cancellation_key(&hash))? {
+            TransferToEthereumStatus::Cancelled
+        } else if signed.contains(&hash) {
+            TransferToEthereumStatus::Signed
+        } else {
+            TransferToEthereumStatus::Pending
+        };
+        Ok((transfer, status))
+    })
+    .collect::<storage_api::Result<_>>()?;
+
+    let mut to_namada = vec![];
+    for (event, voting_power) in read_unseen_ethereum_events(ctx.wl_storage)? {
+        let transfers = match event {
+            EthereumEvent::TransfersToNamada { transfers, .. } => transfers,
+            EthereumEvent::TransfersToNamadaBatch { batches, .. } => {
+                batches.into_iter().flatten().collect()
+            }
+            _ => continue,
+        };
+        to_namada.extend(
+            transfers
+                .into_iter()
+                .filter(|transfer| transfer.receiver == owner)
+                .map(|transfer| (transfer, voting_power)),
+        );
+    }
+
+    Ok(BridgeTransfers {
+        to_ethereum,
+        to_namada,
+    })
+}
+
+/// Suggest a gas fee, paid in `gas_token`, for a new transfer to
+/// Ethereum.
+///
+/// Relayers pick the most profitable transfers in the Bridge pool,
+/// therefore the suggested fee is the highest of:
+///
+/// - the lowest fee of the transfers being relayed, i.e. a fee which relayers
+///   have recently accepted;
+/// - the median fee of the transfers pending in the Bridge pool, such that the
+///   new transfer is more profitable to relay than half of the backlog.
+fn estimate_bridge_pool_fee<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    gas_token: Address,
+) -> storage_api::Result<BridgePoolFeeEstimate>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let relayed = read_transfer_to_ethereum_progress(ctx.wl_storage)?;
+    let relayed_fees: Vec<_> = relayed
+        .keys()
+        .filter(|transfer| transfer.gas_fee.token == gas_token)
+        .map(|transfer| transfer.gas_fee.amount)
+        .collect();
+    let lowest_relayed_fee = relayed_fees.iter().min().copied();
+
+    let mut pending_fees: Vec<_> = read_ethereum_bridge_pool_at_height(
+        ctx.wl_storage.storage.get_last_block_height(),
+        ctx.wl_storage,
+    )
+    .into_iter()
+    .filter(|transfer| {
+        transfer.gas_fee.token == gas_token && !relayed.contains_key(transfer)
+    })
+    .map(|transfer| transfer.gas_fee.amount)
+    .collect();
+    pending_fees.sort_unstable();
+    let median_pending_fee = pending_fees.get(pending_fees.len() / 2).copied();
+
+    Ok(BridgePoolFeeEstimate {
+        pending_transfers: pending_fees.len() as u64,
+        relayed_transfers: relayed_fees.len() as u64,
+        suggested_fee: lowest_relayed_fee.max(median_pending_fee),
+    })
 }
 
 /// Read a validator set update proof from storage.
@@ -531,7 +872,7 @@ where
 fn read_valset_upd_proof<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     epoch: Epoch,
-) -> storage_api::Result<EncodeCell<EthereumProof<(Epoch, VotingPowersMap)>>>
+) -> storage_api::Result<ValsetUpdProof>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
@@ -554,6 +895,10 @@ where
         )));
     }
 
+    if let Some(proof) = ctx.valset_proof_cache.get(epoch) {
+        return Ok(proof);
+    }
+
     if !ctx.wl_storage.ethbridge_queries().valset_upd_seen(epoch) {
         return Err(storage_api::Error::Custom(CustomError(
             format!(
@@ -571,7 +916,10 @@ where
         );
 
     // NOTE: we pass the epoch of the new set of validators
-    Ok(proof.map(|set| (epoch, set)).encode())
+    let proof = proof.map(|set| (epoch, set)).encode();
+    // the proof is complete, therefore it may no longer change
+    ctx.valset_proof_cache.insert(epoch, proof.clone());
+    Ok(proof)
 }
 
 /// Request the set of bridge validators at the given epoch.
@@ -659,6 +1007,58 @@ fn voting_powers_at_epoch<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     epoch: Epoch,
 ) -> storage_api::Result<VotingPowersMap>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_voting_powers_epoch(&ctx, epoch)?;
+    let (_, voting_powers) = ctx
+        .wl_storage
+        .ethbridge_queries()
+        .get_bridge_validator_set(Some(epoch));
+    Ok(voting_powers)
+}
+
+/// Retrieve the total voting power of the consensus validators at
+/// the given [`Epoch`].
+fn total_voting_power_at_epoch<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> storage_api::Result<TotalVotingPower>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_voting_powers_epoch(&ctx, epoch)?;
+    Ok(ctx
+        .wl_storage
+        .ethbridge_queries()
+        .get_total_normalized_voting_power(Some(epoch)))
+}
+
+/// Retrieve the normalized voting powers of the consensus validators
+/// at the given [`Epoch`].
+fn normalized_voting_powers_at_epoch<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<NormalizedVotingPower>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_voting_powers_epoch(&ctx, epoch)?;
+    Ok(ctx
+        .wl_storage
+        .ethbridge_queries()
+        .get_normalized_voting_powers(Some(epoch)))
+}
+
+/// Check that the voting powers of the consensus validators can be
+/// queried at the given [`Epoch`].
+fn check_voting_powers_epoch<D, H, V, T>(
+    ctx: &RequestCtx<'_, D, H, V, T>,
+    epoch: Epoch,
+) -> storage_api::Result<()>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
@@ -669,11 +1069,7 @@ where
             "The requested epoch cannot be queried",
         ));
     }
-    let (_, voting_powers) = ctx
-        .wl_storage
-        .ethbridge_queries()
-        .get_bridge_validator_set(Some(epoch));
-    Ok(voting_powers)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -805,6 +1201,72 @@ mod test_ethbridge_router {
         );
     }
 
+    /// Test that the normalized voting powers match the voting powers
+    /// of the bridge validator set, and add up to the total normalized
+    /// voting power.
+    #[tokio::test]
+    async fn test_read_normalized_voting_powers() {
+        let mut client = TestClient::new(RPC);
+        let epoch = Epoch(0);
+
+        // write validator to storage
+        test_utils::init_default_storage(&mut client.wl_storage);
+
+        // commit the changes
+        client
+            .wl_storage
+            .storage
+            .commit_block(MockDBWriteBatch)
+            .expect("Test failed");
+
+        // check the responses
+        let normalized = RPC
+            .shell()
+            .eth_bridge()
+            .normalized_voting_powers_at_epoch(&client, &epoch)
+            .await
+            .unwrap();
+        let total = RPC
+            .shell()
+            .eth_bridge()
+            .total_voting_power_at_epoch(&client, &epoch)
+            .await
+            .unwrap();
+        let validator_set = RPC
+            .shell()
+            .eth_bridge()
+            .read_bridge_valset(&client, &epoch)
+            .await
+            .unwrap();
+
+        assert!(!normalized.is_empty());
+        let (validators, voting_powers): (Vec<_>, Vec<_>) = normalized
+            .iter()
+            .map(|power| (power.addr_book.hot_key_addr, power.normalized))
+            .unzip();
+        assert_eq!(validators, validator_set.validators);
+        assert_eq!(voting_powers, validator_set.voting_powers);
+        assert_eq!(
+            total.voting_power,
+            client
+                .wl_storage
+                .pos_queries()
+                .get_total_voting_power(Some(epoch))
+        );
+        assert_eq!(
+            total.normalized,
+            voting_powers.into_iter().map(u128::from).sum::<u128>()
+        );
+
+        // epochs too far ahead cannot be queried
+        let result = RPC
+            .shell()
+            .eth_bridge()
+            .total_voting_power_at_epoch(&client, &Epoch(999_999))
+            .await;
+        assert!(result.is_err());
+    }
+
     /// Test that reading a validator set proof works.
     #[tokio::test]
     async fn test_read_valset_upd_proof() {
@@ -863,6 +1325,36 @@ mod test_ethbridge_router {
         };
 
         assert_eq!(proof, expected);
+
+        // the proof is now served from the cache
+        assert_eq!(client.valset_proof_cache.get(Epoch(1)), Some(expected));
+        client
+            .wl_storage
+            .delete(&vote_tallies::Keys::from(&Epoch(1)).body())
+            .expect("Test failed");
+        let cached = RPC
+            .shell()
+            .eth_bridge()
+            .read_valset_upd_proof(&client, &Epoch(1))
+            .await
+            .unwrap();
+        assert_eq!(cached, proof);
+    }
+
+    /// Test that only the proofs of the latest epochs are cached.
+    #[test]
+    fn test_valset_proof_cache_is_bounded() {
+        let cache = ValsetProofCache::default();
+        let proof = |epoch: u64| {
+            EthereumProof::new((Epoch(epoch), VotingPowersMap::new())).encode()
+        };
+        let num_proofs = MAX_CACHED_VALSET_PROOFS as u64 + 1;
+        for epoch in 1..=num_proofs {
+            cache.insert(Epoch(epoch), proof(epoch));
+        }
+        assert_eq!(cache.get(Epoch(1)), None);
+        assert_eq!(cache.get(Epoch(2)), Some(proof(2)));
+        assert_eq!(cache.get(Epoch(num_proofs)), Some(proof(num_proofs)));
     }
 
     /// Test that when reading a validator set proof too far ahead,
@@ -1388,6 +1880,253 @@ mod test_ethbridge_router {
         assert_eq!(expected, resp);
     }
 
+    /// Test that the suggested gas fee of a transfer to Ethereum
+    /// accounts for both the pending and the relayed transfers.
+    #[tokio::test]
+    async fn test_estimate_bridge_pool_fee() {
+        let mut client = TestClient::new(RPC);
+        let transfer = |amount: u64, gas_token: Address| PendingTransfer {
+            transfer: TransferToEthereum {
+                kind: TransferToEthereumKind::Erc20,
+                asset: EthAddress([0; 20]),
+                recipient: EthAddress([0; 20]),
+                sender: bertha_address(),
+                amount: amount.into(),
+            },
+            gas_fee: GasFee {
+                token: gas_token,
+                amount: (amount * 10).into(),
+                payer: bertha_address(),
+            },
+        };
+        let (_, dummy_validator_stake) = test_utils::default_validator();
+        test_utils::init_default_storage(&mut client.wl_storage);
+
+        // write some transfers into the bridge pool
+        let relayed = transfer(1, nam());
+        client.wl_storage.storage.block.height = 1.into();
+        for transfer in [
+            relayed.clone(),
+            transfer(2, nam()),
+            transfer(3, nam()),
+            transfer(4, nam()),
+            transfer(5, bertha_address()),
+        ] {
+            client
+                .wl_storage
+                .write_bytes(
+                    &get_pending_key(&transfer),
+                    transfer.serialize_to_vec(),
+                )
+                .expect("Test failed");
+        }
+
+        // one of the transfers is being relayed
+        let eth_event = EthereumEvent::TransfersToEthereum {
+            nonce: Default::default(),
+            transfers: vec![(&relayed).into()],
+            relayer: bertha_address(),
+        };
+        let eth_msg_key = vote_tallies::Keys::from(&eth_event);
+        client
+            .wl_storage
+            .write_bytes(&eth_msg_key.body(), eth_event.serialize_to_vec())
+            .expect("Test failed");
+        client
+            .wl_storage
+            .write_bytes(
+                &eth_msg_key.voting_power(),
+                EpochedVotingPower::from([(
+                    0.into(),
+                    FractionalVotingPower::HALF * dummy_validator_stake,
+                )])
+                .serialize_to_vec(),
+            )
+            .expect("Test failed");
+        client
+            .wl_storage
+            .write(&eth_msg_key.seen(), false)
+            .expect("Test failed");
+
+        // commit the changes and increase block height
+        client.wl_storage.commit_block().expect("Test failed");
+        client.wl_storage.storage.block.height += 1;
+
+        // the median of the pending fees is above the relayed fee
+        let estimate = RPC
+            .shell()
+            .eth_bridge()
+            .estimate_bridge_pool_fee(&client, &nam())
+            .await
+            .unwrap();
+        assert_eq!(
+            estimate,
+            BridgePoolFeeEstimate {
+                pending_transfers: 3,
+                relayed_transfers: 1,
+                suggested_fee: Some(30.into()),
+            }
+        );
+
+        // no transfer pays fees in this token
+        let estimate = RPC
+            .shell()
+            .eth_bridge()
+            .estimate_bridge_pool_fee(&client, &established_address_1())
+            .await
+            .unwrap();
+        assert_eq!(
+            estimate,
+            BridgePoolFeeEstimate {
+                pending_transfers: 0,
+                relayed_transfers: 0,
+                suggested_fee: None,
+            }
+        );
+    }
+
+    /// Test that the pending transfers across the Ethereum bridge are
+    /// filtered by address, along with their status.
+    #[tokio::test]
+    async fn test_read_bridge_transfers() {
+        let mut client = TestClient::new(RPC);
+        let transfer =
+            |amount: u64, sender: Address, payer: Address| PendingTransfer {
+                transfer: TransferToEthereum {
+                    kind: TransferToEthereumKind::Erc20,
+                    asset: EthAddress([0; 20]),
+                    recipient: EthAddress([0; 20]),
+                    sender,
+                    amount: amount.into(),
+                },
+                gas_fee: GasFee {
+                    token: nam(),
+                    amount: 0.into(),
+                    payer,
+                },
+            };
+        let owner = bertha_address();
+        let other = established_address_1();
+        let (_, dummy_validator_stake) = test_utils::default_validator();
+        test_utils::init_default_storage(&mut client.wl_storage);
+
+        // write some transfers into the bridge pool, which are
+        // covered by a signed root
+        let signed = transfer(1, owner.clone(), other.clone());
+        let relayed = transfer(2, owner.clone(), owner.clone());
+        for transfer in [&signed, &relayed] {
+            client
+                .wl_storage
+                .write_bytes(
+                    &get_pending_key(transfer),
+                    transfer.serialize_to_vec(),
+                )
+                .expect("Test failed");
+        }
+        let signed_root = BridgePoolRootProof {
+            signatures: Default::default(),
+            data: (signed.keccak256(), 0.into()),
+        };
+        let written_height = client.wl_storage.storage.block.height;
+        client.wl_storage.commit_block().expect("Test failed");
+        client.wl_storage.storage.block.height += 1;
+
+        // write some more transfers, not covered by the signed root
+        let pending = transfer(3, other.clone(), owner.clone());
+        let unrelated = transfer(4, other.clone(), other.clone());
+        let cancelled = transfer(5, owner.clone(), owner.clone());
+        for transfer in [&pending, &unrelated, &cancelled] {
+            client
+                .wl_storage
+                .write_bytes(
+                    &get_pending_key(transfer),
+                    transfer.serialize_to_vec(),
+                )
+                .expect("Test failed");
+        }
+        client
+            .wl_storage
+            .write_bytes(
+                &get_signed_root_key(),
+                (signed_root, written_height).serialize_to_vec(),
+            )
+            .expect("Test failed");
+        client
+            .wl_storage
+            .write(
+                &get_cancellation_key(&cancelled.keccak256()),
+                client.wl_storage.storage.block.epoch,
+            )
+            .expect("Test failed");
+
+        // one of the transfers is being relayed, and some transfers
+        // to Namada are being voted on
+        let to_namada = |amount: u64, receiver: Address| TransferToNamada {
+            amount: amount.into(),
+            asset: EthAddress([0; 20]),
+            receiver,
+        };
+        let eth_events = [
+            EthereumEvent::TransfersToEthereum {
+                nonce: Default::default(),
+                transfers: vec![(&relayed).into()],
+                relayer: other.clone(),
+            },
+            EthereumEvent::TransfersToNamada {
+                nonce: Default::default(),
+                transfers: vec![
+                    to_namada(5, owner.clone()),
+                    to_namada(6, other.clone()),
+                ],
+            },
+        ];
+        for eth_event in eth_events {
+            let eth_msg_key = vote_tallies::Keys::from(&eth_event);
+            client
+                .wl_storage
+                .write_bytes(&eth_msg_key.body(), eth_event.serialize_to_vec())
+                .expect("Test failed");
+            client
+                .wl_storage
+                .write_bytes(
+                    &eth_msg_key.voting_power(),
+                    EpochedVotingPower::from([(
+                        0.into(),
+                        FractionalVotingPower::HALF * dummy_validator_stake,
+                    )])
+                    .serialize_to_vec(),
+                )
+                .expect("Test failed");
+            client
+                .wl_storage
+                .write(&eth_msg_key.seen(), false)
+                .expect("Test failed");
+        }
+        client.wl_storage.commit_block().expect("Test failed");
+        client.wl_storage.storage.block.height += 1;
+
+        let resp = RPC
+            .shell()
+            .eth_bridge()
+            .read_bridge_transfers(&client, &owner)
+            .await
+            .unwrap();
+        let to_ethereum: HashMap<_, _> = resp.to_ethereum.into_iter().collect();
+        let expected: HashMap<_, _> = [
+            (signed, TransferToEthereumStatus::Signed),
+            (relayed, TransferToEthereumStatus::Relayed),
+            (pending, TransferToEthereumStatus::Pending),
+            (cancelled, TransferToEthereumStatus::Cancelled),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(to_ethereum, expected);
+        assert_eq!(
+            resp.to_namada,
+            vec![(to_namada(5, owner), FractionalVotingPower::HALF)]
+        );
+    }
+
     /// Test if the a transfer has been removed from the
     /// pool (either because it was transferred or timed out),
     /// a proof is not generated for it, even if it was
@@ -1559,6 +2298,36 @@ mod test_ethbridge_router {
             Ok(f) if f.supply == supply_amount && f.cap == cap_amount
         );
     }
+
+    /// Test that the last Ethereum block processed as of the last
+    /// committed block, and by the oracle, are returned.
+    #[tokio::test]
+    async fn test_read_ethereum_heights() {
+        let mut client = TestClient::new(RPC);
+        let heights = RPC
+            .shell()
+            .eth_bridge()
+            .read_ethereum_heights(&client)
+            .await
+            .unwrap();
+        assert_eq!(heights, EthereumHeights::default());
+
+        client.wl_storage.storage.ethereum_height = Some(5u64.into());
+        client.oracle_last_processed_block = Some(7u64.into());
+        let heights = RPC
+            .shell()
+            .eth_bridge()
+            .read_ethereum_heights(&client)
+            .await
+            .unwrap();
+        assert_eq!(
+            heights,
+            EthereumHeights {
+                committed: Some(5u64.into()),
+                oracle: Some(7u64.into()),
+            }
+        );
+    }
 }
 
 #[cfg(any(feature = "testing", test))]
@@ -0,0 +1,95 @@
+//! Node health related shell queries.
+
+use std::collections::VecDeque;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+use namada_core::ledger::storage_api;
+use namada_core::types::time::DateTimeUtc;
+
+use crate::queries::{RequestCtx, Shared};
+
+/// The maximum number of crash reports retained by [`CrashReports`].
+pub const MAX_CRASH_REPORTS: usize = 64;
+
+/// Report of a panic caught in one of the supervised, non-consensus
+/// subsystems of the ledger.
+#[derive(Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CrashReport {
+    /// The name of the subsystem that crashed.
+    pub subsystem: String,
+    /// The panic message, if one could be recovered.
+    pub message: String,
+    /// The time at which the crash took place.
+    pub time: DateTimeUtc,
+    /// The number of times the subsystem has been restarted, including
+    /// the restart which followed this crash.
+    pub restarts: u64,
+}
+
+/// Shared, bounded log of [`CrashReport`] instances.
+#[derive(Debug, Clone, Default)]
+pub struct CrashReports {
+    inner: Shared<VecDeque<CrashReport>>,
+}
+
+impl CrashReports {
+    /// Record a new [`CrashReport`], evicting the oldest report if
+    /// [`MAX_CRASH_REPORTS`] has been reached.
+    pub fn record(&self, report: CrashReport) {
+        let mut reports = self.inner.lock();
+        if reports.len() == MAX_CRASH_REPORTS {
+            reports.pop_front();
+        }
+        reports.push_back(report);
+    }
+
+    /// Return a copy of all the retained crash reports, from oldest to
+    /// newest.
+    pub fn snapshot(&self) -> Vec<CrashReport> {
+        self.inner.lock().iter().cloned().collect()
+    }
+}
+
+router! {HEALTH,
+    // Crash reports of the ledger's supervised subsystems
+    ( "crash_reports" ) -> Vec<CrashReport> = crash_reports,
+}
+
+/// Read the crash reports of the ledger's supervised subsystems.
+fn crash_reports<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Vec<CrashReport>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Ok(ctx.crash_reports.snapshot())
+}
+
+#[cfg(test)]
+mod test_health_queries {
+    use super::*;
+
+    /// Test that the crash reports log evicts the oldest reports.
+    #[test]
+    fn test_crash_reports_are_bounded() {
+        let reports = CrashReports::default();
+        let handle = reports.clone();
+        for restarts in 0..(MAX_CRASH_REPORTS as u64 + 2) {
+            handle.record(CrashReport {
+                subsystem: "Test".into(),
+                message: "boom".into(),
+                time: DateTimeUtc::now(),
+                restarts,
+            });
+        }
+        let snapshot = reports.snapshot();
+        assert_eq!(snapshot.len(), MAX_CRASH_REPORTS);
+        assert_eq!(snapshot[0].restarts, 2);
+        assert_eq!(
+            snapshot.last().unwrap().restarts,
+            MAX_CRASH_REPORTS as u64 + 1
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Node statistics related shell queries.
+
+use std::collections::HashMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+use namada_core::ledger::storage_api;
+use namada_core::types::storage::{BlockHeight, Epoch, Key};
+
+use crate::queries::{RequestCtx, Shared};
+
+/// The number of leading segments of a storage key that make up the
+/// prefix under which writes to that key are accounted.
+pub const WRITE_HOTSPOT_PREFIX_SEGMENTS: usize = 2;
+
+/// The maximum number of key prefixes reported by a
+/// [`WriteHotspotsReport`], per time window.
+pub const MAX_WRITE_HOTSPOTS: usize = 32;
+
+/// The number of writes made to storage keys sharing a common prefix.
+#[derive(Debug, Clone, Eq, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct KeyPrefixWrites {
+    /// The key prefix.
+    pub prefix: String,
+    /// The number of writes made to keys with this prefix.
+    pub writes: u64,
+}
+
+/// The most frequently written storage key prefixes, in the last block
+/// and in the current epoch, from most to least written.
+#[derive(
+    Debug, Clone, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct WriteHotspotsReport {
+    /// The height of the last recorded block.
+    pub height: BlockHeight,
+    /// The epoch of the last recorded block.
+    pub epoch: Epoch,
+    /// The hotspots of the last recorded block.
+    pub last_block: Vec<KeyPrefixWrites>,
+    /// The hotspots of all the recorded blocks of the current epoch.
+    pub current_epoch: Vec<KeyPrefixWrites>,
+}
+
+/// Write counters of [`WriteHotspots`].
+#[derive(Debug, Default)]
+struct WriteCounters {
+    height: BlockHeight,
+    epoch: Epoch,
+    last_block: HashMap<String, u64>,
+    current_epoch: HashMap<String, u64>,
+}
+
+/// Shared record of the number of writes made to each storage key prefix
+/// by the txs applied in the last block and in the current epoch.
+#[derive(Debug, Clone, Default)]
+pub struct WriteHotspots {
+    inner: Shared<WriteCounters>,
+}
+
+impl WriteHotspots {
+    /// Return the prefix under which writes to `key` are accounted.
+    pub fn key_prefix(key: &Key) -> String {
+        Key {
+            segments: key
+                .segments
+                .iter()
+                .take(WRITE_HOTSPOT_PREFIX_SEGMENTS)
+                .cloned()
+                .collect(),
+        }
+        .to_string()
+    }
+
+    /// Record the number of writes made to each key prefix in the block
+    /// at the given height. The counters of the current epoch are reset
+    /// once a block of a new epoch is recorded.
+    pub fn record_block(
+        &self,
+        height: BlockHeight,
+        epoch: Epoch,
+        writes: HashMap<String, u64>,
+    ) {
+        let mut counters = self.inner.lock();
+        if counters.epoch != epoch {
+            counters.current_epoch.clear();
+        }
+        for (prefix, num_writes) in writes.iter() {
+            *counters.current_epoch.entry(prefix.clone()).or_default() +=
+                num_writes;
+        }
+        counters.height = height;
+        counters.epoch = epoch;
+        counters.last_block = writes;
+    }
+
+    /// Return the most frequently written key prefixes.
+    pub fn report(&self) -> WriteHotspotsReport {
+        let counters = self.inner.lock();
+        WriteHotspotsReport {
+            height: counters.height,
+            epoch: counters.epoch,
+            last_block: top_hotspots(&counters.last_block),
+            current_epoch: top_hotspots(&counters.current_epoch),
+        }
+    }
+}
+
+/// Counters of the evidence of validator misbehavior discarded by the
+/// ledger, instead of being turned into slashes.
+#[derive(
+    Debug, Clone, Copy, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct DiscardedEvidenceMetrics {
+    /// The number of evidences with an invalid block height or kind, or
+    /// against an unknown validator.
+    pub invalid: u64,
+    /// The number of evidences older than the maximum evidence age.
+    pub expired: u64,
+    /// The number of evidences that should have already been processed,
+    /// according to the slashing offsets.
+    pub outdated: u64,
+}
+
+/// Counters of the queue of Ethereum events received from the oracle of a
+/// validator, pending their inclusion in vote extensions.
+#[derive(
+    Debug, Clone, Copy, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct EthEventsQueueMetrics {
+    /// The number of times new events were left with the oracle, because
+    /// the queue was full.
+    pub full: u64,
+    /// The number of queued events evicted because they were no longer
+    /// valid, e.g. because they were confirmed in the meantime.
+    pub evicted: u64,
+    /// The number of queued events reported as stalled.
+    pub stalled: u64,
+}
+
+/// Counters of the calls of the Ethereum oracle of a validator to its RPC
+/// endpoints, which were affected by rate limiting.
+#[derive(
+    Debug, Clone, Copy, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct EthRpcThrottleMetrics {
+    /// The number of calls delayed by the client-side rate limiter.
+    pub throttled_calls: u64,
+    /// The number of calls rejected by an endpoint, for exceeding its
+    /// request cap.
+    pub rejected_calls: u64,
+}
+
+/// Node-local metrics of the ledger, accumulated since it was started.
+#[derive(
+    Debug, Clone, Default, Eq, PartialEq, BorshSerialize, BorshDeserialize,
+)]
+pub struct NodeMetricsReport {
+    /// Counters of the discarded evidence of validator misbehavior.
+    pub discarded_evidence: DiscardedEvidenceMetrics,
+    /// Counters of the queue of Ethereum events of a validator.
+    pub eth_events_queue: EthEventsQueueMetrics,
+    /// Counters of the rate limited Ethereum RPC calls of a validator.
+    pub eth_rpc_throttle: EthRpcThrottleMetrics,
+}
+
+/// Shared record of the node-local metrics of the ledger.
+#[derive(Debug, Clone, Default)]
+pub struct NodeMetrics {
+    inner: Shared<NodeMetricsReport>,
+}
+
+impl NodeMetrics {
+    /// Update the recorded metrics.
+    pub fn update<F>(&self, update: F)
+    where
+        F: FnOnce(&mut NodeMetricsReport),
+    {
+        update(&mut self.inner.lock())
+    }
+
+    /// Return a copy of the recorded metrics.
+    pub fn report(&self) -> NodeMetricsReport {
+        self.inner.lock().clone()
+    }
+}
+
+/// Return up to [`MAX_WRITE_HOTSPOTS`] of the most written key prefixes,
+/// breaking ties by the prefixes themselves.
+fn top_hotspots(writes: &HashMap<String, u64>) -> Vec<KeyPrefixWrites> {
+    let mut hotspots: Vec<_> = writes
+        .iter()
+        .map(|(prefix, &writes)| KeyPrefixWrites {
+            prefix: prefix.clone(),
+            writes,
+        })
+        .collect();
+    hotspots.sort_unstable_by(|a, b| {
+        b.writes
+            .cmp(&a.writes)
+            .then_with(|| a.prefix.cmp(&b.prefix))
+    });
+    hotspots.truncate(MAX_WRITE_HOTSPOTS);
+    hotspots
+}
+
+router! {STATS,
+    // The most frequently written storage key prefixes
+    ( "write_hotspots" ) -> WriteHotspotsReport = write_hotspots,
+
+    // The node-local metrics of the ledger
+    ( "node_metrics" ) -> NodeMetricsReport = node_metrics,
+}
+
+/// Read the most frequently written storage key prefixes.
+fn write_hotspots<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<WriteHotspotsReport>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Ok(ctx.write_hotspots.report())
+}
+
+/// Read the node-local metrics of the ledger.
+fn node_metrics<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<NodeMetricsReport>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    Ok(ctx.node_metrics.report())
+}
+
+#[cfg(test)]
+mod test_stats_queries {
+    use super::*;
+
+    /// Test that the write counters of the current epoch are accumulated
+    /// across blocks, and reset on new epochs.
+    #[test]
+    fn test_write_hotspots_per_epoch() {
+        let hotspots = WriteHotspots::default();
+        let key = Key::parse("token/balance/owner").unwrap();
+        let prefix = WriteHotspots::key_prefix(&key);
+        assert_eq!(prefix, "token/balance");
+
+        let writes = |n| HashMap::from([(prefix.clone(), n)]);
+        hotspots.record_block(BlockHeight(1), Epoch(0), writes(2));
+        hotspots.record_block(BlockHeight(2), Epoch(0), writes(3));
+        let report = hotspots.report();
+        assert_eq!(report.height, BlockHeight(2));
+        assert_eq!(report.last_block[0].writes, 3);
+        assert_eq!(report.current_epoch[0].writes, 5);
+
+        hotspots.record_block(BlockHeight(3), Epoch(1), writes(1));
+        let report = hotspots.report();
+        assert_eq!(report.epoch, Epoch(1));
+        assert_eq!(report.current_epoch[0].writes, 1);
+    }
+}
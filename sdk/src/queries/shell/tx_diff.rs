@@ -0,0 +1,269 @@
+//! Hierarchical diffs of the storage changes made by a dry-run tx.
+//!
+//! Rather than raw key/value bytes, the changes are grouped by the account
+//! they belong to and by the subspace of the account they were made in,
+//! with the values of known types (such as balances and bonds) decoded,
+//! such that clients can show a human-readable preview of what a tx would
+//! change before it is signed.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::types::address::Address;
+use namada_core::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use namada_core::types::token;
+use namada_core::types::transaction::TxResult;
+use namada_proof_of_stake::storage::{is_bond_key, is_unbond_key};
+
+/// The subspace of balance changes.
+pub const BALANCES_SUBSPACE: &str = "balances";
+
+/// The subspace of bond changes.
+pub const BONDS_SUBSPACE: &str = "bonds";
+
+/// The subspace of unbond changes.
+pub const UNBONDS_SUBSPACE: &str = "unbonds";
+
+/// Result of dry running a tx, along with the diff of its storage changes.
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct DryRunDiff {
+    /// The result of the dry run
+    pub result: TxResult,
+    /// The changes the tx would make to storage
+    pub diff: TxStateDiff,
+}
+
+/// The storage changes made by a tx, grouped by account.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct TxStateDiff {
+    /// The changes made to the storage of each account
+    pub accounts: BTreeMap<Address, AccountDiff>,
+    /// The changes made to keys not belonging to any account
+    pub other: Vec<StateChange>,
+}
+
+/// The storage changes made to some account, grouped by subspace.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct AccountDiff {
+    /// The changes made in each subspace of the account. Decoded changes
+    /// are found in the [`BALANCES_SUBSPACE`], [`BONDS_SUBSPACE`] and
+    /// [`UNBONDS_SUBSPACE`]. Any other change is found in the subspace
+    /// named after the first segment of its key following the account.
+    pub subspaces: BTreeMap<String, Vec<StateChange>>,
+}
+
+/// A change of the value of a storage key.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum StateChange {
+    /// The balance of the account in some token changed
+    Balance {
+        /// The token of the balance
+        token: Address,
+        /// The balance before the tx
+        old: token::Amount,
+        /// The balance after the tx
+        new: token::Amount,
+    },
+    /// A bond of the account changed
+    Bond {
+        /// The validator bonded to
+        validator: Address,
+        /// The epoch at which the bond starts
+        start: Epoch,
+        /// The bonded amount before the tx
+        old: token::Amount,
+        /// The bonded amount after the tx
+        new: token::Amount,
+    },
+    /// An unbond of the account changed
+    Unbond {
+        /// The validator unbonded from
+        validator: Address,
+        /// The epoch at which the unbonded bond started
+        start: Epoch,
+        /// The epoch at which the unbond can be withdrawn
+        withdraw: Epoch,
+        /// The unbonded amount before the tx
+        old: token::Amount,
+        /// The unbonded amount after the tx
+        new: token::Amount,
+    },
+    /// The value of a key whose type is not known changed. A value of
+    /// [`None`] means that the key is not present.
+    Raw {
+        /// The changed key
+        key: Key,
+        /// The value before the tx
+        old: Option<Vec<u8>>,
+        /// The value after the tx
+        new: Option<Vec<u8>>,
+    },
+}
+
+impl TxStateDiff {
+    /// Add a change of the value of the given storage key to the diff,
+    /// decoding the values of known types. A value of [`None`] means that
+    /// the key is not present. Keys whose value did not change are
+    /// ignored.
+    pub fn insert(
+        &mut self,
+        key: Key,
+        old: Option<Vec<u8>>,
+        new: Option<Vec<u8>>,
+    ) {
+        if old == new {
+            return;
+        }
+        if let Some([token, owner]) = token::is_any_token_balance_key(&key) {
+            if let Some((old, new)) = decode_amounts(&old, &new) {
+                let change = StateChange::Balance {
+                    token: token.clone(),
+                    old,
+                    new,
+                };
+                return self.push(owner.clone(), BALANCES_SUBSPACE, change);
+            }
+        }
+        if let Some((bond_id, start)) = is_bond_key(&key) {
+            if let Some((old, new)) = decode_amounts(&old, &new) {
+                let change = StateChange::Bond {
+                    validator: bond_id.validator,
+                    start,
+                    old,
+                    new,
+                };
+                return self.push(bond_id.source, BONDS_SUBSPACE, change);
+            }
+        }
+        if let Some((bond_id, start, withdraw)) = is_unbond_key(&key) {
+            if let Some((old, new)) = decode_amounts(&old, &new) {
+                let change = StateChange::Unbond {
+                    validator: bond_id.validator,
+                    start,
+                    withdraw,
+                    old,
+                    new,
+                };
+                return self.push(bond_id.source, UNBONDS_SUBSPACE, change);
+            }
+        }
+        match key.segments.as_slice() {
+            [DbKeySeg::AddressSeg(owner), rest @ ..] => {
+                let owner = owner.clone();
+                let subspace =
+                    rest.first().map(|seg| seg.raw()).unwrap_or_default();
+                self.push(owner, &subspace, StateChange::Raw { key, old, new })
+            }
+            _ => self.other.push(StateChange::Raw { key, old, new }),
+        }
+    }
+
+    fn push(&mut self, owner: Address, subspace: &str, change: StateChange) {
+        self.accounts
+            .entry(owner)
+            .or_default()
+            .subspaces
+            .entry(subspace.to_owned())
+            .or_default()
+            .push(change)
+    }
+}
+
+/// Decode the amounts stored under a key before and after a change,
+/// where a missing value stands for a zero amount.
+fn decode_amounts(
+    old: &Option<Vec<u8>>,
+    new: &Option<Vec<u8>>,
+) -> Option<(token::Amount, token::Amount)> {
+    let decode = |value: &Option<Vec<u8>>| match value {
+        Some(bytes) => token::Amount::try_from_slice(bytes).ok(),
+        None => Some(token::Amount::zero()),
+    };
+    Some((decode(old)?, decode(new)?))
+}
+
+#[cfg(test)]
+mod test {
+    use borsh_ext::BorshSerializeExt;
+    use namada_core::types::address::nam;
+    use namada_core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use namada_proof_of_stake::bond_handle;
+
+    use super::*;
+
+    /// Test that changes are grouped by account and subspace, with
+    /// the values of known types decoded.
+    #[test]
+    fn test_tx_state_diff() {
+        let owner = established_address_1();
+        let validator = established_address_2();
+        let mut diff = TxStateDiff::default();
+
+        let balance_key = token::balance_key(&nam(), &owner);
+        diff.insert(
+            balance_key,
+            Some(token::Amount::native_whole(10).serialize_to_vec()),
+            Some(token::Amount::native_whole(4).serialize_to_vec()),
+        );
+        let bond_key = bond_handle(&owner, &validator)
+            .get_data_handler()
+            .get_data_key(&Epoch(2));
+        diff.insert(
+            bond_key,
+            None,
+            Some(token::Amount::native_whole(6).serialize_to_vec()),
+        );
+        let raw_key = Key::from(owner.to_db_key())
+            .push(&"threshold".to_owned())
+            .unwrap();
+        diff.insert(raw_key.clone(), None, Some(vec![1]));
+        let unchanged_key = Key::from(owner.to_db_key())
+            .push(&"unchanged".to_owned())
+            .unwrap();
+        diff.insert(unchanged_key, Some(vec![1]), Some(vec![1]));
+        let other_key = Key::parse("other").unwrap();
+        diff.insert(other_key.clone(), Some(vec![1]), None);
+
+        let subspaces = &diff.accounts[&owner].subspaces;
+        assert_eq!(subspaces.len(), 3);
+        assert_eq!(
+            subspaces[BALANCES_SUBSPACE],
+            vec![StateChange::Balance {
+                token: nam(),
+                old: token::Amount::native_whole(10),
+                new: token::Amount::native_whole(4),
+            }]
+        );
+        assert_eq!(
+            subspaces[BONDS_SUBSPACE],
+            vec![StateChange::Bond {
+                validator,
+                start: Epoch(2),
+                old: token::Amount::zero(),
+                new: token::Amount::native_whole(6),
+            }]
+        );
+        assert_eq!(
+            subspaces["threshold"],
+            vec![StateChange::Raw {
+                key: raw_key,
+                old: None,
+                new: Some(vec![1]),
+            }]
+        );
+        assert_eq!(
+            diff.other,
+            vec![StateChange::Raw {
+                key: other_key,
+                old: Some(vec![1]),
+                new: None,
+            }]
+        );
+    }
+}
@@ -1,11 +1,19 @@
 use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-use namada_core::ledger::storage::{DBIter, StorageHasher, WlStorage, DB};
+use borsh::{BorshDeserialize, BorshSerialize};
+use namada_core::ledger::storage::{
+    DBIter, StorageHasher, TempWlStorage, WlStorage, DB,
+};
 use namada_core::ledger::storage_api;
+use namada_core::types::ethereum_structs;
 use namada_core::types::storage::BlockHeight;
 use thiserror::Error;
 
 use crate::events::log::EventLog;
+use crate::queries::{
+    CrashReports, NodeMetrics, ValsetProofCache, WriteHotspots,
+};
 use crate::tendermint::merkle::proof::ProofOps;
 pub use crate::tendermint::v0_37::abci::request::Query as RequestQuery;
 /// A request context provides read-only access to storage and WASM compilation
@@ -28,6 +36,59 @@ where
     /// limit the how many block heights in the past can the storage be
     /// queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// Crash reports of the ledger's supervised subsystems.
+    pub crash_reports: &'shell CrashReports,
+    /// The most frequently written storage key prefixes.
+    pub write_hotspots: &'shell WriteHotspots,
+    /// Node-local metrics of the ledger.
+    pub node_metrics: &'shell NodeMetrics,
+    /// Cache of the complete validator set update proofs.
+    pub valset_proof_cache: &'shell ValsetProofCache,
+    /// Taken from config `archive_endpoints`. Endpoints of archive nodes
+    /// suggested to clients querying heights that are no longer available.
+    pub archive_endpoints: &'shell [String],
+    /// The last Ethereum block processed by the Ethereum oracle of the
+    /// node, if it runs one.
+    pub oracle_last_processed_block: Option<ethereum_structs::BlockHeight>,
+}
+
+impl<'shell, D, H, VpCache, TxCache> RequestCtx<'shell, D, H, VpCache, TxCache>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    /// Return an isolated view of the storage, pinned at the last committed
+    /// block height. Unlike reading from [`Self::wl_storage`], the returned
+    /// view ignores any uncommitted changes in the write log, such that the
+    /// results of long-running queries (e.g. prefix dumps and their proofs)
+    /// are internally consistent.
+    #[inline]
+    pub fn committed_snapshot(&self) -> TempWlStorage<'shell, D, H> {
+        TempWlStorage::new(&self.wl_storage.storage)
+    }
+}
+
+/// A value shared between the ledger and its query handlers, e.g. the crash
+/// reports or the node metrics of a [`RequestCtx`]. Cloning it yields a new
+/// reference to the same value.
+///
+/// The updates of the shared values cannot leave them in an inconsistent
+/// state if they panic, so lock poisoning is ignored.
+#[derive(Debug, Default)]
+pub struct Shared<T>(Arc<Mutex<T>>);
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Shared<T> {
+    /// Lock the shared value.
+    #[inline]
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 /// A `Router` handles parsing read-only query requests and dispatching them to
@@ -75,6 +136,43 @@ pub enum Error {
     Query(String, u32),
     #[error("Invalid block height: {0} (overflown i64)")]
     InvalidHeight(BlockHeight),
+    #[error("{0}")]
+    PastHeight(PastHeightError),
+}
+
+/// The ABCI query response code of a [`PastHeightError`]. The value of the
+/// response holds the borsh-encoded error.
+pub const PAST_HEIGHT_ERROR_CODE: u32 = 2;
+
+/// Error returned by queries of a block height whose state is no longer
+/// available, either because it is beyond the node's
+/// `storage_read_past_height_limit`, or because it has been pruned.
+#[derive(
+    Error, Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+#[error(
+    "The state at height {requested_height} is no longer available. The \
+     earliest available height is {earliest_height}.{}",
+    fmt_archive_endpoints(archive_endpoints)
+)]
+pub struct PastHeightError {
+    /// The queried block height
+    pub requested_height: BlockHeight,
+    /// The earliest block height that may be queried
+    pub earliest_height: BlockHeight,
+    /// Endpoints of archive nodes that may still serve the queried height
+    pub archive_endpoints: Vec<String>,
+}
+
+fn fmt_archive_endpoints(archive_endpoints: &[String]) -> String {
+    if archive_endpoints.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " Try querying an archive node: {}",
+            archive_endpoints.join(", ")
+        )
+    }
 }
 
 /// Generic response from a query
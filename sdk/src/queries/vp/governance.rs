@@ -1,18 +1,47 @@
 // cd shared && cargo expand ledger::queries::vp::governance
 
-use namada_core::ledger::governance::parameters::GovernanceParameters;
+use std::collections::{BTreeMap, HashMap};
+
+use namada_core::ledger::governance::parameters::{
+    GovernanceParameters, ProposalEpochs,
+};
+use namada_core::ledger::governance::storage::keys as gov_storage;
 use namada_core::ledger::governance::storage::proposal::StorageProposal;
-use namada_core::ledger::governance::utils::Vote;
+use namada_core::ledger::governance::utils::{
+    compute_proposal_result, ProposalExecutionCountdown, ProposalResult,
+    ProposalStakeSnapshot, ProposalState, ProposalVotes, ProposalsFilter,
+    ProposalsPage, TallyVote, Vote, VotePower,
+};
 use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
-use namada_core::ledger::storage_api;
+use namada_core::ledger::storage_api::{self, StorageRead};
+use namada_core::types::address::Address;
+use namada_core::types::storage::Epoch;
+use namada_core::types::vote_extensions::validator_set_update::ValidatorSetSnapshot;
+use namada_ethereum_bridge::storage::eth_bridge_queries::EthBridgeQueries;
+use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::types::BondId;
+use namada_proof_of_stake::{
+    bond_amount, read_all_validator_addresses, read_pos_params,
+    read_total_stake, read_validator_stake,
+};
 
 use crate::queries::types::RequestCtx;
 
+/// The maximum number of proposals that may be returned by a single page of
+/// a proposals query.
+pub const MAX_PROPOSALS_PER_PAGE: u64 = 100;
+
 // Governance queries
 router! {GOV,
     ( "proposal" / [id: u64 ] ) -> Option<StorageProposal> = proposal_id,
     ( "proposal" / [id: u64 ] / "votes" ) -> Vec<Vote> = proposal_id_votes,
+    ( "proposal" / [id: u64 ] / "execution" ) -> Option<ProposalExecutionCountdown> = proposal_id_execution,
+    ( "proposal" / [id: u64 ] / "tally" ) -> Option<ProposalResult> = proposal_id_tally,
+    ( "proposal" / [id: u64 ] / "bridge_valset" ) -> Option<ValidatorSetSnapshot> = proposal_id_bridge_valset,
+    ( "proposals" / [limit: u64] / [after: opt u64] / [state: opt ProposalState] / [author: opt Address] / "from" / [from_epoch: opt Epoch] / "to" / [to_epoch: opt Epoch] )
+        -> ProposalsPage = proposals_page,
     ( "parameters" ) -> GovernanceParameters = parameters,
+    ( "proposal_epochs" ) -> Option<ProposalEpochs> = proposal_epochs,
 }
 
 /// Find if the given address belongs to a validator account.
@@ -39,6 +68,158 @@ where
     storage_api::governance::get_proposal_votes(ctx.wl_storage, id)
 }
 
+/// Get the countdown to the execution of a passed proposal, if its execution
+/// has been delayed.
+fn proposal_id_execution<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    id: u64,
+) -> storage_api::Result<Option<ProposalExecutionCountdown>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    let execution_epoch =
+        storage_api::governance::get_proposal_execution_epoch(
+            ctx.wl_storage,
+            id,
+        )?;
+    Ok(execution_epoch.map(|execution_epoch| {
+        ProposalExecutionCountdown::new(execution_epoch, current_epoch)
+    }))
+}
+
+/// Tally the votes of a proposal with the voting power snapshotted when it
+/// entered its voting period. If the proposal has already been tallied, its
+/// final result is returned instead.
+fn proposal_id_tally<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    id: u64,
+) -> storage_api::Result<Option<ProposalResult>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let proposal = match storage_api::governance::get_proposal_by_id(
+        ctx.wl_storage,
+        id,
+    )? {
+        Some(proposal) => proposal,
+        None => return Ok(None),
+    };
+
+    let result_key = gov_storage::get_proposal_result_key(id);
+    if let Some(result) = ctx.wl_storage.read(&result_key)? {
+        return Ok(Some(result));
+    }
+
+    let is_steward =
+        storage_api::pgf::is_steward(ctx.wl_storage, &proposal.author)?;
+    let tally_type = proposal.get_tally_type(is_steward);
+
+    let params = read_pos_params(ctx.wl_storage)?;
+    let snapshot = read_proposal_stake_snapshot(
+        ctx.wl_storage,
+        &params,
+        id,
+        proposal.voting_start_epoch,
+    )?;
+    let votes = compute_proposal_votes(ctx.wl_storage, id, &snapshot)?;
+    let veto_threshold =
+        storage_api::governance::get_veto_threshold(ctx.wl_storage)?;
+
+    Ok(Some(compute_proposal_result(
+        votes,
+        snapshot.total_voting_power,
+        tally_type,
+        veto_threshold,
+    )))
+}
+
+/// Get the Ethereum bridge validator sets snapshotted when a proposal that
+/// changes the bridge entered its voting period. The validator sets of an
+/// expedited proposal, which are not snapshotted, are read from its start
+/// epoch. Snapshots are pruned once the proposal is executed or rejected;
+/// the one of a proposal that rotated the Bridge contract is kept until the
+/// rotation is applied, see the `contracts/rotation_valset` eth bridge
+/// query.
+fn proposal_id_bridge_valset<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    id: u64,
+) -> storage_api::Result<Option<ValidatorSetSnapshot>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if let Some(snapshot) =
+        storage_api::governance::get_proposal_bridge_valset_snapshot(
+            ctx.wl_storage,
+            id,
+        )?
+    {
+        return Ok(Some(snapshot));
+    }
+    let proposal = match storage_api::governance::get_proposal_by_id(
+        ctx.wl_storage,
+        id,
+    )? {
+        Some(proposal) => proposal,
+        None => return Ok(None),
+    };
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    if !proposal.r#type.affects_eth_bridge()
+        || proposal.voting_end_height.is_none()
+        || proposal.voting_start_epoch > current_epoch
+    {
+        return Ok(None);
+    }
+    Ok(Some(
+        ctx.wl_storage
+            .ethbridge_queries()
+            .get_validator_set_snapshot(Some(proposal.voting_start_epoch)),
+    ))
+}
+
+/// A page of the proposals matching the given filters, ordered by their id,
+/// starting after the `after` id, if any
+fn proposals_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    limit: u64,
+    after: Option<u64>,
+    state: Option<ProposalState>,
+    author: Option<Address>,
+    from_epoch: Option<Epoch>,
+    to_epoch: Option<Epoch>,
+) -> storage_api::Result<ProposalsPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if limit == 0 || limit > MAX_PROPOSALS_PER_PAGE {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid page size {limit}. Between 1 and \
+                 {MAX_PROPOSALS_PER_PAGE} proposals may be queried at once."
+            ),
+        )));
+    }
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    let filter = ProposalsFilter {
+        state,
+        author,
+        from_epoch,
+        to_epoch,
+    };
+    storage_api::governance::get_proposals_page(
+        ctx.wl_storage,
+        current_epoch,
+        &filter,
+        after,
+        limit as usize,
+    )
+}
+
 /// Get the governane parameters
 fn parameters<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -49,3 +230,131 @@ where
 {
     storage_api::governance::get_parameters(ctx.wl_storage)
 }
+
+/// Get the earliest epochs accepted for a proposal submitted in the current
+/// epoch
+fn proposal_epochs<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<ProposalEpochs>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let params = storage_api::governance::get_parameters(ctx.wl_storage)?;
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    Ok(params.earliest_proposal_epochs(current_epoch))
+}
+
+/// Take a snapshot of the voting power of the validators at the given epoch
+pub fn take_stake_snapshot<S>(
+    storage: &S,
+    params: &PosParams,
+    epoch: Epoch,
+) -> storage_api::Result<ProposalStakeSnapshot>
+where
+    S: StorageRead,
+{
+    let total_voting_power = read_total_stake(storage, params, epoch)?;
+    let mut validator_voting_power = BTreeMap::new();
+    for validator in read_all_validator_addresses(storage, epoch)? {
+        let stake = read_validator_stake(storage, params, &validator, epoch)?;
+        if !stake.is_zero() {
+            validator_voting_power.insert(validator, stake);
+        }
+    }
+
+    Ok(ProposalStakeSnapshot {
+        epoch,
+        total_voting_power,
+        validator_voting_power,
+    })
+}
+
+/// Read the voting power snapshot of a proposal. Expedited proposals enter
+/// their voting period as soon as they are submitted, so their snapshot is
+/// taken from the voting start epoch when they are tallied instead.
+pub fn read_proposal_stake_snapshot<S>(
+    storage: &S,
+    params: &PosParams,
+    proposal_id: u64,
+    voting_start_epoch: Epoch,
+) -> storage_api::Result<ProposalStakeSnapshot>
+where
+    S: StorageRead,
+{
+    match storage_api::governance::get_proposal_stake_snapshot(
+        storage,
+        proposal_id,
+    )? {
+        Some(snapshot) => Ok(snapshot),
+        None => take_stake_snapshot(storage, params, voting_start_epoch),
+    }
+}
+
+/// Collect the votes of a proposal with the voting power of the voters in the
+/// given snapshot
+pub fn compute_proposal_votes<S>(
+    storage: &S,
+    proposal_id: u64,
+    snapshot: &ProposalStakeSnapshot,
+) -> storage_api::Result<ProposalVotes>
+where
+    S: StorageRead,
+{
+    let votes =
+        storage_api::governance::get_proposal_votes(storage, proposal_id)?;
+
+    let mut validators_vote: HashMap<Address, TallyVote> = HashMap::default();
+    let mut validator_voting_power: HashMap<Address, VotePower> =
+        HashMap::default();
+    let mut delegators_vote: HashMap<Address, HashMap<Address, TallyVote>> =
+        HashMap::default();
+    let mut delegator_voting_power: HashMap<
+        Address,
+        HashMap<Address, VotePower>,
+    > = HashMap::default();
+
+    for vote in votes {
+        if vote.is_validator() {
+            let validator = vote.validator.clone();
+            let vote_data = vote.data.clone();
+
+            let validator_stake = snapshot
+                .validator_voting_power
+                .get(&validator)
+                .copied()
+                .unwrap_or_default();
+
+            validators_vote.insert(validator.clone(), vote_data.into());
+            validator_voting_power.insert(validator, validator_stake);
+        } else {
+            let validator = vote.validator.clone();
+            let delegator = vote.delegator.clone();
+            let vote_data = vote.data.clone();
+
+            let bond_id = BondId {
+                source: delegator.clone(),
+                validator: validator.clone(),
+            };
+            let delegator_stake =
+                bond_amount(storage, &bond_id, snapshot.epoch)
+                    .unwrap_or_default();
+
+            delegators_vote
+                .entry(delegator.clone())
+                .or_default()
+                .insert(validator.clone(), vote_data.into());
+            delegator_voting_power
+                .entry(delegator)
+                .or_default()
+                .insert(validator, delegator_stake);
+        }
+    }
+
+    Ok(ProposalVotes {
+        validators_vote,
+        validator_voting_power,
+        delegators_vote,
+        delegator_voting_power,
+    })
+}
@@ -0,0 +1,37 @@
+use namada_core::ledger::htlc::TimeLock;
+use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+use namada_core::ledger::storage_api;
+use namada_core::types::address::Address;
+
+use crate::queries::types::RequestCtx;
+
+// HTLC validity predicate queries
+router! {HTLC,
+    ( "time_lock" / [ id: u64 ] ) -> Option<TimeLock> = time_lock,
+    ( "time_locks" / [ owner: Address ] ) -> Vec<(u64, TimeLock)> = time_locks,
+}
+
+/// Query the time lock with the given id
+fn time_lock<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    id: u64,
+) -> storage_api::Result<Option<TimeLock>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    storage_api::htlc::read_time_lock(ctx.wl_storage, id)
+}
+
+/// Query the time locks which the given address is either the source or the
+/// receiver of
+fn time_locks<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+) -> storage_api::Result<Vec<(u64, TimeLock)>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    storage_api::htlc::read_time_locks_of(ctx.wl_storage, &owner)
+}
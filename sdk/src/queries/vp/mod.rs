@@ -7,7 +7,10 @@ pub use pos::Pos;
 use pos::POS;
 pub use token::Token;
 use token::TOKEN;
-mod governance;
+pub mod governance;
+pub use htlc::Htlc;
+use htlc::HTLC;
+mod htlc;
 pub use pgf::Pgf;
 use pgf::PGF;
 mod pgf;
@@ -21,4 +24,5 @@ router! {VP,
     ( "token" ) = (sub TOKEN),
     ( "governance" ) = (sub GOV),
     ( "pgf" ) = (sub PGF),
+    ( "htlc" ) = (sub HTLC),
 }
@@ -6,32 +6,56 @@ use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
 use namada_core::ledger::storage_api;
 use namada_core::ledger::storage_api::collections::lazy_map;
-use namada_core::ledger::storage_api::OptionExt;
+use namada_core::ledger::storage_api::{OptionExt, ResultExt};
 use namada_core::types::address::Address;
+use namada_core::types::dec::Dec;
 use namada_core::types::key::common;
-use namada_core::types::storage::Epoch;
+use namada_core::types::storage::{BlockHeight, Epoch};
 use namada_core::types::token;
-use namada_proof_of_stake::parameters::PosParams;
+use namada_core::types::vote_extensions::heartbeat::BuildInfo;
+use namada_proof_of_stake::parameters::{
+    BridgeLivenessParams, InsuranceParams, PosParams,
+};
 use namada_proof_of_stake::types::{
-    BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, CommissionPair,
-    Slash, ValidatorMetaData, ValidatorState, WeightedValidator,
+    BondId, BondsAndUnbondsDetail, BondsAndUnbondsDetails, BridgeParticipation,
+    CommissionPair, DelegationsPage, PendingRedelegation, ProjectedRewards,
+    Slash, ValidatorMetaData, ValidatorSetDiff, ValidatorState,
+    WeightedValidator, WithdrawableUnbond,
 };
 use namada_proof_of_stake::{
     self, bond_amount, bond_handle, find_all_enqueued_slashes,
     find_all_slashes, find_delegation_validators, find_delegations,
-    query_reward_tokens, read_all_validator_addresses,
+    find_delegations_page, find_pending_redelegations,
+    find_validator_delegations_page, find_withdrawable_unbonds,
+    projected_rewards, query_claimable_rewards, query_reward_tokens,
+    read_all_validator_addresses,
     read_below_capacity_validator_set_addresses_with_stake,
-    read_consensus_validator_set_addresses_with_stake, read_pos_params,
-    read_total_stake, read_validator_description,
-    read_validator_discord_handle, read_validator_email,
-    read_validator_last_slash_epoch, read_validator_max_commission_rate_change,
-    read_validator_stake, read_validator_website, unbond_handle,
+    read_block_proposer_address, read_bridge_liveness_params,
+    read_bridge_participation,
+    read_consensus_validator_set_addresses_with_stake, read_insurance_params,
+    read_insurance_pool_balance, read_max_validator_stake_share,
+    read_pos_params, read_total_stake, read_validator_commission_history,
+    read_validator_heartbeats, read_validator_last_slash_epoch,
+    read_validator_max_commission_rate_change, read_validator_metadata,
+    read_validator_stake, read_validator_total_slashed, unbond_handle,
     validator_commission_rate_handle, validator_incoming_redelegations_handle,
     validator_slashes_handle, validator_state_handle,
 };
 
 use crate::queries::types::RequestCtx;
 
+/// The maximum number of blocks that may be spanned by a single block
+/// proposers query.
+pub const MAX_BLOCK_PROPOSERS_RANGE: u64 = 1_000;
+
+/// The maximum number of slashes that may be returned by a single page of
+/// a slash history query.
+pub const MAX_SLASHES_PER_PAGE: u64 = 100;
+
+/// The maximum number of delegations that may be returned by a single page
+/// of a delegations query.
+pub const MAX_DELEGATIONS_PER_PAGE: u64 = 100;
+
 // PoS validity predicate queries
 router! {POS,
     ( "validator" ) = {
@@ -46,12 +70,24 @@ router! {POS,
         ( "slashes" / [validator: Address] )
             -> Vec<Slash> = validator_slashes,
 
+        ( "slash_history" / [validator: Address] / [page: u64] / [per_page: u64] )
+            -> SlashHistory = validator_slash_history,
+
+        ( "delegations" / [validator: Address] / [limit: u64] / [after: opt Address] / [epoch: opt Epoch] )
+            -> DelegationsPage = validator_delegations_page,
+
         ( "commission" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<CommissionPair> = validator_commission,
 
+        ( "commission_history" / [validator: Address] )
+            -> BTreeMap<Epoch, Dec> = validator_commission_history,
+
         ( "metadata" / [validator: Address] )
             -> Option<ValidatorMetaData> = validator_metadata,
 
+        ( "all_metadata" )
+            -> BTreeMap<Address, ValidatorMetaData> = validators_metadata,
+
         ( "state" / [validator: Address] / [epoch: opt Epoch] )
             -> Option<ValidatorState> = validator_state,
 
@@ -69,11 +105,27 @@ router! {POS,
         ( "below_capacity" / [epoch: opt Epoch] )
             -> BTreeSet<WeightedValidator> = below_capacity_validator_set,
 
+        ( "diff" / [from_epoch: Epoch] / [to_epoch: opt Epoch] )
+            -> ValidatorSetDiff = consensus_validator_set_diff,
+
         // TODO: add "below_threshold"
     },
 
     ( "pos_params") -> PosParams = pos_params,
 
+    ( "insurance_params" ) -> InsuranceParams = insurance_params,
+
+    ( "insurance_pool" ) -> token::Amount = insurance_pool_balance,
+
+    ( "max_validator_stake_share" )
+        -> Option<Dec> = max_validator_stake_share,
+
+    ( "bridge_liveness_params" )
+        -> Option<BridgeLivenessParams> = bridge_liveness_params,
+
+    ( "bridge_participation" / [epoch: opt Epoch] )
+        -> BridgeParticipation = bridge_participation,
+
     ( "total_stake" / [epoch: opt Epoch] )
         -> token::Amount = total_stake,
 
@@ -83,6 +135,9 @@ router! {POS,
     ( "delegations_at" / [owner: Address] / [epoch: opt Epoch] )
         -> HashMap<Address, token::Amount> = delegations,
 
+    ( "delegations_page" / [owner: Address] / [limit: u64] / [after: opt Address] / [epoch: opt Epoch] )
+        -> DelegationsPage = delegations_page,
+
     ( "bond_deltas" / [source: Address] / [validator: Address] )
         -> HashMap<Epoch, token::Change> = bond_deltas,
 
@@ -92,6 +147,12 @@ router! {POS,
     ( "rewards" / [validator: Address] / [source: opt Address] )
         -> token::Amount = rewards,
 
+    ( "claimable_rewards" / [source: Address] )
+        -> BTreeMap<Address, token::Amount> = claimable_rewards,
+
+    ( "projected_rewards" / [validator: Address] / [amount: token::DenominatedAmount] )
+        -> Option<ProjectedRewards> = validator_projected_rewards,
+
     ( "bond_with_slashing" / [source: Address] / [validator: Address] / [epoch: opt Epoch] )
         -> token::Amount = bond_with_slashing,
 
@@ -107,6 +168,15 @@ router! {POS,
     ( "bonds_and_unbonds" / [source: opt Address] / [validator: opt Address] )
         -> BondsAndUnbondsDetails = bonds_and_unbonds,
 
+    ( "bonds_and_unbonds_in_epochs" / [from_epoch: Epoch] / [to_epoch: opt Epoch] )
+        -> BondsAndUnbondsDetails = bonds_and_unbonds_in_epochs,
+
+    ( "pending_redelegations" / [delegator: Address] )
+        -> Vec<PendingRedelegation> = pending_redelegations,
+
+    ( "withdrawable_unbonds" / [delegator: Address] )
+        -> Vec<WithdrawableUnbond> = withdrawable_unbonds,
+
     ( "enqueued_slashes" )
         -> HashMap<Address, BTreeMap<Epoch, Vec<Slash>>> = enqueued_slashes,
 
@@ -122,6 +192,26 @@ router! {POS,
     ( "has_bonds" / [source: Address] )
         -> bool = has_bonds,
 
+    ( "block_proposer" / [height: BlockHeight] )
+        -> Option<Address> = block_proposer,
+
+    ( "block_proposers" / [from: BlockHeight] / [to: BlockHeight] )
+        -> BTreeMap<BlockHeight, Address> = block_proposers,
+
+    ( "validator_heartbeats" / [epoch: opt Epoch] )
+        -> BTreeMap<Address, BuildInfo> = validator_heartbeats,
+
+}
+
+/// A page of the history of the slashes of a validator.
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct SlashHistory {
+    /// The slashes on the queried page, in the order they were processed
+    pub slashes: Vec<Slash>,
+    /// The total number of slashes recorded for the validator
+    pub total_slashes: u64,
+    /// The total amount of tokens slashed from the stake of the validator
+    pub total_slashed: token::Amount,
 }
 
 /// Enriched bonds data with extra information calculated from the data queried
@@ -152,6 +242,10 @@ pub type EnrichedBondsAndUnbondsDetails =
 /// with extra information calculated from the data queried from the node.
 pub type EnrichedBondsAndUnbondsDetail = Enriched<BondsAndUnbondsDetail>;
 
+/// A summary of the bonds and unbonds of a source, with the set of the
+/// validators they are bonded to as data.
+pub type BondsSummary = Enriched<BTreeSet<Address>>;
+
 impl<T> Enriched<T> {
     /// The bonds amount reduced by slashes
     pub fn bonds_total_active(&self) -> token::Amount {
@@ -177,6 +271,66 @@ where
     read_pos_params(ctx.wl_storage)
 }
 
+/// Get the parameters of the slashing insurance pool
+fn insurance_params<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<InsuranceParams>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_insurance_params(ctx.wl_storage)
+}
+
+/// Get the maximum share of the total stake that a single validator may
+/// hold, if governance has set one
+fn max_validator_stake_share<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<Dec>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_max_validator_stake_share(ctx.wl_storage)
+}
+
+/// Get the parameters of the jailing of validators for bridge liveness
+/// failures, if governance has set them
+fn bridge_liveness_params<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<Option<BridgeLivenessParams>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_bridge_liveness_params(ctx.wl_storage)
+}
+
+/// Get the participation of the validators in the bridge vote extensions in
+/// the given epoch, defaulting to the last committed epoch
+fn bridge_participation<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<BridgeParticipation>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_bridge_participation(ctx.wl_storage, epoch)
+}
+
+/// Get the amount of tokens held by the slashing insurance pool
+fn insurance_pool_balance<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_insurance_pool_balance(ctx.wl_storage)
+}
+
 /// Find if the given address belongs to a validator account.
 fn is_validator<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -247,6 +401,19 @@ where
     }
 }
 
+/// Get the history of the validator commission rate, keyed by the epoch at
+/// which each rate takes effect
+fn validator_commission_history<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+) -> storage_api::Result<BTreeMap<Epoch, Dec>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_validator_commission_history(ctx.wl_storage, &validator)
+}
+
 /// Get the validator metadata
 fn validator_metadata<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -256,22 +423,27 @@ where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
 {
-    let email = read_validator_email(ctx.wl_storage, &validator)?;
-    let description = read_validator_description(ctx.wl_storage, &validator)?;
-    let website = read_validator_website(ctx.wl_storage, &validator)?;
-    let discord_handle =
-        read_validator_discord_handle(ctx.wl_storage, &validator)?;
+    read_validator_metadata(ctx.wl_storage, &validator)
+}
 
-    // Email is the only required field for a validator in storage
-    match email {
-        Some(email) => Ok(Some(ValidatorMetaData {
-            email,
-            description,
-            website,
-            discord_handle,
-        })),
-        _ => Ok(None),
+/// Get the metadata of all the validators in the last committed epoch that
+/// have some
+fn validators_metadata<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+) -> storage_api::Result<BTreeMap<Address, ValidatorMetaData>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = ctx.wl_storage.storage.last_epoch;
+    let mut metadata = BTreeMap::new();
+    for validator in read_all_validator_addresses(ctx.wl_storage, epoch)? {
+        if let Some(data) = read_validator_metadata(ctx.wl_storage, &validator)?
+        {
+            metadata.insert(validator, data);
+        }
     }
+    Ok(metadata)
 }
 
 /// Get the validator state
@@ -375,6 +547,25 @@ where
     )
 }
 
+/// Get the changes of the consensus validator set from `from_epoch` to
+/// `to_epoch`, or to the current epoch when `None`.
+fn consensus_validator_set_diff<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_epoch: Epoch,
+    to_epoch: Option<Epoch>,
+) -> storage_api::Result<ValidatorSetDiff>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let to_epoch = to_epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    namada_proof_of_stake::consensus_validator_set_diff(
+        ctx.wl_storage,
+        from_epoch,
+        to_epoch,
+    )
+}
+
 /// Get the total stake in PoS system at the given epoch or current when `None`.
 fn total_stake<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -537,6 +728,35 @@ where
     )
 }
 
+fn claimable_rewards<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    source: Address,
+) -> storage_api::Result<BTreeMap<Address, token::Amount>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    query_claimable_rewards(ctx.wl_storage, &source, current_epoch)
+}
+
+fn validator_projected_rewards<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    amount: token::DenominatedAmount,
+) -> storage_api::Result<Option<ProjectedRewards>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let amount = amount
+        .increase_precision(token::NATIVE_MAX_DECIMAL_PLACES.into())
+        .into_storage_result()?
+        .amount;
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    projected_rewards(ctx.wl_storage, &validator, amount, current_epoch)
+}
+
 fn bonds_and_unbonds<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
     source: Option<Address>,
@@ -549,6 +769,61 @@ where
     namada_proof_of_stake::bonds_and_unbonds(ctx.wl_storage, source, validator)
 }
 
+/// Find the bonds and unbonds of all sources and validators that take effect
+/// in the given range of epochs (inclusive). If `to_epoch` is `None`, only
+/// the records taking effect in `from_epoch` are returned.
+fn bonds_and_unbonds_in_epochs<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from_epoch: Epoch,
+    to_epoch: Option<Epoch>,
+) -> storage_api::Result<BondsAndUnbondsDetails>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let to_epoch = to_epoch.unwrap_or(from_epoch);
+    if from_epoch > to_epoch {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid epoch range from {from_epoch} to {to_epoch}."),
+        )));
+    }
+    namada_proof_of_stake::bonds_and_unbonds_in_epochs(
+        ctx.wl_storage,
+        from_epoch,
+        to_epoch,
+    )
+}
+
+/// Find the redelegations of the given delegator which cannot be
+/// redelegated again yet, in the current epoch.
+fn pending_redelegations<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    delegator: Address,
+) -> storage_api::Result<Vec<PendingRedelegation>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    find_pending_redelegations(ctx.wl_storage, &delegator, current_epoch)
+}
+
+/// Find the unbonds of the given delegator that have not been withdrawn
+/// yet, with the number of epochs left until they can be withdrawn, in the
+/// current epoch.
+fn withdrawable_unbonds<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    delegator: Address,
+) -> storage_api::Result<Vec<WithdrawableUnbond>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let current_epoch = ctx.wl_storage.storage.last_epoch;
+    find_withdrawable_unbonds(ctx.wl_storage, &delegator, current_epoch)
+}
+
 /// Find all the validator addresses to whom the given `owner` address has
 /// some delegation in any epoch
 fn delegation_validators<D, H, V, T>(
@@ -577,6 +852,69 @@ where
     find_delegations(ctx.wl_storage, &owner, &epoch)
 }
 
+/// A page of the delegations of the given `owner` address, ordered by the
+/// address of their validator, starting after the `after` address, if any
+fn delegations_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    owner: Address,
+    limit: u64,
+    after: Option<Address>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<DelegationsPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_delegations_page_limit(limit)?;
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    find_delegations_page(
+        ctx.wl_storage,
+        &owner,
+        epoch,
+        after.as_ref(),
+        limit as usize,
+    )
+}
+
+/// A page of the delegations to the given `validator`, ordered by the
+/// address of their delegator, starting after the `after` address, if any
+fn validator_delegations_page<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    limit: u64,
+    after: Option<Address>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<DelegationsPage>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_delegations_page_limit(limit)?;
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    find_validator_delegations_page(
+        ctx.wl_storage,
+        &validator,
+        epoch,
+        after.as_ref(),
+        limit as usize,
+    )
+}
+
+/// Check the number of delegations requested in a single page
+fn check_delegations_page_limit(limit: u64) -> storage_api::Result<()> {
+    if limit == 0 || limit > MAX_DELEGATIONS_PER_PAGE {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid page size {limit}. Between 1 and \
+                 {MAX_DELEGATIONS_PER_PAGE} delegations may be queried at \
+                 once."
+            ),
+        )));
+    }
+    Ok(())
+}
+
 /// Validator slashes
 fn validator_slashes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -590,6 +928,46 @@ where
     slash_handle.iter(ctx.wl_storage)?.collect()
 }
 
+/// A page of the slashes recorded for a validator, along with the total
+/// amount of tokens slashed from its stake. Pages are numbered from 0.
+fn validator_slash_history<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    validator: Address,
+    page: u64,
+    per_page: u64,
+) -> storage_api::Result<SlashHistory>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if per_page == 0 || per_page > MAX_SLASHES_PER_PAGE {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid page size {per_page}. Between 1 and \
+                 {MAX_SLASHES_PER_PAGE} slashes may be queried at once."
+            ),
+        )));
+    }
+    let slash_handle = validator_slashes_handle(&validator);
+    let total_slashes = slash_handle.len(ctx.wl_storage)?;
+    let start = page.saturating_mul(per_page);
+    let end = start.saturating_add(per_page).min(total_slashes);
+    let mut slashes = vec![];
+    for index in start..end {
+        if let Some(slash) = slash_handle.get(ctx.wl_storage, index)? {
+            slashes.push(slash);
+        }
+    }
+    let total_slashed =
+        read_validator_total_slashed(ctx.wl_storage, &validator)?;
+    Ok(SlashHistory {
+        slashes,
+        total_slashes,
+        total_slashed,
+    })
+}
+
 /// All slashes
 fn slashes<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -625,6 +1003,66 @@ where
     namada_proof_of_stake::find_validator_by_raw_hash(ctx.wl_storage, tm_addr)
 }
 
+/// Native address of the proposer of the block at the given height
+fn block_proposer<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    height: BlockHeight,
+) -> storage_api::Result<Option<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_block_proposer_address(ctx.wl_storage, height)
+}
+
+/// Native addresses of the proposers of the blocks from height `from` up to
+/// and including height `to`. Blocks without a recorded proposer are
+/// omitted.
+fn block_proposers<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> storage_api::Result<BTreeMap<BlockHeight, Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if from > to || to.0 - from.0 >= MAX_BLOCK_PROPOSERS_RANGE {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid height range from {from} to {to}. At most \
+                 {MAX_BLOCK_PROPOSERS_RANGE} blocks may be queried at once."
+            ),
+        )));
+    }
+    let mut proposers = BTreeMap::new();
+    for height in from.0..=to.0 {
+        let height = BlockHeight(height);
+        if let Some(proposer) =
+            read_block_proposer_address(ctx.wl_storage, height)?
+        {
+            proposers.insert(height, proposer);
+        }
+    }
+    Ok(proposers)
+}
+
+/// Get the builds of the node software attested to by the heartbeats of
+/// validators in the given epoch, defaulting to the last committed epoch.
+/// The heartbeats of the epochs before the last one are pruned.
+fn validator_heartbeats<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<BTreeMap<Address, BuildInfo>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = epoch.unwrap_or(ctx.wl_storage.storage.last_epoch);
+    read_validator_heartbeats(ctx.wl_storage, epoch)
+}
+
 /// Native validator address by looking up the Tendermint address
 fn consensus_key_set<D, H, V, T>(
     ctx: RequestCtx<'_, D, H, V, T>,
@@ -678,6 +1116,39 @@ pub mod client_only_methods {
     }
 }
 
+/// Summarize the bonds and unbonds of the given source, with the amounts
+/// withdrawable in the given current epoch.
+pub(crate) fn bonds_summary<S>(
+    storage: &S,
+    source: &Address,
+    current_epoch: Epoch,
+) -> storage_api::Result<BondsSummary>
+where
+    S: storage_api::StorageRead,
+{
+    let bonds_and_unbonds = namada_proof_of_stake::bonds_and_unbonds(
+        storage,
+        Some(source.clone()),
+        None,
+    )?;
+    let Enriched {
+        data,
+        bonds_total,
+        bonds_total_slashed,
+        unbonds_total,
+        unbonds_total_slashed,
+        total_withdrawable,
+    } = enrich_bonds_and_unbonds(current_epoch, bonds_and_unbonds);
+    Ok(Enriched {
+        data: data.into_keys().map(|bond_id| bond_id.validator).collect(),
+        bonds_total,
+        bonds_total_slashed,
+        unbonds_total,
+        unbonds_total_slashed,
+        total_withdrawable,
+    })
+}
+
 /// Calculate extra information from the bonds and unbonds details.
 fn enrich_bonds_and_unbonds(
     current_epoch: Epoch,
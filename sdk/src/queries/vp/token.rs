@@ -1,15 +1,19 @@
 //! Token validity predicate queries
 
+use borsh::BorshDeserialize;
 use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
-use namada_core::ledger::storage_api;
 use namada_core::ledger::storage_api::token::read_denom;
+use namada_core::ledger::storage_api::{self, ResultExt};
 use namada_core::types::address::Address;
+use namada_core::types::storage::BlockHeight;
 use namada_core::types::token;
 
+use crate::queries::shell::check_committed_height;
 use crate::queries::RequestCtx;
 
 router! {TOKEN,
     ( "denomination" / [addr: Address] ) -> Option<token::Denomination> = denomination,
+    ( "balance" / [token: Address] / [owner: Address] / [height: BlockHeight] ) -> token::Amount = balance_at_height,
 }
 
 /// Get the number of decimal places (in base 10) for a
@@ -25,6 +29,36 @@ where
     read_denom(ctx.wl_storage, &addr)
 }
 
+/// Get the balance of the given `token` belonging to the given `owner` at the
+/// end of the block at the given `height`, read from the stored diffs of the
+/// balance. The height must be within the `storage_read_past_height_limit`
+/// of the last committed height, and its state must not have been pruned.
+fn balance_at_height<D, H, V, T>(
+    ctx: RequestCtx<'_, D, H, V, T>,
+    token: Address,
+    owner: Address,
+    height: BlockHeight,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_committed_height(&ctx, height)?;
+
+    let balance_key = token::balance_key(&token, &owner);
+    let (value, _gas) = ctx
+        .wl_storage
+        .storage
+        .read_with_height(&balance_key, height)
+        .into_storage_result()?;
+    value
+        .map(|bytes| {
+            token::Amount::try_from_slice(&bytes).into_storage_result()
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
 #[cfg(any(test, feature = "async-client"))]
 pub mod client_only_methods {
     use borsh::BorshDeserialize;
@@ -5,18 +5,25 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ops::ControlFlow;
 
 use borsh::BorshDeserialize;
+use borsh_ext::BorshSerializeExt;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
 use masp_primitives::sapling::Node;
-use namada_core::ledger::governance::parameters::GovernanceParameters;
+use namada_core::ledger::governance::parameters::{
+    GovernanceParameters, ProposalEpochs,
+};
 use namada_core::ledger::governance::storage::proposal::StorageProposal;
-use namada_core::ledger::governance::utils::Vote;
+use namada_core::ledger::governance::utils::{
+    ProposalExecutionCountdown, ProposalResult, ProposalsFilter, ProposalsPage,
+    Vote,
+};
 use namada_core::ledger::ibc::storage::{
     ibc_denom_key, ibc_denom_key_prefix, is_ibc_denom_key,
 };
 use namada_core::ledger::storage::LastBlock;
 use namada_core::types::account::Account;
 use namada_core::types::address::{Address, InternalAddress};
+use namada_core::types::dec::Dec;
 use namada_core::types::hash::Hash;
 use namada_core::types::key::common;
 use namada_core::types::storage::{
@@ -25,22 +32,31 @@ use namada_core::types::storage::{
 use namada_core::types::token::{
     Amount, DenominatedAmount, Denomination, MaspDenom,
 };
+use namada_core::types::vote_extensions::validator_set_update::ValidatorSetSnapshot;
 use namada_core::types::{storage, token};
-use namada_proof_of_stake::parameters::PosParams;
+use namada_proof_of_stake::parameters::{
+    BridgeLivenessParams, InsuranceParams, PosParams,
+};
 use namada_proof_of_stake::types::{
-    BondsAndUnbondsDetails, CommissionPair, ValidatorMetaData, ValidatorState,
+    BondsAndUnbondsDetails, BridgeParticipation, CommissionPair,
+    DelegationsPage, PendingRedelegation, ProjectedRewards, ValidatorMetaData,
+    ValidatorSetDiff, ValidatorState, WithdrawableUnbond,
 };
 use serde::Serialize;
 
 use crate::args::InputAmount;
 use crate::control_flow::time;
 use crate::error::{EncodingError, Error, QueryError, TxError};
+use crate::events::index::IndexedTx;
+use crate::events::log::{EventId, EventsFilter, EventsPage};
 use crate::events::Event;
 use crate::internal_macros::echo_error;
 use crate::io::Io;
 use crate::proto::Tx;
-use crate::queries::vp::pos::EnrichedBondsAndUnbondsDetails;
-use crate::queries::{Client, RPC};
+use crate::queries::vp::pos::{EnrichedBondsAndUnbondsDetails, SlashHistory};
+use crate::queries::{
+    AccountInfo, BatchQuery, BatchQueryResponse, Client, StateDiff, RPC,
+};
 use crate::tendermint::block::Height;
 use crate::tendermint::merkle::proof::ProofOps;
 use crate::tendermint_rpc::error::Error as TError;
@@ -171,6 +187,23 @@ pub async fn get_token_balance<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query token amount of owner at the end of the block at the given height,
+/// which must not be older than the `storage_read_past_height_limit` of the
+/// queried node.
+pub async fn get_token_balance_at_height<C: crate::queries::Client + Sync>(
+    client: &C,
+    token: &Address,
+    owner: &Address,
+    height: BlockHeight,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .token()
+            .balance_at_height(client, token, owner, &height)
+            .await,
+    )
+}
+
 /// Check if the given address is a known validator.
 pub async fn is_validator<C: crate::queries::Client + Sync>(
     client: &C,
@@ -230,6 +263,29 @@ pub async fn get_consensus_keys<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the address of the proposer of the block at the given height, if it
+/// has been recorded
+pub async fn query_block_proposer<C: crate::queries::Client + Sync>(
+    client: &C,
+    height: BlockHeight,
+) -> Result<Option<Address>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().block_proposer(client, &height).await,
+    )
+}
+
+/// Get the addresses of the proposers of the blocks from height `from` up to
+/// and including height `to`
+pub async fn query_block_proposers<C: crate::queries::Client + Sync>(
+    client: &C,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> Result<BTreeMap<BlockHeight, Address>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().block_proposers(client, &from, &to).await,
+    )
+}
+
 /// Check if the address exists on chain. Established address exists if it has a
 /// stored validity predicate. Implicit and internal addresses always return
 /// true.
@@ -365,6 +421,64 @@ pub async fn query_storage_value_bytes<C: crate::queries::Client + Sync>(
     })
 }
 
+/// Query the values of a batch of storage keys in a single request. The
+/// values are returned in the same order as the keys, along with the
+/// concatenated proofs of every key, if requested.
+pub async fn query_storage_values_bytes<C: crate::queries::Client + Sync>(
+    client: &C,
+    keys: &[storage::Key],
+    height: Option<BlockHeight>,
+    prove: bool,
+) -> Result<(Vec<Option<Vec<u8>>>, Option<ProofOps>), error::Error> {
+    let data = Some(keys.serialize_to_vec());
+    let response = convert_response::<C, _>(
+        RPC.shell()
+            .storage_values(client, data, height, prove)
+            .await,
+    )?;
+    Ok((response.data, response.proof))
+}
+
+/// Dispatch a batch of queries in a single request. The responses are
+/// returned in the same order as the queries.
+pub async fn query_batch<C: crate::queries::Client + Sync>(
+    client: &C,
+    queries: &[BatchQuery],
+) -> Result<Vec<BatchQueryResponse>, error::Error> {
+    let data = Some(queries.serialize_to_vec());
+    let response = convert_response::<C, _>(
+        RPC.shell().batch(client, data, None, false).await,
+    )?;
+    Ok(response.data)
+}
+
+/// Query a page of the events logged by the node matching the given filters,
+/// starting after the event with the given id, if any
+pub async fn query_events_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    limit: u64,
+    after: Option<EventId>,
+    filter: EventsFilter,
+) -> Result<EventsPage, error::Error> {
+    let EventsFilter {
+        event_type,
+        tx_hash,
+        account,
+    } = filter;
+    convert_response::<C, _>(
+        RPC.shell()
+            .events_page(
+                client,
+                &limit,
+                &after,
+                &event_type,
+                &tx_hash,
+                &account,
+            )
+            .await,
+    )
+}
+
 /// Query a range of storage values with a matching prefix and decode them with
 /// [`BorshDeserialize`]. Returns an iterator of the storage keys paired with
 /// their associated values.
@@ -410,6 +524,22 @@ pub async fn query_has_storage_key<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.shell().storage_has_key(client, key).await)
 }
 
+/// Query the keys changed after the block at height `from`, up to and
+/// including the block at height `to`, optionally along with their values at
+/// both heights
+pub async fn query_state_diff<C: crate::queries::Client + Sync>(
+    client: &C,
+    from: BlockHeight,
+    to: BlockHeight,
+    with_values: bool,
+) -> Result<Vec<StateDiff>, Error> {
+    convert_response::<C, _>(
+        RPC.shell()
+            .state_diff(client, &from, &to, &with_values)
+            .await,
+    )
+}
+
 /// Represents a query for an event pertaining to the specified transaction
 #[derive(Debug, Copy, Clone)]
 pub enum TxEventQuery<'a> {
@@ -471,6 +601,16 @@ pub async fn query_tx_events<C: crate::queries::Client + Sync>(
     }
 }
 
+/// Look up the result of a tx by its hash in the index of applied txs of the
+/// node. The hash of a wrapper tx resolves to the result of its inner tx once
+/// it's applied.
+pub async fn query_indexed_tx<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_hash: &Hash,
+) -> Result<Option<IndexedTx>, error::Error> {
+    convert_response::<C, _>(RPC.shell().indexed_tx(client, tx_hash).await)
+}
+
 /// Dry run a transaction
 pub async fn dry_run_tx<N: Namada>(
     context: &N,
@@ -487,6 +627,19 @@ pub async fn dry_run_tx<N: Namada>(
     Ok(result)
 }
 
+/// Execute a read-only view transaction against the last committed state,
+/// returning its output.
+pub async fn view_tx<C: crate::queries::Client + Sync>(
+    client: &C,
+    tx_bytes: Vec<u8>,
+) -> Result<namada_core::types::transaction::ViewTxResult, Error> {
+    let (data, height, prove) = (Some(tx_bytes), None, false);
+    convert_response::<C, _>(
+        RPC.shell().view_tx(client, data, height, prove).await,
+    )
+    .map(|response| response.data)
+}
+
 /// Data needed for broadcasting a tx and
 /// monitoring its progress on chain
 ///
@@ -668,6 +821,53 @@ pub async fn get_pos_params<C: crate::queries::Client + Sync>(
     convert_response::<C, _>(RPC.vp().pos().pos_params(client).await)
 }
 
+/// Get the parameters of the slashing insurance pool
+pub async fn get_insurance_params<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<InsuranceParams, error::Error> {
+    convert_response::<C, _>(RPC.vp().pos().insurance_params(client).await)
+}
+
+/// Get the maximum share of the total stake that a single validator may
+/// hold, if governance has set one
+pub async fn get_max_validator_stake_share<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<Dec>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().max_validator_stake_share(client).await,
+    )
+}
+
+/// Get the parameters of the jailing of validators for bridge liveness
+/// failures, if governance has set them
+pub async fn get_bridge_liveness_params<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<Option<BridgeLivenessParams>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().bridge_liveness_params(client).await,
+    )
+}
+
+/// Get the participation of the validators in the bridge vote extensions in
+/// the given epoch, or in the last committed epoch if `None`.
+pub async fn get_bridge_participation<C: crate::queries::Client + Sync>(
+    client: &C,
+    epoch: Option<Epoch>,
+) -> Result<BridgeParticipation, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().bridge_participation(client, &epoch).await,
+    )
+}
+
+/// Get the amount of tokens held by the slashing insurance pool
+pub async fn get_insurance_pool_balance<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<token::Amount, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().pos().insurance_pool_balance(client).await,
+    )
+}
+
 /// Get all validators in the given epoch
 pub async fn get_all_validators<C: crate::queries::Client + Sync>(
     client: &C,
@@ -769,6 +969,103 @@ pub async fn query_commission_rate<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query and return the history of a validator's commission rate, keyed by
+/// the epoch at which each rate takes effect
+pub async fn query_commission_history<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+) -> Result<BTreeMap<Epoch, Dec>, Error> {
+    convert_response::<C, BTreeMap<Epoch, Dec>>(
+        RPC.vp()
+            .pos()
+            .validator_commission_history(client, validator)
+            .await,
+    )
+}
+
+/// Query and return a page of the history of a validator's slashes, along
+/// with the total amount of tokens slashed from its stake
+pub async fn query_slash_history<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    page: u64,
+    per_page: u64,
+) -> Result<SlashHistory, Error> {
+    convert_response::<C, SlashHistory>(
+        RPC.vp()
+            .pos()
+            .validator_slash_history(client, validator, &page, &per_page)
+            .await,
+    )
+}
+
+/// Query a page of the delegations to the given validator, starting after the
+/// given delegator address, if any
+pub async fn query_validator_delegations<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    limit: u64,
+    after: Option<Address>,
+    epoch: Option<Epoch>,
+) -> Result<DelegationsPage, Error> {
+    convert_response::<C, DelegationsPage>(
+        RPC.vp()
+            .pos()
+            .validator_delegations_page(
+                client, validator, &limit, &after, &epoch,
+            )
+            .await,
+    )
+}
+
+/// Query a page of the delegations of the given owner, starting after the
+/// given validator address, if any
+pub async fn query_delegations_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+    limit: u64,
+    after: Option<Address>,
+    epoch: Option<Epoch>,
+) -> Result<DelegationsPage, Error> {
+    convert_response::<C, DelegationsPage>(
+        RPC.vp()
+            .pos()
+            .delegations_page(client, owner, &limit, &after, &epoch)
+            .await,
+    )
+}
+
+/// Query the amounts of reward tokens that the given source can claim, for
+/// each validator it has bonded to
+pub async fn query_claimable_rewards<C: crate::queries::Client + Sync>(
+    client: &C,
+    source: &Address,
+) -> Result<BTreeMap<Address, token::Amount>, Error> {
+    convert_response::<C, BTreeMap<Address, token::Amount>>(
+        RPC.vp().pos().claimable_rewards(client, source).await,
+    )
+}
+
+/// Query the staking rewards projected over a year for bonding the given
+/// amount of tokens to a validator. Returns `None` if the given address is
+/// not a validator.
+pub async fn query_projected_rewards<C: crate::queries::Client + Sync>(
+    client: &C,
+    validator: &Address,
+    amount: token::Amount,
+) -> Result<Option<ProjectedRewards>, Error> {
+    convert_response::<C, Option<ProjectedRewards>>(
+        RPC.vp()
+            .pos()
+            .validator_projected_rewards(
+                client,
+                validator,
+                &DenominatedAmount::native(amount),
+            )
+            .await,
+    )
+}
+
 /// Query and return validator's metadata, including the commission rate and max
 /// commission rate change
 pub async fn query_metadata<C: crate::queries::Client + Sync>(
@@ -788,6 +1085,16 @@ pub async fn query_metadata<C: crate::queries::Client + Sync>(
     Ok((metadata, commission_info))
 }
 
+/// Query and return the metadata of all the validators that have some, for
+/// explorers listing them
+pub async fn query_all_validators_metadata<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Result<BTreeMap<Address, ValidatorMetaData>, Error> {
+    convert_response::<C, BTreeMap<Address, ValidatorMetaData>>(
+        RPC.vp().pos().validators_metadata(client).await,
+    )
+}
+
 /// Query and return the incoming redelegation epoch for a given pair of source
 /// validator and delegator, if there is any.
 pub async fn query_incoming_redelegations<C: crate::queries::Client + Sync>(
@@ -803,6 +1110,33 @@ pub async fn query_incoming_redelegations<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query and return the redelegations of the given delegator whose tokens
+/// may still be slashed for an infraction of their source validator, and
+/// which therefore cannot be redelegated again yet.
+pub async fn query_pending_redelegations<C: crate::queries::Client + Sync>(
+    client: &C,
+    delegator: &Address,
+) -> Result<Vec<PendingRedelegation>, Error> {
+    convert_response::<C, Vec<PendingRedelegation>>(
+        RPC.vp()
+            .pos()
+            .pending_redelegations(client, delegator)
+            .await,
+    )
+}
+
+/// Query and return the unbonds of the given delegator that have not been
+/// withdrawn yet, along with the number of epochs left until they can be
+/// withdrawn.
+pub async fn query_withdrawable_unbonds<C: crate::queries::Client + Sync>(
+    client: &C,
+    delegator: &Address,
+) -> Result<Vec<WithdrawableUnbond>, Error> {
+    convert_response::<C, Vec<WithdrawableUnbond>>(
+        RPC.vp().pos().withdrawable_unbonds(client, delegator).await,
+    )
+}
+
 /// Query a validator's bonds for a given epoch
 pub async fn query_bond<C: crate::queries::Client + Sync>(
     client: &C,
@@ -838,6 +1172,28 @@ pub async fn get_account_info<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the account of an owner at the end of the block at the given height,
+/// which must not be older than the `storage_read_past_height_limit` of the
+/// queried node.
+pub async fn get_account_info_at_height<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+    height: BlockHeight,
+) -> Result<Option<Account>, error::Error> {
+    convert_response::<C, Option<Account>>(
+        RPC.shell().account_at_height(client, owner, &height).await,
+    )
+}
+
+/// Query the VP code hash, public keys, token balances and a summary of the
+/// bonds of an address at once
+pub async fn query_account_info<C: crate::queries::Client + Sync>(
+    client: &C,
+    owner: &Address,
+) -> Result<AccountInfo, error::Error> {
+    convert_response::<C, _>(RPC.shell().account_info(client, owner).await)
+}
+
 /// Query if the public_key is revealed
 pub async fn is_public_key_revealed<C: crate::queries::Client + Sync>(
     client: &C,
@@ -939,6 +1295,14 @@ pub async fn query_governance_parameters<C: crate::queries::Client + Sync>(
     unwrap_client_response::<C, _>(RPC.vp().gov().parameters(client).await)
 }
 
+/// Get the earliest epochs accepted for a proposal submitted in the current
+/// epoch, if the governance parameters allow any proposal
+pub async fn query_proposal_epochs<C: crate::queries::Client + Sync>(
+    client: &C,
+) -> Option<ProposalEpochs> {
+    unwrap_client_response::<C, _>(RPC.vp().gov().proposal_epochs(client).await)
+}
+
 /// Get the givernance parameters
 pub async fn query_proposal_votes<C: crate::queries::Client + Sync>(
     client: &C,
@@ -949,6 +1313,74 @@ pub async fn query_proposal_votes<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Query the countdown to the delayed execution of a passed proposal
+pub async fn query_proposal_execution<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal_id: u64,
+) -> Result<Option<ProposalExecutionCountdown>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .gov()
+            .proposal_id_execution(client, &proposal_id)
+            .await,
+    )
+}
+
+/// Query the tally of the votes of a proposal, or its result if it has
+/// already been tallied
+pub async fn query_proposal_tally<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal_id: u64,
+) -> Result<Option<ProposalResult>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp().gov().proposal_id_tally(client, &proposal_id).await,
+    )
+}
+
+/// Query the Ethereum bridge validator sets snapshotted for a proposal that
+/// changes the bridge
+pub async fn query_proposal_bridge_valset<C: crate::queries::Client + Sync>(
+    client: &C,
+    proposal_id: u64,
+) -> Result<Option<ValidatorSetSnapshot>, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .gov()
+            .proposal_id_bridge_valset(client, &proposal_id)
+            .await,
+    )
+}
+
+/// Query a page of the proposals matching the given filters, starting after
+/// the given proposal id, if any
+pub async fn query_proposals_page<C: crate::queries::Client + Sync>(
+    client: &C,
+    limit: u64,
+    after: Option<u64>,
+    filter: ProposalsFilter,
+) -> Result<ProposalsPage, error::Error> {
+    let ProposalsFilter {
+        state,
+        author,
+        from_epoch,
+        to_epoch,
+    } = filter;
+    convert_response::<C, _>(
+        RPC.vp()
+            .gov()
+            .proposals_page(
+                client,
+                &limit,
+                &after,
+                &state,
+                &author,
+                &from_epoch,
+                &to_epoch,
+            )
+            .await,
+    )
+}
+
 /// Get the bond amount at the given epoch
 pub async fn get_bond_amount_at<C: crate::queries::Client + Sync>(
     client: &C,
@@ -980,6 +1412,40 @@ pub async fn bonds_and_unbonds<C: crate::queries::Client + Sync>(
     )
 }
 
+/// Get the bonds and unbonds of all sources and validators that take effect in
+/// the given range of epochs (inclusive), with all details, grouped by their
+/// bond IDs. If `to_epoch` is `None`, only the bonds and unbonds taking effect
+/// in `from_epoch` are returned.
+pub async fn bonds_and_unbonds_in_epochs<C: crate::queries::Client + Sync>(
+    client: &C,
+    from_epoch: Epoch,
+    to_epoch: Option<Epoch>,
+) -> Result<BondsAndUnbondsDetails, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .bonds_and_unbonds_in_epochs(client, &from_epoch, &to_epoch)
+            .await,
+    )
+}
+
+/// Get the changes of the consensus validator set from `from_epoch` to
+/// `to_epoch`, or to the current epoch if `None`.
+pub async fn get_consensus_validator_set_diff<
+    C: crate::queries::Client + Sync,
+>(
+    client: &C,
+    from_epoch: Epoch,
+    to_epoch: Option<Epoch>,
+) -> Result<ValidatorSetDiff, error::Error> {
+    convert_response::<C, _>(
+        RPC.vp()
+            .pos()
+            .consensus_validator_set_diff(client, &from_epoch, &to_epoch)
+            .await,
+    )
+}
+
 /// Get bonds and unbonds with all details (slashes and rewards, if any)
 /// grouped by their bond IDs, enriched with extra information calculated from
 /// the data.
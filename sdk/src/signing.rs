@@ -25,7 +25,7 @@ use namada_core::types::token::Transfer;
 use namada_core::types::token::{Amount, DenominatedAmount, MaspDenom};
 use namada_core::types::transaction::account::{InitAccount, UpdateAccount};
 use namada_core::types::transaction::governance::{
-    InitProposalData, VoteProposalData,
+    InitProposalData, VoteProposalData, WithdrawProposalData,
 };
 use namada_core::types::transaction::pos::BecomeValidator;
 use namada_core::types::transaction::{pos, Fee};
@@ -49,12 +49,15 @@ use crate::masp::make_asset_type;
 use crate::proto::{MaspBuilder, Section, Tx};
 use crate::rpc::validate_amount;
 use crate::tx::{
-    TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM, TX_CHANGE_COMMISSION_WASM,
-    TX_CHANGE_CONSENSUS_KEY_WASM, TX_CHANGE_METADATA_WASM,
-    TX_CLAIM_REWARDS_WASM, TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM,
-    TX_INIT_ACCOUNT_WASM, TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM,
-    TX_REVEAL_PK, TX_TRANSFER_WASM, TX_UNBOND_WASM, TX_UNJAIL_VALIDATOR_WASM,
-    TX_UPDATE_ACCOUNT_WASM, TX_VOTE_PROPOSAL, TX_WITHDRAW_WASM, VP_USER_WASM,
+    TX_AUTO_COMPOUND_WASM, TX_BECOME_VALIDATOR_WASM, TX_BOND_WASM,
+    TX_CHANGE_COMMISSION_WASM, TX_CHANGE_CONSENSUS_KEY_WASM,
+    TX_CHANGE_ETH_BRIDGE_KEYS_WASM, TX_CHANGE_METADATA_WASM,
+    TX_CHANGE_PROTOCOL_KEY_WASM, TX_CLAIM_REWARDS_WASM,
+    TX_DEACTIVATE_VALIDATOR_WASM, TX_IBC_WASM, TX_INIT_ACCOUNT_WASM,
+    TX_INIT_PROPOSAL, TX_REACTIVATE_VALIDATOR_WASM, TX_REVEAL_PK,
+    TX_SLASHING_INSURANCE_WASM, TX_TRANSFER_WASM, TX_UNBOND_WASM,
+    TX_UNJAIL_VALIDATOR_WASM, TX_UPDATE_ACCOUNT_WASM, TX_VOTE_PROPOSAL,
+    TX_WITHDRAW_PROPOSAL, TX_WITHDRAW_WASM, VP_USER_WASM,
 };
 pub use crate::wallet::store::AddressVpType;
 use crate::wallet::{Wallet, WalletIo};
@@ -970,10 +973,26 @@ impl<'a> Display for LedgerProposalVote<'a> {
                 VoteType::PGFPayment => {
                     write!(f, "yay for PGF payment proposal")
                 }
+                VoteType::ParameterChange => {
+                    write!(f, "yay for parameter change proposal")
+                }
+                VoteType::CodeAllowlist => {
+                    write!(f, "yay for code allowlist proposal")
+                }
+                VoteType::ConsensusParams => {
+                    write!(f, "yay for consensus params proposal")
+                }
+                VoteType::TreasurySpend => {
+                    write!(f, "yay for treasury spend proposal")
+                }
+                VoteType::Text => {
+                    write!(f, "yay for text proposal")
+                }
             },
 
             StorageProposalVote::Nay => write!(f, "nay"),
             StorageProposalVote::Abstain => write!(f, "abstain"),
+            StorageProposalVote::Veto => write!(f, "veto"),
         }
     }
 }
@@ -998,6 +1017,11 @@ impl<'a> Display for LedgerProposalType<'a> {
             }
             ProposalType::PGFSteward(_) => write!(f, "PGF Steward"),
             ProposalType::PGFPayment(_) => write!(f, "PGF Payment"),
+            ProposalType::ParameterChange(_) => write!(f, "Parameter Change"),
+            ProposalType::CodeAllowlist(_) => write!(f, "Code Allowlist"),
+            ProposalType::ConsensusParams(_) => write!(f, "Consensus Params"),
+            ProposalType::TreasurySpend(_) => write!(f, "Treasury Spend"),
+            ProposalType::Text => write!(f, "Text"),
         }
     }
 }
@@ -1117,6 +1141,13 @@ pub async fn to_ledger_vector(
             tv.output
                 .push(format!("Discord handle : {}", discord_handle));
         }
+        if let Some(moniker) = &init_validator.moniker {
+            tv.output.push(format!("Moniker : {}", moniker));
+        }
+        if let Some(security_contact) = &init_validator.security_contact {
+            tv.output
+                .push(format!("Security contact : {}", security_contact));
+        }
 
         tv.output_expert.extend(vec![
             format!("Address : {}", init_validator.address),
@@ -1142,6 +1173,13 @@ pub async fn to_ledger_vector(
             tv.output_expert
                 .push(format!("Discord handle : {}", discord_handle));
         }
+        if let Some(moniker) = &init_validator.moniker {
+            tv.output_expert.push(format!("Moniker : {}", moniker));
+        }
+        if let Some(security_contact) = &init_validator.security_contact {
+            tv.output_expert
+                .push(format!("Security contact : {}", security_contact));
+        }
     } else if code_sec.tag == Some(TX_INIT_PROPOSAL.to_string()) {
         let init_proposal_data = InitProposalData::try_from_slice(
             &tx.data()
@@ -1181,6 +1219,9 @@ pub async fn to_ledger_vector(
             format!("Grace epoch : {}", init_proposal_data.grace_epoch),
             format!("Content : {}", HEXLOWER.encode(&extra.0)),
         ]);
+        if init_proposal_data.expedited {
+            tv.output.push("Expedited : true".to_string());
+        }
 
         if let Some(id) = init_proposal_data.id.as_ref() {
             tv.output_expert.push(format!("ID : {}", id));
@@ -1202,6 +1243,9 @@ pub async fn to_ledger_vector(
             format!("Grace epoch : {}", init_proposal_data.grace_epoch),
             format!("Content : {}", HEXLOWER.encode(&extra.0)),
         ]);
+        if init_proposal_data.expedited {
+            tv.output_expert.push("Expedited : true".to_string());
+        }
     } else if code_sec.tag == Some(TX_VOTE_PROPOSAL.to_string()) {
         let vote_proposal = VoteProposalData::try_from_slice(
             &tx.data()
@@ -1232,6 +1276,27 @@ pub async fn to_ledger_vector(
             tv.output_expert
                 .push(format!("Delegation : {}", delegation));
         }
+    } else if code_sec.tag == Some(TX_WITHDRAW_PROPOSAL.to_string()) {
+        let withdraw_proposal = WithdrawProposalData::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Withdraw_Proposal_0".to_string();
+
+        tv.output.extend(vec![
+            format!("Type : Withdraw Proposal"),
+            format!("ID : {}", withdraw_proposal.id),
+            format!("Author : {}", withdraw_proposal.author),
+        ]);
+
+        tv.output_expert.extend(vec![
+            format!("ID : {}", withdraw_proposal.id),
+            format!("Author : {}", withdraw_proposal.author),
+        ]);
     } else if code_sec.tag == Some(TX_REVEAL_PK.to_string()) {
         let public_key = common::PublicKey::try_from_slice(
             &tx.data()
@@ -1468,7 +1533,10 @@ pub async fn to_ledger_vector(
             ),
         ]);
     } else if code_sec.tag == Some(TX_UNBOND_WASM.to_string()) {
-        let unbond = pos::Unbond::try_from_slice(
+        let pos::UnbondWithOptions {
+            unbond,
+            auto_withdraw,
+        } = pos::UnbondWithOptions::decode(
             &tx.data()
                 .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
         )
@@ -1489,6 +1557,9 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&unbond.amount.to_string_native())
             ),
         ]);
+        if auto_withdraw {
+            tv.output.push("Auto withdraw : Yes".to_string());
+        }
 
         if let Some(source) = unbond.source.as_ref() {
             tv.output_expert.push(format!("Source : {}", source));
@@ -1500,6 +1571,9 @@ pub async fn to_ledger_vector(
                 to_ledger_decimal(&unbond.amount.to_string_native())
             ),
         ]);
+        if auto_withdraw {
+            tv.output_expert.push("Auto withdraw : Yes".to_string());
+        }
     } else if code_sec.tag == Some(TX_WITHDRAW_WASM.to_string()) {
         let withdraw = pos::Withdraw::try_from_slice(
             &tx.data()
@@ -1545,6 +1619,58 @@ pub async fn to_ledger_vector(
         }
         tv.output_expert
             .push(format!("Validator : {}", claim.validator));
+    } else if code_sec.tag == Some(TX_AUTO_COMPOUND_WASM.to_string()) {
+        let auto_compound = pos::AutoCompound::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Auto_Compound_0".to_string();
+
+        tv.output.push("Type : Auto Compound".to_string());
+        if let Some(source) = auto_compound.source.as_ref() {
+            tv.output.push(format!("Source : {}", source));
+        }
+        tv.output
+            .push(format!("Validator : {}", auto_compound.validator));
+        let enabled = if auto_compound.enabled { "Yes" } else { "No" };
+        tv.output.push(format!("Enabled : {}", enabled));
+
+        if let Some(source) = auto_compound.source.as_ref() {
+            tv.output_expert.push(format!("Source : {}", source));
+        }
+        tv.output_expert
+            .push(format!("Validator : {}", auto_compound.validator));
+        tv.output_expert.push(format!("Enabled : {}", enabled));
+    } else if code_sec.tag == Some(TX_SLASHING_INSURANCE_WASM.to_string()) {
+        let insurance = pos::SlashingInsurance::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Slashing_Insurance_0".to_string();
+
+        tv.output.push("Type : Slashing Insurance".to_string());
+        if let Some(source) = insurance.source.as_ref() {
+            tv.output.push(format!("Source : {}", source));
+        }
+        tv.output
+            .push(format!("Validator : {}", insurance.validator));
+        let enabled = if insurance.enabled { "Yes" } else { "No" };
+        tv.output.push(format!("Enabled : {}", enabled));
+
+        if let Some(source) = insurance.source.as_ref() {
+            tv.output_expert.push(format!("Source : {}", source));
+        }
+        tv.output_expert
+            .push(format!("Validator : {}", insurance.validator));
+        tv.output_expert.push(format!("Enabled : {}", enabled));
     } else if code_sec.tag == Some(TX_CHANGE_COMMISSION_WASM.to_string()) {
         let commission_change = pos::CommissionChange::try_from_slice(
             &tx.data()
@@ -1605,6 +1731,23 @@ pub async fn to_ledger_vector(
                     .push(format!("New discord handle : {}", discord_handle));
             }
         }
+        if let Some(moniker) = metadata_change.moniker {
+            if moniker.is_empty() {
+                other_items.push("Moniker removed".to_string());
+            } else {
+                other_items.push(format!("New moniker : {}", moniker));
+            }
+        }
+        if let Some(security_contact) = metadata_change.security_contact {
+            if security_contact.is_empty() {
+                other_items.push("Security contact removed".to_string());
+            } else {
+                other_items.push(format!(
+                    "New security contact : {}",
+                    security_contact
+                ));
+            }
+        }
 
         tv.output.extend(other_items.clone());
         tv.output_expert.extend(other_items);
@@ -1635,6 +1778,53 @@ pub async fn to_ledger_vector(
             ),
             format!("Validator : {}", consensus_key_change.validator),
         ]);
+    } else if code_sec.tag == Some(TX_CHANGE_PROTOCOL_KEY_WASM.to_string()) {
+        let protocol_key_change = pos::ProtocolKeyChange::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Change_Protocol_Key_0".to_string();
+
+        tv.output.extend(vec![
+            format!("Type : Change protocol key"),
+            format!("New protocol key : {}", protocol_key_change.protocol_key),
+            format!("Validator : {}", protocol_key_change.validator),
+        ]);
+
+        tv.output_expert.extend(vec![
+            format!("New protocol key : {}", protocol_key_change.protocol_key),
+            format!("Validator : {}", protocol_key_change.validator),
+        ]);
+    } else if code_sec.tag == Some(TX_CHANGE_ETH_BRIDGE_KEYS_WASM.to_string()) {
+        let eth_keys_change = pos::EthBridgeKeysChange::try_from_slice(
+            &tx.data()
+                .ok_or_else(|| Error::Other("Invalid Data".to_string()))?,
+        )
+        .map_err(|err| {
+            Error::from(EncodingError::Conversion(err.to_string()))
+        })?;
+
+        tv.name = "Change_Eth_Bridge_Keys_0".to_string();
+
+        let mut key_items = vec![];
+        if let Some(eth_hot_key) = &eth_keys_change.eth_hot_key {
+            key_items.push(format!("New Eth hot key : {}", eth_hot_key));
+        }
+        if let Some(eth_cold_key) = &eth_keys_change.eth_cold_key {
+            key_items.push(format!("New Eth cold key : {}", eth_cold_key));
+        }
+        let validator = format!("Validator : {}", eth_keys_change.validator);
+
+        tv.output.push("Type : Change Eth bridge keys".to_string());
+        tv.output.extend(key_items.clone());
+        tv.output.push(validator.clone());
+
+        tv.output_expert.extend(key_items);
+        tv.output_expert.push(validator);
     } else if code_sec.tag == Some(TX_UNJAIL_VALIDATOR_WASM.to_string()) {
         let address = Address::try_from_slice(
             &tx.data()
@@ -1,6 +1,6 @@
 //! SDK functions to construct different types of transactions
 use std::borrow::Cow;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -24,8 +24,9 @@ use namada_core::ibc::core::client::types::Height as IbcHeight;
 use namada_core::ibc::core::host::types::identifiers::{ChannelId, PortId};
 use namada_core::ibc::primitives::{Msg, Timestamp as IbcTimestamp};
 use namada_core::ledger::governance::cli::onchain::{
-    DefaultProposal, OnChainProposal, PgfFundingProposal, PgfStewardProposal,
-    ProposalVote,
+    CodeAllowlistProposal, ConsensusParamsProposal, DefaultProposal,
+    OnChainProposal, ParameterChangeProposal, PgfFundingProposal,
+    PgfStewardProposal, ProposalVote, TextProposal, TreasurySpendProposal,
 };
 use namada_core::ledger::governance::storage::proposal::ProposalType;
 use namada_core::ledger::governance::storage::vote::StorageProposalVote;
@@ -42,13 +43,14 @@ use namada_core::types::time::DateTimeUtc;
 use namada_core::types::token::MaspDenom;
 use namada_core::types::transaction::account::{InitAccount, UpdateAccount};
 use namada_core::types::transaction::governance::{
-    InitProposalData, VoteProposalData,
+    InitProposalData, VoteProposalData, WithdrawProposalData,
 };
 use namada_core::types::transaction::pgf::UpdateStewardCommission;
 use namada_core::types::transaction::pos;
 use namada_core::types::{storage, token};
 use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::types::{CommissionPair, ValidatorState};
+use namada_proof_of_stake::MAX_VALIDATOR_METADATA_LEN;
 
 use crate::args::{self, InputAmount};
 use crate::control_flow::time;
@@ -81,6 +83,8 @@ pub const TX_REACTIVATE_VALIDATOR_WASM: &str = "tx_reactivate_validator.wasm";
 pub const TX_INIT_PROPOSAL: &str = "tx_init_proposal.wasm";
 /// Vote transaction WASM path
 pub const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
+/// Withdraw proposal transaction WASM path
+pub const TX_WITHDRAW_PROPOSAL: &str = "tx_withdraw_proposal.wasm";
 /// Reveal public key transaction WASM path
 pub const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 /// Update validity predicate WASM path
@@ -99,13 +103,24 @@ pub const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 pub const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
 /// Claim-rewards WASM path
 pub const TX_CLAIM_REWARDS_WASM: &str = "tx_claim_rewards.wasm";
+/// Auto-compound WASM path
+pub const TX_AUTO_COMPOUND_WASM: &str = "tx_auto_compound.wasm";
+/// Slashing insurance WASM path
+pub const TX_SLASHING_INSURANCE_WASM: &str = "tx_slashing_insurance.wasm";
 /// Bridge pool WASM path
 pub const TX_BRIDGE_POOL_WASM: &str = "tx_bridge_pool.wasm";
+/// Bridge pool transfer cancellation WASM path
+pub const TX_BRIDGE_POOL_CANCEL_WASM: &str = "tx_bridge_pool_cancel.wasm";
 /// Change commission WASM path
 pub const TX_CHANGE_COMMISSION_WASM: &str =
     "tx_change_validator_commission.wasm";
 /// Change consensus key WASM path
 pub const TX_CHANGE_CONSENSUS_KEY_WASM: &str = "tx_change_consensus_key.wasm";
+/// Change protocol key WASM path
+pub const TX_CHANGE_PROTOCOL_KEY_WASM: &str = "tx_change_protocol_key.wasm";
+/// Change Ethereum bridge keys WASM path
+pub const TX_CHANGE_ETH_BRIDGE_KEYS_WASM: &str =
+    "tx_change_eth_bridge_keys.wasm";
 /// Change validator metadata WASM path
 pub const TX_CHANGE_METADATA_WASM: &str = "tx_change_validator_metadata.wasm";
 /// Resign steward WASM path
@@ -115,6 +130,12 @@ pub const TX_UPDATE_STEWARD_COMMISSION: &str =
     "tx_update_steward_commission.wasm";
 /// Redelegate transaction WASM path
 pub const TX_REDELEGATE_WASM: &str = "tx_redelegate.wasm";
+/// Time-locked transfer transaction WASM path
+pub const TX_HTLC_LOCK_WASM: &str = "tx_htlc_lock.wasm";
+/// Claim time lock transaction WASM path
+pub const TX_HTLC_CLAIM_WASM: &str = "tx_htlc_claim.wasm";
+/// Refund time lock transaction WASM path
+pub const TX_HTLC_REFUND_WASM: &str = "tx_htlc_refund.wasm";
 
 /// Default timeout in seconds for requests to the `/accepted`
 /// and `/applied` ABCI query endpoints.
@@ -624,6 +645,8 @@ pub async fn build_validator_metadata_change(
         description,
         website,
         discord_handle,
+        moniker,
+        security_contact,
         commission_rate,
         tx_code_path,
     }: &args::MetaDataChange,
@@ -659,6 +682,29 @@ pub async fn build_validator_metadata_change(
         }
     }
 
+    // The new metadata must not exceed the maximum length allowed on chain
+    for (field, value) in [
+        ("email", email),
+        ("description", description),
+        ("website", website),
+        ("discord handle", discord_handle),
+        ("moniker", moniker),
+        ("security contact", security_contact),
+    ] {
+        let len = value.as_ref().map(String::len).unwrap_or_default();
+        if len > MAX_VALIDATOR_METADATA_LEN {
+            let err = TxError::MetadataTooLong(
+                field.to_owned(),
+                len,
+                MAX_VALIDATOR_METADATA_LEN,
+            );
+            edisplay_line!(context.io(), "{err}");
+            if !tx_args.force {
+                return Err(Error::from(err));
+            }
+        }
+    }
+
     // If there's a new commission rate, it must be valid
     if let Some(rate) = commission_rate.as_ref() {
         if *rate < Dec::zero() || *rate > Dec::one() {
@@ -727,6 +773,8 @@ pub async fn build_validator_metadata_change(
         website: website.clone(),
         description: description.clone(),
         discord_handle: discord_handle.clone(),
+        moniker: moniker.clone(),
+        security_contact: security_contact.clone(),
         commission_rate: *commission_rate,
     };
 
@@ -1396,6 +1444,114 @@ pub async fn build_claim_rewards(
     .map(|(tx, epoch)| (tx, signing_data, epoch))
 }
 
+/// Submit transaction to enable or disable the automatic compounding of the
+/// rewards of a bond
+pub async fn build_auto_compound(
+    context: &impl Namada,
+    args::AutoCompound {
+        tx: tx_args,
+        validator,
+        source,
+        enabled,
+        tx_code_path,
+    }: &args::AutoCompound,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_address = source.clone().unwrap_or(validator.clone());
+    let default_signer = Some(default_address.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(default_address),
+        default_signer,
+    )
+    .await?;
+
+    // Check that the validator address is actually a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+
+    // Check that the source address exists on chain
+    let source = match source.clone() {
+        Some(source) => source_exists_or_err(source, tx_args.force, context)
+            .await
+            .map(Some),
+        None => Ok(source.clone()),
+    }?;
+
+    let data = pos::AutoCompound {
+        validator,
+        source,
+        enabled: *enabled,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Submit transaction to opt a bond into or out of the slashing insurance
+/// pool
+pub async fn build_slashing_insurance(
+    context: &impl Namada,
+    args::SlashingInsurance {
+        tx: tx_args,
+        validator,
+        source,
+        enabled,
+        tx_code_path,
+    }: &args::SlashingInsurance,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_address = source.clone().unwrap_or(validator.clone());
+    let default_signer = Some(default_address.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx_args,
+        Some(default_address),
+        default_signer,
+    )
+    .await?;
+
+    // Check that the validator address is actually a validator
+    let validator =
+        known_validator_or_err(validator.clone(), tx_args.force, context)
+            .await?;
+
+    // Check that the source address exists on chain
+    let source = match source.clone() {
+        Some(source) => source_exists_or_err(source, tx_args.force, context)
+            .await
+            .map(Some),
+        None => Ok(source.clone()),
+    }?;
+
+    let data = pos::SlashingInsurance {
+        validator,
+        source,
+        enabled: *enabled,
+    };
+
+    build(
+        context,
+        tx_args,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
 /// Submit a transaction to unbond
 pub async fn build_unbond(
     context: &impl Namada,
@@ -1404,6 +1560,7 @@ pub async fn build_unbond(
         validator,
         amount,
         source,
+        auto_withdraw,
         tx_code_path,
     }: &args::Unbond,
 ) -> Result<(
@@ -1484,22 +1641,41 @@ pub async fn build_unbond(
     }
     let latest_withdrawal_pre = withdrawable.into_iter().last();
 
-    let data = pos::Unbond {
+    let unbond = pos::Unbond {
         validator: validator.clone(),
         amount: *amount,
         source: source.clone(),
     };
 
-    let (tx, epoch) = build(
-        context,
-        tx_args,
-        tx_code_path.clone(),
-        data,
-        do_nothing,
-        &signing_data.fee_payer,
-        None,
-    )
-    .await?;
+    // The options are only encoded when set, such that the data of a plain
+    // unbond remains unchanged
+    let (tx, epoch) = if *auto_withdraw {
+        let data = pos::UnbondWithOptions {
+            unbond,
+            auto_withdraw: true,
+        };
+        build(
+            context,
+            tx_args,
+            tx_code_path.clone(),
+            data,
+            do_nothing,
+            &signing_data.fee_payer,
+            None,
+        )
+        .await?
+    } else {
+        build(
+            context,
+            tx_args,
+            tx_code_path.clone(),
+            unbond,
+            do_nothing,
+            &signing_data.fee_payer,
+            None,
+        )
+        .await?
+    };
     Ok((tx, signing_data, epoch, latest_withdrawal_pre))
 }
 
@@ -1698,6 +1874,11 @@ pub async fn build_default_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: DefaultProposal,
@@ -1828,6 +2009,65 @@ pub async fn build_vote_proposal(
     .map(|(tx, epoch)| (tx, signing_data, epoch))
 }
 
+/// Build a proposal withdrawal
+pub async fn build_withdraw_proposal(
+    context: &impl Namada,
+    args::WithdrawProposal {
+        tx,
+        proposal_id,
+        tx_code_path,
+    }: &args::WithdrawProposal,
+    epoch: Epoch,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let proposal = if let Some(proposal) =
+        rpc::query_proposal_by_id(context.client(), *proposal_id).await?
+    {
+        proposal
+    } else {
+        return Err(Error::from(TxError::ProposalDoesNotExist(*proposal_id)));
+    };
+
+    let default_signer = Some(proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    if epoch >= proposal.voting_start_epoch {
+        if tx.force {
+            edisplay_line!(
+                context.io(),
+                "Invalid proposal {} withdrawal period.",
+                proposal_id
+            );
+        } else {
+            return Err(Error::from(TxError::InvalidProposalWithdrawalPeriod(
+                *proposal_id,
+            )));
+        }
+    }
+
+    let data = WithdrawProposalData {
+        id: *proposal_id,
+        author: proposal.author,
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        data,
+        do_nothing,
+        &signing_data.fee_payer,
+        None,
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
 /// Build a pgf funding proposal governance
 pub async fn build_pgf_funding_proposal(
     context: &impl Namada,
@@ -1838,6 +2078,11 @@ pub async fn build_pgf_funding_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfFundingProposal,
@@ -1883,6 +2128,11 @@ pub async fn build_pgf_stewards_proposal(
         is_offline: _,
         is_pgf_stewards: _,
         is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
         tx_code_path,
     }: &args::InitProposal,
     proposal: PgfStewardProposal,
@@ -1919,6 +2169,261 @@ pub async fn build_pgf_stewards_proposal(
     .map(|(tx, epoch)| (tx, signing_data, epoch))
 }
 
+/// Build a parameter change proposal governance
+pub async fn build_parameter_change_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        native_token: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: ParameterChangeProposal,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Build a code allowlist proposal governance
+pub async fn build_code_allowlist_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        native_token: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: CodeAllowlistProposal,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Build a consensus params proposal governance
+pub async fn build_consensus_params_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        native_token: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: ConsensusParamsProposal,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Build a treasury spend proposal governance
+pub async fn build_treasury_spend_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        native_token: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: TreasurySpendProposal,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
+/// Build a text proposal governance
+pub async fn build_text_proposal(
+    context: &impl Namada,
+    args::InitProposal {
+        tx,
+        proposal_data: _,
+        native_token: _,
+        is_offline: _,
+        is_pgf_stewards: _,
+        is_pgf_funding: _,
+        is_parameter_change: _,
+        is_code_allowlist: _,
+        is_consensus_params: _,
+        is_treasury_spend: _,
+        is_text: _,
+        tx_code_path,
+    }: &args::InitProposal,
+    proposal: TextProposal,
+) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
+    let default_signer = Some(proposal.proposal.author.clone());
+    let signing_data = signing::aux_signing_data(
+        context,
+        tx,
+        Some(proposal.proposal.author.clone()),
+        default_signer,
+    )
+    .await?;
+
+    let init_proposal_data = InitProposalData::try_from(proposal.clone())
+        .map_err(|e| TxError::InvalidProposal(e.to_string()))?;
+
+    let add_section = |tx: &mut Tx, data: &mut InitProposalData| {
+        let (_, extra_section_hash) =
+            tx.add_extra_section(proposal_to_vec(proposal.proposal)?, None);
+        data.content = extra_section_hash;
+        Ok(())
+    };
+
+    build(
+        context,
+        tx,
+        tx_code_path.clone(),
+        init_proposal_data,
+        add_section,
+        &signing_data.fee_payer,
+        None, // TODO: need to pay the fee to submit a proposal
+    )
+    .await
+    .map(|(tx, epoch)| (tx, signing_data, epoch))
+}
+
 /// Submit an IBC transfer
 pub async fn build_ibc_transfer(
     context: &impl Namada,
@@ -2407,6 +2912,7 @@ pub async fn build_update_account(
         addr,
         public_keys,
         threshold,
+        allowed_tx_code_paths,
     }: &args::TxUpdateAccount,
 ) -> Result<(Tx, SigningTxData, Option<Epoch>)> {
     let default_signer = Some(addr.clone());
@@ -2436,6 +2942,19 @@ pub async fn build_update_account(
         None => None,
     };
 
+    let allowed_tx_codes = match allowed_tx_code_paths {
+        Some(code_paths) => {
+            let mut code_hashes = BTreeSet::new();
+            for code_path in code_paths {
+                code_hashes.insert(
+                    query_wasm_code_hash_buf(context, code_path).await?,
+                );
+            }
+            Some(code_hashes)
+        }
+        None => None,
+    };
+
     let chain_id = tx_args.chain_id.clone().unwrap();
     let mut tx = Tx::new(chain_id, tx_args.expiration);
     let extra_section_hash = vp_code_path.as_ref().zip(vp_code_hash).map(
@@ -2452,6 +2971,7 @@ pub async fn build_update_account(
         vp_code_hash: extra_section_hash,
         public_keys: public_keys.clone(),
         threshold: *threshold,
+        allowed_tx_codes,
     };
 
     let add_code_hash = |tx: &mut Tx, data: &mut UpdateAccount| {
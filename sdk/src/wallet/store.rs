@@ -38,6 +38,14 @@ pub struct ValidatorKeys {
     pub protocol_keypair: common::SecretKey,
     /// Special hot keypair for signing Ethereum bridge txs
     pub eth_bridge_keypair: common::SecretKey,
+    /// The protocol keypair the validator is rotating to, which replaces
+    /// the current one once it becomes active on chain
+    #[serde(default)]
+    pub next_protocol_keypair: Option<common::SecretKey>,
+    /// The Ethereum bridge hot keypair the validator is rotating to, which
+    /// replaces the current one once it becomes active on chain
+    #[serde(default)]
+    pub next_eth_bridge_keypair: Option<common::SecretKey>,
 }
 
 impl ValidatorKeys {
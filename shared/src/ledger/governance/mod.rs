@@ -2,29 +2,49 @@
 
 pub mod utils;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
 
 use borsh::BorshDeserialize;
+use namada_core::ledger::eth_bridge::storage as bridge_storage;
+use namada_core::ledger::governance::cli::is_valid_proposal_epochs;
 use namada_core::ledger::governance::storage::keys as gov_storage;
 use namada_core::ledger::governance::storage::proposal::{
-    AddRemove, ProposalType,
+    AddRemove, ConsensusParams, ParameterChange, ProposalType,
 };
 use namada_core::ledger::governance::storage::vote::StorageProposalVote;
 use namada_core::ledger::governance::utils::is_valid_validator_voting_period;
+use namada_core::ledger::parameters::storage::{
+    self as params_storage, get_max_proposal_bytes_key,
+    is_protocol_parameter_key,
+};
+use namada_core::ledger::parameters::EpochDuration;
 use namada_core::ledger::storage;
 use namada_core::ledger::storage_api::account;
-use namada_core::ledger::storage_api::governance::is_proposal_accepted;
+use namada_core::ledger::storage_api::governance::{
+    get_parameters, is_proposal_accepted,
+};
 use namada_core::ledger::vp_env::VpEnv;
 use namada_core::proto::Tx;
+use namada_core::types::ethereum_structs;
+use namada_ethereum_bridge::parameters::{
+    ContractRotation, MinimumConfirmations, UpgradeableContract,
+};
+use namada_ethereum_bridge::storage::eth_bridge_queries::EthBridgeStatus;
 use namada_proof_of_stake::is_validator;
 use thiserror::Error;
 
 use self::utils::ReadType;
 use crate::ledger::native_vp::{Ctx, NativeVp};
 use crate::ledger::storage_api::StorageRead;
-use crate::ledger::{native_vp, pos};
+use crate::ledger::{native_vp, pgf, pos};
 use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::{Epoch, Key};
+use crate::types::chain::ProposalBytes;
+use crate::types::dec::Dec;
+use crate::types::ethereum_events::EthAddress;
+use crate::types::hash::Hash;
+use crate::types::storage::{BlockHeight, Epoch, Key};
+use crate::types::time::DurationSecs;
 use crate::types::token;
 use crate::vm::WasmCacheAccess;
 
@@ -37,6 +57,13 @@ pub const ADDRESS: Address = Address::Internal(InternalAddress::Governance);
 /// The maximum number of item in a pgf proposal
 pub const MAX_PGF_ACTIONS: usize = 20;
 
+/// The maximum number of parameters changed by a single proposal
+pub const MAX_PARAMETER_CHANGES: usize = 20;
+
+/// The maximum number of code hashes added to or removed from the allowlists
+/// by a single proposal
+pub const MAX_CODE_ALLOWLIST_CHANGES: usize = 20;
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum Error {
@@ -82,12 +109,24 @@ where
             return Ok(false);
         };
         let native_token = self.ctx.pre().get_native_token()?;
+        let withdrawn_ids = self.get_withdrawn_proposal_ids(keys_changed)?;
 
         Ok(keys_changed.iter().all(|key| {
             let proposal_id = gov_storage::get_proposal_id(key);
-            let key_type = KeyType::from_key(key, &native_token);
+            let withdrawn_id = proposal_id
+                .or_else(|| gov_storage::get_commit_proposal_id(key))
+                .filter(|id| withdrawn_ids.contains(id));
+            let (key_type, proposal_id) = match withdrawn_id {
+                Some(id) => (KeyType::WITHDRAWAL, Some(id)),
+                None => (KeyType::from_key(key, &native_token), proposal_id),
+            };
 
             let result = match (key_type, proposal_id) {
+                (KeyType::WITHDRAWAL, Some(proposal_id)) => {
+                    self.is_valid_withdrawal(proposal_id, key, verifiers)
+                }
+                (KeyType::BALANCE, _) if !withdrawn_ids.is_empty() => self
+                    .is_valid_withdrawal_balance(&withdrawn_ids, &native_token),
                 (KeyType::VOTE, Some(proposal_id)) => {
                     self.is_valid_vote_key(proposal_id, key, verifiers)
                 }
@@ -109,6 +148,9 @@ where
                 (KeyType::END_EPOCH, Some(proposal_id)) => {
                     self.is_valid_end_epoch(proposal_id)
                 }
+                (KeyType::VOTING_END_HEIGHT, Some(proposal_id)) => {
+                    self.is_valid_voting_end_height(proposal_id)
+                }
                 (KeyType::FUNDS, Some(proposal_id)) => {
                     self.is_valid_funds(proposal_id, &native_token)
                 }
@@ -189,6 +231,8 @@ where
         let voting_end_epoch_key =
             gov_storage::get_voting_end_epoch_key(proposal_id);
         let proposal_type_key = gov_storage::get_proposal_type_key(proposal_id);
+        let voting_end_height_key =
+            gov_storage::get_voting_end_height_key(proposal_id);
 
         let current_epoch = self.ctx.get_block_epoch()?;
 
@@ -199,6 +243,8 @@ where
             self.force_read(&voting_end_epoch_key, ReadType::Pre)?;
         let proposal_type: ProposalType =
             self.force_read(&proposal_type_key, ReadType::Pre)?;
+        let pre_voting_end_height: Option<BlockHeight> =
+            self.ctx.pre().read(&voting_end_height_key)?;
 
         let voter = gov_storage::get_voter_address(key);
         let delegation_address = gov_storage::get_vote_delegation_address(key);
@@ -222,6 +268,42 @@ where
             return Ok(false);
         }
 
+        // Expedited proposals are voted on by both validators and delegators
+        // until their voting window closes
+        if let Some(voting_end_height) = pre_voting_end_height {
+            let current_height = self.ctx.get_block_height()?;
+            if current_height >= voting_end_height {
+                tracing::info!(
+                    "Voted outside the voting window of expedited proposal. \
+                     Current height: {current_height}, end: \
+                     {voting_end_height}."
+                );
+                return Ok(false);
+            }
+            if !vote.is_compatible(&proposal_type) {
+                return Err(Error::InvalidVoteType);
+            }
+            let is_validator = self
+                .is_validator(
+                    pre_voting_start_epoch,
+                    verifiers,
+                    voter_address,
+                    delegation_address,
+                )
+                .unwrap_or(false);
+            if is_validator {
+                return Ok(true);
+            }
+            return Ok(self
+                .is_delegator(
+                    pre_voting_start_epoch,
+                    verifiers,
+                    voter_address,
+                    delegation_address,
+                )
+                .unwrap_or(false));
+        }
+
         // Voted outside of voting window. We dont check for validator because
         // if the proposal type is validator, we need to let
         // them vote for the entire voting window.
@@ -340,10 +422,120 @@ where
                     Ok(true)
                 }
             }
+            ProposalType::ParameterChange(changes) => {
+                if changes.is_empty() || changes.len() > MAX_PARAMETER_CHANGES {
+                    return Ok(false);
+                }
+                let mut keys = BTreeSet::new();
+                let is_valid = changes.iter().all(|change| {
+                    is_protocol_parameter_key(&change.key)
+                        && keys.insert(&change.key)
+                });
+                if !is_valid {
+                    tracing::info!(
+                        "Invalid parameter change proposal {}: it may only \
+                         change each protocol parameter once.",
+                        proposal_id
+                    );
+                    return Ok(false);
+                }
+                for change in &changes {
+                    if !self.is_valid_parameter_change(change)? {
+                        tracing::info!(
+                            "Invalid parameter change proposal {}: the new \
+                             value of {} is not valid.",
+                            proposal_id,
+                            change.key
+                        );
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ProposalType::CodeAllowlist(update) => {
+                let is_valid = update.is_valid()
+                    && update.len() <= MAX_CODE_ALLOWLIST_CHANGES;
+                if !is_valid {
+                    tracing::info!(
+                        "Invalid code allowlist proposal {}: it must change \
+                         between 1 and {} code hashes, each at most once per \
+                         allowlist.",
+                        proposal_id,
+                        MAX_CODE_ALLOWLIST_CHANGES
+                    );
+                }
+                Ok(is_valid)
+            }
+            ProposalType::ConsensusParams(update) => {
+                let current_params: ConsensusParams = self.force_read(
+                    &gov_storage::get_consensus_params_key(),
+                    ReadType::Pre,
+                )?;
+                if !update.is_valid(&current_params) {
+                    tracing::info!(
+                        "Invalid consensus params proposal {}: it must change \
+                         some parameters to values accepted by CometBFT.",
+                        proposal_id
+                    );
+                    return Ok(false);
+                }
+                // blocks must still fit the largest proposals allowed by the
+                // protocol
+                let max_proposal_bytes: ProposalBytes = self
+                    .force_read(&get_max_proposal_bytes_key(), ReadType::Pre)?;
+                let is_valid = update.block.as_ref().map_or(true, |block| {
+                    block.max_bytes >= max_proposal_bytes.get()
+                });
+                if !is_valid {
+                    tracing::info!(
+                        "Invalid consensus params proposal {}: the max block \
+                         bytes must be at least the max proposal bytes {}.",
+                        proposal_id,
+                        max_proposal_bytes.get()
+                    );
+                }
+                Ok(is_valid)
+            }
+            ProposalType::TreasurySpend(spend) => {
+                let is_valid = spend.is_valid();
+                if !is_valid {
+                    tracing::info!(
+                        "Invalid treasury spend proposal {}: it must transfer \
+                         a non-zero amount to an address other than the \
+                         treasury.",
+                        proposal_id
+                    );
+                }
+                Ok(is_valid)
+            }
             _ => Ok(true), // default proposal
         }
     }
 
+    /// Validate the new value of a protocol parameter changed by a
+    /// proposal
+    fn is_valid_parameter_change(
+        &self,
+        ParameterChange { key, value }: &ParameterChange,
+    ) -> Result<bool> {
+        if !is_valid_parameter_value(key, value) {
+            return Ok(false);
+        }
+        if *key == get_max_proposal_bytes_key() {
+            // proposals must still fit in blocks
+            let max_proposal_bytes = ProposalBytes::try_from_slice(value)
+                .expect("The value should have been validated");
+            let consensus_params: ConsensusParams = self.force_read(
+                &gov_storage::get_consensus_params_key(),
+                ReadType::Pre,
+            )?;
+            return Ok(
+                max_proposal_bytes.get() <= consensus_params.block.max_bytes
+            );
+        }
+        Ok(true)
+    }
+
     /// Validate a proposal code
     pub fn is_valid_proposal_code(&self, proposal_id: u64) -> Result<bool> {
         let proposal_type_key = gov_storage::get_proposal_type_key(proposal_id);
@@ -373,30 +565,19 @@ where
 
     /// Validate a grace_epoch key
     pub fn is_valid_grace_epoch(&self, proposal_id: u64) -> Result<bool> {
-        let start_epoch_key =
-            gov_storage::get_voting_start_epoch_key(proposal_id);
-        let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
         let grace_epoch_key = gov_storage::get_grace_epoch_key(proposal_id);
-        let max_proposal_period = gov_storage::get_max_proposal_period_key();
-        let min_grace_epoch_key =
-            gov_storage::get_min_proposal_grace_epoch_key();
 
         let has_pre_grace_epoch = self.ctx.has_key_pre(&grace_epoch_key)?;
         if has_pre_grace_epoch {
             return Ok(false);
         }
 
-        let start_epoch: Epoch =
-            self.force_read(&start_epoch_key, ReadType::Post)?;
-        let end_epoch: Epoch =
-            self.force_read(&end_epoch_key, ReadType::Post)?;
+        if self.is_expedited(proposal_id)? {
+            return self.is_valid_proposal_epochs(proposal_id);
+        }
+
         let grace_epoch: Epoch =
             self.force_read(&grace_epoch_key, ReadType::Post)?;
-        let min_grace_epoch: u64 =
-            self.force_read(&min_grace_epoch_key, ReadType::Pre)?;
-        let max_proposal_period: u64 =
-            self.force_read(&max_proposal_period, ReadType::Pre)?;
-
         let committing_epoch_key = gov_storage::get_committing_proposals_key(
             proposal_id,
             grace_epoch.into(),
@@ -407,28 +588,8 @@ where
             tracing::info!("Committing proposal key is missing present");
         }
 
-        let is_valid_grace_epoch = end_epoch < grace_epoch
-            && (grace_epoch - end_epoch).0 >= min_grace_epoch;
-        if !is_valid_grace_epoch {
-            tracing::info!(
-                "Expected min duration between the end and grace epoch \
-                 {min_grace_epoch}, but got {}",
-                grace_epoch - end_epoch
-            );
-        }
-        let is_valid_max_proposal_period = start_epoch < grace_epoch
-            && grace_epoch.0 - start_epoch.0 <= max_proposal_period;
-        if !is_valid_max_proposal_period {
-            tracing::info!(
-                "Expected max duration between the start and grace epoch \
-                 {max_proposal_period}, but got {}",
-                grace_epoch - start_epoch
-            );
-        }
-
         Ok(has_post_committing_epoch
-            && is_valid_grace_epoch
-            && is_valid_max_proposal_period)
+            && self.is_valid_proposal_epochs(proposal_id)?)
     }
 
     /// Validate a start_epoch key
@@ -436,10 +597,6 @@ where
         let start_epoch_key =
             gov_storage::get_voting_start_epoch_key(proposal_id);
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
-        let min_period_parameter_key =
-            gov_storage::get_min_proposal_voting_period_key();
-
-        let current_epoch = self.ctx.get_block_epoch()?;
 
         let has_pre_start_epoch = self.ctx.has_key_pre(&start_epoch_key)?;
         let has_pre_end_epoch = self.ctx.has_key_pre(&end_epoch_key)?;
@@ -448,19 +605,7 @@ where
             return Ok(false);
         }
 
-        let start_epoch: Epoch =
-            self.force_read(&start_epoch_key, ReadType::Post)?;
-        let end_epoch: Epoch =
-            self.force_read(&end_epoch_key, ReadType::Post)?;
-        let min_period: u64 =
-            self.force_read(&min_period_parameter_key, ReadType::Pre)?;
-
-        if end_epoch <= start_epoch || start_epoch <= current_epoch {
-            return Ok(false);
-        }
-
-        Ok((end_epoch - start_epoch) % min_period == 0
-            && (end_epoch - start_epoch).0 >= min_period)
+        self.is_valid_proposal_epochs(proposal_id)
     }
 
     /// Validate a end_epoch key
@@ -468,12 +613,6 @@ where
         let start_epoch_key =
             gov_storage::get_voting_start_epoch_key(proposal_id);
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
-        let min_period_parameter_key =
-            gov_storage::get_min_proposal_voting_period_key();
-        let max_period_parameter_key =
-            gov_storage::get_max_proposal_period_key();
-
-        let current_epoch = self.ctx.get_block_epoch()?;
 
         let has_pre_start_epoch = self.ctx.has_key_pre(&start_epoch_key)?;
         let has_pre_end_epoch = self.ctx.has_key_pre(&end_epoch_key)?;
@@ -482,26 +621,92 @@ where
             return Ok(false);
         }
 
+        self.is_valid_proposal_epochs(proposal_id)
+    }
+
+    /// Validate the voting start, end and grace epochs of a proposal against
+    /// the governance parameters, with the rules that are also checked when
+    /// the proposal tx is added to the mempool or to a block
+    fn is_valid_proposal_epochs(&self, proposal_id: u64) -> Result<bool> {
+        let start_epoch_key =
+            gov_storage::get_voting_start_epoch_key(proposal_id);
+        let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
+        let grace_epoch_key = gov_storage::get_grace_epoch_key(proposal_id);
+
         let start_epoch: Epoch =
             self.force_read(&start_epoch_key, ReadType::Post)?;
         let end_epoch: Epoch =
             self.force_read(&end_epoch_key, ReadType::Post)?;
-        let min_period: u64 =
-            self.force_read(&min_period_parameter_key, ReadType::Pre)?;
-        let max_period: u64 =
-            self.force_read(&max_period_parameter_key, ReadType::Pre)?;
+        let grace_epoch: Epoch =
+            self.force_read(&grace_epoch_key, ReadType::Post)?;
+        let current_epoch = self.ctx.get_block_epoch()?;
+        let governance_parameters = get_parameters(&self.ctx.pre())?;
+
+        match is_valid_proposal_epochs(
+            start_epoch,
+            end_epoch,
+            grace_epoch,
+            self.is_expedited(proposal_id)?,
+            current_epoch,
+            &governance_parameters,
+        ) {
+            Ok(()) => Ok(true),
+            Err(err) => {
+                tracing::info!("Invalid proposal {}: {}", proposal_id, err);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Validate the voting end height key of an expedited proposal
+    fn is_valid_voting_end_height(&self, proposal_id: u64) -> Result<bool> {
+        let voting_end_height_key =
+            gov_storage::get_voting_end_height_key(proposal_id);
+        let voting_period_parameter_key =
+            gov_storage::get_expedited_proposal_voting_period_key();
+
+        let has_pre_voting_end_height =
+            self.ctx.has_key_pre(&voting_end_height_key)?;
+        if has_pre_voting_end_height {
+            return Ok(false);
+        }
 
-        if end_epoch <= start_epoch || start_epoch <= current_epoch {
+        let voting_end_height: BlockHeight =
+            self.force_read(&voting_end_height_key, ReadType::Post)?;
+        let voting_period: u64 =
+            self.force_read(&voting_period_parameter_key, ReadType::Pre)?;
+        let current_height = self.ctx.get_block_height()?;
+
+        let expedited_proposals_key = gov_storage::get_expedited_proposals_key(
+            proposal_id,
+            voting_end_height.0,
+        );
+        let has_post_expedited_proposal =
+            self.ctx.has_key_post(&expedited_proposals_key)?;
+        if !has_post_expedited_proposal {
+            tracing::info!("Expedited proposal key is missing");
+        }
+
+        let is_valid_voting_end_height =
+            voting_end_height == current_height + voting_period;
+        if !is_valid_voting_end_height {
             tracing::info!(
-                "Proposal end ({end_epoch}) must be after start \
-                 ({start_epoch}) and start before current epoch \
-                 ({current_epoch})."
+                "Expected the voting window of the expedited proposal to \
+                 close at height {}, but got {voting_end_height}",
+                current_height + voting_period
             );
-            return Ok(false);
         }
-        Ok((end_epoch - start_epoch) % min_period == 0
-            && (end_epoch - start_epoch).0 >= min_period
-            && (end_epoch - start_epoch).0 <= max_period)
+
+        Ok(has_post_expedited_proposal && is_valid_voting_end_height)
+    }
+
+    /// Check if a proposal submitted in the current tx is expedited
+    fn is_expedited(&self, proposal_id: u64) -> Result<bool> {
+        let voting_end_height_key =
+            gov_storage::get_voting_end_height_key(proposal_id);
+        self.ctx
+            .has_key_post(&voting_end_height_key)
+            .map_err(Error::NativeVpError)
     }
 
     /// Validate a funds key
@@ -513,7 +718,14 @@ where
         let funds_key = gov_storage::get_funds_key(proposal_id);
         let balance_key =
             token::balance_key(native_token_address, self.ctx.address);
-        let min_funds_parameter_key = gov_storage::get_min_proposal_fund_key();
+        let proposal_type_key = gov_storage::get_proposal_type_key(proposal_id);
+        let proposal_type: ProposalType =
+            self.force_read(&proposal_type_key, ReadType::Post)?;
+        let min_funds_parameter_key =
+            gov_storage::get_min_proposal_fund_key_for(
+                &proposal_type,
+                self.is_expedited(proposal_id)?,
+            );
 
         let min_funds_parameter: token::Amount =
             self.force_read(&min_funds_parameter_key, ReadType::Pre)?;
@@ -537,12 +749,18 @@ where
         let balance_key =
             token::balance_key(native_token_address, self.ctx.address);
         let min_funds_parameter_key = gov_storage::get_min_proposal_fund_key();
+        let min_text_funds_parameter_key =
+            gov_storage::get_min_text_proposal_fund_key();
 
         let pre_balance: Option<token::Amount> =
             self.ctx.pre().read(&balance_key)?;
 
-        let min_funds_parameter: token::Amount =
-            self.force_read(&min_funds_parameter_key, ReadType::Pre)?;
+        // the funds of each proposal are checked against its type, so the
+        // balance must only increase by the lowest minimum funds
+        let min_funds_parameter: token::Amount = std::cmp::min(
+            self.force_read(&min_funds_parameter_key, ReadType::Pre)?,
+            self.force_read(&min_text_funds_parameter_key, ReadType::Pre)?,
+        );
         let post_balance: token::Amount =
             self.force_read(&balance_key, ReadType::Post)?;
 
@@ -574,6 +792,115 @@ where
         Ok(author_exists && verifiers.contains(&author))
     }
 
+    /// Get the ids of the proposals withdrawn in the current tx, whose author
+    /// key is deleted
+    fn get_withdrawn_proposal_ids(
+        &self,
+        keys: &BTreeSet<Key>,
+    ) -> Result<BTreeSet<u64>> {
+        let mut ids = BTreeSet::new();
+        for key in keys.iter().filter(|key| gov_storage::is_author_key(key)) {
+            if let Some(id) = gov_storage::get_proposal_id(key) {
+                if self.ctx.has_key_pre(key)? && !self.ctx.has_key_post(key)? {
+                    ids.insert(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Validate the deletion of a key of a withdrawn proposal. A proposal can
+    /// only be withdrawn by its author before its voting period starts, and
+    /// all of its keys must be deleted.
+    fn is_valid_withdrawal(
+        &self,
+        proposal_id: u64,
+        key: &Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        if self.ctx.has_key_post(key)? {
+            return Ok(false);
+        }
+
+        let author_key = gov_storage::get_author_key(proposal_id);
+        let start_epoch_key =
+            gov_storage::get_voting_start_epoch_key(proposal_id);
+        let grace_epoch_key = gov_storage::get_grace_epoch_key(proposal_id);
+
+        let author: Address = self.force_read(&author_key, ReadType::Pre)?;
+        let start_epoch: Epoch =
+            self.force_read(&start_epoch_key, ReadType::Pre)?;
+        let grace_epoch: Epoch =
+            self.force_read(&grace_epoch_key, ReadType::Pre)?;
+        let current_epoch = self.ctx.get_block_epoch()?;
+
+        if !verifiers.contains(&author) || start_epoch <= current_epoch {
+            return Ok(false);
+        }
+
+        for key in [
+            gov_storage::get_content_key(proposal_id),
+            author_key,
+            gov_storage::get_proposal_type_key(proposal_id),
+            gov_storage::get_proposal_code_key(proposal_id),
+            start_epoch_key,
+            gov_storage::get_voting_end_epoch_key(proposal_id),
+            grace_epoch_key,
+            gov_storage::get_committing_proposals_key(
+                proposal_id,
+                grace_epoch.0,
+            ),
+            gov_storage::get_funds_key(proposal_id),
+        ] {
+            if self.ctx.has_key_post(&key)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Validate the balance key on the withdrawal of proposals. The locked
+    /// funds of the withdrawn proposals must be released, and the share of
+    /// them that is not refunded to their authors must be transferred to the
+    /// PGF treasury.
+    fn is_valid_withdrawal_balance(
+        &self,
+        withdrawn_ids: &BTreeSet<u64>,
+        native_token_address: &Address,
+    ) -> Result<bool> {
+        let balance_key =
+            token::balance_key(native_token_address, self.ctx.address);
+        let pgf_balance_key =
+            token::balance_key(native_token_address, &pgf::ADDRESS);
+        let refund_key = gov_storage::get_proposal_withdrawal_refund_key();
+
+        let refund: Dec = self.force_read(&refund_key, ReadType::Pre)?;
+        let mut funds = token::Amount::zero();
+        let mut withheld_funds = token::Amount::zero();
+        for id in withdrawn_ids {
+            let funds_key = gov_storage::get_funds_key(*id);
+            let proposal_funds: token::Amount =
+                self.force_read(&funds_key, ReadType::Pre)?;
+            let refunded_funds =
+                std::cmp::min(refund * proposal_funds, proposal_funds);
+            funds += proposal_funds;
+            withheld_funds += proposal_funds - refunded_funds;
+        }
+
+        let pre_balance: token::Amount =
+            self.ctx.pre().read(&balance_key)?.unwrap_or_default();
+        let post_balance: token::Amount =
+            self.ctx.post().read(&balance_key)?.unwrap_or_default();
+        let pre_pgf_balance: token::Amount =
+            self.ctx.pre().read(&pgf_balance_key)?.unwrap_or_default();
+        let post_pgf_balance: token::Amount =
+            self.ctx.post().read(&pgf_balance_key)?.unwrap_or_default();
+
+        Ok(pre_balance.checked_sub(post_balance) == Some(funds)
+            && post_pgf_balance.checked_sub(pre_pgf_balance)
+                >= Some(withheld_funds))
+    }
+
     /// Validate a counter key
     pub fn is_valid_counter(&self, set_count: u64) -> Result<bool> {
         let counter_key = gov_storage::get_counter_key();
@@ -599,11 +926,25 @@ where
 
     /// Validate a governance parameter
     pub fn is_valid_parameter(&self, tx: &Tx) -> Result<bool> {
-        match tx.data() {
+        let is_accepted = match tx.data() {
             Some(data) => is_proposal_accepted(&self.ctx.pre(), data.as_ref())
-                .map_err(Error::NativeVpError),
-            None => Ok(true),
+                .map_err(Error::NativeVpError)?,
+            None => true,
+        };
+        if !is_accepted {
+            return Ok(false);
         }
+        // the changed parameters must still be valid together with the
+        // unchanged ones
+        let errors = get_parameters(&self.ctx.post())?.validate();
+        if !errors.is_empty() {
+            tracing::info!(
+                "Invalid governance parameters change: {:?}",
+                errors
+            );
+            return Ok(false);
+        }
+        Ok(true)
     }
 
     /// Check if a vote is from a validator
@@ -703,6 +1044,8 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     END_EPOCH,
     #[allow(non_camel_case_types)]
+    VOTING_END_HEIGHT,
+    #[allow(non_camel_case_types)]
     FUNDS,
     #[allow(non_camel_case_types)]
     BALANCE,
@@ -711,11 +1054,102 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     PARAMETER,
     #[allow(non_camel_case_types)]
+    WITHDRAWAL,
+    #[allow(non_camel_case_types)]
     UNKNOWN_GOVERNANCE,
     #[allow(non_camel_case_types)]
     UNKNOWN,
 }
 
+/// Check if the given value decodes to the type of the protocol parameter
+/// stored at the given key, and is accepted by the protocol.
+fn is_valid_parameter_value(key: &Key, value: &[u8]) -> bool {
+    fn is_valid<T: BorshDeserialize>(
+        value: &[u8],
+        check: impl FnOnce(T) -> bool,
+    ) -> bool {
+        T::try_from_slice(value).map_or(false, check)
+    }
+    fn decodes<T: BorshDeserialize>(value: &[u8]) -> bool {
+        is_valid(value, |_: T| true)
+    }
+
+    if params_storage::is_attestation_lane_key(key).is_some()
+        || *key == bridge_storage::oracle_paused_key()
+    {
+        decodes::<bool>(value)
+    } else if *key == get_max_proposal_bytes_key() {
+        is_valid(value, |bytes: ProposalBytes| {
+            ProposalBytes::new(bytes.get()).is_some()
+        })
+    } else if *key == params_storage::get_max_tx_bytes_key() {
+        is_valid(value, |bytes: u32| bytes > 0)
+    } else if *key == params_storage::get_epoch_duration_storage_key() {
+        is_valid(value, |duration: EpochDuration| {
+            duration.min_num_of_blocks > 0
+        })
+    } else if *key == params_storage::get_max_expected_time_per_block_key() {
+        is_valid(value, |duration: DurationSecs| duration.0 > 0)
+    } else if *key == params_storage::get_epochs_per_year_key()
+        || *key == params_storage::get_max_block_gas_key()
+        || *key == params_storage::get_invariant_checks_interval_key()
+        || *key == bridge_storage::max_vext_eth_events_key()
+    {
+        is_valid(value, |num: u64| num > 0)
+    } else if *key == params_storage::get_max_signatures_per_transaction_key() {
+        is_valid(value, |num: u8| num > 0)
+    } else if *key == params_storage::get_pos_gain_p_key()
+        || *key == params_storage::get_pos_gain_d_key()
+    {
+        is_valid(value, |gain: Dec| !gain.is_negative())
+    } else if *key == params_storage::get_staked_ratio_key() {
+        is_valid(value, |ratio: Dec| {
+            !ratio.is_negative() && ratio <= Dec::one()
+        })
+    } else if *key == params_storage::get_pos_inflation_amount_key()
+        || *key == bridge_storage::relay_reward_key()
+    {
+        decodes::<token::Amount>(value)
+    } else if *key == params_storage::get_implicit_vp_key() {
+        Hash::try_from(value).is_ok()
+    } else if *key == params_storage::get_vp_whitelist_storage_key()
+        || *key == params_storage::get_tx_whitelist_storage_key()
+    {
+        is_valid(value, |hashes: Vec<String>| {
+            hashes.iter().all(|hash| Hash::from_str(hash).is_ok())
+        })
+    } else if *key == params_storage::get_invariant_checks_key() {
+        decodes::<Vec<String>>(value)
+    } else if *key == params_storage::get_chain_halted_key() {
+        decodes::<String>(value)
+    } else if *key == params_storage::get_gas_cost_key() {
+        decodes::<BTreeMap<Address, token::Amount>>(value)
+    } else if *key == params_storage::get_fee_unshielding_gas_limit_key()
+        || *key == params_storage::get_fee_unshielding_descriptions_limit_key()
+        || *key == bridge_storage::reorg_safety_depth_key()
+        || *key == bridge_storage::vext_min_consensus_epochs_key()
+        || *key == bridge_storage::bridge_pool_cancellation_delay_key()
+    {
+        decodes::<u64>(value)
+    } else if *key == bridge_storage::eth_start_height_key() {
+        decodes::<ethereum_structs::BlockHeight>(value)
+    } else if *key == bridge_storage::active_key() {
+        decodes::<EthBridgeStatus>(value)
+    } else if *key == bridge_storage::min_confirmations_key() {
+        decodes::<MinimumConfirmations>(value)
+    } else if *key == bridge_storage::native_erc20_key()
+        || *key == bridge_storage::nft_bridge_contract_key()
+    {
+        decodes::<EthAddress>(value)
+    } else if *key == bridge_storage::bridge_contract_key() {
+        decodes::<UpgradeableContract>(value)
+    } else if *key == bridge_storage::bridge_contract_rotation_key() {
+        decodes::<ContractRotation>(value)
+    } else {
+        false
+    }
+}
+
 impl KeyType {
     fn from_key(key: &Key, native_token: &Address) -> Self {
         if gov_storage::is_vote_key(key) {
@@ -730,10 +1164,15 @@ impl KeyType {
             KeyType::GRACE_EPOCH
         } else if gov_storage::is_start_epoch_key(key) {
             KeyType::START_EPOCH
-        } else if gov_storage::is_commit_proposal_key(key) {
+        } else if gov_storage::is_commit_proposal_key(key)
+            || gov_storage::is_expedited_proposal_key(key)
+            || gov_storage::is_starting_proposal_key(key)
+        {
             KeyType::PROPOSAL_COMMIT
         } else if gov_storage::is_end_epoch_key(key) {
             KeyType::END_EPOCH
+        } else if gov_storage::is_voting_end_height_key(key) {
+            KeyType::VOTING_END_HEIGHT
         } else if gov_storage::is_balance_key(key) {
             KeyType::FUNDS
         } else if gov_storage::is_author_key(key) {
@@ -751,3 +1190,71 @@ impl KeyType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use borsh_ext::BorshSerializeExt;
+
+    use super::*;
+
+    /// Test that the new value of a protocol parameter must decode to the
+    /// type of the parameter, and be accepted by the protocol.
+    #[test]
+    fn test_is_valid_parameter_value() {
+        let max_tx_bytes_key = params_storage::get_max_tx_bytes_key();
+        assert!(is_valid_parameter_value(
+            &max_tx_bytes_key,
+            &1234_u32.serialize_to_vec()
+        ));
+        assert!(!is_valid_parameter_value(
+            &max_tx_bytes_key,
+            &0_u32.serialize_to_vec()
+        ));
+        // a value of another type is rejected
+        assert!(!is_valid_parameter_value(
+            &max_tx_bytes_key,
+            &1234_u64.serialize_to_vec()
+        ));
+
+        let staked_ratio_key = params_storage::get_staked_ratio_key();
+        assert!(is_valid_parameter_value(
+            &staked_ratio_key,
+            &Dec::one().serialize_to_vec()
+        ));
+        assert!(!is_valid_parameter_value(
+            &staked_ratio_key,
+            &Dec::two().serialize_to_vec()
+        ));
+
+        let min_confirmations_key = bridge_storage::min_confirmations_key();
+        assert!(is_valid_parameter_value(
+            &min_confirmations_key,
+            &1_u64.serialize_to_vec()
+        ));
+        assert!(!is_valid_parameter_value(
+            &min_confirmations_key,
+            &0_u64.serialize_to_vec()
+        ));
+
+        // vote extensions must have room for at least one Ethereum event
+        let max_vext_eth_events_key = bridge_storage::max_vext_eth_events_key();
+        assert!(is_valid_parameter_value(
+            &max_vext_eth_events_key,
+            &1_u64.serialize_to_vec()
+        ));
+        assert!(!is_valid_parameter_value(
+            &max_vext_eth_events_key,
+            &0_u64.serialize_to_vec()
+        ));
+
+        let tx_whitelist_key = params_storage::get_tx_whitelist_storage_key();
+        assert!(is_valid_parameter_value(
+            &tx_whitelist_key,
+            &vec![Hash::zero().to_string()].serialize_to_vec()
+        ));
+        assert!(!is_valid_parameter_value(
+            &tx_whitelist_key,
+            &vec!["not a hash".to_string()].serialize_to_vec()
+        ));
+    }
+}
@@ -2,7 +2,11 @@
 
 use std::collections::HashMap;
 
-use namada_core::ledger::governance::utils::TallyResult;
+use namada_core::ledger::governance::storage::proposal::StorageProposal;
+use namada_core::ledger::governance::storage::vote::StorageProposalVote;
+use namada_core::ledger::governance::utils::{ProposalResult, TallyResult};
+use namada_core::types::address::Address;
+use namada_core::types::token;
 use namada_sdk::events::{Event, EventLevel};
 use thiserror::Error;
 
@@ -83,6 +87,15 @@ impl ProposalEvent {
         )
     }
 
+    /// Create a new proposal event for a proposal rejected by a veto
+    pub fn vetoed_proposal_event(proposal_id: u64) -> Self {
+        let mut event = ProposalEvent::rejected_proposal_event(proposal_id);
+        event
+            .attributes
+            .insert("vetoed".to_string(), true.to_string());
+        event
+    }
+
     /// Create a new proposal event for default proposal
     pub fn default_proposal_event(
         proposal_id: u64,
@@ -98,6 +111,17 @@ impl ProposalEvent {
         )
     }
 
+    /// Create a new proposal event for a passed proposal whose code failed to
+    /// execute
+    pub fn failed_execution_proposal_event(proposal_id: u64) -> Self {
+        let mut event =
+            ProposalEvent::default_proposal_event(proposal_id, true, false);
+        event
+            .attributes
+            .insert("execution_failed".to_string(), true.to_string());
+        event
+    }
+
     /// Create a new proposal event for pgf stewards proposal
     pub fn pgf_steward_proposal_event(proposal_id: u64, result: bool) -> Self {
         ProposalEvent::new(
@@ -120,6 +144,84 @@ impl ProposalEvent {
         )
     }
 
+    /// Create a new event for the payment of a continuous pgf funding
+    pub fn pgf_funding_payment_event(
+        proposal_id: u64,
+        target: &Address,
+        amount: token::Amount,
+        result: bool,
+    ) -> Self {
+        let attributes = HashMap::from([
+            ("proposal_id".to_string(), proposal_id.to_string()),
+            ("pgf_funding_target".to_string(), target.to_string()),
+            ("pgf_funding_amount".to_string(), amount.to_string_native()),
+            (
+                "pgf_funding_payment_status".to_string(),
+                (!result as u64).to_string(),
+            ),
+        ]);
+        Self {
+            event_type: EventType::Proposal.to_string(),
+            attributes,
+        }
+    }
+
+    /// Create a new proposal event for parameter change proposal
+    pub fn parameter_change_proposal_event(
+        proposal_id: u64,
+        result: bool,
+    ) -> Self {
+        ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            proposal_id,
+            false,
+            result,
+        )
+    }
+
+    /// Create a new proposal event for code allowlist proposal
+    pub fn code_allowlist_proposal_event(
+        proposal_id: u64,
+        result: bool,
+    ) -> Self {
+        ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            proposal_id,
+            false,
+            result,
+        )
+    }
+
+    /// Create a new proposal event for consensus params proposal
+    pub fn consensus_params_proposal_event(
+        proposal_id: u64,
+        result: bool,
+    ) -> Self {
+        ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            proposal_id,
+            false,
+            result,
+        )
+    }
+
+    /// Create a new proposal event for treasury spend proposal
+    pub fn treasury_spend_proposal_event(
+        proposal_id: u64,
+        result: bool,
+    ) -> Self {
+        ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            proposal_id,
+            false,
+            result,
+        )
+    }
+
     /// Create a new proposal event for eth proposal
     pub fn eth_proposal_event(proposal_id: u64, result: bool) -> Self {
         ProposalEvent::new(
@@ -131,3 +233,168 @@ impl ProposalEvent {
         )
     }
 }
+
+/// Typed events of the lifecycle of a governance proposal, letting indexers
+/// follow governance without diffing storage
+pub enum GovernanceEvent {
+    /// A new proposal was submitted
+    ProposalSubmitted {
+        /// The submitted proposal
+        proposal: StorageProposal,
+    },
+    /// The voting period of a proposal started
+    VotingStarted {
+        /// The proposal that is open for voting
+        proposal: StorageProposal,
+    },
+    /// A vote was cast on a proposal for one delegation of the voter
+    VoteCast {
+        /// The proposal id
+        id: u64,
+        /// The address of the voter
+        voter: Address,
+        /// The validator of the delegation the vote is cast for
+        validator: Address,
+        /// The vote
+        vote: StorageProposalVote,
+    },
+    /// The votes of a proposal were tallied
+    ProposalTallied {
+        /// The proposal id
+        id: u64,
+        /// The outcome of the tally with its voting power breakdown
+        result: ProposalResult,
+        /// Whether the proposal is on the expedited track
+        expedited: bool,
+        /// Whether the proposal was vetoed
+        vetoed: bool,
+    },
+    /// The payload of a passed proposal was executed
+    ProposalExecuted {
+        /// The proposal id
+        id: u64,
+        /// The name of the proposal type
+        proposal_type: String,
+        /// Whether the execution succeeded
+        succeeded: bool,
+    },
+    /// A proposal was withdrawn by its author before its voting period
+    /// started
+    ProposalWithdrawn {
+        /// The proposal id
+        id: u64,
+    },
+    /// The treasury transfer of a passed treasury spend proposal was
+    /// executed
+    TreasurySpend {
+        /// The proposal id
+        id: u64,
+        /// The recipient of the funds
+        recipient: Address,
+        /// The amount of native tokens spent
+        amount: token::Amount,
+        /// Whether the treasury could cover the spend
+        succeeded: bool,
+    },
+}
+
+impl From<GovernanceEvent> for Event {
+    fn from(governance_event: GovernanceEvent) -> Self {
+        let event_type = match &governance_event {
+            GovernanceEvent::ProposalSubmitted { .. } => {
+                EventType::ProposalSubmitted
+            }
+            GovernanceEvent::VotingStarted { .. } => {
+                EventType::ProposalVotingStarted
+            }
+            GovernanceEvent::VoteCast { .. } => EventType::ProposalVote,
+            GovernanceEvent::ProposalTallied { .. } => {
+                EventType::ProposalTallied
+            }
+            GovernanceEvent::ProposalExecuted { .. } => {
+                EventType::ProposalExecuted
+            }
+            GovernanceEvent::ProposalWithdrawn { .. } => {
+                EventType::ProposalWithdrawn
+            }
+            GovernanceEvent::TreasurySpend { .. } => EventType::TreasurySpend,
+        };
+        let mut event = Self {
+            event_type,
+            level: EventLevel::Block,
+            attributes: HashMap::new(),
+        };
+        match governance_event {
+            GovernanceEvent::ProposalSubmitted { proposal }
+            | GovernanceEvent::VotingStarted { proposal } => {
+                event["proposal_id"] = proposal.id.to_string();
+                event["author"] = proposal.author.to_string();
+                event["proposal_type"] = proposal.r#type.to_string();
+                event["voting_start_epoch"] =
+                    proposal.voting_start_epoch.to_string();
+                event["voting_end_epoch"] =
+                    proposal.voting_end_epoch.to_string();
+                event["grace_epoch"] = proposal.grace_epoch.to_string();
+                if let Some(height) = proposal.voting_end_height {
+                    event["voting_end_height"] = height.to_string();
+                }
+            }
+            GovernanceEvent::VoteCast {
+                id,
+                voter,
+                validator,
+                vote,
+            } => {
+                event["proposal_id"] = id.to_string();
+                event["voter"] = voter.to_string();
+                event["validator"] = validator.to_string();
+                event["vote"] = vote.to_string();
+            }
+            GovernanceEvent::ProposalTallied {
+                id,
+                result,
+                expedited,
+                vetoed,
+            } => {
+                event["proposal_id"] = id.to_string();
+                event["tally_result"] = result.result.to_string();
+                event["vetoed"] = vetoed.to_string();
+                event["expedited"] = expedited.to_string();
+                event["total_voting_power"] =
+                    result.total_voting_power.to_string_native();
+                event["total_yay_power"] =
+                    result.total_yay_power.to_string_native();
+                event["total_nay_power"] =
+                    result.total_nay_power.to_string_native();
+                event["total_abstain_power"] =
+                    result.total_abstain_power.to_string_native();
+                event["total_veto_power"] =
+                    result.total_veto_power.to_string_native();
+            }
+            GovernanceEvent::ProposalExecuted {
+                id,
+                proposal_type,
+                succeeded,
+            } => {
+                event["proposal_id"] = id.to_string();
+                event["proposal_type"] = proposal_type;
+                event["succeeded"] = succeeded.to_string();
+            }
+            GovernanceEvent::ProposalWithdrawn { id } => {
+                event["proposal_id"] = id.to_string();
+            }
+            GovernanceEvent::TreasurySpend {
+                id,
+                recipient,
+                amount,
+                succeeded,
+            } => {
+                event["proposal_id"] = id.to_string();
+                event["recipient"] = recipient.to_string();
+                event["amount"] = amount.to_string_native();
+                event["succeeded"] = succeeded.to_string();
+            }
+        }
+        event
+    }
+}
@@ -13,15 +13,21 @@ pub mod storage;
 pub mod vp_host_fns;
 
 #[cfg(feature = "wasm-runtime")]
-pub use dry_run_tx::dry_run_tx;
+pub use dry_run_tx::{dry_run_tx, dry_run_tx_diff};
 pub use namada_core::ledger::{
     gas, parameters, replay_protection, storage_api, tx_env, vp_env,
 };
+#[cfg(feature = "wasm-runtime")]
+pub use view_tx::{view_tx, MAX_VIEW_TX_GAS};
 
 #[cfg(feature = "wasm-runtime")]
 mod dry_run_tx {
-    use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+    use borsh_ext::BorshSerializeExt;
+    use namada_core::ledger::storage::{
+        DBIter, StorageHasher, TempWlStorage, DB,
+    };
     use namada_core::ledger::storage_api::ResultExt;
+    use namada_core::types::transaction::TxResult;
     use namada_sdk::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
 
     use super::{protocol, storage_api};
@@ -38,9 +44,75 @@ mod dry_run_tx {
         H: 'static + StorageHasher + Sync,
         CA: 'static + WasmCacheAccess + Sync,
     {
-        use borsh_ext::BorshSerializeExt;
+        let (data, _) = execute(&mut ctx, request)?;
+        // NOTE: the keys changed by the wrapper transaction (if any) are not
+        // returned from this function
+        let data = data.serialize_to_vec();
+        Ok(EncodedResponseQuery {
+            data,
+            proof: None,
+            info: Default::default(),
+        })
+    }
+
+    /// Dry run a transaction, returning a diff of the changes it would make
+    /// to storage, grouped by account and subspace, along with its result.
+    /// Unlike the changed keys of the result, the diff includes the changes
+    /// made by the wrapper transaction (if any), such as the fee payment.
+    pub fn dry_run_tx_diff<D, H, CA>(
+        mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> storage_api::Result<EncodedResponseQuery>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
+        use namada_core::ledger::storage::write_log::StorageModification;
+        use namada_core::ledger::storage_api::StorageRead;
+        use namada_sdk::queries::{DryRunDiff, TxStateDiff};
+
+        let (result, temp_wl_storage) = execute(&mut ctx, request)?;
+        let write_log = &temp_wl_storage.write_log;
+        let mut diff = TxStateDiff::default();
+        let mut changed_keys = write_log.get_block_keys();
+        changed_keys.extend(write_log.get_keys_with_precommit());
+        for key in changed_keys {
+            let new = match write_log.read(&key).0 {
+                Some(StorageModification::Write { value }) => {
+                    Some(value.clone())
+                }
+                Some(StorageModification::InitAccount { vp_code_hash }) => {
+                    Some(vp_code_hash.0.to_vec())
+                }
+                Some(StorageModification::Delete) => None,
+                // temporary values are never persisted
+                Some(StorageModification::Temp { .. }) | None => continue,
+            };
+            let old = ctx.wl_storage.read_bytes(&key)?;
+            diff.insert(key, old, new);
+        }
+
+        let data = DryRunDiff { result, diff }.serialize_to_vec();
+        Ok(EncodedResponseQuery {
+            data,
+            proof: None,
+            info: Default::default(),
+        })
+    }
+
+    /// Execute a transaction against temporary storage, returning its
+    /// result and the storage holding its changes.
+    fn execute<'a, D, H, CA>(
+        ctx: &mut RequestCtx<'a, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> storage_api::Result<(TxResult, TempWlStorage<'a, D, H>)>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
         use namada_core::ledger::gas::{Gas, GasMetering, TxGasMeter};
-        use namada_core::ledger::storage::TempWlStorage;
         use namada_core::proto::Tx;
         use namada_core::types::transaction::DecryptedTx;
 
@@ -51,7 +123,8 @@ mod dry_run_tx {
         let mut tx = Tx::try_from(&request.data[..]).into_storage_result()?;
         tx.validate_tx().into_storage_result()?;
 
-        let mut temp_wl_storage = TempWlStorage::new(&ctx.wl_storage.storage);
+        let wl_storage = ctx.wl_storage;
+        let mut temp_wl_storage = TempWlStorage::new(&wl_storage.storage);
         let mut cumulated_gas = Gas::default();
 
         // Wrapper dry run to allow estimating the gas cost of a transaction
@@ -101,6 +174,12 @@ mod dry_run_tx {
                         .into(),
                 )
             }
+            TxType::View => {
+                return Err(storage_api::Error::new_const(
+                    "View transactions can only be executed via the view_tx \
+                     query",
+                ));
+            }
         };
 
         let mut data = protocol::apply_wasm_tx(
@@ -121,9 +200,96 @@ mod dry_run_tx {
             ))?;
         // Account gas for both inner and wrapper (if available)
         data.gas_used = cumulated_gas;
-        // NOTE: the keys changed by the wrapper transaction (if any) are not
-        // returned from this function
-        let data = data.serialize_to_vec();
+        Ok((data, temp_wl_storage))
+    }
+}
+
+#[cfg(feature = "wasm-runtime")]
+mod view_tx {
+    use namada_core::ledger::storage::{DBIter, StorageHasher, DB};
+    use namada_core::ledger::storage_api::ResultExt;
+    use namada_sdk::queries::{EncodedResponseQuery, RequestCtx, RequestQuery};
+
+    use super::storage_api;
+    use crate::vm::wasm::{TxCache, VpCache};
+    use crate::vm::WasmCacheAccess;
+
+    /// The maximum amount of gas a view transaction may consume, regardless
+    /// of the maximum block gas.
+    pub const MAX_VIEW_TX_GAS: u64 = 5_000_000;
+
+    /// Execute a read-only view transaction against the last committed
+    /// state. The transaction is not allowed to persist any writes to
+    /// storage; its output is made up of the values it writes to temporary
+    /// storage.
+    pub fn view_tx<D, H, CA>(
+        mut ctx: RequestCtx<'_, D, H, VpCache<CA>, TxCache<CA>>,
+        request: &RequestQuery,
+    ) -> storage_api::Result<EncodedResponseQuery>
+    where
+        D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+        H: 'static + StorageHasher + Sync,
+        CA: 'static + WasmCacheAccess + Sync,
+    {
+        use std::collections::BTreeMap;
+
+        use borsh_ext::BorshSerializeExt;
+        use namada_core::ledger::gas::{GasMetering, TxGasMeter};
+        use namada_core::ledger::storage::write_log::StorageModification;
+        use namada_core::proto::Tx;
+        use namada_core::types::transaction::ViewTxResult;
+
+        use crate::types::storage::TxIndex;
+        use crate::types::transaction::TxType;
+        use crate::vm::wasm;
+
+        let tx = Tx::try_from(&request.data[..]).into_storage_result()?;
+        if !matches!(tx.header().tx_type, TxType::View) {
+            return Err(storage_api::Error::new_const(
+                "Only view transactions can be executed via the view_tx query",
+            ));
+        }
+        tx.validate_tx().into_storage_result()?;
+
+        let gas_limit =
+            namada_core::ledger::gas::get_max_block_gas(ctx.wl_storage)?
+                .min(MAX_VIEW_TX_GAS);
+        let mut tx_gas_meter = TxGasMeter::new(gas_limit.into());
+        let mut temp_wl_storage = ctx.committed_snapshot();
+
+        wasm::run::tx(
+            temp_wl_storage.storage,
+            &mut temp_wl_storage.write_log,
+            &mut tx_gas_meter,
+            &TxIndex(0),
+            &tx,
+            &mut ctx.vp_wasm_cache,
+            &mut ctx.tx_wasm_cache,
+        )
+        .into_storage_result()?;
+
+        let write_log = &temp_wl_storage.write_log;
+        let mut output = BTreeMap::new();
+        for key in write_log.get_keys() {
+            match write_log.read(&key).0 {
+                Some(StorageModification::Temp { value }) => {
+                    output.insert(key, value.clone());
+                }
+                Some(_) => {
+                    return Err(storage_api::Error::new(format!(
+                        "View transactions must not write to storage, but a \
+                         write to {key} was attempted"
+                    )));
+                }
+                None => {}
+            }
+        }
+
+        let data = ViewTxResult {
+            gas_used: tx_gas_meter.get_tx_consumed_gas(),
+            output,
+        }
+        .serialize_to_vec();
         Ok(EncodedResponseQuery {
             data,
             proof: None,
@@ -138,13 +304,17 @@ mod test {
     use borsh_ext::BorshSerializeExt;
     use namada_core::ledger::storage::testing::TestWlStorage;
     use namada_core::ledger::storage_api::{self, StorageWrite};
+    use namada_core::types::dec::Dec;
     use namada_core::types::hash::Hash;
-    use namada_core::types::storage::{BlockHeight, Key};
+    use namada_core::types::storage::{BlockHeight, Epoch, Key};
     use namada_core::types::transaction::decrypted::DecryptedTx;
     use namada_core::types::transaction::TxType;
     use namada_core::types::{address, token};
+    use namada_sdk::queries::vp::pos::MAX_SLASHES_PER_PAGE;
     use namada_sdk::queries::{
-        EncodedResponseQuery, RequestCtx, RequestQuery, Router, RPC,
+        CrashReports, EncodedResponseQuery, NodeMetrics, RequestCtx,
+        RequestQuery, Router, StateDiff, StateDiffValues, ValsetProofCache,
+        WriteHotspots, MAX_BATCH_READ_KEYS, RPC,
     };
     use namada_test_utils::TestWasms;
     use tempfile::TempDir;
@@ -152,6 +322,9 @@ mod test {
 
     use crate::ledger::events::log::EventLog;
     use crate::ledger::queries::Client;
+    use crate::proof_of_stake::storage::validator_total_slashed_key;
+    use crate::proof_of_stake::types::{Slash, SlashType};
+    use crate::proof_of_stake::validator_slashes_handle;
     use crate::proto::{Code, Data, Tx};
     use crate::vm::wasm::{TxCache, VpCache};
     use crate::vm::{wasm, WasmCacheRoAccess};
@@ -167,6 +340,14 @@ mod test {
         pub wl_storage: TestWlStorage,
         /// event log
         pub event_log: EventLog,
+        /// crash reports
+        pub crash_reports: CrashReports,
+        /// storage write hotspots
+        pub write_hotspots: WriteHotspots,
+        /// node-local metrics
+        pub node_metrics: NodeMetrics,
+        /// validator set update proofs cache
+        pub valset_proof_cache: ValsetProofCache,
         /// VP wasm compilation cache
         pub vp_wasm_cache: VpCache<WasmCacheRoAccess>,
         /// tx wasm compilation cache
@@ -211,6 +392,10 @@ mod test {
                 rpc,
                 wl_storage,
                 event_log,
+                crash_reports: CrashReports::default(),
+                write_hotspots: WriteHotspots::default(),
+                node_metrics: NodeMetrics::default(),
+                valset_proof_cache: ValsetProofCache::default(),
                 vp_wasm_cache: vp_wasm_cache.read_only(),
                 tx_wasm_cache: tx_wasm_cache.read_only(),
                 vp_cache_dir,
@@ -250,11 +435,21 @@ mod test {
                 vp_wasm_cache: self.vp_wasm_cache.clone(),
                 tx_wasm_cache: self.tx_wasm_cache.clone(),
                 storage_read_past_height_limit: None,
+                crash_reports: &self.crash_reports,
+                write_hotspots: &self.write_hotspots,
+                node_metrics: &self.node_metrics,
+                valset_proof_cache: &self.valset_proof_cache,
+                archive_endpoints: &[],
+                oracle_last_processed_block: None,
             };
             // TODO: this is a hack to propagate errors to the caller, we should
             // really permit error types other than [`std::io::Error`]
             if request.path == "/shell/dry_run_tx" {
                 super::dry_run_tx(ctx, &request)
+            } else if request.path == "/shell/dry_run_tx_diff" {
+                super::dry_run_tx_diff(ctx, &request)
+            } else if request.path == "/shell/view_tx" {
+                super::view_tx(ctx, &request)
             } else {
                 self.rpc.handle(ctx, &request)
             }
@@ -307,6 +502,35 @@ mod test {
             .unwrap();
         assert!(result.data.is_accepted());
 
+        // Request the state diff of a dry run tx - the no-op tx doesn't
+        // change anything
+        let result = RPC
+            .shell()
+            .dry_run_tx_diff(&client, Some(outer_tx.to_bytes()), None, false)
+            .await
+            .unwrap();
+        assert!(result.data.result.is_accepted());
+        assert!(result.data.diff.accounts.is_empty());
+
+        // Request a view tx
+        let mut view_tx = Tx::from_type(TxType::View);
+        view_tx.header.chain_id = client.wl_storage.storage.chain_id.clone();
+        view_tx.set_code(Code::from_hash(tx_hash, None));
+        view_tx.set_data(Data::new(vec![]));
+        let result = RPC
+            .shell()
+            .view_tx(&client, Some(view_tx.to_bytes()), None, false)
+            .await
+            .unwrap();
+        assert!(result.data.output.is_empty());
+
+        // Non-view txs must be rejected by the view tx query
+        let result = RPC
+            .shell()
+            .view_tx(&client, Some(outer_tx.to_bytes()), None, false)
+            .await;
+        assert!(result.is_err());
+
         // Request storage value for a balance key ...
         let token_addr = address::testing::established_address_1();
         let owner = address::testing::established_address_2();
@@ -370,6 +594,187 @@ mod test {
             .unwrap();
         assert!(has_balance_key);
 
+        // Request a batch of storage values, including a missing key
+        let missing_key = token::balance_key(
+            &token_addr,
+            &address::testing::established_address_3(),
+        );
+        let keys = vec![balance_key.clone(), missing_key];
+        let read_values = RPC
+            .shell()
+            .storage_values(&client, Some(keys.serialize_to_vec()), None, false)
+            .await
+            .unwrap();
+        assert_eq!(read_values.data.len(), 2);
+        assert_eq!(
+            balance,
+            token::Amount::try_from_slice(
+                read_values.data[0].as_ref().unwrap()
+            )
+            .unwrap()
+        );
+        assert!(read_values.data[1].is_none());
+        assert!(read_values.proof.is_none());
+
+        // Batches over the size limit are rejected
+        let keys = vec![balance_key.clone(); MAX_BATCH_READ_KEYS + 1];
+        let result = RPC
+            .shell()
+            .storage_values(&client, Some(keys.serialize_to_vec()), None, false)
+            .await;
+        assert!(result.is_err());
+
+        // Write another balance, without committing the block ...
+        let other_owner = address::testing::established_address_3();
+        let other_balance_key = token::balance_key(&token_addr, &other_owner);
+        StorageWrite::write(
+            &mut client.wl_storage,
+            &other_balance_key,
+            balance,
+        )?;
+        client.wl_storage.commit_tx();
+        // ... queries should only observe the last committed state
+        let read_balances = RPC
+            .shell()
+            .storage_prefix(&client, None, None, false, &balance_prefix)
+            .await
+            .unwrap();
+        assert_eq!(read_balances.data.len(), 1);
+        let has_balance_key = RPC
+            .shell()
+            .storage_has_key(&client, &other_balance_key)
+            .await
+            .unwrap();
+        assert!(!has_balance_key);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shell_queries_state_diff() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+        let key = Key::parse("changed").unwrap();
+        let restored_key = Key::parse("restored").unwrap();
+
+        // Commit a block at height 1 ...
+        client.wl_storage.storage.block.height = BlockHeight(1);
+        StorageWrite::write(&mut client.wl_storage, &key, 1_u64)?;
+        StorageWrite::write(&mut client.wl_storage, &restored_key, 1_u64)?;
+        client.wl_storage.commit_tx();
+        client.wl_storage.commit_block().unwrap();
+        // ... and another one at height 2
+        client.wl_storage.storage.block.height = BlockHeight(2);
+        StorageWrite::write(&mut client.wl_storage, &key, 2_u64)?;
+        StorageWrite::delete(&mut client.wl_storage, &restored_key)?;
+        client.wl_storage.commit_tx();
+        client.wl_storage.commit_block().unwrap();
+
+        // Keys which were added and then removed are left out
+        let diff = RPC
+            .shell()
+            .state_diff(&client, &BlockHeight(0), &BlockHeight(2), &true)
+            .await
+            .unwrap();
+        assert_eq!(
+            diff,
+            vec![StateDiff {
+                key: key.clone(),
+                values: Some(StateDiffValues {
+                    old: None,
+                    new: Some(2_u64.serialize_to_vec()),
+                }),
+            }]
+        );
+
+        let diff = RPC
+            .shell()
+            .state_diff(&client, &BlockHeight(1), &BlockHeight(2), &true)
+            .await
+            .unwrap();
+        assert_eq!(diff.len(), 2);
+        assert_eq!(
+            diff[0],
+            StateDiff {
+                key: key.clone(),
+                values: Some(StateDiffValues {
+                    old: Some(1_u64.serialize_to_vec()),
+                    new: Some(2_u64.serialize_to_vec()),
+                }),
+            }
+        );
+        assert_eq!(diff[1].key, restored_key);
+
+        // The values are only returned on request
+        let diff = RPC
+            .shell()
+            .state_diff(&client, &BlockHeight(1), &BlockHeight(2), &false)
+            .await
+            .unwrap();
+        assert!(diff.iter().all(|diff| diff.values.is_none()));
+
+        // Heights past the last committed block cannot be queried
+        let result = RPC
+            .shell()
+            .state_diff(&client, &BlockHeight(1), &BlockHeight(3), &false)
+            .await;
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pos_queries_slash_history() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+        let validator = address::testing::established_address_1();
+        let slashes = validator_slashes_handle(&validator);
+        for height in 0..3_u64 {
+            let slash = Slash {
+                epoch: Epoch(height),
+                block_height: height,
+                r#type: SlashType::DuplicateVote,
+                rate: Dec::new(1, 2).unwrap(),
+            };
+            slashes.push(&mut client.wl_storage, slash)?;
+        }
+        let total_slashed = token::Amount::native_whole(42);
+        StorageWrite::write(
+            &mut client.wl_storage,
+            &validator_total_slashed_key(&validator),
+            total_slashed,
+        )?;
+
+        // The slashes are paged in the order they were processed
+        let history = RPC
+            .vp()
+            .pos()
+            .validator_slash_history(&client, &validator, &1, &2)
+            .await
+            .unwrap();
+        assert_eq!(history.total_slashes, 3);
+        assert_eq!(history.total_slashed, total_slashed);
+        assert_eq!(history.slashes.len(), 1);
+        assert_eq!(history.slashes[0].block_height, 2);
+
+        // The pages past the last slash are empty
+        let history = RPC
+            .vp()
+            .pos()
+            .validator_slash_history(&client, &validator, &2, &2)
+            .await
+            .unwrap();
+        assert!(history.slashes.is_empty());
+        assert_eq!(history.total_slashes, 3);
+
+        // The page size is bounded
+        for per_page in [0, MAX_SLASHES_PER_PAGE + 1] {
+            let result = RPC
+                .vp()
+                .pos()
+                .validator_slash_history(&client, &validator, &0, &per_page)
+                .await;
+            assert!(result.is_err());
+        }
+
         Ok(())
     }
 }
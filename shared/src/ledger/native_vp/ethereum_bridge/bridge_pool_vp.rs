@@ -10,6 +10,10 @@
 //! correctly. This means that the appropriate data is
 //! added to the pool and gas fees are submitted appropriately
 //! and that tokens to be transferred are escrowed.
+//!
+//! It also checks that requests to cancel a transfer are only
+//! made by its sender, once the transfer has sat unrelayed in
+//! the pool for long enough.
 
 use std::borrow::Cow;
 use std::collections::BTreeSet;
@@ -19,9 +23,12 @@ use borsh::BorshDeserialize;
 use eyre::eyre;
 use namada_core::hints;
 use namada_core::ledger::eth_bridge::storage::bridge_pool::{
-    get_pending_key, is_bridge_pool_key, BRIDGE_POOL_ADDRESS,
+    get_cancellation_key, get_key_from_hash, get_pending_key,
+    is_bridge_pool_key, is_cancellation_key, BRIDGE_POOL_ADDRESS,
+};
+use namada_core::ledger::eth_bridge::storage::{
+    bridge_pool_cancellation_delay_key, whitelist,
 };
-use namada_core::ledger::eth_bridge::storage::whitelist;
 use namada_core::ledger::eth_bridge::ADDRESS as BRIDGE_ADDRESS;
 use namada_ethereum_bridge::parameters::read_native_erc20_address;
 use namada_ethereum_bridge::storage::wrapped_erc20s;
@@ -31,9 +38,11 @@ use crate::ledger::storage::traits::StorageHasher;
 use crate::ledger::storage::{DBIter, DB};
 use crate::proto::Tx;
 use crate::types::address::{Address, InternalAddress};
-use crate::types::eth_bridge_pool::{PendingTransfer, TransferToEthereumKind};
+use crate::types::eth_bridge_pool::{
+    CancelTransfer, PendingTransfer, TransferToEthereumKind,
+};
 use crate::types::ethereum_events::EthAddress;
-use crate::types::storage::Key;
+use crate::types::storage::{BlockHeight, Epoch, Key};
 use crate::types::token::{balance_key, Amount};
 use crate::vm::WasmCacheAccess;
 
@@ -311,6 +320,118 @@ where
         Ok(true)
     }
 
+    /// Validate a request to cancel a transfer in the Bridge pool.
+    ///
+    /// The request must be authorized by the sender of the transfer,
+    /// which must have sat unrelayed in the pool for at least the
+    /// number of epochs set by governance. No other change to the pool
+    /// is allowed.
+    fn validate_cancellation(
+        &self,
+        tx_data: &[u8],
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool, Error> {
+        let CancelTransfer { transfer_hash } =
+            BorshDeserialize::try_from_slice(tx_data)
+                .map_err(|e| Error(e.into()))?;
+        let cancellation_key = get_cancellation_key(&transfer_hash);
+        for key in keys_changed.iter().filter(|k| is_bridge_pool_key(k)) {
+            if *key != cancellation_key {
+                tracing::debug!(
+                    %key,
+                    "Rejecting transaction as it is attempting to change an \
+                     incorrect key in the Ethereum bridge pool, while \
+                     cancelling a transfer"
+                );
+                return Ok(false);
+            }
+        }
+        let pending_key = get_key_from_hash(&transfer_hash);
+        let Some(transfer) =
+            (&self.ctx).read_pre_value::<PendingTransfer>(&pending_key)?
+        else {
+            tracing::debug!(
+                %transfer_hash,
+                "Rejecting the cancellation of a transfer which is not in \
+                 the Ethereum bridge pool"
+            );
+            return Ok(false);
+        };
+        if (&self.ctx)
+            .read_pre_value::<Epoch>(&cancellation_key)?
+            .is_some()
+        {
+            tracing::debug!(
+                ?transfer,
+                "The transfer has already been cancelled"
+            );
+            return Ok(false);
+        }
+        let current_epoch = self.ctx.storage.get_current_epoch().0;
+        if (&self.ctx).read_post_value::<Epoch>(&cancellation_key)?
+            != Some(current_epoch)
+        {
+            tracing::debug!(
+                ?transfer,
+                "The cancellation of the transfer was not properly recorded"
+            );
+            return Ok(false);
+        }
+        if !verifiers.contains(&transfer.transfer.sender) {
+            tracing::debug!(
+                ?transfer,
+                "The cancellation of a transfer must be authorized by its \
+                 sender"
+            );
+            return Ok(false);
+        }
+
+        let Some(delay) = (&self.ctx)
+            .read_pre_value::<u64>(&bridge_pool_cancellation_delay_key())?
+        else {
+            tracing::debug!("Cancelling Bridge pool transfers is disabled");
+            return Ok(false);
+        };
+        // NB: the Merkle tree of the Bridge pool records the height at
+        // which each transfer was added to the pool
+        let inserted_epoch = self
+            .ctx
+            .storage
+            .block
+            .tree
+            .get(&pending_key)
+            .ok()
+            .and_then(|height| BlockHeight::try_from_slice(&height).ok())
+            .and_then(|height| {
+                self.ctx.storage.block.pred_epochs.get_epoch(height)
+            })
+            .ok_or_else(|| {
+                eyre!(
+                    "Could not determine the epoch at which the transfer \
+                     {transfer_hash} was added to the Bridge pool"
+                )
+            })?;
+        if current_epoch < inserted_epoch + delay {
+            tracing::debug!(
+                ?transfer,
+                %inserted_epoch,
+                %current_epoch,
+                delay,
+                "The transfer has not sat in the Bridge pool for long enough \
+                 to be cancelled"
+            );
+            return Ok(false);
+        }
+
+        tracing::info!(
+            ?transfer,
+            "The Ethereum bridge pool VP accepted the cancellation of a \
+             transfer"
+        );
+        Ok(true)
+    }
+
     /// Deteremine the debit and credit amounts that should be checked.
     fn determine_escrow_checks<'trans, 'this: 'trans>(
         &'this self,
@@ -530,16 +651,26 @@ where
         &self,
         tx: &Tx,
         keys_changed: &BTreeSet<Key>,
-        _verifiers: &BTreeSet<Address>,
+        verifiers: &BTreeSet<Address>,
     ) -> Result<bool, Error> {
         tracing::debug!(
             keys_changed_len = keys_changed.len(),
-            verifiers_len = _verifiers.len(),
+            verifiers_len = verifiers.len(),
             "Ethereum Bridge Pool VP triggered",
         );
         let Some(tx_data) = tx.data() else {
             return Err(eyre!("No transaction data found").into());
         };
+        if keys_changed
+            .iter()
+            .any(|key| is_cancellation_key(key).is_some())
+        {
+            return self.validate_cancellation(
+                &tx_data,
+                keys_changed,
+                verifiers,
+            );
+        }
         let transfer: PendingTransfer =
             BorshDeserialize::try_from_slice(&tx_data[..])
                 .map_err(|e| Error(e.into()))?;
@@ -659,6 +790,7 @@ mod test_bridge_pool_vp {
     use crate::ledger::storage_api::StorageWrite;
     use crate::types::address::{nam, wnam, InternalAddress};
     use crate::types::chain::ChainId;
+    use crate::types::eth_abi::Encode;
     use crate::types::eth_bridge_pool::{GasFee, TransferToEthereum};
     use crate::types::hash::Hash;
     use crate::types::storage::TxIndex;
@@ -1941,4 +2073,97 @@ mod test_bridge_pool_vp {
 
         assert!(!delta.validate(&some_changed_keys));
     }
+
+    /// Run the Bridge pool VP on the cancellation of the transfer in the
+    /// initial pool, with the given cancellation delay and current epoch.
+    fn assert_cancellation(
+        delay: Option<u64>,
+        current_epoch: Epoch,
+        verifiers: BTreeSet<Address>,
+        expect: Expect,
+    ) {
+        let mut wl_storage = setup_storage();
+        if let Some(delay) = delay {
+            wl_storage
+                .write(&bridge_pool_cancellation_delay_key(), delay)
+                .expect("Test failed");
+            wl_storage.write_log.commit_tx();
+        }
+        wl_storage.storage.block.epoch = current_epoch;
+
+        let transfer_hash = initial_pool().keccak256();
+        let cancellation_key = get_cancellation_key(&transfer_hash);
+        wl_storage
+            .write_log
+            .write(&cancellation_key, current_epoch.serialize_to_vec())
+            .expect("Test failed");
+        let keys_changed = BTreeSet::from([cancellation_key]);
+
+        let tx = Tx::from_type(TxType::Raw);
+        let vp = BridgePoolVp {
+            ctx: setup_ctx(
+                &tx,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &keys_changed,
+                &verifiers,
+            ),
+        };
+        let mut tx = Tx::new(wl_storage.storage.chain_id.clone(), None);
+        tx.add_data(CancelTransfer { transfer_hash });
+
+        let res = vp.validate_tx(&tx, &keys_changed, &verifiers);
+        match expect {
+            Expect::True => assert!(res.expect("Test failed")),
+            Expect::False => assert!(!res.expect("Test failed")),
+            Expect::Error => assert!(res.is_err()),
+        }
+    }
+
+    /// Test that the sender of a transfer can cancel it, once it has sat
+    /// in the pool for long enough.
+    #[test]
+    fn test_cancel_transfer() {
+        assert_cancellation(
+            Some(1),
+            Epoch(1),
+            BTreeSet::from([bertha_address()]),
+            Expect::True,
+        );
+    }
+
+    /// Test that a transfer cannot be cancelled before the cancellation
+    /// delay has elapsed.
+    #[test]
+    fn test_cancel_transfer_too_early() {
+        assert_cancellation(
+            Some(2),
+            Epoch(1),
+            BTreeSet::from([bertha_address()]),
+            Expect::False,
+        );
+    }
+
+    /// Test that a transfer can only be cancelled by its sender.
+    #[test]
+    fn test_cancel_transfer_not_sender() {
+        assert_cancellation(
+            Some(1),
+            Epoch(1),
+            BTreeSet::from([established_address_1()]),
+            Expect::False,
+        );
+    }
+
+    /// Test that transfers cannot be cancelled if no cancellation delay
+    /// was set by governance.
+    #[test]
+    fn test_cancel_transfer_disabled() {
+        assert_cancellation(
+            None,
+            Epoch(1),
+            BTreeSet::from([bertha_address()]),
+            Expect::False,
+        );
+    }
 }
@@ -350,6 +350,31 @@ mod tests {
         }
     }
 
+    /// Test that the owners of the NFTs bridged from Ethereum cannot be
+    /// changed by txs, since NFTs cannot be bridged back.
+    #[test]
+    fn test_rejects_nft_owner_changes() {
+        let owner_key = eth_bridge::storage::nft::owner_key(
+            &EthAddress([1; 20]),
+            &1u64.into(),
+        );
+        let keys_changed = BTreeSet::from([owner_key.clone()]);
+
+        let result = validate_changed_keys(&nam(), &keys_changed);
+
+        assert_matches!(result, Ok(false));
+
+        let keys_changed = BTreeSet::from([
+            owner_key,
+            balance_key(&nam(), &established_address_1()),
+            balance_key(&nam(), &eth_bridge::ADDRESS),
+        ]);
+
+        let result = validate_changed_keys(&nam(), &keys_changed);
+
+        assert_matches!(result, Ok(false));
+    }
+
     /// Test that escrowing Nam is accepted.
     #[test]
     fn test_escrow_nam_accepted() {
@@ -0,0 +1,461 @@
+//! Native VP for hashed time-locked transfers
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use namada_core::ledger::htlc::{
+    storage as htlc_keys, TimeLock, TimeLockStatus, ADDRESS,
+};
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage;
+use crate::ledger::vp_env::VpEnv;
+use crate::proto::Tx;
+use crate::types::address::Address;
+use crate::types::storage::{Epoch, Key};
+use crate::types::token::{self, Amount};
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// HTLC functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// HTLC VP
+pub struct HtlcVp<'a, DB, H, CA>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+/// The changes to storage implied by the time locks modified by a tx
+#[derive(Default)]
+struct ExpectedChanges {
+    /// The ids of the new time locks
+    created: BTreeSet<u64>,
+    /// The expiry index keys written or deleted
+    expiry_keys: BTreeSet<Key>,
+    /// The amounts of each token locked
+    locked: BTreeMap<Address, Amount>,
+    /// The amounts of each token released from the HTLC address
+    released: BTreeMap<Address, Amount>,
+    /// The minimum credits to the balances of the beneficiaries of the
+    /// settled time locks, indexed by token and beneficiary
+    credits: BTreeMap<(Address, Address), Amount>,
+}
+
+impl<'a, DB, H, CA> NativeVp for HtlcVp<'a, DB, H, CA>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + storage::StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn validate_tx(
+        &self,
+        _tx: &Tx,
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let epoch = self.ctx.get_block_epoch()?;
+        let mut expected = ExpectedChanges::default();
+        let mut expiry_keys_changed = BTreeSet::new();
+        let mut balances_changed = BTreeSet::new();
+        for key in keys_changed {
+            if let Some(id) = htlc_keys::is_lock_key(key) {
+                let pre: Option<TimeLock> = self.ctx.read_pre(key)?;
+                let post: Option<TimeLock> = self.ctx.read_post(key)?;
+                if !is_valid_transition(
+                    id,
+                    pre,
+                    post,
+                    epoch,
+                    verifiers,
+                    &mut expected,
+                ) {
+                    tracing::info!(id, "Rejecting invalid time lock change");
+                    return Ok(false);
+                }
+            } else if htlc_keys::is_expiry_key(key).is_some() {
+                expiry_keys_changed.insert(key.clone());
+            } else if let Some([token, owner]) =
+                token::is_any_token_balance_key(key)
+            {
+                if owner == &ADDRESS {
+                    balances_changed.insert(token.clone());
+                }
+            } else if htlc_keys::is_htlc_key(key)
+                && !htlc_keys::is_counter_key(key)
+            {
+                // Reject when trying to update an unexpected key under
+                // `#HTLC/...`
+                return Ok(false);
+            }
+        }
+
+        // The expiry index must be updated along with the time locks
+        if expiry_keys_changed != expected.expiry_keys {
+            return Ok(false);
+        }
+        for key in &expected.expiry_keys {
+            let Some((_, id)) = htlc_keys::is_expiry_key(key) else {
+                return Ok(false);
+            };
+            let post: Option<u64> = self.ctx.read_post(key)?;
+            let valid = if expected.created.contains(&id) {
+                post == Some(id)
+            } else {
+                post.is_none()
+            };
+            if !valid {
+                return Ok(false);
+            }
+        }
+
+        // New time locks take consecutive ids from the counter
+        let counter_key = htlc_keys::get_counter_key();
+        let counter_pre: u64 =
+            self.ctx.read_pre(&counter_key)?.unwrap_or_default();
+        let counter_post: u64 =
+            self.ctx.read_post(&counter_key)?.unwrap_or_default();
+        // The counter may never be decremented, lest ids get reused
+        if counter_post < counter_pre {
+            return Ok(false);
+        }
+        let expected_ids = (counter_pre..counter_post).collect();
+        if expected.created != expected_ids {
+            return Ok(false);
+        }
+
+        // The balances of the HTLC address must back the pending time locks
+        let tokens = balances_changed
+            .iter()
+            .chain(expected.locked.keys())
+            .chain(expected.released.keys());
+        for token in tokens {
+            let key = token::balance_key(token, &ADDRESS);
+            let pre: Amount = self.ctx.read_pre(&key)?.unwrap_or_default();
+            let post: Amount = self.ctx.read_post(&key)?.unwrap_or_default();
+            let locked =
+                expected.locked.get(token).copied().unwrap_or_default();
+            let released =
+                expected.released.get(token).copied().unwrap_or_default();
+            let expected_post = pre
+                .checked_add(locked)
+                .and_then(|balance| balance.checked_sub(released));
+            if expected_post != Some(post) {
+                return Ok(false);
+            }
+        }
+
+        // The beneficiaries of the settled time locks must be credited
+        for ((token, beneficiary), amount) in &expected.credits {
+            let key = token::balance_key(token, beneficiary);
+            let pre: Amount = self.ctx.read_pre(&key)?.unwrap_or_default();
+            let post: Amount = self.ctx.read_post(&key)?.unwrap_or_default();
+            match pre.checked_add(*amount) {
+                Some(min_post) if post >= min_post => {}
+                _ => return Ok(false),
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Check if a change to the time lock with the given id is valid, and
+/// record the changes to storage it implies.
+fn is_valid_transition(
+    id: u64,
+    pre: Option<TimeLock>,
+    post: Option<TimeLock>,
+    epoch: Epoch,
+    verifiers: &BTreeSet<Address>,
+    expected: &mut ExpectedChanges,
+) -> bool {
+    match (pre, post) {
+        // A new time lock, whose tokens are debited from the source
+        (None, Some(lock)) => {
+            if !lock.is_pending()
+                || lock.timelock <= epoch
+                || !verifiers.contains(&lock.source)
+            {
+                return false;
+            }
+            expected.created.insert(id);
+            expected
+                .expiry_keys
+                .insert(htlc_keys::get_expiry_key(lock.timelock, id));
+            add_amount(&mut expected.locked, lock.token, lock.amount)
+        }
+        // A pending time lock settled by a tx
+        (Some(pre), Some(post)) if pre.is_pending() => {
+            let terms = TimeLock {
+                status: TimeLockStatus::Pending,
+                ..post.clone()
+            };
+            if terms != pre {
+                return false;
+            }
+            let beneficiary = match &post.status {
+                TimeLockStatus::Claimed { preimage } => {
+                    if epoch >= pre.timelock || !pre.is_valid_preimage(preimage)
+                    {
+                        return false;
+                    }
+                    pre.receiver
+                }
+                TimeLockStatus::Refunded => {
+                    if !verifiers.contains(&pre.receiver) {
+                        return false;
+                    }
+                    pre.source
+                }
+                // Time locks are only released by the protocol
+                TimeLockStatus::Pending | TimeLockStatus::Released => {
+                    return false;
+                }
+            };
+            expected
+                .expiry_keys
+                .insert(htlc_keys::get_expiry_key(pre.timelock, id));
+            add_amount(
+                &mut expected.credits,
+                (pre.token.clone(), beneficiary),
+                pre.amount,
+            ) && add_amount(&mut expected.released, pre.token, pre.amount)
+        }
+        // Time locks are never deleted, nor updated once settled
+        _ => false,
+    }
+}
+
+/// Add `amount` to the entry of `key` in `amounts`. Returns `false` on
+/// overflow.
+fn add_amount<K: Ord>(
+    amounts: &mut BTreeMap<K, Amount>,
+    key: K,
+    amount: Amount,
+) -> bool {
+    let sum = amounts.entry(key).or_default();
+    match sum.checked_add(amount) {
+        Some(new_sum) => {
+            *sum = new_sum;
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_core::ledger::gas::TxGasMeter;
+    use namada_core::ledger::storage_api::{self, StorageWrite};
+    use namada_core::types::hash::Hash;
+    use namada_core::types::transaction::htlc::LockTransfer;
+
+    use super::*;
+    use crate::core::ledger::storage::testing::TestWlStorage;
+    use crate::core::types::address::nam;
+    use crate::core::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+    use crate::ledger::gas::VpGasMeter;
+    use crate::types::storage::TxIndex;
+    use crate::types::transaction::TxType;
+    use crate::vm::wasm::compilation_cache::common::testing::cache as wasm_cache;
+
+    const PREIMAGE: &[u8] = b"secret";
+
+    /// Set up the storage with a pending time lock of 10 NAM, with a
+    /// hashlock expiring at epoch 5.
+    fn setup() -> TestWlStorage {
+        let mut wl_storage = TestWlStorage::default();
+        let source = established_address_1();
+        let amount = Amount::native_whole(100);
+        wl_storage
+            .write(&token::balance_key(&nam(), &source), amount)
+            .expect("Test failed");
+        storage_api::htlc::lock_transfer(
+            &mut wl_storage,
+            LockTransfer {
+                source,
+                receiver: established_address_2(),
+                token: nam(),
+                amount: Amount::native_whole(10),
+                hashlock: Some(Hash::sha256(PREIMAGE)),
+                timelock: Epoch(5),
+            },
+        )
+        .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+        wl_storage
+    }
+
+    /// Run the HTLC VP against the changes in the write log.
+    fn validate(
+        wl_storage: &TestWlStorage,
+        verifiers_from_tx: BTreeSet<Address>,
+    ) -> bool {
+        let (verifiers, keys_changed) = wl_storage
+            .write_log
+            .verifiers_and_changed_keys(&verifiers_from_tx);
+        let tx = Tx::from_type(TxType::Raw);
+        let tx_index = TxIndex::default();
+        let gas_meter = VpGasMeter::new_from_tx_meter(
+            &TxGasMeter::new_from_sub_limit(u64::MAX.into()),
+        );
+        let (vp_wasm_cache, _vp_cache_dir) = wasm_cache();
+        let ctx = Ctx::new(
+            &ADDRESS,
+            &wl_storage.storage,
+            &wl_storage.write_log,
+            &tx,
+            &tx_index,
+            gas_meter,
+            &keys_changed,
+            &verifiers,
+            vp_wasm_cache,
+        );
+        let vp = HtlcVp { ctx };
+        vp.validate_tx(&tx, &keys_changed, &verifiers)
+            .expect("Test failed")
+    }
+
+    /// Test that a new time lock must be backed by a debit of its source.
+    #[test]
+    fn test_lock_transfer() {
+        let mut wl_storage = TestWlStorage::default();
+        let source = established_address_1();
+        wl_storage
+            .write(
+                &token::balance_key(&nam(), &source),
+                Amount::native_whole(100),
+            )
+            .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+
+        storage_api::htlc::lock_transfer(
+            &mut wl_storage,
+            LockTransfer {
+                source: source.clone(),
+                receiver: established_address_2(),
+                token: nam(),
+                amount: Amount::native_whole(10),
+                hashlock: None,
+                timelock: Epoch(5),
+            },
+        )
+        .expect("Test failed");
+        assert!(validate(&wl_storage, BTreeSet::new()));
+
+        // the locked tokens are not held by the HTLC address
+        wl_storage
+            .write(&token::balance_key(&nam(), &ADDRESS), Amount::zero())
+            .expect("Test failed");
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+    }
+
+    /// Test that a time lock may only be claimed with the preimage of its
+    /// hashlock.
+    #[test]
+    fn test_claim_time_lock() {
+        let mut wl_storage = setup();
+        storage_api::htlc::claim_time_lock(&mut wl_storage, 0, PREIMAGE.into())
+            .expect("Test failed");
+        assert!(validate(&wl_storage, BTreeSet::new()));
+
+        let mut wl_storage = setup();
+        let mut lock = storage_api::htlc::read_time_lock(&wl_storage, 0)
+            .expect("Test failed")
+            .expect("Test failed");
+        lock.status = TimeLockStatus::Claimed {
+            preimage: b"guess".to_vec(),
+        };
+        wl_storage
+            .write(&htlc_keys::get_lock_key(0), lock)
+            .expect("Test failed");
+        wl_storage
+            .delete(&htlc_keys::get_expiry_key(Epoch(5), 0))
+            .expect("Test failed");
+        storage_api::token::transfer(
+            &mut wl_storage,
+            &nam(),
+            &ADDRESS,
+            &established_address_2(),
+            Amount::native_whole(10),
+        )
+        .expect("Test failed");
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+    }
+
+    /// Test that refunding a time lock requires the receiver to be a
+    /// verifier.
+    #[test]
+    fn test_refund_time_lock() {
+        let mut wl_storage = setup();
+        storage_api::htlc::refund_time_lock(&mut wl_storage, 0)
+            .expect("Test failed");
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+        assert!(validate(
+            &wl_storage,
+            BTreeSet::from([established_address_2()])
+        ));
+    }
+
+    /// Test that the counter of the time lock ids may not be decremented.
+    #[test]
+    fn test_counter_decrement_rejected() {
+        let mut wl_storage = setup();
+        wl_storage
+            .write(&htlc_keys::get_counter_key(), 0_u64)
+            .expect("Test failed");
+        assert!(!validate(&wl_storage, BTreeSet::new()));
+    }
+
+    /// Test that expired time locks are settled at the start of the epoch
+    /// at which they expire, and that a lock which fails to be settled
+    /// doesn't hold up the others.
+    #[test]
+    fn test_settle_expired_time_locks() {
+        let mut wl_storage = setup();
+        // an expiry entry of a time lock which doesn't exist
+        wl_storage
+            .write(&htlc_keys::get_expiry_key(Epoch(5), 7), 7_u64)
+            .expect("Test failed");
+        let settled = storage_api::htlc::settle_expired_time_locks(
+            &mut wl_storage,
+            Epoch(4),
+        )
+        .expect("Test failed");
+        assert!(settled.is_empty());
+
+        let settled = storage_api::htlc::settle_expired_time_locks(
+            &mut wl_storage,
+            Epoch(5),
+        )
+        .expect("Test failed");
+        assert_eq!(settled, vec![0]);
+        let lock = storage_api::htlc::read_time_lock(&wl_storage, 0)
+            .expect("Test failed")
+            .expect("Test failed");
+        assert_eq!(lock.status, TimeLockStatus::Refunded);
+        let balance = storage_api::token::read_balance(
+            &wl_storage,
+            &nam(),
+            &established_address_1(),
+        )
+        .expect("Test failed");
+        assert_eq!(balance, Amount::native_whole(100));
+    }
+}
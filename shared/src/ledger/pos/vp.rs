@@ -7,8 +7,20 @@ use namada_core::ledger::storage_api::governance;
 pub use namada_proof_of_stake;
 pub use namada_proof_of_stake::parameters::PosParams;
 // use namada_proof_of_stake::validation::validate;
-use namada_proof_of_stake::read_pos_params;
+use namada_proof_of_stake::storage::{
+    is_bridge_liveness_params_key, is_insurance_params_key,
+    is_max_validator_stake_share_key, is_pending_max_validator_slots_key,
+    is_pending_slashing_params_key, is_validator_commission_history_key,
+    is_validator_commission_rate_key,
+    is_validator_max_commission_rate_change_key, is_validator_metadata_key,
+};
 pub use namada_proof_of_stake::types;
+use namada_proof_of_stake::{
+    read_pos_params, read_validator_max_commission_rate_change,
+    validator_commission_history_handle, validator_commission_rate_handle,
+    BridgeLivenessParams, InsuranceParams, SlashingParamsChange,
+    MAX_VALIDATOR_METADATA_LEN,
+};
 use thiserror::Error;
 
 use super::is_params_key;
@@ -21,7 +33,8 @@ use crate::ledger::storage::{self as ledger_storage, StorageHasher};
 use crate::ledger::storage_api::StorageRead;
 use crate::proto::Tx;
 use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::{Key, KeySeg};
+use crate::types::dec::Dec;
+use crate::types::storage::{Epoch, Key, KeySeg};
 use crate::vm::WasmCacheAccess;
 
 #[allow(missing_docs)]
@@ -55,6 +68,187 @@ where
     pub fn new(ctx: Ctx<'a, DB, H, CA>) -> Self {
         Self { ctx }
     }
+
+    /// Check that a validator's commission rate taking effect at the given
+    /// epoch, written at the given `key`, is a valid rate, which differs from
+    /// the rate of the previous epoch by no more than the validator's
+    /// maximum change per epoch.
+    fn is_valid_commission_rate_change(
+        &self,
+        key: &Key,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> Result<bool> {
+        let Some(new_rate) = self.ctx.post().read::<Dec>(key)? else {
+            // The rate of a past epoch was pruned
+            return Ok(true);
+        };
+        if new_rate.is_negative() || new_rate > Dec::one() {
+            tracing::info!(
+                "PoS commission rate {new_rate} of {validator} at epoch \
+                 {epoch} is out of range"
+            );
+            return Ok(false);
+        }
+        let params = read_pos_params(&self.ctx.post())?;
+        let prev_rate = match epoch.checked_sub(Epoch(1)) {
+            Some(prev_epoch) => validator_commission_rate_handle(validator)
+                .get(&self.ctx.post(), prev_epoch, &params)?,
+            None => None,
+        };
+        let Some(prev_rate) = prev_rate else {
+            // A new validator has no previous rate
+            return Ok(true);
+        };
+        let Some(max_change) = read_validator_max_commission_rate_change(
+            &self.ctx.post(),
+            validator,
+        )?
+        else {
+            return Ok(false);
+        };
+        let change = new_rate.abs_diff(&prev_rate);
+        if change > max_change {
+            tracing::info!(
+                "PoS commission rate change of {validator} at epoch {epoch} \
+                 by {change} exceeds the maximum change of {max_change}"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that a rate recorded in the history of a validator's commission
+    /// rate matches the rate that takes effect at the given epoch.
+    fn is_valid_commission_history_change(
+        &self,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> Result<bool> {
+        let Some(recorded) = validator_commission_history_handle(validator)
+            .get(&self.ctx.post(), &epoch)?
+        else {
+            // The history must not be erased
+            return Ok(false);
+        };
+        let params = read_pos_params(&self.ctx.post())?;
+        let rate = validator_commission_rate_handle(validator).get(
+            &self.ctx.post(),
+            epoch,
+            &params,
+        )?;
+        Ok(rate == Some(recorded))
+    }
+
+    /// Check that a change of the slashing parameters, written at the given
+    /// `key`, has valid values with respect to the current parameters.
+    fn is_valid_slashing_params_change(&self, key: &Key) -> Result<bool> {
+        let Some(change) = self.ctx.post().read::<SlashingParamsChange>(key)?
+        else {
+            // The pending change was applied
+            return Ok(true);
+        };
+        let params = read_pos_params(&self.ctx.pre())?;
+        let errors = change.validate(&params.owned);
+        if !errors.is_empty() {
+            tracing::info!(
+                "Invalid PoS slashing parameters change {change:?}: {errors:?}"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that a change of the maximum number of consensus validators,
+    /// written at the given `key`, results in valid PoS parameters.
+    fn is_valid_max_validator_slots_change(&self, key: &Key) -> Result<bool> {
+        let Some(max_validator_slots) = self.ctx.post().read::<u64>(key)?
+        else {
+            // The pending change was applied
+            return Ok(true);
+        };
+        let mut params = read_pos_params(&self.ctx.pre())?.owned;
+        params.max_validator_slots = max_validator_slots;
+        let errors = params.validate();
+        if !errors.is_empty() {
+            tracing::info!(
+                "Invalid maximum number of consensus validators \
+                 {max_validator_slots}: {errors:?}"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the parameters of the slashing insurance pool, written at
+    /// the given `key`, have valid values.
+    fn is_valid_insurance_params_change(&self, key: &Key) -> Result<bool> {
+        let Some(params) = self.ctx.post().read::<InsuranceParams>(key)? else {
+            // The parameters must not be erased
+            return Ok(false);
+        };
+        let errors = params.validate();
+        if !errors.is_empty() {
+            tracing::info!(
+                "Invalid PoS insurance parameters {params:?}: {errors:?}"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the parameters of the jailing of validators for bridge
+    /// liveness failures, written at the given `key`, have valid values.
+    fn is_valid_bridge_liveness_params_change(
+        &self,
+        key: &Key,
+    ) -> Result<bool> {
+        let Some(params) = self.ctx.post().read::<BridgeLivenessParams>(key)?
+        else {
+            // The jailing for bridge liveness failures was disabled
+            return Ok(true);
+        };
+        let errors = params.validate();
+        if !errors.is_empty() {
+            tracing::info!(
+                "Invalid PoS bridge liveness parameters {params:?}: {errors:?}"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the maximum share of the total stake that a single
+    /// validator may hold, written at the given `key`, is a valid fraction.
+    fn is_valid_max_validator_stake_share_change(
+        &self,
+        key: &Key,
+    ) -> Result<bool> {
+        let Some(max_share) = self.ctx.post().read::<Dec>(key)? else {
+            // The cap was removed
+            return Ok(true);
+        };
+        if max_share.is_negative()
+            || max_share.is_zero()
+            || max_share > Dec::one()
+        {
+            tracing::info!(
+                "Invalid maximum validator stake share {max_share}, it must \
+                 be in (0, 1]"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the maximum number of consensus validators is not changed
+    /// directly in the PoS parameters, as the validator sets must be resized
+    /// along with it. It may only be changed via its pending change.
+    fn is_max_validator_slots_unchanged(&self) -> Result<bool> {
+        let pre = read_pos_params(&self.ctx.pre())?;
+        let post = read_pos_params(&self.ctx.post())?;
+        Ok(pre.max_validator_slots == post.max_validator_slots)
+    }
 }
 
 impl<'a, DB, H, CA> NativeVp for PosVP<'a, DB, H, CA>
@@ -82,7 +276,13 @@ where
         tracing::debug!("\nValidating PoS Tx\n");
 
         for key in keys_changed {
-            if is_params_key(key) {
+            if is_params_key(key)
+                || is_pending_slashing_params_key(key)
+                || is_pending_max_validator_slots_key(key)
+                || is_insurance_params_key(key)
+                || is_max_validator_stake_share_key(key)
+                || is_bridge_liveness_params_key(key)
+            {
                 let data = if let Some(data) = tx_data.data() {
                     data
                 } else {
@@ -93,6 +293,77 @@ where
                 {
                     return Ok(false);
                 }
+                if is_pending_slashing_params_key(key)
+                    && !self.is_valid_slashing_params_change(key)?
+                {
+                    return Ok(false);
+                }
+                if is_pending_max_validator_slots_key(key)
+                    && !self.is_valid_max_validator_slots_change(key)?
+                {
+                    return Ok(false);
+                }
+                if is_insurance_params_key(key)
+                    && !self.is_valid_insurance_params_change(key)?
+                {
+                    return Ok(false);
+                }
+                if is_max_validator_stake_share_key(key)
+                    && !self.is_valid_max_validator_stake_share_change(key)?
+                {
+                    return Ok(false);
+                }
+                if is_bridge_liveness_params_key(key)
+                    && !self.is_valid_bridge_liveness_params_change(key)?
+                {
+                    return Ok(false);
+                }
+                if is_params_key(key)
+                    && !self.is_max_validator_slots_unchanged()?
+                {
+                    return Ok(false);
+                }
+            } else if let Some((validator, epoch)) =
+                is_validator_commission_rate_key(key)
+            {
+                if !self
+                    .is_valid_commission_rate_change(key, validator, epoch)?
+                {
+                    return Ok(false);
+                }
+            } else if let Some((validator, epoch)) =
+                is_validator_commission_history_key(key)
+            {
+                if !self.is_valid_commission_history_change(validator, epoch)? {
+                    return Ok(false);
+                }
+            } else if let Some(validator) =
+                is_validator_max_commission_rate_change_key(key)
+            {
+                // The max commission rate change cannot be modified once set
+                if read_validator_max_commission_rate_change(
+                    &self.ctx.pre(),
+                    validator,
+                )?
+                .is_some()
+                {
+                    tracing::info!(
+                        "PoS max commission rate change of {validator} cannot \
+                         be modified"
+                    );
+                    return Ok(false);
+                }
+            } else if is_validator_metadata_key(key).is_some() {
+                let metadata: Option<String> = self.ctx.post().read(key)?;
+                if metadata.map(|data| data.len()).unwrap_or_default()
+                    > MAX_VALIDATOR_METADATA_LEN
+                {
+                    tracing::info!(
+                        "PoS validator metadata {key} exceeds the maximum \
+                         length of {MAX_VALIDATOR_METADATA_LEN} bytes"
+                    );
+                    return Ok(false);
+                }
             } else if key.segments.get(0) == Some(&addr.to_db_key()) {
                 // Unknown changes to this address space are disallowed
                 // tracing::info!("PoS unrecognized key change {} rejected",
@@ -1,6 +1,7 @@
 //! The ledger's protocol
 use std::collections::BTreeSet;
 
+use borsh::BorshDeserialize;
 use borsh_ext::BorshSerializeExt;
 use eyre::{eyre, WrapErr};
 use masp_primitives::transaction::Transaction;
@@ -21,6 +22,7 @@ use crate::ledger::governance::GovernanceVp;
 use crate::ledger::native_vp::ethereum_bridge::bridge_pool_vp::BridgePoolVp;
 use crate::ledger::native_vp::ethereum_bridge::nut::NonUsableTokens;
 use crate::ledger::native_vp::ethereum_bridge::vp::EthBridge;
+use crate::ledger::native_vp::htlc::HtlcVp;
 use crate::ledger::native_vp::ibc::Ibc;
 use crate::ledger::native_vp::masp::MaspVp;
 use crate::ledger::native_vp::multitoken::MultitokenVp;
@@ -28,7 +30,7 @@ use crate::ledger::native_vp::parameters::{self, ParametersVp};
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pgf::PgfVp;
 use crate::ledger::pos::{self, PosVP};
-use crate::ledger::storage::write_log::WriteLog;
+use crate::ledger::storage::write_log::{StorageModification, WriteLog};
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, WlStorage, DB};
 use crate::ledger::storage_api;
 use crate::proto::{self, Tx};
@@ -85,6 +87,8 @@ pub enum Error {
     GovernanceNativeVpError(crate::ledger::governance::Error),
     #[error("Pgf native VP error: {0}")]
     PgfNativeVpError(crate::ledger::pgf::Error),
+    #[error("HTLC native VP error: {0}")]
+    HtlcNativeVpError(native_vp::htlc::Error),
     #[error("Ethereum bridge native VP error: {0:?}")]
     EthBridgeNativeVpError(native_vp::ethereum_bridge::vp::Error),
     #[error("Ethereum bridge pool native VP error: {0:?}")]
@@ -95,6 +99,13 @@ pub enum Error {
     MaspNativeVpError(native_vp::masp::Error),
     #[error("Access to an internal address {0:?} is forbidden")]
     AccessForbidden(InternalAddress),
+    #[error("The tx code {0} is not allowed to act on the storage of {1}")]
+    TxCodeNotAllowed(Hash, Address),
+    #[error(
+        "The tx code {0} cannot restrict the tx codes acting on the storage \
+         of {1} without allowing itself"
+    )]
+    TxCodeLockout(Hash, Address),
 }
 
 /// Shell parameters for running wasm transactions.
@@ -158,7 +169,7 @@ where
     CA: 'static + WasmCacheAccess + Sync,
 {
     match tx.header().tx_type {
-        TxType::Raw => Err(Error::TxTypeError),
+        TxType::Raw | TxType::View => Err(Error::TxTypeError),
         TxType::Decrypted(DecryptedTx::Decrypted) => apply_wasm_tx(
             tx,
             &tx_index,
@@ -546,6 +557,55 @@ pub fn apply_wasm_tx<'a, D, H, CA, WLS>(
     tx_index: &TxIndex,
     shell_params: ShellParams<'a, CA, WLS>,
 ) -> Result<TxResult>
+where
+    CA: 'static + WasmCacheAccess + Sync,
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    WLS: WriteLogAndStorage<D = D, H = H>,
+{
+    run_wasm_tx(tx, tx_index, shell_params, wasm::run::tx)
+}
+
+/// Apply the code of a governance proposal going via the wasm environment.
+/// Unlike [`apply_wasm_tx`], the memory of the code is capped at
+/// [`wasm::memory::PROPOSAL_MEMORY_MAX_PAGES`].
+pub fn apply_proposal_code<'a, D, H, CA, WLS>(
+    tx: Tx,
+    shell_params: ShellParams<'a, CA, WLS>,
+) -> Result<TxResult>
+where
+    CA: 'static + WasmCacheAccess + Sync,
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    WLS: WriteLogAndStorage<D = D, H = H>,
+{
+    run_wasm_tx(
+        tx,
+        &TxIndex::default(),
+        shell_params,
+        wasm::run::proposal_tx,
+    )
+}
+
+/// A runner of the wasm code of a transaction, from [`wasm::run`].
+type TxRunner<D, H, CA> = fn(
+    &Storage<D, H>,
+    &mut WriteLog,
+    &mut TxGasMeter,
+    &TxIndex,
+    &Tx,
+    &mut VpCache<CA>,
+    &mut TxCache<CA>,
+) -> wasm::run::Result<BTreeSet<Address>>;
+
+/// Run the code of a tx with the given `runner`, then check the triggered
+/// validity predicates.
+fn run_wasm_tx<'a, D, H, CA, WLS>(
+    tx: Tx,
+    tx_index: &TxIndex,
+    shell_params: ShellParams<'a, CA, WLS>,
+    runner: TxRunner<D, H, CA>,
+) -> Result<TxResult>
 where
     CA: 'static + WasmCacheAccess + Sync,
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -585,6 +645,7 @@ where
         write_log,
         vp_wasm_cache,
         tx_wasm_cache,
+        runner,
     )?;
 
     let vps_result = check_vps(CheckVps {
@@ -674,6 +735,22 @@ where
             )
             .map_err(Error::ProtocolTxError)
         }
+        EthereumTxData::AttestationVext(_) => {
+            // NB: attestations are aggregated by the hooks of their
+            // respective lanes, once all the txs of a block have been
+            // applied
+            Ok(TxResult::default())
+        }
+        EthereumTxData::MisbehaviorEvidence(_) => {
+            // NB: misbehaving validators are slashed by the shell,
+            // once all the txs of a block have been applied
+            Ok(TxResult::default())
+        }
+        EthereumTxData::ValidatorHeartbeat(_) => {
+            // NB: heartbeats are recorded by the shell, once all
+            // the txs of a block have been applied
+            Ok(TxResult::default())
+        }
         EthereumTxData::EthereumEvents(_)
         | EthereumTxData::BridgePool(_)
         | EthereumTxData::ValidatorSetUpdate(_) => {
@@ -697,13 +774,14 @@ fn execute_tx<D, H, CA>(
     write_log: &mut WriteLog,
     vp_wasm_cache: &mut VpCache<CA>,
     tx_wasm_cache: &mut TxCache<CA>,
+    runner: TxRunner<D, H, CA>,
 ) -> Result<BTreeSet<Address>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
-    wasm::run::tx(
+    runner(
         storage,
         write_log,
         tx_gas_meter,
@@ -755,6 +833,15 @@ where
     let (verifiers, keys_changed) =
         write_log.verifiers_and_changed_keys(verifiers_from_tx);
 
+    check_allowed_tx_codes(
+        tx,
+        storage,
+        write_log,
+        tx_gas_meter,
+        &verifiers,
+        &keys_changed,
+    )?;
+
     let vps_result = execute_vps(
         verifiers,
         keys_changed,
@@ -774,6 +861,137 @@ where
     Ok(vps_result)
 }
 
+/// Check that the code of the tx is allowed to act on the storage of each
+/// of the given verifiers, which may restrict the tx codes acting on their
+/// storage, on top of their validity predicates.
+///
+/// A tx restricting the tx codes acting on the storage of an account must
+/// itself remain allowed, such that the account is never locked out of
+/// lifting the restriction.
+fn check_allowed_tx_codes<D, H>(
+    tx: &Tx,
+    storage: &Storage<D, H>,
+    write_log: &WriteLog,
+    tx_gas_meter: &mut TxGasMeter,
+    verifiers: &BTreeSet<Address>,
+    keys_changed: &BTreeSet<Key>,
+) -> Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    // only look up the code hash of the tx if some verifier restricts the
+    // tx codes acting on its storage
+    let mut tx_code_hash = None;
+    let mut get_tx_code_hash = || -> Result<Hash> {
+        if let Some(code_hash) = tx_code_hash {
+            return Ok(code_hash);
+        }
+        let code_hash = tx
+            .get_section(tx.code_sechash())
+            .and_then(|section| section.code_sec())
+            .map(|code| code.code.hash())
+            .ok_or_else(|| {
+                Error::MissingSection(tx.code_sechash().to_string())
+            })?;
+        Ok(*tx_code_hash.insert(code_hash))
+    };
+    for owner in verifiers {
+        if let Address::Internal(_) = owner {
+            continue;
+        }
+        let Some(allowed) =
+            read_allowed_tx_codes(storage, write_log, tx_gas_meter, owner)?
+        else {
+            continue;
+        };
+        let code_hash = get_tx_code_hash()?;
+        if !allowed.contains(&code_hash) {
+            tracing::info!(
+                %owner,
+                %code_hash,
+                "Rejecting a tx code not allowed to act on the storage of an \
+                 account"
+            );
+            return Err(Error::TxCodeNotAllowed(code_hash, owner.clone()));
+        }
+    }
+    for key in keys_changed {
+        let Some(owner) = crate::types::key::is_allowed_tx_codes_key(key)
+        else {
+            continue;
+        };
+        let (log_val, gas) = write_log.read(key);
+        tx_gas_meter
+            .consume(gas)
+            .map_err(|err| Error::GasError(err.to_string()))?;
+        let Some(StorageModification::Write { value }) = log_val else {
+            continue;
+        };
+        let allowed = decode_allowed_tx_codes(value)?;
+        let code_hash = get_tx_code_hash()?;
+        if !allowed.contains(&code_hash) {
+            tracing::info!(
+                %owner,
+                %code_hash,
+                "Rejecting a tx code restricting the tx codes acting on the \
+                 storage of an account, such that it is no longer allowed \
+                 itself"
+            );
+            return Err(Error::TxCodeLockout(code_hash, owner.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Read the hashes of the tx codes allowed to act on the storage of an
+/// account, prior to the execution of the current tx.
+fn read_allowed_tx_codes<D, H>(
+    storage: &Storage<D, H>,
+    write_log: &WriteLog,
+    tx_gas_meter: &mut TxGasMeter,
+    owner: &Address,
+) -> Result<Option<BTreeSet<Hash>>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let key = crate::types::key::allowed_tx_codes_key(owner);
+    let (log_val, gas) = write_log.read_pre(&key);
+    tx_gas_meter
+        .consume(gas)
+        .map_err(|err| Error::GasError(err.to_string()))?;
+    let bytes = match log_val {
+        Some(StorageModification::Write { value }) => Some(value.clone()),
+        Some(
+            StorageModification::Delete
+            | StorageModification::InitAccount { .. }
+            | StorageModification::Temp { .. },
+        ) => None,
+        None => {
+            let (value, gas) =
+                storage.read(&key).map_err(Error::StorageError)?;
+            tx_gas_meter
+                .consume(gas)
+                .map_err(|err| Error::GasError(err.to_string()))?;
+            value
+        }
+    };
+    bytes
+        .map(|bytes| decode_allowed_tx_codes(&bytes))
+        .transpose()
+}
+
+/// Decode the hashes of the tx codes allowed to act on the storage of an
+/// account.
+fn decode_allowed_tx_codes(bytes: &[u8]) -> Result<BTreeSet<Hash>> {
+    BTreeSet::try_from_slice(bytes).map_err(|err| {
+        Error::StorageError(crate::ledger::storage::Error::BorshCodingError(
+            err,
+        ))
+    })
+}
+
 /// Execute verifiers' validity predicates
 #[allow(clippy::too_many_arguments)]
 fn execute_vps<D, H, CA>(
@@ -964,6 +1182,17 @@ where
                                 gas_meter = pgf_vp.ctx.gas_meter.into_inner();
                                 (result, pgf_vp.ctx.sentinel.into_inner())
                             }
+                            InternalAddress::Htlc => {
+                                let htlc = HtlcVp { ctx };
+                                let result = htlc
+                                    .validate_tx(tx, &keys_changed, &verifiers)
+                                    .map_err(Error::HtlcNativeVpError);
+                                // Take the gas meter and the sentinel
+                                // back
+                                // out of the context
+                                gas_meter = htlc.ctx.gas_meter.into_inner();
+                                (result, htlc.ctx.sentinel.into_inner())
+                            }
                             InternalAddress::Nut(_) => {
                                 let non_usable_tokens = NonUsableTokens { ctx };
                                 let result = non_usable_tokens
@@ -1097,10 +1326,12 @@ fn merge_vp_results(
 mod tests {
     use std::collections::HashMap;
 
+    use assert_matches::assert_matches;
     use borsh::BorshDeserialize;
     use eyre::Result;
+    use namada_core::ledger::storage::testing::TestWlStorage;
     use namada_core::ledger::storage_api::StorageRead;
-    use namada_core::proto::{SignableEthMessage, Signed};
+    use namada_core::proto::{Code, SignableEthMessage, Signed};
     use namada_core::types::ethereum_events::testing::DAI_ERC20_ETH_ADDRESS;
     use namada_core::types::ethereum_events::{
         EthereumEvent, TransferToNamada,
@@ -1252,4 +1483,95 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    /// Test that txs whose code is not allowed to act on the storage of an
+    /// account are rejected, before the VP of the account gets triggered.
+    fn test_check_allowed_tx_codes() {
+        let mut wl_storage = TestWlStorage::default();
+        let owner = address::testing::established_address_1();
+        let verifiers = BTreeSet::from([owner.clone()]);
+        let tx_code = b"tx_transfer".to_vec();
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_code(Code::new(tx_code.clone(), None));
+        let allowed_tx_codes_key =
+            crate::types::key::allowed_tx_codes_key(&owner);
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(u64::MAX.into());
+        let mut check = |wl_storage: &TestWlStorage| {
+            let keys_changed = wl_storage
+                .write_log
+                .verifiers_and_changed_keys(&BTreeSet::new())
+                .1;
+            check_allowed_tx_codes(
+                &tx,
+                &wl_storage.storage,
+                &wl_storage.write_log,
+                &mut gas_meter,
+                &verifiers,
+                &keys_changed,
+            )
+        };
+
+        // any tx code may act on the storage of an unrestricted account
+        assert!(check(&wl_storage).is_ok());
+
+        storage_api::account::set_allowed_tx_codes(
+            &mut wl_storage,
+            &owner,
+            BTreeSet::from([Hash::sha256(b"tx_update_account")]),
+        )
+        .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+        assert_matches!(
+            check(&wl_storage),
+            Err(Error::TxCodeNotAllowed(code_hash, address))
+                if code_hash == Hash::sha256(&tx_code) && address == owner
+        );
+
+        storage_api::account::set_allowed_tx_codes(
+            &mut wl_storage,
+            &owner,
+            BTreeSet::from([Hash::sha256(&tx_code)]),
+        )
+        .expect("Test failed");
+        wl_storage.commit_block().expect("Test failed");
+        assert!(check(&wl_storage).is_ok());
+
+        // the tx cannot restrict the tx codes of the account such that it
+        // is no longer allowed itself
+        wl_storage
+            .write_log
+            .write(
+                &allowed_tx_codes_key,
+                BTreeSet::from([Hash::sha256(b"tx_update_account")])
+                    .serialize_to_vec(),
+            )
+            .expect("Test failed");
+        assert_matches!(
+            check(&wl_storage),
+            Err(Error::TxCodeLockout(code_hash, address))
+                if code_hash == Hash::sha256(&tx_code) && address == owner
+        );
+        wl_storage.write_log.drop_tx();
+
+        // it may keep itself allowed, or lift the restriction
+        wl_storage
+            .write_log
+            .write(
+                &allowed_tx_codes_key,
+                BTreeSet::from([
+                    Hash::sha256(&tx_code),
+                    Hash::sha256(b"tx_update_account"),
+                ])
+                .serialize_to_vec(),
+            )
+            .expect("Test failed");
+        assert!(check(&wl_storage).is_ok());
+        wl_storage.write_log.drop_tx();
+        wl_storage
+            .write_log
+            .delete(&allowed_tx_codes_key)
+            .expect("Test failed");
+        assert!(check(&wl_storage).is_ok());
+    }
 }
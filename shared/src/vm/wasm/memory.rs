@@ -49,6 +49,10 @@ pub const TX_MEMORY_MAX_PAGES: u32 = 200; // 12.8 MiB
 pub const VP_MEMORY_INIT_PAGES: u32 = 100; // 6.4 MiB
 /// Mamixmum pages in VP memory
 pub const VP_MEMORY_MAX_PAGES: u32 = 200; // 12.8 MiB
+/// Initial pages in governance proposal code memory
+pub const PROPOSAL_MEMORY_INIT_PAGES: u32 = 50; // 3.2 MiB
+/// Maximum pages in governance proposal code memory
+pub const PROPOSAL_MEMORY_MAX_PAGES: u32 = 100; // 6.4 MiB
 
 /// Prepare memory for instantiating a transaction module
 pub fn prepare_tx_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
@@ -60,6 +64,18 @@ pub fn prepare_tx_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
     Memory::new(store, mem_type).map_err(Error::InitMemoryError)
 }
 
+/// Prepare memory for instantiating a governance proposal code module
+pub fn prepare_proposal_memory(
+    store: &wasmer::Store,
+) -> Result<wasmer::Memory> {
+    let mem_type = wasmer::MemoryType::new(
+        PROPOSAL_MEMORY_INIT_PAGES,
+        Some(PROPOSAL_MEMORY_MAX_PAGES),
+        false,
+    );
+    Memory::new(store, mem_type).map_err(Error::InitMemoryError)
+}
+
 /// Prepare memory for instantiating a validity predicate module
 pub fn prepare_vp_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
     let mem_type = wasmer::MemoryType::new(
@@ -315,6 +331,14 @@ pub fn tx_limit() -> Limit<BaseTunables> {
     Limit { limit, base }
 }
 
+/// A [`Limit`] with memory limit setup for governance proposal code WASM
+/// execution.
+pub fn proposal_limit() -> Limit<BaseTunables> {
+    let base = BaseTunables::for_target(&Target::default());
+    let limit = Pages(PROPOSAL_MEMORY_MAX_PAGES);
+    Limit { limit, base }
+}
+
 impl<T: Tunables> Limit<T> {
     /// Takes an input memory type as requested by the guest and sets
     /// a maximum if missing. The resulting memory type is final if
@@ -152,7 +152,100 @@ where
         storage,
         gas_meter,
     )?;
+    let initial_memory =
+        memory::prepare_tx_memory(&store).map_err(Error::MemoryError)?;
 
+    run_tx_module(
+        module,
+        store,
+        initial_memory,
+        storage,
+        write_log,
+        gas_meter,
+        tx_index,
+        tx,
+        vp_wasm_cache,
+        tx_wasm_cache,
+    )
+}
+
+/// Execute the code of a governance proposal, as a transaction. The code is
+/// compiled outside of the wasm cache, such that its memory is capped at
+/// [`memory::PROPOSAL_MEMORY_MAX_PAGES`] rather than at the limit of txs.
+/// Returns the set verifiers addresses requested by the proposal code.
+#[allow(clippy::too_many_arguments)]
+pub fn proposal_tx<DB, H, CA>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    gas_meter: &mut TxGasMeter,
+    tx_index: &TxIndex,
+    tx: &Tx,
+    vp_wasm_cache: &mut VpCache<CA>,
+    tx_wasm_cache: &mut TxCache<CA>,
+) -> Result<BTreeSet<Address>>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    let tx_code = tx
+        .get_section(tx.code_sechash())
+        .and_then(|x| Section::code_sec(x.as_ref()))
+        .ok_or(Error::MissingSection(tx.code_sechash().to_string()))?;
+    let Commitment::Id(code) = &tx_code.code else {
+        return Err(Error::LoadWasmCode(
+            "The code of a proposal must be included in the tx".to_string(),
+        ));
+    };
+
+    let code_len = code.len() as u64;
+    gas_meter
+        .add_wasm_validation_gas(code_len)
+        .map_err(|e| Error::GasError(e.to_string()))?;
+    validate_untrusted_wasm(code).map_err(Error::ValidationError)?;
+    gas_meter
+        .add_compiling_gas(code_len)
+        .map_err(|e| Error::GasError(e.to_string()))?;
+    let code = prepare_wasm_code(code)?;
+    let store = untrusted_wasm_store(memory::proposal_limit());
+    let module = Module::new(&store, code).map_err(Error::CompileError)?;
+    let initial_memory =
+        memory::prepare_proposal_memory(&store).map_err(Error::MemoryError)?;
+
+    run_tx_module(
+        module,
+        store,
+        initial_memory,
+        storage,
+        write_log,
+        gas_meter,
+        tx_index,
+        tx,
+        vp_wasm_cache,
+        tx_wasm_cache,
+    )
+}
+
+/// Instantiate the given compiled tx module with its `initial_memory`, and
+/// run it. Returns the set verifiers addresses requested by the transaction.
+#[allow(clippy::too_many_arguments)]
+fn run_tx_module<DB, H, CA>(
+    module: Module,
+    store: Store,
+    initial_memory: wasmer::Memory,
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    gas_meter: &mut TxGasMeter,
+    tx_index: &TxIndex,
+    tx: &Tx,
+    vp_wasm_cache: &mut VpCache<CA>,
+    tx_wasm_cache: &mut TxCache<CA>,
+) -> Result<BTreeSet<Address>>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
     let mut iterators: PrefixIterators<'_, DB> = PrefixIterators::default();
     let mut verifiers = BTreeSet::new();
     let mut result_buffer: Option<Vec<u8>> = None;
@@ -173,8 +266,6 @@ where
         tx_wasm_cache,
     );
 
-    let initial_memory =
-        memory::prepare_tx_memory(&store).map_err(Error::MemoryError)?;
     let imports = tx_imports(&store, initial_memory, env);
 
     // Instantiate the wasm module
@@ -757,6 +848,77 @@ mod tests {
         assert_stack_overflow(&error);
     }
 
+    /// Test that the code of a governance proposal is executed with a lower
+    /// memory limit than txs, and that it must be included in the tx.
+    #[test]
+    fn test_proposal_memory_limiter_in_guest() {
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let mut gas_meter = TxGasMeter::new_from_sub_limit(TX_GAS_LIMIT.into());
+        let tx_index = TxIndex::default();
+
+        // This code will allocate memory of the given size
+        let tx_code = TestWasms::TxMemoryLimit.read_bytes();
+
+        // Assuming 100 pages, 6.4 MiB limit
+        assert_eq!(memory::PROPOSAL_MEMORY_MAX_PAGES, 100);
+
+        // Allocating `2^22` (4 MiB) should be below the memory limit and
+        // shouldn't fail
+        let tx_data = 2_usize.pow(22).serialize_to_vec();
+        let (mut vp_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let (mut tx_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::new(tx_code.clone(), None));
+        outer_tx.set_data(Data::new(tx_data));
+        let result = proposal_tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+        );
+        assert!(result.is_ok(), "Expected success, got {:?}", result);
+
+        // Allocating `2^23` (8 MiB) is allowed for txs, but should be above
+        // the memory limit of proposals and should fail
+        let tx_data = 2_usize.pow(23).serialize_to_vec();
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::new(tx_code.clone(), None));
+        outer_tx.set_data(Data::new(tx_data));
+        let error = proposal_tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+        )
+        .expect_err("Expected to run out of memory");
+        assert_stack_overflow(&error);
+
+        // The code of a proposal cannot be referred to by its hash
+        let mut outer_tx = Tx::from_type(TxType::Raw);
+        outer_tx.set_code(Code::from_hash(Hash::sha256(&tx_code), None));
+        outer_tx.set_data(Data::new(0_usize.serialize_to_vec()));
+        let error = proposal_tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            &tx_index,
+            &outer_tx,
+            &mut vp_cache,
+            &mut tx_cache,
+        )
+        .expect_err("Expected the code to be missing");
+        assert!(matches!(error, Error::LoadWasmCode(_)));
+    }
+
     /// Test that when a validity predicate wasm goes over the memory limit
     /// inside the wasm execution when calling `eval` host function, the `eval`
     /// fails and hence returns `false`.
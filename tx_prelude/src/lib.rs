@@ -23,7 +23,7 @@ pub use namada_core::ledger::governance::storage as gov_storage;
 pub use namada_core::ledger::parameters::storage as parameters_storage;
 pub use namada_core::ledger::storage::types::encode;
 pub use namada_core::ledger::storage_api::{
-    self, governance, iter_prefix, iter_prefix_bytes, Error, OptionExt,
+    self, governance, htlc, iter_prefix, iter_prefix_bytes, Error, OptionExt,
     ResultExt, StorageRead, StorageWrite,
 };
 pub use namada_core::ledger::tx_env::TxEnv;
@@ -8,9 +8,11 @@ pub use namada_proof_of_stake::parameters::PosParams;
 use namada_proof_of_stake::types::ValidatorMetaData;
 use namada_proof_of_stake::{
     become_validator, bond_tokens, change_consensus_key,
+    change_eth_bridge_keys, change_protocol_key,
     change_validator_commission_rate, change_validator_metadata,
     claim_reward_tokens, deactivate_validator, reactivate_validator,
-    read_pos_params, redelegate_tokens, unbond_tokens, unjail_validator,
+    read_pos_params, redelegate_tokens, schedule_auto_withdrawal,
+    set_auto_compound, set_slashing_insurance, unbond_tokens, unjail_validator,
     withdraw_tokens,
 };
 pub use namada_proof_of_stake::{parameters, types, ResultSlashing};
@@ -56,6 +58,45 @@ impl Ctx {
         withdraw_tokens(self, source, validator, current_epoch)
     }
 
+    /// Schedule the tokens unbonded in the current epoch from a self-bond
+    /// or a delegation to be automatically withdrawn once they become
+    /// withdrawable. Returns the epoch at which they are withdrawn.
+    pub fn schedule_auto_withdrawal(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+    ) -> EnvResult<Epoch> {
+        let current_epoch = self.get_block_epoch()?;
+        schedule_auto_withdrawal(self, source, validator, current_epoch)
+    }
+
+    /// Enable or disable the automatic compounding of the rewards of a
+    /// self-bond to a validator when `source` is `None` or equal to the
+    /// `validator` address, or of a delegation from the `source` to the
+    /// `validator`.
+    pub fn set_auto_compound(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        enabled: bool,
+    ) -> TxResult {
+        set_auto_compound(self, source, validator, enabled)
+    }
+
+    /// Opt a self-bond to a validator when `source` is `None` or equal to the
+    /// `validator` address, or a delegation from the `source` to the
+    /// `validator` into or out of the slashing insurance pool. A bond opted
+    /// in is only insured after the slash processing epoch offset.
+    pub fn set_slashing_insurance(
+        &mut self,
+        source: Option<&Address>,
+        validator: &Address,
+        enabled: bool,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        set_slashing_insurance(self, source, validator, enabled, current_epoch)
+    }
+
     /// Change validator consensus key.
     pub fn change_validator_consensus_key(
         &mut self,
@@ -66,6 +107,35 @@ impl Ctx {
         change_consensus_key(self, validator, consensus_key, current_epoch)
     }
 
+    /// Change validator protocol key.
+    pub fn change_validator_protocol_key(
+        &mut self,
+        validator: &Address,
+        protocol_key: &common::PublicKey,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        change_protocol_key(self, validator, protocol_key, current_epoch)
+    }
+
+    /// Change validator Ethereum bridge hot and/or cold keys.
+    pub fn change_validator_eth_bridge_keys(
+        &mut self,
+        validator: &Address,
+        eth_hot_key: Option<key::secp256k1::PublicKey>,
+        eth_cold_key: Option<key::secp256k1::PublicKey>,
+    ) -> TxResult {
+        let current_epoch = self.get_block_epoch()?;
+        let eth_hot_key = eth_hot_key.map(key::common::PublicKey::Secp256k1);
+        let eth_cold_key = eth_cold_key.map(key::common::PublicKey::Secp256k1);
+        change_eth_bridge_keys(
+            self,
+            validator,
+            eth_hot_key.as_ref(),
+            eth_cold_key.as_ref(),
+            current_epoch,
+        )
+    }
+
     /// Change validator commission rate.
     pub fn change_validator_commission_rate(
         &mut self,
@@ -127,6 +197,8 @@ impl Ctx {
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
         }: BecomeValidator,
     ) -> EnvResult<Address> {
         let current_epoch = self.get_block_epoch()?;
@@ -151,6 +223,8 @@ impl Ctx {
                     description,
                     website,
                     discord_handle,
+                    moniker,
+                    security_contact,
                 },
                 offset_opt: None,
             },
@@ -180,6 +254,8 @@ impl Ctx {
         description: Option<String>,
         website: Option<String>,
         discord_handle: Option<String>,
+        moniker: Option<String>,
+        security_contact: Option<String>,
         commission_rate: Option<Dec>,
     ) -> TxResult {
         let current_epoch = self.get_block_epoch()?;
@@ -190,6 +266,8 @@ impl Ctx {
             description,
             website,
             discord_handle,
+            moniker,
+            security_contact,
             commission_rate,
             current_epoch,
         )
@@ -1,11 +1,19 @@
+#[cfg(feature = "tx_auto_compound")]
+pub mod tx_auto_compound;
 #[cfg(feature = "tx_become_validator")]
 pub mod tx_become_validator;
 #[cfg(feature = "tx_bond")]
 pub mod tx_bond;
 #[cfg(feature = "tx_bridge_pool")]
 pub mod tx_bridge_pool;
+#[cfg(feature = "tx_bridge_pool_cancel")]
+pub mod tx_bridge_pool_cancel;
 #[cfg(feature = "tx_change_consensus_key")]
 pub mod tx_change_consensus_key;
+#[cfg(feature = "tx_change_eth_bridge_keys")]
+pub mod tx_change_eth_bridge_keys;
+#[cfg(feature = "tx_change_protocol_key")]
+pub mod tx_change_protocol_key;
 #[cfg(feature = "tx_change_validator_commission")]
 pub mod tx_change_validator_commission;
 #[cfg(feature = "tx_change_validator_metadata")]
@@ -14,6 +22,12 @@ pub mod tx_change_validator_metadata;
 pub mod tx_claim_rewards;
 #[cfg(feature = "tx_deactivate_validator")]
 pub mod tx_deactivate_validator;
+#[cfg(feature = "tx_htlc_claim")]
+pub mod tx_htlc_claim;
+#[cfg(feature = "tx_htlc_lock")]
+pub mod tx_htlc_lock;
+#[cfg(feature = "tx_htlc_refund")]
+pub mod tx_htlc_refund;
 #[cfg(feature = "tx_ibc")]
 pub mod tx_ibc;
 #[cfg(feature = "tx_init_account")]
@@ -28,6 +42,8 @@ pub mod tx_redelegate;
 pub mod tx_resign_steward;
 #[cfg(feature = "tx_reveal_pk")]
 pub mod tx_reveal_pk;
+#[cfg(feature = "tx_slashing_insurance")]
+pub mod tx_slashing_insurance;
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer;
 #[cfg(feature = "tx_unbond")]
@@ -42,6 +58,8 @@ pub mod tx_update_steward_commission;
 pub mod tx_vote_proposal;
 #[cfg(feature = "tx_withdraw")]
 pub mod tx_withdraw;
+#[cfg(feature = "tx_withdraw_proposal")]
+pub mod tx_withdraw_proposal;
 
 #[cfg(feature = "vp_implicit")]
 pub mod vp_implicit;
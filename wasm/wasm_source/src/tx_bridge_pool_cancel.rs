@@ -0,0 +1,32 @@
+//! A tx for the sender of a transfer in the Ethereum bridge pool to cancel
+//! it. The escrowed tokens are refunded by the protocol, at the start of the
+//! next epoch at the latest.
+
+use eth_bridge::storage::bridge_pool;
+use eth_bridge_pool::{CancelTransfer, PendingTransfer};
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1038546)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, signed: Tx) -> TxResult {
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let CancelTransfer { transfer_hash } =
+        CancelTransfer::try_from_slice(&data[..])
+            .wrap_err("failed to decode CancelTransfer")?;
+
+    let transfer: PendingTransfer = ctx
+        .read(&bridge_pool::get_key_from_hash(&transfer_hash))?
+        .ok_or_err_msg("The transfer is not in the Bridge pool")?;
+    // The cancellation must be authorized by the sender
+    ctx.insert_verifier(&transfer.transfer.sender)?;
+    let current_epoch = ctx.get_block_epoch()?;
+    ctx.write(
+        &bridge_pool::get_cancellation_key(&transfer_hash),
+        current_epoch,
+    )
+    .wrap_err("Could not cancel the transfer")?;
+    log_string("Cancelled the transfer in the Bridge pool.");
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! A tx for a validator to change their Ethereum bridge hot and/or cold keys.
+
+use namada_tx_prelude::transaction::pos::EthBridgeKeysChange;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 220000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let EthBridgeKeysChange {
+        validator,
+        eth_hot_key,
+        eth_cold_key,
+    } = EthBridgeKeysChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode EthBridgeKeysChange")?;
+
+    // Check that the tx has been signed with all the new keys
+    let new_pks = eth_hot_key
+        .iter()
+        .chain(eth_cold_key.iter())
+        .cloned()
+        .map(key::common::PublicKey::Secp256k1)
+        .collect();
+    if !matches!(verify_signatures_of_pks(ctx, &signed, new_pks), Ok(true)) {
+        debug_log!("Eth bridge keys ownership signature verification failed");
+        panic!()
+    }
+
+    ctx.change_validator_eth_bridge_keys(&validator, eth_hot_key, eth_cold_key)
+}
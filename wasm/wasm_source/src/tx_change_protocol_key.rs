@@ -0,0 +1,26 @@
+//! A tx for a validator to change their protocol key.
+
+use namada_tx_prelude::transaction::pos::ProtocolKeyChange;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 220000)] // TODO: need to benchmark this gas
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let ProtocolKeyChange {
+        validator,
+        protocol_key,
+    } = ProtocolKeyChange::try_from_slice(&data[..])
+        .wrap_err("failed to decode ProtocolKeyChange")?;
+
+    // Check that the tx has been signed with the new protocol key
+    if !matches!(
+        verify_signatures_of_pks(ctx, &signed, vec![protocol_key.clone()]),
+        Ok(true)
+    ) {
+        debug_log!("Protocol key ownership signature verification failed");
+        panic!()
+    }
+
+    ctx.change_validator_protocol_key(&validator, &protocol_key)
+}
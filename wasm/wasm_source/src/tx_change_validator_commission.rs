@@ -21,10 +21,13 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
 #[cfg(test)]
 mod tests {
     use std::cmp;
+    use std::collections::BTreeMap;
 
     use namada::ledger::pos::{OwnedPosParams, PosVP};
     use namada::proof_of_stake::types::GenesisValidator;
-    use namada::proof_of_stake::validator_commission_rate_handle;
+    use namada::proof_of_stake::{
+        read_validator_commission_history, validator_commission_rate_handle,
+    };
     use namada::types::dec::{Dec, POS_DECIMAL_PRECISION};
     use namada::types::storage::Epoch;
     use namada_tests::log::test;
@@ -151,6 +154,19 @@ mod tests {
             );
         }
 
+        // The new rate is recorded in the commission history
+        let history = read_validator_commission_history(
+            ctx(),
+            &commission_change.validator,
+        )?;
+        assert_eq!(
+            history,
+            BTreeMap::from([
+                (Epoch(0), initial_rate),
+                (Epoch(pos_params.pipeline_len), commission_change.new_rate),
+            ])
+        );
+
         // Use the tx_env to run PoS VP
         let tx_env = tx_host_env::take();
         let vp_env = TestNativeVpEnv::from_tx_env(tx_env, address::POS);
@@ -165,6 +181,56 @@ mod tests {
         Ok(())
     }
 
+    /// Test that the PoS VP rejects a commission rate written directly to
+    /// storage, which exceeds the validator's max change per epoch.
+    #[test]
+    fn test_vp_rejects_too_large_commission_rate_change() {
+        let validator = address::testing::established_address_1();
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: token::Amount::from_uint(1_000_000, 0).unwrap(),
+            consensus_key: key::testing::keypair_1().ref_to(),
+            protocol_key: key::testing::keypair_2().ref_to(),
+            commission_rate: Dec::new(5, 2).unwrap(),
+            max_commission_rate_change: Dec::new(1, 2).unwrap(),
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            metadata: Default::default(),
+        }];
+        let pos_params = init_pos(
+            &genesis_validators[..],
+            &OwnedPosParams::default(),
+            Epoch(0),
+        );
+
+        // Bypass the commission rate change checks of the PoS library
+        validator_commission_rate_handle(&validator)
+            .set(
+                ctx(),
+                Dec::new(5, 1).unwrap(),
+                Epoch(0),
+                pos_params.pipeline_len,
+            )
+            .unwrap();
+
+        let tx_env = tx_host_env::take();
+        let vp_env = TestNativeVpEnv::from_tx_env(tx_env, address::POS);
+        let result = vp_env
+            .validate_tx(PosVP::new)
+            .expect("Validation must not fail");
+        assert!(
+            !result,
+            "PoS Validity predicate must reject a too large commission rate \
+             change"
+        );
+    }
+
     fn arb_rate(min: Dec, max: Dec) -> impl Strategy<Value = Dec> {
         let int_min: i128 = (min * scale()).try_into().unwrap();
         let int_max: i128 = (max * scale()).try_into().unwrap();
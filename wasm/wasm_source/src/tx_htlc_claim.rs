@@ -0,0 +1,20 @@
+//! A tx to claim the tokens of a time lock with the preimage of its
+//! hashlock.
+
+use namada_tx_prelude::transaction::htlc::ClaimTimeLock;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1220000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let claim = ClaimTimeLock::try_from_slice(&data[..])
+        .wrap_err("failed to decode ClaimTimeLock")?;
+
+    htlc::claim_time_lock(ctx, claim.id, claim.preimage)?;
+
+    Ok(())
+}
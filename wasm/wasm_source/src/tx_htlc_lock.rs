@@ -0,0 +1,21 @@
+//! A tx to lock tokens in a hashed time-locked transfer.
+
+use namada_tx_prelude::transaction::htlc::LockTransfer;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1760000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let lock = LockTransfer::try_from_slice(&data[..])
+        .wrap_err("failed to decode LockTransfer")?;
+    debug_log!("apply_tx called to lock a transfer: {:#?}", lock);
+
+    let id = htlc::lock_transfer(ctx, lock)?;
+    debug_log!("Created time lock {}", id);
+
+    Ok(())
+}
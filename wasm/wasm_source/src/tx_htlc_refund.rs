@@ -0,0 +1,23 @@
+//! A tx for the receiver of a time lock to refund its tokens to the source.
+
+use namada_tx_prelude::transaction::htlc::RefundTimeLock;
+use namada_tx_prelude::*;
+
+#[transaction(gas = 1220000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let refund = RefundTimeLock::try_from_slice(&data[..])
+        .wrap_err("failed to decode RefundTimeLock")?;
+
+    let lock = htlc::read_time_lock(ctx, refund.id)?
+        .ok_or_err_msg("The time lock doesn't exist")?;
+    // The refund must be authorized by the receiver
+    ctx.insert_verifier(&lock.receiver)?;
+    htlc::refund_time_lock(ctx, refund.id)?;
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+//! A tx for a user to opt a bond into or out of the PoS slashing insurance
+//! pool.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 260000)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data")?;
+    let insurance =
+        transaction::pos::SlashingInsurance::try_from_slice(&data[..])
+            .wrap_err("failed to decode SlashingInsurance")?;
+
+    ctx.set_slashing_insurance(
+        insurance.source.as_ref(),
+        &insurance.validator,
+        insurance.enabled,
+    )
+}
@@ -10,7 +10,10 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         ctx.set_commitment_sentinel();
         err
     })?;
-    let unbond = transaction::pos::Unbond::try_from_slice(&data[..])
+    let transaction::pos::UnbondWithOptions {
+        unbond,
+        auto_withdraw,
+    } = transaction::pos::UnbondWithOptions::decode(&data[..])
         .wrap_err("failed to decode Unbond")?;
 
     ctx.unbond_tokens(
@@ -18,6 +21,12 @@ fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
         &unbond.validator,
         unbond.amount,
     )?;
+    if auto_withdraw {
+        ctx.schedule_auto_withdrawal(
+            unbond.source.as_ref(),
+            &unbond.validator,
+        )?;
+    }
     // TODO: would using debug_log! be useful?
 
     Ok(())
@@ -54,5 +54,17 @@ fn apply_tx(ctx: &mut Ctx, tx: Tx) -> TxResult {
         }
     }
 
+    if let Some(code_hashes) = tx_data.allowed_tx_codes {
+        if code_hashes.is_empty() {
+            storage_api::account::clear_allowed_tx_codes(ctx, owner)?;
+        } else {
+            storage_api::account::set_allowed_tx_codes(
+                ctx,
+                owner,
+                code_hashes,
+            )?;
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,24 @@
+//! A tx for the author of a governance proposal to withdraw it before its
+//! voting period starts.
+
+use namada_tx_prelude::*;
+
+#[transaction(gas = 969395)] // TODO: needs to be benchmarked
+fn apply_tx(ctx: &mut Ctx, tx_data: Tx) -> TxResult {
+    let signed = tx_data;
+    let data = signed.data().ok_or_err_msg("Missing data").map_err(|err| {
+        ctx.set_commitment_sentinel();
+        err
+    })?;
+    let tx_data =
+        transaction::governance::WithdrawProposalData::try_from_slice(
+            &data[..],
+        )
+        .wrap_err("failed to decode WithdrawProposalData")?;
+
+    debug_log!("apply_tx called to withdraw a governance proposal");
+
+    // The withdrawal must be authorized by the author
+    ctx.insert_verifier(&tx_data.author)?;
+    governance::withdraw_proposal(ctx, tx_data)
+}
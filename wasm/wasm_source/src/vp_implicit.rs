@@ -23,6 +23,9 @@ enum KeyType<'a> {
     },
     PoS,
     GovernanceVote(&'a Address),
+    GovernanceAuthor,
+    HtlcLock,
+    BridgePoolCancellation(keccak::KeccakHash),
     Unknown,
 }
 
@@ -41,6 +44,14 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if gov_storage::keys::is_author_key(key) {
+            Self::GovernanceAuthor
+        } else if htlc::storage::is_lock_key(key).is_some() {
+            Self::HtlcLock
+        } else if let Some(transfer_hash) =
+            eth_bridge::storage::bridge_pool::is_cancellation_key(key)
+        {
+            Self::BridgePoolCancellation(transfer_hash)
         } else {
             Self::Unknown
         }
@@ -138,11 +149,18 @@ fn validate_tx(
                         proof_of_stake::storage::is_unbond_key(key)
                             .map(|(bond_id, _, _)| bond_id)
                     });
-                let valid = match bond_id {
-                    Some(bond_id) => {
-                        // Bonds and unbonds changes for this address
-                        // must be signed
-                        bond_id.source != addr || *valid_sig
+                let source = match &bond_id {
+                    Some(bond_id) => Some(&bond_id.source),
+                    None => proof_of_stake::storage::is_auto_compound_key(key)
+                        .or_else(|| {
+                            proof_of_stake::storage::is_insured_bonds_key(key)
+                        }),
+                };
+                let valid = match source {
+                    Some(source) => {
+                        // Bonds, unbonds, auto-compounding and insurance
+                        // changes for this address must be signed
+                        *source != addr || *valid_sig
                     }
                     None => {
                         // Any other PoS changes are allowed without signature
@@ -163,6 +181,48 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::GovernanceAuthor => {
+                // Withdrawing a proposal must be authorized by its author
+                let author: Option<Address> = ctx.read_pre(key)?;
+                match author {
+                    Some(author)
+                        if author == addr && !ctx.has_key_post(key)? =>
+                    {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
+            KeyType::HtlcLock => {
+                // Refunding a time lock must be authorized by its receiver
+                let lock: Option<htlc::TimeLock> = ctx.read_post(key)?;
+                match lock {
+                    Some(lock)
+                        if lock.receiver == addr
+                            && lock.status
+                                == htlc::TimeLockStatus::Refunded =>
+                    {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
+            KeyType::BridgePoolCancellation(transfer_hash) => {
+                // Cancelling a transfer in the Bridge pool must be
+                // authorized by its sender
+                let pending_key =
+                    eth_bridge::storage::bridge_pool::get_key_from_hash(
+                        &transfer_hash,
+                    );
+                let transfer: Option<eth_bridge_pool::PendingTransfer> =
+                    ctx.read_pre(&pending_key)?;
+                match transfer {
+                    Some(transfer) if transfer.transfer.sender == addr => {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
             KeyType::Unknown => {
                 if key.segments.get(0) == Some(&addr.to_db_key()) {
                     // Unknown changes to this address space require a valid
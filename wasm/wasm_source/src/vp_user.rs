@@ -6,8 +6,8 @@
 //! It allows to bond, unbond and withdraw tokens to and from PoS system with a
 //! valid signature(s).
 //!
-//! For validator a tx to change a validator's commission rate or metadata
-//! requires a valid signature(s) only from the validator.
+//! For validator a tx to change a validator's commission rate, metadata or
+//! protocol key requires a valid signature(s) only from the validator.
 //!
 //! Any other storage key changes are allowed only with a valid signature.
 
@@ -19,10 +19,14 @@ use proof_of_stake::types::ValidatorState;
 enum KeyType<'a> {
     Token { owner: &'a Address },
     PoS,
+    ValidatorProtocolKey(&'a Address),
     Vp(&'a Address),
     Masp,
     PgfStward(&'a Address),
     GovernanceVote(&'a Address),
+    GovernanceAuthor,
+    HtlcLock,
+    BridgePoolCancellation(keccak::KeccakHash),
     Unknown,
 }
 
@@ -32,6 +36,10 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             Self::Token { owner }
         } else if proof_of_stake::storage::is_pos_key(key) {
             Self::PoS
+        } else if let Some(validator) =
+            proof_of_stake::storage::is_validator_protocol_key_subkey(key)
+        {
+            Self::ValidatorProtocolKey(validator)
         } else if gov_storage::keys::is_vote_key(key) {
             let voter_address = gov_storage::keys::get_voter_address(key);
             if let Some(address) = voter_address {
@@ -39,6 +47,14 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if gov_storage::keys::is_author_key(key) {
+            Self::GovernanceAuthor
+        } else if htlc::storage::is_lock_key(key).is_some() {
+            Self::HtlcLock
+        } else if let Some(transfer_hash) =
+            eth_bridge::storage::bridge_pool::is_cancellation_key(key)
+        {
+            Self::BridgePoolCancellation(transfer_hash)
         } else if let Some(address) = pgf_storage::keys::is_stewards_key(key) {
             Self::PgfStward(address)
         } else if let Some(address) = key.is_validity_predicate() {
@@ -145,6 +161,29 @@ fn validate_tx(
                     Some(address) => *address == addr && *valid_sig,
                     None => true,
                 };
+                // Changes to the automatic compounding of the rewards of the
+                // bonds of this address must be signed
+                let auto_compound =
+                    proof_of_stake::storage::is_auto_compound_key(key);
+                let valid_auto_compound_change = match auto_compound {
+                    Some(source) => *source != addr || *valid_sig,
+                    None => true,
+                };
+                // Changes to the slashing insurance of the bonds of this
+                // address must be signed
+                let insured_bonds =
+                    proof_of_stake::storage::is_insured_bonds_key(key);
+                let valid_insurance_change = match insured_bonds {
+                    Some(source) => *source != addr || *valid_sig,
+                    None => true,
+                };
+                // Ethereum bridge key changes must be signed by the validator
+                let eth_key =
+                    proof_of_stake::storage::is_validator_eth_key_subkey(key);
+                let valid_eth_key_change = match eth_key {
+                    Some(validator) => *validator != addr || *valid_sig,
+                    None => true,
+                };
 
                 // Changes due to unjailing, deactivating, and reactivating are
                 // marked by changes in validator state
@@ -205,6 +244,13 @@ fn validate_tx(
                     && valid_commission_rate_change
                     && valid_state_change
                     && valid_metadata_change
+                    && valid_auto_compound_change
+                    && valid_insurance_change
+                    && valid_eth_key_change
+            }
+            KeyType::ValidatorProtocolKey(validator) => {
+                // Protocol key changes must be signed by the validator
+                *validator == addr && *valid_sig
             }
             KeyType::GovernanceVote(voter) => {
                 if voter == &addr {
@@ -236,6 +282,48 @@ fn validate_tx(
                 }
             }
             KeyType::Masp => true,
+            KeyType::GovernanceAuthor => {
+                // Withdrawing a proposal must be authorized by its author
+                let author: Option<Address> = ctx.read_pre(key)?;
+                match author {
+                    Some(author)
+                        if author == addr && !ctx.has_key_post(key)? =>
+                    {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
+            KeyType::HtlcLock => {
+                // Refunding a time lock must be authorized by its receiver
+                let lock: Option<htlc::TimeLock> = ctx.read_post(key)?;
+                match lock {
+                    Some(lock)
+                        if lock.receiver == addr
+                            && lock.status
+                                == htlc::TimeLockStatus::Refunded =>
+                    {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
+            KeyType::BridgePoolCancellation(transfer_hash) => {
+                // Cancelling a transfer in the Bridge pool must be
+                // authorized by its sender
+                let pending_key =
+                    eth_bridge::storage::bridge_pool::get_key_from_hash(
+                        &transfer_hash,
+                    );
+                let transfer: Option<eth_bridge_pool::PendingTransfer> =
+                    ctx.read_pre(&pending_key)?;
+                match transfer {
+                    Some(transfer) if transfer.transfer.sender == addr => {
+                        *valid_sig
+                    }
+                    _ => true,
+                }
+            }
             KeyType::Unknown => {
                 if key.segments.get(0) == Some(&addr.to_db_key()) {
                     // Unknown changes to this address space require a valid
@@ -622,6 +710,8 @@ mod tests {
                 description: None,
                 website: None,
                 discord_handle: None,
+                moniker: None,
+                security_contact: None,
             };
             tx::ctx().become_validator(args).unwrap();
         });
@@ -711,6 +801,8 @@ mod tests {
                     Some("desc".to_owned()),
                     Some("website".to_owned()),
                     Some("discord".to_owned()),
+                    Some("moniker".to_owned()),
+                    Some("security".to_owned()),
                     Some(Dec::new(6, 2).unwrap()),
                 )
                 .unwrap();
@@ -891,6 +983,8 @@ mod tests {
                 description: None,
                 website: None,
                 discord_handle: None,
+                moniker: None,
+                security_contact: None,
             };
             tx::ctx().become_validator(args).unwrap();
         });
@@ -995,6 +1089,8 @@ mod tests {
                     Some("desc".to_owned()),
                     Some("website".to_owned()),
                     Some("discord".to_owned()),
+                    Some("moniker".to_owned()),
+                    Some("security".to_owned()),
                     Some(Dec::new(6, 2).unwrap()),
                 )
                 .unwrap();
@@ -1023,6 +1119,82 @@ mod tests {
         );
     }
 
+    /// Test that a tx of a third party rotating the protocol key of a
+    /// validator is rejected, even when signed with the new protocol key.
+    #[test]
+    fn test_third_party_protocol_key_change_rejected() {
+        // Init PoS genesis
+        let pos_params = PosParams::default();
+        let validator = address::testing::established_address_3();
+        let initial_stake = token::Amount::from_uint(10_098_123, 0).unwrap();
+        let consensus_key = key::testing::keypair_2().ref_to();
+        let protocol_key = key::testing::keypair_1().ref_to();
+        let commission_rate = Dec::new(5, 2).unwrap();
+        let max_commission_rate_change = Dec::new(1, 2).unwrap();
+
+        let genesis_validators = [GenesisValidator {
+            address: validator.clone(),
+            tokens: initial_stake,
+            consensus_key,
+            protocol_key,
+            commission_rate,
+            max_commission_rate_change,
+            eth_hot_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            eth_cold_key: key::common::PublicKey::Secp256k1(
+                key::testing::gen_keypair::<key::secp256k1::SigScheme>()
+                    .ref_to(),
+            ),
+            metadata: Default::default(),
+        }];
+
+        init_pos(&genesis_validators[..], &pos_params, Epoch(0));
+
+        // Initialize a tx environment
+        let mut tx_env = tx_host_env::take();
+
+        let validator_key = key::testing::keypair_1().ref_to();
+        tx_env.init_account_storage(&validator, vec![validator_key], 1);
+
+        // The third party's key, to take over the validator's protocol key
+        let secret_key = key::testing::keypair_3();
+        let public_key = secret_key.ref_to();
+
+        vp_host_env::init_from_tx(validator.clone(), tx_env, |_address| {
+            tx::ctx()
+                .change_validator_protocol_key(&validator, &public_key)
+                .unwrap();
+        });
+
+        let pks_map = AccountPublicKeysMap::from_iter(vec![public_key]);
+
+        let mut vp_env = vp_host_env::take();
+        let mut tx = vp_env.tx.clone();
+        tx.set_data(Data::new(vec![]));
+        tx.set_code(Code::new(vec![], None));
+        tx.add_section(Section::Signature(Signature::new(
+            vec![tx.raw_header_hash()],
+            pks_map.index_secret_keys(vec![secret_key]),
+            None,
+        )));
+        let signed_tx = tx.clone();
+        vp_env.tx = signed_tx.clone();
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        assert!(keys_changed.iter().any(|key| {
+            proof_of_stake::storage::is_validator_protocol_key_subkey(key)
+                == Some(&validator)
+        }));
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        vp_host_env::set(vp_env);
+        assert!(
+            !validate_tx(&CTX, signed_tx, validator, keys_changed, verifiers)
+                .unwrap()
+        );
+    }
+
     /// Test that a transfer on with accounts other than self is accepted.
     #[test]
     fn test_transfer_between_other_parties_accepted() {